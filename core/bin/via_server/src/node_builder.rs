@@ -1,7 +1,18 @@
 use anyhow::Context;
-use via_da_clients::celestia::wiring_layer::ViaCelestiaClientWiringLayer;
+use via_da_clients::{
+    bitcoin::{self as bitcoin_da, wiring_layer::ViaBitcoinDAClientWiringLayer},
+    celestia::{self, wiring_layer::ViaCelestiaClientWiringLayer},
+    multi::{
+        self,
+        wiring_layer::{ViaDABackend, ViaMultiDAClientWiringLayer},
+    },
+};
 use zksync_config::{
-    configs::{via_celestia::ProofSendingMode, via_secrets::ViaSecrets, via_wallets::ViaWallets},
+    configs::{
+        via_celestia::ProofSendingMode,
+        via_secrets::{ViaKeystoreSecrets, ViaSecrets},
+        via_wallets::ViaWallets,
+    },
     ContractsConfig, GenesisConfig, ViaGeneralConfig,
 };
 use zksync_core_leftovers::ViaComponent;
@@ -175,6 +186,10 @@ impl ViaNodeBuilder {
         Ok(self)
     }
 
+    fn keystore_secrets(&self) -> ViaKeystoreSecrets {
+        self.secrets.via_keystore.clone().unwrap_or_default()
+    }
+
     // VIA related layers
     fn add_init_node_storage_layer(mut self) -> anyhow::Result<Self> {
         let via_genesis_config = try_load_config!(self.configs.via_genesis_config);
@@ -214,14 +229,15 @@ impl ViaNodeBuilder {
     fn add_btc_sender_layer(mut self) -> anyhow::Result<Self> {
         let btc_sender_config = try_load_config!(self.configs.via_btc_sender_config);
         let wallet = self.wallets.btc_sender.clone().unwrap();
-        self.node.add_layer(ViaBtcInscriptionAggregatorLayer::new(
-            btc_sender_config.clone(),
-            wallet.clone(),
-        ));
-        self.node.add_layer(ViaInscriptionManagerLayer::new(
-            btc_sender_config,
-            wallet.clone(),
-        ));
+        let keystore_password = self.keystore_secrets().btc_sender_password;
+        self.node.add_layer(
+            ViaBtcInscriptionAggregatorLayer::new(btc_sender_config.clone(), wallet.clone())
+                .with_keystore_password(keystore_password.clone()),
+        );
+        self.node.add_layer(
+            ViaInscriptionManagerLayer::new(btc_sender_config, wallet.clone())
+                .with_keystore_password(keystore_password),
+        );
         Ok(self)
     }
 
@@ -451,21 +467,89 @@ impl ViaNodeBuilder {
         let state_keeper_config = try_load_config!(self.configs.state_keeper_config);
         let da_config = try_load_config!(self.configs.da_dispatcher_config);
         let celestia_config = try_load_config!(self.configs.via_celestia_config);
+        let btc_sender_config = try_load_config!(self.configs.via_btc_sender_config);
 
+        // A proof blob covers as many L1 batches as the BTC sender commits in one proof.
         self.node.add_layer(DataAvailabilityDispatcherLayer::new(
             state_keeper_config,
             da_config,
             celestia_config.proof_sending_mode == ProofSendingMode::OnlyRealProofs,
+            btc_sender_config.max_aggregated_proofs_to_commit.max(1) as usize,
         ));
 
         Ok(self)
     }
 
-    fn add_via_celestia_da_client_layer(mut self) -> anyhow::Result<Self> {
+    fn via_celestia_da_client_layer(&self) -> anyhow::Result<ViaCelestiaClientWiringLayer> {
         let celestia_config = try_load_config!(self.configs.via_celestia_config);
         let secrets = self.secrets.via_da.clone().unwrap();
-        self.node
-            .add_layer(ViaCelestiaClientWiringLayer::new(celestia_config, secrets));
+        Ok(ViaCelestiaClientWiringLayer::new(celestia_config, secrets))
+    }
+
+    fn via_bitcoin_da_client_layer(&self) -> anyhow::Result<ViaBitcoinDAClientWiringLayer> {
+        let via_btc_client_config = try_load_config!(self.configs.via_btc_client_config);
+        let secrets = self.secrets.via_l1.clone().unwrap();
+        let wallet = self
+            .wallets
+            .da_inscriber
+            .clone()
+            .context("Empty DA inscriber wallet")?;
+        Ok(
+            ViaBitcoinDAClientWiringLayer::new(via_btc_client_config, secrets, Some(wallet))
+                .with_keystore_password(self.keystore_secrets().da_inscriber_password),
+        )
+    }
+
+    fn via_da_backend(&self, da_identifier: &str) -> anyhow::Result<ViaDABackend> {
+        match da_identifier {
+            celestia::DA_IDENTIFIER => {
+                Ok(ViaDABackend::Celestia(self.via_celestia_da_client_layer()?))
+            }
+            bitcoin_da::DA_IDENTIFIER => {
+                Ok(ViaDABackend::Bitcoin(self.via_bitcoin_da_client_layer()?))
+            }
+            other => anyhow::bail!("Unsupported DA layer `{other}`"),
+        }
+    }
+
+    /// Returns the other known DA layers with a configuration, to fetch the blobs inscribed with
+    /// them before the DA layers were switched. They never dispatch, so don't need a wallet.
+    fn via_read_only_da_backends(
+        &self,
+        da_identifiers: &[&str],
+    ) -> anyhow::Result<Vec<ViaDABackend>> {
+        let mut backends = Vec::new();
+        if !da_identifiers.contains(&celestia::DA_IDENTIFIER) && self.secrets.via_da.is_some() {
+            backends.push(ViaDABackend::Celestia(self.via_celestia_da_client_layer()?));
+        }
+        if !da_identifiers.contains(&bitcoin_da::DA_IDENTIFIER) {
+            let via_btc_client_config = try_load_config!(self.configs.via_btc_client_config);
+            let secrets = self.secrets.via_l1.clone().context("Empty L1 secrets")?;
+            backends.push(ViaDABackend::Bitcoin(ViaBitcoinDAClientWiringLayer::new(
+                via_btc_client_config,
+                secrets,
+                None,
+            )));
+        }
+        Ok(backends)
+    }
+
+    /// Adds the client of the DA layers the BTC sender inscribes the blobs references of. Several
+    /// DA layers joined by `+` are all dispatched to, and tried in order when fetching the blobs.
+    /// The blobs are fetched from the DA layers inscribed with them, which can differ after a switch.
+    fn add_via_da_client_layer(mut self) -> anyhow::Result<Self> {
+        let btc_sender_config = try_load_config!(self.configs.via_btc_sender_config);
+        let da_identifier = btc_sender_config.da_identifier();
+        let da_identifiers = multi::split_da_identifier(&da_identifier);
+        let backends = da_identifiers
+            .iter()
+            .map(|da_identifier| self.via_da_backend(da_identifier))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let read_only_backends = self.via_read_only_da_backends(&da_identifiers)?;
+
+        self.node.add_layer(
+            ViaMultiDAClientWiringLayer::new(backends).with_read_only_backends(read_only_backends),
+        );
         Ok(self)
     }
 
@@ -626,7 +710,7 @@ impl ViaNodeBuilder {
                         .add_l1_gas_layer()?;
                 }
                 ViaComponent::Celestia => {
-                    self = self.add_via_celestia_da_client_layer()?;
+                    self = self.add_via_da_client_layer()?;
                 }
             }
         }
@@ -6,6 +6,7 @@ pub const DEFAULT_POLLING_INTERVAL_MS: u32 = 5000;
 pub const DEFAULT_MAX_ROWS_TO_DISPATCH: u32 = 100;
 pub const DEFAULT_MAX_RETRIES: u16 = 5;
 pub const DEFAULT_USE_DUMMY_INCLUSION_DATA: bool = false;
+pub const DEFAULT_COMPRESS_BLOBS: bool = false;
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct DADispatcherConfig {
@@ -19,6 +20,9 @@ pub struct DADispatcherConfig {
     // TODO: run a verification task to check if the L1 contract expects the inclusion proofs to
     // avoid the scenario where contracts expect real proofs, and server is using dummy proofs.
     pub use_dummy_inclusion_data: Option<bool>,
+    /// Whether the blobs are compressed with zstd before being dispatched. The compressed blobs
+    /// are wrapped in an envelope, enable it once all the verifiers are able to decode it.
+    pub compress_blobs: Option<bool>,
 }
 
 impl DADispatcherConfig {
@@ -28,6 +32,7 @@ impl DADispatcherConfig {
             max_rows_to_dispatch: Some(DEFAULT_MAX_ROWS_TO_DISPATCH),
             max_retries: Some(DEFAULT_MAX_RETRIES),
             use_dummy_inclusion_data: Some(DEFAULT_USE_DUMMY_INCLUSION_DATA),
+            compress_blobs: Some(DEFAULT_COMPRESS_BLOBS),
         }
     }
 
@@ -51,4 +56,8 @@ impl DADispatcherConfig {
         self.use_dummy_inclusion_data
             .unwrap_or(DEFAULT_USE_DUMMY_INCLUSION_DATA)
     }
+
+    pub fn compress_blobs(&self) -> bool {
+        self.compress_blobs.unwrap_or(DEFAULT_COMPRESS_BLOBS)
+    }
 }
@@ -19,6 +19,13 @@ pub struct ViaBridgeConfig {
 
     /// The agreement threshold required for the verifier to finalize an L1 batch.
     pub zk_agreement_threshold: f64,
+
+    /// The number of verifiers required to spend the bridge UTXOs through the k-of-n script path
+    /// of the bridge address. When unset, the bridge is spent with the n-of-n MuSig2 key only.
+    pub threshold: Option<usize>,
+
+    /// The hex encoded governance script, sibling leaf of the k-of-n leaf in the bridge taproot tree.
+    pub governance_script: Option<String>,
 }
 
 impl ViaBridgeConfig {
@@ -13,6 +13,17 @@ pub struct ViaBtcClientConfig {
     pub fee_strategies: Vec<String>,
     /// Use the RPC node as main fee rate provider.
     pub use_rpc_for_fee_rate: Option<bool>,
+    /// Base URL of an Esplora REST API. When set, it's used instead of the bitcoind RPC node,
+    /// so no wallet-enabled node is required.
+    pub esplora_url: Option<String>,
+    /// Fee rate providers to query, any of `rpc`, `mempool`, `block_stats` and `external_api`.
+    /// Defaults to the RPC node (if `use_rpc_for_fee_rate` is set) followed by the external APIs.
+    pub fee_rate_providers: Option<Vec<String>>,
+    /// How the provider estimates are combined: `fallback` (the first available one),
+    /// `median` or `min_of_quorum`.
+    pub fee_rate_aggregation: Option<String>,
+    /// Number of providers that have to agree on a fee rate with `min_of_quorum` aggregation.
+    pub fee_rate_quorum: Option<usize>,
 }
 
 impl ViaBtcClientConfig {
@@ -35,6 +46,28 @@ impl ViaBtcClientConfig {
         }
         true
     }
+
+    pub fn fee_rate_providers(&self) -> Vec<String> {
+        if let Some(providers) = &self.fee_rate_providers {
+            return providers.clone();
+        }
+        let mut providers = vec![];
+        if self.use_rpc_for_fee_rate() {
+            providers.push("rpc".to_string());
+        }
+        providers.push("external_api".to_string());
+        providers
+    }
+
+    pub fn fee_rate_aggregation(&self) -> String {
+        self.fee_rate_aggregation
+            .clone()
+            .unwrap_or_else(|| "fallback".to_string())
+    }
+
+    pub fn fee_rate_quorum(&self) -> usize {
+        self.fee_rate_quorum.unwrap_or(2)
+    }
 }
 
 impl ViaBtcClientConfig {
@@ -45,6 +78,10 @@ impl ViaBtcClientConfig {
             external_apis: vec![],
             fee_strategies: vec![],
             use_rpc_for_fee_rate: Some(true),
+            esplora_url: None,
+            fee_rate_providers: None,
+            fee_rate_aggregation: None,
+            fee_rate_quorum: None,
         }
     }
 }
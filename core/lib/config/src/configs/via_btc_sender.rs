@@ -12,7 +12,8 @@ pub struct ViaBtcSenderConfig {
     // Number of blocks to commit at time, should be 'one'.
     pub max_aggregated_blocks_to_commit: i32,
 
-    // Number of proofs to commit at time, should be 'one'.
+    // Max number of consecutive L1 batches covered by a proof, the proof of a range is
+    // dispatched as one DA blob and inscribed once.
     pub max_aggregated_proofs_to_commit: i32,
 
     // The max number of inscription in flight
@@ -21,7 +22,7 @@ pub struct ViaBtcSenderConfig {
     /// Number of block confirmations required to mark the inscription request as confirmed.
     pub block_confirmations: u32,
 
-    /// The identifier of the DA layer.
+    /// The identifier of the DA layer, several DA layers are joined with `+`.
     pub da_identifier: Option<String>,
 
     /// The btc sender wallet address.
@@ -35,6 +36,18 @@ pub struct ViaBtcSenderConfig {
 
     /// The required time (seconds) to wait before create a proof inscription.
     pub block_time_to_proof: Option<u32>,
+
+    /// The max number of times a stuck inscription is re-broadcast with a higher fee, 0 disables fee bumping.
+    pub max_fee_bump_attempts: Option<u32>,
+
+    /// The minimum fee rate increase (percentage) applied on each fee bump attempt.
+    pub fee_bump_percentage: Option<u64>,
+
+    /// The max fee rate (sat/vB) a fee bump is allowed to pay.
+    pub max_fee_rate: Option<u64>,
+
+    /// Pack the next proof inscription with the next L1 batch commit inscription into a single reveal tx.
+    pub pack_proof_with_commit: Option<bool>,
 }
 
 impl ViaBtcSenderConfig {
@@ -60,6 +73,22 @@ impl ViaBtcSenderConfig {
     pub fn stuck_inscription_block_number(&self) -> u32 {
         self.stuck_inscription_block_number.unwrap_or(6)
     }
+
+    pub fn max_fee_bump_attempts(&self) -> u32 {
+        self.max_fee_bump_attempts.unwrap_or(5)
+    }
+
+    pub fn fee_bump_percentage(&self) -> u64 {
+        self.fee_bump_percentage.unwrap_or(25)
+    }
+
+    pub fn max_fee_rate(&self) -> u64 {
+        self.max_fee_rate.unwrap_or(500)
+    }
+
+    pub fn pack_proof_with_commit(&self) -> bool {
+        self.pack_proof_with_commit.unwrap_or_default()
+    }
 }
 
 impl ViaBtcSenderConfig {
@@ -76,6 +105,10 @@ impl ViaBtcSenderConfig {
             block_time_to_commit: None,
             block_time_to_proof: None,
             stuck_inscription_block_number: None,
+            max_fee_bump_attempts: None,
+            fee_bump_percentage: None,
+            max_fee_rate: None,
+            pack_proof_with_commit: None,
         }
     }
 }
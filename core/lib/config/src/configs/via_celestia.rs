@@ -11,7 +11,7 @@ pub struct ViaCelestiaConfig {
     /// Celestia url.
     pub api_node_url: String,
 
-    /// Celestia blob limit
+    /// Celestia blob limit, larger payloads are split into several blobs.
     pub blob_size_limit: usize,
 
     /// The mode in which proofs are sent.
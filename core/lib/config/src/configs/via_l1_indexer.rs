@@ -1,3 +1,7 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+
 use super::{
     api::HealthCheckConfig, via_btc_client::ViaBtcClientConfig, via_consensus::ViaGenesisConfig,
     via_secrets::ViaSecrets, ObservabilityConfig, PostgresConfig, PrometheusConfig,
@@ -16,4 +20,29 @@ pub struct ViaIndexerConfig {
     pub prometheus_config: PrometheusConfig,
     pub postgres_config: PostgresConfig,
     pub secrets: ViaSecrets,
+    pub api_config: ViaIndexerApiConfig,
+}
+
+/// Configuration of the HTTP API serving the indexed deposits and withdrawals.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ViaIndexerApiConfig {
+    /// Port to which the indexer API server is listening.
+    pub port: u16,
+
+    /// Max number of items returned in a page.
+    pub max_page_size: Option<u32>,
+
+    /// JSON-RPC URL of an L2 node, used to look up the withdrawals by L2 transaction hash. The
+    /// lookup is disabled when not set.
+    pub l2_rpc_url: Option<String>,
+}
+
+impl ViaIndexerApiConfig {
+    pub fn bind_addr(&self) -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), self.port)
+    }
+
+    pub fn max_page_size(&self) -> u32 {
+        self.max_page_size.unwrap_or(100)
+    }
 }
@@ -52,11 +52,36 @@ impl Debug for ViaDASecrets {
     }
 }
 
+/// Passwords of the encrypted keystores of the wallets, see `ViaSignerConfig::Keystore`.
+#[derive(Clone, Default, Deserialize, PartialEq)]
+pub struct ViaKeystoreSecrets {
+    /// Password of the keystore of the BTC sender wallet.
+    pub btc_sender_password: Option<String>,
+
+    /// Password of the keystore of the verifier wallet.
+    pub verifier_password: Option<String>,
+
+    /// Password of the keystore of the DA inscriber wallet.
+    pub da_inscriber_password: Option<String>,
+}
+
+impl Debug for ViaKeystoreSecrets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mask = |password: &Option<String>| password.as_ref().map(|_| "********");
+        f.debug_struct("ViaKeystoreSecrets")
+            .field("btc_sender_password", &mask(&self.btc_sender_password))
+            .field("verifier_password", &mask(&self.verifier_password))
+            .field("da_inscriber_password", &mask(&self.da_inscriber_password))
+            .finish()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ViaSecrets {
     pub base_secrets: Secrets,
     pub via_l1: Option<ViaL1Secrets>,
     pub via_da: Option<ViaDASecrets>,
+    pub via_keystore: Option<ViaKeystoreSecrets>,
 }
 
 impl Default for ViaSecrets {
@@ -69,6 +94,7 @@ impl Default for ViaSecrets {
             },
             via_l1: None,
             via_da: None,
+            via_keystore: None,
         }
     }
 }
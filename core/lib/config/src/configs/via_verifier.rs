@@ -39,6 +39,66 @@ pub struct ViaVerifierConfig {
 
     /// Transaction weight limit.
     pub max_tx_weight: Option<u64>,
+
+    /// The coordinator url of each verifier, in the order of the bridge verifiers public keys.
+    /// When set, the coordinator role rotates among the verifiers and `role` is ignored.
+    pub coordinator_urls: Option<Vec<String>>,
+
+    /// Number of L1 batches coordinated by a leader before the role moves to the next verifier.
+    pub leader_rotation_interval: Option<u64>,
+
+    /// Number of L1 blocks after which the role moves to the next verifier within a term, so an
+    /// unresponsive leader is replaced.
+    pub leader_liveness_l1_blocks: Option<u64>,
+
+    /// Time (in seconds) a signing session waits for all the verifiers before the bridge
+    /// transaction is spent through the k-of-n script path.
+    pub threshold_fallback_timeout: Option<u64>,
+
+    /// Whether the verifier subscribes to the session events of the coordinator, on top of polling.
+    pub session_events: Option<bool>,
+
+    /// PEM certificate chain of the node, presented to the peers when mutual TLS is enabled.
+    pub tls_cert_path: Option<String>,
+
+    /// PKCS#8 PEM private key of the node certificate.
+    pub tls_key_path: Option<String>,
+
+    /// PEM certificate of the CA which issued the certificates of the verifier network.
+    pub tls_ca_cert_path: Option<String>,
+
+    /// Max amount (in sats) withdrawn from the bridge by a single withdrawal session.
+    pub max_session_outflow: Option<u64>,
+
+    /// Max amount (in sats) withdrawn from the bridge within the outflow window.
+    pub max_window_outflow: Option<u64>,
+
+    /// Max amount (in sats) withdrawn to a single address within the outflow window.
+    pub max_address_outflow: Option<u64>,
+
+    /// Rolling window (in L1 blocks) of the window and address outflow limits. It's anchored to the
+    /// L1 block the L1 batch of the session was inscribed in, so all the verifiers agree on it.
+    pub outflow_window_l1_blocks: Option<u32>,
+
+    /// Compressed public key of the governance, which can approve a withdrawal session above the
+    /// outflow limits.
+    pub outflow_governance_pub_key: Option<String>,
+
+    /// Max fee rate (in sat/vB) at which the small bridge UTXOs are consolidated.
+    pub consolidation_max_fee_rate: Option<u64>,
+
+    /// Only the bridge UTXOs below this amount (in sats) are consolidated.
+    pub consolidation_max_utxo_amount: Option<u64>,
+
+    /// Port of the status API of the node, not served when unset.
+    pub status_api_port: Option<u16>,
+
+    /// Bearer token required by the status API.
+    pub status_api_token: Option<String>,
+
+    /// Last L1 batch whose DA blob only holds the pubdata, dispatched before the state diffs were
+    /// appended to it. Its state diffs are not checked against the published data.
+    pub legacy_pubdata_last_l1_batch: Option<u32>,
 }
 
 impl ViaVerifierConfig {
@@ -65,6 +125,63 @@ impl ViaVerifierConfig {
             .unwrap_or((MAX_STANDARD_TX_WEIGHT - 20000).into())
     }
 
+    pub fn leader_rotation_enabled(&self) -> bool {
+        self.coordinator_urls
+            .as_ref()
+            .map_or(false, |urls| !urls.is_empty())
+    }
+
+    pub fn leader_rotation_interval(&self) -> u64 {
+        self.leader_rotation_interval.unwrap_or(10).max(1)
+    }
+
+    pub fn leader_liveness_l1_blocks(&self) -> u64 {
+        self.leader_liveness_l1_blocks.unwrap_or(6).max(1)
+    }
+
+    pub fn threshold_fallback_timeout(&self) -> u64 {
+        self.threshold_fallback_timeout.unwrap_or(120)
+    }
+
+    pub fn session_events_enabled(&self) -> bool {
+        self.session_events.unwrap_or(true)
+    }
+
+    /// Mutual TLS between the verifiers and the coordinator, enabled when the certificate, its key
+    /// and the CA certificate are all configured.
+    pub fn mtls_enabled(&self) -> bool {
+        [
+            &self.tls_cert_path,
+            &self.tls_key_path,
+            &self.tls_ca_cert_path,
+        ]
+        .iter()
+        .all(|path| path.as_ref().map_or(false, |path| !path.is_empty()))
+    }
+
+    pub fn outflow_window_l1_blocks(&self) -> u32 {
+        self.outflow_window_l1_blocks.unwrap_or(144).max(1)
+    }
+
+    /// The status API is served when its port and token are both configured.
+    pub fn status_api_addr(&self) -> Option<SocketAddr> {
+        let has_token = self
+            .status_api_token
+            .as_ref()
+            .map_or(false, |token| !token.is_empty());
+        self.status_api_port
+            .filter(|_| has_token)
+            .map(|port| SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), port))
+    }
+
+    pub fn consolidation_max_fee_rate(&self) -> u64 {
+        self.consolidation_max_fee_rate.unwrap_or(2)
+    }
+
+    pub fn consolidation_max_utxo_amount(&self) -> u64 {
+        self.consolidation_max_utxo_amount.unwrap_or(100_000)
+    }
+
     pub fn for_tests() -> Self {
         Self {
             role: ViaNodeRole::Verifier,
@@ -77,6 +194,24 @@ impl ViaVerifierConfig {
             session_timeout: 30,
             max_tx_weight: None,
             bridge_address_merkle_root: None,
+            coordinator_urls: None,
+            leader_rotation_interval: None,
+            leader_liveness_l1_blocks: None,
+            threshold_fallback_timeout: None,
+            session_events: None,
+            tls_cert_path: None,
+            tls_key_path: None,
+            tls_ca_cert_path: None,
+            max_session_outflow: None,
+            max_window_outflow: None,
+            max_address_outflow: None,
+            outflow_window_l1_blocks: None,
+            outflow_governance_pub_key: None,
+            consolidation_max_fee_rate: None,
+            consolidation_max_utxo_amount: None,
+            status_api_port: None,
+            status_api_token: None,
+            legacy_pubdata_last_l1_batch: None,
         }
     }
 
@@ -1,13 +1,39 @@
 use core::fmt;
+use std::path::PathBuf;
 
 use zksync_basic_types::{H160, H256};
 
 use super::wallets::{AddressWallet, StateKeeper, TokenMultiplierSetter, Wallet};
 
+/// Signing backend of a wallet, used instead of the plaintext private key.
+#[derive(Clone, PartialEq)]
+pub enum ViaSignerConfig {
+    /// Encrypted keystore file, unlocked on startup with its password from `ViaKeystoreSecrets`.
+    Keystore { path: PathBuf },
+    /// Remote signing service, the key never leaves it.
+    Remote {
+        url: String,
+        auth_token: Option<String>,
+    },
+}
+
+impl fmt::Debug for ViaSignerConfig {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Keystore { path } => formatter
+                .debug_struct("Keystore")
+                .field("path", path)
+                .finish(),
+            Self::Remote { url, .. } => formatter.debug_struct("Remote").field("url", url).finish(),
+        }
+    }
+}
+
 #[derive(Default, Clone, PartialEq)]
 pub struct ViaWallet {
     pub address: String,
     pub private_key: String,
+    pub signer: Option<ViaSignerConfig>,
 }
 
 impl fmt::Debug for ViaWallet {
@@ -15,6 +41,7 @@ impl fmt::Debug for ViaWallet {
         formatter
             .debug_struct("Secret")
             .field("address", &self.address)
+            .field("signer", &self.signer)
             .finish()
     }
 }
@@ -24,6 +51,7 @@ impl ViaWallet {
         Self {
             address,
             private_key,
+            signer: None,
         }
     }
 }
@@ -36,6 +64,9 @@ pub struct ViaWallets {
     /// Via wallets
     pub btc_sender: Option<ViaWallet>,
     pub vote_operator: Option<ViaWallet>,
+    /// Wallet paying for the blobs inscribed by the Bitcoin DA client. It must not be shared
+    /// with another inscriber.
+    pub da_inscriber: Option<ViaWallet>,
 }
 
 impl ViaWallets {
@@ -49,6 +80,7 @@ impl ViaWallets {
             }),
             btc_sender: Some(ViaWallet::new("".into(), String::from("pk"))),
             vote_operator: Some(ViaWallet::new("".into(), String::from("pk"))),
+            da_inscriber: None,
         }
     }
 }
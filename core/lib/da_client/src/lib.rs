@@ -18,6 +18,16 @@ pub trait DataAvailabilityClient: Sync + Send + fmt::Debug {
     /// Fetches the inclusion data for a given blob_id.
     async fn get_inclusion_data(&self, blob_id: &str) -> Result<Option<InclusionData>, DAError>;
 
+    /// Fetches the inclusion data for a given blob_id, dispatched to the DA layer `da_identifier`.
+    /// The clients of a single DA layer ignore the `da_identifier`.
+    async fn get_inclusion_data_from(
+        &self,
+        _da_identifier: &str,
+        blob_id: &str,
+    ) -> Result<Option<InclusionData>, DAError> {
+        self.get_inclusion_data(blob_id).await
+    }
+
     /// Clones the client and wraps it in a Box.
     fn clone_boxed(&self) -> Box<dyn DataAvailabilityClient>;
 
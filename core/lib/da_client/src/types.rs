@@ -13,6 +13,11 @@ impl DAError {
     pub fn is_retriable(&self) -> bool {
         self.is_retriable
     }
+
+    /// Whether the DA layer returned a blob failing its verification, see `InvalidBlobError`.
+    pub fn is_invalid_blob(&self) -> bool {
+        self.error.downcast_ref::<InvalidBlobError>().is_some()
+    }
 }
 
 impl Display for DAError {
@@ -28,6 +33,19 @@ impl Display for DAError {
 
 impl error::Error for DAError {}
 
+/// Error wrapped in the `DAError` of the clients when the blob returned by the DA layer doesn't
+/// match its commitment or inclusion proof, e.g. when the DA node is compromised.
+#[derive(Debug)]
+pub struct InvalidBlobError(pub String);
+
+impl Display for InvalidBlobError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid blob: {}", self.0)
+    }
+}
+
+impl error::Error for InvalidBlobError {}
+
 /// `DispatchResponse` is the response received from the DA layer after dispatching a blob.
 #[derive(Default)]
 pub struct DispatchResponse {
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_via_btc_inscriptions_request_history_commit_tx_id;
+
+ALTER TABLE via_btc_inscriptions_request_history
+    ADD CONSTRAINT via_btc_inscriptions_request_history_commit_tx_id_key UNIQUE (commit_tx_id);
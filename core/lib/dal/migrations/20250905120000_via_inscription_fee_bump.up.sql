@@ -0,0 +1,6 @@
+-- Fee bumps replace the reveal tx only, so several history rows share the same commit tx.
+ALTER TABLE via_btc_inscriptions_request_history
+    DROP CONSTRAINT IF EXISTS via_btc_inscriptions_request_history_commit_tx_id_key;
+
+CREATE INDEX IF NOT EXISTS idx_via_btc_inscriptions_request_history_commit_tx_id
+    ON via_btc_inscriptions_request_history (commit_tx_id);
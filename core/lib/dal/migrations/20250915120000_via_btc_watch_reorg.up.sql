@@ -0,0 +1,12 @@
+-- Hashes of the Bitcoin blocks processed by an indexer, used to detect reorgs.
+CREATE TABLE IF NOT EXISTS via_indexer_blocks (
+    module VARCHAR NOT NULL,
+    number BIGINT NOT NULL,
+    hash BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (module, number)
+);
+
+-- The Bitcoin block a row was indexed from, so it can be removed when the block is orphaned.
+ALTER TABLE via_votes ADD COLUMN IF NOT EXISTS l1_block_number BIGINT;
+ALTER TABLE via_wallets ADD COLUMN IF NOT EXISTS l1_block_number BIGINT;
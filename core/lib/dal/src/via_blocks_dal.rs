@@ -90,6 +90,17 @@ impl ViaBlocksDal<'_, '_> {
                 }
                 Ok(())
             }
+            ViaBtcInscriptionRequestType::CommitL1BatchAndProofOnchain => {
+                let instrumentation = Instrumented::new("set_inscription_request_tx_id#packed")
+                    .with_arg("batch_number", &batch_number)
+                    .with_arg("inscription_request_id", &inscription_request_id);
+                Err(instrumentation.arg_error(
+                    "inscription_request",
+                    anyhow::anyhow!(
+                        "A packed inscription request is linked once per commit and proof L1 batch"
+                    ),
+                ))
+            }
         }
     }
 
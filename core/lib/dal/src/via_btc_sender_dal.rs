@@ -195,6 +195,36 @@ impl ViaBtcSenderDal<'_, '_> {
         Ok(inscription_request_history.map(ViaBtcInscriptionRequestHistory::from))
     }
 
+    /// List the inscription request history, newest attempt first.
+    pub async fn list_inscription_request_history(
+        &mut self,
+        inscription_request_id: i64,
+    ) -> DalResult<Vec<ViaBtcInscriptionRequestHistory>> {
+        let records = sqlx::query_as!(
+            ViaStorageBtcInscriptionRequestHistory,
+            r#"
+            SELECT
+                *
+            FROM
+                via_btc_inscriptions_request_history
+            WHERE
+                inscription_request_id = $1
+            ORDER BY
+                id DESC
+            "#,
+            inscription_request_id
+        )
+        .instrument("list_inscription_request_history")
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(ViaBtcInscriptionRequestHistory::from)
+            .collect())
+    }
+
     pub async fn get_inscription_request(
         &mut self,
         id: i64,
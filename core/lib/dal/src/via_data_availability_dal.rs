@@ -115,6 +115,29 @@ impl ViaDataAvailabilityDal<'_, '_> {
         Ok(())
     }
 
+    /// Returns the number of L1 batches proven by the proof DA blob, more than one when the proof
+    /// covers a range of L1 batches.
+    pub async fn get_proof_blob_l1_batches_count(&mut self, blob_id: &str) -> DalResult<i64> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                via_data_availability
+            WHERE
+                blob_id = $1
+                AND is_proof = TRUE
+            "#,
+            blob_id,
+        )
+        .instrument("get_proof_blob_l1_batches_count")
+        .with_arg("blob_id", &blob_id)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(count)
+    }
+
     /// Saves the inclusion data for the given L1 batch. If the inclusion data is already present,
     /// verifies that it matches the one provided in the function arguments
     /// (meaning that the inclusion data corresponds to the same DA blob).
@@ -127,6 +127,61 @@ impl ViaTransactionsDal<'_, '_> {
         Ok(())
     }
 
+    /// Returns whether a priority transaction from an L1 block above `l1_block_number` was
+    /// already included in an L2 block.
+    pub async fn has_executed_priority_txs_after_l1_block(
+        &mut self,
+        l1_block_number: u32,
+    ) -> DalResult<bool> {
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        transactions
+                    WHERE
+                        is_priority = TRUE
+                        AND l1_block_number > $1
+                        AND miniblock_number IS NOT NULL
+                ) AS "exists!"
+            "#,
+            l1_block_number as i32,
+        )
+        .instrument("has_executed_priority_txs_after_l1_block")
+        .with_arg("l1_block_number", &l1_block_number)
+        .report_latency()
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(record.exists)
+    }
+
+    /// Removes the pending priority transactions from the L1 blocks above `l1_block_number`.
+    pub async fn delete_priority_txs_after_l1_block(
+        &mut self,
+        l1_block_number: u32,
+    ) -> DalResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM transactions
+            WHERE
+                is_priority = TRUE
+                AND l1_block_number > $1
+                AND miniblock_number IS NULL
+            "#,
+            l1_block_number as i32,
+        )
+        .instrument("delete_priority_txs_after_l1_block")
+        .with_arg("l1_block_number", &l1_block_number)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Retrieves the L1 block number of the most recently processed priority transaction.
     /// Queries transactions table ordered by priority operation ID in descending order.
     pub async fn get_last_processed_l1_block(&mut self) -> DalResult<Option<L1BlockNumber>> {
@@ -7,26 +7,34 @@ pub struct ViaVotesDal<'c, 'a> {
 }
 
 impl ViaVotesDal<'_, '_> {
-    /// Inserts a new vote for an l1 batch.
+    /// Inserts a new vote for an l1 batch, indexed from the L1 block `l1_block_number`.
     pub async fn insert_vote(
         &mut self,
         l1_batch_number: u32,
         proof_reveal_tx_id: &[u8],
         verifier_address: &str,
         vote: bool,
+        l1_block_number: u32,
     ) -> DalResult<()> {
         sqlx::query!(
             r#"
             INSERT INTO
-                via_votes (l1_batch_number, proof_reveal_tx_id, verifier_address, vote)
+                via_votes (
+                    l1_batch_number,
+                    proof_reveal_tx_id,
+                    verifier_address,
+                    vote,
+                    l1_block_number
+                )
             VALUES
-                ($1, $2, $3, $4)
+                ($1, $2, $3, $4, $5)
             ON CONFLICT (l1_batch_number, proof_reveal_tx_id, verifier_address) DO NOTHING
             "#,
             l1_batch_number as i32,
             proof_reveal_tx_id,
             verifier_address,
-            vote
+            vote,
+            i64::from(l1_block_number)
         )
         .instrument("insert_vote")
         .report_latency()
@@ -134,4 +142,41 @@ impl ViaVotesDal<'_, '_> {
 
         Ok(reached_threshold)
     }
+
+    /// Removes the votes indexed from the L1 blocks above `l1_block_number` and resets the
+    /// finalization of the l1 batches they were cast for, so it is decided again from the votes
+    /// found on the new chain.
+    pub async fn delete_votes_after_l1_block(&mut self, l1_block_number: u32) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            WITH
+                deleted_votes AS (
+                    DELETE FROM via_votes
+                    WHERE
+                        l1_block_number > $1
+                    RETURNING
+                        l1_batch_number
+                )
+            UPDATE via_l1_batch_inscription_request
+            SET
+                is_finalized = NULL,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number IN (
+                    SELECT
+                        l1_batch_number
+                    FROM
+                        deleted_votes
+                )
+            "#,
+            i64::from(l1_block_number),
+        )
+        .instrument("delete_votes_after_l1_block")
+        .with_arg("l1_block_number", &l1_block_number)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
 }
@@ -10,10 +10,12 @@ pub struct ViaWalletDal<'c, 'a> {
 }
 
 impl ViaWalletDal<'_, '_> {
-    /// Inserts a new set of wallets.
+    /// Inserts a new set of wallets. `l1_block_number` is the L1 block the wallets were indexed
+    /// from, if any.
     pub async fn insert_wallets(
         &mut self,
         wallets_details: &SystemWalletsDetails,
+        l1_block_number: Option<u32>,
     ) -> DalResult<()> {
         let mut transaction = self.storage.start_transaction().await?;
 
@@ -29,14 +31,15 @@ impl ViaWalletDal<'_, '_> {
             sqlx::query!(
                 r#"
                 INSERT INTO
-                    via_wallets (ROLE, address, tx_hash)
+                    via_wallets (ROLE, address, tx_hash, l1_block_number)
                 VALUES
-                    ($1, $2, $3)
+                    ($1, $2, $3, $4)
                 ON CONFLICT (tx_hash, address, ROLE) DO NOTHING
                 "#,
                 role.to_string(),
                 addresses_str,
                 role_info.txid.to_string(),
+                l1_block_number.map(i64::from),
             )
             .instrument("insert_wallet")
             .report_latency()
@@ -79,4 +82,24 @@ impl ViaWalletDal<'_, '_> {
 
         Ok(Some(wallets))
     }
+
+    /// Removes the wallets indexed from the L1 blocks above `l1_block_number`, restoring the
+    /// wallets that were active before them.
+    pub async fn delete_wallets_after_l1_block(&mut self, l1_block_number: u32) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM via_wallets
+            WHERE
+                l1_block_number > $1
+            "#,
+            i64::from(l1_block_number),
+        )
+        .instrument("delete_wallets_after_l1_block")
+        .with_arg("l1_block_number", &l1_block_number)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
 }
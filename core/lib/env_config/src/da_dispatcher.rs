@@ -27,6 +27,7 @@ mod tests {
             max_rows_to_dispatch: Some(rows_limit),
             max_retries: Some(max_retries),
             use_dummy_inclusion_data: Some(true),
+            compress_blobs: Some(true),
         }
     }
 
@@ -38,6 +39,7 @@ mod tests {
             DA_DISPATCHER_MAX_ROWS_TO_DISPATCH=60
             DA_DISPATCHER_MAX_RETRIES=7
             DA_DISPATCHER_USE_DUMMY_INCLUSION_DATA="true"
+            DA_DISPATCHER_COMPRESS_BLOBS="true"
         "#;
         lock.set_env(config);
         let actual = DADispatcherConfig::from_env().unwrap();
@@ -25,6 +25,7 @@ mod via_btc_client;
 mod via_btc_sender;
 mod via_celestia;
 mod via_consensus;
+mod via_l1_indexer;
 mod via_wallets;
 
 mod via_verifier;
@@ -1,5 +1,5 @@
 use zksync_config::{
-    configs::via_secrets::{ViaDASecrets, ViaL1Secrets},
+    configs::via_secrets::{ViaDASecrets, ViaKeystoreSecrets, ViaL1Secrets},
     ViaBtcSenderConfig,
 };
 
@@ -22,3 +22,9 @@ impl FromEnv for ViaDASecrets {
         envy_load("via_da_secrets", "VIA_CELESTIA_CLIENT_")
     }
 }
+
+impl FromEnv for ViaKeystoreSecrets {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("via_keystore_secrets", "VIA_KEYSTORE_")
+    }
+}
@@ -0,0 +1,9 @@
+use zksync_config::configs::via_l1_indexer::ViaIndexerApiConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for ViaIndexerApiConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("via_indexer_api", "VIA_INDEXER_API_")
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::Context;
 use zksync_config::configs::{
-    via_wallets::{ViaWallet, ViaWallets},
+    via_wallets::{ViaSignerConfig, ViaWallet, ViaWallets},
     wallets::Wallets,
 };
 
@@ -13,6 +13,24 @@ fn pk_from_env(env_var: &str, context: &str) -> anyhow::Result<Option<String>> {
         .transpose()
 }
 
+/// Loads the signing backend of a wallet, `prefix` being the wallet env prefix (e.g. `VIA_BTC_SENDER`).
+fn signer_from_env(prefix: &str) -> anyhow::Result<Option<ViaSignerConfig>> {
+    let remote_signer_url = std::env::var(format!("{prefix}_REMOTE_SIGNER_URL")).ok();
+    let keystore_path = std::env::var(format!("{prefix}_KEYSTORE_PATH")).ok();
+
+    match (remote_signer_url, keystore_path) {
+        (Some(_), Some(_)) => {
+            anyhow::bail!("{prefix}_REMOTE_SIGNER_URL and {prefix}_KEYSTORE_PATH are exclusive")
+        }
+        (Some(url), None) => Ok(Some(ViaSignerConfig::Remote {
+            url,
+            auth_token: std::env::var(format!("{prefix}_REMOTE_SIGNER_AUTH_TOKEN")).ok(),
+        })),
+        (None, Some(path)) => Ok(Some(ViaSignerConfig::Keystore { path: path.into() })),
+        (None, None) => Ok(None),
+    }
+}
+
 impl FromEnv for ViaWallets {
     fn from_env() -> anyhow::Result<Self> {
         let wallets = Wallets::from_env()?;
@@ -27,17 +45,38 @@ impl FromEnv for ViaWallets {
             pk_from_env("VIA_VERIFIER_WALLET_ADDRESS", "Malformed verifier address")?;
         let verifier_pk = pk_from_env("VIA_VERIFIER_PRIVATE_KEY", "Malformed verifier pk")?;
 
+        let da_inscriber_address = pk_from_env(
+            "VIA_DA_INSCRIBER_WALLET_ADDRESS",
+            "Malformed DA inscriber address",
+        )?;
+        let da_inscriber_pk =
+            pk_from_env("VIA_DA_INSCRIBER_PRIVATE_KEY", "Malformed DA inscriber pk")?;
+        let da_inscriber_signer = signer_from_env("VIA_DA_INSCRIBER")?;
+        // Only the nodes inscribing the DA blobs on Bitcoin have a DA inscriber wallet.
+        let da_inscriber = if da_inscriber_pk.is_some() || da_inscriber_signer.is_some() {
+            Some(ViaWallet {
+                address: da_inscriber_address.unwrap_or_default(),
+                private_key: da_inscriber_pk.unwrap_or_default(),
+                signer: da_inscriber_signer,
+            })
+        } else {
+            None
+        };
+
         Ok(Self {
             state_keeper: wallets.state_keeper,
             token_multiplier_setter: wallets.token_multiplier_setter,
             btc_sender: Some(ViaWallet {
                 address: btc_sender_address.unwrap_or_default(),
                 private_key: btc_sender_pk.clone().unwrap_or_default(),
+                signer: signer_from_env("VIA_BTC_SENDER")?,
             }),
             vote_operator: Some(ViaWallet {
                 address: verifier_address.unwrap_or_default(),
                 private_key: verifier_pk.unwrap_or_default(),
+                signer: signer_from_env("VIA_VERIFIER")?,
             }),
+            da_inscriber,
         })
     }
 }
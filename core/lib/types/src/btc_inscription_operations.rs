@@ -6,6 +6,8 @@ use crate::aggregated_operations::AggregatedActionType;
 pub enum ViaBtcInscriptionRequestType {
     CommitL1BatchOnchain,
     CommitProofOnchain,
+    /// An L1 batch commitment and a proof of a previous L1 batch packed into a single inscription.
+    CommitL1BatchAndProofOnchain,
 }
 
 impl ViaBtcInscriptionRequestType {
@@ -13,6 +15,7 @@ impl ViaBtcInscriptionRequestType {
         match self {
             Self::CommitL1BatchOnchain => "CommitL1BatchOnchain",
             Self::CommitProofOnchain => "CommitProofOnchain",
+            Self::CommitL1BatchAndProofOnchain => "CommitL1BatchAndProofOnchain",
         }
     }
 }
@@ -28,6 +31,9 @@ impl From<String> for ViaBtcInscriptionRequestType {
         match s.as_str() {
             "CommitL1BatchOnchain" => ViaBtcInscriptionRequestType::CommitL1BatchOnchain,
             "CommitProofOnchain" => ViaBtcInscriptionRequestType::CommitProofOnchain,
+            "CommitL1BatchAndProofOnchain" => {
+                ViaBtcInscriptionRequestType::CommitL1BatchAndProofOnchain
+            }
             _ => panic!("Unexpected value for ViaBtcInscriptionRequestType: {}", s),
         }
     }
@@ -36,7 +42,10 @@ impl From<String> for ViaBtcInscriptionRequestType {
 impl From<ViaBtcInscriptionRequestType> for AggregatedActionType {
     fn from(tx_type: ViaBtcInscriptionRequestType) -> Self {
         match tx_type {
-            ViaBtcInscriptionRequestType::CommitL1BatchOnchain => AggregatedActionType::Commit,
+            ViaBtcInscriptionRequestType::CommitL1BatchOnchain
+            | ViaBtcInscriptionRequestType::CommitL1BatchAndProofOnchain => {
+                AggregatedActionType::Commit
+            }
             ViaBtcInscriptionRequestType::CommitProofOnchain => {
                 AggregatedActionType::PublishProofOnchain
             }
@@ -51,8 +60,9 @@ impl FromStr for ViaBtcInscriptionRequestType {
         match s {
             "CommitL1BatchOnchain" => Ok(Self::CommitL1BatchOnchain),
             "CommitProofOnchain" => Ok(Self::CommitProofOnchain),
+            "CommitL1BatchAndProofOnchain" => Ok(Self::CommitL1BatchAndProofOnchain),
             _ => Err(
-                "Incorrect aggregated action type; expected one of `CommitL1BatchOnchain`, `CommitProofOnchain`",
+                "Incorrect aggregated action type; expected one of `CommitL1BatchOnchain`, `CommitProofOnchain`, `CommitL1BatchAndProofOnchain`",
             ),
         }
     }
@@ -36,13 +36,21 @@ pub struct ViaL1Deposit {
 
 impl ViaL1Deposit {
     pub fn is_valid_deposit(&self) -> bool {
+        self.invalid_reason().is_none()
+    }
+
+    /// Returns why the deposit can't be credited on L2, if it can't.
+    pub fn invalid_reason(&self) -> Option<&'static str> {
         if self.l2_receiver_address <= H160::from_str(MAX_SYSTEM_CONTRACT_ADDRESS).unwrap() {
-            return false;
+            return Some("invalid L2 receiver address");
         }
 
         // CHeck if the amount can cover the transaction cost.
         let gas_fee = U256::from(GAS_LIMIT) * U256::from(MAX_FEE_PER_GAS);
-        self.value() >= gas_fee
+        if self.value() < gas_fee {
+            return Some("amount doesn't cover the L2 transaction cost");
+        }
+        None
     }
 
     pub fn l1_tx(&self) -> Option<L1Tx> {
@@ -30,6 +30,10 @@ async fn main() -> Result<()> {
         external_apis: vec![],
         fee_strategies: vec![],
         use_rpc_for_fee_rate: None,
+        esplora_url: None,
+        fee_rate_providers: None,
+        fee_rate_aggregation: None,
+        fee_rate_quorum: None,
     };
     let client = Arc::new(BitcoinClient::new(&RPC_URL, auth, config)?);
     let inscriber = Inscriber::new(client, &PK, None)
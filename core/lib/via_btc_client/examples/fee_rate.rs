@@ -29,6 +29,10 @@ async fn main() -> Result<()> {
         external_apis: vec!["https://mempool.space/testnet/api/v1/fees/recommended".into()],
         fee_strategies: vec!["fastestFee".into()],
         use_rpc_for_fee_rate: Some(use_rpc_for_fee_rate),
+        esplora_url: None,
+        fee_rate_providers: None,
+        fee_rate_aggregation: None,
+        fee_rate_quorum: None,
     };
     let client = Arc::new(BitcoinClient::new(&RPC_URL, auth, config)?);
     let fee_rate = client.get_fee_rate(1).await?;
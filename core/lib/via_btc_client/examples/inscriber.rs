@@ -27,6 +27,10 @@ async fn main() -> Result<()> {
         external_apis: vec![],
         fee_strategies: vec![],
         use_rpc_for_fee_rate: None,
+        esplora_url: None,
+        fee_rate_providers: None,
+        fee_rate_aggregation: None,
+        fee_rate_quorum: None,
     };
     let client = Arc::new(BitcoinClient::new(&rpc_url, auth, config)?);
 
@@ -65,6 +69,7 @@ async fn main() -> Result<()> {
         l1_batch_reveal_txid: inscribe_info.final_reveal_tx.txid,
         da_identifier: "da_identifier_celestia".to_string(),
         blob_id: "proof_temp_blob_id".to_string(),
+        next_l1_batch_reveal_txids: vec![],
     };
 
     let _da_proof_ref_reveal_txid = inscriber_instance
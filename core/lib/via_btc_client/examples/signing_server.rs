@@ -0,0 +1,51 @@
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+
+use anyhow::{Context, Result};
+use via_btc_client::{
+    signer::{server::spawn_signing_server, KeyManager},
+    types::BitcoinNetwork,
+};
+
+const NETWORK: BitcoinNetwork = BitcoinNetwork::Regtest;
+const DEFAULT_ADDR: &str = "127.0.0.1:3035";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    // Serve the key from an encrypted keystore or, for local setups, from a WIF.
+
+    // export SIGNER_KEYSTORE_PATH="/path/to/keystore.json"
+    // export SIGNER_KEYSTORE_PASSWORD="password"
+    // or
+    // export SIGNER_PRV=example_wif
+    //
+    // export SIGNER_ADDR="127.0.0.1:3035" (optional)
+    // export SIGNER_AUTH_TOKEN="token" (optional)
+
+    let key_manager = match std::env::var("SIGNER_KEYSTORE_PATH") {
+        Ok(path) => {
+            let password = std::env::var("SIGNER_KEYSTORE_PASSWORD")
+                .context("SIGNER_KEYSTORE_PASSWORD not set")?;
+            KeyManager::from_keystore(&PathBuf::from(path), &password, NETWORK)?
+        }
+        Err(_) => {
+            let prv = std::env::var("SIGNER_PRV")
+                .context("Neither SIGNER_KEYSTORE_PATH nor SIGNER_PRV is set")?;
+            KeyManager::new(&prv, NETWORK)?
+        }
+    };
+    let addr: SocketAddr = std::env::var("SIGNER_ADDR")
+        .unwrap_or_else(|_| DEFAULT_ADDR.to_string())
+        .parse()
+        .context("Invalid SIGNER_ADDR")?;
+    let auth_token = std::env::var("SIGNER_AUTH_TOKEN").ok();
+
+    println!("Serving signer for {:?}", key_manager);
+    let (local_addr, handle) =
+        spawn_signing_server(Arc::new(key_manager), auth_token, addr).await?;
+    println!("Signing server listening on {local_addr}");
+
+    handle.await?;
+    Ok(())
+}
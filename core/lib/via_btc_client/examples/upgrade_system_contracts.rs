@@ -27,6 +27,10 @@ async fn create_inscriber(
         external_apis: vec![],
         fee_strategies: vec![],
         use_rpc_for_fee_rate: None,
+        esplora_url: None,
+        fee_rate_providers: None,
+        fee_rate_aggregation: None,
+        fee_rate_quorum: None,
     };
     let client = Arc::new(BitcoinClient::new(rpc_url, auth, config)?);
     Inscriber::new(client, signer_private_key, None)
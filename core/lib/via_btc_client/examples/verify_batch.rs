@@ -23,6 +23,10 @@ async fn create_inscriber(signer_private_key: &str) -> anyhow::Result<Inscriber>
         external_apis: vec![],
         fee_strategies: vec![],
         use_rpc_for_fee_rate: None,
+        esplora_url: None,
+        fee_rate_providers: None,
+        fee_rate_aggregation: None,
+        fee_rate_quorum: None,
     };
     let client = Arc::new(BitcoinClient::new(RPC_URL, auth, config)?);
     Inscriber::new(client, signer_private_key, None)
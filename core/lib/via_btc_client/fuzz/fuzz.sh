@@ -0,0 +1,2 @@
+# Usage: ./fuzz.sh <parse_system_transaction|parse_op_return|decode_message>
+cargo +nightly fuzz run --sanitizer none --release "${1:-parse_system_transaction}"
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use via_btc_client::{traits::Serializable, types::InscriptionMessage};
+
+fuzz_target!(|input: &[u8]| {
+    if let Ok(message) = InscriptionMessage::from_bytes(input) {
+        let bytes = message.to_bytes().unwrap();
+        assert_eq!(InscriptionMessage::from_bytes(&bytes).unwrap(), message);
+    }
+    let _ = Vec::<InscriptionMessage>::from_bytes(input);
+});
@@ -0,0 +1,51 @@
+#![no_main]
+
+use std::str::FromStr;
+
+use bitcoin::{
+    absolute::LockTime, script::PushBytesBuf, transaction::Version, Address, Amount, Network,
+    ScriptBuf, Transaction, TxOut,
+};
+use libfuzzer_sys::fuzz_target;
+use via_btc_client::{indexer::MessageParser, types::TransactionWithMetadata};
+use zksync_types::via_wallet::SystemWallets;
+
+fuzz_target!(|input: &[u8]| {
+    let Ok(data) = PushBytesBuf::try_from(input.to_vec()) else {
+        return;
+    };
+
+    let mut parser = MessageParser::new(Network::Regtest);
+    let wallets = system_wallets();
+
+    let tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![],
+        output: vec![
+            TxOut {
+                value: Amount::from_sat(1000),
+                script_pubkey: wallets.bridge.script_pubkey(),
+            },
+            TxOut {
+                value: Amount::ZERO,
+                script_pubkey: ScriptBuf::new_op_return(data),
+            },
+        ],
+    };
+    let mut tx = TransactionWithMetadata::new(tx, 0);
+
+    parser.parse_protocol_upgrade_transactions(&tx, 0);
+    parser.parse_bridge_transaction(&mut tx, 0, &wallets);
+});
+
+fn system_wallets() -> SystemWallets {
+    let address = |s: &str| Address::from_str(s).unwrap().assume_checked();
+
+    SystemWallets {
+        sequencer: address("bcrt1qw2mvkvm6alfhe86yf328kgvr7mupdx4vln7kpv"),
+        bridge: address("bcrt1pcx974cg2w66cqhx67zadf85t8k4sd2wp68l8x8agd3aj4tuegsgsz97amg"),
+        governance: address("bcrt1q92gkfme6k9dkpagrkwt76etkaq29hvf02w5m38f6shs4ddpw7hzqp347zm"),
+        verifiers: vec![],
+    }
+}
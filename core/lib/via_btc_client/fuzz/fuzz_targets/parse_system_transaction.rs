@@ -0,0 +1,74 @@
+#![no_main]
+
+use std::str::FromStr;
+
+use bitcoin::{
+    absolute::LockTime, consensus::deserialize, transaction::Version, Address, Network, OutPoint,
+    ScriptBuf, Sequence, Transaction, TxIn, Witness,
+};
+use libfuzzer_sys::fuzz_target;
+use via_btc_client::indexer::MessageParser;
+use zksync_types::via_wallet::SystemWallets;
+
+// Compressed and x-only encodings of the secp256k1 generator, valid keys the fuzzer can't guess.
+const PUBLIC_KEY: [u8; 33] = [
+    0x02, 0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87, 0x0b,
+    0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b, 0x16, 0xf8, 0x17,
+    0x98,
+];
+const TAPSCRIPT_LEAF_VERSION: u8 = 0xc0;
+
+fuzz_target!(|input: &[u8]| {
+    let mut parser = MessageParser::new(Network::Regtest);
+    let wallets = system_wallets();
+
+    // Arbitrary transactions.
+    if let Ok(tx) = deserialize::<Transaction>(input) {
+        parser.parse_system_transaction(&tx, 0, None);
+        parser.parse_system_transaction(&tx, 0, Some(&wallets));
+    }
+
+    // Well-formed reveal transaction with an arbitrary tapscript, to reach the message parsers.
+    let tx = reveal_transaction(input);
+    parser.parse_system_transaction(&tx, 0, None);
+    parser.parse_system_transaction(&tx, 0, Some(&wallets));
+});
+
+fn reveal_transaction(script: &[u8]) -> Transaction {
+    let mut control_block = vec![TAPSCRIPT_LEAF_VERSION];
+    control_block.extend_from_slice(&PUBLIC_KEY[1..]);
+
+    let mut inscription_witness = Witness::new();
+    inscription_witness.push([1; 64]);
+    inscription_witness.push(script);
+    inscription_witness.push(control_block);
+
+    let mut fee_payer_witness = Witness::new();
+    fee_payer_witness.push([0; 71]);
+    fee_payer_witness.push(PUBLIC_KEY);
+
+    let input = |witness| TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness,
+    };
+
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![input(fee_payer_witness), input(inscription_witness)],
+        output: vec![],
+    }
+}
+
+fn system_wallets() -> SystemWallets {
+    let address = |s: &str| Address::from_str(s).unwrap().assume_checked();
+
+    SystemWallets {
+        sequencer: address("bcrt1qw2mvkvm6alfhe86yf328kgvr7mupdx4vln7kpv"),
+        bridge: address("bcrt1pcx974cg2w66cqhx67zadf85t8k4sd2wp68l8x8agd3aj4tuegsgsz97amg"),
+        governance: address("bcrt1q92gkfme6k9dkpagrkwt76etkaq29hvf02w5m38f6shs4ddpw7hzqp347zm"),
+        verifiers: vec![],
+    }
+}
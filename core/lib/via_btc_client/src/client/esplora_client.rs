@@ -0,0 +1,713 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bitcoin::{
+    consensus::{deserialize, encode::serialize_hex},
+    Address, Block, BlockHash, OutPoint, Transaction, Txid,
+};
+use bitcoincore_rpc::json::{
+    EstimateMode, EstimateSmartFeeResult, GetBlockStatsResult, GetBlockchainInfoResult,
+    GetMempoolInfoResult, GetRawTransactionResult,
+};
+use futures::future::try_join_all;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use tracing::{debug, instrument};
+
+use crate::{
+    traits::BitcoinRpc,
+    types::{BitcoinError, BitcoinRpcResult},
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Number of transactions returned by a single `/block/:hash/txs/:start_index` page.
+const BLOCK_TXS_PAGE_SIZE: usize = 25;
+/// Minimum relay fee assumed for the mempool, as Esplora doesn't expose the node's value.
+const DEFAULT_MIN_RELAY_FEE_BTC_KVB: f64 = 0.00001;
+
+#[derive(Debug, Deserialize)]
+struct EsploraTxStatus {
+    confirmed: bool,
+    block_height: Option<u64>,
+    block_hash: Option<BlockHash>,
+    block_time: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EsploraUtxo {
+    txid: Txid,
+    vout: u32,
+    value: u64,
+    status: EsploraTxStatus,
+}
+
+#[derive(Debug, Deserialize)]
+struct EsploraBlock {
+    id: BlockHash,
+    height: u64,
+    timestamp: u64,
+    mediantime: u64,
+    tx_count: usize,
+    size: usize,
+    weight: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct EsploraVin {
+    is_coinbase: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct EsploraVout {
+    value: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct EsploraBlockTx {
+    vin: Vec<EsploraVin>,
+    vout: Vec<EsploraVout>,
+    size: usize,
+    weight: usize,
+    fee: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct EsploraMempool {
+    count: u64,
+    vsize: u64,
+    total_fee: u64,
+    fee_histogram: Vec<(f64, u64)>,
+}
+
+/// [`BitcoinRpc`] implementation backed by the [Esplora](https://github.com/Blockstream/esplora/blob/master/API.md)
+/// REST API.
+///
+/// Unlike bitcoind, Esplora indexes every address, so UTXOs and balances can be queried without
+/// a wallet-enabled node or a slow `scantxoutset`. Block statistics are derived from the block's
+/// transaction list, and `getblockchaininfo` isn't available.
+#[derive(Debug, Clone)]
+pub struct EsploraClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl EsploraClient {
+    #[instrument(target = "bitcoin_client::esplora_client")]
+    pub fn new(base_url: &str) -> BitcoinRpcResult<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| BitcoinError::Rpc(format!("failed to build HTTP client: {e}")))?;
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    async fn get(&self, path: &str) -> BitcoinRpcResult<reqwest::Response> {
+        let url = format!("{}{}", self.base_url, path);
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| BitcoinError::Rpc(format!("GET {path} failed: {e}")))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            let body = response.text().await.unwrap_or_default();
+            return Err(BitcoinError::NotFound(format!("GET {path}: {body}")));
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(BitcoinError::Rpc(format!(
+                "GET {path} returned {status}: {body}"
+            )));
+        }
+        Ok(response)
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> BitcoinRpcResult<T> {
+        let bytes = self
+            .get(path)
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| BitcoinError::Rpc(e.to_string()))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| BitcoinError::Rpc(format!("failed to decode {path} response: {e}")))
+    }
+
+    async fn get_text(&self, path: &str) -> BitcoinRpcResult<String> {
+        let text = self
+            .get(path)
+            .await?
+            .text()
+            .await
+            .map_err(|e| BitcoinError::Rpc(e.to_string()))?;
+        Ok(text.trim().to_string())
+    }
+
+    async fn get_raw<T: bitcoin::consensus::Decodable>(&self, path: &str) -> BitcoinRpcResult<T> {
+        let bytes = self
+            .get(path)
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| BitcoinError::Rpc(e.to_string()))?;
+        deserialize(&bytes).map_err(|e| BitcoinError::Rpc(e.to_string()))
+    }
+
+    async fn confirmed_utxos(&self, address: &Address) -> BitcoinRpcResult<Vec<EsploraUtxo>> {
+        let utxos: Vec<EsploraUtxo> = self.get_json(&format!("/address/{address}/utxo")).await?;
+        // bitcoind-based lookups only return outputs with at least one confirmation.
+        Ok(utxos
+            .into_iter()
+            .filter(|utxo| utxo.status.confirmed)
+            .collect())
+    }
+
+    async fn get_block_hash(&self, block_height: u128) -> BitcoinRpcResult<BlockHash> {
+        let hash = self
+            .get_text(&format!("/block-height/{block_height}"))
+            .await?;
+        Ok(hash.parse()?)
+    }
+
+    async fn get_block_txs(&self, block: &EsploraBlock) -> BitcoinRpcResult<Vec<EsploraBlockTx>> {
+        let pages = (0..block.tx_count)
+            .step_by(BLOCK_TXS_PAGE_SIZE)
+            .map(|start_index| {
+                self.get_json::<Vec<EsploraBlockTx>>(&format!(
+                    "/block/{}/txs/{start_index}",
+                    block.id
+                ))
+            });
+        Ok(try_join_all(pages).await?.into_iter().flatten().collect())
+    }
+}
+
+/// Computes the `getblockstats` fields the Via nodes rely on from the block's transactions.
+fn block_stats_json(block: &EsploraBlock, txs: &[EsploraBlockTx]) -> serde_json::Value {
+    let non_coinbase: Vec<&EsploraBlockTx> = txs
+        .iter()
+        .filter(|tx| !tx.vin.iter().any(|vin| vin.is_coinbase))
+        .collect();
+
+    // Fee rates are expressed in sat/vB, as in bitcoind.
+    let mut fee_rates: Vec<u64> = non_coinbase
+        .iter()
+        .map(|tx| tx.fee * 4 / tx.weight.max(1) as u64)
+        .collect();
+    fee_rates.sort_unstable();
+    let mut fees: Vec<u64> = non_coinbase.iter().map(|tx| tx.fee).collect();
+    fees.sort_unstable();
+    let mut sizes: Vec<usize> = non_coinbase.iter().map(|tx| tx.size).collect();
+    sizes.sort_unstable();
+
+    let percentile = |values: &[u64], p: usize| -> u64 {
+        if values.is_empty() {
+            0
+        } else {
+            values[(values.len() - 1) * p / 100]
+        }
+    };
+    let total_fee: u64 = fees.iter().sum();
+    let total_vsize: u64 = non_coinbase
+        .iter()
+        .map(|tx| (tx.weight as u64).div_ceil(4))
+        .sum();
+    let ins: usize = non_coinbase.iter().map(|tx| tx.vin.len()).sum();
+    let outs: usize = txs.iter().map(|tx| tx.vout.len()).sum();
+    let total_out: u64 = non_coinbase
+        .iter()
+        .flat_map(|tx| tx.vout.iter())
+        .map(|vout| vout.value)
+        .sum();
+    let count = non_coinbase.len().max(1);
+
+    json!({
+        "avgfee": total_fee / count as u64,
+        "avgfeerate": total_fee.checked_div(total_vsize).unwrap_or(0),
+        "avgtxsize": sizes.iter().sum::<usize>() / count,
+        "blockhash": block.id,
+        "feerate_percentiles": [
+            percentile(&fee_rates, 10),
+            percentile(&fee_rates, 25),
+            percentile(&fee_rates, 50),
+            percentile(&fee_rates, 75),
+            percentile(&fee_rates, 90),
+        ],
+        "height": block.height,
+        "ins": ins,
+        "maxfee": fees.last().copied().unwrap_or(0),
+        "maxfeerate": fee_rates.last().copied().unwrap_or(0),
+        "maxtxsize": sizes.last().copied().unwrap_or(0),
+        "medianfee": percentile(&fees, 50),
+        "mediantime": block.mediantime,
+        "mediantxsize": sizes.get(sizes.len() / 2).copied().unwrap_or(0),
+        "minfee": fees.first().copied().unwrap_or(0),
+        "minfeerate": fee_rates.first().copied().unwrap_or(0),
+        "mintxsize": sizes.first().copied().unwrap_or(0),
+        "outs": outs,
+        "subsidy": 0,
+        "swtotal_size": 0,
+        "swtotal_weight": 0,
+        "swtxs": 0,
+        "time": block.timestamp,
+        "total_out": total_out,
+        "total_size": block.size,
+        "total_weight": block.weight,
+        "totalfee": total_fee,
+        "txs": block.tx_count,
+        "utxo_increase": outs as i64 - ins as i64,
+        "utxo_size_inc": 0,
+    })
+}
+
+/// Picks the estimate of the closest confirmation target that is not slower than `conf_target`.
+fn pick_fee_estimate(
+    estimates: &serde_json::Map<String, serde_json::Value>,
+    conf_target: u16,
+) -> Option<(u16, f64)> {
+    let mut targets: Vec<(u16, f64)> = estimates
+        .iter()
+        .filter_map(|(target, rate)| Some((target.parse().ok()?, rate.as_f64()?)))
+        .collect();
+    targets.sort_by_key(|(target, _)| *target);
+
+    targets
+        .iter()
+        .rev()
+        .find(|(target, _)| *target <= conf_target)
+        .or_else(|| targets.first())
+        .copied()
+}
+
+#[async_trait]
+impl BitcoinRpc for EsploraClient {
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_balance(&self, address: &Address) -> BitcoinRpcResult<u64> {
+        debug!("Getting balance");
+        let utxos = self.confirmed_utxos(address).await?;
+        Ok(utxos.iter().map(|utxo| utxo.value).sum())
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_balance_scan(&self, address: &Address) -> BitcoinRpcResult<u64> {
+        self.get_balance(address).await
+    }
+
+    #[instrument(skip(self, tx_hex), target = "bitcoin_client::esplora_client")]
+    async fn send_raw_transaction(&self, tx_hex: &str) -> BitcoinRpcResult<Txid> {
+        debug!("Sending raw transaction");
+        let response = self
+            .http
+            .post(format!("{}/tx", self.base_url))
+            .body(tx_hex.to_string())
+            .send()
+            .await
+            .map_err(|e| BitcoinError::Rpc(format!("POST /tx failed: {e}")))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| BitcoinError::Rpc(e.to_string()))?;
+        if !status.is_success() {
+            return Err(BitcoinError::Rpc(format!(
+                "POST /tx returned {status}: {body}"
+            )));
+        }
+        Ok(body.trim().parse()?)
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn list_unspent_based_on_node_wallet(
+        &self,
+        address: &Address,
+    ) -> BitcoinRpcResult<Vec<OutPoint>> {
+        self.list_unspent(address).await
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn list_unspent(&self, address: &Address) -> BitcoinRpcResult<Vec<OutPoint>> {
+        debug!("Listing unspent outputs");
+        let utxos = self.confirmed_utxos(address).await?;
+        Ok(utxos
+            .into_iter()
+            .map(|utxo| OutPoint {
+                txid: utxo.txid,
+                vout: utxo.vout,
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_transaction(&self, txid: &Txid) -> BitcoinRpcResult<Transaction> {
+        debug!("Getting transaction");
+        self.get_raw(&format!("/tx/{txid}/raw")).await
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_block_count(&self) -> BitcoinRpcResult<u64> {
+        debug!("Getting block count");
+        let height = self.get_text("/blocks/tip/height").await?;
+        height
+            .parse()
+            .map_err(|e| BitcoinError::Rpc(format!("invalid tip height {height}: {e}")))
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_block_by_height(&self, block_height: u128) -> BitcoinRpcResult<Block> {
+        debug!("Getting block by height");
+        let block_hash = self.get_block_hash(block_height).await?;
+        self.get_block_by_hash(&block_hash).await
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_blocks_by_height(&self, block_heights: &[u128]) -> BitcoinRpcResult<Vec<Block>> {
+        debug!("Getting {} blocks by height", block_heights.len());
+        try_join_all(
+            block_heights
+                .iter()
+                .map(|block_height| self.get_block_by_height(*block_height)),
+        )
+        .await
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_block_by_hash(&self, block_hash: &BlockHash) -> BitcoinRpcResult<Block> {
+        debug!("Getting block by hash");
+        self.get_raw(&format!("/block/{block_hash}/raw")).await
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_best_block_hash(&self) -> BitcoinRpcResult<BlockHash> {
+        debug!("Getting best block hash");
+        let hash = self.get_text("/blocks/tip/hash").await?;
+        Ok(hash.parse()?)
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_block_stats(&self, height: u64) -> BitcoinRpcResult<GetBlockStatsResult> {
+        debug!("Getting block stats");
+        let block_hash = self.get_block_hash(height as u128).await?;
+        let block: EsploraBlock = self.get_json(&format!("/block/{block_hash}")).await?;
+        let txs = self.get_block_txs(&block).await?;
+        serde_json::from_value(block_stats_json(&block, &txs))
+            .map_err(|e| BitcoinError::Rpc(format!("failed to build block stats: {e}")))
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_block_stats_batch(
+        &self,
+        heights: &[u64],
+    ) -> BitcoinRpcResult<Vec<GetBlockStatsResult>> {
+        debug!("Getting block stats of {} blocks", heights.len());
+        try_join_all(heights.iter().map(|height| self.get_block_stats(*height))).await
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_raw_transaction_info(
+        &self,
+        txid: &Txid,
+    ) -> BitcoinRpcResult<GetRawTransactionResult> {
+        debug!("Getting raw transaction info");
+        let tx = self.get_transaction(txid).await?;
+        let status: EsploraTxStatus = self.get_json(&format!("/tx/{txid}/status")).await?;
+        let confirmations = match status.block_height {
+            Some(block_height) if status.confirmed => {
+                let tip = self.get_block_count().await?;
+                Some(tip.saturating_sub(block_height) + 1)
+            }
+            _ => None,
+        };
+
+        // Inputs and outputs are available from `get_transaction`; only the confirmation data is
+        // filled in here.
+        serde_json::from_value(json!({
+            "in_active_chain": status.confirmed,
+            "hex": serialize_hex(&tx),
+            "txid": tx.compute_txid(),
+            "hash": tx.compute_wtxid(),
+            "size": tx.total_size(),
+            "vsize": tx.vsize(),
+            "version": tx.version.0,
+            "locktime": tx.lock_time.to_consensus_u32(),
+            "vin": [],
+            "vout": [],
+            "blockhash": status.block_hash,
+            "confirmations": confirmations,
+            "time": status.block_time,
+            "blocktime": status.block_time,
+        }))
+        .map_err(|e| BitcoinError::Rpc(format!("failed to build transaction info: {e}")))
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn estimate_smart_fee(
+        &self,
+        conf_target: u16,
+        _estimate_mode: Option<EstimateMode>,
+    ) -> BitcoinRpcResult<EstimateSmartFeeResult> {
+        debug!("Estimating smart fee");
+        let estimates: serde_json::Map<String, serde_json::Value> =
+            self.get_json("/fee-estimates").await?;
+
+        Ok(match pick_fee_estimate(&estimates, conf_target) {
+            Some((blocks, sat_per_vb)) => EstimateSmartFeeResult {
+                fee_rate: Some(bitcoin::Amount::from_sat(
+                    (sat_per_vb * 1000.0).round() as u64
+                )),
+                errors: None,
+                blocks: blocks as i64,
+            },
+            None => EstimateSmartFeeResult {
+                fee_rate: None,
+                errors: Some(vec!["Esplora returned no fee estimates".to_string()]),
+                blocks: conf_target as i64,
+            },
+        })
+    }
+
+    async fn get_blockchain_info(&self) -> BitcoinRpcResult<GetBlockchainInfoResult> {
+        Err(BitcoinError::Rpc(
+            "getblockchaininfo is not supported by the Esplora backend".to_string(),
+        ))
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_mempool_info(&self) -> BitcoinRpcResult<GetMempoolInfoResult> {
+        debug!("Getting mempool info");
+        let mempool: EsploraMempool = self.get_json("/mempool").await?;
+        serde_json::from_value(json!({
+            "loaded": true,
+            "size": mempool.count,
+            "bytes": mempool.vsize,
+            "usage": mempool.vsize,
+            "total_fee": bitcoin::Amount::from_sat(mempool.total_fee).to_btc(),
+            "maxmempool": 300_000_000u64,
+            "mempoolminfee": DEFAULT_MIN_RELAY_FEE_BTC_KVB,
+            "minrelaytxfee": DEFAULT_MIN_RELAY_FEE_BTC_KVB,
+            "incrementalrelayfee": DEFAULT_MIN_RELAY_FEE_BTC_KVB,
+            "unbroadcastcount": 0,
+            "fullrbf": false,
+        }))
+        .map_err(|e| BitcoinError::Rpc(format!("failed to build mempool info: {e}")))
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::esplora_client")]
+    async fn get_mempool_fee_histogram(&self) -> BitcoinRpcResult<Vec<(f64, u64)>> {
+        debug!("Getting mempool fee histogram");
+        let mempool: EsploraMempool = self.get_json("/mempool").await?;
+        Ok(mempool.fee_histogram)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use axum::{
+        extract::Path,
+        http::StatusCode,
+        routing::{get, post},
+        Json, Router,
+    };
+    use bitcoin::{
+        absolute::LockTime, block::Header, hashes::Hash, transaction::Version, Amount, Network,
+        TxIn, TxMerkleNode, TxOut,
+    };
+
+    use super::*;
+
+    fn test_tx() -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output: vec![TxOut {
+                value: Amount::from_sat(50_000),
+                script_pubkey: Default::default(),
+            }],
+        }
+    }
+
+    fn test_block() -> Block {
+        Block {
+            header: Header {
+                version: Default::default(),
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: 1_700_000_000,
+                bits: Default::default(),
+                nonce: 0,
+            },
+            txdata: vec![test_tx()],
+        }
+    }
+
+    /// Spawns a minimal Esplora stand-in on a random local port and returns its base URL.
+    async fn spawn_mock_esplora() -> String {
+        let block_hash = test_block().block_hash();
+        let txid = test_tx().compute_txid();
+
+        let app = Router::new()
+            .route(
+                "/address/:address/utxo",
+                get(move || async move {
+                    Json(json!([
+                        {
+                            "txid": txid,
+                            "vout": 0,
+                            "value": 50_000,
+                            "status": { "confirmed": true, "block_height": 100, "block_hash": block_hash, "block_time": 1_700_000_000 }
+                        },
+                        {
+                            "txid": txid,
+                            "vout": 1,
+                            "value": 10_000,
+                            "status": { "confirmed": false }
+                        }
+                    ]))
+                }),
+            )
+            .route(
+                "/tx/:txid/raw",
+                get(|| async { bitcoin::consensus::serialize(&test_tx()) }),
+            )
+            .route(
+                "/tx/:txid/status",
+                get(move || async move {
+                    Json(json!({ "confirmed": true, "block_height": 100, "block_hash": block_hash, "block_time": 1_700_000_000 }))
+                }),
+            )
+            .route("/blocks/tip/height", get(|| async { "102" }))
+            .route(
+                "/block-height/:height",
+                get(move |Path(height): Path<u64>| async move {
+                    if height == 100 {
+                        Ok(block_hash.to_string())
+                    } else {
+                        Err((StatusCode::NOT_FOUND, "Block not found"))
+                    }
+                }),
+            )
+            .route(
+                "/block/:hash/raw",
+                get(|| async { bitcoin::consensus::serialize(&test_block()) }),
+            )
+            .route(
+                "/fee-estimates",
+                get(|| async { Json(json!({ "1": 20.5, "3": 10.0, "6": 5.2, "144": 1.0 })) }),
+            )
+            .route(
+                "/tx",
+                post(move |body: String| async move {
+                    assert_eq!(body, "deadbeef");
+                    txid.to_string()
+                }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}/")
+    }
+
+    fn test_address() -> Address {
+        Address::from_str("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq")
+            .unwrap()
+            .require_network(Network::Bitcoin)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_utxos_and_balance() {
+        let client = EsploraClient::new(&spawn_mock_esplora().await).unwrap();
+
+        let utxos = client.list_unspent(&test_address()).await.unwrap();
+        assert_eq!(
+            utxos,
+            vec![OutPoint {
+                txid: test_tx().compute_txid(),
+                vout: 0
+            }]
+        );
+        assert_eq!(client.get_balance(&test_address()).await.unwrap(), 50_000);
+    }
+
+    #[tokio::test]
+    async fn test_transactions_and_blocks() {
+        let client = EsploraClient::new(&spawn_mock_esplora().await).unwrap();
+        let txid = test_tx().compute_txid();
+
+        assert_eq!(client.get_transaction(&txid).await.unwrap(), test_tx());
+        assert_eq!(client.get_block_count().await.unwrap(), 102);
+        assert_eq!(client.get_block_by_height(100).await.unwrap(), test_block());
+        assert!(client.get_block_by_height(101).await.is_err());
+
+        let info = client.get_raw_transaction_info(&txid).await.unwrap();
+        assert_eq!(info.confirmations, Some(3));
+        assert_eq!(info.blockhash, Some(test_block().block_hash()));
+
+        assert_eq!(client.send_raw_transaction("deadbeef").await.unwrap(), txid);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_smart_fee() {
+        let client = EsploraClient::new(&spawn_mock_esplora().await).unwrap();
+
+        let estimate = client.estimate_smart_fee(4, None).await.unwrap();
+        assert_eq!(estimate.blocks, 3);
+        assert_eq!(estimate.fee_rate, Some(Amount::from_sat(10_000)));
+
+        // Targets faster than any estimate fall back to the fastest one.
+        let estimate = client.estimate_smart_fee(0, None).await.unwrap();
+        assert_eq!(estimate.blocks, 1);
+    }
+
+    #[test]
+    fn test_block_stats_json() {
+        let block = EsploraBlock {
+            id: BlockHash::all_zeros(),
+            height: 10,
+            timestamp: 1_700_000_000,
+            mediantime: 1_699_999_000,
+            tx_count: 3,
+            size: 1000,
+            weight: 4000,
+        };
+        let txs = vec![
+            EsploraBlockTx {
+                vin: vec![EsploraVin { is_coinbase: true }],
+                vout: vec![EsploraVout { value: 625_000_000 }],
+                size: 100,
+                weight: 400,
+                fee: 0,
+            },
+            EsploraBlockTx {
+                vin: vec![EsploraVin { is_coinbase: false }],
+                vout: vec![EsploraVout { value: 1_000 }],
+                size: 200,
+                weight: 800,
+                fee: 400,
+            },
+            EsploraBlockTx {
+                vin: vec![EsploraVin { is_coinbase: false }],
+                vout: vec![EsploraVout { value: 2_000 }],
+                size: 200,
+                weight: 800,
+                fee: 2_000,
+            },
+        ];
+
+        let stats: GetBlockStatsResult =
+            serde_json::from_value(block_stats_json(&block, &txs)).unwrap();
+        assert_eq!(stats.min_fee_rate, Amount::from_sat(2));
+        assert_eq!(stats.max_fee_rate, Amount::from_sat(10));
+        assert_eq!(stats.time, 1_700_000_000);
+        assert_eq!(stats.txs, 3);
+    }
+}
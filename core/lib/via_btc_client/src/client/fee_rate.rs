@@ -0,0 +1,545 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use bitcoin::Amount;
+use bitcoincore_rpc::json::{EstimateMode, FeeRatePercentiles};
+use futures::future::join_all;
+use tokio::sync::Mutex;
+use tracing::warn;
+use zksync_config::configs::via_btc_client::ViaBtcClientConfig;
+
+use crate::{
+    metrics::{FeeRateProviderLabel, METRICS},
+    traits::BitcoinRpc,
+    types::{BitcoinClientResult, BitcoinError},
+};
+
+/// Virtual size of a full block, used to find which mempool transactions make it in the next blocks.
+const BLOCK_VSIZE: u64 = 1_000_000;
+/// Number of recent blocks whose fee rate percentiles are taken into account.
+const BLOCK_STATS_WINDOW: u64 = 6;
+/// Minimum relay fee rate (sat/kB), enough when the whole mempool fits in the target blocks.
+const MIN_RELAY_FEE_RATE_SAT_KB: u64 = 1000;
+/// How long a mempool fee histogram is reused: `getrawmempool true` is expensive on a big mempool.
+const MEMPOOL_HISTOGRAM_TTL: Duration = Duration::from_secs(30);
+
+/// A source of fee rate estimates.
+#[async_trait]
+pub trait FeeRateProvider: Send + Sync + fmt::Debug {
+    /// Name of the provider, used in logs and metrics.
+    fn name(&self) -> String;
+
+    /// Estimates the fee rate (in sat/kB) needed to confirm within `conf_target` blocks.
+    async fn estimate_fee_rate(&self, conf_target: u16) -> BitcoinClientResult<u64>;
+}
+
+/// Uses the node's `estimatesmartfee`.
+#[derive(Debug)]
+pub struct RpcFeeRateProvider {
+    rpc: Arc<dyn BitcoinRpc>,
+}
+
+impl RpcFeeRateProvider {
+    pub fn new(rpc: Arc<dyn BitcoinRpc>) -> Self {
+        Self { rpc }
+    }
+}
+
+#[async_trait]
+impl FeeRateProvider for RpcFeeRateProvider {
+    fn name(&self) -> String {
+        "rpc".to_string()
+    }
+
+    async fn estimate_fee_rate(&self, conf_target: u16) -> BitcoinClientResult<u64> {
+        let estimation = self
+            .rpc
+            .estimate_smart_fee(conf_target, Some(EstimateMode::Economical))
+            .await?;
+
+        estimation
+            .fee_rate
+            .map(|fee_rate| fee_rate.to_sat())
+            .ok_or_else(|| {
+                BitcoinError::FeeEstimationFailed(format!(
+                    "RPC fee estimate missing value: {}",
+                    estimation
+                        .errors
+                        .map(|e| e.join(", "))
+                        .unwrap_or_else(|| "Unknown error".to_string())
+                ))
+            })
+    }
+}
+
+/// Looks at the current mempool: the estimate is the lowest fee rate that still makes it in
+/// the next `conf_target` blocks if they are filled with the best paying transactions.
+#[derive(Debug)]
+pub struct MempoolFeeRateProvider {
+    rpc: Arc<dyn BitcoinRpc>,
+    /// Last fetched histogram with the time it was fetched at, shared by concurrent estimates.
+    histogram: Mutex<Option<(Instant, Vec<(f64, u64)>)>>,
+}
+
+impl MempoolFeeRateProvider {
+    pub fn new(rpc: Arc<dyn BitcoinRpc>) -> Self {
+        Self {
+            rpc,
+            histogram: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached histogram, or fetches a new one if it's older than [`MEMPOOL_HISTOGRAM_TTL`].
+    async fn histogram(&self) -> BitcoinClientResult<Vec<(f64, u64)>> {
+        let mut cached = self.histogram.lock().await;
+        if let Some((fetched_at, histogram)) = cached.as_ref() {
+            if fetched_at.elapsed() < MEMPOOL_HISTOGRAM_TTL {
+                return Ok(histogram.clone());
+            }
+        }
+
+        let histogram = self.rpc.get_mempool_fee_histogram().await?;
+        *cached = Some((Instant::now(), histogram.clone()));
+        Ok(histogram)
+    }
+}
+
+#[async_trait]
+impl FeeRateProvider for MempoolFeeRateProvider {
+    fn name(&self) -> String {
+        "mempool".to_string()
+    }
+
+    async fn estimate_fee_rate(&self, conf_target: u16) -> BitcoinClientResult<u64> {
+        let histogram = self.histogram().await?;
+        Ok(fee_rate_from_histogram(&histogram, conf_target))
+    }
+}
+
+/// Returns the fee rate (sat/kB) of the last transaction that fits in `conf_target` blocks,
+/// `histogram` being sorted by decreasing fee rate (sat/vB).
+fn fee_rate_from_histogram(histogram: &[(f64, u64)], conf_target: u16) -> u64 {
+    let capacity = BLOCK_VSIZE * u64::from(conf_target.max(1));
+    let mut filled = 0;
+    for (fee_rate, vsize) in histogram {
+        filled += vsize;
+        if filled >= capacity {
+            return (fee_rate * 1000.0).round() as u64;
+        }
+    }
+    MIN_RELAY_FEE_RATE_SAT_KB
+}
+
+/// Looks at the fee rates paid in the recent blocks (`getblockstats`). The percentile depends on
+/// the confirmation target, and the median over the last blocks is used.
+#[derive(Debug)]
+pub struct BlockStatsFeeRateProvider {
+    rpc: Arc<dyn BitcoinRpc>,
+}
+
+impl BlockStatsFeeRateProvider {
+    pub fn new(rpc: Arc<dyn BitcoinRpc>) -> Self {
+        Self { rpc }
+    }
+}
+
+#[async_trait]
+impl FeeRateProvider for BlockStatsFeeRateProvider {
+    fn name(&self) -> String {
+        "block_stats".to_string()
+    }
+
+    async fn estimate_fee_rate(&self, conf_target: u16) -> BitcoinClientResult<u64> {
+        let tip = self.rpc.get_block_count().await?;
+        let heights: Vec<u64> = (tip.saturating_sub(BLOCK_STATS_WINDOW - 1)..=tip).collect();
+        let fee_rates: Vec<u64> = self
+            .rpc
+            .get_block_stats_batch(&heights)
+            .await?
+            .iter()
+            .map(|stats| block_fee_rate(&stats.fee_rate_percentiles, conf_target).to_sat() * 1000)
+            .collect();
+
+        median(&fee_rates).ok_or_else(|| {
+            BitcoinError::FeeEstimationFailed("No block stats available".to_string())
+        })
+    }
+}
+
+/// Faster targets have to outbid more of the transactions included in the recent blocks.
+fn block_fee_rate(percentiles: &FeeRatePercentiles, conf_target: u16) -> Amount {
+    match conf_target {
+        0..=1 => percentiles.fr_75th,
+        2..=3 => percentiles.fr_50th,
+        4..=6 => percentiles.fr_25th,
+        _ => percentiles.fr_10th,
+    }
+}
+
+/// Reads the fee rate (sat/vB) under `fee_key` in the JSON returned by `url`, e.g. the
+/// `fastestFee` of mempool.space's `/api/v1/fees/recommended`.
+#[derive(Debug)]
+pub struct ExternalApiFeeRateProvider {
+    http: reqwest::Client,
+    url: String,
+    fee_key: String,
+}
+
+impl ExternalApiFeeRateProvider {
+    pub fn new(url: String, fee_key: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url,
+            fee_key,
+        }
+    }
+}
+
+#[async_trait]
+impl FeeRateProvider for ExternalApiFeeRateProvider {
+    fn name(&self) -> String {
+        format!("external_api:{}", self.url)
+    }
+
+    async fn estimate_fee_rate(&self, _conf_target: u16) -> BitcoinClientResult<u64> {
+        let json: serde_json::Value = self
+            .http
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|e| {
+                BitcoinError::FeeEstimationFailed(format!("Failed to fetch {}: {e}", self.url))
+            })?
+            .json()
+            .await
+            .map_err(|e| {
+                BitcoinError::FeeEstimationFailed(format!(
+                    "Failed to parse JSON from {}: {e}",
+                    self.url
+                ))
+            })?;
+
+        let fee_rate_vb = json
+            .get(&self.fee_key)
+            .and_then(|v| v.as_f64())
+            .ok_or_else(|| {
+                BitcoinError::FeeEstimationFailed(format!(
+                    "Missing '{}' in response from {}",
+                    self.fee_key, self.url
+                ))
+            })?;
+        Ok((fee_rate_vb * 1000.0).round() as u64)
+    }
+}
+
+/// How the estimates of several providers are combined into a single fee rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeRateAggregation {
+    /// The providers are queried in order and the first estimate is used.
+    Fallback,
+    /// The median of all the estimates.
+    Median,
+    /// The lowest of the `n` highest estimates, i.e. the highest fee rate that at least `n`
+    /// providers consider necessary. A single provider can't push the fee rate up or down.
+    MinOfQuorum(usize),
+}
+
+impl FeeRateAggregation {
+    pub fn from_config(config: &ViaBtcClientConfig) -> BitcoinClientResult<Self> {
+        match config.fee_rate_aggregation().as_str() {
+            "fallback" => Ok(Self::Fallback),
+            "median" => Ok(Self::Median),
+            "min_of_quorum" => Ok(Self::MinOfQuorum(config.fee_rate_quorum().max(1))),
+            other => Err(BitcoinError::FeeEstimationFailed(format!(
+                "Unknown fee rate aggregation: {other}"
+            ))),
+        }
+    }
+
+    /// Returns `None` if there are not enough estimates.
+    fn aggregate(&self, fee_rates: &[u64]) -> Option<u64> {
+        match self {
+            Self::Fallback => fee_rates.first().copied(),
+            Self::Median => median(fee_rates),
+            Self::MinOfQuorum(quorum) => {
+                let mut sorted = fee_rates.to_vec();
+                sorted.sort_unstable();
+                sorted.len().checked_sub(*quorum).map(|index| sorted[index])
+            }
+        }
+    }
+}
+
+fn median(values: &[u64]) -> Option<u64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2),
+        _ => Some(sorted[mid]),
+    }
+}
+
+/// Outcome of a fee rate estimation, with what each of the queried providers returned.
+#[derive(Debug, Clone)]
+pub struct FeeRateEstimation {
+    pub conf_target: u16,
+    pub aggregation: FeeRateAggregation,
+    /// Estimates (sat/kB) of the providers that answered.
+    pub estimates: Vec<(String, u64)>,
+    /// Errors of the providers that failed.
+    pub failures: Vec<(String, String)>,
+    /// The aggregated fee rate (sat/kB).
+    pub fee_rate_sat_kb: u64,
+}
+
+impl fmt::Display for FeeRateEstimation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} sat/kB for {} blocks ({:?} of [",
+            self.fee_rate_sat_kb, self.conf_target, self.aggregation
+        )?;
+        for (i, (provider, fee_rate)) in self.estimates.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{provider}: {fee_rate}")?;
+        }
+        write!(f, "]")?;
+        for (provider, error) in &self.failures {
+            write!(f, ", {provider} failed: {error}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Queries the configured [`FeeRateProvider`]s and aggregates their estimates.
+#[derive(Debug, Clone)]
+pub struct FeeRateEstimator {
+    providers: Vec<Arc<dyn FeeRateProvider>>,
+    aggregation: FeeRateAggregation,
+}
+
+impl FeeRateEstimator {
+    pub fn new(providers: Vec<Arc<dyn FeeRateProvider>>, aggregation: FeeRateAggregation) -> Self {
+        Self {
+            providers,
+            aggregation,
+        }
+    }
+
+    pub fn from_config(
+        config: &ViaBtcClientConfig,
+        rpc: Arc<dyn BitcoinRpc>,
+    ) -> BitcoinClientResult<Self> {
+        let mut providers: Vec<Arc<dyn FeeRateProvider>> = vec![];
+        for provider in config.fee_rate_providers() {
+            match provider.as_str() {
+                "rpc" => providers.push(Arc::new(RpcFeeRateProvider::new(rpc.clone()))),
+                "mempool" => providers.push(Arc::new(MempoolFeeRateProvider::new(rpc.clone()))),
+                "block_stats" => {
+                    providers.push(Arc::new(BlockStatsFeeRateProvider::new(rpc.clone())))
+                }
+                "external_api" => {
+                    for (url, fee_key) in config.external_apis.iter().zip(&config.fee_strategies) {
+                        providers.push(Arc::new(ExternalApiFeeRateProvider::new(
+                            url.clone(),
+                            fee_key.clone(),
+                        )));
+                    }
+                }
+                other => {
+                    return Err(BitcoinError::FeeEstimationFailed(format!(
+                        "Unknown fee rate provider: {other}"
+                    )))
+                }
+            }
+        }
+
+        Ok(Self::new(
+            providers,
+            FeeRateAggregation::from_config(config)?,
+        ))
+    }
+
+    pub async fn estimate(&self, conf_target: u16) -> BitcoinClientResult<FeeRateEstimation> {
+        let mut estimation = FeeRateEstimation {
+            conf_target,
+            aggregation: self.aggregation,
+            estimates: vec![],
+            failures: vec![],
+            fee_rate_sat_kb: 0,
+        };
+
+        if self.aggregation == FeeRateAggregation::Fallback {
+            for provider in &self.providers {
+                let result = provider.estimate_fee_rate(conf_target).await;
+                if record_estimate(&mut estimation, provider.as_ref(), result) {
+                    break;
+                }
+            }
+        } else {
+            let results = join_all(
+                self.providers
+                    .iter()
+                    .map(|provider| provider.estimate_fee_rate(conf_target)),
+            )
+            .await;
+            for (provider, result) in self.providers.iter().zip(results) {
+                record_estimate(&mut estimation, provider.as_ref(), result);
+            }
+        }
+
+        let fee_rates: Vec<u64> = estimation.estimates.iter().map(|(_, rate)| *rate).collect();
+        estimation.fee_rate_sat_kb = self.aggregation.aggregate(&fee_rates).ok_or_else(|| {
+            BitcoinError::FeeEstimationFailed(format!(
+                "Not enough fee rate estimates: {estimation}"
+            ))
+        })?;
+
+        Ok(estimation)
+    }
+}
+
+/// Adds the provider's result to `estimation`, returns whether it succeeded.
+fn record_estimate(
+    estimation: &mut FeeRateEstimation,
+    provider: &dyn FeeRateProvider,
+    result: BitcoinClientResult<u64>,
+) -> bool {
+    let label = FeeRateProviderLabel {
+        provider: provider.name(),
+    };
+    match result {
+        Ok(fee_rate) => {
+            METRICS.fee_rate_estimates[&label].set(fee_rate);
+            estimation.estimates.push((provider.name(), fee_rate));
+            true
+        }
+        Err(err) => {
+            METRICS.fee_rate_provider_errors[&label].inc();
+            warn!("Fee rate provider {} failed: {err}", provider.name());
+            estimation.failures.push((provider.name(), err.to_string()));
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct FixedFeeRateProvider {
+        name: &'static str,
+        fee_rate: Option<u64>,
+    }
+
+    #[async_trait]
+    impl FeeRateProvider for FixedFeeRateProvider {
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+
+        async fn estimate_fee_rate(&self, _conf_target: u16) -> BitcoinClientResult<u64> {
+            self.fee_rate
+                .ok_or_else(|| BitcoinError::FeeEstimationFailed("unavailable".to_string()))
+        }
+    }
+
+    fn estimator(fee_rates: &[Option<u64>], aggregation: FeeRateAggregation) -> FeeRateEstimator {
+        const NAMES: [&str; 4] = ["a", "b", "c", "d"];
+        let providers = fee_rates
+            .iter()
+            .zip(NAMES)
+            .map(|(fee_rate, name)| {
+                Arc::new(FixedFeeRateProvider {
+                    name,
+                    fee_rate: *fee_rate,
+                }) as Arc<dyn FeeRateProvider>
+            })
+            .collect();
+        FeeRateEstimator::new(providers, aggregation)
+    }
+
+    #[tokio::test]
+    async fn test_fallback_uses_first_available_estimate() {
+        let estimation = estimator(
+            &[None, Some(5000), Some(9000)],
+            FeeRateAggregation::Fallback,
+        )
+        .estimate(1)
+        .await
+        .unwrap();
+
+        assert_eq!(estimation.fee_rate_sat_kb, 5000);
+        assert_eq!(estimation.estimates, vec![("b".to_string(), 5000)]);
+        assert_eq!(estimation.failures.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_median_ignores_outlier() {
+        let estimation = estimator(
+            &[Some(4000), Some(100_000), Some(5000), None],
+            FeeRateAggregation::Median,
+        )
+        .estimate(1)
+        .await
+        .unwrap();
+
+        assert_eq!(estimation.fee_rate_sat_kb, 5000);
+        assert_eq!(estimation.estimates.len(), 3);
+        assert_eq!(estimation.failures.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_min_of_quorum() {
+        let fee_rates = [Some(4000), Some(100_000), Some(6000), None];
+
+        let estimation = estimator(&fee_rates, FeeRateAggregation::MinOfQuorum(2))
+            .estimate(1)
+            .await
+            .unwrap();
+        assert_eq!(estimation.fee_rate_sat_kb, 6000);
+
+        let err = estimator(&fee_rates, FeeRateAggregation::MinOfQuorum(4))
+            .estimate(1)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BitcoinError::FeeEstimationFailed(_)));
+    }
+
+    #[test]
+    fn test_fee_rate_from_histogram() {
+        let histogram = [(50.0, 400_000), (20.5, 800_000), (10.0, 1_000_000)];
+
+        assert_eq!(fee_rate_from_histogram(&histogram, 1), 20_500);
+        assert_eq!(fee_rate_from_histogram(&histogram, 2), 10_000);
+        assert_eq!(
+            fee_rate_from_histogram(&histogram, 3),
+            MIN_RELAY_FEE_RATE_SAT_KB
+        );
+    }
+
+    #[test]
+    fn test_block_fee_rate() {
+        let percentiles = FeeRatePercentiles {
+            fr_10th: Amount::from_sat(1),
+            fr_25th: Amount::from_sat(2),
+            fr_50th: Amount::from_sat(3),
+            fr_75th: Amount::from_sat(4),
+            fr_90th: Amount::from_sat(5),
+        };
+
+        assert_eq!(block_fee_rate(&percentiles, 1), Amount::from_sat(4));
+        assert_eq!(block_fee_rate(&percentiles, 3), Amount::from_sat(3));
+        assert_eq!(block_fee_rate(&percentiles, 6), Amount::from_sat(2));
+        assert_eq!(block_fee_rate(&percentiles, 144), Amount::from_sat(1));
+    }
+}
@@ -2,17 +2,28 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use bitcoin::{Address, Block, BlockHash, Network, OutPoint, Transaction, TxOut, Txid};
-use bitcoincore_rpc::json::{EstimateMode, GetBlockStatsResult};
-use futures::future::join_all;
-use tracing::{debug, error, instrument};
+use bitcoincore_rpc::json::GetBlockStatsResult;
+use tracing::{debug, error, info, instrument};
 use zksync_config::configs::via_btc_client::ViaBtcClientConfig;
 
+mod esplora_client;
 mod fee_limits;
+mod fee_rate;
 mod rpc_client;
-
+mod transport;
+
+pub use self::{
+    esplora_client::EsploraClient,
+    fee_rate::{
+        BlockStatsFeeRateProvider, ExternalApiFeeRateProvider, FeeRateAggregation,
+        FeeRateEstimation, FeeRateEstimator, FeeRateProvider, MempoolFeeRateProvider,
+        RpcFeeRateProvider,
+    },
+    rpc_client::BitcoinRpcClient,
+    transport::RpcTransportOptions,
+};
 use crate::{
-    client::{fee_limits::FeeRateLimits, rpc_client::BitcoinRpcClient},
-    metrics::{RpcMethodLabel, METRICS},
+    client::fee_limits::FeeRateLimits,
     traits::{BitcoinOps, BitcoinRpc},
     types::{BitcoinClientResult, BitcoinError, BitcoinNetwork, NodeAuth},
 };
@@ -20,6 +31,7 @@ use crate::{
 #[derive(Debug)]
 pub struct BitcoinClient {
     rpc: Arc<dyn BitcoinRpc>,
+    fee_rate_estimator: FeeRateEstimator,
     pub config: ViaBtcClientConfig,
 }
 
@@ -34,9 +46,14 @@ impl BitcoinClient {
         Self: Sized,
     {
         debug!("Creating new BitcoinClient");
-        let rpc = BitcoinRpcClient::new(rpc_url, auth)?;
+        let rpc: Arc<dyn BitcoinRpc> = match &config.esplora_url {
+            Some(esplora_url) => Arc::new(EsploraClient::new(esplora_url)?),
+            None => Arc::new(BitcoinRpcClient::new(rpc_url, auth)?),
+        };
+        let fee_rate_estimator = FeeRateEstimator::from_config(&config, rpc.clone())?;
         Ok(Self {
-            rpc: Arc::new(rpc),
+            rpc,
+            fee_rate_estimator,
             config,
         })
     }
@@ -116,85 +133,9 @@ impl BitcoinOps for BitcoinClient {
     #[instrument(skip(self), target = "bitcoin_client")]
     async fn get_fee_rate(&self, conf_target: u16) -> BitcoinClientResult<u64> {
         debug!("Estimating fee rate");
-        let mut fee_rate_sat_kb: Option<u64> = None;
-
-        if self.config.use_rpc_for_fee_rate() {
-            let estimation_result = self
-                .rpc
-                .estimate_smart_fee(conf_target, Some(EstimateMode::Economical))
-                .await;
-
-            fee_rate_sat_kb = match estimation_result {
-                Ok(estimation) => {
-                    if let Some(fee_rate) = estimation.fee_rate {
-                        Some(fee_rate.to_sat())
-                    } else {
-                        error!(
-                            "RPC fee estimate missing value: {}",
-                            estimation
-                                .errors
-                                .map(|e| e.join(", "))
-                                .unwrap_or_else(|| "Unknown error".to_string())
-                        );
-                        None
-                    }
-                }
-                Err(err) => {
-                    METRICS.rpc_errors[&RpcMethodLabel {
-                        method: "rpc_estimate_smart_fee".into(),
-                    }]
-                        .inc();
-                    error!("Failed to estimate smart fee via RPC: {:?}", err);
-                    None
-                }
-            };
-        }
-
-        // Fallback to external APIs if RPC failed or returned no fee
-        if fee_rate_sat_kb.is_none() {
-            let client = reqwest::Client::new();
-
-            for (api_url, fee_target_key) in self
-                .config
-                .external_apis
-                .iter()
-                .zip(self.config.fee_strategies.iter())
-            {
-                match client.get(api_url).send().await {
-                    Ok(resp) => match resp.json::<serde_json::Value>().await {
-                        Ok(json) => {
-                            if let Some(fee_rate_vb) =
-                                json.get(fee_target_key).and_then(|v| v.as_f64())
-                            {
-                                fee_rate_sat_kb = Some((fee_rate_vb * 1000.0).round() as u64);
-                                debug!(
-                                    "Fee rate from API {} [{}]: {} sat/vB",
-                                    api_url, fee_target_key, fee_rate_vb
-                                );
-                                break;
-                            } else {
-                                error!("Missing '{}' in response from {}", fee_target_key, api_url);
-                            }
-                        }
-                        Err(e) => {
-                            error!("Failed to parse JSON from {}: {:?}", api_url, e);
-                        }
-                    },
-                    Err(e) => {
-                        METRICS.rpc_errors[&RpcMethodLabel {
-                            method: "estimate_smart_fee".into(),
-                        }]
-                            .inc();
-                        error!("Failed to fetch from {}: {:?}", api_url, e);
-                    }
-                }
-            }
-        }
-
-        // If no fee estimate was obtained
-        let mut fee_rate_sat_kb = fee_rate_sat_kb.ok_or_else(|| {
-            BitcoinError::FeeEstimationFailed("All fee estimation methods failed".into())
-        })?;
+        let estimation = self.fee_rate_estimator.estimate(conf_target).await?;
+        info!("Fee rate estimation: {estimation}");
+        let mut fee_rate_sat_kb = estimation.fee_rate_sat_kb;
 
         // Add a small buffer to avoid precision loss
         fee_rate_sat_kb += 1000;
@@ -228,6 +169,12 @@ impl BitcoinOps for BitcoinClient {
         self.rpc.get_block_by_height(block_height).await
     }
 
+    #[instrument(skip(self), target = "bitcoin_client")]
+    async fn fetch_blocks(&self, block_heights: &[u128]) -> BitcoinClientResult<Vec<Block>> {
+        debug!("Fetching {} blocks", block_heights.len());
+        self.rpc.get_blocks_by_height(block_heights).await
+    }
+
     #[instrument(skip(self), target = "bitcoin_client")]
     async fn get_transaction(&self, txid: &Txid) -> BitcoinClientResult<Transaction> {
         debug!("Getting transaction");
@@ -255,24 +202,16 @@ impl BitcoinOps for BitcoinClient {
     ) -> BitcoinClientResult<Vec<u64>> {
         debug!("Fetching blocks fee history");
 
-        let mut fetch_blocks_futures = Vec::new();
-        for block_height in from_block_height..to_block_height {
-            fetch_blocks_futures.push(self.get_block_stats(block_height as u64));
-        }
+        // All the `getblockstats` calls are sent to the node as a single JSON-RPC batch.
+        let heights: Vec<u64> = (from_block_height as u64..to_block_height as u64).collect();
+        let fee_history = self
+            .rpc
+            .get_block_stats_batch(&heights)
+            .await?
+            .into_iter()
+            .map(|block| std::cmp::max(block.min_fee_rate.to_sat(), 1))
+            .collect();
 
-        let blocks = join_all(fetch_blocks_futures).await;
-        let mut fee_history: Vec<u64> = Vec::new();
-
-        for block_result in blocks {
-            match block_result {
-                Ok(block) => {
-                    fee_history.push(std::cmp::max(block.min_fee_rate.to_sat(), 1));
-                }
-                Err(err) => {
-                    return BitcoinClientResult::Err(err.clone());
-                }
-            }
-        }
         Ok(fee_history)
     }
 }
@@ -281,6 +220,7 @@ impl Clone for BitcoinClient {
     fn clone(&self) -> Self {
         Self {
             rpc: Arc::clone(&self.rpc),
+            fee_rate_estimator: self.fee_rate_estimator.clone(),
             config: ViaBtcClientConfig::for_tests(),
         }
     }
@@ -313,20 +253,26 @@ mod tests {
             async fn get_transaction(&self, txid: &Txid) -> BitcoinClientResult<Transaction>;
             async fn get_block_count(&self) -> BitcoinClientResult<u64>;
             async fn get_block_by_height(&self, block_height: u128) -> BitcoinClientResult<Block>;
+            async fn get_blocks_by_height(&self, block_heights: &[u128]) -> BitcoinClientResult<Vec<Block>>;
             async fn get_block_by_hash(&self, block_hash: &BlockHash) -> BitcoinClientResult<Block>;
             async fn get_best_block_hash(&self) -> BitcoinClientResult<BlockHash>;
             async fn get_raw_transaction_info(&self, txid: &Txid) -> BitcoinClientResult<GetRawTransactionResult>;
             async fn estimate_smart_fee(&self, conf_target: u16, estimate_mode: Option<EstimateMode>) -> BitcoinClientResult<EstimateSmartFeeResult>;
             async fn get_blockchain_info(&self) -> BitcoinRpcResult<GetBlockchainInfoResult>;
             async fn get_block_stats(&self, height: u64) -> BitcoinClientResult<GetBlockStatsResult>;
+            async fn get_block_stats_batch(&self, heights: &[u64]) -> BitcoinClientResult<Vec<GetBlockStatsResult>>;
             async fn get_mempool_info(&self) -> BitcoinRpcResult<GetMempoolInfoResult>;
+            async fn get_mempool_fee_histogram(&self) -> BitcoinRpcResult<Vec<(f64, u64)>>;
         }
     }
 
     fn get_client_with_mock(mock_bitcoin_rpc: MockBitcoinRpc) -> BitcoinClient {
+        let rpc: Arc<dyn BitcoinRpc> = Arc::new(mock_bitcoin_rpc);
+        let config = ViaBtcClientConfig::for_tests();
         BitcoinClient {
-            rpc: Arc::new(mock_bitcoin_rpc),
-            config: ViaBtcClientConfig::for_tests(),
+            fee_rate_estimator: FeeRateEstimator::from_config(&config, rpc.clone()).unwrap(),
+            rpc,
+            config,
         }
     }
 
@@ -438,6 +384,23 @@ mod tests {
         assert_eq!(height, 654321);
     }
 
+    fn mempool_info() -> GetMempoolInfoResult {
+        serde_json::from_value(serde_json::json!({
+            "loaded": true,
+            "size": 0,
+            "bytes": 0,
+            "usage": 0,
+            "total_fee": 0.0,
+            "maxmempool": 300_000_000u64,
+            "mempoolminfee": 0.00001,
+            "minrelaytxfee": 0.00001,
+            "incrementalrelayfee": 0.00001,
+            "unbroadcastcount": 0,
+            "fullrbf": false,
+        }))
+        .unwrap()
+    }
+
     #[tokio::test]
     async fn test_get_fee_rate() {
         let mut mock_rpc = MockBitcoinRpc::new();
@@ -448,11 +411,65 @@ mod tests {
                 blocks: 0,
             })
         });
+        mock_rpc
+            .expect_get_mempool_info()
+            .return_once(|| Ok(mempool_info()));
 
         let client = get_client_with_mock(mock_rpc);
 
         let fee_rate = client.get_fee_rate(6).await.unwrap();
-        // 1000 sat/kb = 1 sat/byte
-        assert_eq!(fee_rate, 1);
+        // 1000 sat/kb = 1 sat/byte, plus the 1 sat/byte buffer
+        assert_eq!(fee_rate, 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_fee_rate_aggregates_providers() {
+        let mut mock_rpc = MockBitcoinRpc::new();
+        mock_rpc
+            .expect_estimate_smart_fee()
+            .times(2)
+            .returning(|_, _| {
+                Ok(EstimateSmartFeeResult {
+                    fee_rate: Some(Amount::from_sat(3000)),
+                    errors: None,
+                    blocks: 1,
+                })
+            });
+        // A single outlier can't drive the fee rate with the median.
+        mock_rpc
+            .expect_get_mempool_fee_histogram()
+            .return_once(|| Ok(vec![(500.0, 2_000_000)]));
+        mock_rpc
+            .expect_get_mempool_info()
+            .return_once(|| Ok(mempool_info()));
+
+        let rpc: Arc<dyn BitcoinRpc> = Arc::new(mock_rpc);
+        let providers: Vec<Arc<dyn FeeRateProvider>> = vec![
+            Arc::new(RpcFeeRateProvider::new(rpc.clone())),
+            Arc::new(MempoolFeeRateProvider::new(rpc.clone())),
+            Arc::new(RpcFeeRateProvider::new(rpc.clone())),
+        ];
+        let client = BitcoinClient {
+            rpc,
+            fee_rate_estimator: FeeRateEstimator::new(providers, FeeRateAggregation::Median),
+            config: ViaBtcClientConfig::for_tests(),
+        };
+
+        let fee_rate = client.get_fee_rate(1).await.unwrap();
+        assert_eq!(fee_rate, 4);
+    }
+
+    #[tokio::test]
+    async fn test_mempool_fee_histogram_is_cached() {
+        let mut mock_rpc = MockBitcoinRpc::new();
+        mock_rpc
+            .expect_get_mempool_fee_histogram()
+            .times(1)
+            .returning(|| Ok(vec![(20.0, 2_000_000)]));
+
+        let provider = MempoolFeeRateProvider::new(Arc::new(mock_rpc));
+        assert_eq!(provider.estimate_fee_rate(1).await.unwrap(), 20_000);
+        // The cached histogram is reused for the other confirmation targets.
+        assert_eq!(provider.estimate_fee_rate(3).await.unwrap(), 1000);
     }
 }
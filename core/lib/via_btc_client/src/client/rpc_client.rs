@@ -1,70 +1,135 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
 use async_trait::async_trait;
-use bitcoin::{Address, Block, BlockHash, OutPoint, Transaction, Txid};
+use bitcoin::{
+    consensus::deserialize, Address, Amount, Block, BlockHash, OutPoint, Transaction, Txid,
+};
 use bitcoincore_rpc::{
     bitcoincore_rpc_json::EstimateMode,
     json::{
         EstimateSmartFeeResult, GetBlockStatsResult, GetBlockchainInfoResult, GetMempoolInfoResult,
-        ScanTxOutRequest,
+        GetRawTransactionResult, ListUnspentResultEntry, ScanTxOutResult,
     },
-    Client, RpcApi,
 };
+use serde::Deserialize;
+use serde_json::{json, Value};
 use tracing::{debug, instrument};
 
 use crate::{
+    client::transport::{RpcTransport, RpcTransportOptions},
     traits::BitcoinRpc,
-    types::{BitcoinRpcResult, NodeAuth},
-    utils::with_retry,
+    types::{BitcoinError, BitcoinRpcResult, NodeAuth},
 };
 
-const RPC_MAX_RETRIES: u8 = 3;
-const RPC_RETRY_DELAY_MS: u64 = 500;
+/// Upper bound of the confirmation range used by `listunspent`, same as the bitcoind default.
+const LIST_UNSPENT_MAX_CONF: u32 = 9_999_999;
 
 #[derive(Debug)]
 pub struct BitcoinRpcClient {
-    client: Arc<Client>,
+    transport: Arc<RpcTransport>,
 }
 
 impl BitcoinRpcClient {
     #[instrument(skip(auth), target = "bitcoin_client::rpc_client")]
-    pub fn new(url: &str, auth: NodeAuth) -> Result<Self, bitcoincore_rpc::Error> {
-        let client = Client::new(url, auth.clone())?;
+    pub fn new(url: &str, auth: NodeAuth) -> BitcoinRpcResult<Self> {
+        Self::with_options(url, auth, RpcTransportOptions::default())
+    }
+
+    #[instrument(skip(auth, options), target = "bitcoin_client::rpc_client")]
+    pub fn with_options(
+        url: &str,
+        auth: NodeAuth,
+        options: RpcTransportOptions,
+    ) -> BitcoinRpcResult<Self> {
+        let transport = RpcTransport::new(url, auth, options)?;
         Ok(Self {
-            client: Arc::new(client),
+            transport: Arc::new(transport),
         })
     }
 
-    async fn retry_rpc<F, T>(f: F) -> BitcoinRpcResult<T>
-    where
-        F: Fn() -> BitcoinRpcResult<T> + Send + Sync,
-    {
-        with_retry(f, RPC_MAX_RETRIES, RPC_RETRY_DELAY_MS, "RPC call").await
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &[Value],
+    ) -> BitcoinRpcResult<T> {
+        self.transport.call(method, params).await
+    }
+
+    async fn get_block_hashes(&self, heights: &[u128]) -> BitcoinRpcResult<Vec<BlockHash>> {
+        let params = heights
+            .iter()
+            .map(|height| vec![json!(*height as u64)])
+            .collect();
+        self.transport.batch_call("getblockhash", params).await
+    }
+
+    async fn get_blocks_by_hashes(&self, hashes: &[BlockHash]) -> BitcoinRpcResult<Vec<Block>> {
+        // Verbosity 0 returns the serialized block, which is much smaller than the JSON form.
+        let params = hashes
+            .iter()
+            .map(|hash| vec![json!(hash), json!(0)])
+            .collect();
+        let blocks: Vec<String> = self.transport.batch_call("getblock", params).await?;
+        blocks.iter().map(|hex| decode_hex(hex)).collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MempoolEntry {
+    vsize: u64,
+    fees: MempoolEntryFees,
+}
+
+#[derive(Debug, Deserialize)]
+struct MempoolEntryFees {
+    #[serde(with = "bitcoin::amount::serde::as_btc")]
+    modified: Amount,
+}
+
+/// Groups the mempool entries by fee rate (sat/vB, rounded to 0.1), highest fee rate first.
+fn fee_histogram(entries: impl Iterator<Item = MempoolEntry>) -> Vec<(f64, u64)> {
+    let mut buckets: BTreeMap<u64, u64> = BTreeMap::new();
+    for entry in entries {
+        let fee_rate = entry.fees.modified.to_sat() * 10 / entry.vsize.max(1);
+        *buckets.entry(fee_rate).or_default() += entry.vsize;
     }
+    buckets
+        .into_iter()
+        .rev()
+        .map(|(fee_rate, vsize)| (fee_rate as f64 / 10.0, vsize))
+        .collect()
+}
+
+fn decode_hex<T: bitcoin::consensus::Decodable>(hex_str: &str) -> BitcoinRpcResult<T> {
+    let bytes = hex::decode(hex_str).map_err(|e| BitcoinError::Rpc(e.to_string()))?;
+    deserialize(&bytes).map_err(|e| BitcoinError::Rpc(e.to_string()))
 }
 
 #[async_trait]
 impl BitcoinRpc for BitcoinRpcClient {
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
     async fn get_balance(&self, address: &Address) -> BitcoinRpcResult<u64> {
-        Self::retry_rpc(|| {
-            debug!("Getting balance");
-            let result = self.client.list_unspent(
-                Some(1),          // minconf
-                None,             // maxconf
-                Some(&[address]), // addresses
-                None,             // include_unsafe
-                None,             // query_options
-            )?;
-
-            let total_amount: u64 = result
-                .into_iter()
-                .map(|unspent| unspent.amount.to_sat())
-                .sum();
-
-            Ok(total_amount)
-        })
-        .await
+        debug!("Getting balance");
+        let result: Vec<ListUnspentResultEntry> = self
+            .call(
+                "listunspent",
+                &[
+                    json!(1),
+                    json!(LIST_UNSPENT_MAX_CONF),
+                    json!([address.to_string()]),
+                ],
+            )
+            .await?;
+
+        let total_amount: u64 = result
+            .into_iter()
+            .map(|unspent| unspent.amount.to_sat())
+            .sum();
+
+        Ok(total_amount)
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
@@ -83,13 +148,8 @@ impl BitcoinRpc for BitcoinRpcClient {
 
     #[instrument(skip(self, tx_hex), target = "bitcoin_client::rpc_client")]
     async fn send_raw_transaction(&self, tx_hex: &str) -> BitcoinRpcResult<Txid> {
-        Self::retry_rpc(|| {
-            debug!("Sending raw transaction");
-            self.client
-                .send_raw_transaction(tx_hex)
-                .map_err(|e| e.into())
-        })
-        .await
+        debug!("Sending raw transaction");
+        self.call("sendrawtransaction", &[json!(tx_hex)]).await
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
@@ -97,109 +157,101 @@ impl BitcoinRpc for BitcoinRpcClient {
         &self,
         address: &Address,
     ) -> BitcoinRpcResult<Vec<OutPoint>> {
-        Self::retry_rpc(|| {
-            debug!("Listing unspent outputs based on node wallet");
-            let result = self.client.list_unspent(
-                Some(1),          // minconf
-                None,             // maxconf
-                Some(&[address]), // addresses
-                None,             // include_unsafe
-                None,
-            )?;
-
-            let unspent: Vec<OutPoint> = result
-                .into_iter()
-                .map(|unspent| OutPoint {
-                    txid: unspent.txid,
-                    vout: unspent.vout,
-                })
-                .collect();
-
-            Ok(unspent)
-        })
-        .await
+        debug!("Listing unspent outputs based on node wallet");
+        let result: Vec<ListUnspentResultEntry> = self
+            .call(
+                "listunspent",
+                &[
+                    json!(1),
+                    json!(LIST_UNSPENT_MAX_CONF),
+                    json!([address.to_string()]),
+                ],
+            )
+            .await?;
+
+        let unspent: Vec<OutPoint> = result
+            .into_iter()
+            .map(|unspent| OutPoint {
+                txid: unspent.txid,
+                vout: unspent.vout,
+            })
+            .collect();
+
+        Ok(unspent)
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
     async fn list_unspent(&self, address: &Address) -> BitcoinRpcResult<Vec<OutPoint>> {
-        Self::retry_rpc(|| {
-            debug!("Listing unspent outputs");
-            let descriptor = format!("addr({})", address);
-            let request = vec![ScanTxOutRequest::Single(descriptor)];
-            let result = self.client.scan_tx_out_set_blocking(&request)?;
-            let unspent = result
-                .unspents
-                .into_iter()
-                .map(|unspent| OutPoint {
-                    txid: unspent.txid,
-                    vout: unspent.vout,
-                })
-                .collect();
-            Ok(unspent)
-        })
-        .await
+        debug!("Listing unspent outputs");
+        let descriptor = format!("addr({})", address);
+        let result: ScanTxOutResult = self
+            .call("scantxoutset", &[json!("start"), json!([descriptor])])
+            .await?;
+        let unspent = result
+            .unspents
+            .into_iter()
+            .map(|unspent| OutPoint {
+                txid: unspent.txid,
+                vout: unspent.vout,
+            })
+            .collect();
+        Ok(unspent)
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
     async fn get_transaction(&self, txid: &Txid) -> BitcoinRpcResult<Transaction> {
-        Self::retry_rpc(|| {
-            debug!("Getting transaction");
-            self.client
-                .get_raw_transaction(txid, None)
-                .map_err(|e| e.into())
-        })
-        .await
+        debug!("Getting transaction");
+        let hex: String = self
+            .call("getrawtransaction", &[json!(txid), json!(false)])
+            .await?;
+        decode_hex(&hex)
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
     async fn get_block_count(&self) -> BitcoinRpcResult<u64> {
-        Self::retry_rpc(|| {
-            debug!("Getting block count");
-            self.client.get_block_count().map_err(|e| e.into())
-        })
-        .await
+        debug!("Getting block count");
+        self.call("getblockcount", &[]).await
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
     async fn get_block_by_height(&self, block_height: u128) -> BitcoinRpcResult<Block> {
-        Self::retry_rpc(|| {
-            debug!("Getting block by height");
-            let block_hash = self.client.get_block_hash(block_height as u64)?;
-            self.client.get_block(&block_hash).map_err(|e| e.into())
-        })
-        .await
+        debug!("Getting block by height");
+        let block_hash: BlockHash = self
+            .call("getblockhash", &[json!(block_height as u64)])
+            .await?;
+        self.get_block_by_hash(&block_hash).await
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
+    async fn get_blocks_by_height(&self, block_heights: &[u128]) -> BitcoinRpcResult<Vec<Block>> {
+        debug!("Getting {} blocks by height", block_heights.len());
+        let block_hashes = self.get_block_hashes(block_heights).await?;
+        self.get_blocks_by_hashes(&block_hashes).await
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
     async fn get_block_by_hash(&self, block_hash: &BlockHash) -> BitcoinRpcResult<Block> {
-        Self::retry_rpc(|| {
-            debug!("Getting block by hash");
-            self.client.get_block(block_hash).map_err(|e| e.into())
-        })
-        .await
+        debug!("Getting block by hash");
+        let hex: String = self
+            .call("getblock", &[json!(block_hash), json!(0)])
+            .await?;
+        decode_hex(&hex)
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
     async fn get_best_block_hash(&self) -> BitcoinRpcResult<BlockHash> {
-        Self::retry_rpc(|| {
-            debug!("Getting best block hash");
-            self.client.get_best_block_hash().map_err(|e| e.into())
-        })
-        .await
+        debug!("Getting best block hash");
+        self.call("getbestblockhash", &[]).await
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
     async fn get_raw_transaction_info(
         &self,
         txid: &Txid,
-    ) -> BitcoinRpcResult<bitcoincore_rpc::json::GetRawTransactionResult> {
-        Self::retry_rpc(|| {
-            debug!("Getting raw transaction info");
-            self.client
-                .get_raw_transaction_info(txid, None)
-                .map_err(|e| e.into())
-        })
-        .await
+    ) -> BitcoinRpcResult<GetRawTransactionResult> {
+        debug!("Getting raw transaction info");
+        self.call("getrawtransaction", &[json!(txid), json!(true)])
+            .await
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
@@ -208,47 +260,55 @@ impl BitcoinRpc for BitcoinRpcClient {
         conf_target: u16,
         estimate_mode: Option<EstimateMode>,
     ) -> BitcoinRpcResult<EstimateSmartFeeResult> {
-        Self::retry_rpc(|| {
-            debug!("Estimating smart fee");
-            self.client
-                .estimate_smart_fee(conf_target, estimate_mode)
-                .map_err(|e| e.into())
-        })
-        .await
+        debug!("Estimating smart fee");
+        let mut params = vec![json!(conf_target)];
+        if let Some(estimate_mode) = estimate_mode {
+            params.push(json!(estimate_mode));
+        }
+        self.call("estimatesmartfee", &params).await
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
     async fn get_blockchain_info(&self) -> BitcoinRpcResult<GetBlockchainInfoResult> {
-        Self::retry_rpc(|| {
-            debug!("Getting blockchain info");
-            self.client.get_blockchain_info().map_err(|e| e.into())
-        })
-        .await
+        debug!("Getting blockchain info");
+        self.call("getblockchaininfo", &[]).await
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
     async fn get_block_stats(&self, height: u64) -> BitcoinRpcResult<GetBlockStatsResult> {
-        Self::retry_rpc(|| {
-            debug!("Getting block stats");
-            self.client.get_block_stats(height).map_err(|e| e.into())
-        })
-        .await
+        debug!("Getting block stats");
+        self.call("getblockstats", &[json!(height)]).await
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
+    async fn get_block_stats_batch(
+        &self,
+        heights: &[u64],
+    ) -> BitcoinRpcResult<Vec<GetBlockStatsResult>> {
+        debug!("Getting block stats of {} blocks", heights.len());
+        let params = heights.iter().map(|height| vec![json!(height)]).collect();
+        self.transport.batch_call("getblockstats", params).await
     }
 
     #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
     async fn get_mempool_info(&self) -> BitcoinRpcResult<GetMempoolInfoResult> {
-        Self::retry_rpc(|| {
-            debug!("Getting mempool info");
-            self.client.get_mempool_info().map_err(|e| e.into())
-        })
-        .await
+        debug!("Getting mempool info");
+        self.call("getmempoolinfo", &[]).await
+    }
+
+    #[instrument(skip(self), target = "bitcoin_client::rpc_client")]
+    async fn get_mempool_fee_histogram(&self) -> BitcoinRpcResult<Vec<(f64, u64)>> {
+        debug!("Getting mempool fee histogram");
+        let entries: HashMap<Txid, MempoolEntry> =
+            self.call("getrawmempool", &[json!(true)]).await?;
+        Ok(fee_histogram(entries.into_values()))
     }
 }
 
 impl Clone for BitcoinRpcClient {
     fn clone(&self) -> Self {
         Self {
-            client: Arc::clone(&self.client),
+            transport: Arc::clone(&self.transport),
         }
     }
 }
@@ -0,0 +1,559 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+use crate::{
+    metrics::{RpcMethodLabel, METRICS},
+    types::{BitcoinError, BitcoinRpcResult, NodeAuth},
+};
+
+/// Unknown transaction, block or address.
+const RPC_INVALID_ADDRESS_OR_KEY: i64 = -5;
+/// bitcoind is warming up (loading the block index, verifying blocks, ...).
+const RPC_IN_WARMUP: i64 = -28;
+/// The node is still in initial block download.
+const RPC_CLIENT_IN_INITIAL_DOWNLOAD: i64 = -10;
+/// The node has no connected peers.
+const RPC_CLIENT_NOT_CONNECTED: i64 = -9;
+
+/// Tuning knobs of the JSON-RPC transport.
+#[derive(Debug, Clone)]
+pub struct RpcTransportOptions {
+    /// Maximum number of requests that are in flight at the same time. bitcoind serves RPC
+    /// requests from a small thread pool (`-rpcthreads`) with a bounded work queue
+    /// (`-rpcworkqueue`), so flooding it only results in HTTP 503 responses.
+    pub max_concurrent_requests: usize,
+    /// Maximum number of idle keep-alive connections kept in the pool.
+    pub max_idle_connections: usize,
+    /// Maximum number of calls sent in a single JSON-RPC batch.
+    pub max_batch_size: usize,
+    /// Timeout used to establish a TCP connection.
+    pub connect_timeout: Duration,
+    /// Timeout of a regular RPC call.
+    pub default_timeout: Duration,
+    /// Timeout of calls that return whole blocks or block statistics.
+    pub block_timeout: Duration,
+    /// Timeout of `scantxoutset`, which walks the whole UTXO set.
+    pub scan_timeout: Duration,
+    /// Number of retries of a call that failed with a transient error.
+    pub max_retries: u8,
+    /// Delay before the first retry, doubled on every following attempt.
+    pub retry_delay: Duration,
+}
+
+impl Default for RpcTransportOptions {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 8,
+            max_idle_connections: 8,
+            max_batch_size: 50,
+            connect_timeout: Duration::from_secs(5),
+            default_timeout: Duration::from_secs(15),
+            block_timeout: Duration::from_secs(60),
+            scan_timeout: Duration::from_secs(300),
+            max_retries: 3,
+            retry_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RpcTransportOptions {
+    /// Returns the timeout applied to a single call of the given method.
+    pub fn timeout_for(&self, method: &str) -> Duration {
+        match method {
+            "scantxoutset" => self.scan_timeout,
+            "getblock" | "getblockstats" => self.block_timeout,
+            _ => self.default_timeout,
+        }
+    }
+}
+
+/// Error returned by a single JSON-RPC call, before it's converted into a [`BitcoinError`].
+#[derive(Debug, Clone)]
+pub(crate) enum RpcCallError {
+    /// The request did not reach the node or the connection broke before a response arrived.
+    Transport { message: String, timeout: bool },
+    /// The node answered with a non-success HTTP status and no JSON-RPC error in the body.
+    Http { status: StatusCode, body: String },
+    /// The node answered with a JSON-RPC error object.
+    Rpc { code: i64, message: String },
+    /// The response could not be decoded into the expected type.
+    Decode(String),
+}
+
+impl RpcCallError {
+    /// Whether retrying the very same call can succeed.
+    ///
+    /// Network failures, timeouts, an overloaded work queue and a node that is still warming
+    /// up are transient. Errors reported by the node about the call itself (unknown
+    /// transaction, invalid parameters, rejected transaction, ...) are permanent and are
+    /// returned to the caller right away.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Transport { .. } => true,
+            Self::Http { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            Self::Rpc { code, .. } => matches!(
+                *code,
+                RPC_IN_WARMUP | RPC_CLIENT_IN_INITIAL_DOWNLOAD | RPC_CLIENT_NOT_CONNECTED
+            ),
+            Self::Decode(_) => false,
+        }
+    }
+
+    fn error_type(&self) -> &'static str {
+        match self {
+            Self::Transport { timeout: true, .. } => "timeout",
+            Self::Transport { .. } => "transport",
+            Self::Http { .. } => "http",
+            Self::Rpc { .. } => "rpc",
+            Self::Decode(_) => "decode",
+        }
+    }
+}
+
+impl From<RpcCallError> for BitcoinError {
+    fn from(error: RpcCallError) -> Self {
+        match error {
+            RpcCallError::Transport { message, .. } => {
+                BitcoinError::Rpc(format!("transport error: {message}"))
+            }
+            RpcCallError::Http { status, body } => {
+                BitcoinError::Rpc(format!("HTTP status {status}: {body}"))
+            }
+            RpcCallError::Rpc {
+                code: RPC_INVALID_ADDRESS_OR_KEY,
+                message,
+            } => BitcoinError::NotFound(message),
+            RpcCallError::Rpc { code, message } => {
+                BitcoinError::Rpc(format!("JSON-RPC error {code}: {message}"))
+            }
+            RpcCallError::Decode(message) => {
+                BitcoinError::Rpc(format!("failed to decode response: {message}"))
+            }
+        }
+    }
+}
+
+impl From<reqwest::Error> for RpcCallError {
+    fn from(error: reqwest::Error) -> Self {
+        RpcCallError::Transport {
+            timeout: error.is_timeout(),
+            message: error.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRpcRequest<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: &'a [Value],
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<JsonRpcErrorObject>,
+    id: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcErrorObject {
+    code: i64,
+    message: String,
+}
+
+impl JsonRpcResponse {
+    fn into_result(self) -> Result<Value, RpcCallError> {
+        match self.error {
+            Some(error) => Err(RpcCallError::Rpc {
+                code: error.code,
+                message: error.message,
+            }),
+            // bitcoind encodes a `null` result as a missing field in some versions.
+            None => Ok(self.result.unwrap_or(Value::Null)),
+        }
+    }
+}
+
+/// Non-blocking JSON-RPC client for bitcoind.
+///
+/// HTTP connections are kept alive and reused by the underlying `reqwest` pool, the number of
+/// requests in flight is bounded by a semaphore so that concurrent components (btc_watch,
+/// btc_sender, the verifier) share the node fairly, and several calls can be sent as one
+/// JSON-RPC batch.
+#[derive(Debug)]
+pub(crate) struct RpcTransport {
+    http: reqwest::Client,
+    url: String,
+    credentials: Option<(String, Option<String>)>,
+    permits: Semaphore,
+    next_id: AtomicU64,
+    options: RpcTransportOptions,
+}
+
+impl RpcTransport {
+    pub fn new(url: &str, auth: NodeAuth, options: RpcTransportOptions) -> BitcoinRpcResult<Self> {
+        let (user, password) = auth.get_user_pass()?;
+        let http = reqwest::Client::builder()
+            .pool_max_idle_per_host(options.max_idle_connections)
+            .pool_idle_timeout(Duration::from_secs(60))
+            .tcp_keepalive(Duration::from_secs(30))
+            .connect_timeout(options.connect_timeout)
+            .build()
+            .map_err(|e| BitcoinError::Rpc(format!("failed to build HTTP client: {e}")))?;
+
+        Ok(Self {
+            http,
+            url: url.to_string(),
+            credentials: user.map(|user| (user, password)),
+            permits: Semaphore::new(options.max_concurrent_requests.max(1)),
+            next_id: AtomicU64::new(0),
+            options,
+        })
+    }
+
+    /// Performs a single call, retrying it while it fails with a transient error.
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &[Value],
+    ) -> BitcoinRpcResult<T> {
+        let mut attempt = 0;
+        loop {
+            let started_at = Instant::now();
+            let result = self.send_single(method, params).await;
+            METRICS.rpc_latency[&RpcMethodLabel {
+                method: method.into(),
+            }]
+                .observe(started_at.elapsed());
+
+            match result.and_then(decode) {
+                Ok(value) => return Ok(value),
+                Err(err) => {
+                    self.report_error(method, &err);
+                    if !err.is_transient() {
+                        return Err(err.into());
+                    }
+                    if attempt >= self.options.max_retries {
+                        METRICS.rpc_max_retries_exceeded[&RpcMethodLabel {
+                            method: method.into(),
+                        }]
+                            .inc();
+                        return Err(err.into());
+                    }
+                    warn!(error = ?err, attempt, "RPC call {method} failed, retrying");
+                    tokio::time::sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Performs the same method with different parameters as JSON-RPC batches.
+    ///
+    /// The results are returned in the order of `params`. Calls that failed with a transient
+    /// error are resent (only those, not the whole batch) until they succeed or the retries are
+    /// exhausted; the first permanent error fails the whole batch.
+    pub async fn batch_call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Vec<Vec<Value>>,
+    ) -> BitcoinRpcResult<Vec<T>> {
+        let mut results: Vec<Option<T>> = params.iter().map(|_| None).collect();
+
+        for chunk_start in (0..params.len()).step_by(self.options.max_batch_size.max(1)) {
+            let chunk_end = (chunk_start + self.options.max_batch_size.max(1)).min(params.len());
+            let mut pending: Vec<usize> = (chunk_start..chunk_end).collect();
+            let mut attempt = 0;
+
+            while !pending.is_empty() {
+                let calls: Vec<&[Value]> = pending.iter().map(|&i| params[i].as_slice()).collect();
+                let started_at = Instant::now();
+                let responses = self.send_batch(method, &calls).await;
+                METRICS.rpc_latency[&RpcMethodLabel {
+                    method: format!("{method}_batch"),
+                }]
+                    .observe(started_at.elapsed());
+
+                let mut retry = Vec::new();
+                let mut last_error = None;
+                match responses {
+                    Ok(responses) => {
+                        for (index, response) in pending.iter().zip(responses) {
+                            match response.and_then(decode) {
+                                Ok(value) => results[*index] = Some(value),
+                                Err(err) => {
+                                    self.report_error(method, &err);
+                                    if !err.is_transient() {
+                                        return Err(err.into());
+                                    }
+                                    retry.push(*index);
+                                    last_error = Some(err);
+                                }
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        self.report_error(method, &err);
+                        if !err.is_transient() {
+                            return Err(err.into());
+                        }
+                        retry = pending.clone();
+                        last_error = Some(err);
+                    }
+                }
+
+                if let Some(err) = last_error {
+                    if attempt >= self.options.max_retries {
+                        METRICS.rpc_max_retries_exceeded[&RpcMethodLabel {
+                            method: method.into(),
+                        }]
+                            .inc();
+                        return Err(err.into());
+                    }
+                    warn!(
+                        error = ?err,
+                        attempt,
+                        failed_calls = retry.len(),
+                        "Batched RPC call {method} failed, retrying"
+                    );
+                    tokio::time::sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                pending = retry;
+            }
+        }
+
+        Ok(results
+            .into_iter()
+            .map(|value| value.expect("every batched call either succeeded or returned early"))
+            .collect())
+    }
+
+    async fn send_single(&self, method: &str, params: &[Value]) -> Result<Value, RpcCallError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            id,
+            method,
+            params,
+        };
+        debug!("Sending RPC request {method}");
+        let (status, body) = self
+            .post(&request, self.options.timeout_for(method))
+            .await?;
+
+        // bitcoind reports JSON-RPC errors with a non-success HTTP status (500/404), so the body
+        // has to be inspected before the status.
+        match serde_json::from_slice::<JsonRpcResponse>(&body) {
+            Ok(response) => response.into_result(),
+            Err(_) if !status.is_success() => Err(RpcCallError::Http {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }),
+            Err(err) => Err(RpcCallError::Decode(err.to_string())),
+        }
+    }
+
+    async fn send_batch(
+        &self,
+        method: &str,
+        calls: &[&[Value]],
+    ) -> Result<Vec<Result<Value, RpcCallError>>, RpcCallError> {
+        let first_id = self
+            .next_id
+            .fetch_add(calls.len() as u64, Ordering::Relaxed);
+        let requests: Vec<_> = calls
+            .iter()
+            .enumerate()
+            .map(|(i, params)| JsonRpcRequest {
+                jsonrpc: "2.0",
+                id: first_id + i as u64,
+                method,
+                params: *params,
+            })
+            .collect();
+        debug!("Sending batch of {} {method} RPC requests", requests.len());
+        let (status, body) = self
+            .post(&requests, self.options.timeout_for(method))
+            .await?;
+
+        match serde_json::from_slice::<Vec<JsonRpcResponse>>(&body) {
+            Ok(responses) => order_batch_responses(first_id, calls.len(), responses),
+            Err(_) if !status.is_success() => Err(RpcCallError::Http {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }),
+            Err(err) => Err(RpcCallError::Decode(err.to_string())),
+        }
+    }
+
+    async fn post<R: Serialize + ?Sized>(
+        &self,
+        request: &R,
+        timeout: Duration,
+    ) -> Result<(StatusCode, Vec<u8>), RpcCallError> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|e| RpcCallError::Transport {
+                message: e.to_string(),
+                timeout: false,
+            })?;
+
+        let body = serde_json::to_vec(request).map_err(|e| RpcCallError::Decode(e.to_string()))?;
+        let mut builder = self
+            .http
+            .post(&self.url)
+            .timeout(timeout)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+        if let Some((user, password)) = &self.credentials {
+            builder = builder.basic_auth(user, password.as_ref());
+        }
+        let response = builder.send().await?;
+        let status = response.status();
+        let body = response.bytes().await?;
+        Ok((status, body.to_vec()))
+    }
+
+    fn backoff(&self, attempt: u8) -> Duration {
+        self.options.retry_delay * 2u32.saturating_pow(attempt as u32)
+    }
+
+    fn report_error(&self, method: &str, err: &RpcCallError) {
+        METRICS.rpc_errors[&RpcMethodLabel {
+            method: format!("{method}_{}", err.error_type()),
+        }]
+            .inc();
+    }
+}
+
+fn decode<T: DeserializeOwned>(value: Value) -> Result<T, RpcCallError> {
+    serde_json::from_value(value).map_err(|e| RpcCallError::Decode(e.to_string()))
+}
+
+/// Matches batch responses (which may come in any order) with the requests they answer.
+fn order_batch_responses(
+    first_id: u64,
+    len: usize,
+    responses: Vec<JsonRpcResponse>,
+) -> Result<Vec<Result<Value, RpcCallError>>, RpcCallError> {
+    let mut by_id: HashMap<u64, JsonRpcResponse> = responses
+        .into_iter()
+        .filter_map(|response| response.id.as_u64().map(|id| (id, response)))
+        .collect();
+
+    (0..len as u64)
+        .map(|offset| {
+            by_id
+                .remove(&(first_id + offset))
+                .map(JsonRpcResponse::into_result)
+                .ok_or_else(|| {
+                    RpcCallError::Decode(format!(
+                        "missing response for batched request {}",
+                        first_id + offset
+                    ))
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_retry_classification() {
+        let warmup = RpcCallError::Rpc {
+            code: RPC_IN_WARMUP,
+            message: "Loading block index...".into(),
+        };
+        assert!(warmup.is_transient());
+
+        let not_found = RpcCallError::Rpc {
+            code: RPC_INVALID_ADDRESS_OR_KEY,
+            message: "No such mempool or blockchain transaction".into(),
+        };
+        assert!(!not_found.is_transient());
+        assert!(matches!(
+            BitcoinError::from(not_found),
+            BitcoinError::NotFound(_)
+        ));
+
+        let work_queue_exceeded = RpcCallError::Http {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            body: "Work queue depth exceeded".into(),
+        };
+        assert!(work_queue_exceeded.is_transient());
+
+        let unauthorized = RpcCallError::Http {
+            status: StatusCode::UNAUTHORIZED,
+            body: String::new(),
+        };
+        assert!(!unauthorized.is_transient());
+
+        assert!(RpcCallError::Transport {
+            message: "connection reset".into(),
+            timeout: false,
+        }
+        .is_transient());
+        assert!(!RpcCallError::Decode("invalid type".into()).is_transient());
+    }
+
+    #[test]
+    fn test_per_method_timeouts() {
+        let options = RpcTransportOptions::default();
+        assert_eq!(options.timeout_for("scantxoutset"), options.scan_timeout);
+        assert_eq!(options.timeout_for("getblockstats"), options.block_timeout);
+        assert_eq!(
+            options.timeout_for("getblockcount"),
+            options.default_timeout
+        );
+    }
+
+    #[test]
+    fn test_order_batch_responses() {
+        let responses: Vec<JsonRpcResponse> = serde_json::from_value(json!([
+            { "result": 12, "error": null, "id": 11 },
+            { "result": null, "error": { "code": -8, "message": "Block height out of range" }, "id": 12 },
+            { "result": 10, "error": null, "id": 10 },
+        ]))
+        .unwrap();
+
+        let ordered = order_batch_responses(10, 3, responses).unwrap();
+        assert_eq!(ordered[0].as_ref().unwrap(), &json!(10));
+        assert_eq!(ordered[1].as_ref().unwrap(), &json!(12));
+        assert!(matches!(
+            ordered[2],
+            Err(RpcCallError::Rpc { code: -8, .. })
+        ));
+    }
+
+    #[test]
+    fn test_order_batch_responses_missing_id() {
+        let responses: Vec<JsonRpcResponse> =
+            serde_json::from_value(json!([{ "result": 1, "error": null, "id": 0 }])).unwrap();
+
+        let ordered = order_batch_responses(0, 2, responses).unwrap();
+        assert!(ordered[0].is_ok());
+        assert!(matches!(ordered[1], Err(RpcCallError::Decode(_))));
+    }
+}
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
-use bitcoin::{Address, Amount, BlockHash, OutPoint, Transaction as BitcoinTransaction, Txid};
+use bitcoin::{
+    Address, Amount, Block, BlockHash, OutPoint, Transaction as BitcoinTransaction, Txid,
+};
 use tracing::{debug, info, instrument, warn};
 
 mod parser;
-pub use parser::{get_eth_address, MessageParser};
+pub use parser::{get_eth_address, MessageParser, ParseError, ParseResult};
 use zksync_basic_types::L1BatchNumber;
 use zksync_types::via_wallet::SystemWallets;
 
@@ -12,7 +14,7 @@ use crate::{
     client::BitcoinClient,
     traits::BitcoinOps,
     types::{
-        BitcoinIndexerResult, BridgeWithdrawal, FullInscriptionMessage, L1ToL2Message,
+        BitcoinIndexerResult, FullInscriptionMessage, L1ToL2Message, ProofDAReferenceInput,
         SystemTransactions, TransactionWithMetadata,
     },
 };
@@ -31,9 +33,14 @@ impl BitcoinInscriptionIndexer {
         target = "bitcoin_indexer"
     )]
     pub fn new(client: Arc<BitcoinClient>, wallets: Arc<SystemWallets>) -> Self {
+        Self::with_client(client, wallets)
+    }
+
+    /// Creates an indexer reading the blocks from any Bitcoin client, e.g. a mock in tests.
+    pub fn with_client(client: Arc<dyn BitcoinOps>, wallets: Arc<SystemWallets>) -> Self {
         Self {
-            client: client.clone(),
             parser: MessageParser::new(client.get_network()),
+            client,
             wallets,
         }
     }
@@ -44,16 +51,37 @@ impl BitcoinInscriptionIndexer {
         starting_block: u32,
         ending_block: u32,
     ) -> BitcoinIndexerResult<Vec<FullInscriptionMessage>> {
+        let (messages, _) = self
+            .process_blocks_with_hashes(starting_block, ending_block)
+            .await?;
+        Ok(messages)
+    }
+
+    /// Processes the blocks like [`Self::process_blocks`] and also returns the height and hash of
+    /// every processed block, so the caller can detect when they are reorganized out of the chain.
+    #[instrument(skip(self), target = "bitcoin_indexer")]
+    pub async fn process_blocks_with_hashes(
+        &mut self,
+        starting_block: u32,
+        ending_block: u32,
+    ) -> BitcoinIndexerResult<(Vec<FullInscriptionMessage>, Vec<(u32, BlockHash)>)> {
         info!(
             "Processing blocks from {} to {}",
             starting_block, ending_block
         );
-        let mut res = Vec::with_capacity((ending_block - starting_block + 1) as usize);
-        for block in starting_block..=ending_block {
-            res.extend(self.process_block(block).await?);
+        let heights: Vec<u128> = (starting_block..=ending_block)
+            .map(|height| height as u128)
+            .collect();
+        let blocks = self.client.fetch_blocks(&heights).await?;
+
+        let mut res = Vec::with_capacity(blocks.len());
+        let mut hashes = Vec::with_capacity(blocks.len());
+        for (block_height, block) in (starting_block..=ending_block).zip(blocks) {
+            hashes.push((block_height, block.block_hash()));
+            res.extend(self.process_fetched_block(block, block_height).await);
         }
         debug!("Processed {} blocks", ending_block - starting_block + 1);
-        Ok(res)
+        Ok((res, hashes))
     }
 
     #[instrument(skip(self), target = "bitcoin_indexer")]
@@ -95,7 +123,14 @@ impl BitcoinInscriptionIndexer {
         debug!("Processing block at height {}", block_height);
 
         let block = self.client.fetch_block(block_height as u128).await?;
-        // TODO: check block header is belong to a valid chain of blocks (reorg detection and management)
+        Ok(self.process_fetched_block(block, block_height).await)
+    }
+
+    async fn process_fetched_block(
+        &mut self,
+        block: Block,
+        block_height: u32,
+    ) -> Vec<FullInscriptionMessage> {
         // TODO: deal with malicious sequencer, verifiers from being able to make trouble by sending invalid messages / valid messages with invalid data
 
         let mut valid_messages = Vec::new();
@@ -166,7 +201,7 @@ impl BitcoinInscriptionIndexer {
             valid_messages.len(),
             block_height
         );
-        Ok(valid_messages)
+        valid_messages
     }
 
     fn extract_important_transactions(
@@ -252,6 +287,13 @@ impl BitcoinInscriptionIndexer {
         Ok(are_connected)
     }
 
+    /// Returns the hash of the block at `block_height` in the current best chain.
+    #[instrument(skip(self), target = "bitcoin_indexer")]
+    pub async fn fetch_block_hash(&self, block_height: u32) -> BitcoinIndexerResult<BlockHash> {
+        let block = self.client.fetch_block(block_height as u128).await?;
+        Ok(block.block_hash())
+    }
+
     pub async fn fetch_block_height(&self) -> BitcoinIndexerResult<u64> {
         self.client.fetch_block_height().await.map_err(|e| e.into())
     }
@@ -260,21 +302,30 @@ impl BitcoinInscriptionIndexer {
         self.wallets.clone()
     }
 
-    pub async fn get_l1_batch_number(
+    /// Returns the L1 batches proven by a proof DA reference, or by the proof an attestation votes
+    /// on. A proof covers a range of consecutive L1 batches starting at its first reveal txid.
+    pub async fn get_l1_batch_numbers(
         &mut self,
         msg: &FullInscriptionMessage,
-    ) -> Option<L1BatchNumber> {
-        match msg {
-            FullInscriptionMessage::ProofDAReference(proof_msg) => self
-                .get_l1_batch_number_from_proof_tx_id(&proof_msg.input.l1_batch_reveal_txid)
-                .await
-                .ok(),
+    ) -> Option<Vec<L1BatchNumber>> {
+        let proof_input = match msg {
+            FullInscriptionMessage::ProofDAReference(proof_msg) => proof_msg.input.clone(),
             FullInscriptionMessage::ValidatorAttestation(va_msg) => self
-                .get_l1_batch_number_from_validation_tx_id(&va_msg.input.reference_txid)
+                .get_proof_da_reference_from_tx_id(&va_msg.input.reference_txid)
                 .await
-                .ok(),
-            _ => None,
-        }
+                .ok()?,
+            _ => return None,
+        };
+        let first_l1_batch_number = self
+            .get_l1_batch_number_from_proof_tx_id(&proof_input.l1_batch_reveal_txid)
+            .await
+            .ok()?;
+
+        Some(
+            (0..=proof_input.next_l1_batch_reveal_txids.len() as u32)
+                .map(|offset| first_l1_batch_number + offset)
+                .collect(),
+        )
     }
 
     pub fn get_number_of_verifiers(&self) -> usize {
@@ -327,9 +378,14 @@ impl BitcoinInscriptionIndexer {
     async fn is_valid_bridge_message(&self, message: &FullInscriptionMessage) -> bool {
         match message {
             FullInscriptionMessage::L1ToL2Message(m) => self.is_valid_l1_to_l2_transfer(m),
-            FullInscriptionMessage::BridgeWithdrawal(m) => {
-                self.is_valid_bridge_withdrawal(m).await.unwrap_or(false)
-            }
+            FullInscriptionMessage::BridgeWithdrawal(m) => self
+                .is_valid_bridge_spend(m.input.inputs.first())
+                .await
+                .unwrap_or(false),
+            FullInscriptionMessage::BridgeRefund(m) => self
+                .is_valid_bridge_spend(m.input.inputs.first())
+                .await
+                .unwrap_or(false),
             _ => false,
         }
     }
@@ -372,9 +428,10 @@ impl BitcoinInscriptionIndexer {
         is_valid_receiver && is_valid_amount
     }
 
-    #[instrument(skip(self, message), target = "bitcoin_indexer")]
-    async fn is_valid_bridge_withdrawal(&self, message: &BridgeWithdrawal) -> anyhow::Result<bool> {
-        if let Some(outpoint) = message.input.inputs.first() {
+    /// Withdrawals and refunds are only valid when they spend an output of the bridge address.
+    #[instrument(skip(self, outpoint_opt), target = "bitcoin_indexer")]
+    async fn is_valid_bridge_spend(&self, outpoint_opt: Option<&OutPoint>) -> anyhow::Result<bool> {
+        if let Some(outpoint) = outpoint_opt {
             let tx = self.client.get_transaction(&outpoint.txid).await?;
             if let Some(txout) = tx.output.get(outpoint.vout as usize) {
                 return Ok(txout.script_pubkey == self.wallets.bridge.script_pubkey());
@@ -402,34 +459,31 @@ impl BitcoinInscriptionIndexer {
         let b = self
             .parser
             .parse_system_transaction(&a, 0, Some(&self.wallets));
-        let msg = b
-            .first()
-            .ok_or_else(|| anyhow::anyhow!("No message found"))?;
-
-        match msg {
-            FullInscriptionMessage::L1BatchDAReference(da_msg) => Ok(da_msg.input.l1_batch_index),
-            _ => Err(anyhow::anyhow!("Invalid message type")),
-        }
+        // The transaction can pack other messages, but a single L1 batch reference.
+        b.iter()
+            .find_map(|msg| match msg {
+                FullInscriptionMessage::L1BatchDAReference(da_msg) => {
+                    Some(da_msg.input.l1_batch_index)
+                }
+                _ => None,
+            })
+            .ok_or_else(|| anyhow::anyhow!("No L1 batch DA reference message found"))
     }
 
-    async fn get_l1_batch_number_from_validation_tx_id(
+    async fn get_proof_da_reference_from_tx_id(
         &mut self,
         txid: &Txid,
-    ) -> anyhow::Result<L1BatchNumber> {
+    ) -> anyhow::Result<ProofDAReferenceInput> {
         let a = self.client.get_transaction(txid).await?;
         let b = self
             .parser
             .parse_system_transaction(&a, 0, Some(&self.wallets));
-        let msg = b
-            .first()
-            .ok_or_else(|| anyhow::anyhow!("No message found"))?;
-
-        match msg {
-            FullInscriptionMessage::ProofDAReference(da_msg) => Ok(self
-                .get_l1_batch_number_from_proof_tx_id(&da_msg.input.l1_batch_reveal_txid)
-                .await?),
-            _ => Err(anyhow::anyhow!("Invalid message type")),
-        }
+        b.into_iter()
+            .find_map(|msg| match msg {
+                FullInscriptionMessage::ProofDAReference(da_msg) => Some(da_msg.input),
+                _ => None,
+            })
+            .ok_or_else(|| anyhow::anyhow!("No proof DA reference message found"))
     }
 }
 
@@ -572,6 +626,36 @@ mod tests {
         assert_eq!(result.unwrap().len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_process_blocks_with_hashes() {
+        let mock_block = Block {
+            header: Header {
+                version: Default::default(),
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: 0,
+                bits: Default::default(),
+                nonce: 0,
+            },
+            txdata: vec![],
+        };
+        let block_hash = mock_block.block_hash();
+
+        let mut mock_client = MockBitcoinOps::new();
+        mock_client
+            .expect_fetch_block()
+            .returning(move |_| Ok(mock_block.clone()))
+            .times(2);
+        mock_client
+            .expect_get_network()
+            .returning(|| Network::Testnet);
+
+        let mut indexer = get_indexer_with_mock(mock_client);
+        let (messages, hashes) = indexer.process_blocks_with_hashes(5, 6).await.unwrap();
+        assert!(messages.is_empty());
+        assert_eq!(hashes, vec![(5, block_hash), (6, block_hash)]);
+    }
+
     #[tokio::test]
     async fn test_is_valid_message() {
         let indexer = get_indexer_with_mock(MockBitcoinOps::new());
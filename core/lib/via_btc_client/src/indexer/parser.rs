@@ -3,10 +3,12 @@ use std::str::FromStr;
 use bitcoin::{
     address::NetworkUnchecked,
     hashes::Hash,
+    opcodes::all::{OP_ENDIF, OP_PUSHNUM_1, OP_PUSHNUM_16, OP_PUSHNUM_2},
     script::{Instruction, PushBytesBuf},
     taproot::{ControlBlock, Signature as TaprootSignature},
     Address, Amount, CompressedPublicKey, Network, ScriptBuf, Transaction, TxOut, Txid, Witness,
 };
+use thiserror::Error;
 use tracing::{debug, instrument, warn};
 use zksync_basic_types::H256;
 use zksync_types::{
@@ -15,17 +17,19 @@ use zksync_types::{
 };
 
 use crate::types::{
-    self, BridgeWithdrawal, BridgeWithdrawalInput, CommonFields, FullInscriptionMessage,
-    L1BatchDAReference, L1BatchDAReferenceInput, L1ToL2Message, L1ToL2MessageInput,
-    ProofDAReference, ProofDAReferenceInput, ProposeSequencer, ProposeSequencerInput,
-    SystemBootstrapping, SystemBootstrappingInput, SystemContractUpgrade,
-    SystemContractUpgradeInput, SystemContractUpgradeProposal, SystemContractUpgradeProposalInput,
-    TransactionWithMetadata, UpdateBridge, UpdateBridgeInput, UpdateBridgeProposal,
-    UpdateBridgeProposalInput, UpdateGovernance, UpdateGovernanceInput, UpdateSequencer,
-    UpdateSequencerInput, ValidatorAttestation, ValidatorAttestationInput, Vote,
+    self, BridgeRefund, BridgeRefundInput, BridgeWithdrawal, BridgeWithdrawalInput, CommonFields,
+    DABlobChunk, DABlobChunkInput, FullInscriptionMessage, L1BatchDAReference,
+    L1BatchDAReferenceInput, L1ToL2Message, L1ToL2MessageInput, ProofDAReference,
+    ProofDAReferenceInput, ProposeSequencer, ProposeSequencerInput, SystemBootstrapping,
+    SystemBootstrappingInput, SystemContractUpgrade, SystemContractUpgradeInput,
+    SystemContractUpgradeProposal, SystemContractUpgradeProposalInput, TransactionWithMetadata,
+    UpdateBridge, UpdateBridgeInput, UpdateBridgeProposal, UpdateBridgeProposalInput,
+    UpdateGovernance, UpdateGovernanceInput, UpdateSequencer, UpdateSequencerInput,
+    ValidatorAttestation, ValidatorAttestationInput, Vote,
 };
 
 const OP_RETURN_WITHDRAW_PREFIX: &[u8] = b"VIA_PROTOCOL:WITHDRAWAL";
+const OP_RETURN_REFUND_PREFIX: &[u8] = b"VIA_PROTOCOL:REFUND";
 const OP_RETURN_UPGRADE_PROTOCOL_PREFIX: &[u8] = b"VIA_PROTOCOL:UPGRADE";
 const OP_RETURN_UPDATE_SEQUENCER_PREFIX: &[u8] = b"VIA_PROTOCOL:SEQ";
 const OP_RETURN_UPDATE_BRIDGE_PREFIX: &[u8] = b"VIA_PROTOCOL:BRI";
@@ -41,6 +45,36 @@ const MIN_PROOF_DA_REFERENCE_INSTRUCTIONS: usize = 5;
 const MIN_L1_TO_L2_MESSAGE_INSTRUCTIONS: usize = 5;
 const MIN_SYSTEM_CONTRACT_UPGRADE_PROPOSAL: usize = 6;
 const MIN_UPDATE_BRIDGE_PROPOSAL: usize = 5;
+const MIN_DA_BLOB_CHUNK_INSTRUCTIONS: usize = 4;
+
+/// Index of the first `(address, code hash)` pair of a system contract upgrade proposal.
+const SYSTEM_CONTRACTS_START_INDEX: usize = 6;
+const DA_BLOB_CHUNK_DATA_START_INDEX: usize = 4;
+
+/// Error returned when a transaction carries a Via message that can't be decoded. Such messages
+/// are skipped by the parser, they never abort the indexing of a block.
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("Insufficient instructions: expected at least {expected}, got {actual}")]
+    InsufficientInstructions { expected: usize, actual: usize },
+    #[error("Missing field `{0}`")]
+    MissingField(&'static str),
+    #[error("Invalid field `{field}`: {reason}")]
+    InvalidField { field: &'static str, reason: String },
+    #[error("Unsupported inscription envelope version: {0}")]
+    UnsupportedEnvelopeVersion(u8),
+}
+
+impl ParseError {
+    fn invalid_field(field: &'static str, reason: impl ToString) -> Self {
+        Self::InvalidField {
+            field,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
 
 #[derive(Debug, Clone)]
 pub struct MessageParser {
@@ -70,10 +104,10 @@ impl MessageParser {
 
         match sender_addresses {
             Some(address) => {
-                // parsing messages
+                // parsing messages, an input can carry several messages
                 tx.input
                     .iter()
-                    .filter_map(|input| {
+                    .flat_map(|input| {
                         self.parse_system_input(input, tx, block_height, address.clone(), wallets)
                     })
                     .collect()
@@ -92,24 +126,26 @@ impl MessageParser {
     ) -> Vec<FullInscriptionMessage> {
         let mut messages = Vec::new();
 
-        if let Some(update_sequencer) = self.parse_op_return_update_governance(&tx.tx, block_height)
-        {
-            messages.push(update_sequencer);
-        }
-
-        if let Some(upgrade_protocol) = self.parse_op_return_protocol_upgrade(&tx.tx, block_height)
-        {
-            messages.push(upgrade_protocol);
-        }
-
-        if let Some(update_bridge) = self.parse_op_return_update_bridge(&tx.tx, block_height) {
-            messages.push(update_bridge);
-        }
-
-        if let Some(update_sequencer) = self.parse_op_return_update_sequencer(&tx.tx, block_height)
-        {
-            messages.push(update_sequencer);
-        }
+        push_parsed(
+            &mut messages,
+            &tx.tx,
+            self.parse_op_return_update_governance(&tx.tx, block_height),
+        );
+        push_parsed(
+            &mut messages,
+            &tx.tx,
+            self.parse_op_return_protocol_upgrade(&tx.tx, block_height),
+        );
+        push_parsed(
+            &mut messages,
+            &tx.tx,
+            self.parse_op_return_update_bridge(&tx.tx, block_height),
+        );
+        push_parsed(
+            &mut messages,
+            &tx.tx,
+            self.parse_op_return_update_sequencer(&tx.tx, block_height),
+        );
 
         messages
     }
@@ -140,24 +176,32 @@ impl MessageParser {
         let bridge_output = &tx.tx.output[vout];
 
         // Try to parse as inscription-based deposit first
-        if let Some(inscription_message) = self.parse_inscription_deposit(tx, block_height, wallets)
-        {
-            messages.push(inscription_message);
-        }
+        push_parsed(
+            &mut messages,
+            &tx.tx,
+            self.parse_inscription_deposit(tx, block_height, wallets),
+        );
 
         // If not an inscription, try to parse as OP_RETURN based deposit
-        if let Some(op_return_message) =
-            self.parse_op_return_deposit(tx, block_height, bridge_output)
-        {
-            messages.push(op_return_message);
-        }
+        push_parsed(
+            &mut messages,
+            &tx.tx,
+            self.parse_op_return_deposit(tx, block_height, bridge_output),
+        );
 
         // Try to parse withdrawals processed by the bridge address.
-        if let Some(bridge_withdrawals) =
-            self.parse_op_return_withdrawal(&tx.tx, block_height, wallets)
-        {
-            messages.push(bridge_withdrawals);
-        }
+        push_parsed(
+            &mut messages,
+            &tx.tx,
+            self.parse_op_return_withdrawal(&tx.tx, block_height, wallets),
+        );
+
+        // Try to parse refunds of invalid deposits processed by the bridge address.
+        push_parsed(
+            &mut messages,
+            &tx.tx,
+            self.parse_op_return_refund(&tx.tx, block_height, wallets),
+        );
 
         messages
     }
@@ -170,17 +214,17 @@ impl MessageParser {
         block_height: u32,
         address: Address,
         wallets: Option<&SystemWallets>,
-    ) -> Option<FullInscriptionMessage> {
+    ) -> Vec<FullInscriptionMessage> {
         let witness = &input.witness;
         if witness.len() < MIN_WITNESS_LENGTH {
-            return None;
+            return vec![];
         }
 
         let signature = match TaprootSignature::from_slice(&witness[0]) {
             Ok(sig) => sig,
             Err(e) => {
                 warn!("Failed to parse Taproot signature: {}", e);
-                return None;
+                return vec![];
             }
         };
         let script = ScriptBuf::from_bytes(witness[1].to_vec());
@@ -188,18 +232,16 @@ impl MessageParser {
             Ok(cb) => cb,
             Err(e) => {
                 warn!("Failed to decode control block: {}", e);
-                return None;
+                return vec![];
             }
         };
 
         let instructions: Vec<_> = script.instructions().filter_map(Result::ok).collect();
-        let via_index = match find_via_inscription_protocol(&instructions) {
-            Some(index) => index,
-            None => {
-                debug!("VIA inscription protocol not found in script");
-                return None;
-            }
-        };
+        let envelopes = find_via_inscription_envelopes(&instructions);
+        if envelopes.is_empty() {
+            debug!("VIA inscription protocol not found in script");
+            return vec![];
+        }
 
         let public_key = control_block.internal_key;
         let common_fields = CommonFields {
@@ -212,7 +254,17 @@ impl MessageParser {
             output_vout: None,
         };
 
-        self.parse_system_message(tx, &instructions[via_index..], &common_fields, wallets)
+        let mut messages = Vec::with_capacity(envelopes.len());
+        for envelope in envelopes {
+            push_parsed(
+                &mut messages,
+                tx,
+                envelope_message(envelope).and_then(|instructions| {
+                    self.parse_system_message(tx, instructions, &common_fields, wallets)
+                }),
+            );
+        }
+        messages
     }
 
     #[instrument(skip(self), target = "bitcoin_indexer::parser")]
@@ -237,55 +289,66 @@ impl MessageParser {
         instructions: &[Instruction],
         common_fields: &CommonFields,
         wallets: Option<&SystemWallets>,
-    ) -> Option<FullInscriptionMessage> {
-        let message_type = instructions.get(1)?;
+    ) -> ParseResult<Option<FullInscriptionMessage>> {
+        let message_type = instructions
+            .get(1)
+            .ok_or(ParseError::MissingField("message_type"))?;
 
-        match message_type {
+        let message = match message_type {
             Instruction::PushBytes(bytes)
                 if bytes.as_bytes() == types::SYSTEM_BOOTSTRAPPING_MSG.as_bytes() =>
             {
                 debug!("Parsing system bootstrapping message");
-                self.parse_system_bootstrapping(instructions, common_fields)
+                self.parse_system_bootstrapping(instructions, common_fields)?
             }
             Instruction::PushBytes(bytes)
                 if bytes.as_bytes() == types::PROPOSE_SEQUENCER_MSG.as_bytes() =>
             {
                 debug!("Parsing propose sequencer message");
-                self.parse_propose_sequencer(instructions, common_fields)
+                self.parse_propose_sequencer(instructions, common_fields)?
             }
             Instruction::PushBytes(bytes)
                 if bytes.as_bytes() == types::VALIDATOR_ATTESTATION_MSG.as_bytes() =>
             {
                 debug!("Parsing validator attestation message");
-                self.parse_validator_attestation(instructions, common_fields)
+                self.parse_validator_attestation(instructions, common_fields)?
             }
             Instruction::PushBytes(bytes)
                 if bytes.as_bytes() == types::L1_BATCH_DA_REFERENCE_MSG.as_bytes() =>
             {
                 debug!("Parsing L1 batch DA reference message");
-                self.parse_l1_batch_da_reference(instructions, common_fields)
+                self.parse_l1_batch_da_reference(instructions, common_fields)?
             }
             Instruction::PushBytes(bytes)
                 if bytes.as_bytes() == types::PROOF_DA_REFERENCE_MSG.as_bytes() =>
             {
                 debug!("Parsing proof DA reference message");
-                self.parse_proof_da_reference(instructions, common_fields)
+                self.parse_proof_da_reference(instructions, common_fields)?
             }
             Instruction::PushBytes(bytes) if bytes.as_bytes() == types::L1_TO_L2_MSG.as_bytes() => {
+                let Some(wallets) = wallets else {
+                    return Ok(None);
+                };
                 debug!("Parsing L1 to L2 message");
-                self.parse_l1_to_l2_message(tx, instructions, common_fields, wallets)
+                self.parse_l1_to_l2_message(tx, instructions, common_fields, wallets)?
             }
             Instruction::PushBytes(bytes)
                 if bytes.as_bytes() == types::SYSTEM_CONTRACT_UPGRADE_MSG.as_bytes() =>
             {
                 debug!("Parsing System contract upgrade proposal");
-                self.parse_system_contract_upgrade_message(instructions, common_fields)
+                self.parse_system_contract_upgrade_message(instructions, common_fields)?
             }
             Instruction::PushBytes(bytes)
                 if bytes.as_bytes() == types::UPGRADE_BRIDGE_MSG.as_bytes() =>
             {
                 debug!("Parsing update bridge proposal");
-                self.parse_update_bridge_proposal_message(instructions, common_fields)
+                self.parse_update_bridge_proposal_message(instructions, common_fields)?
+            }
+            Instruction::PushBytes(bytes)
+                if bytes.as_bytes() == types::DA_BLOB_CHUNK_MSG.as_bytes() =>
+            {
+                debug!("Parsing DA blob chunk");
+                self.parse_da_blob_chunk(instructions, common_fields)?
             }
             Instruction::PushBytes(bytes) => {
                 warn!("Unknown message type for system transaction parser");
@@ -293,14 +356,16 @@ impl MessageParser {
                     "first instruction: {:?}",
                     String::from_utf8(bytes.as_bytes().to_vec())
                 );
-                None
+                return Ok(None);
             }
             Instruction::Op(_) => {
                 warn!("Invalid message type");
                 warn!("Instructions: {:?}", instructions);
-                None
+                return Ok(None);
             }
-        }
+        };
+
+        Ok(Some(message))
     }
 
     #[instrument(
@@ -311,35 +376,17 @@ impl MessageParser {
         &mut self,
         instructions: &[Instruction],
         common_fields: &CommonFields,
-    ) -> Option<FullInscriptionMessage> {
-        if instructions.len() < MIN_SYSTEM_BOOTSTRAPPING_INSTRUCTIONS {
-            warn!("Insufficient instructions for system bootstrapping");
-            return None;
-        }
+    ) -> ParseResult<FullInscriptionMessage> {
+        require_instructions(instructions, MIN_SYSTEM_BOOTSTRAPPING_INSTRUCTIONS)?;
 
-        let start_block_height = u32::from_be_bytes(
-            instructions
-                .get(2)?
-                .push_bytes()?
-                .as_bytes()
-                .try_into()
-                .ok()?,
-        );
+        let start_block_height = read_u32_be(instructions, 2, "start_block_height")?;
 
         debug!("Parsed start block height: {}", start_block_height);
 
         // network unchecked is required to enable serde serialization and deserialization on the library structs
         let network_unchecked_verifier_addresses = instructions[3..instructions.len() - 5]
             .iter()
-            .filter_map(|instr| {
-                if let Instruction::PushBytes(bytes) = instr {
-                    std::str::from_utf8(bytes.as_bytes())
-                        .ok()
-                        .and_then(|s| s.parse::<Address<NetworkUnchecked>>().ok())
-                } else {
-                    None
-                }
-            })
+            .filter_map(parse_unchecked_address)
             .collect::<Vec<_>>();
 
         debug!(
@@ -347,51 +394,32 @@ impl MessageParser {
             network_unchecked_verifier_addresses.len()
         );
 
-        let network_unchecked_bridge_address =
-            instructions.get(instructions.len() - 5).and_then(|instr| {
-                if let Instruction::PushBytes(bytes) = instr {
-                    std::str::from_utf8(bytes.as_bytes())
-                        .ok()
-                        .and_then(|s| s.parse::<Address<NetworkUnchecked>>().ok())
-                } else {
-                    None
-                }
-            })?;
+        let network_unchecked_bridge_address = read_address(
+            instructions,
+            instructions.len() - 5,
+            "bridge_musig2_address",
+        )?;
 
         debug!("Parsed bridge address");
 
-        let bootloader_hash = H256::from_slice(
-            instructions
-                .get(instructions.len() - 4)?
-                .push_bytes()?
-                .as_bytes(),
-        );
+        let bootloader_hash = read_h256(instructions, instructions.len() - 4, "bootloader_hash")?;
 
         debug!("Parsed bootloader hash");
 
-        let abstract_account_hash = H256::from_slice(
-            instructions
-                .get(instructions.len() - 3)?
-                .push_bytes()?
-                .as_bytes(),
-        );
+        let abstract_account_hash = read_h256(
+            instructions,
+            instructions.len() - 3,
+            "abstract_account_hash",
+        )?;
 
         debug!("Parsed abstract account hash");
 
         let network_unchecked_governance_address =
-            instructions.get(instructions.len() - 2).and_then(|instr| {
-                if let Instruction::PushBytes(bytes) = instr {
-                    std::str::from_utf8(bytes.as_bytes())
-                        .ok()
-                        .and_then(|s| s.parse::<Address<NetworkUnchecked>>().ok())
-                } else {
-                    None
-                }
-            })?;
+            read_address(instructions, instructions.len() - 2, "governance_address")?;
 
         debug!("Parsed governance address");
 
-        Some(FullInscriptionMessage::SystemBootstrapping(
+        Ok(FullInscriptionMessage::SystemBootstrapping(
             SystemBootstrapping {
                 common: common_fields.clone(),
                 input: SystemBootstrappingInput {
@@ -414,28 +442,16 @@ impl MessageParser {
         &self,
         instructions: &[Instruction],
         common_fields: &CommonFields,
-    ) -> Option<FullInscriptionMessage> {
-        if instructions.len() < MIN_PROPOSE_SEQUENCER_INSTRUCTIONS {
-            warn!("Insufficient instructions for propose sequencer");
-            return None;
-        }
+    ) -> ParseResult<FullInscriptionMessage> {
+        require_instructions(instructions, MIN_PROPOSE_SEQUENCER_INSTRUCTIONS)?;
 
-        let sequencer_address = instructions.get(2).and_then(|instr| {
-            if let Instruction::PushBytes(bytes) = instr {
-                std::str::from_utf8(bytes.as_bytes()).ok().and_then(|s| {
-                    s.parse::<Address<NetworkUnchecked>>()
-                        .ok()?
-                        .require_network(self.network)
-                        .ok()
-                })
-            } else {
-                None
-            }
-        })?;
+        let sequencer_address = read_address(instructions, 2, "sequencer_address")?
+            .require_network(self.network)
+            .map_err(|e| ParseError::invalid_field("sequencer_address", e))?;
 
         debug!("Parsed sequencer address");
 
-        Some(FullInscriptionMessage::ProposeSequencer(ProposeSequencer {
+        Ok(FullInscriptionMessage::ProposeSequencer(ProposeSequencer {
             common: common_fields.clone(),
             input: ProposeSequencerInput {
                 sequencer_new_p2wpkh_address: sequencer_address.as_unchecked().clone(),
@@ -451,31 +467,17 @@ impl MessageParser {
         &self,
         instructions: &[Instruction],
         common_fields: &CommonFields,
-    ) -> Option<FullInscriptionMessage> {
-        if instructions.len() < MIN_VALIDATOR_ATTESTATION_INSTRUCTIONS {
-            warn!("Insufficient instructions for validator attestation");
-            return None;
-        }
+    ) -> ParseResult<FullInscriptionMessage> {
+        require_instructions(instructions, MIN_VALIDATOR_ATTESTATION_INSTRUCTIONS)?;
 
-        let reference_txid = match Txid::from_slice(instructions.get(2)?.push_bytes()?.as_bytes()) {
-            Ok(txid) => {
-                debug!("Parsed reference txid");
-                txid
-            }
-            Err(e) => {
-                warn!("Failed to parse reference txid: {}", e);
-                return None;
-            }
-        };
+        let reference_txid = read_txid(instructions, 2, "reference_txid")?;
+        debug!("Parsed reference txid");
 
-        let attestation = match instructions.get(3)? {
+        let attestation = match &instructions[3] {
             Instruction::PushBytes(bytes) => match bytes.as_bytes() {
                 b"OP_1" => Vote::Ok,
                 b"" => Vote::NotOk,
-                _ => {
-                    warn!("Invalid attestation value");
-                    return None;
-                }
+                _ => return Err(ParseError::invalid_field("attestation", "unknown vote")),
             },
             Instruction::Op(op) => {
                 if op.to_u8() == 0x51 {
@@ -483,15 +485,14 @@ impl MessageParser {
                 } else if op.to_u8() == 0x00 {
                     Vote::NotOk
                 } else {
-                    warn!("Invalid attestation value");
-                    return None;
+                    return Err(ParseError::invalid_field("attestation", "unknown vote"));
                 }
             }
         };
 
         debug!("Parsed attestation: {:?}", attestation);
 
-        Some(FullInscriptionMessage::ValidatorAttestation(
+        Ok(FullInscriptionMessage::ValidatorAttestation(
             ValidatorAttestation {
                 common: common_fields.clone(),
                 input: ValidatorAttestationInput {
@@ -510,39 +511,25 @@ impl MessageParser {
         &self,
         instructions: &[Instruction],
         common_fields: &CommonFields,
-    ) -> Option<FullInscriptionMessage> {
-        if instructions.len() < MIN_L1_BATCH_DA_REFERENCE_INSTRUCTIONS {
-            warn!("Insufficient instructions for L1 batch DA reference");
-            return None;
-        }
+    ) -> ParseResult<FullInscriptionMessage> {
+        require_instructions(instructions, MIN_L1_BATCH_DA_REFERENCE_INSTRUCTIONS)?;
 
-        let l1_batch_hash = H256::from_slice(instructions.get(2)?.push_bytes()?.as_bytes());
+        let l1_batch_hash = read_h256(instructions, 2, "l1_batch_hash")?;
         debug!("Parsed L1 batch hash");
 
-        let l1_batch_index = L1BatchNumber(u32::from_be_bytes(
-            instructions
-                .get(3)?
-                .push_bytes()?
-                .as_bytes()
-                .try_into()
-                .ok()?,
-        ));
+        let l1_batch_index = L1BatchNumber(read_u32_be(instructions, 3, "l1_batch_index")?);
         debug!("Parsed L1 batch index: {}", l1_batch_index);
 
-        let da_identifier = std::str::from_utf8(instructions.get(4)?.push_bytes()?.as_bytes())
-            .ok()?
-            .to_string();
+        let da_identifier = read_string(instructions, 4, "da_identifier")?;
         debug!("Parsed DA identifier: {}", da_identifier);
 
-        let blob_id = std::str::from_utf8(instructions.get(5)?.push_bytes()?.as_bytes())
-            .ok()?
-            .to_string();
+        let (blob_id, next_index) = read_blob_id(instructions, 5)?;
         debug!("Parsed blob ID: {}", blob_id);
 
-        let prev_l1_batch_hash = H256::from_slice(instructions.get(6)?.push_bytes()?.as_bytes());
+        let prev_l1_batch_hash = read_h256(instructions, next_index, "prev_l1_batch_hash")?;
         debug!("Parsed previous L1 batch hash");
 
-        Some(FullInscriptionMessage::L1BatchDAReference(
+        Ok(FullInscriptionMessage::L1BatchDAReference(
             L1BatchDAReference {
                 common: common_fields.clone(),
                 input: L1BatchDAReferenceInput {
@@ -564,40 +551,77 @@ impl MessageParser {
         &self,
         instructions: &[Instruction],
         common_fields: &CommonFields,
-    ) -> Option<FullInscriptionMessage> {
-        if instructions.len() < MIN_PROOF_DA_REFERENCE_INSTRUCTIONS {
-            warn!("Insufficient instructions for proof DA reference");
-            return None;
-        }
+    ) -> ParseResult<FullInscriptionMessage> {
+        require_instructions(instructions, MIN_PROOF_DA_REFERENCE_INSTRUCTIONS)?;
 
-        let l1_batch_reveal_txid =
-            match Txid::from_slice(instructions.get(2)?.push_bytes()?.as_bytes()) {
-                Ok(txid) => {
-                    debug!("Parsed L1 batch reveal txid");
-                    txid
-                }
-                Err(e) => {
-                    warn!("Failed to parse L1 batch reveal txid: {}", e);
-                    return None;
-                }
-            };
+        let l1_batch_reveal_txid = read_txid(instructions, 2, "l1_batch_reveal_txid")?;
+        debug!("Parsed L1 batch reveal txid");
 
-        let da_identifier = std::str::from_utf8(instructions.get(3)?.push_bytes()?.as_bytes())
-            .ok()?
-            .to_string();
+        let da_identifier = read_string(instructions, 3, "da_identifier")?;
         debug!("Parsed DA identifier: {}", da_identifier);
 
-        let blob_id = std::str::from_utf8(instructions.get(4)?.push_bytes()?.as_bytes())
-            .ok()?
-            .to_string();
+        let (blob_id, next_index) = read_blob_id(instructions, 4)?;
         debug!("Parsed blob ID: {}", blob_id);
 
-        Some(FullInscriptionMessage::ProofDAReference(ProofDAReference {
+        // The reveal txids of the next L1 batches of the range fill the envelope up to its
+        // closing `OP_ENDIF`.
+        let end = match instructions.last() {
+            Some(Instruction::Op(op)) if *op == OP_ENDIF => instructions.len() - 1,
+            _ => instructions.len(),
+        };
+        let next_l1_batch_reveal_txids = (next_index..end)
+            .map(|index| read_txid(instructions, index, "next_l1_batch_reveal_txid"))
+            .collect::<ParseResult<Vec<_>>>()?;
+        debug!(
+            "Parsed {} next L1 batch reveal txids",
+            next_l1_batch_reveal_txids.len()
+        );
+
+        Ok(FullInscriptionMessage::ProofDAReference(ProofDAReference {
             common: common_fields.clone(),
             input: ProofDAReferenceInput {
                 l1_batch_reveal_txid,
                 da_identifier,
                 blob_id,
+                next_l1_batch_reveal_txids,
+            },
+        }))
+    }
+
+    #[instrument(
+        skip(self, instructions, common_fields),
+        target = "bitcoin_indexer::parser"
+    )]
+    fn parse_da_blob_chunk(
+        &self,
+        instructions: &[Instruction],
+        common_fields: &CommonFields,
+    ) -> ParseResult<FullInscriptionMessage> {
+        require_instructions(instructions, MIN_DA_BLOB_CHUNK_INSTRUCTIONS)?;
+
+        let blob_hash = read_h256(instructions, 2, "blob_hash")?;
+        debug!("Parsed blob hash");
+
+        let chunk_index = read_u32_be(instructions, 3, "chunk_index")?;
+        debug!("Parsed chunk index: {}", chunk_index);
+
+        // The chunk data is split over the pushes up to the closing `OP_ENDIF`.
+        let end = match instructions.last() {
+            Some(Instruction::Op(op)) if *op == OP_ENDIF => instructions.len() - 1,
+            _ => instructions.len(),
+        };
+        let mut data = Vec::new();
+        for index in DA_BLOB_CHUNK_DATA_START_INDEX..end {
+            data.extend_from_slice(push_bytes(instructions, index, "data")?);
+        }
+        debug!("Parsed {} bytes of chunk data", data.len());
+
+        Ok(FullInscriptionMessage::DABlobChunk(DABlobChunk {
+            common: common_fields.clone(),
+            input: DABlobChunkInput {
+                blob_hash,
+                chunk_index,
+                data,
             },
         }))
     }
@@ -611,24 +635,17 @@ impl MessageParser {
         tx: &Transaction,
         instructions: &[Instruction],
         common_fields: &CommonFields,
-        wallets_opt: Option<&SystemWallets>,
-    ) -> Option<FullInscriptionMessage> {
-        let wallets = wallets_opt?;
-
-        if instructions.len() < MIN_L1_TO_L2_MESSAGE_INSTRUCTIONS {
-            warn!("Insufficient instructions for L1 to L2 message");
-            return None;
-        }
+        wallets: &SystemWallets,
+    ) -> ParseResult<FullInscriptionMessage> {
+        require_instructions(instructions, MIN_L1_TO_L2_MESSAGE_INSTRUCTIONS)?;
 
-        let receiver_l2_address =
-            EVMAddress::from_slice(instructions.get(2)?.push_bytes()?.as_bytes());
+        let receiver_l2_address = read_evm_address(instructions, 2, "receiver_l2_address")?;
         debug!("Parsed receiver L2 address");
 
-        let l2_contract_address =
-            EVMAddress::from_slice(instructions.get(3)?.push_bytes()?.as_bytes());
+        let l2_contract_address = read_evm_address(instructions, 3, "l2_contract_address")?;
         debug!("Parsed L2 contract address");
 
-        let call_data = instructions.get(4)?.push_bytes()?.as_bytes().to_vec();
+        let call_data = push_bytes(instructions, 4, "call_data")?.to_vec();
         debug!("Parsed call data, length: {}", call_data.len());
 
         let amount = tx
@@ -642,7 +659,7 @@ impl MessageParser {
             .unwrap_or(Amount::ZERO);
         debug!("Parsed amount: {}", amount);
 
-        Some(FullInscriptionMessage::L1ToL2Message(L1ToL2Message {
+        Ok(FullInscriptionMessage::L1ToL2Message(L1ToL2Message {
             common: common_fields.clone(),
             amount,
             input: L1ToL2MessageInput {
@@ -662,39 +679,53 @@ impl MessageParser {
         &self,
         instructions: &[Instruction],
         common_fields: &CommonFields,
-    ) -> Option<FullInscriptionMessage> {
-        if instructions.len() < MIN_SYSTEM_CONTRACT_UPGRADE_PROPOSAL {
-            return None;
-        }
+    ) -> ParseResult<FullInscriptionMessage> {
+        require_instructions(instructions, MIN_SYSTEM_CONTRACT_UPGRADE_PROPOSAL)?;
 
+        let packed_version = read_h256(instructions, 2, "version")?;
         let version = ProtocolSemanticVersion::try_from_packed(U256::from_big_endian(
-            instructions.get(2)?.push_bytes()?.as_bytes(),
+            packed_version.as_bytes(),
         ))
-        .ok()?;
+        .map_err(|e| ParseError::invalid_field("version", e))?;
         debug!("Parsed protocol version");
 
-        let bootloader_code_hash = H256::from_slice(instructions.get(3)?.push_bytes()?.as_bytes());
+        let bootloader_code_hash = read_h256(instructions, 3, "bootloader_code_hash")?;
         debug!("Parsed bootloader code hash");
 
-        let default_account_code_hash =
-            H256::from_slice(instructions.get(4)?.push_bytes()?.as_bytes());
+        let default_account_code_hash = read_h256(instructions, 4, "default_account_code_hash")?;
         debug!("Parsed default account code hash");
 
         let recursion_scheduler_level_vk_hash =
-            H256::from_slice(instructions.get(5)?.push_bytes()?.as_bytes());
+            read_h256(instructions, 5, "recursion_scheduler_level_vk_hash")?;
         debug!("Parsed recursion scheduler level vk hash");
 
-        let len = instructions.len() - 7;
-        let mut system_contracts = Vec::with_capacity(len / 2);
-
-        for i in (6..len).step_by(2) {
-            let address = EVMAddress::from_slice(instructions.get(i)?.push_bytes()?.as_bytes());
-            let hash = H256::from_slice(instructions.get(i + 1)?.push_bytes()?.as_bytes());
-            system_contracts.push((address, hash))
+        // The `(address, code hash)` pairs fill the envelope up to its closing `OP_ENDIF`.
+        let end = match instructions.last() {
+            Some(Instruction::Op(op)) if *op == OP_ENDIF => instructions.len() - 1,
+            _ => instructions.len(),
+        };
+        let pairs = instructions
+            .get(SYSTEM_CONTRACTS_START_INDEX..end)
+            .unwrap_or_default();
+        if pairs.len() % 2 != 0 {
+            return Err(ParseError::invalid_field(
+                "system_contracts",
+                "odd number of instructions",
+            ));
         }
-        debug!("Parsed system contracts");
 
-        Some(FullInscriptionMessage::SystemContractUpgradeProposal(
+        let system_contracts = pairs
+            .chunks_exact(2)
+            .map(|pair| -> ParseResult<_> {
+                Ok((
+                    read_evm_address(pair, 0, "system_contract_address")?,
+                    read_h256(pair, 1, "system_contract_hash")?,
+                ))
+            })
+            .collect::<ParseResult<Vec<_>>>()?;
+        debug!("Parsed {} system contracts", system_contracts.len());
+
+        Ok(FullInscriptionMessage::SystemContractUpgradeProposal(
             SystemContractUpgradeProposal {
                 common: common_fields.clone(),
                 input: SystemContractUpgradeProposalInput {
@@ -716,23 +747,13 @@ impl MessageParser {
         &self,
         instructions: &[Instruction],
         common_fields: &CommonFields,
-    ) -> Option<FullInscriptionMessage> {
-        if instructions.len() < MIN_UPDATE_BRIDGE_PROPOSAL {
-            return None;
-        }
+    ) -> ParseResult<FullInscriptionMessage> {
+        require_instructions(instructions, MIN_UPDATE_BRIDGE_PROPOSAL)?;
 
         // network unchecked is required to enable serde serialization and deserialization on the library structs
         let verifier_p2wpkh_addresses = instructions[1..instructions.len() - 2]
             .iter()
-            .filter_map(|instr| {
-                if let Instruction::PushBytes(bytes) = instr {
-                    std::str::from_utf8(bytes.as_bytes())
-                        .ok()
-                        .and_then(|s| s.parse::<Address<NetworkUnchecked>>().ok())
-                } else {
-                    None
-                }
-            })
+            .filter_map(parse_unchecked_address)
             .collect::<Vec<_>>();
 
         debug!(
@@ -740,19 +761,15 @@ impl MessageParser {
             verifier_p2wpkh_addresses.len()
         );
 
-        let bridge_musig2_address = instructions.get(instructions.len() - 2).and_then(|instr| {
-            if let Instruction::PushBytes(bytes) = instr {
-                std::str::from_utf8(bytes.as_bytes())
-                    .ok()
-                    .and_then(|s| s.parse::<Address<NetworkUnchecked>>().ok())
-            } else {
-                None
-            }
-        })?;
+        let bridge_musig2_address = read_address(
+            instructions,
+            instructions.len() - 2,
+            "bridge_musig2_address",
+        )?;
 
         debug!("Parsed bridge address");
 
-        Some(FullInscriptionMessage::UpdateBridgeProposal(
+        Ok(FullInscriptionMessage::UpdateBridgeProposal(
             UpdateBridgeProposal {
                 common: common_fields.clone(),
                 input: UpdateBridgeProposalInput {
@@ -768,7 +785,7 @@ impl MessageParser {
         tx: &TransactionWithMetadata,
         block_height: u32,
         wallets: &SystemWallets,
-    ) -> Option<FullInscriptionMessage> {
+    ) -> ParseResult<Option<FullInscriptionMessage>> {
         // Try to find any witness data that contains a valid inscription
         for input in tx.tx.input.iter() {
             let witness = &input.witness;
@@ -777,15 +794,27 @@ impl MessageParser {
             }
 
             // Parse signature and control block
-            let signature = TaprootSignature::from_slice(&witness[0]).ok()?;
+            let Ok(signature) = TaprootSignature::from_slice(&witness[0]) else {
+                return Ok(None);
+            };
             let script = ScriptBuf::from_bytes(witness[1].to_vec());
-            let control_block = ControlBlock::decode(&witness[2]).ok()?;
+            let Ok(control_block) = ControlBlock::decode(&witness[2]) else {
+                return Ok(None);
+            };
 
             let instructions: Vec<_> = script.instructions().filter_map(Result::ok).collect();
-            let via_index = find_via_inscription_protocol(&instructions)?;
+            let Some(via_index) = find_via_inscription_protocol(&instructions) else {
+                return Ok(None);
+            };
 
-            // Try to parse p2wpkh address if possible, but make it optional
-            let p2wpkh_address = self.parse_p2wpkh(witness);
+            // Try to parse p2wpkh address if possible, but make it optional. The inscription is
+            // usually funded by a p2wpkh input, which identifies the sender for refunds.
+            let p2wpkh_address = self.parse_p2wpkh(witness).or_else(|| {
+                tx.tx
+                    .input
+                    .first()
+                    .and_then(|input| self.parse_p2wpkh(&input.witness))
+            });
 
             let common_fields = CommonFields {
                 schnorr_signature: signature,
@@ -798,15 +827,13 @@ impl MessageParser {
             };
 
             // Parse L1ToL2Message from instructions
-            return self.parse_l1_to_l2_message(
-                &tx.tx,
-                &instructions[via_index..],
-                &common_fields,
-                Some(wallets),
-            );
+            let instructions = envelope_message(&instructions[via_index..])?;
+            return self
+                .parse_l1_to_l2_message(&tx.tx, instructions, &common_fields, wallets)
+                .map(Some);
         }
 
-        None
+        Ok(None)
     }
 
     fn parse_op_return_deposit(
@@ -814,33 +841,46 @@ impl MessageParser {
         tx: &TransactionWithMetadata,
         block_height: u32,
         bridge_output: &TxOut,
-    ) -> Option<FullInscriptionMessage> {
+    ) -> ParseResult<Option<FullInscriptionMessage>> {
         // Find OP_RETURN output
-        let op_return_output = tx
+        let Some(op_return_output) = tx
             .tx
             .output
             .iter()
-            .find(|output| output.script_pubkey.is_op_return())?;
+            .find(|output| output.script_pubkey.is_op_return())
+        else {
+            return Ok(None);
+        };
 
         // Parse OP_RETURN data
         let op_return_data = op_return_output.script_pubkey.as_bytes();
         if op_return_data.len() < 2 {
-            return None;
+            return Ok(None);
         }
 
         // Parse OP_RETURN data
         if let Some(op_return_data) = op_return_output.script_pubkey.as_bytes().get(2..) {
             if op_return_data.starts_with(OP_RETURN_WITHDRAW_PREFIX)
+                || op_return_data.starts_with(OP_RETURN_REFUND_PREFIX)
                 || op_return_data.starts_with(OP_RETURN_UPGRADE_PROTOCOL_PREFIX)
                 || op_return_data.starts_with(OP_RETURN_UPDATE_SEQUENCER_PREFIX)
                 || op_return_data.starts_with(OP_RETURN_UPDATE_BRIDGE_PREFIX)
                 || op_return_data.starts_with(OP_RETURN_UPDATE_GOVERNANCE_PREFIX)
             {
-                return None;
+                return Ok(None);
             }
-            // Parse receiver address from OP_RETURN data
-
-            let receiver_l2_address = EVMAddress::from_slice(&op_return_data[0..20]);
+            // Parse receiver address from OP_RETURN data. A deposit without a valid receiver is
+            // still indexed, with the zero address, so the BTC can be refunded to the sender.
+            let receiver_l2_address = match op_return_data.get(..EVMAddress::len_bytes()) {
+                Some(receiver) => EVMAddress::from_slice(receiver),
+                None => {
+                    warn!(
+                        "Deposit {} without a valid receiver address",
+                        tx.tx.compute_txid()
+                    );
+                    EVMAddress::zero()
+                }
+            };
 
             let input = L1ToL2MessageInput {
                 receiver_l2_address,
@@ -857,7 +897,7 @@ impl MessageParser {
 
             // Create common fields with empty signature for OP_RETURN
             let common_fields = CommonFields {
-                schnorr_signature: TaprootSignature::from_slice(&[0; 64]).ok()?,
+                schnorr_signature: empty_schnorr_signature()?,
                 encoded_public_key: PushBytesBuf::new(),
                 block_height,
                 tx_id: tx.tx.compute_ntxid().into(),
@@ -866,14 +906,14 @@ impl MessageParser {
                 output_vout: tx.output_vout,
             };
 
-            return Some(FullInscriptionMessage::L1ToL2Message(L1ToL2Message {
+            return Ok(Some(FullInscriptionMessage::L1ToL2Message(L1ToL2Message {
                 common: common_fields,
                 amount: bridge_output.value,
                 input,
                 tx_outputs: tx.tx.output.clone(),
-            }));
+            })));
         }
-        None
+        Ok(None)
     }
 
     fn parse_op_return_withdrawal(
@@ -881,289 +921,432 @@ impl MessageParser {
         tx: &Transaction,
         block_height: u32,
         wallets: &SystemWallets,
-    ) -> Option<FullInscriptionMessage> {
-        // Find OP_RETURN output
-        let op_return_output = tx
-            .output
-            .iter()
-            .find(|output| output.script_pubkey.is_op_return())?;
+    ) -> ParseResult<Option<FullInscriptionMessage>> {
+        let Some(op_return_data) = op_return_payload(tx, OP_RETURN_WITHDRAW_PREFIX) else {
+            return Ok(None);
+        };
 
-        // Parse OP_RETURN data
-        if let Some(op_return_data) = op_return_output.script_pubkey.as_bytes().get(2..) {
-            if !op_return_data.starts_with(OP_RETURN_WITHDRAW_PREFIX) {
-                return None;
+        let start = OP_RETURN_WITHDRAW_PREFIX.len() + 1;
+
+        // Parse l1_batch_reveal_tx_id from OP_RETURN data
+        let l1_batch_proof_reveal_tx_id =
+            op_return_txid(op_return_data, start, "l1_batch_proof_reveal_tx_id")?
+                .as_raw_hash()
+                .as_byte_array()
+                .to_vec();
+
+        // Optional l1_batch_number, followed by the optional index_withdrawal. The withdrawals of
+        // the L1 batches proven by the same proof carry the number of their L1 batch.
+        let (l1_batch_number, index_start) = match op_return_data.len().checked_sub(start + 32) {
+            Some(12) => {
+                let bytes = &op_return_data[start + 32..start + 36];
+                (
+                    Some(u32::from_le_bytes(fixed_bytes(bytes, "l1_batch_number")?)),
+                    start + 36,
+                )
             }
+            _ => (None, start + 32),
+        };
+        let index_withdrawal = match op_return_data.get(index_start..index_start + 8) {
+            Some(bytes) => i64::from_le_bytes(fixed_bytes(bytes, "index_withdrawal")?),
+            None => 0,
+        };
 
-            let start = OP_RETURN_WITHDRAW_PREFIX.len() + 1;
-            if op_return_data.len() < start + 32 {
-                return None;
+        let mut withdrawals = Vec::new();
+        for output in &tx.output {
+            let address = match Address::from_script(&output.script_pubkey, self.network) {
+                Ok(address) => address,
+                Err(_) => continue,
+            };
+
+            if address == wallets.bridge {
+                continue;
             }
 
-            // Parse l1_batch_reveal_tx_id from OP_RETURN data
-            let l1_batch_proof_reveal_tx_id =
-                match Txid::from_slice(&op_return_data[start..start + 32]) {
-                    Ok(tx_id) => tx_id.as_raw_hash().as_byte_array().to_vec(),
-                    Err(_) => return None,
-                };
+            withdrawals.push((address.to_string(), output.value.to_sat() as i64));
+        }
 
-            // Optional index_withdrawal
-            let index_withdrawal = if op_return_data.len() >= start + 40 {
-                let bytes: [u8; 8] = op_return_data[start + 32..start + 40].try_into().ok()?;
-                i64::from_le_bytes(bytes)
-            } else {
-                0
-            };
+        let input = BridgeWithdrawalInput {
+            index_withdrawal,
+            v_size: tx.vsize() as i64,
+            total_size: tx.total_size() as i64,
+            inputs: tx.input.iter().map(|input| input.previous_output).collect(),
+            output_amount: tx.output.iter().map(|out| out.value.to_sat()).sum(),
+            l1_batch_proof_reveal_tx_id,
+            l1_batch_number,
+            withdrawals,
+        };
 
-            let mut withdrawals = Vec::new();
-            for output in &tx.output {
-                let address = match Address::from_script(&output.script_pubkey, self.network) {
-                    Ok(address) => address,
-                    Err(_) => continue,
-                };
+        Ok(Some(FullInscriptionMessage::BridgeWithdrawal(
+            BridgeWithdrawal {
+                common: op_return_common_fields(tx, block_height)?,
+                input,
+            },
+        )))
+    }
 
-                if address == wallets.bridge {
-                    continue;
-                }
+    fn parse_op_return_refund(
+        &self,
+        tx: &Transaction,
+        block_height: u32,
+        wallets: &SystemWallets,
+    ) -> ParseResult<Option<FullInscriptionMessage>> {
+        let Some(op_return_data) = op_return_payload(tx, OP_RETURN_REFUND_PREFIX) else {
+            return Ok(None);
+        };
 
-                withdrawals.push((address.to_string(), output.value.to_sat() as i64));
-            }
+        let start = OP_RETURN_REFUND_PREFIX.len() + 1;
 
-            let input = BridgeWithdrawalInput {
-                index_withdrawal,
-                v_size: tx.vsize() as i64,
-                total_size: tx.total_size() as i64,
-                inputs: tx.input.iter().map(|input| input.previous_output).collect(),
-                output_amount: tx.output.iter().map(|out| out.value.to_sat()).sum(),
-                l1_batch_proof_reveal_tx_id,
-                withdrawals,
-            };
+        // Parse the refunded deposit tx_id from OP_RETURN data
+        let deposit_tx_id = op_return_txid(op_return_data, start, "deposit_tx_id")?;
 
-            // Create common fields with empty signature for OP_RETURN
-            let common_fields = CommonFields {
-                schnorr_signature: TaprootSignature::from_slice(&[0; 64]).ok()?,
-                encoded_public_key: PushBytesBuf::new(),
-                block_height,
-                tx_id: tx.compute_ntxid().into(),
-                p2wpkh_address: None,
-                tx_index: None,
-                output_vout: None,
+        let mut refunds = Vec::new();
+        for output in &tx.output {
+            let address = match Address::from_script(&output.script_pubkey, self.network) {
+                Ok(address) => address,
+                Err(_) => continue,
             };
 
-            return Some(FullInscriptionMessage::BridgeWithdrawal(BridgeWithdrawal {
-                common: common_fields,
-                input,
-            }));
+            if address == wallets.bridge {
+                continue;
+            }
+
+            refunds.push((address.to_string(), output.value.to_sat() as i64));
         }
-        None
+
+        let input = BridgeRefundInput {
+            inputs: tx.input.iter().map(|input| input.previous_output).collect(),
+            deposit_tx_id,
+            refunds,
+        };
+
+        Ok(Some(FullInscriptionMessage::BridgeRefund(BridgeRefund {
+            common: op_return_common_fields(tx, block_height)?,
+            input,
+        })))
     }
 
     fn parse_op_return_protocol_upgrade(
         &self,
         tx: &Transaction,
         block_height: u32,
-    ) -> Option<FullInscriptionMessage> {
-        // Find OP_RETURN output
-        let op_return_output = tx
-            .output
-            .iter()
-            .find(|output| output.script_pubkey.is_op_return())?;
-
-        // Parse OP_RETURN data
-        if let Some(op_return_data) = op_return_output.script_pubkey.as_bytes().get(2..) {
-            if !op_return_data.starts_with(OP_RETURN_UPGRADE_PROTOCOL_PREFIX) {
-                return None;
-            }
+    ) -> ParseResult<Option<FullInscriptionMessage>> {
+        let Some(op_return_data) = op_return_payload(tx, OP_RETURN_UPGRADE_PROTOCOL_PREFIX) else {
+            return Ok(None);
+        };
 
-            let start = OP_RETURN_UPGRADE_PROTOCOL_PREFIX.len() + 1;
-            if op_return_data.len() < start + 32 {
-                return None;
-            }
+        let start = OP_RETURN_UPGRADE_PROTOCOL_PREFIX.len() + 1;
 
-            // Parse proposal_tx_id from OP_RETURN data
-            let proposal_tx_id = match Txid::from_slice(&op_return_data[start..start + 32]) {
-                Ok(tx_id) => tx_id,
-                Err(_) => return None,
-            };
+        // Parse proposal_tx_id from OP_RETURN data
+        let proposal_tx_id = op_return_txid(op_return_data, start, "proposal_tx_id")?;
 
-            let input = SystemContractUpgradeInput {
-                inputs: tx.input.iter().map(|input| input.previous_output).collect(),
-                proposal_tx_id,
-            };
-
-            // Create common fields with empty signature for OP_RETURN
-            let common_fields = CommonFields {
-                schnorr_signature: TaprootSignature::from_slice(&[0; 64]).ok()?,
-                encoded_public_key: PushBytesBuf::new(),
-                block_height,
-                tx_id: tx.compute_ntxid().into(),
-                p2wpkh_address: None,
-                tx_index: None,
-                output_vout: None,
-            };
+        let input = SystemContractUpgradeInput {
+            inputs: tx.input.iter().map(|input| input.previous_output).collect(),
+            proposal_tx_id,
+        };
 
-            return Some(FullInscriptionMessage::SystemContractUpgrade(
-                SystemContractUpgrade {
-                    common: common_fields,
-                    input,
-                },
-            ));
-        }
-        None
+        Ok(Some(FullInscriptionMessage::SystemContractUpgrade(
+            SystemContractUpgrade {
+                common: op_return_common_fields(tx, block_height)?,
+                input,
+            },
+        )))
     }
 
     fn parse_op_return_update_bridge(
         &self,
         tx: &Transaction,
         block_height: u32,
-    ) -> Option<FullInscriptionMessage> {
-        // Find OP_RETURN output
-        let op_return_output = tx
-            .output
-            .iter()
-            .find(|output| output.script_pubkey.is_op_return())?;
-
-        // Parse OP_RETURN data
-        if let Some(op_return_data) = op_return_output.script_pubkey.as_bytes().get(2..) {
-            if !op_return_data.starts_with(OP_RETURN_UPDATE_BRIDGE_PREFIX) {
-                return None;
-            }
-
-            let start = OP_RETURN_UPDATE_BRIDGE_PREFIX.len() + 1;
-            if op_return_data.len() < start + 32 {
-                return None;
-            }
+    ) -> ParseResult<Option<FullInscriptionMessage>> {
+        let Some(op_return_data) = op_return_payload(tx, OP_RETURN_UPDATE_BRIDGE_PREFIX) else {
+            return Ok(None);
+        };
 
-            // Parse proposal_tx_id from OP_RETURN data
-            let proposal_tx_id = match Txid::from_slice(&op_return_data[start..start + 32]) {
-                Ok(tx_id) => tx_id,
-                Err(_) => return None,
-            };
+        let start = OP_RETURN_UPDATE_BRIDGE_PREFIX.len() + 1;
 
-            let input = UpdateBridgeInput {
-                inputs: tx.input.iter().map(|input| input.previous_output).collect(),
-                proposal_tx_id,
-            };
+        // Parse proposal_tx_id from OP_RETURN data
+        let proposal_tx_id = op_return_txid(op_return_data, start, "proposal_tx_id")?;
 
-            // Create common fields with empty signature for OP_RETURN
-            let common = CommonFields {
-                schnorr_signature: TaprootSignature::from_slice(&[0; 64]).ok()?,
-                encoded_public_key: PushBytesBuf::new(),
-                block_height,
-                tx_id: tx.compute_ntxid().into(),
-                p2wpkh_address: None,
-                tx_index: None,
-                output_vout: None,
-            };
+        let input = UpdateBridgeInput {
+            inputs: tx.input.iter().map(|input| input.previous_output).collect(),
+            proposal_tx_id,
+        };
 
-            return Some(FullInscriptionMessage::UpdateBridge(UpdateBridge {
-                common,
-                input,
-            }));
-        }
-        None
+        Ok(Some(FullInscriptionMessage::UpdateBridge(UpdateBridge {
+            common: op_return_common_fields(tx, block_height)?,
+            input,
+        })))
     }
 
     fn parse_op_return_update_sequencer(
         &self,
         tx: &Transaction,
         block_height: u32,
-    ) -> Option<FullInscriptionMessage> {
-        // Find OP_RETURN output
-        let op_return_output = tx
-            .output
-            .iter()
-            .find(|output| output.script_pubkey.is_op_return())?;
+    ) -> ParseResult<Option<FullInscriptionMessage>> {
+        let Some(op_return_data) = op_return_payload(tx, OP_RETURN_UPDATE_SEQUENCER_PREFIX) else {
+            return Ok(None);
+        };
 
-        // Parse OP_RETURN data
-        if let Some(op_return_data) = op_return_output.script_pubkey.as_bytes().get(2..) {
-            if !op_return_data.starts_with(OP_RETURN_UPDATE_SEQUENCER_PREFIX) {
-                return None;
-            }
+        let start = OP_RETURN_UPDATE_SEQUENCER_PREFIX.len() + 1;
 
-            let start = OP_RETURN_UPDATE_SEQUENCER_PREFIX.len() + 1;
+        // Parse sequencer address from OP_RETURN data
+        let address = op_return_address(op_return_data, start, "sequencer_address")?;
 
-            // Parse sequencer address from OP_RETURN data
-            let address_str = std::str::from_utf8(&op_return_data[start..]).ok()?;
-            let address = match Address::from_str(address_str) {
-                Ok(address) => address,
-                Err(_) => return None,
-            };
-
-            let input = UpdateSequencerInput {
-                inputs: tx.input.iter().map(|input| input.previous_output).collect(),
-                address,
-            };
-
-            // Create common fields with empty signature for OP_RETURN
-            let common = CommonFields {
-                schnorr_signature: TaprootSignature::from_slice(&[0; 64]).ok()?,
-                encoded_public_key: PushBytesBuf::new(),
-                block_height,
-                tx_id: tx.compute_ntxid().into(),
-                p2wpkh_address: None,
-                tx_index: None,
-                output_vout: None,
-            };
+        let input = UpdateSequencerInput {
+            inputs: tx.input.iter().map(|input| input.previous_output).collect(),
+            address,
+        };
 
-            return Some(FullInscriptionMessage::UpdateSequencer(UpdateSequencer {
-                common,
+        Ok(Some(FullInscriptionMessage::UpdateSequencer(
+            UpdateSequencer {
+                common: op_return_common_fields(tx, block_height)?,
                 input,
-            }));
-        }
-        None
+            },
+        )))
     }
 
     fn parse_op_return_update_governance(
         &self,
         tx: &Transaction,
         block_height: u32,
-    ) -> Option<FullInscriptionMessage> {
-        // Find OP_RETURN output
-        let op_return_output = tx
-            .output
-            .iter()
-            .find(|output| output.script_pubkey.is_op_return())?;
+    ) -> ParseResult<Option<FullInscriptionMessage>> {
+        let Some(op_return_data) = op_return_payload(tx, OP_RETURN_UPDATE_GOVERNANCE_PREFIX) else {
+            return Ok(None);
+        };
 
-        // Parse OP_RETURN data
-        if let Some(op_return_data) = op_return_output.script_pubkey.as_bytes().get(2..) {
-            if !op_return_data.starts_with(OP_RETURN_UPDATE_GOVERNANCE_PREFIX) {
-                return None;
-            }
+        let start = OP_RETURN_UPDATE_GOVERNANCE_PREFIX.len() + 1;
 
-            let start = OP_RETURN_UPDATE_GOVERNANCE_PREFIX.len() + 1;
+        // Parse governance address from OP_RETURN data
+        let address = op_return_address(op_return_data, start, "governance_address")?;
 
-            // Parse sequencer address from OP_RETURN data
-            let address_str = std::str::from_utf8(&op_return_data[start..]).ok()?;
-            let address = match Address::from_str(address_str) {
-                Ok(address) => address,
-                Err(_) => return None,
-            };
+        let input = UpdateGovernanceInput {
+            inputs: tx.input.iter().map(|input| input.previous_output).collect(),
+            address,
+        };
 
-            let input = UpdateGovernanceInput {
-                inputs: tx.input.iter().map(|input| input.previous_output).collect(),
-                address,
-            };
+        Ok(Some(FullInscriptionMessage::UpdateGovernance(
+            UpdateGovernance {
+                common: op_return_common_fields(tx, block_height)?,
+                input,
+            },
+        )))
+    }
+}
 
-            // Create common fields with empty signature for OP_RETURN
-            let common = CommonFields {
-                schnorr_signature: TaprootSignature::from_slice(&[0; 64]).ok()?,
-                encoded_public_key: PushBytesBuf::new(),
-                block_height,
-                tx_id: tx.compute_ntxid().into(),
-                p2wpkh_address: None,
-                tx_index: None,
-                output_vout: None,
-            };
+/// Collects a parsed message, malformed messages are logged and skipped.
+fn push_parsed(
+    messages: &mut Vec<FullInscriptionMessage>,
+    tx: &Transaction,
+    parsed: ParseResult<Option<FullInscriptionMessage>>,
+) {
+    match parsed {
+        Ok(Some(message)) => messages.push(message),
+        Ok(None) => {}
+        Err(err) => warn!(
+            "Skipping malformed message in transaction {}: {}",
+            tx.compute_txid(),
+            err
+        ),
+    }
+}
 
-            return Some(FullInscriptionMessage::UpdateGovernance(UpdateGovernance {
-                common,
-                input,
-            }));
+fn require_instructions(instructions: &[Instruction], expected: usize) -> ParseResult<()> {
+    if instructions.len() < expected {
+        return Err(ParseError::InsufficientInstructions {
+            expected,
+            actual: instructions.len(),
+        });
+    }
+    Ok(())
+}
+
+fn push_bytes<'a>(
+    instructions: &'a [Instruction],
+    index: usize,
+    field: &'static str,
+) -> ParseResult<&'a [u8]> {
+    match instructions.get(index) {
+        Some(Instruction::PushBytes(bytes)) => Ok(bytes.as_bytes()),
+        Some(Instruction::Op(op)) => Err(ParseError::invalid_field(
+            field,
+            format!("expected a data push, got {op}"),
+        )),
+        None => Err(ParseError::MissingField(field)),
+    }
+}
+
+fn fixed_bytes<const N: usize>(bytes: &[u8], field: &'static str) -> ParseResult<[u8; N]> {
+    bytes.try_into().map_err(|_| {
+        ParseError::invalid_field(field, format!("expected {N} bytes, got {}", bytes.len()))
+    })
+}
+
+fn read_h256(instructions: &[Instruction], index: usize, field: &'static str) -> ParseResult<H256> {
+    fixed_bytes(push_bytes(instructions, index, field)?, field).map(H256)
+}
+
+fn read_evm_address(
+    instructions: &[Instruction],
+    index: usize,
+    field: &'static str,
+) -> ParseResult<EVMAddress> {
+    fixed_bytes::<20>(push_bytes(instructions, index, field)?, field).map(EVMAddress::from)
+}
+
+fn read_u32_be(
+    instructions: &[Instruction],
+    index: usize,
+    field: &'static str,
+) -> ParseResult<u32> {
+    fixed_bytes(push_bytes(instructions, index, field)?, field).map(u32::from_be_bytes)
+}
+
+fn read_txid(instructions: &[Instruction], index: usize, field: &'static str) -> ParseResult<Txid> {
+    Txid::from_slice(push_bytes(instructions, index, field)?)
+        .map_err(|e| ParseError::invalid_field(field, e))
+}
+
+fn read_string(
+    instructions: &[Instruction],
+    index: usize,
+    field: &'static str,
+) -> ParseResult<String> {
+    std::str::from_utf8(push_bytes(instructions, index, field)?)
+        .map(str::to_string)
+        .map_err(|e| ParseError::invalid_field(field, e))
+}
+
+/// Reads the `blob_id` of a DA reference at `index`, returns it along with the index of the next
+/// field. A `blob_id` split over several pushes is preceded by their count.
+fn read_blob_id(instructions: &[Instruction], index: usize) -> ParseResult<(String, usize)> {
+    let (start, pushes) = match instructions.get(index) {
+        Some(Instruction::Op(op))
+            if (OP_PUSHNUM_2.to_u8()..=OP_PUSHNUM_16.to_u8()).contains(&op.to_u8()) =>
+        {
+            (index + 1, (op.to_u8() - OP_PUSHNUM_1.to_u8() + 1) as usize)
         }
+        _ => (index, 1),
+    };
+
+    let mut blob_id = Vec::new();
+    for index in start..start + pushes {
+        blob_id.extend_from_slice(push_bytes(instructions, index, "blob_id")?);
+    }
+    let blob_id =
+        String::from_utf8(blob_id).map_err(|e| ParseError::invalid_field("blob_id", e))?;
+    Ok((blob_id, start + pushes))
+}
+
+fn read_address(
+    instructions: &[Instruction],
+    index: usize,
+    field: &'static str,
+) -> ParseResult<Address<NetworkUnchecked>> {
+    read_string(instructions, index, field)?
+        .parse()
+        .map_err(|e| ParseError::invalid_field(field, e))
+}
+
+fn parse_unchecked_address(instruction: &Instruction) -> Option<Address<NetworkUnchecked>> {
+    if let Instruction::PushBytes(bytes) = instruction {
+        std::str::from_utf8(bytes.as_bytes())
+            .ok()
+            .and_then(|s| s.parse::<Address<NetworkUnchecked>>().ok())
+    } else {
         None
     }
 }
 
+/// Returns the data pushed by the first OP_RETURN output of `tx` if it starts with `prefix`.
+fn op_return_payload<'a>(tx: &'a Transaction, prefix: &[u8]) -> Option<&'a [u8]> {
+    let op_return_output = tx
+        .output
+        .iter()
+        .find(|output| output.script_pubkey.is_op_return())?;
+
+    op_return_output
+        .script_pubkey
+        .as_bytes()
+        .get(2..)
+        .filter(|data| data.starts_with(prefix))
+}
+
+fn op_return_txid(data: &[u8], start: usize, field: &'static str) -> ParseResult<Txid> {
+    let bytes = data
+        .get(start..start + 32)
+        .ok_or(ParseError::MissingField(field))?;
+    Txid::from_slice(bytes).map_err(|e| ParseError::invalid_field(field, e))
+}
+
+fn op_return_address(
+    data: &[u8],
+    start: usize,
+    field: &'static str,
+) -> ParseResult<Address<NetworkUnchecked>> {
+    let bytes = data.get(start..).ok_or(ParseError::MissingField(field))?;
+    let address_str =
+        std::str::from_utf8(bytes).map_err(|e| ParseError::invalid_field(field, e))?;
+    Address::from_str(address_str).map_err(|e| ParseError::invalid_field(field, e))
+}
+
+/// Common fields of the OP_RETURN messages, which carry no signature.
+fn op_return_common_fields(tx: &Transaction, block_height: u32) -> ParseResult<CommonFields> {
+    Ok(CommonFields {
+        schnorr_signature: empty_schnorr_signature()?,
+        encoded_public_key: PushBytesBuf::new(),
+        block_height,
+        tx_id: tx.compute_ntxid().into(),
+        p2wpkh_address: None,
+        tx_index: None,
+        output_vout: None,
+    })
+}
+
+fn empty_schnorr_signature() -> ParseResult<TaprootSignature> {
+    TaprootSignature::from_slice(&[0; 64])
+        .map_err(|e| ParseError::invalid_field("schnorr_signature", e))
+}
+
+/// Splits the script instructions into the via inscription envelopes it contains, each envelope
+/// starting at the protocol identifier and ending with its `OP_ENDIF`.
+#[instrument(skip(instructions), target = "bitcoin_indexer::parser")]
+fn find_via_inscription_envelopes<'a, 'b>(
+    instructions: &'a [Instruction<'b>],
+) -> Vec<&'a [Instruction<'b>]> {
+    let mut envelopes = Vec::new();
+    let mut remaining = instructions;
+
+    while let Some(via_index) = find_via_inscription_protocol(remaining) {
+        let envelope = &remaining[via_index..];
+        let end = envelope
+            .iter()
+            .position(|instr| matches!(instr, Instruction::Op(op) if *op == OP_ENDIF))
+            .map_or(envelope.len(), |index| index + 1);
+
+        envelopes.push(&envelope[..end]);
+        remaining = &envelope[end..];
+    }
+
+    envelopes
+}
+
+/// Strips the version push of an envelope, so its message type is always the second instruction.
+/// Legacy envelopes, without version push, use the layout of version 1.
+fn envelope_message<'a, 'b>(envelope: &'a [Instruction<'b>]) -> ParseResult<&'a [Instruction<'b>]> {
+    match envelope.get(1) {
+        Some(Instruction::Op(op))
+            if (OP_PUSHNUM_1.to_u8()..=OP_PUSHNUM_16.to_u8()).contains(&op.to_u8()) =>
+        {
+            let version = op.to_u8() - OP_PUSHNUM_1.to_u8() + 1;
+            if version != types::VIA_INSCRIPTION_ENVELOPE_VERSION {
+                return Err(ParseError::UnsupportedEnvelopeVersion(version));
+            }
+            Ok(&envelope[1..])
+        }
+        _ => Ok(envelope),
+    }
+}
+
 #[instrument(skip(instructions), target = "bitcoin_indexer::parser")]
 fn find_via_inscription_protocol(instructions: &[Instruction]) -> Option<usize> {
     let position = instructions.iter().position(|instr| {
@@ -1195,9 +1378,64 @@ pub fn get_eth_address(common_fields: &CommonFields) -> Option<EVMAddress> {
 
 #[cfg(test)]
 mod tests {
-    use bitcoin::{consensus::encode::deserialize, hashes::hex::FromHex};
+    use bitcoin::{
+        absolute::LockTime,
+        consensus::encode::deserialize,
+        hashes::hex::FromHex,
+        script::PushBytes,
+        secp256k1::{Keypair, Message, Secp256k1},
+        taproot::LeafVersion,
+        transaction::Version,
+        OutPoint, Sequence, TxIn,
+    };
+    use zksync_types::protocol_version::{ProtocolVersionId, VersionPatch};
 
     use super::*;
+    use crate::{inscriber::script_builder::InscriptionData, types::InscriptionMessage};
+
+    /// Builds a reveal-like transaction spending the tapscript of `messages`.
+    fn build_inscription_transaction(
+        messages: &[InscriptionMessage],
+        network: Network,
+    ) -> Transaction {
+        let secp = Secp256k1::new();
+        let keypair = Keypair::new(&secp, &mut rand::thread_rng());
+        let internal_key = keypair.x_only_public_key().0;
+
+        let inscription_data =
+            InscriptionData::from_messages(messages, &secp, internal_key, network).unwrap();
+        let control_block = inscription_data
+            .taproot_spend_info
+            .control_block(&(
+                inscription_data.inscription_script.clone(),
+                LeafVersion::TapScript,
+            ))
+            .unwrap();
+        let signature = secp.sign_schnorr_no_aux_rand(&Message::from_digest([1; 32]), &keypair);
+
+        let mut inscription_witness = Witness::new();
+        inscription_witness.push(signature.as_ref());
+        inscription_witness.push(inscription_data.inscription_script.as_bytes());
+        inscription_witness.push(control_block.serialize());
+
+        let mut fee_payer_witness = Witness::new();
+        fee_payer_witness.push([0; 71]);
+        fee_payer_witness.push(keypair.public_key().serialize());
+
+        let input = |witness| TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness,
+        };
+
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![input(fee_payer_witness), input(inscription_witness)],
+            output: vec![],
+        }
+    }
 
     fn setup_test_transaction() -> Transaction {
         // TODO: Replace with a real transaction
@@ -1252,4 +1490,504 @@ mod tests {
             panic!("Expected SystemBootstrapping message");
         }
     }
+
+    #[test]
+    fn test_parse_multi_message_inscription() {
+        let network = Network::Regtest;
+        let mut parser = MessageParser::new(network);
+
+        let l1_batch_da_reference = L1BatchDAReferenceInput {
+            l1_batch_hash: H256::repeat_byte(1),
+            l1_batch_index: L1BatchNumber(2),
+            da_identifier: "celestia".to_string(),
+            blob_id: "batch_blob_id".to_string(),
+            prev_l1_batch_hash: H256::repeat_byte(2),
+        };
+        let proof_da_reference = ProofDAReferenceInput {
+            l1_batch_reveal_txid: Txid::from_slice(&[3; 32]).unwrap(),
+            da_identifier: "celestia".to_string(),
+            blob_id: "proof_blob_id".to_string(),
+            next_l1_batch_reveal_txids: vec![],
+        };
+
+        let tx = build_inscription_transaction(
+            &[
+                InscriptionMessage::L1BatchDAReference(l1_batch_da_reference.clone()),
+                InscriptionMessage::ProofDAReference(proof_da_reference.clone()),
+            ],
+            network,
+        );
+
+        let messages = parser.parse_system_transaction(&tx, 10, Some(&system_wallets()));
+        assert_eq!(messages.len(), 2);
+
+        let FullInscriptionMessage::L1BatchDAReference(l1_batch_message) = &messages[0] else {
+            panic!("Expected L1BatchDAReference message");
+        };
+        assert_eq!(l1_batch_message.input, l1_batch_da_reference);
+
+        let FullInscriptionMessage::ProofDAReference(proof_message) = &messages[1] else {
+            panic!("Expected ProofDAReference message");
+        };
+        assert_eq!(proof_message.input, proof_da_reference);
+        assert_eq!(proof_message.common.tx_id, l1_batch_message.common.tx_id);
+    }
+
+    #[test]
+    fn test_parse_proof_da_reference_of_range() {
+        let network = Network::Regtest;
+        let mut parser = MessageParser::new(network);
+
+        let proof_da_reference = ProofDAReferenceInput {
+            l1_batch_reveal_txid: Txid::from_slice(&[3; 32]).unwrap(),
+            da_identifier: "celestia".to_string(),
+            blob_id: "proof_blob_id".to_string(),
+            next_l1_batch_reveal_txids: vec![
+                Txid::from_slice(&[4; 32]).unwrap(),
+                Txid::from_slice(&[5; 32]).unwrap(),
+            ],
+        };
+
+        let tx = build_inscription_transaction(
+            &[InscriptionMessage::ProofDAReference(
+                proof_da_reference.clone(),
+            )],
+            network,
+        );
+
+        let messages = parser.parse_system_transaction(&tx, 10, Some(&system_wallets()));
+        let [FullInscriptionMessage::ProofDAReference(proof_message)] = messages.as_slice() else {
+            panic!("Expected ProofDAReference message");
+        };
+        assert_eq!(proof_message.input, proof_da_reference);
+        assert_eq!(proof_message.input.l1_batch_reveal_txids().count(), 3);
+    }
+
+    #[test]
+    fn test_parse_da_references_with_split_blob_id() {
+        let network = Network::Regtest;
+        let mut parser = MessageParser::new(network);
+
+        // Larger than a single script push.
+        let blob_id = "ab".repeat(800);
+        let l1_batch_da_reference = L1BatchDAReferenceInput {
+            l1_batch_hash: H256::repeat_byte(1),
+            l1_batch_index: L1BatchNumber(2),
+            da_identifier: "celestia+bitcoin".to_string(),
+            blob_id: blob_id.clone(),
+            prev_l1_batch_hash: H256::repeat_byte(2),
+        };
+        let proof_da_reference = ProofDAReferenceInput {
+            l1_batch_reveal_txid: Txid::from_slice(&[3; 32]).unwrap(),
+            da_identifier: "celestia+bitcoin".to_string(),
+            blob_id,
+            next_l1_batch_reveal_txids: vec![Txid::from_slice(&[4; 32]).unwrap()],
+        };
+
+        let tx = build_inscription_transaction(
+            &[
+                InscriptionMessage::L1BatchDAReference(l1_batch_da_reference.clone()),
+                InscriptionMessage::ProofDAReference(proof_da_reference.clone()),
+            ],
+            network,
+        );
+
+        let messages = parser.parse_system_transaction(&tx, 10, Some(&system_wallets()));
+        assert_eq!(messages.len(), 2);
+
+        let FullInscriptionMessage::L1BatchDAReference(l1_batch_message) = &messages[0] else {
+            panic!("Expected L1BatchDAReference message");
+        };
+        assert_eq!(l1_batch_message.input, l1_batch_da_reference);
+
+        let FullInscriptionMessage::ProofDAReference(proof_message) = &messages[1] else {
+            panic!("Expected ProofDAReference message");
+        };
+        assert_eq!(proof_message.input, proof_da_reference);
+    }
+
+    #[test]
+    fn test_parse_da_blob_chunk() {
+        let network = Network::Regtest;
+        let mut parser = MessageParser::new(network);
+
+        // Larger than a single script push.
+        let da_blob_chunk = DABlobChunkInput {
+            blob_hash: H256::repeat_byte(6),
+            chunk_index: 2,
+            data: (0..2000).map(|i| i as u8).collect(),
+        };
+
+        let tx = build_inscription_transaction(
+            &[InscriptionMessage::DABlobChunk(da_blob_chunk.clone())],
+            network,
+        );
+
+        let messages = parser.parse_system_transaction(&tx, 10, None);
+        let [FullInscriptionMessage::DABlobChunk(chunk_message)] = messages.as_slice() else {
+            panic!("Expected DABlobChunk message");
+        };
+        assert_eq!(chunk_message.input, da_blob_chunk);
+    }
+
+    #[test]
+    fn test_parse_single_message_inscription() {
+        let network = Network::Regtest;
+        let mut parser = MessageParser::new(network);
+
+        let attestation = ValidatorAttestationInput {
+            reference_txid: Txid::from_slice(&[4; 32]).unwrap(),
+            attestation: Vote::Ok,
+        };
+        let tx = build_inscription_transaction(
+            &[InscriptionMessage::ValidatorAttestation(
+                attestation.clone(),
+            )],
+            network,
+        );
+
+        let messages = parser.parse_system_transaction(&tx, 10, Some(&system_wallets()));
+        assert_eq!(messages.len(), 1);
+
+        let FullInscriptionMessage::ValidatorAttestation(message) = &messages[0] else {
+            panic!("Expected ValidatorAttestation message");
+        };
+        assert_eq!(message.input.reference_txid, attestation.reference_txid);
+        assert_eq!(message.input.attestation, attestation.attestation);
+    }
+
+    #[test]
+    fn test_parse_inscription_envelope_version() {
+        let mut parser = MessageParser::new(Network::Regtest);
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        };
+        let common_fields = op_return_common_fields(&tx, 0).unwrap();
+        let reference_txid = Txid::from_slice(&[4; 32]).unwrap();
+        let parse = |parser: &mut MessageParser, script: &ScriptBuf| {
+            let instructions: Vec<_> = script.instructions().filter_map(Result::ok).collect();
+            let envelopes = find_via_inscription_envelopes(&instructions);
+            assert_eq!(envelopes.len(), 1);
+            envelope_message(envelopes[0]).and_then(|instructions| {
+                parser.parse_system_message(&tx, instructions, &common_fields, None)
+            })
+        };
+
+        // Envelopes built by the inscriber carry the current version.
+        let secp = Secp256k1::new();
+        let keypair = Keypair::new(&secp, &mut rand::thread_rng());
+        let inscription_data = InscriptionData::new(
+            &InscriptionMessage::ValidatorAttestation(ValidatorAttestationInput {
+                reference_txid,
+                attestation: Vote::Ok,
+            }),
+            &secp,
+            keypair.x_only_public_key().0,
+            Network::Regtest,
+        )
+        .unwrap();
+        let script = &inscription_data.inscription_script;
+        let instructions: Vec<_> = script.instructions().filter_map(Result::ok).collect();
+        let via_index = find_via_inscription_protocol(&instructions).unwrap();
+        assert_eq!(
+            instructions[via_index + 1],
+            Instruction::Op(OP_PUSHNUM_1),
+            "the version must follow the protocol identifier"
+        );
+        let Ok(Some(FullInscriptionMessage::ValidatorAttestation(message))) =
+            parse(&mut parser, script)
+        else {
+            panic!("Expected ValidatorAttestation message");
+        };
+        assert_eq!(message.input.reference_txid, reference_txid);
+
+        // Legacy envelopes have no version push.
+        let push = |data: &'static [u8]| -> &'static PushBytes { data.try_into().unwrap() };
+        let envelope = |version: Option<i64>| {
+            let script = bitcoin::script::Builder::new()
+                .push_opcode(bitcoin::opcodes::OP_FALSE)
+                .push_opcode(bitcoin::opcodes::all::OP_IF)
+                .push_slice(push(types::VIA_INSCRIPTION_PROTOCOL.as_bytes()));
+            let script = match version {
+                Some(version) => script.push_int(version),
+                None => script,
+            };
+            script
+                .push_slice(&*types::VALIDATOR_ATTESTATION_MSG)
+                .push_slice(push(&[4; 32]))
+                .push_opcode(bitcoin::opcodes::OP_TRUE)
+                .push_opcode(OP_ENDIF)
+                .into_script()
+        };
+        let Ok(Some(FullInscriptionMessage::ValidatorAttestation(message))) =
+            parse(&mut parser, &envelope(None))
+        else {
+            panic!("Expected legacy ValidatorAttestation message");
+        };
+        assert_eq!(message.input.reference_txid, reference_txid);
+        assert_eq!(message.input.attestation, Vote::Ok);
+
+        assert!(matches!(
+            parse(&mut parser, &envelope(Some(2))),
+            Err(ParseError::UnsupportedEnvelopeVersion(2))
+        ));
+    }
+
+    #[test]
+    fn test_parse_system_contract_upgrade_proposal() {
+        let network = Network::Regtest;
+        let mut parser = MessageParser::new(network);
+
+        let proposal = SystemContractUpgradeProposalInput {
+            version: ProtocolSemanticVersion::new(ProtocolVersionId::latest(), VersionPatch(1)),
+            bootloader_code_hash: H256::repeat_byte(1),
+            default_account_code_hash: H256::repeat_byte(2),
+            recursion_scheduler_level_vk_hash: H256::repeat_byte(3),
+            system_contracts: vec![
+                (EVMAddress::repeat_byte(4), H256::repeat_byte(5)),
+                (EVMAddress::repeat_byte(6), H256::repeat_byte(7)),
+                (EVMAddress::repeat_byte(8), H256::repeat_byte(9)),
+            ],
+        };
+        let tx = build_inscription_transaction(
+            &[InscriptionMessage::SystemContractUpgradeProposal(
+                proposal.clone(),
+            )],
+            network,
+        );
+
+        let messages = parser.parse_system_transaction(&tx, 10, Some(&system_wallets()));
+        assert_eq!(messages.len(), 1);
+
+        let FullInscriptionMessage::SystemContractUpgradeProposal(message) = &messages[0] else {
+            panic!("Expected SystemContractUpgradeProposal message");
+        };
+        assert_eq!(message.input, proposal);
+    }
+
+    #[test]
+    fn test_parse_malformed_inscription_pushes() {
+        let parser = MessageParser::new(Network::Regtest);
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        };
+        let common_fields = op_return_common_fields(&tx, 0).unwrap();
+        let push = |data: &'static [u8]| -> &'static PushBytes { data.try_into().unwrap() };
+
+        let script = bitcoin::script::Builder::new()
+            .push_slice(push(types::VIA_INSCRIPTION_PROTOCOL.as_bytes()))
+            .push_slice(&*types::L1_BATCH_DA_REFERENCE_MSG)
+            .push_slice(push(&[1; 31]))
+            .push_slice(push(&[0, 0, 0, 1]))
+            .push_slice(push(b"celestia"))
+            .push_slice(push(b"blob"))
+            .push_slice(push(&[2; 32]))
+            .into_script();
+        let instructions: Vec<_> = script.instructions().filter_map(Result::ok).collect();
+        assert!(matches!(
+            parser.parse_l1_batch_da_reference(&instructions, &common_fields),
+            Err(ParseError::InvalidField {
+                field: "l1_batch_hash",
+                ..
+            })
+        ));
+
+        let script = bitcoin::script::Builder::new()
+            .push_slice(push(types::VIA_INSCRIPTION_PROTOCOL.as_bytes()))
+            .push_slice(&*types::L1_TO_L2_MSG)
+            .push_slice(push(&[1; 19]))
+            .push_slice(push(&[2; 20]))
+            .push_slice(push(b""))
+            .into_script();
+        let instructions: Vec<_> = script.instructions().filter_map(Result::ok).collect();
+        assert!(matches!(
+            parser.parse_l1_to_l2_message(&tx, &instructions, &common_fields, &system_wallets()),
+            Err(ParseError::InvalidField {
+                field: "receiver_l2_address",
+                ..
+            })
+        ));
+
+        assert!(matches!(
+            parser.parse_system_contract_upgrade_message(&instructions[..2], &common_fields),
+            Err(ParseError::InsufficientInstructions { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_malformed_op_return() {
+        let wallets = system_wallets();
+        let mut parser = MessageParser::new(Network::Regtest);
+
+        let op_return_tx = |data: &[u8]| {
+            let data: &PushBytes = data.try_into().unwrap();
+            TransactionWithMetadata::new(
+                Transaction {
+                    version: Version::TWO,
+                    lock_time: LockTime::ZERO,
+                    input: vec![],
+                    output: vec![
+                        TxOut {
+                            value: Amount::from_sat(1000),
+                            script_pubkey: wallets.bridge.script_pubkey(),
+                        },
+                        TxOut {
+                            value: Amount::ZERO,
+                            script_pubkey: ScriptBuf::new_op_return(data),
+                        },
+                    ],
+                },
+                0,
+            )
+        };
+
+        // A deposit receiver shorter than an L2 address is indexed as the zero address, so the
+        // deposit is refunded.
+        let mut tx = op_return_tx(&[1; 19]);
+        let messages = parser.parse_bridge_transaction(&mut tx, 0, &wallets);
+        let [FullInscriptionMessage::L1ToL2Message(deposit)] = messages.as_slice() else {
+            panic!("Expected L1ToL2Message message");
+        };
+        assert_eq!(deposit.input.receiver_l2_address, EVMAddress::zero());
+        let mut tx = op_return_tx(&[1; 20]);
+        assert_eq!(
+            parser.parse_bridge_transaction(&mut tx, 0, &wallets).len(),
+            1
+        );
+
+        // Prefixes without payload.
+        for prefix in [OP_RETURN_WITHDRAW_PREFIX, OP_RETURN_REFUND_PREFIX] {
+            let mut tx = op_return_tx(prefix);
+            assert!(parser
+                .parse_bridge_transaction(&mut tx, 0, &wallets)
+                .is_empty());
+        }
+        for prefix in [
+            OP_RETURN_UPGRADE_PROTOCOL_PREFIX,
+            OP_RETURN_UPDATE_SEQUENCER_PREFIX,
+            OP_RETURN_UPDATE_BRIDGE_PREFIX,
+            OP_RETURN_UPDATE_GOVERNANCE_PREFIX,
+        ] {
+            let tx = op_return_tx(prefix);
+            assert!(parser
+                .parse_protocol_upgrade_transactions(&tx, 0)
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn test_parse_op_return_refund() {
+        let wallets = system_wallets();
+        let mut parser = MessageParser::new(Network::Regtest);
+
+        let deposit_tx_id = Txid::from_slice(&[5; 32]).unwrap();
+        let sender = Address::from_str("bcrt1qw2mvkvm6alfhe86yf328kgvr7mupdx4vln7kpv")
+            .unwrap()
+            .assume_checked();
+        let op_return_data: PushBytesBuf = [
+            b"VIA_PROTOCOL:REFUND:".as_slice(),
+            deposit_tx_id.as_byte_array(),
+            &0u64.to_le_bytes(),
+        ]
+        .concat()
+        .try_into()
+        .unwrap();
+
+        let mut tx = TransactionWithMetadata::new(
+            Transaction {
+                version: Version::TWO,
+                lock_time: LockTime::ZERO,
+                input: vec![],
+                output: vec![
+                    TxOut {
+                        value: Amount::from_sat(9000),
+                        script_pubkey: sender.script_pubkey(),
+                    },
+                    TxOut {
+                        value: Amount::ZERO,
+                        script_pubkey: ScriptBuf::new_op_return(op_return_data),
+                    },
+                    TxOut {
+                        value: Amount::from_sat(1000),
+                        script_pubkey: wallets.bridge.script_pubkey(),
+                    },
+                ],
+            },
+            0,
+        );
+
+        let messages = parser.parse_bridge_transaction(&mut tx, 0, &wallets);
+        let [FullInscriptionMessage::BridgeRefund(refund)] = messages.as_slice() else {
+            panic!("Expected a single BridgeRefund message");
+        };
+        assert_eq!(refund.input.deposit_tx_id, deposit_tx_id);
+        assert_eq!(refund.input.refunds, vec![(sender.to_string(), 9000)]);
+    }
+
+    #[test]
+    fn test_parse_op_return_withdrawal() {
+        let wallets = system_wallets();
+        let mut parser = MessageParser::new(Network::Regtest);
+
+        let proof_tx_id = Txid::from_slice(&[6; 32]).unwrap();
+        let receiver = Address::from_str("bcrt1qw2mvkvm6alfhe86yf328kgvr7mupdx4vln7kpv")
+            .unwrap()
+            .assume_checked();
+        let withdrawal_tx = |l1_batch_number: Option<u32>| {
+            let op_return_data: PushBytesBuf = [
+                b"VIA_PROTOCOL:WITHDRAWAL:".as_slice(),
+                proof_tx_id.as_byte_array(),
+                &l1_batch_number
+                    .map(|number| number.to_le_bytes().to_vec())
+                    .unwrap_or_default(),
+                &2u64.to_le_bytes(),
+            ]
+            .concat()
+            .try_into()
+            .unwrap();
+            TransactionWithMetadata::new(
+                Transaction {
+                    version: Version::TWO,
+                    lock_time: LockTime::ZERO,
+                    input: vec![],
+                    output: vec![
+                        TxOut {
+                            value: Amount::from_sat(9000),
+                            script_pubkey: receiver.script_pubkey(),
+                        },
+                        TxOut {
+                            value: Amount::ZERO,
+                            script_pubkey: ScriptBuf::new_op_return(op_return_data),
+                        },
+                        TxOut {
+                            value: Amount::from_sat(1000),
+                            script_pubkey: wallets.bridge.script_pubkey(),
+                        },
+                    ],
+                },
+                0,
+            )
+        };
+
+        // Withdrawals of a proof covering a range of L1 batches carry their L1 batch number.
+        for l1_batch_number in [None, Some(7)] {
+            let mut tx = withdrawal_tx(l1_batch_number);
+            let messages = parser.parse_bridge_transaction(&mut tx, 0, &wallets);
+            let [FullInscriptionMessage::BridgeWithdrawal(withdrawal)] = messages.as_slice() else {
+                panic!("Expected a single BridgeWithdrawal message");
+            };
+            assert_eq!(
+                withdrawal.input.l1_batch_proof_reveal_tx_id,
+                proof_tx_id.as_byte_array().to_vec()
+            );
+            assert_eq!(withdrawal.input.l1_batch_number, l1_batch_number);
+            assert_eq!(withdrawal.input.index_withdrawal, 2);
+        }
+    }
 }
@@ -46,3 +46,12 @@ pub struct InscriberInfo {
     pub reveal_tx_output_info: RevealTxOutputRes,
     pub commit_tx_input_info: CommitTxInputRes,
 }
+
+#[derive(Debug)]
+pub struct ReplacementInfo {
+    pub final_reveal_tx: FinalTx,
+    pub replaced_reveal_txid: Txid,
+    pub replaced_reveal_fee: Amount,
+    pub reveal_fee: Amount,
+    pub reveal_fee_rate: u64,
+}
@@ -20,23 +20,25 @@ use crate::{
     inscriber::{
         fee::InscriberFeeCalculator,
         internal_type::{
-            CommitTxInputRes, CommitTxOutputRes, FinalTx, InscriberInfo, RevealTxInputRes,
-            RevealTxOutputRes,
+            CommitTxInputRes, CommitTxOutputRes, FinalTx, InscriberInfo, ReplacementInfo,
+            RevealTxInputRes, RevealTxOutputRes,
         },
         script_builder::InscriptionData,
     },
     signer::KeyManager,
     traits::{BitcoinOps, BitcoinSigner},
-    types::{InscriberContext, InscriptionMessage, Recipient},
+    types::{
+        AncestorsPackage, BitcoinError, FeeRateTarget, InscriberContext, InscriptionMessage,
+        InscriptionRequest, Recipient,
+    },
 };
 
 mod fee;
 mod internal_type;
-mod script_builder;
+pub(crate) mod script_builder;
 pub mod test_utils;
 
 const CTX_REQUIRED_CONFIRMATIONS: u32 = 1;
-const FEE_RATE_CONF_TARGET: u16 = 1;
 
 const COMMIT_TX_CHANGE_OUTPUT_INDEX: u32 = 0;
 const COMMIT_TX_TAPSCRIPT_OUTPUT_INDEX: u32 = 1;
@@ -63,6 +65,10 @@ const BROADCAST_RETRY_COUNT: u32 = 3;
 // https://bitcoin.stackexchange.com/questions/10986/what-is-meant-by-bitcoin-dust
 // https://bitcointalk.org/index.php?topic=5453107.msg62262343#msg62262343
 const P2TR_DUST_LIMIT: Amount = Amount::from_sat(330);
+const P2WPKH_DUST_LIMIT: Amount = Amount::from_sat(294);
+
+/// The incremental relay fee rate (sat/vB) a replacement has to pay on top of the replaced fee (BIP-125).
+const INCREMENTAL_RELAY_FEE_RATE: u64 = 1;
 
 #[derive(Debug)]
 pub struct Inscriber {
@@ -92,6 +98,23 @@ impl Inscriber {
         })
     }
 
+    /// Creates a new Inscriber signing with `signer`, e.g. a keystore or a remote signer.
+    #[instrument(skip(client, signer), target = "bitcoin_inscriber")]
+    pub async fn with_signer(
+        client: Arc<BitcoinClient>,
+        signer: Arc<dyn BitcoinSigner>,
+        persisted_ctx: Option<InscriberContext>,
+    ) -> Result<Self> {
+        info!("Creating new Inscriber");
+        let context = persisted_ctx.unwrap_or_default();
+
+        Ok(Self {
+            client,
+            signer,
+            context,
+        })
+    }
+
     #[instrument(skip(self), target = "bitcoin_inscriber")]
     pub async fn get_balance(&self) -> Result<u128> {
         debug!("Getting balance");
@@ -106,6 +129,17 @@ impl Inscriber {
         &mut self,
         input: &InscriptionMessage,
         recipient: Option<Recipient>,
+    ) -> Result<InscriberInfo> {
+        self.prepare_inscribe_messages(std::slice::from_ref(input), recipient)
+            .await
+    }
+
+    /// Prepares a single commit/reveal pair carrying all the `inputs` messages.
+    #[instrument(skip(self, inputs), target = "bitcoin_inscriber")]
+    pub async fn prepare_inscribe_messages(
+        &mut self,
+        inputs: &[InscriptionMessage],
+        recipient: Option<Recipient>,
     ) -> Result<InscriberInfo> {
         self.sync_context_with_blockchain().await?;
 
@@ -113,7 +147,9 @@ impl Inscriber {
         let internal_key = self.signer.get_internal_key()?;
         let network = self.client.get_network();
 
-        let inscription_data = InscriptionData::new(input, secp_ref, internal_key, network)?;
+        let inscription_data =
+            InscriptionData::from_messages(inputs, secp_ref, internal_key, network)?;
+        let fee_rate_target = FeeRateTarget::for_messages(inputs);
 
         let commit_tx_input_info = self.prepare_commit_tx_input().await?;
 
@@ -121,10 +157,13 @@ impl Inscriber {
             .prepare_commit_tx_output(
                 &commit_tx_input_info,
                 inscription_data.script_pubkey.clone(),
+                fee_rate_target,
             )
             .await?;
 
-        let final_commit_tx = self.sign_commit_tx(&commit_tx_input_info, &commit_tx_output_info)?;
+        let final_commit_tx = self
+            .sign_commit_tx(&commit_tx_input_info, &commit_tx_output_info)
+            .await?;
 
         let reveal_tx_input_info = self.prepare_reveal_tx_input(
             &commit_tx_output_info,
@@ -138,14 +177,17 @@ impl Inscriber {
                 &inscription_data,
                 recipient,
                 commit_tx_output_info.commit_tx_fee,
+                fee_rate_target,
             )
             .await?;
 
-        let final_reveal_tx = self.sign_reveal_tx(
-            &reveal_tx_input_info,
-            &reveal_tx_output_info,
-            &inscription_data,
-        )?;
+        let final_reveal_tx = self
+            .sign_reveal_tx(
+                &reveal_tx_input_info,
+                &reveal_tx_output_info,
+                &inscription_data,
+            )
+            .await?;
 
         Ok(InscriberInfo {
             final_commit_tx,
@@ -161,11 +203,34 @@ impl Inscriber {
         &mut self,
         input: InscriptionMessage,
         recipient: Option<Recipient>,
+    ) -> Result<InscriberInfo> {
+        self.inscribe_messages_with_recipient(vec![input], recipient)
+            .await
+    }
+
+    #[instrument(skip(self, input), target = "bitcoin_inscriber")]
+    pub async fn inscribe(&mut self, input: InscriptionMessage) -> Result<InscriberInfo> {
+        self.inscribe_with_recipient(input, None).await
+    }
+
+    /// Inscribes all the `inputs` messages with a single commit/reveal pair.
+    #[instrument(skip(self, inputs), target = "bitcoin_inscriber")]
+    pub async fn inscribe_messages(
+        &mut self,
+        inputs: Vec<InscriptionMessage>,
+    ) -> Result<InscriberInfo> {
+        self.inscribe_messages_with_recipient(inputs, None).await
+    }
+
+    async fn inscribe_messages_with_recipient(
+        &mut self,
+        inputs: Vec<InscriptionMessage>,
+        recipient: Option<Recipient>,
     ) -> Result<InscriberInfo> {
         info!("Starting inscription process");
 
         let inscriber_info = self
-            .prepare_inscribe(&input, recipient)
+            .prepare_inscribe_messages(&inputs, recipient)
             .await
             .with_context(|| "Error prepare inscriber infos")?;
 
@@ -175,17 +240,12 @@ impl Inscriber {
         )
         .await?;
 
-        self.insert_inscription_to_context(input, inscriber_info.borrow())?;
+        self.insert_inscription_to_context(inputs, inscriber_info.borrow())?;
 
         info!("Inscription process completed successfully");
         Ok(inscriber_info)
     }
 
-    #[instrument(skip(self, input), target = "bitcoin_inscriber")]
-    pub async fn inscribe(&mut self, input: InscriptionMessage) -> Result<InscriberInfo> {
-        self.inscribe_with_recipient(input, None).await
-    }
-
     #[instrument(skip(self), target = "bitcoin_inscriber")]
     async fn sync_context_with_blockchain(&mut self) -> Result<()> {
         debug!("Syncing context with blockchain");
@@ -195,11 +255,7 @@ impl Inscriber {
         }
 
         while let Some(inscription) = self.context.fifo_queue.pop_front() {
-            let txid_ref = &inscription.fee_payer_ctx.fee_payer_utxo_txid;
-            let res = self
-                .client
-                .check_tx_confirmation(txid_ref, CTX_REQUIRED_CONFIRMATIONS)
-                .await?;
+            let res = self.is_inscription_confirmed(&inscription).await?;
 
             if !res {
                 debug!("Transaction not confirmed, adding back to queue");
@@ -212,6 +268,38 @@ impl Inscriber {
         Ok(())
     }
 
+    #[instrument(skip(self, inscription), target = "bitcoin_inscriber")]
+    async fn is_inscription_confirmed(&self, inscription: &InscriptionRequest) -> Result<bool> {
+        let txid_ref = &inscription.fee_payer_ctx.fee_payer_utxo_txid;
+        if self
+            .client
+            .check_tx_confirmation(txid_ref, CTX_REQUIRED_CONFIRMATIONS)
+            .await?
+        {
+            return Ok(true);
+        }
+
+        // A replaced reveal tx can still be mined if the replacement didn't propagate in time.
+        for replaced_txid in inscription.replaced_reveal_txids.iter().rev() {
+            let is_confirmed = match self
+                .client
+                .check_tx_confirmation(replaced_txid, CTX_REQUIRED_CONFIRMATIONS)
+                .await
+            {
+                Ok(is_confirmed) => is_confirmed,
+                // The node drops the replaced reveal txs from its mempool.
+                Err(BitcoinError::NotFound(_)) => false,
+                Err(err) => return Err(err.into()),
+            };
+            if is_confirmed {
+                warn!("Replaced reveal transaction {replaced_txid} was confirmed instead of {txid_ref}");
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
     #[instrument(skip(self), target = "bitcoin_inscriber")]
     async fn prepare_commit_tx_input(&self) -> Result<CommitTxInputRes> {
         debug!("Preparing commit transaction input");
@@ -342,6 +430,7 @@ impl Inscriber {
         &self,
         tx_input_data: &CommitTxInputRes,
         inscription_pubkey: ScriptBuf,
+        fee_rate_target: FeeRateTarget,
     ) -> Result<CommitTxOutputRes> {
         debug!("Preparing commit transaction output");
         let inscription_commitment_output = TxOut {
@@ -349,7 +438,7 @@ impl Inscriber {
             script_pubkey: inscription_pubkey,
         };
 
-        let fee_rate = self.get_fee_rate().await?;
+        let fee_rate = self.get_fee_rate(fee_rate_target).await?;
 
         let mut fee_amount = InscriberFeeCalculator::estimate_fee(
             tx_input_data.inputs_count,
@@ -391,15 +480,15 @@ impl Inscriber {
     }
 
     #[instrument(skip(self), target = "bitcoin_inscriber")]
-    async fn get_fee_rate(&self) -> Result<u64> {
+    async fn get_fee_rate(&self, target: FeeRateTarget) -> Result<u64> {
         debug!("Getting fee rate");
-        let res = self.client.get_fee_rate(FEE_RATE_CONF_TARGET).await?;
+        let res = self.client.get_fee_rate(target.conf_target()).await?;
         debug!("Fee rate obtained: {}", res);
         Ok(std::cmp::max(res, 1))
     }
 
     #[instrument(skip(self, input, output), target = "bitcoin_inscriber")]
-    fn sign_commit_tx(
+    async fn sign_commit_tx(
         &self,
         input: &CommitTxInputRes,
         output: &CommitTxOutputRes,
@@ -437,7 +526,7 @@ impl Inscriber {
 
             // Sign the sighash using the signer
             let msg = Message::from(sighash);
-            let signature = self.signer.sign_ecdsa(msg)?;
+            let signature = self.signer.sign_ecdsa(msg).await?;
 
             // Update the witness stack.
             let signature = bitcoin::ecdsa::Signature {
@@ -559,9 +648,10 @@ impl Inscriber {
         inscription_data: &InscriptionData,
         recipient: Option<Recipient>,
         commit_tx_fee: Amount,
+        fee_rate_target: FeeRateTarget,
     ) -> Result<RevealTxOutputRes> {
         debug!("Preparing reveal transaction output");
-        let fee_rate = self.get_fee_rate().await?;
+        let fee_rate = self.get_fee_rate(fee_rate_target).await?;
         let pending_tx_in_context = self.context.fifo_queue.len();
 
         let mut reveal_tx_p2wpkh_output_count = REVEAL_TX_P2WPKH_OUTPUT_COUNT;
@@ -632,7 +722,7 @@ impl Inscriber {
         skip(self, input, output, inscription_data),
         target = "bitcoin_inscriber"
     )]
-    fn sign_reveal_tx(
+    async fn sign_reveal_tx(
         &self,
         input: &RevealTxInputRes,
         output: &RevealTxOutputRes,
@@ -670,7 +760,7 @@ impl Inscriber {
 
         // Sign the fee payer sighash using the signer
         let fee_payer_msg = Message::from(fee_payer_input_sighash);
-        let fee_payer_signature = self.signer.sign_ecdsa(fee_payer_msg)?;
+        let fee_payer_signature = self.signer.sign_ecdsa(fee_payer_msg).await?;
 
         // Update the witness stack.
 
@@ -705,7 +795,7 @@ impl Inscriber {
 
         // Sign the tapscript reveal sighash using the signer
         let msg = Message::from_digest(reveal_input_sighash.to_byte_array());
-        let reveal_input_signature = self.signer.sign_schnorr(msg)?;
+        let reveal_input_signature = self.signer.sign_schnorr(msg).await?;
 
         // Update the witness stack.
 
@@ -762,12 +852,17 @@ impl Inscriber {
     #[instrument(skip(self, req, inscriber_info,), target = "bitcoin_inscriber")]
     fn insert_inscription_to_context(
         &mut self,
-        req: InscriptionMessage,
+        mut req: Vec<InscriptionMessage>,
         inscriber_info: &InscriberInfo,
     ) -> Result<()> {
         debug!("Inserting inscription to context");
+        if req.is_empty() {
+            anyhow::bail!("An inscription requires at least one message");
+        }
+        let message = req.remove(0);
         let inscription_request = crate::types::InscriptionRequest {
-            message: req,
+            message,
+            packed_messages: req,
             inscriber_output: crate::types::InscriberOutput {
                 commit_txid: inscriber_info.final_commit_tx.txid,
                 commit_raw_tx: inscriber_info.final_commit_tx.tx.raw_hex().to_string(),
@@ -788,6 +883,7 @@ impl Inscriber {
             commit_tx_input: crate::types::CommitTxInput {
                 spent_utxo: inscriber_info.commit_tx_input_info.commit_tx_inputs.clone(),
             },
+            replaced_reveal_txids: vec![],
         };
 
         self.context.fifo_queue.push_back(inscription_request);
@@ -796,6 +892,205 @@ impl Inscriber {
         Ok(())
     }
 
+    /// Replaces the reveal tx of a pending inscription with one paying `fee_rate` for the whole
+    /// commit/reveal package. The commit tx is left untouched: the replacement follows the RBF
+    /// rules for the reveal and bumps the commit through CPFP, so the UTXOs spent by the commit
+    /// stay valid for the rest of the context.
+    ///
+    /// `inputs` are the messages of the inscription and `paid_fees` is the total fee currently
+    /// paid by the commit and reveal txs. Only a reveal without descendants (the newest pending
+    /// inscription) can be replaced, it pays `fee_rate` for its unconfirmed `ancestors` too.
+    #[instrument(skip(self, inputs, commit_tx, reveal_tx), target = "bitcoin_inscriber")]
+    pub async fn bump_inscription_fee(
+        &mut self,
+        inputs: &[InscriptionMessage],
+        commit_tx: &Transaction,
+        reveal_tx: &Transaction,
+        paid_fees: Amount,
+        ancestors: AncestorsPackage,
+        fee_rate: u64,
+    ) -> Result<ReplacementInfo> {
+        info!("Bumping inscription fee");
+        let replaced_reveal_txid = reveal_tx.compute_txid();
+
+        if let Some(position) = self.context_position(&replaced_reveal_txid) {
+            if position != self.context.fifo_queue.len() - 1 {
+                anyhow::bail!(
+                    "Reveal transaction {replaced_reveal_txid} has pending descendants and can't be replaced"
+                );
+            }
+        }
+
+        let secp_ref = &self.signer.get_secp_ref();
+        let internal_key = self.signer.get_internal_key()?;
+        let network = self.client.get_network();
+
+        let inscription_data =
+            InscriptionData::from_messages(inputs, secp_ref, internal_key, network)?;
+
+        let commit_output = |index: u32| {
+            commit_tx
+                .output
+                .get(index as usize)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Commit transaction output {index} not found"))
+        };
+
+        let commit_tx_output_info = CommitTxOutputRes {
+            commit_tx_change_output: commit_output(COMMIT_TX_CHANGE_OUTPUT_INDEX)?,
+            commit_tx_tapscript_output: commit_output(COMMIT_TX_TAPSCRIPT_OUTPUT_INDEX)?,
+            commit_tx_fee_rate: 0,
+            commit_tx_fee: Amount::ZERO,
+        };
+
+        if commit_tx_output_info
+            .commit_tx_tapscript_output
+            .script_pubkey
+            != inscription_data.script_pubkey
+        {
+            anyhow::bail!("Inscription message doesn't match the commit transaction");
+        }
+
+        let final_commit_tx = FinalTx {
+            tx: commit_tx.clone(),
+            txid: commit_tx.compute_txid(),
+        };
+
+        let reveal_tx_input_info = self.prepare_reveal_tx_input(
+            &commit_tx_output_info,
+            &final_commit_tx,
+            &inscription_data,
+        )?;
+
+        let replaced_reveal_fee = reveal_tx_input_info
+            .unlock_value
+            .checked_sub(reveal_tx.output.iter().map(|output| output.value).sum())
+            .ok_or_else(|| anyhow::anyhow!("Reveal transaction spends more than its inputs"))?;
+
+        // The reveal pays for the commit and the ancestors, so the package fee rate is what the
+        // miners look at.
+        let commit_fee = paid_fees
+            .checked_sub(replaced_reveal_fee)
+            .unwrap_or(Amount::ZERO);
+        let package_vsize = ancestors.vsize + (commit_tx.vsize() + reveal_tx.vsize()) as u64;
+        let package_fee = Amount::from_sat(fee_rate * package_vsize);
+        let min_replacement_fee = replaced_reveal_fee
+            + Amount::from_sat(INCREMENTAL_RELAY_FEE_RATE * reveal_tx.vsize() as u64);
+
+        let reveal_fee = std::cmp::max(
+            package_fee
+                .checked_sub(commit_fee + ancestors.fees)
+                .unwrap_or(Amount::ZERO),
+            min_replacement_fee,
+        );
+
+        // Keep the recipient output (if any) of the replaced reveal tx.
+        let recipient_tx_output = reveal_tx.output.get(1).cloned();
+        let recipient_amount = recipient_tx_output
+            .as_ref()
+            .map_or(Amount::ZERO, |output| output.value);
+
+        let reveal_change_amount = reveal_tx_input_info
+            .unlock_value
+            .checked_sub(reveal_fee + recipient_amount)
+            .filter(|amount| *amount >= P2WPKH_DUST_LIMIT)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Required Amount:{:?} Spendable Amount: {:?} ",
+                    reveal_fee + recipient_amount + P2WPKH_DUST_LIMIT,
+                    reveal_tx_input_info.unlock_value
+                )
+            })?;
+
+        let reveal_tx_output_info = RevealTxOutputRes {
+            reveal_tx_change_output: TxOut {
+                value: reveal_change_amount,
+                script_pubkey: self.signer.get_p2wpkh_script_pubkey().clone(),
+            },
+            recipient_tx_output,
+            reveal_fee_rate: fee_rate,
+            _reveal_fee: reveal_fee,
+        };
+
+        let final_reveal_tx = self
+            .sign_reveal_tx(
+                &reveal_tx_input_info,
+                &reveal_tx_output_info,
+                &inscription_data,
+            )
+            .await?;
+
+        // The commit tx may have been evicted from the mempool, in that case the replacement
+        // can't be accepted without it. Rejections of an already known commit tx are expected.
+        let commit_tx_hex = final_commit_tx.tx.raw_hex().to_string();
+        if let Err(err) = self
+            .client
+            .broadcast_signed_transaction(&commit_tx_hex)
+            .await
+        {
+            debug!(
+                "Commit transaction {} not re-broadcast: {err}",
+                final_commit_tx.txid
+            );
+        }
+
+        let reveal_tx_hex = final_reveal_tx.tx.raw_hex().to_string();
+        self.client
+            .broadcast_signed_transaction(&reveal_tx_hex)
+            .await?;
+
+        info!(
+            "Reveal transaction {replaced_reveal_txid} replaced by {}",
+            final_reveal_tx.txid
+        );
+
+        self.replace_reveal_in_context(
+            &replaced_reveal_txid,
+            &final_reveal_tx,
+            &reveal_tx_output_info,
+        );
+
+        Ok(ReplacementInfo {
+            final_reveal_tx,
+            replaced_reveal_txid,
+            replaced_reveal_fee,
+            reveal_fee,
+            reveal_fee_rate: fee_rate,
+        })
+    }
+
+    fn context_position(&self, reveal_txid: &Txid) -> Option<usize> {
+        self.context
+            .fifo_queue
+            .iter()
+            .position(|req| req.inscriber_output.reveal_txid == *reveal_txid)
+    }
+
+    #[instrument(skip(self, reveal_tx, reveal_output), target = "bitcoin_inscriber")]
+    fn replace_reveal_in_context(
+        &mut self,
+        replaced_reveal_txid: &Txid,
+        reveal_tx: &FinalTx,
+        reveal_output: &RevealTxOutputRes,
+    ) {
+        let Some(position) = self.context_position(replaced_reveal_txid) else {
+            debug!("Replaced reveal transaction not found in context");
+            return;
+        };
+
+        let inscription = &mut self.context.fifo_queue[position];
+        inscription.inscriber_output.reveal_txid = reveal_tx.txid;
+        inscription.inscriber_output.reveal_raw_tx = reveal_tx.tx.raw_hex().to_string();
+        inscription.inscriber_output.reveal_tx_fee_rate = reveal_output.reveal_fee_rate;
+        inscription.fee_payer_ctx.fee_payer_utxo_txid = reveal_tx.txid;
+        inscription.fee_payer_ctx.fee_payer_utxo_value =
+            reveal_output.reveal_tx_change_output.value;
+        inscription
+            .replaced_reveal_txids
+            .push(*replaced_reveal_txid);
+        debug!("Replaced reveal transaction updated in context");
+    }
+
     #[instrument(skip(self), target = "bitcoin_inscriber")]
     pub fn get_context_snapshot(&self) -> Result<InscriberContext> {
         debug!("Getting context snapshot");
@@ -867,9 +1162,10 @@ mod tests {
 
     mock! {
         BitcoinSigner {}
+        #[async_trait]
         impl BitcoinSigner for BitcoinSigner {
-            fn sign_ecdsa(&self, msg: Message) -> BitcoinSignerResult<ECDSASignature>;
-            fn sign_schnorr(&self, msg: Message) -> BitcoinSignerResult<SchnorrSignature>;
+            async fn sign_ecdsa(&self, msg: Message) -> BitcoinSignerResult<ECDSASignature>;
+            async fn sign_schnorr(&self, msg: Message) -> BitcoinSignerResult<SchnorrSignature>;
             fn get_p2wpkh_address(&self) -> BitcoinSignerResult<Address>;
             fn get_p2wpkh_script_pubkey(&self) -> &ScriptBuf;
             fn get_secp_ref(&self) -> &Secp256k1<All>;
@@ -979,4 +1275,214 @@ mod tests {
         assert_ne!(res.final_commit_tx.txid, Txid::all_zeros());
         assert_ne!(res.final_reveal_tx.txid, Txid::all_zeros());
     }
+
+    #[tokio::test]
+    async fn test_inscriber_bump_inscription_fee() {
+        let mut inscriber =
+            test_utils::get_mock_inscriber_and_conditions(test_utils::MockBitcoinOpsConfig {
+                fee_rate: 2,
+                ..Default::default()
+            });
+
+        let l1_da_batch_ref = L1BatchDAReferenceInput {
+            l1_batch_hash: zksync_basic_types::H256([0; 32]),
+            l1_batch_index: zksync_basic_types::L1BatchNumber(0_u32),
+            da_identifier: "da_identifier_celestia".to_string(),
+            blob_id: "batch_temp_blob_id".to_string(),
+            prev_l1_batch_hash: zksync_basic_types::H256([0; 32]),
+        };
+
+        let inscribe_message = InscriptionMessage::L1BatchDAReference(l1_da_batch_ref);
+
+        let res = inscriber.inscribe(inscribe_message.clone()).await.unwrap();
+        let paid_fees =
+            res.commit_tx_output_info.commit_tx_fee + res.reveal_tx_output_info._reveal_fee;
+
+        let replacement = inscriber
+            .bump_inscription_fee(
+                std::slice::from_ref(&inscribe_message),
+                &res.final_commit_tx.tx,
+                &res.final_reveal_tx.tx,
+                paid_fees,
+                AncestorsPackage::default(),
+                10,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(replacement.replaced_reveal_txid, res.final_reveal_tx.txid);
+        assert_ne!(replacement.final_reveal_tx.txid, res.final_reveal_tx.txid);
+        assert!(replacement.reveal_fee > replacement.replaced_reveal_fee);
+
+        // The replacement spends the same commit outputs.
+        assert_eq!(
+            replacement.final_reveal_tx.tx.input[REVEAL_TX_FEE_INPUT_INDEX as usize]
+                .previous_output,
+            res.final_reveal_tx.tx.input[REVEAL_TX_FEE_INPUT_INDEX as usize].previous_output
+        );
+
+        let context = inscriber.get_context_snapshot().unwrap();
+        let inscription = context.fifo_queue.back().unwrap();
+        assert_eq!(
+            inscription.inscriber_output.commit_txid,
+            res.final_commit_tx.txid
+        );
+        assert_eq!(
+            inscription.fee_payer_ctx.fee_payer_utxo_txid,
+            replacement.final_reveal_tx.txid
+        );
+        assert_eq!(
+            inscription.fee_payer_ctx.fee_payer_utxo_value,
+            replacement.final_reveal_tx.tx.output[REVEAL_TX_CHANGE_OUTPUT_INDEX as usize].value
+        );
+        assert_eq!(
+            inscription.replaced_reveal_txids,
+            vec![res.final_reveal_tx.txid]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inscriber_skips_replaced_reveal_not_found() {
+        let mut inscriber =
+            test_utils::get_mock_inscriber_and_conditions(test_utils::MockBitcoinOpsConfig {
+                fee_rate: 2,
+                ..Default::default()
+            });
+
+        let inscribe_message = InscriptionMessage::L1BatchDAReference(L1BatchDAReferenceInput {
+            l1_batch_hash: zksync_basic_types::H256([0; 32]),
+            l1_batch_index: zksync_basic_types::L1BatchNumber(0_u32),
+            da_identifier: "da_identifier_celestia".to_string(),
+            blob_id: "batch_temp_blob_id".to_string(),
+            prev_l1_batch_hash: zksync_basic_types::H256([0; 32]),
+        });
+        let res = inscriber.inscribe(inscribe_message.clone()).await.unwrap();
+        let paid_fees =
+            res.commit_tx_output_info.commit_tx_fee + res.reveal_tx_output_info._reveal_fee;
+        inscriber
+            .bump_inscription_fee(
+                std::slice::from_ref(&inscribe_message),
+                &res.final_commit_tx.tx,
+                &res.final_reveal_tx.tx,
+                paid_fees,
+                AncestorsPackage::default(),
+                10,
+            )
+            .await
+            .unwrap();
+        let inscription = inscriber
+            .get_context_snapshot()
+            .unwrap()
+            .fifo_queue
+            .back()
+            .cloned()
+            .unwrap();
+
+        // The node doesn't know the replaced reveal tx anymore.
+        let replaced_reveal_txid = res.final_reveal_tx.txid;
+        let mut client = MockBitcoinOps::new();
+        client
+            .expect_check_tx_confirmation()
+            .returning(move |txid, _| {
+                if *txid == replaced_reveal_txid {
+                    Err(BitcoinError::NotFound(
+                        "No such mempool or blockchain transaction".into(),
+                    ))
+                } else {
+                    Ok(false)
+                }
+            });
+        inscriber.client = Arc::new(client);
+
+        assert!(!inscriber
+            .is_inscription_confirmed(&inscription)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_inscriber_bump_inscription_fee_pays_for_ancestors() {
+        let mut inscriber =
+            test_utils::get_mock_inscriber_and_conditions(test_utils::MockBitcoinOpsConfig {
+                fee_rate: 2,
+                ..Default::default()
+            });
+
+        let inscribe_message = InscriptionMessage::L1BatchDAReference(L1BatchDAReferenceInput {
+            l1_batch_hash: zksync_basic_types::H256([0; 32]),
+            l1_batch_index: zksync_basic_types::L1BatchNumber(0_u32),
+            da_identifier: "da_identifier_celestia".to_string(),
+            blob_id: "batch_temp_blob_id".to_string(),
+            prev_l1_batch_hash: zksync_basic_types::H256([0; 32]),
+        });
+
+        let res = inscriber.inscribe(inscribe_message.clone()).await.unwrap();
+        let commit_fee = res.commit_tx_output_info.commit_tx_fee;
+        let ancestors = AncestorsPackage {
+            vsize: 500,
+            fees: Amount::from_sat(1000),
+        };
+
+        let replacement = inscriber
+            .bump_inscription_fee(
+                std::slice::from_ref(&inscribe_message),
+                &res.final_commit_tx.tx,
+                &res.final_reveal_tx.tx,
+                commit_fee + res.reveal_tx_output_info._reveal_fee,
+                ancestors,
+                10,
+            )
+            .await
+            .unwrap();
+
+        // The whole chain reaches the fee rate, the ancestors included.
+        let package_vsize = ancestors.vsize
+            + (res.final_commit_tx.tx.vsize() + res.final_reveal_tx.tx.vsize()) as u64;
+        assert!(
+            ancestors.fees + commit_fee + replacement.reveal_fee
+                >= Amount::from_sat(10 * package_vsize)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inscriber_bump_inscription_fee_rejects_other_message() {
+        let mut inscriber =
+            test_utils::get_mock_inscriber_and_conditions(test_utils::MockBitcoinOpsConfig {
+                fee_rate: 2,
+                ..Default::default()
+            });
+
+        let l1_da_batch_ref = L1BatchDAReferenceInput {
+            l1_batch_hash: zksync_basic_types::H256([0; 32]),
+            l1_batch_index: zksync_basic_types::L1BatchNumber(0_u32),
+            da_identifier: "da_identifier_celestia".to_string(),
+            blob_id: "batch_temp_blob_id".to_string(),
+            prev_l1_batch_hash: zksync_basic_types::H256([0; 32]),
+        };
+
+        let res = inscriber
+            .inscribe(InscriptionMessage::L1BatchDAReference(
+                l1_da_batch_ref.clone(),
+            ))
+            .await
+            .unwrap();
+
+        let other_message = InscriptionMessage::L1BatchDAReference(L1BatchDAReferenceInput {
+            blob_id: "other_blob_id".to_string(),
+            ..l1_da_batch_ref
+        });
+
+        let result = inscriber
+            .bump_inscription_fee(
+                &[other_message],
+                &res.final_commit_tx.tx,
+                &res.final_reveal_tx.tx,
+                res.commit_tx_output_info.commit_tx_fee + res.reveal_tx_output_info._reveal_fee,
+                AncestorsPackage::default(),
+                10,
+            )
+            .await;
+
+        assert!(result.is_err());
+    }
 }
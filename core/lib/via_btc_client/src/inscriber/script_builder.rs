@@ -16,6 +16,9 @@ use zksync_types::{ethabi::ethereum_types::BigEndianHash, H256};
 
 use crate::types;
 
+/// The max size of a data push in a tapscript.
+const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+
 pub struct InscriptionData {
     pub inscription_script: ScriptBuf,
     pub script_size: usize,
@@ -34,7 +37,33 @@ impl InscriptionData {
         internal_key: UntweakedPublicKey,
         network: Network,
     ) -> Result<Self> {
-        debug!("Creating new InscriptionData");
+        Self::from_messages(
+            std::slice::from_ref(inscription_message),
+            secp,
+            internal_key,
+            network,
+        )
+    }
+
+    /// Packs several messages into a single tapscript, one envelope per message.
+    #[instrument(
+        skip(inscription_messages, secp, internal_key),
+        target = "bitcoin_inscriber::script_builder"
+    )]
+    pub fn from_messages<C: Signing + Verification>(
+        inscription_messages: &[types::InscriptionMessage],
+        secp: &Secp256k1<C>,
+        internal_key: UntweakedPublicKey,
+        network: Network,
+    ) -> Result<Self> {
+        debug!(
+            "Creating new InscriptionData with {} messages",
+            inscription_messages.len()
+        );
+        if inscription_messages.is_empty() {
+            anyhow::bail!("An inscription requires at least one message");
+        }
+
         let serialized_pubkey = internal_key.serialize();
         let mut encoded_pubkey = PushBytesBuf::with_capacity(serialized_pubkey.len());
         encoded_pubkey.extend_from_slice(&serialized_pubkey).ok();
@@ -42,7 +71,7 @@ impl InscriptionData {
         let basic_script = Self::build_basic_inscription_script(&encoded_pubkey)?;
 
         let (inscription_script, script_size) =
-            Self::complete_inscription(basic_script, inscription_message, network)?;
+            Self::complete_inscription(basic_script, inscription_messages, network)?;
 
         let (script_pubkey, taproot_spend_info) = Self::construct_inscription_commitment_data(
             secp,
@@ -91,61 +120,75 @@ impl InscriptionData {
     #[instrument(skip(encoded_pubkey), target = "bitcoin_inscriber::script_builder")]
     fn build_basic_inscription_script(encoded_pubkey: &PushBytesBuf) -> Result<ScriptBuilder> {
         debug!("Building basic inscription script");
+        let script = ScriptBuilder::new()
+            .push_slice(encoded_pubkey.as_push_bytes())
+            .push_opcode(all::OP_CHECKSIG);
+
+        debug!("Basic inscription script built");
+        Ok(script)
+    }
+
+    fn start_envelope(script: ScriptBuilder) -> ScriptBuilder {
         let mut via_prefix_encoded =
             PushBytesBuf::with_capacity(types::VIA_INSCRIPTION_PROTOCOL.len());
         via_prefix_encoded
             .extend_from_slice(types::VIA_INSCRIPTION_PROTOCOL.as_bytes())
             .ok();
 
-        let script = ScriptBuilder::new()
-            .push_slice(encoded_pubkey.as_push_bytes())
-            .push_opcode(all::OP_CHECKSIG)
+        script
             .push_opcode(OP_FALSE)
             .push_opcode(all::OP_IF)
-            .push_slice(via_prefix_encoded);
-
-        debug!("Basic inscription script built");
-        Ok(script)
+            .push_slice(via_prefix_encoded)
+            .push_int(types::VIA_INSCRIPTION_ENVELOPE_VERSION.into())
     }
 
     #[instrument(
-        skip(basic_script, message),
+        skip(basic_script, messages),
         target = "bitcoin_inscriber::script_builder"
     )]
     fn complete_inscription(
         basic_script: ScriptBuilder,
-        message: &types::InscriptionMessage,
+        messages: &[types::InscriptionMessage],
         network: Network,
     ) -> Result<(ScriptBuf, usize)> {
-        debug!("Completing inscription for message type: {:?}", message);
-        let final_script_result = match message {
-            types::InscriptionMessage::L1BatchDAReference(input) => {
-                Self::build_l1_batch_da_reference_script(basic_script, input)
-            }
-            types::InscriptionMessage::ProofDAReference(input) => {
-                Self::build_proof_da_reference_script(basic_script, input)
-            }
-            types::InscriptionMessage::ValidatorAttestation(input) => {
-                Self::build_validator_attestation_script(basic_script, input)
-            }
-            types::InscriptionMessage::SystemBootstrapping(input) => {
-                Self::build_system_bootstrapping_script(basic_script, input, network)?
-            }
-            types::InscriptionMessage::ProposeSequencer(input) => {
-                Self::build_propose_sequencer_script(basic_script, input, network)?
-            }
-            types::InscriptionMessage::L1ToL2Message(input) => {
-                Self::build_l1_to_l2_message_script(basic_script, input)
-            }
-            types::InscriptionMessage::SystemContractUpgradeProposal(input) => {
-                Self::build_system_contract_upgrade_message_script(basic_script, input)
-            }
-            types::InscriptionMessage::UpdateBridgeProposal(input) => {
-                Self::build_update_bridge_script(basic_script, input, network)?
-            }
-        };
-
-        let final_script = final_script_result.push_opcode(all::OP_ENDIF).into_script();
+        let mut script = basic_script;
+
+        for message in messages {
+            debug!("Completing inscription for message type: {:?}", message);
+            let envelope = Self::start_envelope(script);
+            let envelope = match message {
+                types::InscriptionMessage::L1BatchDAReference(input) => {
+                    Self::build_l1_batch_da_reference_script(envelope, input)?
+                }
+                types::InscriptionMessage::ProofDAReference(input) => {
+                    Self::build_proof_da_reference_script(envelope, input)?
+                }
+                types::InscriptionMessage::ValidatorAttestation(input) => {
+                    Self::build_validator_attestation_script(envelope, input)
+                }
+                types::InscriptionMessage::SystemBootstrapping(input) => {
+                    Self::build_system_bootstrapping_script(envelope, input, network)?
+                }
+                types::InscriptionMessage::ProposeSequencer(input) => {
+                    Self::build_propose_sequencer_script(envelope, input, network)?
+                }
+                types::InscriptionMessage::L1ToL2Message(input) => {
+                    Self::build_l1_to_l2_message_script(envelope, input)
+                }
+                types::InscriptionMessage::SystemContractUpgradeProposal(input) => {
+                    Self::build_system_contract_upgrade_message_script(envelope, input)
+                }
+                types::InscriptionMessage::UpdateBridgeProposal(input) => {
+                    Self::build_update_bridge_script(envelope, input, network)?
+                }
+                types::InscriptionMessage::DABlobChunk(input) => {
+                    Self::build_da_blob_chunk_script(envelope, input)
+                }
+            };
+            script = envelope.push_opcode(all::OP_ENDIF);
+        }
+
+        let final_script = script.into_script();
         let script_size = final_script.len();
 
         debug!("Inscription completed, script size: {}", script_size);
@@ -159,22 +202,21 @@ impl InscriptionData {
     fn build_l1_batch_da_reference_script(
         basic_script: ScriptBuilder,
         input: &types::L1BatchDAReferenceInput,
-    ) -> ScriptBuilder {
+    ) -> Result<ScriptBuilder> {
         debug!("Building L1BatchDAReference script");
         let l1_batch_hash_encoded = Self::encode_push_bytes(input.l1_batch_hash.as_bytes());
         let l1_batch_index_encoded = Self::encode_push_bytes(&input.l1_batch_index.to_be_bytes());
         let da_identifier_encoded = Self::encode_push_bytes(input.da_identifier.as_bytes());
-        let da_reference_encoded = Self::encode_push_bytes(input.blob_id.as_bytes());
         let prev_l1_batch_hash_encoded =
             Self::encode_push_bytes(input.prev_l1_batch_hash.as_bytes());
 
-        basic_script
+        let script = basic_script
             .push_slice(&*types::L1_BATCH_DA_REFERENCE_MSG)
             .push_slice(l1_batch_hash_encoded)
             .push_slice(l1_batch_index_encoded)
-            .push_slice(da_identifier_encoded)
-            .push_slice(da_reference_encoded)
-            .push_slice(prev_l1_batch_hash_encoded)
+            .push_slice(da_identifier_encoded);
+
+        Ok(Self::push_blob_id(script, &input.blob_id)?.push_slice(prev_l1_batch_hash_encoded))
     }
 
     #[instrument(
@@ -184,18 +226,48 @@ impl InscriptionData {
     fn build_proof_da_reference_script(
         basic_script: ScriptBuilder,
         input: &types::ProofDAReferenceInput,
-    ) -> ScriptBuilder {
+    ) -> Result<ScriptBuilder> {
         debug!("Building ProofDAReference script");
         let l1_batch_reveal_txid_encoded =
             Self::encode_push_bytes(input.l1_batch_reveal_txid.as_raw_hash().as_byte_array());
         let da_identifier_encoded = Self::encode_push_bytes(input.da_identifier.as_bytes());
-        let da_reference_encoded = Self::encode_push_bytes(input.blob_id.as_bytes());
 
-        basic_script
+        let script = basic_script
             .push_slice(&*types::PROOF_DA_REFERENCE_MSG)
             .push_slice(l1_batch_reveal_txid_encoded)
-            .push_slice(da_identifier_encoded)
-            .push_slice(da_reference_encoded)
+            .push_slice(da_identifier_encoded);
+        let script = Self::push_blob_id(script, &input.blob_id)?;
+
+        // The next L1 batches of the range proven along with the first one.
+        Ok(input
+            .next_l1_batch_reveal_txids
+            .iter()
+            .fold(script, |script, txid| {
+                script.push_slice(Self::encode_push_bytes(txid.as_raw_hash().as_byte_array()))
+            }))
+    }
+
+    /// Pushes the `blob_id` of a DA reference. A `blob_id` that doesn't fit in a single push is
+    /// split over several pushes, preceded by their count so the fields after it can be located.
+    fn push_blob_id(script: ScriptBuilder, blob_id: &str) -> Result<ScriptBuilder> {
+        if blob_id.len() <= MAX_SCRIPT_ELEMENT_SIZE {
+            return Ok(script.push_slice(Self::encode_push_bytes(blob_id.as_bytes())));
+        }
+
+        anyhow::ensure!(
+            blob_id.len() <= types::MAX_DA_REFERENCE_BLOB_ID_SIZE,
+            "Blob id of {} bytes is too large for a DA reference",
+            blob_id.len()
+        );
+
+        // At most 16 pushes, their count is pushed as `OP_PUSHNUM_<count>`.
+        let pushes = blob_id.len().div_ceil(MAX_SCRIPT_ELEMENT_SIZE);
+        Ok(blob_id
+            .as_bytes()
+            .chunks(MAX_SCRIPT_ELEMENT_SIZE)
+            .fold(script.push_int(pushes as i64), |script, data| {
+                script.push_slice(Self::encode_push_bytes(data))
+            }))
     }
 
     #[instrument(
@@ -373,6 +445,32 @@ impl InscriptionData {
         Ok(script.push_slice(bridge_address_encoded))
     }
 
+    #[instrument(
+        skip(basic_script, input),
+        target = "bitcoin_inscriber::script_builder"
+    )]
+    fn build_da_blob_chunk_script(
+        basic_script: ScriptBuilder,
+        input: &types::DABlobChunkInput,
+    ) -> ScriptBuilder {
+        debug!("Building DABlobChunk script");
+        let blob_hash_encoded = Self::encode_push_bytes(input.blob_hash.as_bytes());
+        let chunk_index_encoded = Self::encode_push_bytes(&input.chunk_index.to_be_bytes());
+
+        let script = basic_script
+            .push_slice(&*types::DA_BLOB_CHUNK_MSG)
+            .push_slice(blob_hash_encoded)
+            .push_slice(chunk_index_encoded);
+
+        // The data doesn't fit in a single push, it's split over as many pushes as needed.
+        input
+            .data
+            .chunks(MAX_SCRIPT_ELEMENT_SIZE)
+            .fold(script, |script, data| {
+                script.push_slice(Self::encode_push_bytes(data))
+            })
+    }
+
     #[instrument(skip(data), target = "bitcoin_inscriber::script_builder")]
     fn encode_push_bytes(data: &[u8]) -> PushBytesBuf {
         let mut encoded = PushBytesBuf::with_capacity(data.len());
@@ -130,8 +130,10 @@ impl BitcoinOps for MockBitcoinOps {
         BitcoinClientResult::Ok(self.block.clone().expect("Block not set"))
     }
 
-    async fn get_transaction(&self, _txid: &Txid) -> BitcoinClientResult<Transaction> {
-        BitcoinClientResult::Ok(self.transaction.clone().expect("No transaction found"))
+    async fn get_transaction(&self, txid: &Txid) -> BitcoinClientResult<Transaction> {
+        self.transaction
+            .clone()
+            .ok_or_else(|| types::BitcoinError::NotFound(format!("Transaction {txid}")))
     }
 
     async fn fetch_block_by_hash(&self, _block_hash: &BlockHash) -> BitcoinClientResult<Block> {
@@ -242,11 +244,11 @@ impl MockBitcoinSigner {
 
 #[async_trait::async_trait]
 impl BitcoinSigner for MockBitcoinSigner {
-    fn sign_ecdsa(&self, _: Message) -> types::BitcoinSignerResult<ECDSASignature> {
+    async fn sign_ecdsa(&self, _: Message) -> types::BitcoinSignerResult<ECDSASignature> {
         BitcoinClientResult::Ok(self.ecdsa_signature)
     }
 
-    fn sign_schnorr(&self, _: Message) -> types::BitcoinSignerResult<SchnorrSignature> {
+    async fn sign_schnorr(&self, _: Message) -> types::BitcoinSignerResult<SchnorrSignature> {
         BitcoinClientResult::Ok(self.schnorr_signature)
     }
 
@@ -272,13 +274,14 @@ impl BitcoinSigner for MockBitcoinSigner {
 }
 
 pub fn get_mock_inscriber_and_conditions(config: MockBitcoinOpsConfig) -> Inscriber {
-    let client = MockBitcoinOps::new(config);
-    let signer = MockBitcoinSigner::new();
-    let context = InscriberContext::default();
+    get_mock_inscriber(Arc::new(MockBitcoinOps::new(config)))
+}
 
+/// Returns an inscriber signing with a mock signer and broadcasting through `client`.
+pub fn get_mock_inscriber(client: Arc<dyn BitcoinOps>) -> Inscriber {
     Inscriber {
-        client: Arc::new(client),
-        signer: Arc::new(signer),
-        context,
+        client,
+        signer: Arc::new(MockBitcoinSigner::new()),
+        context: InscriberContext::default(),
     }
 }
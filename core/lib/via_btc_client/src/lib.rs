@@ -8,5 +8,6 @@ pub mod inscriber;
 pub mod metrics;
 #[cfg(feature = "regtest")]
 pub mod regtest;
-pub(crate) mod signer;
+pub mod signer;
 pub mod utils;
+pub mod wire;
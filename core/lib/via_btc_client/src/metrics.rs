@@ -1,10 +1,17 @@
-use vise::{Counter, EncodeLabelSet, Family, Metrics};
+use std::time::Duration;
+
+use vise::{Buckets, Counter, EncodeLabelSet, Family, Gauge, Histogram, Metrics, Unit};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
 pub struct RpcMethodLabel {
     pub method: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub struct FeeRateProviderLabel {
+    pub provider: String,
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "via_btc_client")]
 pub struct ViaBtcClientMetrics {
@@ -13,6 +20,16 @@ pub struct ViaBtcClientMetrics {
 
     /// Number of RPC errors encountered, by method and error type
     pub rpc_max_retries_exceeded: Family<RpcMethodLabel, Counter>,
+
+    /// Latency of a single RPC request (or batch of requests), by method
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub rpc_latency: Family<RpcMethodLabel, Histogram<Duration>>,
+
+    /// Last fee rate (sat/kB) estimated by each fee rate provider
+    pub fee_rate_estimates: Family<FeeRateProviderLabel, Gauge<u64>>,
+
+    /// Number of failed fee rate estimations, by provider
+    pub fee_rate_provider_errors: Family<FeeRateProviderLabel, Counter>,
 }
 
 #[vise::register]
@@ -0,0 +1,251 @@
+use std::{fs, path::Path};
+
+use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    Key, XChaCha20Poly1305, XNonce,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::types::{BitcoinError, BitcoinSignerResult};
+
+const KEYSTORE_VERSION: u32 = 1;
+const CIPHER: &str = "xchacha20poly1305";
+const KDF: &str = "scrypt";
+const SALT_SIZE: usize = 32;
+const NONCE_SIZE: usize = 24;
+const DERIVED_KEY_SIZE: usize = 32;
+
+/// Scrypt parameters used to derive the encryption key from the keystore password.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScryptParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl Default for ScryptParams {
+    fn default() -> Self {
+        Self {
+            log_n: 15,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct KeystoreCrypto {
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+    kdf: String,
+    kdfparams: ScryptParams,
+    salt: String,
+}
+
+/// Password-encrypted secret key stored as JSON on disk.
+///
+/// The key is encrypted with XChaCha20-Poly1305 using a key derived from the password with scrypt.
+/// The public key is kept in clear so the wallet can be identified without the password.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    version: u32,
+    public_key: String,
+    crypto: KeystoreCrypto,
+}
+
+impl Keystore {
+    pub fn encrypt(secret_key: &SecretKey, password: &str) -> BitcoinSignerResult<Self> {
+        Self::encrypt_with_params(secret_key, password, ScryptParams::default())
+    }
+
+    pub fn encrypt_with_params(
+        secret_key: &SecretKey,
+        password: &str,
+        params: ScryptParams,
+    ) -> BitcoinSignerResult<Self> {
+        let mut salt = [0u8; SALT_SIZE];
+        let mut nonce = [0u8; NONCE_SIZE];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let derived_key = derive_key(password, &salt, params)?;
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(derived_key.as_slice()));
+        let ciphertext = cipher
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                secret_key.secret_bytes().as_slice(),
+            )
+            .map_err(|_| BitcoinError::SigningError("Failed to encrypt the keystore".into()))?;
+
+        let public_key = secret_key.public_key(&Secp256k1::signing_only());
+
+        Ok(Self {
+            version: KEYSTORE_VERSION,
+            public_key: hex::encode(public_key.serialize()),
+            crypto: KeystoreCrypto {
+                cipher: CIPHER.into(),
+                nonce: hex::encode(nonce),
+                ciphertext: hex::encode(ciphertext),
+                kdf: KDF.into(),
+                kdfparams: params,
+                salt: hex::encode(salt),
+            },
+        })
+    }
+
+    pub fn decrypt(&self, password: &str) -> BitcoinSignerResult<SecretKey> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(invalid_keystore(format!(
+                "unsupported version {}",
+                self.version
+            )));
+        }
+        if self.crypto.cipher != CIPHER || self.crypto.kdf != KDF {
+            return Err(invalid_keystore(format!(
+                "unsupported cipher {} or kdf {}",
+                self.crypto.cipher, self.crypto.kdf
+            )));
+        }
+
+        let salt = decode_hex_field("salt", &self.crypto.salt)?;
+        let nonce = decode_hex_field("nonce", &self.crypto.nonce)?;
+        let ciphertext = decode_hex_field("ciphertext", &self.crypto.ciphertext)?;
+        if nonce.len() != NONCE_SIZE {
+            return Err(invalid_keystore("invalid nonce length".into()));
+        }
+
+        let derived_key = derive_key(password, &salt, self.crypto.kdfparams)?;
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(derived_key.as_slice()));
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+                .map_err(|_| {
+                    BitcoinError::InvalidPrivateKey(
+                        "Failed to decrypt the keystore, wrong password?".into(),
+                    )
+                })?,
+        );
+
+        let secret_key = SecretKey::from_slice(&plaintext)
+            .map_err(|e| BitcoinError::InvalidPrivateKey(e.to_string()))?;
+
+        if self.public_key()? != secret_key.public_key(&Secp256k1::signing_only()) {
+            return Err(invalid_keystore(
+                "public key doesn't match the secret key".into(),
+            ));
+        }
+
+        Ok(secret_key)
+    }
+
+    pub fn public_key(&self) -> BitcoinSignerResult<PublicKey> {
+        let bytes = decode_hex_field("public_key", &self.public_key)?;
+        PublicKey::from_slice(&bytes).map_err(|e| invalid_keystore(e.to_string()))
+    }
+
+    pub fn load(path: &Path) -> BitcoinSignerResult<Self> {
+        let data = fs::read_to_string(path)
+            .map_err(|e| invalid_keystore(format!("failed to read {}: {e}", path.display())))?;
+        serde_json::from_str(&data).map_err(|e| invalid_keystore(e.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> BitcoinSignerResult<()> {
+        let data =
+            serde_json::to_string_pretty(self).map_err(|e| invalid_keystore(e.to_string()))?;
+        fs::write(path, data)
+            .map_err(|e| invalid_keystore(format!("failed to write {}: {e}", path.display())))
+    }
+}
+
+fn derive_key(
+    password: &str,
+    salt: &[u8],
+    params: ScryptParams,
+) -> BitcoinSignerResult<Zeroizing<[u8; DERIVED_KEY_SIZE]>> {
+    let scrypt_params = scrypt::Params::new(params.log_n, params.r, params.p)
+        .map_err(|e| invalid_keystore(e.to_string()))?;
+
+    let mut derived_key = Zeroizing::new([0u8; DERIVED_KEY_SIZE]);
+    scrypt::scrypt(
+        password.as_bytes(),
+        salt,
+        &scrypt_params,
+        derived_key.as_mut_slice(),
+    )
+    .map_err(|e| invalid_keystore(e.to_string()))?;
+    Ok(derived_key)
+}
+
+fn decode_hex_field(name: &str, value: &str) -> BitcoinSignerResult<Vec<u8>> {
+    hex::decode(value).map_err(|e| invalid_keystore(format!("invalid {name}: {e}")))
+}
+
+fn invalid_keystore(message: String) -> BitcoinError {
+    BitcoinError::InvalidPrivateKey(format!("Invalid keystore: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cheap parameters, the default ones take a noticeable time to derive the key.
+    const TEST_PARAMS: ScryptParams = ScryptParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let keystore = Keystore::encrypt_with_params(&secret_key, "password", TEST_PARAMS).unwrap();
+
+        assert_eq!(keystore.decrypt("password").unwrap(), secret_key);
+        assert_eq!(
+            keystore.public_key().unwrap(),
+            secret_key.public_key(&Secp256k1::signing_only())
+        );
+    }
+
+    #[test]
+    fn test_keystore_wrong_password() {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let keystore = Keystore::encrypt_with_params(&secret_key, "password", TEST_PARAMS).unwrap();
+
+        assert!(keystore.decrypt("wrong password").is_err());
+    }
+
+    #[test]
+    fn test_keystore_save_and_load() {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let keystore = Keystore::encrypt_with_params(&secret_key, "password", TEST_PARAMS).unwrap();
+
+        let path = std::env::temp_dir().join(format!(
+            "via_keystore_{}.json",
+            hex::encode(
+                secret_key
+                    .public_key(&Secp256k1::signing_only())
+                    .serialize()
+            )
+        ));
+        keystore.save(&path).unwrap();
+        let loaded = Keystore::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, keystore);
+        assert_eq!(loaded.decrypt("password").unwrap(), secret_key);
+    }
+
+    #[test]
+    fn test_keystore_does_not_contain_the_secret_key() {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let keystore = Keystore::encrypt_with_params(&secret_key, "password", TEST_PARAMS).unwrap();
+
+        let json = serde_json::to_string(&keystore).unwrap();
+        assert!(!json.contains(&hex::encode(secret_key.secret_bytes())));
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use bitcoin::{
     key::UntweakedPublicKey,
@@ -7,12 +9,22 @@ use bitcoin::{
     },
     Address, CompressedPublicKey, Network, PrivateKey, ScriptBuf,
 };
+use zksync_config::configs::via_wallets::{ViaSignerConfig, ViaWallet};
 
+pub use self::{
+    keystore::{Keystore, ScryptParams},
+    remote::RemoteSigner,
+};
 use crate::{
     traits::BitcoinSigner,
     types::{BitcoinError, BitcoinSignerResult},
 };
 
+mod keystore;
+mod remote;
+#[cfg(any(test, feature = "signing-server"))]
+pub mod server;
+
 /// KeyManager handles the creation and management of Bitcoin keys and addresses.
 /// It provides functionality for signing transactions using both ECDSA and Schnorr signatures.
 #[derive(Clone)]
@@ -25,6 +37,14 @@ pub struct KeyManager {
     script_pubkey: ScriptBuf,
 }
 
+impl std::fmt::Debug for KeyManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyManager")
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
 impl KeyManager {
     /// Creates a new KeyManager instance from a WIF-encoded private key and network.
     ///
@@ -36,13 +56,18 @@ impl KeyManager {
     /// # Returns
     ///
     /// A Result containing the KeyManager instance or a BitcoinError
-    pub(crate) fn new(private_key_wif_str: &str, network: Network) -> BitcoinSignerResult<Self> {
-        let secp = Secp256k1::new();
-
+    pub fn new(private_key_wif_str: &str, network: Network) -> BitcoinSignerResult<Self> {
         let private_key = PrivateKey::from_wif(private_key_wif_str)
             .map_err(|e| BitcoinError::InvalidPrivateKey(e.to_string()))?;
 
-        let sk = private_key.inner;
+        Self::from_secret_key(private_key.inner, network)
+    }
+
+    /// Creates a new KeyManager instance from a secret key and network.
+    pub fn from_secret_key(sk: SecretKey, network: Network) -> BitcoinSignerResult<Self> {
+        let secp = Secp256k1::new();
+
+        let private_key = PrivateKey::new(sk, network);
 
         let pk = bitcoin::PublicKey::new(sk.public_key(&secp));
         let wpkh = pk.wpubkey_hash().map_err(|_e| {
@@ -69,6 +94,65 @@ impl KeyManager {
             script_pubkey,
         })
     }
+
+    /// Returns the secret key, for the signing schemes not covered by [`BitcoinSigner`] (e.g.
+    /// MuSig2).
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.sk
+    }
+
+    /// Creates a new KeyManager instance from an encrypted keystore file.
+    pub fn from_keystore(
+        path: &std::path::Path,
+        password: &str,
+        network: Network,
+    ) -> BitcoinSignerResult<Self> {
+        let sk = Keystore::load(path)?.decrypt(password)?;
+        Self::from_secret_key(sk, network)
+    }
+}
+
+/// Creates the signer of a wallet: its signing backend when configured, otherwise the private key.
+/// `keystore_password` unlocks a keystore backend.
+pub async fn signer_from_wallet(
+    wallet: &ViaWallet,
+    keystore_password: Option<&str>,
+    network: Network,
+) -> BitcoinSignerResult<Arc<dyn BitcoinSigner>> {
+    let signer: Arc<dyn BitcoinSigner> = match &wallet.signer {
+        Some(ViaSignerConfig::Remote { url, auth_token }) => {
+            Arc::new(RemoteSigner::connect(url, auth_token.clone(), network).await?)
+        }
+        _ => Arc::new(key_manager_from_wallet(wallet, keystore_password, network)?),
+    };
+    Ok(signer)
+}
+
+/// Creates the local signer of a wallet, unlocking its keystore with `keystore_password` when
+/// configured.
+///
+/// Meant for the components that need the key itself (e.g. MuSig2 signing), a remote signer is
+/// not supported there.
+pub fn key_manager_from_wallet(
+    wallet: &ViaWallet,
+    keystore_password: Option<&str>,
+    network: Network,
+) -> BitcoinSignerResult<KeyManager> {
+    match &wallet.signer {
+        Some(ViaSignerConfig::Keystore { path }) => {
+            let password = keystore_password.ok_or_else(|| {
+                BitcoinError::InvalidPrivateKey(format!(
+                    "Missing the password of the keystore {}",
+                    path.display()
+                ))
+            })?;
+            KeyManager::from_keystore(path, password, network)
+        }
+        Some(ViaSignerConfig::Remote { .. }) => Err(BitcoinError::InvalidPrivateKey(
+            "A remote signer doesn't expose the private key".to_string(),
+        )),
+        None => KeyManager::new(&wallet.private_key, network),
+    }
 }
 
 impl Default for KeyManager {
@@ -111,12 +195,12 @@ impl BitcoinSigner for KeyManager {
         Ok(self.internal_key)
     }
 
-    fn sign_ecdsa(&self, msg: Message) -> BitcoinSignerResult<ECDSASignature> {
+    async fn sign_ecdsa(&self, msg: Message) -> BitcoinSignerResult<ECDSASignature> {
         let signature = self.secp.sign_ecdsa(&msg, &self.sk);
         Ok(signature)
     }
 
-    fn sign_schnorr(&self, msg: Message) -> BitcoinSignerResult<SchnorrSignature> {
+    async fn sign_schnorr(&self, msg: Message) -> BitcoinSignerResult<SchnorrSignature> {
         let signature = self.secp.sign_schnorr_no_aux_rand(&msg, &self.keypair);
         Ok(signature)
     }
@@ -151,22 +235,22 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_sign_ecdsa() {
+    #[tokio::test]
+    async fn test_sign_ecdsa() {
         let key_manager = KeyManager::default();
         let message = Message::from_digest_slice(&[1; 32]).unwrap();
-        let signature = key_manager.sign_ecdsa(message).unwrap();
+        let signature = key_manager.sign_ecdsa(message).await.unwrap();
         assert!(key_manager
             .secp
             .verify_ecdsa(&message, &signature, &key_manager.get_public_key())
             .is_ok());
     }
 
-    #[test]
-    fn test_sign_schnorr() {
+    #[tokio::test]
+    async fn test_sign_schnorr() {
         let key_manager = KeyManager::default();
         let message = Message::from_digest_slice(&[1; 32]).unwrap();
-        let signature = key_manager.sign_schnorr(message).unwrap();
+        let signature = key_manager.sign_schnorr(message).await.unwrap();
         assert!(key_manager
             .secp
             .verify_schnorr(
@@ -176,4 +260,76 @@ mod tests {
             )
             .is_ok());
     }
+
+    #[tokio::test]
+    async fn test_remote_signer() {
+        let key_manager = Arc::new(KeyManager::default());
+        let (addr, _handle) = server::spawn_signing_server(
+            key_manager.clone(),
+            Some("token".into()),
+            "127.0.0.1:0".parse().unwrap(),
+        )
+        .await
+        .unwrap();
+        let url = format!("http://{addr}");
+
+        let remote_signer = RemoteSigner::connect(&url, Some("token".into()), Network::Testnet)
+            .await
+            .unwrap();
+        assert_eq!(remote_signer.get_public_key(), key_manager.get_public_key());
+        assert_eq!(
+            remote_signer.get_p2wpkh_script_pubkey(),
+            key_manager.get_p2wpkh_script_pubkey()
+        );
+
+        let message = Message::from_digest_slice(&[1; 32]).unwrap();
+        assert_eq!(
+            remote_signer.sign_ecdsa(message).await.unwrap(),
+            key_manager.sign_ecdsa(message).await.unwrap()
+        );
+        assert_eq!(
+            remote_signer.sign_schnorr(message).await.unwrap(),
+            key_manager.sign_schnorr(message).await.unwrap()
+        );
+
+        assert!(
+            RemoteSigner::connect(&url, Some("wrong".into()), Network::Testnet)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_signer_from_wallet_keystore() {
+        let key_manager = KeyManager::default();
+        let keystore = Keystore::encrypt_with_params(
+            &key_manager.sk,
+            "password",
+            ScryptParams {
+                log_n: 4,
+                r: 8,
+                p: 1,
+            },
+        )
+        .unwrap();
+        let path = std::env::temp_dir().join(format!("via_wallet_{}.json", key_manager.address));
+        keystore.save(&path).unwrap();
+
+        let wallet = ViaWallet {
+            signer: Some(ViaSignerConfig::Keystore { path: path.clone() }),
+            ..Default::default()
+        };
+
+        let signer = signer_from_wallet(&wallet, Some("password"), Network::Testnet)
+            .await
+            .unwrap();
+        let unlocked =
+            key_manager_from_wallet(&wallet, Some("password"), Network::Testnet).unwrap();
+        let missing_password = key_manager_from_wallet(&wallet, None, Network::Testnet);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(signer.get_public_key(), key_manager.get_public_key());
+        assert_eq!(unlocked.secret_key(), key_manager.secret_key());
+        assert!(missing_password.is_err());
+    }
 }
@@ -0,0 +1,212 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bitcoin::{
+    key::UntweakedPublicKey,
+    secp256k1::{
+        ecdsa::Signature as ECDSASignature, schnorr::Signature as SchnorrSignature, All, Message,
+        PublicKey, Secp256k1,
+    },
+    Address, CompressedPublicKey, Network, ScriptBuf,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    traits::BitcoinSigner,
+    types::{BitcoinError, BitcoinSignerResult},
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(crate) const PUBLIC_KEY_PATH: &str = "/public_key";
+pub(crate) const SIGN_ECDSA_PATH: &str = "/sign/ecdsa";
+pub(crate) const SIGN_SCHNORR_PATH: &str = "/sign/schnorr";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct PublicKeyResponse {
+    /// Hex encoded compressed public key.
+    pub public_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SignRequest {
+    /// Hex encoded 32 bytes digest to sign.
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SignResponse {
+    /// Hex encoded 64 bytes compact signature.
+    pub signature: String,
+}
+
+/// Client of a remote signing service.
+///
+/// The service holds the key and only returns signatures, see [`super::server`] for the API.
+/// Every returned signature is verified against the public key fetched on connection.
+#[derive(Clone)]
+pub struct RemoteSigner {
+    http: reqwest::Client,
+    base_url: String,
+    auth_token: Option<String>,
+    secp: Secp256k1<All>,
+    public_key: PublicKey,
+    internal_key: UntweakedPublicKey,
+    address: Address,
+    script_pubkey: ScriptBuf,
+}
+
+impl std::fmt::Debug for RemoteSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteSigner")
+            .field("base_url", &self.base_url)
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+impl RemoteSigner {
+    pub async fn connect(
+        url: &str,
+        auth_token: Option<String>,
+        network: Network,
+    ) -> BitcoinSignerResult<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| BitcoinError::SigningError(e.to_string()))?;
+        let base_url = url.trim_end_matches('/').to_string();
+
+        let response: PublicKeyResponse = send(
+            http.get(format!("{base_url}{PUBLIC_KEY_PATH}")),
+            &auth_token,
+        )
+        .await?;
+        let public_key = hex::decode(&response.public_key)
+            .ok()
+            .and_then(|bytes| CompressedPublicKey::from_slice(&bytes).ok())
+            .ok_or_else(|| {
+                BitcoinError::CompressedPublicKeyError(format!(
+                    "Invalid public key returned by the remote signer: {}",
+                    response.public_key
+                ))
+            })?;
+
+        let address = Address::p2wpkh(&public_key, network);
+        let script_pubkey = address.script_pubkey();
+
+        Ok(Self {
+            http,
+            base_url,
+            auth_token,
+            secp: Secp256k1::new(),
+            public_key: public_key.0,
+            internal_key: public_key.0.x_only_public_key().0,
+            address,
+            script_pubkey,
+        })
+    }
+
+    async fn sign(&self, path: &str, msg: Message) -> BitcoinSignerResult<[u8; 64]> {
+        let request = SignRequest {
+            message: hex::encode(msg.as_ref()),
+        };
+        let response: SignResponse = send(
+            self.http
+                .post(format!("{}{path}", self.base_url))
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(
+                    serde_json::to_vec(&request)
+                        .map_err(|e| BitcoinError::SigningError(e.to_string()))?,
+                ),
+            &self.auth_token,
+        )
+        .await?;
+
+        hex::decode(&response.signature)
+            .ok()
+            .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+            .ok_or_else(|| {
+                BitcoinError::SigningError("Invalid signature returned by the remote signer".into())
+            })
+    }
+}
+
+async fn send<T: DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+    auth_token: &Option<String>,
+) -> BitcoinSignerResult<T> {
+    let request = match auth_token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    };
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| BitcoinError::SigningError(format!("Remote signer request failed: {e}")))?;
+
+    let status = response.status();
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| BitcoinError::SigningError(e.to_string()))?;
+
+    if !status.is_success() {
+        return Err(BitcoinError::SigningError(format!(
+            "Remote signer returned {status}: {}",
+            String::from_utf8_lossy(&body)
+        )));
+    }
+
+    serde_json::from_slice(&body).map_err(|e| BitcoinError::SigningError(e.to_string()))
+}
+
+#[async_trait]
+impl BitcoinSigner for RemoteSigner {
+    async fn sign_ecdsa(&self, msg: Message) -> BitcoinSignerResult<ECDSASignature> {
+        let signature = self.sign(SIGN_ECDSA_PATH, msg).await?;
+        let signature = ECDSASignature::from_compact(&signature)
+            .map_err(|e| BitcoinError::SigningError(e.to_string()))?;
+
+        self.secp
+            .verify_ecdsa(&msg, &signature, &self.public_key)
+            .map_err(|_| {
+                BitcoinError::SigningError("Remote signer returned an invalid signature".into())
+            })?;
+        Ok(signature)
+    }
+
+    async fn sign_schnorr(&self, msg: Message) -> BitcoinSignerResult<SchnorrSignature> {
+        let signature = self.sign(SIGN_SCHNORR_PATH, msg).await?;
+        let signature = SchnorrSignature::from_slice(&signature)
+            .map_err(|e| BitcoinError::SigningError(e.to_string()))?;
+
+        self.secp
+            .verify_schnorr(&signature, &msg, &self.internal_key)
+            .map_err(|_| {
+                BitcoinError::SigningError("Remote signer returned an invalid signature".into())
+            })?;
+        Ok(signature)
+    }
+
+    fn get_p2wpkh_address(&self) -> BitcoinSignerResult<Address> {
+        Ok(self.address.clone())
+    }
+
+    fn get_p2wpkh_script_pubkey(&self) -> &ScriptBuf {
+        &self.script_pubkey
+    }
+
+    fn get_secp_ref(&self) -> &Secp256k1<All> {
+        &self.secp
+    }
+
+    fn get_internal_key(&self) -> BitcoinSignerResult<UntweakedPublicKey> {
+        Ok(self.internal_key)
+    }
+
+    fn get_public_key(&self) -> PublicKey {
+        self.public_key
+    }
+}
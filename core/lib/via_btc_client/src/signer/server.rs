@@ -0,0 +1,121 @@
+//! Local stand-in for a remote signing service, used by tests and local setups.
+//!
+//! API (JSON over HTTP, optional bearer token):
+//! - `GET /public_key` -> `{"public_key": "<hex compressed public key>"}`
+//! - `POST /sign/ecdsa` `{"message": "<hex 32 bytes>"}` -> `{"signature": "<hex compact signature>"}`
+//! - `POST /sign/schnorr` `{"message": "<hex 32 bytes>"}` -> `{"signature": "<hex signature>"}`
+
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    extract::State,
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use bitcoin::secp256k1::Message;
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use super::remote::{
+    PublicKeyResponse, SignRequest, SignResponse, PUBLIC_KEY_PATH, SIGN_ECDSA_PATH,
+    SIGN_SCHNORR_PATH,
+};
+use crate::traits::BitcoinSigner;
+
+#[derive(Clone)]
+struct ServerState {
+    signer: Arc<dyn BitcoinSigner>,
+    auth_token: Option<String>,
+}
+
+type HandlerResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+/// Serves `signer` on `addr`, returns the bound address and the server task.
+pub async fn spawn_signing_server(
+    signer: Arc<dyn BitcoinSigner>,
+    auth_token: Option<String>,
+    addr: SocketAddr,
+) -> anyhow::Result<(SocketAddr, JoinHandle<()>)> {
+    let state = ServerState { signer, auth_token };
+    let app = Router::new()
+        .route(PUBLIC_KEY_PATH, get(public_key))
+        .route(SIGN_ECDSA_PATH, post(sign_ecdsa))
+        .route(SIGN_SCHNORR_PATH, post(sign_schnorr))
+        .with_state(state);
+
+    let listener = TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+    let handle = tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, app).await {
+            tracing::error!("Signing server stopped: {err}");
+        }
+    });
+
+    Ok((local_addr, handle))
+}
+
+fn authorize(state: &ServerState, headers: &HeaderMap) -> Result<(), (StatusCode, String)> {
+    let Some(token) = &state.auth_token else {
+        return Ok(());
+    };
+
+    let expected = format!("Bearer {token}");
+    match headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(value) if value == expected => Ok(()),
+        _ => Err((StatusCode::UNAUTHORIZED, "Unauthorized".into())),
+    }
+}
+
+fn parse_message(request: &SignRequest) -> Result<Message, (StatusCode, String)> {
+    hex::decode(&request.message)
+        .ok()
+        .and_then(|bytes| Message::from_digest_slice(&bytes).ok())
+        .ok_or_else(|| (StatusCode::BAD_REQUEST, "Invalid message".into()))
+}
+
+fn internal_error(err: impl std::fmt::Display) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
+
+async fn public_key(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+) -> HandlerResult<PublicKeyResponse> {
+    authorize(&state, &headers)?;
+    Ok(Json(PublicKeyResponse {
+        public_key: hex::encode(state.signer.get_public_key().serialize()),
+    }))
+}
+
+async fn sign_ecdsa(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+    Json(request): Json<SignRequest>,
+) -> HandlerResult<SignResponse> {
+    authorize(&state, &headers)?;
+    let msg = parse_message(&request)?;
+    let signature = state.signer.sign_ecdsa(msg).await.map_err(internal_error)?;
+    Ok(Json(SignResponse {
+        signature: hex::encode(signature.serialize_compact()),
+    }))
+}
+
+async fn sign_schnorr(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+    Json(request): Json<SignRequest>,
+) -> HandlerResult<SignResponse> {
+    authorize(&state, &headers)?;
+    let msg = parse_message(&request)?;
+    let signature = state
+        .signer
+        .sign_schnorr(msg)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(SignResponse {
+        signature: hex::encode(signature.serialize()),
+    }))
+}
@@ -17,7 +17,7 @@ use secp256k1::{
 };
 use types::BitcoinRpcResult;
 
-use crate::{types, types::BitcoinClientResult};
+use crate::{types, types::BitcoinClientResult, wire::WireFormatResult};
 
 #[async_trait]
 pub trait BitcoinOps: Send + Sync {
@@ -39,6 +39,14 @@ pub trait BitcoinOps: Send + Sync {
     async fn get_fee_rate(&self, conf_target: u16) -> types::BitcoinClientResult<u64>;
     fn get_network(&self) -> Network;
     async fn fetch_block(&self, block_height: u128) -> BitcoinClientResult<Block>;
+    /// Fetches several blocks at once, in the order of `block_heights`.
+    async fn fetch_blocks(&self, block_heights: &[u128]) -> BitcoinClientResult<Vec<Block>> {
+        let mut blocks = Vec::with_capacity(block_heights.len());
+        for block_height in block_heights {
+            blocks.push(self.fetch_block(*block_height).await?);
+        }
+        Ok(blocks)
+    }
 
     async fn get_transaction(&self, txid: &Txid) -> BitcoinClientResult<Transaction>;
     async fn fetch_block_by_hash(&self, block_hash: &BlockHash) -> BitcoinClientResult<Block>;
@@ -69,10 +77,15 @@ pub trait BitcoinRpc: Send + Sync + Debug {
     async fn get_transaction(&self, tx_id: &Txid) -> BitcoinRpcResult<Transaction>;
     async fn get_block_count(&self) -> BitcoinRpcResult<u64>;
     async fn get_block_by_height(&self, block_height: u128) -> BitcoinRpcResult<Block>;
+    async fn get_blocks_by_height(&self, block_heights: &[u128]) -> BitcoinRpcResult<Vec<Block>>;
 
     async fn get_block_by_hash(&self, block_hash: &BlockHash) -> BitcoinRpcResult<Block>;
     async fn get_best_block_hash(&self) -> BitcoinRpcResult<bitcoin::BlockHash>;
     async fn get_block_stats(&self, height: u64) -> BitcoinRpcResult<GetBlockStatsResult>;
+    async fn get_block_stats_batch(
+        &self,
+        heights: &[u64],
+    ) -> BitcoinRpcResult<Vec<GetBlockStatsResult>>;
     async fn get_raw_transaction_info(
         &self,
         txid: &Txid,
@@ -84,12 +97,17 @@ pub trait BitcoinRpc: Send + Sync + Debug {
     ) -> BitcoinRpcResult<bitcoincore_rpc::json::EstimateSmartFeeResult>;
     async fn get_blockchain_info(&self) -> BitcoinRpcResult<GetBlockchainInfoResult>;
     async fn get_mempool_info(&self) -> BitcoinRpcResult<GetMempoolInfoResult>;
+    /// Returns the mempool as `(fee rate in sat/vB, vsize)` buckets, highest fee rate first.
+    async fn get_mempool_fee_histogram(&self) -> BitcoinRpcResult<Vec<(f64, u64)>>;
 }
 
-pub(crate) trait BitcoinSigner: Send + Sync {
-    fn sign_ecdsa(&self, msg: Message) -> types::BitcoinSignerResult<ECDSASignature>;
+/// Signing backend of the inscriber, the signatures are requested asynchronously so the key
+/// can live outside of the process (see [`crate::signer`]).
+#[async_trait]
+pub trait BitcoinSigner: Send + Sync {
+    async fn sign_ecdsa(&self, msg: Message) -> types::BitcoinSignerResult<ECDSASignature>;
 
-    fn sign_schnorr(&self, msg: Message) -> types::BitcoinSignerResult<SchnorrSignature>;
+    async fn sign_schnorr(&self, msg: Message) -> types::BitcoinSignerResult<SchnorrSignature>;
 
     fn get_p2wpkh_address(&self) -> types::BitcoinSignerResult<Address>;
 
@@ -108,9 +126,10 @@ impl std::fmt::Debug for dyn BitcoinSigner + 'static {
     }
 }
 
+/// Off-chain encoding of the protocol messages, see [`crate::wire`].
 pub trait Serializable {
-    fn to_bytes(&self) -> Vec<u8>;
-    fn from_bytes(bytes: &[u8]) -> Self
+    fn to_bytes(&self) -> WireFormatResult<Vec<u8>>;
+    fn from_bytes(bytes: &[u8]) -> WireFormatResult<Self>
     where
         Self: Sized;
 }
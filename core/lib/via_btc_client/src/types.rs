@@ -1,10 +1,9 @@
 use std::collections::VecDeque;
 
-use bincode::{deserialize, serialize};
 pub use bitcoin::{
     address::NetworkUnchecked, secp256k1 as BitcoinSecp256k1, Address as BitcoinAddress,
-    CompressedPublicKey, Network as BitcoinNetwork, PrivateKey as BitcoinPrivateKey,
-    Txid as BitcoinTxid,
+    BlockHash as BitcoinBlockHash, CompressedPublicKey, Network as BitcoinNetwork,
+    PrivateKey as BitcoinPrivateKey, Txid as BitcoinTxid,
 };
 use bitcoin::{
     hashes::FromSliceError, script::PushBytesBuf, taproot::Signature as TaprootSignature, Amount,
@@ -20,7 +19,10 @@ use zksync_types::{
     protocol_version::ProtocolSemanticVersion, Address as EVMAddress, L1BatchNumber,
 };
 
-use crate::traits::Serializable;
+use crate::{
+    traits::Serializable,
+    wire::{self, WireFormatResult},
+};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Vote {
@@ -48,6 +50,16 @@ pub struct ProofDAReferenceInput {
     pub l1_batch_reveal_txid: Txid,
     pub da_identifier: String,
     pub blob_id: String,
+    /// Reveal txids of the L1 batches following `l1_batch_reveal_txid` when the proof covers a
+    /// range of batches, empty for the proof of a single batch.
+    pub next_l1_batch_reveal_txids: Vec<Txid>,
+}
+
+impl ProofDAReferenceInput {
+    /// Reveal txids of all the L1 batches covered by the proof, in order.
+    pub fn l1_batch_reveal_txids(&self) -> impl Iterator<Item = &Txid> {
+        std::iter::once(&self.l1_batch_reveal_txid).chain(&self.next_l1_batch_reveal_txids)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -200,10 +212,28 @@ pub struct BridgeWithdrawalInput {
     pub output_amount: u64,
     /// The L1 batch proof reveal tx_id.
     pub l1_batch_proof_reveal_tx_id: Vec<u8>,
+    /// The L1 batch of the withdrawals, set when the proof can cover several L1 batches.
+    pub l1_batch_number: Option<u32>,
     /// The list of withdrawals.
     pub withdrawals: Vec<(String, i64)>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct BridgeRefund {
+    pub common: CommonFields,
+    pub input: BridgeRefundInput,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BridgeRefundInput {
+    /// The input utxos.
+    pub inputs: Vec<OutPoint>,
+    /// The tx_id of the refunded deposit.
+    pub deposit_tx_id: Txid,
+    /// The list of refunds.
+    pub refunds: Vec<(String, i64)>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ProposeSequencerInput {
     pub sequencer_new_p2wpkh_address: BitcoinAddress<NetworkUnchecked>,
@@ -230,6 +260,22 @@ pub struct L1ToL2Message {
     pub tx_outputs: Vec<TxOut>,
 }
 
+/// A chunk of a blob stored on Bitcoin by the Bitcoin DA client.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DABlobChunkInput {
+    /// The keccak256 hash of the whole blob.
+    pub blob_hash: H256,
+    /// The position of the chunk in the blob.
+    pub chunk_index: u32,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DABlobChunk {
+    pub common: CommonFields,
+    pub input: DABlobChunkInput,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum InscriptionMessage {
     L1BatchDAReference(L1BatchDAReferenceInput),
@@ -240,18 +286,131 @@ pub enum InscriptionMessage {
     L1ToL2Message(L1ToL2MessageInput),
     SystemContractUpgradeProposal(SystemContractUpgradeProposalInput),
     UpdateBridgeProposal(UpdateBridgeProposalInput),
+    DABlobChunk(DABlobChunkInput),
+}
+
+impl InscriptionMessage {
+    /// How quickly the message has to be confirmed.
+    pub fn fee_rate_target(&self) -> FeeRateTarget {
+        match self {
+            InscriptionMessage::ValidatorAttestation(_) => FeeRateTarget::Urgent,
+            InscriptionMessage::L1BatchDAReference(_)
+            | InscriptionMessage::ProofDAReference(_)
+            | InscriptionMessage::DABlobChunk(_) => FeeRateTarget::Economical,
+            _ => FeeRateTarget::Normal,
+        }
+    }
+}
+
+/// Confirmation speed a transaction is paying for, ordered from the cheapest to the fastest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FeeRateTarget {
+    Economical,
+    Normal,
+    Urgent,
+}
+
+impl FeeRateTarget {
+    /// Number of blocks the transaction should be confirmed within.
+    pub fn conf_target(self) -> u16 {
+        match self {
+            FeeRateTarget::Economical => 6,
+            FeeRateTarget::Normal => 3,
+            FeeRateTarget::Urgent => 1,
+        }
+    }
+
+    /// The target of a transaction carrying all the `messages`, that is the most urgent one.
+    pub fn for_messages(messages: &[InscriptionMessage]) -> Self {
+        messages
+            .iter()
+            .map(InscriptionMessage::fee_rate_target)
+            .max()
+            .unwrap_or(FeeRateTarget::Normal)
+    }
+}
+
+/// Layout of the inscription messages stored before the proofs of L1 batch ranges, whose
+/// `ProofDAReferenceInput` had no `next_l1_batch_reveal_txids`.
+#[derive(Deserialize)]
+enum LegacyInscriptionMessage {
+    L1BatchDAReference(L1BatchDAReferenceInput),
+    ProofDAReference {
+        l1_batch_reveal_txid: Txid,
+        da_identifier: String,
+        blob_id: String,
+    },
+    ValidatorAttestation(ValidatorAttestationInput),
+    SystemBootstrapping(SystemBootstrappingInput),
+    ProposeSequencer(ProposeSequencerInput),
+    L1ToL2Message(L1ToL2MessageInput),
+    SystemContractUpgradeProposal(SystemContractUpgradeProposalInput),
+    UpdateBridgeProposal(UpdateBridgeProposalInput),
+}
+
+impl From<LegacyInscriptionMessage> for InscriptionMessage {
+    fn from(message: LegacyInscriptionMessage) -> Self {
+        match message {
+            LegacyInscriptionMessage::L1BatchDAReference(input) => Self::L1BatchDAReference(input),
+            LegacyInscriptionMessage::ProofDAReference {
+                l1_batch_reveal_txid,
+                da_identifier,
+                blob_id,
+            } => Self::ProofDAReference(ProofDAReferenceInput {
+                l1_batch_reveal_txid,
+                da_identifier,
+                blob_id,
+                next_l1_batch_reveal_txids: vec![],
+            }),
+            LegacyInscriptionMessage::ValidatorAttestation(input) => {
+                Self::ValidatorAttestation(input)
+            }
+            LegacyInscriptionMessage::SystemBootstrapping(input) => {
+                Self::SystemBootstrapping(input)
+            }
+            LegacyInscriptionMessage::ProposeSequencer(input) => Self::ProposeSequencer(input),
+            LegacyInscriptionMessage::L1ToL2Message(input) => Self::L1ToL2Message(input),
+            LegacyInscriptionMessage::SystemContractUpgradeProposal(input) => {
+                Self::SystemContractUpgradeProposal(input)
+            }
+            LegacyInscriptionMessage::UpdateBridgeProposal(input) => {
+                Self::UpdateBridgeProposal(input)
+            }
+        }
+    }
 }
 
 impl Serializable for InscriptionMessage {
-    fn to_bytes(&self) -> Vec<u8> {
-        serialize(self).expect("error serialize the InscriptionMessage")
+    fn to_bytes(&self) -> WireFormatResult<Vec<u8>> {
+        wire::encode(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> WireFormatResult<Self>
+    where
+        Self: Sized,
+    {
+        wire::decode(bytes).or_else(|err| {
+            wire::decode::<LegacyInscriptionMessage>(bytes)
+                .map(Into::into)
+                .map_err(|_| err)
+        })
+    }
+}
+
+impl Serializable for Vec<InscriptionMessage> {
+    fn to_bytes(&self) -> WireFormatResult<Vec<u8>> {
+        wire::encode(self)
     }
 
-    fn from_bytes(bytes: &[u8]) -> Self
+    fn from_bytes(bytes: &[u8]) -> WireFormatResult<Self>
     where
         Self: Sized,
     {
-        deserialize(bytes).expect("error deserialize the InscriptionMessage")
+        wire::decode(bytes).or_else(|err| {
+            wire::decode::<Vec<LegacyInscriptionMessage>>(bytes)
+                .map(|messages| messages.into_iter().map(Into::into).collect())
+                .map_err(|_| err)
+        })
     }
 }
 
@@ -277,11 +436,13 @@ pub enum FullInscriptionMessage {
     L1ToL2Message(L1ToL2Message),
     SystemContractUpgradeProposal(SystemContractUpgradeProposal),
     BridgeWithdrawal(BridgeWithdrawal),
+    BridgeRefund(BridgeRefund),
     UpdateBridgeProposal(UpdateBridgeProposal),
     UpdateGovernance(UpdateGovernance),
     UpdateSequencer(UpdateSequencer),
     SystemContractUpgrade(SystemContractUpgrade),
     UpdateBridge(UpdateBridge),
+    DABlobChunk(DABlobChunk),
 }
 
 impl FullInscriptionMessage {
@@ -296,13 +457,15 @@ impl FullInscriptionMessage {
             FullInscriptionMessage::L1ToL2Message(_) => 5,
             FullInscriptionMessage::SystemContractUpgradeProposal(_) => 6,
             FullInscriptionMessage::BridgeWithdrawal(_) => 7,
-            FullInscriptionMessage::UpdateBridgeProposal(_) => 8,
+            FullInscriptionMessage::BridgeRefund(_) => 8,
+            FullInscriptionMessage::UpdateBridgeProposal(_) => 9,
 
             // System inscriptions must be ordered to ensure the indexer always uses the latest wallet state.
-            FullInscriptionMessage::UpdateGovernance(_) => 9,
-            FullInscriptionMessage::UpdateSequencer(_) => 10,
-            FullInscriptionMessage::SystemContractUpgrade(_) => 11,
-            FullInscriptionMessage::UpdateBridge(_) => 12,
+            FullInscriptionMessage::UpdateGovernance(_) => 10,
+            FullInscriptionMessage::UpdateSequencer(_) => 11,
+            FullInscriptionMessage::SystemContractUpgrade(_) => 12,
+            FullInscriptionMessage::UpdateBridge(_) => 13,
+            FullInscriptionMessage::DABlobChunk(_) => 14,
         }
     }
 
@@ -319,6 +482,14 @@ pub struct FeePayerCtx {
     pub fee_payer_utxo_value: Amount,
 }
 
+/// Unconfirmed ancestors of a reveal tx: the pending inscriptions before it, whose reveal tx funds
+/// the commit tx of the next one. A replacement of the reveal tx pays for their missing fees.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AncestorsPackage {
+    pub vsize: u64,
+    pub fees: Amount,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CommitTxInput {
     pub spent_utxo: Vec<TxIn>,
@@ -339,15 +510,30 @@ lazy_static! {
     pub static ref SYSTEM_CONTRACT_UPGRADE_MSG: PushBytesBuf =
         PushBytesBuf::from(b"SystemContractUpgradeProposal");
     pub static ref UPGRADE_BRIDGE_MSG: PushBytesBuf = PushBytesBuf::from(b"UpgradeBridgeProposal");
+    pub static ref DA_BLOB_CHUNK_MSG: PushBytesBuf = PushBytesBuf::from(b"DABlobChunkMessage");
 }
 pub(crate) const VIA_INSCRIPTION_PROTOCOL: &str = "via_inscription_protocol";
+/// Version of the inscription envelope layout, pushed as `OP_PUSHNUM_<version>` right after the
+/// protocol identifier. Envelopes inscribed before the versioning have no version push and use the
+/// layout of version 1.
+pub const VIA_INSCRIPTION_ENVELOPE_VERSION: u8 = 1;
+
+/// Max size of the `blob_id` of a DA reference, which is split over up to 16 script pushes of 520
+/// bytes when it doesn't fit in a single push.
+pub const MAX_DA_REFERENCE_BLOB_ID_SIZE: usize = 16 * 520;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct InscriptionRequest {
     pub message: InscriptionMessage,
+    /// Messages packed into the same reveal tx after `message`.
+    #[serde(default)]
+    pub packed_messages: Vec<InscriptionMessage>,
     pub inscriber_output: InscriberOutput,
     pub fee_payer_ctx: FeePayerCtx,
     pub commit_tx_input: CommitTxInput,
+    /// Reveal txids this inscription was broadcast with before being replaced by a fee bump.
+    #[serde(default)]
+    pub replaced_reveal_txids: Vec<Txid>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -435,6 +621,9 @@ pub enum BitcoinError {
     #[error("Uncompressed public key error: {0}")]
     UncompressedPublicKeyError(String),
 
+    #[error("Not found: {0}")]
+    NotFound(String),
+
     #[error("Other error: {0}")]
     Other(String),
 }
@@ -0,0 +1,162 @@
+//! Versioned binary encoding of the Via protocol messages exchanged off-chain: the inscription
+//! messages stored by the BTC senders, the signing sessions served by the coordinator and the
+//! bridge transactions they carry.
+//!
+//! An encoded message is `WIRE_MAGIC || version || payload`. The version 1 payload is bincode with
+//! fixed-size little-endian integers (`u64` for lengths, `u32` for enum variants), no trailing
+//! bytes and at most [`MAX_PAYLOAD_SIZE`] bytes. Decoding never panics, malformed input is
+//! reported as a [`WireFormatError`].
+//!
+//! Messages written before the versioned encoding have no header and are decoded as version 1
+//! payloads. They start with an enum variant index or a small length, never with the magic byte.
+//!
+//! The inscriptions themselves carry the version of their envelope layout on-chain, see
+//! [`crate::types::VIA_INSCRIPTION_ENVELOPE_VERSION`].
+
+use bincode::Options;
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+/// First byte of every versioned message.
+pub const WIRE_MAGIC: u8 = b'V';
+/// Version of the encoding produced by [`encode`].
+pub const WIRE_VERSION: u8 = 1;
+/// Upper bound of a decoded payload, so a corrupted length prefix can't exhaust the memory.
+pub const MAX_PAYLOAD_SIZE: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum WireFormatError {
+    #[error("Unsupported wire format version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Malformed payload: {0}")]
+    Malformed(#[from] bincode::Error),
+    #[error("Invalid message: {0}")]
+    InvalidMessage(String),
+}
+
+pub type WireFormatResult<T> = Result<T, WireFormatError>;
+
+fn payload_options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_little_endian()
+        .reject_trailing_bytes()
+        .with_limit(MAX_PAYLOAD_SIZE)
+}
+
+/// Encodes `value` with the current version of the wire format.
+pub fn encode<T: Serialize>(value: &T) -> WireFormatResult<Vec<u8>> {
+    let mut bytes = vec![WIRE_MAGIC, WIRE_VERSION];
+    payload_options().serialize_into(&mut bytes, value)?;
+    Ok(bytes)
+}
+
+/// Decodes a message encoded with any supported version of the wire format.
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> WireFormatResult<T> {
+    match bytes {
+        [WIRE_MAGIC, WIRE_VERSION, payload @ ..] => Ok(payload_options().deserialize(payload)?),
+        [WIRE_MAGIC, version, ..] => Err(WireFormatError::UnsupportedVersion(*version)),
+        // Legacy message, without header.
+        _ => Ok(payload_options().deserialize(bytes)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, Txid};
+    use zksync_types::{L1BatchNumber, H256};
+
+    use super::*;
+    use crate::{
+        traits::Serializable,
+        types::{InscriptionMessage, L1BatchDAReferenceInput, ProofDAReferenceInput},
+    };
+
+    fn messages() -> Vec<InscriptionMessage> {
+        vec![
+            InscriptionMessage::L1BatchDAReference(L1BatchDAReferenceInput {
+                l1_batch_hash: H256::repeat_byte(1),
+                l1_batch_index: L1BatchNumber(10),
+                da_identifier: "celestia".to_string(),
+                blob_id: "blob".to_string(),
+                prev_l1_batch_hash: H256::repeat_byte(2),
+            }),
+            InscriptionMessage::ProofDAReference(ProofDAReferenceInput {
+                l1_batch_reveal_txid: Txid::from_byte_array([3; 32]),
+                da_identifier: "celestia".to_string(),
+                blob_id: "proof".to_string(),
+                next_l1_batch_reveal_txids: vec![Txid::from_byte_array([4; 32])],
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for message in messages() {
+            let bytes = message.to_bytes().unwrap();
+            assert_eq!(&bytes[..2], &[WIRE_MAGIC, WIRE_VERSION]);
+            assert_eq!(InscriptionMessage::from_bytes(&bytes).unwrap(), message);
+        }
+
+        let packed = messages();
+        let bytes = packed.to_bytes().unwrap();
+        assert_eq!(
+            Vec::<InscriptionMessage>::from_bytes(&bytes).unwrap(),
+            packed
+        );
+    }
+
+    #[test]
+    fn test_decode_legacy_message() {
+        for message in messages() {
+            let legacy = bincode::serialize(&message).unwrap();
+            assert_eq!(InscriptionMessage::from_bytes(&legacy).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_decode_proof_da_reference_without_range() {
+        // A proof DA reference stored before the proofs of L1 batch ranges.
+        let txid = Txid::from_byte_array([3; 32]);
+        let legacy = (1_u32, txid, "celestia".to_string(), "proof".to_string());
+        let expected = InscriptionMessage::ProofDAReference(ProofDAReferenceInput {
+            l1_batch_reveal_txid: txid,
+            da_identifier: "celestia".to_string(),
+            blob_id: "proof".to_string(),
+            next_l1_batch_reveal_txids: vec![],
+        });
+
+        let bytes = encode(&legacy).unwrap();
+        assert_eq!(InscriptionMessage::from_bytes(&bytes).unwrap(), expected);
+        let bytes = encode(&vec![legacy]).unwrap();
+        assert_eq!(
+            Vec::<InscriptionMessage>::from_bytes(&bytes).unwrap(),
+            vec![expected]
+        );
+    }
+
+    #[test]
+    fn test_decode_malformed_message() {
+        let bytes = messages()[0].to_bytes().unwrap();
+
+        // Truncated payloads and trailing bytes.
+        for len in 0..bytes.len() {
+            assert!(InscriptionMessage::from_bytes(&bytes[..len]).is_err());
+        }
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(InscriptionMessage::from_bytes(&trailing).is_err());
+
+        // Unknown version.
+        let mut future = bytes.clone();
+        future[1] = WIRE_VERSION + 1;
+        assert!(matches!(
+            InscriptionMessage::from_bytes(&future),
+            Err(WireFormatError::UnsupportedVersion(_))
+        ));
+
+        // Huge length prefix.
+        let huge_vec = [&[WIRE_MAGIC, WIRE_VERSION][..], &u64::MAX.to_le_bytes()].concat();
+        assert!(Vec::<InscriptionMessage>::from_bytes(&huge_vec).is_err());
+    }
+}
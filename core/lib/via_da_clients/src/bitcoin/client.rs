@@ -0,0 +1,448 @@
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use bitcoin::hashes::Hash;
+use tokio::sync::Mutex;
+use via_btc_client::{
+    indexer::MessageParser,
+    inscriber::Inscriber,
+    traits::BitcoinOps,
+    types::{BitcoinTxid, DABlobChunkInput, FullInscriptionMessage, InscriptionMessage},
+};
+use zksync_da_client::types::InvalidBlobError;
+pub use zksync_da_client::{types, DataAvailabilityClient};
+use zksync_types::{web3::keccak256, H256};
+
+use crate::dispatch_cache::DispatchCache;
+
+/// Max size of a chunk, each chunk is inscribed in its own reveal tx which has to stay below the
+/// standard transaction weight.
+const CHUNK_SIZE: usize = 200_000;
+
+/// Max number of chunks of a blob. The commit and reveal txs of the chunks are chained, so they
+/// have to stay below the mempool limit of 25 unconfirmed ancestors.
+pub(crate) const MAX_CHUNKS_PER_BLOB: usize = 10;
+
+const BLOB_HASH_SIZE: usize = 32;
+const TXID_SIZE: usize = 32;
+
+/// An implementation of the `DataAvailabilityClient` trait that stores the blobs on Bitcoin, as
+/// chunks inscribed by the DA inscriber wallet.
+#[derive(Clone)]
+pub struct BitcoinDAClient {
+    client: Arc<dyn BitcoinOps>,
+    /// Only set on the nodes dispatching the blobs, the other ones only read them.
+    inscriber: Option<Arc<Mutex<Inscriber>>>,
+    /// The reveal txids of the chunks inscribed for each pending blob.
+    inscribed_chunks: DispatchCache<BitcoinTxid>,
+}
+
+impl BitcoinDAClient {
+    pub fn new(client: Arc<dyn BitcoinOps>, inscriber: Option<Inscriber>) -> Self {
+        Self {
+            client,
+            inscriber: inscriber.map(|inscriber| Arc::new(Mutex::new(inscriber))),
+            inscribed_chunks: DispatchCache::default(),
+        }
+    }
+
+    /// Fetches the reveal tx `txid` and returns the data of the chunk `chunk_index` of the blob.
+    async fn fetch_chunk(
+        &self,
+        txid: &BitcoinTxid,
+        blob_hash: H256,
+        chunk_index: u32,
+    ) -> Result<Vec<u8>, types::DAError> {
+        let tx = self
+            .client
+            .get_transaction(txid)
+            .await
+            .map_err(|error| types::DAError {
+                error: error.into(),
+                is_retriable: true,
+            })?;
+
+        let mut parser = MessageParser::new(self.client.get_network());
+        parser
+            .parse_system_transaction(&tx, 0, None)
+            .into_iter()
+            .find_map(|message| match message {
+                FullInscriptionMessage::DABlobChunk(chunk)
+                    if chunk.input.blob_hash == blob_hash
+                        && chunk.input.chunk_index == chunk_index =>
+                {
+                    Some(chunk.input.data)
+                }
+                _ => None,
+            })
+            .ok_or_else(|| types::DAError {
+                error: anyhow!("Chunk {chunk_index} of blob {blob_hash:?} not found in tx {txid}"),
+                is_retriable: false,
+            })
+    }
+}
+
+/// [32]byte blob hash ++ [32]byte reveal txid of each chunk
+pub(crate) fn encode_blob_id(blob_hash: H256, reveal_txids: &[BitcoinTxid]) -> String {
+    let mut blob_id = Vec::with_capacity(BLOB_HASH_SIZE + TXID_SIZE * reveal_txids.len());
+    blob_id.extend_from_slice(blob_hash.as_bytes());
+    for reveal_txid in reveal_txids {
+        blob_id.extend_from_slice(reveal_txid.as_raw_hash().as_byte_array());
+    }
+    hex::encode(blob_id)
+}
+
+#[async_trait]
+impl DataAvailabilityClient for BitcoinDAClient {
+    async fn dispatch_blob(
+        &self,
+        batch_number: u32,
+        data: Vec<u8>,
+    ) -> Result<types::DispatchResponse, types::DAError> {
+        let inscriber = self.inscriber.as_ref().ok_or_else(|| types::DAError {
+            error: anyhow!("The Bitcoin DA client has no DA inscriber wallet"),
+            is_retriable: false,
+        })?;
+
+        if data.len() > CHUNK_SIZE * MAX_CHUNKS_PER_BLOB {
+            return Err(types::DAError {
+                error: anyhow!("Blob of {} bytes exceeds the size limit", data.len()),
+                is_retriable: false,
+            });
+        }
+
+        let blob_hash = H256(keccak256(&data));
+        // A retry only inscribes the chunks missing after the previous attempt.
+        let mut reveal_txids = self.inscribed_chunks.published_parts(blob_hash);
+
+        let mut inscriber = inscriber.lock().await;
+        for (chunk_index, chunk) in data.chunks(CHUNK_SIZE).enumerate().skip(reveal_txids.len()) {
+            let info = inscriber
+                .inscribe(InscriptionMessage::DABlobChunk(DABlobChunkInput {
+                    blob_hash,
+                    chunk_index: chunk_index as u32,
+                    data: chunk.to_vec(),
+                }))
+                .await
+                .map_err(|error| types::DAError {
+                    error,
+                    is_retriable: true,
+                })?;
+
+            let reveal_txid = info.final_reveal_tx.txid;
+            tracing::info!(
+                "Inscribed chunk {chunk_index} of the blob of batch {batch_number} in tx {reveal_txid}"
+            );
+            self.inscribed_chunks.push_part(blob_hash, reveal_txid);
+            reveal_txids.push(reveal_txid);
+        }
+        self.inscribed_chunks.remove(blob_hash);
+
+        Ok(types::DispatchResponse {
+            blob_id: encode_blob_id(blob_hash, &reveal_txids),
+        })
+    }
+
+    async fn get_inclusion_data(
+        &self,
+        blob_id: &str,
+    ) -> Result<Option<types::InclusionData>, types::DAError> {
+        // [32]byte blob hash ++ [32]byte reveal txid of each chunk
+        let blob_id_bytes = hex::decode(blob_id).map_err(|error| types::DAError {
+            error: error.into(),
+            is_retriable: false,
+        })?;
+
+        if blob_id_bytes.len() < BLOB_HASH_SIZE
+            || (blob_id_bytes.len() - BLOB_HASH_SIZE) % TXID_SIZE != 0
+        {
+            return Err(types::DAError {
+                error: anyhow!("Invalid blob id length {}", blob_id_bytes.len()),
+                is_retriable: false,
+            });
+        }
+        let (blob_hash, txids) = blob_id_bytes.split_at(BLOB_HASH_SIZE);
+        let blob_hash = H256::from_slice(blob_hash);
+
+        let mut data = Vec::new();
+        for (chunk_index, txid) in txids.chunks(TXID_SIZE).enumerate() {
+            let txid = BitcoinTxid::from_slice(txid).map_err(|error| types::DAError {
+                error: error.into(),
+                is_retriable: false,
+            })?;
+            let chunk = self
+                .fetch_chunk(&txid, blob_hash, chunk_index as u32)
+                .await?;
+            data.extend(chunk);
+        }
+
+        if H256(keccak256(&data)) != blob_hash {
+            return Err(types::DAError {
+                error: InvalidBlobError(format!(
+                    "the chunks don't match the blob hash {blob_hash:?}"
+                ))
+                .into(),
+                is_retriable: false,
+            });
+        }
+
+        Ok(Some(types::InclusionData { data }))
+    }
+
+    fn clone_boxed(&self) -> Box<dyn DataAvailabilityClient> {
+        Box::new(self.clone())
+    }
+
+    fn blob_size_limit(&self) -> Option<usize> {
+        Some(CHUNK_SIZE * MAX_CHUNKS_PER_BLOB)
+    }
+}
+
+impl Debug for BitcoinDAClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BitcoinDAClient")
+            .field("can_dispatch", &self.inscriber.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bitcoin::{
+        consensus::encode::deserialize_hex, Address, Block, BlockHash, Network, OutPoint,
+        Transaction, TxOut,
+    };
+    use bitcoincore_rpc::json::GetBlockStatsResult;
+    use via_btc_client::{
+        inscriber::test_utils::{get_mock_inscriber, MockBitcoinOps, MockBitcoinOpsConfig},
+        types::{BitcoinClientResult, BitcoinError, MAX_DA_REFERENCE_BLOB_ID_SIZE},
+    };
+
+    use super::*;
+
+    /// Bitcoin node keeping the broadcast transactions, rejecting the broadcasts once
+    /// `remaining_broadcasts` reaches zero.
+    #[derive(Debug)]
+    struct MockBitcoinNode {
+        inner: MockBitcoinOps,
+        transactions: std::sync::Mutex<HashMap<BitcoinTxid, Transaction>>,
+        remaining_broadcasts: std::sync::Mutex<Option<usize>>,
+    }
+
+    impl MockBitcoinNode {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                inner: MockBitcoinOps::new(MockBitcoinOpsConfig {
+                    fee_rate: 1,
+                    ..Default::default()
+                }),
+                transactions: Default::default(),
+                remaining_broadcasts: Default::default(),
+            })
+        }
+
+        fn broadcasts(&self) -> usize {
+            self.transactions.lock().unwrap().len()
+        }
+    }
+
+    #[async_trait]
+    impl BitcoinOps for MockBitcoinNode {
+        async fn get_balance(&self, address: &Address) -> BitcoinClientResult<u128> {
+            self.inner.get_balance(address).await
+        }
+
+        async fn broadcast_signed_transaction(
+            &self,
+            signed_transaction: &str,
+        ) -> BitcoinClientResult<BitcoinTxid> {
+            if let Some(remaining_broadcasts) = self.remaining_broadcasts.lock().unwrap().as_mut() {
+                if *remaining_broadcasts == 0 {
+                    return Err(BitcoinError::Rpc("Broadcast rejected".to_string()));
+                }
+                *remaining_broadcasts -= 1;
+            }
+
+            let tx: Transaction = deserialize_hex(signed_transaction)
+                .map_err(|error| BitcoinError::InvalidTransaction(error.to_string()))?;
+            let txid = tx.compute_txid();
+            self.transactions.lock().unwrap().insert(txid, tx);
+            Ok(txid)
+        }
+
+        async fn fetch_utxos(
+            &self,
+            address: &Address,
+        ) -> BitcoinClientResult<Vec<(OutPoint, TxOut)>> {
+            self.inner.fetch_utxos(address).await
+        }
+
+        async fn check_tx_confirmation(
+            &self,
+            txid: &BitcoinTxid,
+            conf_num: u32,
+        ) -> BitcoinClientResult<bool> {
+            self.inner.check_tx_confirmation(txid, conf_num).await
+        }
+
+        async fn fetch_block_height(&self) -> BitcoinClientResult<u64> {
+            self.inner.fetch_block_height().await
+        }
+
+        async fn get_fee_rate(&self, conf_target: u16) -> BitcoinClientResult<u64> {
+            self.inner.get_fee_rate(conf_target).await
+        }
+
+        fn get_network(&self) -> Network {
+            self.inner.get_network()
+        }
+
+        async fn fetch_block(&self, block_height: u128) -> BitcoinClientResult<Block> {
+            self.inner.fetch_block(block_height).await
+        }
+
+        async fn get_transaction(&self, txid: &BitcoinTxid) -> BitcoinClientResult<Transaction> {
+            self.transactions
+                .lock()
+                .unwrap()
+                .get(txid)
+                .cloned()
+                .ok_or_else(|| BitcoinError::Rpc(format!("Transaction {txid} not found")))
+        }
+
+        async fn fetch_block_by_hash(&self, block_hash: &BlockHash) -> BitcoinClientResult<Block> {
+            self.inner.fetch_block_by_hash(block_hash).await
+        }
+
+        async fn get_block_stats(&self, height: u64) -> BitcoinClientResult<GetBlockStatsResult> {
+            self.inner.get_block_stats(height).await
+        }
+
+        async fn get_fee_history(
+            &self,
+            from_block_height: usize,
+            to_block_height: usize,
+        ) -> BitcoinClientResult<Vec<u64>> {
+            self.inner
+                .get_fee_history(from_block_height, to_block_height)
+                .await
+        }
+    }
+
+    fn da_client(node: &Arc<MockBitcoinNode>) -> BitcoinDAClient {
+        BitcoinDAClient::new(node.clone(), Some(get_mock_inscriber(node.clone())))
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_and_fetch_chunked_blob() {
+        let node = MockBitcoinNode::new();
+        let client = da_client(&node);
+        let data: Vec<u8> = (0..CHUNK_SIZE + 100).map(|i| i as u8).collect();
+
+        let response = client.dispatch_blob(1, data.clone()).await.unwrap();
+        // A commit and a reveal tx per chunk.
+        assert_eq!(node.broadcasts(), 4);
+        assert_eq!(response.blob_id.len(), 2 * (BLOB_HASH_SIZE + 2 * TXID_SIZE));
+
+        // The blobs are read without the DA inscriber wallet.
+        let inclusion_data = BitcoinDAClient::new(node.clone(), None)
+            .get_inclusion_data(&response.blob_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(inclusion_data.data, data);
+    }
+
+    #[tokio::test]
+    async fn test_retried_dispatch_resumes_after_inscribed_chunks() {
+        let node = MockBitcoinNode::new();
+        let client = da_client(&node);
+        let data: Vec<u8> = (0..2 * CHUNK_SIZE + 100).map(|i| i as u8).collect();
+
+        let blob_hash = H256(keccak256(&data));
+
+        // Only the first chunk is inscribed.
+        *node.remaining_broadcasts.lock().unwrap() = Some(2);
+        let error = client.dispatch_blob(1, data.clone()).await.unwrap_err();
+        assert!(error.is_retriable());
+        let inscribed_chunks = client.inscribed_chunks.published_parts(blob_hash);
+        assert_eq!(inscribed_chunks.len(), 1);
+        let first_chunk_txid = inscribed_chunks[0];
+
+        *node.remaining_broadcasts.lock().unwrap() = None;
+        let response = client.dispatch_blob(1, data.clone()).await.unwrap();
+        // The first chunk isn't inscribed again.
+        assert_eq!(node.broadcasts(), 6);
+        assert!(response
+            .blob_id
+            .starts_with(&encode_blob_id(blob_hash, &[first_chunk_txid])));
+        assert!(client
+            .inscribed_chunks
+            .published_parts(blob_hash)
+            .is_empty());
+
+        let inclusion_data = client
+            .get_inclusion_data(&response.blob_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(inclusion_data.data, data);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_fails_without_inscriber() {
+        let node = MockBitcoinNode::new();
+        let client = BitcoinDAClient::new(node.clone(), None);
+
+        let error = client.dispatch_blob(1, vec![1, 2, 3]).await.unwrap_err();
+        assert!(!error.is_retriable());
+        assert_eq!(node.broadcasts(), 0);
+
+        let error = da_client(&node)
+            .dispatch_blob(1, vec![0; CHUNK_SIZE * MAX_CHUNKS_PER_BLOB + 1])
+            .await
+            .unwrap_err();
+        assert!(!error.is_retriable());
+        assert_eq!(node.broadcasts(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_inclusion_data_rejects_invalid_blobs() {
+        let node = MockBitcoinNode::new();
+        let client = da_client(&node);
+        let response = client.dispatch_blob(1, vec![1, 2, 3]).await.unwrap();
+
+        let error = client
+            .get_inclusion_data(&response.blob_id[..2 * BLOB_HASH_SIZE + 2])
+            .await
+            .unwrap_err();
+        assert!(!error.is_retriable());
+
+        // The chunk doesn't match another blob hash.
+        let mut blob_id = hex::decode(&response.blob_id).unwrap();
+        blob_id[0] ^= 1;
+        let error = client
+            .get_inclusion_data(&hex::encode(blob_id))
+            .await
+            .unwrap_err();
+        assert!(!error.is_retriable());
+    }
+
+    #[test]
+    fn test_max_size_blob_id_fits_in_da_reference() {
+        let reveal_txids = vec![BitcoinTxid::from_byte_array([1; 32]); MAX_CHUNKS_PER_BLOB];
+        let blob_id = encode_blob_id(H256::repeat_byte(2), &reveal_txids);
+
+        assert_eq!(
+            blob_id.len(),
+            2 * (BLOB_HASH_SIZE + TXID_SIZE * MAX_CHUNKS_PER_BLOB)
+        );
+        assert!(blob_id.len() <= MAX_DA_REFERENCE_BLOB_ID_SIZE);
+    }
+}
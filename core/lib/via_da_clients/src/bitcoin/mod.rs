@@ -0,0 +1,5 @@
+/// The `da_identifier` of the blobs stored on Bitcoin.
+pub const DA_IDENTIFIER: &str = "bitcoin";
+
+pub mod client;
+pub mod wiring_layer;
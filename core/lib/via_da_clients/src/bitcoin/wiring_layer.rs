@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use via_btc_client::{client::BitcoinClient, inscriber::Inscriber, signer::signer_from_wallet};
+use zksync_config::configs::{
+    via_btc_client::ViaBtcClientConfig, via_secrets::ViaL1Secrets, via_wallets::ViaWallet,
+};
+use zksync_da_client::DataAvailabilityClient;
+use zksync_node_framework::{
+    implementations::resources::da_client::DAClientResource,
+    wiring_layer::{WiringError, WiringLayer},
+    IntoContext,
+};
+
+use crate::bitcoin::client::BitcoinDAClient;
+
+/// Wiring layer for the DA client storing the blobs on Bitcoin. Without `wallet` the client only
+/// reads the blobs.
+#[derive(Debug)]
+pub struct ViaBitcoinDAClientWiringLayer {
+    config: ViaBtcClientConfig,
+    secrets: ViaL1Secrets,
+    wallet: Option<ViaWallet>,
+    keystore_password: Option<String>,
+}
+
+impl ViaBitcoinDAClientWiringLayer {
+    pub fn new(
+        config: ViaBtcClientConfig,
+        secrets: ViaL1Secrets,
+        wallet: Option<ViaWallet>,
+    ) -> Self {
+        Self {
+            config,
+            secrets,
+            wallet,
+            keystore_password: None,
+        }
+    }
+
+    /// Sets the password unlocking the keystore of the wallet, from `ViaKeystoreSecrets`.
+    pub fn with_keystore_password(mut self, keystore_password: Option<String>) -> Self {
+        self.keystore_password = keystore_password;
+        self
+    }
+
+    pub(crate) async fn create_client(self) -> anyhow::Result<BitcoinDAClient> {
+        let client = BitcoinClient::new(
+            self.secrets.rpc_url.expose_str(),
+            self.secrets.auth_node(),
+            self.config.clone(),
+        )?;
+
+        let inscriber = match self.wallet {
+            Some(wallet) => {
+                let inscriber_client = BitcoinClient::new(
+                    &self.config.rpc_url(
+                        self.secrets.rpc_url.expose_str().to_string(),
+                        wallet.address.clone(),
+                    ),
+                    self.secrets.auth_node(),
+                    self.config.clone(),
+                )?;
+                let inscriber_client = Arc::new(inscriber_client);
+
+                let signer = signer_from_wallet(
+                    &wallet,
+                    self.keystore_password.as_deref(),
+                    inscriber_client.config.network(),
+                )
+                .await
+                .context("Error init DA inscriber signer")?;
+                let inscriber = Inscriber::with_signer(inscriber_client, signer, None)
+                    .await
+                    .context("Error init DA inscriber")?;
+                Some(inscriber)
+            }
+            None => None,
+        };
+
+        Ok(BitcoinDAClient::new(Arc::new(client), inscriber))
+    }
+}
+
+#[derive(Debug, IntoContext)]
+pub struct Output {
+    pub client: DAClientResource,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ViaBitcoinDAClientWiringLayer {
+    type Input = ();
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "via_da_layer"
+    }
+
+    async fn wire(self, _input: Self::Input) -> Result<Self::Output, WiringError> {
+        let client = self.create_client().await?;
+        let client: Box<dyn DataAvailabilityClient> = Box::new(client);
+
+        Ok(Output {
+            client: DAClientResource(client),
+        })
+    }
+}
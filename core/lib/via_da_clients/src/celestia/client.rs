@@ -5,25 +5,42 @@ use std::{
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use celestia_rpc::{BlobClient, Client, P2PClient};
+use celestia_rpc::{BlobClient, Client, HeaderClient, P2PClient};
 use celestia_types::{nmt::Namespace, Blob, Commitment, TxConfig};
 use hex;
 use zksync_config::configs::via_secrets::ViaDASecrets;
 pub use zksync_config::ViaCelestiaConfig;
+use zksync_da_client::types::InvalidBlobError;
 pub use zksync_da_client::{types, DataAvailabilityClient};
-use zksync_types::url::SensitiveUrl;
+use zksync_types::{url::SensitiveUrl, web3::keccak256, H256};
+
+use crate::{
+    celestia::manifest::{BlobManifest, BlobRef, MANIFEST_MAGIC},
+    dispatch_cache::DispatchCache,
+};
 
 /// If no value is provided for GasPrice, then this will be serialized to `-1.0` which means the node that
 /// receives the request will calculate the GasPrice for given blob.
 const GAS_PRICE: f64 = -1.0;
 
+/// Max number of blobs a payload above the blob size limit is split into. Each blob is submitted in
+/// its own Celestia transaction, followed by the manifest referencing them.
+const MAX_BLOBS_PER_PAYLOAD: usize = 16;
+
 /// An implementation of the `DataAvailabilityClient` trait that stores the pubdata in the Celestia DA.
+/// Payloads above the blob size limit are split into several blobs and a manifest blob, whose id is
+/// returned as the `blob_id` of the payload.
 #[derive(Clone)]
 pub struct CelestiaClient {
     light_node_url: SensitiveUrl,
     inner: Arc<Client>,
     blob_size_limit: usize,
     namespace: Namespace,
+    /// Whether the fetched blobs are checked against their commitment and inclusion proof instead of
+    /// trusting the node.
+    verify_inclusion_proofs: bool,
+    /// The blobs submitted for each pending payload split into several blobs.
+    submitted_blobs: DispatchCache<BlobRef>,
 }
 
 impl CelestiaClient {
@@ -47,34 +64,32 @@ impl CelestiaClient {
             inner: Arc::new(client),
             blob_size_limit,
             namespace,
+            verify_inclusion_proofs: false,
+            submitted_blobs: DispatchCache::default(),
         })
     }
-}
 
-#[async_trait]
-impl DataAvailabilityClient for CelestiaClient {
-    async fn dispatch_blob(
-        &self,
-        _batch_number: u32,
-        data: Vec<u8>,
-    ) -> Result<types::DispatchResponse, types::DAError> {
+    pub fn with_inclusion_proof_verification(mut self) -> Self {
+        self.verify_inclusion_proofs = true;
+        self
+    }
+
+    async fn submit_blob(&self, data: Vec<u8>) -> Result<BlobRef, types::DAError> {
         let share_version = celestia_types::consts::appconsts::SHARE_VERSION_ZERO;
 
-        let blob = Blob::new(self.namespace, data.clone()).map_err(|error| types::DAError {
+        let commitment =
+            Commitment::from_blob(self.namespace, share_version, &data).map_err(|error| {
+                types::DAError {
+                    error: error.into(),
+                    is_retriable: false,
+                }
+            })?;
+
+        let blob = Blob::new(self.namespace, data).map_err(|error| types::DAError {
             error: error.into(),
             is_retriable: false,
         })?;
 
-        let commitment_result = match Commitment::from_blob(self.namespace, share_version, &data) {
-            Ok(commit) => commit,
-            Err(error) => {
-                return Err(types::DAError {
-                    error: error.into(),
-                    is_retriable: false,
-                })
-            }
-        };
-
         // NOTE: during refactoring add address to the config
         // we can specify the sender address for the transaction with using TxConfig
         let tx_config = TxConfig {
@@ -82,7 +97,7 @@ impl DataAvailabilityClient for CelestiaClient {
             ..Default::default()
         };
 
-        let block_hight = self
+        let block_height = self
             .inner
             .blob_submit(&[blob], tx_config)
             .await
@@ -91,17 +106,185 @@ impl DataAvailabilityClient for CelestiaClient {
                 is_retriable: true,
             })?;
 
-        // [8]byte block height ++ [32]byte commitment
-        let mut blob_id = Vec::with_capacity(8 + 32);
-        blob_id.extend_from_slice(&block_hight.to_be_bytes());
-        blob_id.extend_from_slice(&commitment_result.0);
+        Ok(BlobRef {
+            block_height,
+            commitment: commitment.0,
+        })
+    }
 
-        // Convert blob_id to a hex string
-        let blob_id_str = hex::encode(blob_id);
+    async fn get_blob(&self, blob_ref: BlobRef) -> Result<Vec<u8>, types::DAError> {
+        let blob = self
+            .inner
+            .blob_get(
+                blob_ref.block_height,
+                self.namespace,
+                Commitment(blob_ref.commitment),
+            )
+            .await
+            .map_err(|error| types::DAError {
+                error: error.into(),
+                is_retriable: true,
+            })?;
+
+        if self.verify_inclusion_proofs {
+            self.verify_inclusion(blob_ref, &blob).await?;
+        }
 
-        return Ok(types::DispatchResponse {
-            blob_id: blob_id_str,
-        });
+        Ok(blob.data)
+    }
+
+    /// Checks `blob` against the commitment of `blob_ref`, and its shares against the inclusion
+    /// proof and the row roots of the Celestia header at the blob height.
+    ///
+    /// The header is fetched from the same node as the blob and is only checked to be internally
+    /// consistent, it's not verified against a trusted header. The commitment check against
+    /// `blob_ref` is the only guarantee that the blob is the one published by the sequencer, the
+    /// inclusion proof only detects a node serving inconsistent data.
+    async fn verify_inclusion(&self, blob_ref: BlobRef, blob: &Blob) -> Result<(), types::DAError> {
+        let invalid_blob = |reason: String| types::DAError {
+            error: InvalidBlobError(reason).into(),
+            is_retriable: false,
+        };
+        let share_version = celestia_types::consts::appconsts::SHARE_VERSION_ZERO;
+
+        let commitment = Commitment::from_blob(self.namespace, share_version, &blob.data)
+            .map_err(|error| invalid_blob(format!("failed to compute the commitment: {error}")))?;
+        if blob.namespace != self.namespace || commitment.0 != blob_ref.commitment {
+            return Err(invalid_blob(format!(
+                "the blob at height {} doesn't match its commitment",
+                blob_ref.block_height
+            )));
+        }
+
+        let header = self
+            .inner
+            .header_get_by_height(blob_ref.block_height)
+            .await
+            .map_err(|error| types::DAError {
+                error: error.into(),
+                is_retriable: true,
+            })?;
+        // Checks the data availability header against the data root of the header, both coming
+        // from the untrusted node.
+        header
+            .validate()
+            .map_err(|error| invalid_blob(format!("invalid header: {error}")))?;
+        if header.height().value() != blob_ref.block_height {
+            return Err(invalid_blob(format!(
+                "got the header at height {} instead of {}",
+                header.height(),
+                blob_ref.block_height
+            )));
+        }
+
+        let proofs = self
+            .inner
+            .blob_get_proof(
+                blob_ref.block_height,
+                self.namespace,
+                Commitment(blob_ref.commitment),
+            )
+            .await
+            .map_err(|error| types::DAError {
+                error: error.into(),
+                is_retriable: true,
+            })?;
+
+        let shares = blob.to_shares().map_err(|error| {
+            invalid_blob(format!("failed to split the blob in shares: {error}"))
+        })?;
+
+        // Each proof covers the shares of the blob in one row of the data square, the rows being
+        // consecutive.
+        let row_roots = &header.dah.row_roots;
+        let mut next_row = None;
+        let mut remaining_shares = shares.as_slice();
+        for proof in &proofs {
+            let shares_in_row = (proof.end_idx() - proof.start_idx()) as usize;
+            if shares_in_row == 0 || shares_in_row > remaining_shares.len() {
+                return Err(invalid_blob(
+                    "the proof doesn't cover the blob shares".into(),
+                ));
+            }
+            let (row_shares, rest) = remaining_shares.split_at(shares_in_row);
+
+            let candidate_rows = match next_row {
+                Some(row) => row..(row + 1).min(row_roots.len()),
+                None => 0..row_roots.len(),
+            };
+            let row = candidate_rows
+                .find(|&row| {
+                    proof
+                        .verify_range(&row_roots[row], row_shares, *self.namespace)
+                        .is_ok()
+                })
+                .ok_or_else(|| {
+                    invalid_blob(format!(
+                        "the blob shares are not included in the data root at height {}",
+                        blob_ref.block_height
+                    ))
+                })?;
+
+            next_row = Some(row + 1);
+            remaining_shares = rest;
+        }
+
+        if !remaining_shares.is_empty() {
+            return Err(invalid_blob(
+                "the proof doesn't cover the blob shares".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for CelestiaClient {
+    async fn dispatch_blob(
+        &self,
+        batch_number: u32,
+        data: Vec<u8>,
+    ) -> Result<types::DispatchResponse, types::DAError> {
+        if data.len() > self.blob_size_limit * MAX_BLOBS_PER_PAYLOAD {
+            return Err(types::DAError {
+                error: anyhow!("Payload of {} bytes exceeds the size limit", data.len()),
+                is_retriable: false,
+            });
+        }
+
+        // A payload starting like a manifest is wrapped in a manifest as well, so it can't be
+        // mistaken for one.
+        let blob_ref = if data.len() <= self.blob_size_limit && !data.starts_with(MANIFEST_MAGIC) {
+            self.submit_blob(data).await?
+        } else {
+            let payload_hash = H256(keccak256(&data));
+
+            // The blobs submitted before a failure are reused on retry.
+            let mut blobs = self.submitted_blobs.published_parts(payload_hash);
+            for chunk in data.chunks(self.blob_size_limit).skip(blobs.len()) {
+                let blob_ref = self.submit_blob(chunk.to_vec()).await?;
+                self.submitted_blobs.push_part(payload_hash, blob_ref);
+                blobs.push(blob_ref);
+            }
+
+            tracing::info!(
+                "Split the payload of batch {batch_number} into {} blobs",
+                blobs.len()
+            );
+
+            let manifest = BlobManifest {
+                payload_hash,
+                blobs,
+            };
+            let manifest_ref = self.submit_blob(manifest.encode()).await?;
+            self.submitted_blobs.remove(payload_hash);
+            manifest_ref
+        };
+
+        Ok(types::DispatchResponse {
+            blob_id: hex::encode(blob_ref.encode()),
+        })
     }
 
     async fn get_inclusion_data(
@@ -113,34 +296,38 @@ impl DataAvailabilityClient for CelestiaClient {
             error: error.into(),
             is_retriable: false,
         })?;
+        let blob_ref = BlobRef::decode(&blob_id_bytes).map_err(|error| types::DAError {
+            error,
+            is_retriable: false,
+        })?;
 
-        let block_height =
-            u64::from_be_bytes(blob_id_bytes[..8].try_into().map_err(|_| types::DAError {
-                error: anyhow!("Failed to convert block height"),
-                is_retriable: false,
-            })?);
+        let data = self.get_blob(blob_ref).await?;
 
-        let commitment_data: [u8; 32] =
-            blob_id_bytes[8..40]
-                .try_into()
-                .map_err(|_| types::DAError {
-                    error: anyhow!("Failed to convert commitment"),
-                    is_retriable: false,
-                })?;
-        let commitment = Commitment(commitment_data);
+        let manifest = BlobManifest::decode(&data).map_err(|error| types::DAError {
+            error,
+            is_retriable: false,
+        })?;
+        let Some(manifest) = manifest else {
+            return Ok(Some(types::InclusionData { data }));
+        };
 
-        let blob = self
-            .inner
-            .blob_get(block_height, self.namespace, commitment)
-            .await
-            .map_err(|error| types::DAError {
-                error: error.into(),
-                is_retriable: true,
-            })?;
+        let mut payload = Vec::new();
+        for blob_ref in manifest.blobs {
+            payload.extend(self.get_blob(blob_ref).await?);
+        }
 
-        let inclusion_data = types::InclusionData { data: blob.data };
+        if H256(keccak256(&payload)) != manifest.payload_hash {
+            return Err(types::DAError {
+                error: InvalidBlobError(format!(
+                    "the blobs don't match the payload hash {:?}",
+                    manifest.payload_hash
+                ))
+                .into(),
+                is_retriable: false,
+            });
+        }
 
-        Ok(Some(inclusion_data))
+        Ok(Some(types::InclusionData { data: payload }))
     }
 
     fn clone_boxed(&self) -> Box<dyn DataAvailabilityClient> {
@@ -148,7 +335,7 @@ impl DataAvailabilityClient for CelestiaClient {
     }
 
     fn blob_size_limit(&self) -> Option<usize> {
-        Some(self.blob_size_limit)
+        Some(self.blob_size_limit * MAX_BLOBS_PER_PAYLOAD)
     }
 }
 
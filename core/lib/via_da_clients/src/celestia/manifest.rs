@@ -0,0 +1,161 @@
+use anyhow::{ensure, Context};
+use zksync_types::H256;
+
+/// Prefix of the manifest blobs, used to tell them apart from the blobs holding a whole payload.
+pub(crate) const MANIFEST_MAGIC: &[u8; 8] = b"VIAMNFST";
+const MANIFEST_VERSION: u8 = 1;
+
+const BLOCK_HEIGHT_SIZE: usize = 8;
+const COMMITMENT_SIZE: usize = 32;
+const HASH_SIZE: usize = 32;
+const COUNT_SIZE: usize = 4;
+const HEADER_SIZE: usize = MANIFEST_MAGIC.len() + 1 + HASH_SIZE + COUNT_SIZE;
+
+/// Reference to a blob published on Celestia, also used as the `blob_id` of the client.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct BlobRef {
+    pub block_height: u64,
+    pub commitment: [u8; COMMITMENT_SIZE],
+}
+
+impl BlobRef {
+    /// [8]byte block height ++ [32]byte commitment
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(BLOCK_HEIGHT_SIZE + COMMITMENT_SIZE);
+        bytes.extend_from_slice(&self.block_height.to_be_bytes());
+        bytes.extend_from_slice(&self.commitment);
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> anyhow::Result<Self> {
+        ensure!(
+            bytes.len() == BLOCK_HEIGHT_SIZE + COMMITMENT_SIZE,
+            "Invalid blob reference length {}",
+            bytes.len()
+        );
+        let (block_height, commitment) = bytes.split_at(BLOCK_HEIGHT_SIZE);
+        Ok(Self {
+            block_height: u64::from_be_bytes(
+                block_height
+                    .try_into()
+                    .context("Failed to convert block height")?,
+            ),
+            commitment: commitment
+                .try_into()
+                .context("Failed to convert commitment")?,
+        })
+    }
+}
+
+/// Manifest of a payload split into several blobs, published as a blob of its own.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct BlobManifest {
+    /// keccak256 of the whole payload.
+    pub payload_hash: H256,
+    /// The blobs holding the payload, in order.
+    pub blobs: Vec<BlobRef>,
+}
+
+impl BlobManifest {
+    /// [8]byte magic ++ [1]byte version ++ [32]byte payload hash ++ [4]byte blob count ++ blob refs
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            HEADER_SIZE + self.blobs.len() * (BLOCK_HEIGHT_SIZE + COMMITMENT_SIZE),
+        );
+        bytes.extend_from_slice(MANIFEST_MAGIC);
+        bytes.push(MANIFEST_VERSION);
+        bytes.extend_from_slice(self.payload_hash.as_bytes());
+        bytes.extend_from_slice(&(self.blobs.len() as u32).to_be_bytes());
+        for blob in &self.blobs {
+            bytes.extend(blob.encode());
+        }
+        bytes
+    }
+
+    /// Returns `None` if `bytes` is not a manifest.
+    pub fn decode(bytes: &[u8]) -> anyhow::Result<Option<Self>> {
+        if !bytes.starts_with(MANIFEST_MAGIC) {
+            return Ok(None);
+        }
+        ensure!(
+            bytes.len() >= HEADER_SIZE,
+            "Invalid manifest length {}",
+            bytes.len()
+        );
+
+        let version = bytes[MANIFEST_MAGIC.len()];
+        ensure!(
+            version == MANIFEST_VERSION,
+            "Unsupported manifest version {version}"
+        );
+
+        let (header, blobs) = bytes.split_at(HEADER_SIZE);
+        let payload_hash = H256::from_slice(
+            &header[MANIFEST_MAGIC.len() + 1..MANIFEST_MAGIC.len() + 1 + HASH_SIZE],
+        );
+        let count = u32::from_be_bytes(
+            header[HEADER_SIZE - COUNT_SIZE..]
+                .try_into()
+                .context("Failed to convert blob count")?,
+        ) as usize;
+        ensure!(
+            count > 0 && blobs.len() == count * (BLOCK_HEIGHT_SIZE + COMMITMENT_SIZE),
+            "Invalid manifest, {count} blobs in {} bytes",
+            blobs.len()
+        );
+
+        let blobs = blobs
+            .chunks(BLOCK_HEIGHT_SIZE + COMMITMENT_SIZE)
+            .map(BlobRef::decode)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Some(Self {
+            payload_hash,
+            blobs,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let manifest = BlobManifest {
+            payload_hash: H256::repeat_byte(0xab),
+            blobs: vec![
+                BlobRef {
+                    block_height: 10,
+                    commitment: [1; 32],
+                },
+                BlobRef {
+                    block_height: 11,
+                    commitment: [2; 32],
+                },
+            ],
+        };
+
+        let decoded = BlobManifest::decode(&manifest.encode()).unwrap();
+        assert_eq!(decoded, Some(manifest));
+    }
+
+    #[test]
+    fn test_decode_not_a_manifest() {
+        assert_eq!(BlobManifest::decode(b"pubdata").unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode_truncated_manifest() {
+        let manifest = BlobManifest {
+            payload_hash: H256::zero(),
+            blobs: vec![BlobRef {
+                block_height: 1,
+                commitment: [0; 32],
+            }],
+        };
+        let bytes = manifest.encode();
+
+        assert!(BlobManifest::decode(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
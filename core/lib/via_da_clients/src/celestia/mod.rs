@@ -1,3 +1,7 @@
+/// The `da_identifier` of the blobs stored on Celestia.
+pub const DA_IDENTIFIER: &str = "celestia";
+
 pub mod client;
 pub mod config;
+pub(crate) mod manifest;
 pub mod wiring_layer;
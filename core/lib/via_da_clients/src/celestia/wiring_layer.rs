@@ -14,11 +14,32 @@ use crate::celestia::client::CelestiaClient;
 pub struct ViaCelestiaClientWiringLayer {
     config: ViaCelestiaConfig,
     secrets: ViaDASecrets,
+    verify_inclusion_proofs: bool,
 }
 
 impl ViaCelestiaClientWiringLayer {
     pub fn new(config: ViaCelestiaConfig, secrets: ViaDASecrets) -> Self {
-        Self { config, secrets }
+        Self {
+            config,
+            secrets,
+            verify_inclusion_proofs: false,
+        }
+    }
+
+    /// Checks the fetched blobs against their commitment and inclusion proof, used by the nodes that
+    /// don't trust the Celestia node. The Celestia header isn't verified against a trusted one.
+    pub fn with_inclusion_proof_verification(mut self) -> Self {
+        self.verify_inclusion_proofs = true;
+        self
+    }
+
+    pub(crate) async fn create_client(self) -> anyhow::Result<CelestiaClient> {
+        let client = CelestiaClient::new(self.secrets, self.config.blob_size_limit).await?;
+        Ok(if self.verify_inclusion_proofs {
+            client.with_inclusion_proof_verification()
+        } else {
+            client
+        })
     }
 }
 
@@ -37,7 +58,7 @@ impl WiringLayer for ViaCelestiaClientWiringLayer {
     }
 
     async fn wire(self, _input: Self::Input) -> Result<Self::Output, WiringError> {
-        let client = CelestiaClient::new(self.secrets, self.config.blob_size_limit).await?;
+        let client = self.create_client().await?;
         let client: Box<dyn DataAvailabilityClient> = Box::new(client);
 
         Ok(Output {
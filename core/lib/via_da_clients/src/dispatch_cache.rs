@@ -0,0 +1,82 @@
+//! Results of the parts of the payloads being dispatched, e.g. the chunks of a blob or the DA
+//! layers it's dispatched to. A dispatch failing halfway is retried by the DA dispatcher, which
+//! then resumes after the last published part instead of publishing the whole payload again.
+
+use std::sync::{Arc, Mutex};
+
+use zksync_types::H256;
+
+/// Max number of payloads whose dispatch didn't complete, the oldest one is dropped above it.
+const MAX_PENDING_PAYLOADS: usize = 16;
+
+/// The published parts of each pending payload, keyed by the payload hash.
+#[derive(Clone)]
+pub(crate) struct DispatchCache<T> {
+    payloads: Arc<Mutex<Vec<(H256, Vec<T>)>>>,
+}
+
+impl<T> Default for DispatchCache<T> {
+    fn default() -> Self {
+        Self {
+            payloads: Arc::default(),
+        }
+    }
+}
+
+impl<T: Clone> DispatchCache<T> {
+    /// Returns the parts of the payload published by the previous attempts, in order.
+    pub(crate) fn published_parts(&self, payload_hash: H256) -> Vec<T> {
+        self.payloads
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(hash, _)| *hash == payload_hash)
+            .map(|(_, parts)| parts.clone())
+            .unwrap_or_default()
+    }
+
+    /// Records the next published part of the payload.
+    pub(crate) fn push_part(&self, payload_hash: H256, part: T) {
+        let mut payloads = self.payloads.lock().unwrap();
+        match payloads.iter_mut().find(|(hash, _)| *hash == payload_hash) {
+            Some((_, parts)) => parts.push(part),
+            None => {
+                if payloads.len() >= MAX_PENDING_PAYLOADS {
+                    payloads.remove(0);
+                }
+                payloads.push((payload_hash, vec![part]));
+            }
+        }
+    }
+
+    /// Drops the parts of a payload once its dispatch completed.
+    pub(crate) fn remove(&self, payload_hash: H256) {
+        self.payloads
+            .lock()
+            .unwrap()
+            .retain(|(hash, _)| *hash != payload_hash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatch_cache_evicts_oldest_payload() {
+        let cache = DispatchCache::default();
+        for i in 0..=MAX_PENDING_PAYLOADS {
+            cache.push_part(H256::from_low_u64_be(i as u64), i);
+        }
+        cache.push_part(H256::from_low_u64_be(1), 100);
+
+        assert!(cache.published_parts(H256::from_low_u64_be(0)).is_empty());
+        assert_eq!(
+            cache.published_parts(H256::from_low_u64_be(1)),
+            vec![1, 100]
+        );
+
+        cache.remove(H256::from_low_u64_be(1));
+        assert!(cache.published_parts(H256::from_low_u64_be(1)).is_empty());
+    }
+}
@@ -1 +1,4 @@
+pub mod bitcoin;
 pub mod celestia;
+mod dispatch_cache;
+pub mod multi;
@@ -0,0 +1,123 @@
+use anyhow::{ensure, Context};
+use zksync_types::H256;
+
+const ENTRY_SEPARATOR: char = ',';
+const DA_SEPARATOR: char = ':';
+
+/// The `blob_id` of a blob dispatched to several DA layers.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct MultiBlobId {
+    /// keccak256 of the blob, checked against the data returned by each DA layer.
+    pub blob_hash: H256,
+    /// The `da_identifier` and `blob_id` of the blob on each DA layer, in dispatch order.
+    pub blob_ids: Vec<(String, String)>,
+}
+
+impl MultiBlobId {
+    /// <hex blob hash>,<da_identifier>:<blob_id>,<da_identifier>:<blob_id>...
+    pub fn encode(&self) -> String {
+        let mut encoded = hex::encode(self.blob_hash.as_bytes());
+        for (da_identifier, blob_id) in &self.blob_ids {
+            encoded.push(ENTRY_SEPARATOR);
+            encoded.push_str(da_identifier);
+            encoded.push(DA_SEPARATOR);
+            encoded.push_str(blob_id);
+        }
+        encoded
+    }
+
+    pub fn decode(blob_id: &str) -> anyhow::Result<Self> {
+        let mut entries = blob_id.split(ENTRY_SEPARATOR);
+
+        let blob_hash = hex::decode(entries.next().unwrap_or_default())
+            .context("Failed to decode the blob hash")?;
+        ensure!(
+            blob_hash.len() == H256::len_bytes(),
+            "Invalid blob hash length {}",
+            blob_hash.len()
+        );
+
+        let blob_ids = entries
+            .map(|entry| {
+                entry
+                    .split_once(DA_SEPARATOR)
+                    .map(|(da_identifier, blob_id)| {
+                        (da_identifier.to_string(), blob_id.to_string())
+                    })
+                    .with_context(|| format!("Invalid blob id entry `{entry}`"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        ensure!(!blob_ids.is_empty(), "No DA layer in blob id `{blob_id}`");
+
+        Ok(Self {
+            blob_hash: H256::from_slice(&blob_hash),
+            blob_ids,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::bitcoin::hashes::Hash;
+    use via_btc_client::types::{BitcoinTxid, MAX_DA_REFERENCE_BLOB_ID_SIZE};
+
+    use super::*;
+    use crate::{
+        bitcoin::{
+            self,
+            client::{encode_blob_id, MAX_CHUNKS_PER_BLOB},
+        },
+        celestia::{self, manifest::BlobRef},
+    };
+
+    #[test]
+    fn test_multi_blob_id_roundtrip() {
+        let blob_id = MultiBlobId {
+            blob_hash: H256::repeat_byte(0x11),
+            blob_ids: vec![
+                ("celestia".to_string(), "00aa".to_string()),
+                ("bitcoin".to_string(), "00bb".to_string()),
+            ],
+        };
+
+        let encoded = blob_id.encode();
+        assert_eq!(
+            encoded,
+            format!("{},celestia:00aa,bitcoin:00bb", "11".repeat(32))
+        );
+        assert_eq!(MultiBlobId::decode(&encoded).unwrap(), blob_id);
+    }
+
+    #[test]
+    fn test_max_size_multi_blob_id_fits_in_da_reference() {
+        let celestia_blob_id = hex::encode(
+            BlobRef {
+                block_height: u64::MAX,
+                commitment: [1; 32],
+            }
+            .encode(),
+        );
+        let bitcoin_blob_id = encode_blob_id(
+            H256::repeat_byte(2),
+            &[BitcoinTxid::from_byte_array([3; 32]); MAX_CHUNKS_PER_BLOB],
+        );
+        let blob_id = MultiBlobId {
+            blob_hash: H256::repeat_byte(4),
+            blob_ids: vec![
+                (celestia::DA_IDENTIFIER.to_string(), celestia_blob_id),
+                (bitcoin::DA_IDENTIFIER.to_string(), bitcoin_blob_id),
+            ],
+        };
+
+        let encoded = blob_id.encode();
+        assert!(encoded.len() <= MAX_DA_REFERENCE_BLOB_ID_SIZE);
+        assert_eq!(MultiBlobId::decode(&encoded).unwrap(), blob_id);
+    }
+
+    #[test]
+    fn test_decode_invalid_multi_blob_id() {
+        assert!(MultiBlobId::decode("00aa").is_err());
+        assert!(MultiBlobId::decode(&"11".repeat(32)).is_err());
+        assert!(MultiBlobId::decode(&format!("{},celestia", "11".repeat(32))).is_err());
+    }
+}
@@ -0,0 +1,402 @@
+use std::fmt::{Debug, Formatter};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use zksync_da_client::types::InvalidBlobError;
+pub use zksync_da_client::{types, DataAvailabilityClient};
+use zksync_types::{web3::keccak256, H256};
+
+use crate::{
+    dispatch_cache::DispatchCache,
+    multi::{blob_id::MultiBlobId, split_da_identifier},
+};
+
+/// An implementation of the `DataAvailabilityClient` trait that dispatches the blobs to several DA
+/// layers, and fetches them from the first one that returns the expected data.
+#[derive(Clone)]
+pub struct MultiDAClient {
+    /// The `da_identifier` and client of each DA layer, in the order they are tried.
+    clients: Vec<(String, Box<dyn DataAvailabilityClient>)>,
+    /// The clients of the other known DA layers, only used to fetch the blobs inscribed with them,
+    /// e.g. before the DA layers were switched.
+    read_only_clients: Vec<(String, Box<dyn DataAvailabilityClient>)>,
+    /// The `da_identifier` and `blob_id` of the DA layers each pending blob was dispatched to.
+    dispatched_layers: DispatchCache<(String, String)>,
+}
+
+impl MultiDAClient {
+    pub fn new(clients: Vec<(String, Box<dyn DataAvailabilityClient>)>) -> Self {
+        Self {
+            clients,
+            read_only_clients: Vec::new(),
+            dispatched_layers: DispatchCache::default(),
+        }
+    }
+
+    pub fn with_read_only_clients(
+        mut self,
+        read_only_clients: Vec<(String, Box<dyn DataAvailabilityClient>)>,
+    ) -> Self {
+        self.read_only_clients = read_only_clients;
+        self
+    }
+
+    fn client(&self, da_identifier: &str) -> Option<&dyn DataAvailabilityClient> {
+        self.clients
+            .iter()
+            .chain(&self.read_only_clients)
+            .find(|(id, _)| id == da_identifier)
+            .map(|(_, client)| client.as_ref())
+    }
+
+    /// Fetches a blob dispatched to several DA layers from the first one returning the expected data.
+    async fn get_multi_inclusion_data(
+        &self,
+        blob_id: &str,
+    ) -> Result<Option<types::InclusionData>, types::DAError> {
+        let multi_blob_id = MultiBlobId::decode(blob_id).map_err(|error| types::DAError {
+            error,
+            is_retriable: false,
+        })?;
+
+        let mut pending = false;
+        let mut last_error = None;
+        for (da_identifier, blob_id) in &multi_blob_id.blob_ids {
+            let Some(client) = self.client(da_identifier) else {
+                tracing::debug!("Skipping the blob on the unconfigured DA layer {da_identifier}");
+                continue;
+            };
+
+            match client.get_inclusion_data(blob_id).await {
+                Ok(Some(inclusion_data))
+                    if H256(keccak256(&inclusion_data.data)) == multi_blob_id.blob_hash =>
+                {
+                    return Ok(Some(inclusion_data));
+                }
+                Ok(Some(_)) => {
+                    tracing::warn!(
+                        "The blob {blob_id} on {da_identifier} doesn't match the blob hash {:?}",
+                        multi_blob_id.blob_hash
+                    );
+                    last_error = Some(types::DAError {
+                        error: InvalidBlobError(format!(
+                            "the blob on {da_identifier} doesn't match the blob hash"
+                        ))
+                        .into(),
+                        is_retriable: false,
+                    });
+                }
+                Ok(None) => pending = true,
+                Err(error) => {
+                    tracing::warn!(
+                        "Failed to fetch the blob {blob_id} from {da_identifier}: {error}"
+                    );
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        // The blob is not included yet on some DA layer, it's fetched again later.
+        if pending {
+            return Ok(None);
+        }
+
+        Err(last_error.unwrap_or_else(|| types::DAError {
+            error: anyhow!("None of the DA layers of the blob {blob_id} is configured"),
+            is_retriable: false,
+        }))
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for MultiDAClient {
+    async fn dispatch_blob(
+        &self,
+        batch_number: u32,
+        data: Vec<u8>,
+    ) -> Result<types::DispatchResponse, types::DAError> {
+        // A single DA layer keeps the `blob_id` of its client, so it's inscribed as before.
+        if let [(_, client)] = self.clients.as_slice() {
+            return client.dispatch_blob(batch_number, data).await;
+        }
+
+        let blob_hash = H256(keccak256(&data));
+
+        // A retry skips the DA layers the blob was already dispatched to.
+        let mut blob_ids = self.dispatched_layers.published_parts(blob_hash);
+        for (da_identifier, client) in self.clients.iter().skip(blob_ids.len()) {
+            let response = client.dispatch_blob(batch_number, data.clone()).await?;
+            let blob_id = (da_identifier.clone(), response.blob_id);
+            self.dispatched_layers.push_part(blob_hash, blob_id.clone());
+            blob_ids.push(blob_id);
+        }
+        self.dispatched_layers.remove(blob_hash);
+
+        Ok(types::DispatchResponse {
+            blob_id: MultiBlobId {
+                blob_hash,
+                blob_ids,
+            }
+            .encode(),
+        })
+    }
+
+    async fn get_inclusion_data(
+        &self,
+        blob_id: &str,
+    ) -> Result<Option<types::InclusionData>, types::DAError> {
+        match self.clients.as_slice() {
+            [(_, client)] => client.get_inclusion_data(blob_id).await,
+            _ => self.get_multi_inclusion_data(blob_id).await,
+        }
+    }
+
+    async fn get_inclusion_data_from(
+        &self,
+        da_identifier: &str,
+        blob_id: &str,
+    ) -> Result<Option<types::InclusionData>, types::DAError> {
+        if split_da_identifier(da_identifier).len() > 1 {
+            return self.get_multi_inclusion_data(blob_id).await;
+        }
+
+        let client = self.client(da_identifier).ok_or_else(|| types::DAError {
+            error: anyhow!("The DA layer {da_identifier} of the blob {blob_id} is not configured"),
+            is_retriable: false,
+        })?;
+        client.get_inclusion_data(blob_id).await
+    }
+
+    fn clone_boxed(&self) -> Box<dyn DataAvailabilityClient> {
+        Box::new(self.clone())
+    }
+
+    fn blob_size_limit(&self) -> Option<usize> {
+        self.clients
+            .iter()
+            .filter_map(|(_, client)| client.blob_size_limit())
+            .min()
+    }
+}
+
+impl Debug for MultiDAClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultiDAClient")
+            .field(
+                "clients",
+                &self.clients.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+            )
+            .field(
+                "read_only_clients",
+                &self
+                    .read_only_clients
+                    .iter()
+                    .map(|(id, _)| id)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+
+    /// In memory DA layer, failing every request when `available` is false.
+    #[derive(Clone, Debug, Default)]
+    struct MockDAClient {
+        blobs: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+        available: bool,
+    }
+
+    impl MockDAClient {
+        fn new(available: bool) -> Self {
+            Self {
+                blobs: Arc::default(),
+                available,
+            }
+        }
+
+        fn unavailable_error() -> types::DAError {
+            types::DAError {
+                error: anyhow!("DA layer unavailable"),
+                is_retriable: true,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl DataAvailabilityClient for MockDAClient {
+        async fn dispatch_blob(
+            &self,
+            batch_number: u32,
+            data: Vec<u8>,
+        ) -> Result<types::DispatchResponse, types::DAError> {
+            if !self.available {
+                return Err(Self::unavailable_error());
+            }
+            let blob_id = hex::encode(batch_number.to_be_bytes());
+            self.blobs.lock().unwrap().insert(blob_id.clone(), data);
+            Ok(types::DispatchResponse { blob_id })
+        }
+
+        async fn get_inclusion_data(
+            &self,
+            blob_id: &str,
+        ) -> Result<Option<types::InclusionData>, types::DAError> {
+            if !self.available {
+                return Err(Self::unavailable_error());
+            }
+            Ok(self
+                .blobs
+                .lock()
+                .unwrap()
+                .get(blob_id)
+                .map(|data| types::InclusionData { data: data.clone() }))
+        }
+
+        fn clone_boxed(&self) -> Box<dyn DataAvailabilityClient> {
+            Box::new(self.clone())
+        }
+
+        fn blob_size_limit(&self) -> Option<usize> {
+            None
+        }
+    }
+
+    fn multi_client(first: &MockDAClient, second: &MockDAClient) -> MultiDAClient {
+        MultiDAClient::new(vec![
+            ("first".to_string(), Box::new(first.clone())),
+            ("second".to_string(), Box::new(second.clone())),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_fetch_blob_from_next_da_layer() {
+        let first = MockDAClient::new(true);
+        let second = MockDAClient::new(true);
+        let response = multi_client(&first, &second)
+            .dispatch_blob(1, vec![1, 2, 3])
+            .await
+            .unwrap();
+
+        // The first DA layer is down.
+        let client = multi_client(&MockDAClient::new(false), &second);
+        let inclusion_data = client
+            .get_inclusion_data(&response.blob_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(inclusion_data.data, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_skip_blob_not_matching_hash() {
+        let first = MockDAClient::new(true);
+        let second = MockDAClient::new(true);
+        let client = multi_client(&first, &second);
+        let response = client.dispatch_blob(1, vec![1, 2, 3]).await.unwrap();
+
+        first
+            .blobs
+            .lock()
+            .unwrap()
+            .values_mut()
+            .for_each(|data| *data = vec![4, 5, 6]);
+
+        let inclusion_data = client
+            .get_inclusion_data(&response.blob_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(inclusion_data.data, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_fail_when_all_da_layers_fail() {
+        let client = multi_client(&MockDAClient::new(true), &MockDAClient::new(false));
+        let error = client.dispatch_blob(1, vec![1, 2, 3]).await.unwrap_err();
+        assert!(error.is_retriable());
+
+        let blob_id = MultiBlobId {
+            blob_hash: H256(keccak256([1, 2, 3])),
+            blob_ids: vec![("second".to_string(), "00000001".to_string())],
+        };
+        let error = client
+            .get_inclusion_data(&blob_id.encode())
+            .await
+            .unwrap_err();
+        assert!(error.is_retriable());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_blob_when_no_da_layer_matches_hash() {
+        let first = MockDAClient::new(true);
+        let second = MockDAClient::new(true);
+        let client = multi_client(&first, &second);
+        let response = client.dispatch_blob(1, vec![1, 2, 3]).await.unwrap();
+
+        for da_client in [&first, &second] {
+            da_client
+                .blobs
+                .lock()
+                .unwrap()
+                .values_mut()
+                .for_each(|data| *data = vec![4, 5, 6]);
+        }
+
+        let error = client
+            .get_inclusion_data(&response.blob_id)
+            .await
+            .unwrap_err();
+        assert!(error.is_invalid_blob());
+        assert!(!error.is_retriable());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_blob_from_inscribed_da_layer() {
+        let first = MockDAClient::new(true);
+        let second = MockDAClient::new(true);
+
+        // The blobs dispatched to a single DA layer keep the blob id of its client.
+        let single_response =
+            MultiDAClient::new(vec![("second".to_string(), Box::new(second.clone()))])
+                .dispatch_blob(1, vec![1, 2, 3])
+                .await
+                .unwrap();
+        assert_eq!(single_response.blob_id, "00000001");
+        let multi_response = multi_client(&first, &second)
+            .dispatch_blob(2, vec![4, 5, 6])
+            .await
+            .unwrap();
+
+        // The blobs are now only dispatched to the first DA layer.
+        let client = MultiDAClient::new(vec![("first".to_string(), Box::new(first.clone()))])
+            .with_read_only_clients(vec![("second".to_string(), Box::new(second.clone()))]);
+
+        let inclusion_data = client
+            .get_inclusion_data_from("second", &single_response.blob_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(inclusion_data.data, vec![1, 2, 3]);
+
+        let inclusion_data = client
+            .get_inclusion_data_from("first+second", &multi_response.blob_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(inclusion_data.data, vec![4, 5, 6]);
+
+        let error = client
+            .get_inclusion_data_from("third", &single_response.blob_id)
+            .await
+            .unwrap_err();
+        assert!(!error.is_retriable());
+    }
+}
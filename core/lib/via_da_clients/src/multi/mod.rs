@@ -0,0 +1,12 @@
+/// Separator of the DA layers in the `da_identifier` of the blobs dispatched to several DA layers,
+/// e.g. `celestia+bitcoin`.
+pub const DA_IDENTIFIER_SEPARATOR: char = '+';
+
+mod blob_id;
+pub mod client;
+pub mod wiring_layer;
+
+/// Returns the DA layers of `da_identifier`, in the order they are tried when fetching the blobs.
+pub fn split_da_identifier(da_identifier: &str) -> Vec<&str> {
+    da_identifier.split(DA_IDENTIFIER_SEPARATOR).collect()
+}
@@ -0,0 +1,91 @@
+use zksync_da_client::DataAvailabilityClient;
+use zksync_node_framework::{
+    implementations::resources::da_client::DAClientResource,
+    wiring_layer::{WiringError, WiringLayer},
+    IntoContext,
+};
+
+use crate::{
+    bitcoin::{self, wiring_layer::ViaBitcoinDAClientWiringLayer},
+    celestia::{self, wiring_layer::ViaCelestiaClientWiringLayer},
+    multi::client::MultiDAClient,
+};
+
+/// A DA layer the blobs are dispatched to by the `MultiDAClient`.
+#[derive(Debug)]
+pub enum ViaDABackend {
+    Celestia(ViaCelestiaClientWiringLayer),
+    Bitcoin(ViaBitcoinDAClientWiringLayer),
+}
+
+impl ViaDABackend {
+    async fn create_client(self) -> anyhow::Result<(String, Box<dyn DataAvailabilityClient>)> {
+        Ok(match self {
+            Self::Celestia(layer) => (
+                celestia::DA_IDENTIFIER.to_string(),
+                Box::new(layer.create_client().await?),
+            ),
+            Self::Bitcoin(layer) => (
+                bitcoin::DA_IDENTIFIER.to_string(),
+                Box::new(layer.create_client().await?),
+            ),
+        })
+    }
+}
+
+/// Wiring layer for the DA client dispatching the blobs to several DA layers. The backends are tried
+/// in order when fetching the blobs.
+#[derive(Debug)]
+pub struct ViaMultiDAClientWiringLayer {
+    backends: Vec<ViaDABackend>,
+    read_only_backends: Vec<ViaDABackend>,
+}
+
+impl ViaMultiDAClientWiringLayer {
+    pub fn new(backends: Vec<ViaDABackend>) -> Self {
+        Self {
+            backends,
+            read_only_backends: Vec::new(),
+        }
+    }
+
+    /// Adds DA layers the blobs are not dispatched to, only used to fetch the blobs inscribed with
+    /// them.
+    pub fn with_read_only_backends(mut self, read_only_backends: Vec<ViaDABackend>) -> Self {
+        self.read_only_backends = read_only_backends;
+        self
+    }
+}
+
+#[derive(Debug, IntoContext)]
+pub struct Output {
+    pub client: DAClientResource,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ViaMultiDAClientWiringLayer {
+    type Input = ();
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "via_da_layer"
+    }
+
+    async fn wire(self, _input: Self::Input) -> Result<Self::Output, WiringError> {
+        let mut clients = Vec::with_capacity(self.backends.len());
+        for backend in self.backends {
+            clients.push(backend.create_client().await?);
+        }
+        let mut read_only_clients = Vec::with_capacity(self.read_only_backends.len());
+        for backend in self.read_only_backends {
+            read_only_clients.push(backend.create_client().await?);
+        }
+
+        let client: Box<dyn DataAvailabilityClient> =
+            Box::new(MultiDAClient::new(clients).with_read_only_clients(read_only_clients));
+
+        Ok(Output {
+            client: DAClientResource(client),
+        })
+    }
+}
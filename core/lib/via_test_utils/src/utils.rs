@@ -45,6 +45,10 @@ pub fn test_bitcoin_client() -> BitcoinClient {
             external_apis: vec!["https://mempool.space/testnet/api/v1/fees/recommended".into()],
             fee_strategies: vec!["fastestFee".into()],
             use_rpc_for_fee_rate: None,
+            esplora_url: None,
+            fee_rate_providers: None,
+            fee_rate_aggregation: None,
+            fee_rate_quorum: None,
         },
     )
     .unwrap()
@@ -247,6 +251,7 @@ pub async fn create_chained_inscriptions(
             l1_batch_reveal_txid: result.final_reveal_tx.txid,
             da_identifier: "celestia".into(),
             blob_id: H256::random().0.to_hex_string(Case::Lower),
+            next_l1_batch_reveal_txids: vec![],
         };
 
         let result = inscriber
@@ -261,6 +266,59 @@ pub async fn create_chained_inscriptions(
     Ok((msgs, prev_l1_batch_hash))
 }
 
+/// Inscribes the pubdata of the L1 batches `start..=end` and a single proof covering the whole range.
+pub async fn create_range_proof_inscription(
+    start: usize,
+    end: usize,
+    prev_batch_hash_opt: Option<H256>,
+) -> anyhow::Result<(Vec<FullInscriptionMessage>, H256)> {
+    let mut inscriber = test_sequencer_inscriber().await?;
+    let client = test_bitcoin_client();
+    let mut parser = MessageParser::new(NETWORK);
+
+    let mut prev_l1_batch_hash = prev_batch_hash_opt.unwrap_or_else(H256::zero);
+    let mut l1_batch_reveal_txids = vec![];
+
+    for i in start..(end + 1) {
+        let l1_batch_hash = H256::random();
+        sleep(Duration::from_millis(500)).await;
+
+        let batch_pubdata = L1BatchDAReferenceInput {
+            l1_batch_hash,
+            blob_id: H256::random().0.to_hex_string(Case::Lower),
+            da_identifier: "celestia".into(),
+            l1_batch_index: L1BatchNumber(i as u32),
+            prev_l1_batch_hash,
+        };
+
+        prev_l1_batch_hash = l1_batch_hash;
+
+        let result = inscriber
+            .inscribe(InscriptionMessage::L1BatchDAReference(batch_pubdata))
+            .await?;
+        l1_batch_reveal_txids.push(result.final_reveal_tx.txid);
+    }
+
+    sleep(Duration::from_millis(500)).await;
+
+    let batch_proof = ProofDAReferenceInput {
+        l1_batch_reveal_txid: l1_batch_reveal_txids.remove(0),
+        da_identifier: "celestia".into(),
+        blob_id: H256::random().0.to_hex_string(Case::Lower),
+        next_l1_batch_reveal_txids: l1_batch_reveal_txids,
+    };
+
+    let result = inscriber
+        .inscribe(InscriptionMessage::ProofDAReference(batch_proof))
+        .await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let tx = client.get_transaction(&result.final_reveal_tx.txid).await?;
+    let msgs = parser.parse_system_transaction(&tx, 0, None);
+
+    Ok((msgs, prev_l1_batch_hash))
+}
+
 pub fn test_create_indexer() -> BitcoinInscriptionIndexer {
     BitcoinInscriptionIndexer::new(Arc::new(test_bitcoin_client()), Arc::new(test_wallets()))
 }
@@ -145,7 +145,7 @@ pub enum ViaComponent {
     VmRunnerBwip,
     /// Component that interacts with Bitcoin network
     Btc,
-    /// Component that writes data to Celestia network
+    /// Component that writes data to the DA layer selected by the BTC sender `da_identifier`.
     Celestia,
 }
 
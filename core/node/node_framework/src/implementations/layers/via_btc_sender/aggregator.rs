@@ -1,4 +1,5 @@
-use via_btc_client::inscriber::Inscriber;
+use anyhow::Context;
+use via_btc_client::{inscriber::Inscriber, signer::signer_from_wallet};
 use via_btc_sender::btc_inscription_aggregator::ViaBtcInscriptionAggregator;
 use zksync_config::{configs::via_wallets::ViaWallet, ViaBtcSenderConfig};
 
@@ -30,6 +31,7 @@ use crate::{
 pub struct ViaBtcInscriptionAggregatorLayer {
     config: ViaBtcSenderConfig,
     wallet: ViaWallet,
+    keystore_password: Option<String>,
 }
 
 #[derive(Debug, FromContext)]
@@ -48,7 +50,17 @@ pub struct Output {
 
 impl ViaBtcInscriptionAggregatorLayer {
     pub fn new(config: ViaBtcSenderConfig, wallet: ViaWallet) -> Self {
-        Self { config, wallet }
+        Self {
+            config,
+            wallet,
+            keystore_password: None,
+        }
+    }
+
+    /// Sets the password unlocking the keystore of the wallet, from `ViaKeystoreSecrets`.
+    pub fn with_keystore_password(mut self, keystore_password: Option<String>) -> Self {
+        self.keystore_password = keystore_password;
+        self
     }
 }
 
@@ -66,9 +78,16 @@ impl WiringLayer for ViaBtcInscriptionAggregatorLayer {
         let master_pool = input.master_pool.get().await.unwrap();
         let client = input.btc_client_resource.btc_sender.unwrap();
 
-        let inscriber = Inscriber::new(client, &self.wallet.private_key, None)
+        let signer = signer_from_wallet(
+            &self.wallet,
+            self.keystore_password.as_deref(),
+            client.config.network(),
+        )
+        .await
+        .context("Error init signer")?;
+        let inscriber = Inscriber::with_signer(client, signer, None)
             .await
-            .unwrap();
+            .context("Error init inscriber")?;
 
         let via_btc_inscription_aggregator =
             ViaBtcInscriptionAggregator::new(inscriber, master_pool, self.config).await?;
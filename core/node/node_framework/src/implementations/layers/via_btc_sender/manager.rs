@@ -1,5 +1,5 @@
 use anyhow::Context;
-use via_btc_client::inscriber::Inscriber;
+use via_btc_client::{inscriber::Inscriber, signer::signer_from_wallet};
 use via_btc_sender::btc_inscription_manager::ViaBtcInscriptionManager;
 use zksync_config::{configs::via_wallets::ViaWallet, ViaBtcSenderConfig};
 
@@ -30,6 +30,7 @@ use crate::{
 pub struct ViaInscriptionManagerLayer {
     config: ViaBtcSenderConfig,
     wallet: ViaWallet,
+    keystore_password: Option<String>,
 }
 
 #[derive(Debug, FromContext)]
@@ -48,7 +49,17 @@ pub struct Output {
 
 impl ViaInscriptionManagerLayer {
     pub fn new(config: ViaBtcSenderConfig, wallet: ViaWallet) -> Self {
-        Self { config, wallet }
+        Self {
+            config,
+            wallet,
+            keystore_password: None,
+        }
+    }
+
+    /// Sets the password unlocking the keystore of the wallet, from `ViaKeystoreSecrets`.
+    pub fn with_keystore_password(mut self, keystore_password: Option<String>) -> Self {
+        self.keystore_password = keystore_password;
+        self
     }
 }
 
@@ -66,7 +77,14 @@ impl WiringLayer for ViaInscriptionManagerLayer {
         let master_pool = input.master_pool.get().await.unwrap();
         let client = input.btc_client_resource.btc_sender.unwrap();
 
-        let inscriber = Inscriber::new(client, &self.wallet.private_key, None)
+        let signer = signer_from_wallet(
+            &self.wallet,
+            self.keystore_password.as_deref(),
+            client.config.network(),
+        )
+        .await
+        .with_context(|| "Error init signer")?;
+        let inscriber = Inscriber::with_signer(client, signer, None)
             .await
             .with_context(|| "Error init inscriber")?;
 
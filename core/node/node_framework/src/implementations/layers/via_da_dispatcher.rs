@@ -19,6 +19,7 @@ pub struct DataAvailabilityDispatcherLayer {
     state_keeper_config: StateKeeperConfig,
     da_config: DADispatcherConfig,
     dispatch_real_proof: bool,
+    max_batches_per_proof: usize,
 }
 
 #[derive(Debug, FromContext)]
@@ -41,11 +42,13 @@ impl DataAvailabilityDispatcherLayer {
         state_keeper_config: StateKeeperConfig,
         da_config: DADispatcherConfig,
         dispatch_real_proof: bool,
+        max_batches_per_proof: usize,
     ) -> Self {
         Self {
             state_keeper_config,
             da_config,
             dispatch_real_proof,
+            max_batches_per_proof,
         }
     }
 }
@@ -80,6 +83,7 @@ impl WiringLayer for DataAvailabilityDispatcherLayer {
             da_client,
             object_store,
             self.dispatch_real_proof,
+            self.max_batches_per_proof,
         );
 
         Ok(Output { da_dispatcher_task })
@@ -0,0 +1,113 @@
+use anyhow::Context;
+use via_btc_client::types::BitcoinNetwork;
+use via_indexer::api::l2_client::{L2RpcWithdrawalsClient, L2WithdrawalsClient};
+use via_indexer_dal::{ConnectionPool, Indexer};
+use zksync_config::configs::via_l1_indexer::ViaIndexerApiConfig;
+use zksync_types::url::SensitiveUrl;
+use zksync_web3_decl::client::{Client, L2};
+
+use crate::{
+    implementations::resources::pools::{IndexerPool, PoolResource},
+    service::StopReceiver,
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+    FromContext, IntoContext,
+};
+
+/// Wiring layer for the indexer query API
+#[derive(Debug)]
+pub struct ViaIndexerApiLayer {
+    api_config: ViaIndexerApiConfig,
+    block_confirmations: u64,
+    network: BitcoinNetwork,
+}
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+pub struct Input {
+    pub master_pool: PoolResource<IndexerPool>,
+}
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+pub struct Output {
+    #[context(task)]
+    pub via_indexer_api_task: ViaIndexerApiTask,
+}
+
+impl ViaIndexerApiLayer {
+    pub fn new(
+        api_config: ViaIndexerApiConfig,
+        block_confirmations: u64,
+        network: BitcoinNetwork,
+    ) -> Self {
+        Self {
+            api_config,
+            block_confirmations,
+            network,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ViaIndexerApiLayer {
+    type Input = Input;
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "via_indexer_api_layer"
+    }
+
+    async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        let master_pool = input.master_pool.get().await?;
+
+        let l2_client = match &self.api_config.l2_rpc_url {
+            Some(url) => {
+                let url: SensitiveUrl = url.parse().context("Invalid L2 RPC url")?;
+                let client = Client::<L2>::http(url)
+                    .context("failed creating JSON-RPC client for L2 node")?
+                    .build();
+                let l2_client: Box<dyn L2WithdrawalsClient> =
+                    Box::new(L2RpcWithdrawalsClient::new(Box::new(client), self.network));
+                Some(l2_client)
+            }
+            None => None,
+        };
+
+        let via_indexer_api_task = ViaIndexerApiTask {
+            api_config: self.api_config,
+            master_pool,
+            block_confirmations: self.block_confirmations,
+            l2_client,
+        };
+        Ok(Output {
+            via_indexer_api_task,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ViaIndexerApiTask {
+    api_config: ViaIndexerApiConfig,
+    master_pool: ConnectionPool<Indexer>,
+    block_confirmations: u64,
+    l2_client: Option<Box<dyn L2WithdrawalsClient>>,
+}
+
+#[async_trait::async_trait]
+impl Task for ViaIndexerApiTask {
+    fn id(&self) -> TaskId {
+        "via_indexer_api".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        via_indexer::api::start_api_server(
+            self.api_config,
+            self.master_pool,
+            self.block_confirmations,
+            self.l2_client,
+            stop_receiver.0,
+        )
+        .await
+    }
+}
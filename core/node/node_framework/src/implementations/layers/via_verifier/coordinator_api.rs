@@ -102,6 +102,11 @@ impl Task for ViaCoordinatorApiTask {
     }
 
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let bridge_taproot = via_verifier_coordinator::utils::bridge_taproot(
+            &self.via_bridge_config,
+            self.verifier_config.bridge_address_merkle_root(),
+        )?;
+
         via_verifier_coordinator::coordinator::api::start_coordinator_server(
             self.verifier_config,
             self.master_pool,
@@ -110,6 +115,7 @@ impl Task for ViaCoordinatorApiTask {
             self.via_bridge_config.bridge_address()?,
             self.via_bridge_config.verifiers_pub_keys.clone(),
             self.via_bridge_config.required_signers,
+            bridge_taproot,
             stop_receiver.0,
         )
         .await
@@ -1,2 +1,3 @@
 pub mod coordinator_api;
+pub mod status_api;
 pub mod verifier;
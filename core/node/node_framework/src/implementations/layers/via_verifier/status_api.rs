@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use via_btc_client::traits::BitcoinOps;
+use via_verifier_dal::{ConnectionPool, Verifier};
+use zksync_config::{configs::via_wallets::ViaWallets, ViaVerifierConfig};
+use zksync_health_check::{HealthUpdater, ReactiveHealthCheck};
+
+use crate::{
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        pools::{PoolResource, VerifierPool},
+        via_btc_client::BtcClientResource,
+    },
+    service::StopReceiver,
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+    FromContext, IntoContext,
+};
+
+/// Wiring layer for the verifier status api, which also reports the status in the health check.
+#[derive(Debug)]
+pub struct ViaVerifierStatusApiLayer {
+    verifier_config: ViaVerifierConfig,
+    wallets: ViaWallets,
+}
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+pub struct Input {
+    pub master_pool: PoolResource<VerifierPool>,
+    pub btc_client_resource: BtcClientResource,
+    #[context(default)]
+    pub app_health: AppHealthCheckResource,
+}
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+pub struct Output {
+    #[context(task)]
+    pub via_verifier_status_api_task: ViaVerifierStatusApiTask,
+}
+
+impl ViaVerifierStatusApiLayer {
+    pub fn new(verifier_config: ViaVerifierConfig, wallets: ViaWallets) -> Self {
+        Self {
+            verifier_config,
+            wallets,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ViaVerifierStatusApiLayer {
+    type Input = Input;
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "via_verifier_status_api_layer"
+    }
+
+    async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        let master_pool = input.master_pool.get().await?;
+        let btc_client = input.btc_client_resource.default;
+
+        let wallets = [
+            ("btc_sender", self.wallets.btc_sender),
+            ("vote_operator", self.wallets.vote_operator),
+        ]
+        .into_iter()
+        .filter_map(|(role, wallet)| wallet.map(|wallet| (role.to_string(), wallet.address)))
+        .collect();
+
+        let (health_check, health_updater) = ReactiveHealthCheck::new("via_verifier_status");
+        input
+            .app_health
+            .0
+            .insert_component(health_check)
+            .map_err(WiringError::internal)?;
+
+        let via_verifier_status_api_task = ViaVerifierStatusApiTask {
+            verifier_config: self.verifier_config,
+            master_pool,
+            btc_client,
+            wallets,
+            health_updater,
+        };
+        Ok(Output {
+            via_verifier_status_api_task,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ViaVerifierStatusApiTask {
+    verifier_config: ViaVerifierConfig,
+    master_pool: ConnectionPool<Verifier>,
+    btc_client: Arc<dyn BitcoinOps>,
+    wallets: Vec<(String, String)>,
+    health_updater: HealthUpdater,
+}
+
+#[async_trait::async_trait]
+impl Task for ViaVerifierStatusApiTask {
+    fn id(&self) -> TaskId {
+        "via_verifier_status_api".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        via_verifier_coordinator::status::run_status_api(
+            self.verifier_config,
+            self.master_pool,
+            self.btc_client,
+            self.wallets,
+            self.health_updater,
+            stop_receiver.0,
+        )
+        .await
+    }
+}
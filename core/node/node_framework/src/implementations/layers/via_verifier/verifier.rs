@@ -1,4 +1,5 @@
 use anyhow::Context;
+use via_btc_client::signer::key_manager_from_wallet;
 use via_verifier_coordinator::verifier::ViaWithdrawalVerifier;
 use via_withdrawal_client::client::WithdrawalClient;
 use zksync_config::{
@@ -27,6 +28,7 @@ pub struct ViaWithdrawalVerifierLayer {
     via_btc_client: ViaBtcClientConfig,
     verifier_config: ViaVerifierConfig,
     wallet: ViaWallet,
+    keystore_password: Option<String>,
 }
 
 #[derive(Debug, FromContext)]
@@ -56,8 +58,15 @@ impl ViaWithdrawalVerifierLayer {
             via_btc_client,
             verifier_config,
             wallet,
+            keystore_password: None,
         }
     }
+
+    /// Sets the password unlocking the keystore of the wallet, from `ViaKeystoreSecrets`.
+    pub fn with_keystore_password(mut self, keystore_password: Option<String>) -> Self {
+        self.keystore_password = keystore_password;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -77,9 +86,17 @@ impl WiringLayer for ViaWithdrawalVerifierLayer {
 
         let btc_client = input.btc_client_resource.verifier.unwrap();
 
+        // MuSig2 signing needs the key itself, so only a keystore can replace the plaintext key.
+        let key_manager = key_manager_from_wallet(
+            &self.wallet,
+            self.keystore_password.as_deref(),
+            self.via_btc_client.network(),
+        )
+        .context("Error to load the verifier key")?;
+
         let via_withdrawal_verifier_task = ViaWithdrawalVerifier::new(
             self.verifier_config,
-            self.wallet,
+            key_manager,
             master_pool,
             btc_client,
             withdrawal_client,
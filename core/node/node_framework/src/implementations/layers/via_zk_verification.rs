@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use via_zk_verifier::ViaVerifier;
-use zksync_config::{configs::via_bridge::ViaBridgeConfig, ViaVerifierConfig};
+use zksync_config::{configs::via_bridge::ViaBridgeConfig, GenesisConfig, ViaVerifierConfig};
 
 use crate::{
     implementations::resources::{
@@ -18,6 +18,7 @@ use crate::{
 pub struct ViaBtcProofVerificationLayer {
     via_bridge_config: ViaBridgeConfig,
     verifier_config: ViaVerifierConfig,
+    genesis: GenesisConfig,
 }
 
 #[derive(Debug, FromContext)]
@@ -36,10 +37,15 @@ pub struct ProofVerificationOutput {
 }
 
 impl ViaBtcProofVerificationLayer {
-    pub fn new(verifier_config: ViaVerifierConfig, via_bridge_config: ViaBridgeConfig) -> Self {
+    pub fn new(
+        verifier_config: ViaVerifierConfig,
+        via_bridge_config: ViaBridgeConfig,
+        genesis: GenesisConfig,
+    ) -> Self {
         Self {
             verifier_config,
             via_bridge_config,
+            genesis,
         }
     }
 }
@@ -62,6 +68,8 @@ impl WiringLayer for ViaBtcProofVerificationLayer {
             main_pool,
             input.da_client.0,
             self.via_bridge_config.zk_agreement_threshold,
+            self.genesis.genesis_commitment,
+            self.genesis.l1_batch_commit_data_generator_mode,
         )
         .await
         .map_err(WiringError::internal)?;
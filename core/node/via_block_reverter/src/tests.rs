@@ -48,7 +48,7 @@ async fn create_inscriptions_and_verifier_votes_for_l1_batch(
 
     storage
         .via_votes_dal()
-        .insert_vote(number, &[], "verifier_address", vote)
+        .insert_vote(number, &[], "verifier_address", vote, 0)
         .await
         .unwrap();
 
@@ -56,6 +56,31 @@ impl ViaAggregator {
         }
     }
 
+    /// Returns the next ready operations. When `pack` is set, the ready L1 batch commit and proof
+    /// operations are both returned so their inscriptions can be packed together.
+    pub async fn get_next_ready_operations(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        pack: bool,
+    ) -> anyhow::Result<Vec<ViaAggregatedOperation>> {
+        if !pack {
+            return Ok(self
+                .get_next_ready_operation(storage)
+                .await?
+                .into_iter()
+                .collect());
+        }
+
+        let mut operations = vec![];
+        if let Some(op) = self.get_commit_l1_batch_operation(storage).await? {
+            operations.push(op);
+        }
+        if let Some(op) = self.get_commit_proof_operation(storage).await? {
+            operations.push(op);
+        }
+        Ok(operations)
+    }
+
     async fn get_commit_l1_batch_operation(
         &mut self,
         storage: &mut Connection<'_, Core>,
@@ -114,16 +139,30 @@ impl ViaAggregator {
         )
         .await
         {
-            return Ok(Some(ViaAggregatedOperation::CommitProofOnchain(l1_batches)));
+            let l1_batches = take_complete_proof_ranges(storage, l1_batches).await?;
+            if !l1_batches.is_empty() {
+                return Ok(Some(ViaAggregatedOperation::CommitProofOnchain(l1_batches)));
+            }
         }
         Ok(None)
     }
 
+    /// Constructs the message of an inscription. A proof message covers all the `batches` proven
+    /// by the same proof blob, any other message a single L1 batch.
     pub fn construct_inscription_message(
         &self,
         inscription_request_type: &ViaBtcInscriptionRequestType,
-        batch: &ViaBtcL1BlockDetails,
+        batches: &[&ViaBtcL1BlockDetails],
     ) -> anyhow::Result<InscriptionMessage> {
+        let (batch, next_batches) = batches
+            .split_first()
+            .ok_or_else(|| anyhow!("No L1 batch to inscribe"))?;
+        if !next_batches.is_empty()
+            && *inscription_request_type != ViaBtcInscriptionRequestType::CommitProofOnchain
+        {
+            anyhow::bail!("Only the proof inscriptions can cover several L1 batches");
+        }
+
         match inscription_request_type {
             ViaBtcInscriptionRequestType::CommitL1BatchOnchain => {
                 let input = L1BatchDAReferenceInput {
@@ -146,10 +185,22 @@ impl ViaAggregator {
                 Ok(InscriptionMessage::L1BatchDAReference(input))
             }
             ViaBtcInscriptionRequestType::CommitProofOnchain => {
+                if next_batches
+                    .iter()
+                    .any(|next_batch| next_batch.blob_id != batch.blob_id)
+                {
+                    anyhow::bail!(
+                        "The L1 batches of a proof inscription have different proof blobs"
+                    );
+                }
                 let input = ProofDAReferenceInput {
                     l1_batch_reveal_txid: batch.reveal_tx_id,
                     da_identifier: self.config.da_identifier().to_string(),
                     blob_id: batch.blob_id.clone(),
+                    next_l1_batch_reveal_txids: next_batches
+                        .iter()
+                        .map(|next_batch| next_batch.reveal_tx_id)
+                        .collect(),
                 };
                 Ok(InscriptionMessage::ProofDAReference(input))
             }
@@ -279,6 +330,29 @@ async fn extract_ready_subrange(
     None
 }
 
+/// Keeps the leading L1 batches whose proof blob ranges are complete, so the proof of a range is
+/// only inscribed once every L1 batch it covers is ready.
+async fn take_complete_proof_ranges(
+    storage: &mut Connection<'_, Core>,
+    l1_batches: Vec<ViaBtcL1BlockDetails>,
+) -> anyhow::Result<Vec<ViaBtcL1BlockDetails>> {
+    let mut complete = 0;
+    for range in l1_batches.chunk_by(|a, b| a.blob_id == b.blob_id) {
+        let range_len = storage
+            .via_data_availability_dal()
+            .get_proof_blob_l1_batches_count(&range[0].blob_id)
+            .await?;
+        if (range.len() as i64) < range_len {
+            break;
+        }
+        complete += range.len();
+    }
+
+    let mut l1_batches = l1_batches;
+    l1_batches.truncate(complete);
+    Ok(l1_batches)
+}
+
 fn validate_l1_batch_sequence(
     last_committed_l1_batch_opt: Option<ViaBtcL1BlockDetails>,
     ready_for_commit_l1_batches: &[ViaBtcL1BlockDetails],
@@ -4,8 +4,13 @@ use via_btc_client::{inscriber::Inscriber, traits::Serializable, types::Inscript
 use zksync_config::ViaBtcSenderConfig;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_shared_metrics::BlockL1Stage;
+use zksync_types::{
+    btc_block::ViaBtcL1BlockDetails, btc_inscription_operations::ViaBtcInscriptionRequestType,
+};
 
-use crate::{aggregator::ViaAggregator, metrics::METRICS};
+use crate::{
+    aggregated_operations::ViaAggregatedOperation, aggregator::ViaAggregator, metrics::METRICS,
+};
 
 #[derive(Debug)]
 pub struct ViaBtcInscriptionAggregator {
@@ -64,58 +69,134 @@ impl ViaBtcInscriptionAggregator {
         let latency = METRICS.inscription_preparation_time.start();
         let mut processed_inscriptions = vec![];
 
-        if let Some(operation) = self.aggregator.get_next_ready_operation(storage).await? {
+        let operations = self
+            .aggregator
+            .get_next_ready_operations(storage, self.config.pack_proof_with_commit())
+            .await?;
+
+        if operations.is_empty() {
+            return Ok(());
+        }
+
+        for operation in &operations {
             tracing::info!("New operation ready to be processed {operation}");
+        }
+
+        let mut transaction = storage.start_transaction().await?;
+
+        for inscription in group_inscriptions(&operations) {
+            let inscription_messages = inscription
+                .iter()
+                .map(|(request_type, batches)| {
+                    self.aggregator
+                        .construct_inscription_message(request_type, batches)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            let (inscription_request_type, inscription_message) =
+                match inscription_messages.as_slice() {
+                    [message] => (inscription[0].0, InscriptionMessage::to_bytes(message)?),
+                    _ => (
+                        ViaBtcInscriptionRequestType::CommitL1BatchAndProofOnchain,
+                        inscription_messages.to_bytes()?,
+                    ),
+                };
+
+            // Estimate the tx fee to execute the inscription request.
+            let inscribe_info = self
+                .inscriber
+                .prepare_inscribe_messages(&inscription_messages, None)
+                .await?;
+
+            let prediction_fee = inscribe_info.reveal_tx_output_info._reveal_fee
+                + inscribe_info.commit_tx_output_info.commit_tx_fee;
+
+            let inscription_request_id = transaction
+                .btc_sender_dal()
+                .via_save_btc_inscriptions_request(
+                    inscription[0].1[0].number,
+                    inscription_request_type.to_string(),
+                    inscription_message,
+                    prediction_fee.to_sat(),
+                )
+                .await?;
 
-            let mut transaction = storage.start_transaction().await?;
-
-            for batch in operation.get_l1_batches_detail() {
-                let inscription_message = self.aggregator.construct_inscription_message(
-                    &operation.get_inscription_request_type(),
-                    batch,
-                )?;
-
-                // Estimate the tx fee to execute the inscription request.
-                let inscribe_info = self
-                    .inscriber
-                    .prepare_inscribe(&inscription_message, None)
-                    .await?;
-
-                let prediction_fee = inscribe_info.reveal_tx_output_info._reveal_fee
-                    + inscribe_info.commit_tx_output_info.commit_tx_fee;
-
-                let inscription_request_id = transaction
-                    .btc_sender_dal()
-                    .via_save_btc_inscriptions_request(
-                        batch.number,
-                        operation.get_inscription_request_type().to_string(),
-                        InscriptionMessage::to_bytes(&inscription_message),
-                        prediction_fee.to_sat(),
-                    )
-                    .await?;
-
-                transaction
-                    .via_blocks_dal()
-                    .insert_l1_batch_inscription_request_id(
-                        batch.number,
-                        inscription_request_id,
-                        operation.get_inscription_request_type(),
-                    )
-                    .await?;
-
-                processed_inscriptions.push((
-                    inscription_request_id as u32,
-                    operation.get_inscription_request_type(),
-                ));
+            for (request_type, batches) in &inscription {
+                for batch in batches {
+                    transaction
+                        .via_blocks_dal()
+                        .insert_l1_batch_inscription_request_id(
+                            batch.number,
+                            inscription_request_id,
+                            *request_type,
+                        )
+                        .await?;
+                }
             }
-            transaction.commit().await?;
 
-            METRICS
-                .track_btc_tx_metrics(storage, BlockL1Stage::Mined, processed_inscriptions)
-                .await;
-            latency.observe();
-            METRICS.pending_inscription_requests.inc_by(1);
+            processed_inscriptions.push((inscription_request_id as u32, inscription_request_type));
         }
+        transaction.commit().await?;
+
+        let created_inscriptions = processed_inscriptions.len() as u64;
+        METRICS
+            .track_btc_tx_metrics(storage, BlockL1Stage::Mined, processed_inscriptions)
+            .await;
+        latency.observe();
+        METRICS
+            .pending_inscription_requests
+            .inc_by(created_inscriptions);
         Ok(())
     }
 }
+
+/// Messages of an inscription along with the L1 batches each of them covers.
+pub(crate) type InscriptionBatches<'a> =
+    Vec<(ViaBtcInscriptionRequestType, Vec<&'a ViaBtcL1BlockDetails>)>;
+
+/// Groups the L1 batches of the ready operations into inscriptions. A commit message covers one
+/// L1 batch, a proof message all the consecutive L1 batches sharing the same proof blob. When both
+/// a commit and a proof operation are ready, the n-th commit message is packed with the n-th proof
+/// message, the commit message first. An inscription never carries more than one message of each
+/// type.
+pub(crate) fn group_inscriptions(
+    operations: &[ViaAggregatedOperation],
+) -> Vec<InscriptionBatches<'_>> {
+    let batches_of = |request_type: ViaBtcInscriptionRequestType| {
+        operations
+            .iter()
+            .filter(|operation| operation.get_inscription_request_type() == request_type)
+            .flat_map(|operation| operation.get_l1_batches_detail())
+            .collect::<Vec<_>>()
+    };
+
+    let commits = batches_of(ViaBtcInscriptionRequestType::CommitL1BatchOnchain)
+        .into_iter()
+        .map(|batch| {
+            (
+                ViaBtcInscriptionRequestType::CommitL1BatchOnchain,
+                vec![batch],
+            )
+        })
+        .collect::<Vec<_>>();
+    let proofs = batches_of(ViaBtcInscriptionRequestType::CommitProofOnchain)
+        .chunk_by(|a, b| a.blob_id == b.blob_id)
+        .map(|range| {
+            (
+                ViaBtcInscriptionRequestType::CommitProofOnchain,
+                range.to_vec(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut commits = commits.into_iter();
+    let mut proofs = proofs.into_iter();
+    let mut inscriptions = vec![];
+    loop {
+        let inscription: Vec<_> = commits.next().into_iter().chain(proofs.next()).collect();
+        if inscription.is_empty() {
+            return inscriptions;
+        }
+        inscriptions.push(inscription);
+    }
+}
@@ -1,16 +1,21 @@
 use std::collections::HashMap;
 
 use anyhow::{Context, Result};
-use bincode::serialize;
-use bitcoin::hashes::Hash;
+use bincode::{deserialize, serialize};
+use bitcoin::{hashes::Hash, Amount, Transaction};
 use tokio::sync::watch;
-use via_btc_client::{inscriber::Inscriber, traits::Serializable, types::InscriptionMessage};
+use via_btc_client::{
+    inscriber::Inscriber,
+    traits::Serializable,
+    types::{AncestorsPackage, BitcoinError, FeeRateTarget, InscriptionMessage},
+};
 use zksync_config::ViaBtcSenderConfig;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_shared_metrics::BlockL1Stage;
 use zksync_types::{
     btc_inscription_operations::ViaBtcInscriptionRequestType,
-    via_btc_sender::ViaBtcInscriptionRequest, via_wallet::SystemWallets,
+    via_btc_sender::{ViaBtcInscriptionRequest, ViaBtcInscriptionRequestHistory},
+    via_wallet::SystemWallets,
 };
 
 use crate::metrics::METRICS;
@@ -89,32 +94,37 @@ impl ViaBtcInscriptionManager {
 
         self.validate_inscriber_address(wallets_map)?;
 
+        let current_block = self
+            .inscriber
+            .get_client()
+            .await
+            .fetch_block_height()
+            .await?;
+        let mut pending_inscriptions = Vec::new();
+
         for inscription_id in inflight_inscriptions_ids {
-            if let Some(last_inscription_history) = storage
+            let history = storage
                 .btc_sender_dal()
-                .get_last_inscription_request_history(inscription_id)
-                .await?
-            {
-                let is_confirmed = self
-                    .inscriber
-                    .get_client()
-                    .await
-                    .check_tx_confirmation(
-                        &last_inscription_history.reveal_tx_id,
-                        self.config.block_confirmations,
-                    )
-                    .await?;
-
-                METRICS.track_block_numbers(storage).await;
-
-                if is_confirmed {
+                .list_inscription_request_history(inscription_id)
+                .await?;
+
+            let Some(last_inscription_history) = history.first() else {
+                continue;
+            };
+
+            let status = self.find_inscription_status(&history).await?;
+
+            METRICS.track_block_numbers(storage).await;
+
+            match status {
+                InscriptionStatus::Confirmed(confirmed_inscription_history) => {
                     let inscription = storage
                         .btc_sender_dal()
-                        .confirm_inscription(inscription_id, last_inscription_history.id)
+                        .confirm_inscription(inscription_id, confirmed_inscription_history.id)
                         .await?;
                     tracing::info!(
                         "Inscription confirmed {reveal_tx}",
-                        reveal_tx = last_inscription_history.reveal_tx_id,
+                        reveal_tx = confirmed_inscription_history.reveal_tx_id,
                     );
 
                     METRICS
@@ -128,23 +138,21 @@ impl ViaBtcInscriptionManager {
                         )
                         .await;
 
-                    METRICS.track_inscription_confirmation(last_inscription_history.created_at);
-                } else {
-                    let current_block = self
-                        .inscriber
-                        .get_client()
-                        .await
-                        .fetch_block_height()
-                        .await?;
-
-                    if last_inscription_history.sent_at_block
-                        + self.config.stuck_inscription_block_number() as i64
-                        > current_block as i64
-                    {
-                        continue;
+                    // The first attempt is the one the inscription was submitted with.
+                    if let Some(first_inscription_history) = history.last() {
+                        METRICS
+                            .track_inscription_confirmation(first_inscription_history.created_at);
                     }
+                }
+                // Waiting for more confirmations, it's no longer in the mempool so it can't be
+                // replaced.
+                InscriptionStatus::Mined => {}
+                InscriptionStatus::Pending { commit_mined } => {
+                    let is_stuck = last_inscription_history.sent_at_block
+                        + self.config.stuck_inscription_block_number() as i64
+                        <= current_block as i64;
 
-                    if report_blocked_l1_batch_inscription.is_none() {
+                    if is_stuck && report_blocked_l1_batch_inscription.is_none() {
                         let l1_batch_number = storage
                             .via_blocks_dal()
                             .get_first_stuck_l1_batch_number_inscription_request(
@@ -165,10 +173,25 @@ impl ViaBtcInscriptionManager {
                             self.config.stuck_inscription_block_number()
                         );
                     }
+
+                    pending_inscriptions.push(PendingInscription {
+                        id: inscription_id,
+                        history,
+                        commit_mined,
+                        is_stuck,
+                    });
                 }
             }
         }
 
+        if let Err(err) = self
+            .bump_stuck_inscriptions(storage, &pending_inscriptions, current_block)
+            .await
+        {
+            METRICS.l1_transient_errors.inc();
+            tracing::error!("Failed to bump the fee of the stuck inscriptions: {err}");
+        }
+
         let balance = self.inscriber.get_balance().await?;
         METRICS.btc_sender_account_balance.set(balance as usize);
 
@@ -225,11 +248,10 @@ impl ViaBtcInscriptionManager {
             .fetch_block_height()
             .await? as i64;
 
-        let input =
-            InscriptionMessage::from_bytes(&tx.inscription_message.clone().unwrap_or_default());
+        let inputs = inscription_messages(tx)?;
 
         let latency = METRICS.broadcast_time.start();
-        let inscribe_info = match self.inscriber.inscribe(input).await {
+        let inscribe_info = match self.inscriber.inscribe_messages(inputs).await {
             Ok(info) => info,
             Err(e) => {
                 METRICS.l1_transient_errors.inc();
@@ -278,6 +300,209 @@ impl ViaBtcInscriptionManager {
         Ok(())
     }
 
+    /// Returns the mined attempt of an inscription, a replaced reveal tx can still be mined if the
+    /// replacement didn't propagate in time. All the attempts spend the same commit tx, so the
+    /// reveal txs are only looked up once it is mined, and at most one of them can be mined.
+    async fn find_inscription_status<'a>(
+        &self,
+        history: &'a [ViaBtcInscriptionRequestHistory],
+    ) -> anyhow::Result<InscriptionStatus<'a>> {
+        let Some(last_inscription_history) = history.first() else {
+            return Ok(InscriptionStatus::Pending {
+                commit_mined: false,
+            });
+        };
+
+        let client = self.inscriber.get_client().await;
+        if !client
+            .check_tx_confirmation(&last_inscription_history.commit_tx_id, 1)
+            .await?
+        {
+            return Ok(InscriptionStatus::Pending {
+                commit_mined: false,
+            });
+        }
+
+        for inscription_history in history {
+            let is_mined = match client
+                .check_tx_confirmation(&inscription_history.reveal_tx_id, 1)
+                .await
+            {
+                Ok(is_mined) => is_mined,
+                // The node drops the replaced reveal txs from its mempool.
+                Err(BitcoinError::NotFound(_)) => false,
+                Err(err) => return Err(err.into()),
+            };
+            if !is_mined {
+                continue;
+            }
+
+            let is_confirmed = client
+                .check_tx_confirmation(
+                    &inscription_history.reveal_tx_id,
+                    self.config.block_confirmations,
+                )
+                .await?;
+            return Ok(if is_confirmed {
+                InscriptionStatus::Confirmed(inscription_history)
+            } else {
+                InscriptionStatus::Mined
+            });
+        }
+        Ok(InscriptionStatus::Pending { commit_mined: true })
+    }
+
+    /// Bumps the fee of the stuck inscriptions. The reveal tx of an inscription funds the commit
+    /// tx of the next one, so replacing it would evict the newer inscriptions. Instead the reveal
+    /// tx of the newest inscription is replaced, paying the bumped fee rate for all the pending
+    /// inscriptions before it (CPFP).
+    async fn bump_stuck_inscriptions(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        pending_inscriptions: &[PendingInscription],
+        current_block: u64,
+    ) -> anyhow::Result<()> {
+        if !pending_inscriptions
+            .iter()
+            .any(|inscription| inscription.is_stuck)
+        {
+            return Ok(());
+        }
+        let Some((newest_inscription, ancestors)) = pending_inscriptions.split_last() else {
+            return Ok(());
+        };
+
+        let mut ancestors_package = AncestorsPackage::default();
+        for ancestor in ancestors {
+            let (vsize, fees) = ancestor.unconfirmed_package()?;
+            ancestors_package.vsize += vsize;
+            ancestors_package.fees += fees;
+        }
+
+        self.bump_inscription_fee(
+            storage,
+            newest_inscription.id,
+            &newest_inscription.history,
+            ancestors_package,
+            current_block,
+        )
+        .await
+    }
+
+    /// Replaces the reveal tx of the newest pending inscription with one paying a higher fee rate
+    /// for itself and its unconfirmed `ancestors`, and records the new attempt in the inscription
+    /// request history.
+    pub(crate) async fn bump_inscription_fee(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        inscription_id: i64,
+        history: &[ViaBtcInscriptionRequestHistory],
+        ancestors: AncestorsPackage,
+        current_block: u64,
+    ) -> anyhow::Result<()> {
+        let Some(last_inscription_history) = history.first() else {
+            return Ok(());
+        };
+
+        let attempts = history.len() as u32 - 1;
+        if attempts >= self.config.max_fee_bump_attempts() {
+            tracing::debug!(
+                "Inscription {inscription_id} reached the max number of fee bumps ({attempts})"
+            );
+            return Ok(());
+        }
+
+        let (Some(signed_commit_tx), Some(signed_reveal_tx)) = (
+            &last_inscription_history.signed_commit_tx,
+            &last_inscription_history.signed_reveal_tx,
+        ) else {
+            anyhow::bail!("Inscription {inscription_id} has no signed transactions");
+        };
+
+        let commit_tx: Transaction =
+            deserialize(signed_commit_tx).with_context(|| "Error deserializing the commit tx")?;
+        let reveal_tx: Transaction =
+            deserialize(signed_reveal_tx).with_context(|| "Error deserializing the reveal tx")?;
+
+        let Some(inscription) = storage
+            .btc_sender_dal()
+            .get_inscription_request(inscription_id)
+            .await?
+        else {
+            anyhow::bail!("Inscription request {inscription_id} not found");
+        };
+
+        let inputs = inscription_messages(&inscription)?;
+
+        let paid_fees = Amount::from_sat(last_inscription_history.actual_fees as u64);
+        let package_vsize = ancestors.vsize + (commit_tx.vsize() + reveal_tx.vsize()) as u64;
+        let current_fee_rate = (ancestors.fees + paid_fees).to_sat() / package_vsize;
+
+        let network_fee_rate = self
+            .inscriber
+            .get_client()
+            .await
+            .get_fee_rate(FeeRateTarget::for_messages(&inputs).conf_target())
+            .await?;
+
+        let Some(fee_rate) = bumped_fee_rate(
+            current_fee_rate,
+            network_fee_rate,
+            self.config.fee_bump_percentage(),
+            self.config.max_fee_rate(),
+        ) else {
+            tracing::warn!(
+                "Inscription {inscription_id} already pays the max fee rate {} sat/vB",
+                self.config.max_fee_rate()
+            );
+            return Ok(());
+        };
+
+        let replacement = self
+            .inscriber
+            .bump_inscription_fee(
+                &inputs, &commit_tx, &reveal_tx, paid_fees, ancestors, fee_rate,
+            )
+            .await?;
+
+        let signed_reveal_tx = serialize(&replacement.final_reveal_tx.tx)
+            .with_context(|| "Error serializing the reveal tx")?;
+
+        let actual_fees = paid_fees
+            .checked_sub(replacement.replaced_reveal_fee)
+            .unwrap_or(Amount::ZERO)
+            + replacement.reveal_fee;
+
+        tracing::info!(
+            "Inscription {inscription_id} fee bumped from {current_fee_rate} to {fee_rate} sat/vB, reveal tx {replaced_reveal_tx} replaced by {reveal_tx}",
+            replaced_reveal_tx = replacement.replaced_reveal_txid,
+            reveal_tx = replacement.final_reveal_tx.txid,
+        );
+
+        storage
+            .btc_sender_dal()
+            .insert_inscription_request_history(
+                &last_inscription_history
+                    .commit_tx_id
+                    .as_raw_hash()
+                    .to_byte_array(),
+                &replacement
+                    .final_reveal_tx
+                    .txid
+                    .as_raw_hash()
+                    .to_byte_array(),
+                inscription_id,
+                signed_commit_tx,
+                &signed_reveal_tx,
+                actual_fees.to_sat() as i64,
+                current_block as i64,
+            )
+            .await?;
+
+        METRICS.inscription_fee_bumps.inc();
+        Ok(())
+    }
+
     fn validate_inscriber_address(
         &self,
         wallets_map: HashMap<String, String>,
@@ -295,3 +520,96 @@ impl ViaBtcInscriptionManager {
         Ok(())
     }
 }
+
+/// Status of an inflight inscription, `commit_mined` tells whether its commit tx left the mempool.
+enum InscriptionStatus<'a> {
+    Confirmed(&'a ViaBtcInscriptionRequestHistory),
+    /// A reveal tx is mined but doesn't have `block_confirmations` yet.
+    Mined,
+    Pending {
+        commit_mined: bool,
+    },
+}
+
+/// An inflight inscription without confirmed attempt.
+struct PendingInscription {
+    id: i64,
+    /// Attempts of the inscription, newest first.
+    history: Vec<ViaBtcInscriptionRequestHistory>,
+    commit_mined: bool,
+    is_stuck: bool,
+}
+
+impl PendingInscription {
+    /// Returns the vsize and the fees of the transactions of the inscription still in the
+    /// mempool: its latest reveal tx, and its commit tx unless it is mined.
+    fn unconfirmed_package(&self) -> anyhow::Result<(u64, Amount)> {
+        let Some(last_inscription_history) = self.history.first() else {
+            return Ok((0, Amount::ZERO));
+        };
+        let (Some(signed_commit_tx), Some(signed_reveal_tx)) = (
+            &last_inscription_history.signed_commit_tx,
+            &last_inscription_history.signed_reveal_tx,
+        ) else {
+            anyhow::bail!("Inscription {} has no signed transactions", self.id);
+        };
+        let commit_tx: Transaction =
+            deserialize(signed_commit_tx).with_context(|| "Error deserializing the commit tx")?;
+        let reveal_tx: Transaction =
+            deserialize(signed_reveal_tx).with_context(|| "Error deserializing the reveal tx")?;
+
+        let paid_fees = Amount::from_sat(last_inscription_history.actual_fees as u64);
+        if !self.commit_mined {
+            return Ok(((commit_tx.vsize() + reveal_tx.vsize()) as u64, paid_fees));
+        }
+
+        // The reveal tx only spends outputs of the commit tx.
+        let reveal_inputs_value: Amount = reveal_tx
+            .input
+            .iter()
+            .filter_map(|input| commit_tx.output.get(input.previous_output.vout as usize))
+            .map(|output| output.value)
+            .sum();
+        let reveal_outputs_value: Amount = reveal_tx.output.iter().map(|output| output.value).sum();
+        let reveal_fee = reveal_inputs_value
+            .checked_sub(reveal_outputs_value)
+            .unwrap_or(Amount::ZERO);
+        Ok((reveal_tx.vsize() as u64, reveal_fee))
+    }
+}
+
+/// Returns the messages of an inscription request, packed requests carry several messages.
+fn inscription_messages(
+    inscription: &ViaBtcInscriptionRequest,
+) -> anyhow::Result<Vec<InscriptionMessage>> {
+    let bytes = inscription.inscription_message.clone().unwrap_or_default();
+    let messages = match ViaBtcInscriptionRequestType::from(inscription.request_type.clone()) {
+        ViaBtcInscriptionRequestType::CommitL1BatchAndProofOnchain => {
+            Vec::<InscriptionMessage>::from_bytes(&bytes)
+        }
+        ViaBtcInscriptionRequestType::CommitL1BatchOnchain
+        | ViaBtcInscriptionRequestType::CommitProofOnchain => {
+            InscriptionMessage::from_bytes(&bytes).map(|message| vec![message])
+        }
+    };
+    messages.with_context(|| format!("Malformed inscription request {}", inscription.id))
+}
+
+/// Returns the fee rate of the next fee bump attempt: at least `percentage` above the current fee
+/// rate and not below the network estimation, capped by `max_fee_rate`. Returns `None` when the
+/// cap doesn't leave room for an increase.
+pub(crate) fn bumped_fee_rate(
+    current_fee_rate: u64,
+    network_fee_rate: u64,
+    percentage: u64,
+    max_fee_rate: u64,
+) -> Option<u64> {
+    let escalated_fee_rate =
+        current_fee_rate + std::cmp::max(current_fee_rate * percentage / 100, 1);
+    let fee_rate = std::cmp::min(
+        std::cmp::max(escalated_fee_rate, network_fee_rate),
+        max_fee_rate,
+    );
+
+    (fee_rate > current_fee_rate).then_some(fee_rate)
+}
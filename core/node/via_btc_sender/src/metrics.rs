@@ -51,6 +51,9 @@ pub struct ViaBtcSenderMetrics {
     /// Error when broadcast a transaction.
     pub l1_transient_errors: Counter,
 
+    /// Number of stuck inscriptions re-broadcast with a higher fee.
+    pub inscription_fee_bumps: Counter,
+
     /// Last L1 block observed by the Ethereum sender.
     pub last_known_l1_block: Family<BlockNumberVariant, Gauge<usize>>,
 
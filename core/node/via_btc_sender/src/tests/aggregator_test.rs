@@ -356,10 +356,13 @@ mod tests {
             .aggregator
             .construct_inscription_message(
                 &ViaBtcInscriptionRequestType::CommitL1BatchOnchain,
-                &batch,
+                &[&batch],
             )
             .unwrap();
-        let message_bytes = InscriptionMessage::to_bytes(&message);
-        assert_eq!(InscriptionMessage::from_bytes(&message_bytes), message);
+        let message_bytes = InscriptionMessage::to_bytes(&message).unwrap();
+        assert_eq!(
+            InscriptionMessage::from_bytes(&message_bytes).unwrap(),
+            message
+        );
     }
 }
@@ -9,16 +9,81 @@ mod tests {
     use zksync_dal::{ConnectionPool, Core, CoreDal};
     use zksync_node_test_utils::l1_batch_metadata_to_commitment_artifacts;
     use zksync_types::{
-        block::L1BatchHeader, btc_inscription_operations::ViaBtcInscriptionRequestType,
-        via_btc_sender::ViaBtcInscriptionRequest, ProtocolVersionId, H256,
+        block::L1BatchHeader, btc_block::ViaBtcL1BlockDetails,
+        btc_inscription_operations::ViaBtcInscriptionRequestType,
+        via_btc_sender::ViaBtcInscriptionRequest, L1BatchNumber, ProtocolVersionId, H256,
     };
 
-    use crate::tests::utils::{
-        create_l1_batch, default_l1_batch_metadata, get_btc_sender_config,
-        get_inscription_aggregator_mock, ViaAggregatorTest, BOOTLOADER_CODE_HASH_TEST,
-        DEFAULT_AA_CODE_HASH_TEST,
+    use crate::{
+        aggregated_operations::ViaAggregatedOperation,
+        btc_inscription_aggregator::group_inscriptions,
+        tests::utils::{
+            create_btc_l1_batch_details, create_l1_batch, default_l1_batch_metadata,
+            get_btc_sender_config, get_inscription_aggregator_mock, ViaAggregatorTest,
+            BOOTLOADER_CODE_HASH_TEST, DEFAULT_AA_CODE_HASH_TEST,
+        },
     };
 
+    #[test]
+    fn test_group_inscriptions_pairs_commit_with_proof() {
+        let operations = vec![
+            ViaAggregatedOperation::CommitL1BatchOnchain(vec![create_btc_l1_batch_details(
+                L1BatchNumber(3),
+                0,
+            )]),
+            ViaAggregatedOperation::CommitProofOnchain(vec![create_btc_l1_batch_details(
+                L1BatchNumber(2),
+                0,
+            )]),
+        ];
+
+        let inscriptions = group_inscriptions(&operations);
+        assert_eq!(inscriptions.len(), 1);
+        assert_eq!(inscriptions[0].len(), 2);
+        assert_eq!(
+            inscriptions[0][0].0,
+            ViaBtcInscriptionRequestType::CommitL1BatchOnchain
+        );
+        assert_eq!(inscriptions[0][0].1[0].number.0, 3);
+        assert_eq!(
+            inscriptions[0][1].0,
+            ViaBtcInscriptionRequestType::CommitProofOnchain
+        );
+        assert_eq!(inscriptions[0][1].1[0].number.0, 2);
+
+        // Without a proof to pair with, the commit is inscribed on its own.
+        let inscriptions = group_inscriptions(&operations[..1]);
+        assert_eq!(inscriptions.len(), 1);
+        assert_eq!(inscriptions[0].len(), 1);
+    }
+
+    #[test]
+    fn test_group_inscriptions_proof_range() {
+        let proof = |number: u32, blob_id: &str| {
+            let mut batch = create_btc_l1_batch_details(L1BatchNumber(number), 0);
+            batch.blob_id = blob_id.to_string();
+            batch
+        };
+        let operations = vec![ViaAggregatedOperation::CommitProofOnchain(vec![
+            proof(1, "range_1_2"),
+            proof(2, "range_1_2"),
+            proof(3, "range_3"),
+        ])];
+
+        // The batches proven by the same blob are inscribed in one message.
+        let inscriptions = group_inscriptions(&operations);
+        assert_eq!(inscriptions.len(), 2);
+        assert_eq!(inscriptions[0].len(), 1);
+        let numbers = |batches: &[&ViaBtcL1BlockDetails]| {
+            batches
+                .iter()
+                .map(|batch| batch.number.0)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(&inscriptions[0][0].1), vec![1, 2]);
+        assert_eq!(numbers(&inscriptions[1][0].1), vec![3]);
+    }
+
     #[tokio::test]
     async fn test_btc_inscription_aggregator_run_multiple_batch() {
         let pool = ConnectionPool::<Core>::test_pool().await;
@@ -70,7 +135,11 @@ mod tests {
             let _ = aggregator_test
                 .storage
                 .via_data_availability_dal()
-                .insert_proof_da(header.number, "blob_id", sent_at)
+                .insert_proof_da(
+                    header.number,
+                    &format!("proof_blob_id_{}", header.number),
+                    sent_at,
+                )
                 .await;
         }
 
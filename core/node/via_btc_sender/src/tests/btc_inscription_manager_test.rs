@@ -4,7 +4,7 @@ mod tests {
 
     use chrono::Utc;
     use tokio::{sync::watch, time};
-    use via_btc_client::inscriber::test_utils::MockBitcoinOpsConfig;
+    use via_btc_client::{inscriber::test_utils::MockBitcoinOpsConfig, types::AncestorsPackage};
     use zksync_config::ViaBtcSenderConfig;
     use zksync_contracts::BaseSystemContractsHashes;
     use zksync_dal::{ConnectionPool, Core, CoreDal};
@@ -14,10 +14,13 @@ mod tests {
         ProtocolVersionId, H256,
     };
 
-    use crate::tests::utils::{
-        create_l1_batch, default_l1_batch_metadata, get_btc_sender_config,
-        get_inscription_aggregator_mock, get_inscription_manager_mock, ViaAggregatorTest,
-        BOOTLOADER_CODE_HASH_TEST, DEFAULT_AA_CODE_HASH_TEST,
+    use crate::{
+        btc_inscription_manager::bumped_fee_rate,
+        tests::utils::{
+            create_l1_batch, default_l1_batch_metadata, get_btc_sender_config,
+            get_inscription_aggregator_mock, get_inscription_manager_mock, ViaAggregatorTest,
+            BOOTLOADER_CODE_HASH_TEST, DEFAULT_AA_CODE_HASH_TEST,
+        },
     };
 
     #[tokio::test]
@@ -81,7 +84,11 @@ mod tests {
             let _ = aggregator_test
                 .storage
                 .via_data_availability_dal()
-                .insert_proof_da(header.number, "blob_id", sent_at)
+                .insert_proof_da(
+                    header.number,
+                    &format!("proof_blob_id_{}", header.number),
+                    sent_at,
+                )
                 .await;
         }
 
@@ -175,6 +182,111 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_btc_inscription_manager_bumps_stuck_inscription_fee() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let config = get_btc_sender_config(1, 1);
+        let mut mock_btc_ops_config = MockBitcoinOpsConfig::default();
+        mock_btc_ops_config.set_block_height(1);
+
+        let header = via_create_l1_batch(1);
+
+        let mut aggregator_test = ViaAggregatorTest::new(
+            header.protocol_version.unwrap(),
+            header.base_system_contracts_hashes,
+            pool.clone(),
+            Some(config.clone()),
+        )
+        .await;
+
+        aggregator_test.create_genesis_l1_batch().await.unwrap();
+        aggregator_test
+            .insert_l1_batch(
+                header.clone(),
+                l1_batch_metadata_to_commitment_artifacts(&default_l1_batch_metadata()),
+            )
+            .await;
+
+        let _ = aggregator_test
+            .storage
+            .via_data_availability_dal()
+            .insert_l1_batch_da(header.number, "blob_id", Utc::now().naive_utc())
+            .await;
+
+        run_aggregator(pool.clone(), config.clone()).await;
+
+        let inscription = aggregator_test
+            .storage
+            .btc_sender_dal()
+            .list_new_inscription_request(1)
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+
+        let mut manager =
+            get_inscription_manager_mock(pool.clone(), config.clone(), mock_btc_ops_config).await;
+
+        manager
+            .send_inscription_tx(&mut aggregator_test.storage, &inscription)
+            .await
+            .unwrap();
+
+        let history = aggregator_test
+            .storage
+            .btc_sender_dal()
+            .list_inscription_request_history(inscription.id)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+
+        manager
+            .bump_inscription_fee(
+                &mut aggregator_test.storage,
+                inscription.id,
+                &history,
+                AncestorsPackage::default(),
+                10,
+            )
+            .await
+            .unwrap();
+
+        let bumped_history = aggregator_test
+            .storage
+            .btc_sender_dal()
+            .list_inscription_request_history(inscription.id)
+            .await
+            .unwrap();
+
+        assert_eq!(bumped_history.len(), 2);
+        assert_eq!(bumped_history[0].commit_tx_id, history[0].commit_tx_id);
+        assert_ne!(bumped_history[0].reveal_tx_id, history[0].reveal_tx_id);
+        assert_eq!(bumped_history[0].sent_at_block, 10);
+        assert!(bumped_history[0].actual_fees > history[0].actual_fees);
+
+        // The inscription stays inflight until one of its attempts is confirmed.
+        let inflight_inscription_ids = aggregator_test
+            .storage
+            .btc_sender_dal()
+            .list_inflight_inscription_ids()
+            .await
+            .unwrap();
+        assert_eq!(inflight_inscription_ids, vec![inscription.id]);
+    }
+
+    #[test]
+    fn test_bumped_fee_rate() {
+        // Escalates by the configured percentage.
+        assert_eq!(bumped_fee_rate(10, 5, 25, 500), Some(12));
+        // Always increases, even when the percentage rounds to zero.
+        assert_eq!(bumped_fee_rate(1, 0, 25, 500), Some(2));
+        // Follows the network when it is above the escalated fee rate.
+        assert_eq!(bumped_fee_rate(10, 30, 25, 500), Some(30));
+        // Capped by the max fee rate.
+        assert_eq!(bumped_fee_rate(10, 30, 25, 20), Some(20));
+        assert_eq!(bumped_fee_rate(20, 30, 25, 20), None);
+    }
+
     async fn run_aggregator(pool: ConnectionPool<Core>, config: ViaBtcSenderConfig) {
         {
             // Create an async channel to break the while loop afer 3 seconds.
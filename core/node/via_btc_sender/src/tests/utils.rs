@@ -251,7 +251,7 @@ impl ViaAggregatorTest {
             .aggregator
             .construct_inscription_message(
                 &ViaBtcInscriptionRequestType::CommitL1BatchOnchain,
-                &batch,
+                &[&batch],
             )
             .unwrap();
 
@@ -261,7 +261,7 @@ impl ViaAggregatorTest {
             .via_save_btc_inscriptions_request(
                 batch.number,
                 ViaBtcInscriptionRequestType::CommitL1BatchOnchain.to_string(),
-                InscriptionMessage::to_bytes(&inscription_message),
+                InscriptionMessage::to_bytes(&inscription_message).unwrap(),
                 0,
             )
             .await
@@ -296,7 +296,11 @@ impl ViaAggregatorTest {
         let _ = self
             .storage
             .via_data_availability_dal()
-            .insert_proof_da(batch.number, "blob_id", sent_at)
+            .insert_proof_da(
+                batch.number,
+                &format!("proof_blob_id_{}", batch.number),
+                sent_at,
+            )
             .await;
 
         (inscription_id, inscription_request_history_id as i64)
@@ -153,7 +153,10 @@ impl SystemWalletProcessor {
 
                     storage
                         .via_wallet_dal()
-                        .insert_wallets(&wallets_details)
+                        .insert_wallets(
+                            &wallets_details,
+                            Some(update_bridge_msg.common.block_height),
+                        )
                         .await?;
 
                     indexer.update_system_wallets(
@@ -212,7 +215,10 @@ impl SystemWalletProcessor {
 
         storage
             .via_wallet_dal()
-            .insert_wallets(&wallets_details)
+            .insert_wallets(
+                &wallets_details,
+                Some(update_sequencer_msg.common.block_height),
+            )
             .await?;
 
         indexer.update_system_wallets(Some(new_sequencer_address), None, None, None);
@@ -262,7 +268,10 @@ impl SystemWalletProcessor {
 
         storage
             .via_wallet_dal()
-            .insert_wallets(&wallets_details)
+            .insert_wallets(
+                &wallets_details,
+                Some(update_governance_msg.common.block_height),
+            )
             .await?;
 
         indexer.update_system_wallets(None, None, None, Some(new_governance_address));
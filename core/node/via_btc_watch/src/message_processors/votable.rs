@@ -28,16 +28,31 @@ impl MessageProcessor for VotableMessageProcessor {
         for msg in msgs {
             match msg {
                 ref f @ FullInscriptionMessage::ValidatorAttestation(ref attestation_msg) => {
-                    if let Some(l1_batch_number) = indexer.get_l1_batch_number(f).await {
-                        let proof_reveal_txid = attestation_msg.input.reference_txid[..].to_vec();
+                    let Some(l1_batch_numbers) = indexer.get_l1_batch_numbers(f).await else {
+                        continue;
+                    };
+                    let proof_reveal_txid = attestation_msg.input.reference_txid[..].to_vec();
 
-                        // Vote = true if attestation_msg.input.attestation == Vote::Ok
-                        let is_ok = matches!(
-                            attestation_msg.input.attestation,
-                            via_btc_client::types::Vote::Ok
-                        );
+                    // Vote = true if attestation_msg.input.attestation == Vote::Ok
+                    let is_ok = matches!(
+                        attestation_msg.input.attestation,
+                        via_btc_client::types::Vote::Ok
+                    );
 
-                        if !storage
+                    let p2wpkh_address = attestation_msg
+                        .common
+                        .p2wpkh_address
+                        .as_ref()
+                        .expect("ValidatorAttestation message must have a p2wpkh address");
+
+                    let mut transaction = storage
+                        .start_transaction()
+                        .await
+                        .map_err(|e| MessageProcessorError::DatabaseError(e.to_string()))?;
+
+                    // The vote on the proof of a range applies to every L1 batch of the range.
+                    for l1_batch_number in l1_batch_numbers {
+                        if !transaction
                             .via_blocks_dal()
                             .l1_batch_proof_tx_exists(l1_batch_number.0 as i64, &proof_reveal_txid)
                             .await
@@ -51,17 +66,6 @@ impl MessageProcessor for VotableMessageProcessor {
                             continue;
                         }
 
-                        let p2wpkh_address = attestation_msg
-                            .common
-                            .p2wpkh_address
-                            .as_ref()
-                            .expect("ValidatorAttestation message must have a p2wpkh address");
-
-                        let mut transaction = storage
-                            .start_transaction()
-                            .await
-                            .map_err(|e| MessageProcessorError::DatabaseError(e.to_string()))?;
-
                         transaction
                             .via_votes_dal()
                             .insert_vote(
@@ -69,6 +73,7 @@ impl MessageProcessor for VotableMessageProcessor {
                                 &proof_reveal_txid,
                                 &p2wpkh_address.to_string(),
                                 is_ok,
+                                attestation_msg.common.block_height,
                             )
                             .await
                             .map_err(|e| MessageProcessorError::DatabaseError(e.to_string()))?;
@@ -94,12 +99,12 @@ impl MessageProcessor for VotableMessageProcessor {
                                 l1_batch_number
                             );
                         }
-
-                        transaction
-                            .commit()
-                            .await
-                            .map_err(|e| MessageProcessorError::DatabaseError(e.to_string()))?;
                     }
+
+                    transaction
+                        .commit()
+                        .await
+                        .map_err(|e| MessageProcessorError::DatabaseError(e.to_string()))?;
                 }
                 _ => (),
             }
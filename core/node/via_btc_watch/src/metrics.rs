@@ -1,4 +1,4 @@
-use vise::{EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
@@ -13,6 +13,8 @@ pub enum InscriptionStage {
 pub struct ViaBtcWatcherMetrics {
     /// Number of inscriptions processed, labeled by type.
     pub inscriptions_processed: Family<InscriptionStage, Gauge<usize>>,
+    /// Number of Bitcoin reorgs that rewound the processed blocks.
+    pub reorgs: Counter,
 }
 
 #[vise::register]
@@ -1 +1,2 @@
+mod reorg;
 mod system_wallets;
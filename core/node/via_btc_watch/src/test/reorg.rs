@@ -0,0 +1,563 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use bitcoin::{
+        block::{Header, Version},
+        hashes::Hash,
+        Address, Block, BlockHash, CompactTarget, Network, OutPoint, Transaction, TxMerkleNode,
+        TxOut, Txid,
+    };
+    use bitcoincore_rpc::json::GetBlockStatsResult;
+    use via_btc_client::{
+        indexer::BitcoinInscriptionIndexer,
+        traits::BitcoinOps,
+        types::{BitcoinClientResult, BitcoinError},
+    };
+    use via_test_utils::utils::{random_bitcoin_wallet, test_bitcoin_client, test_wallets};
+    use zksync_config::ViaBtcWatchConfig;
+    use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+    use zksync_types::{
+        block::{L1BatchHeader, L2BlockHeader},
+        btc_inscription_operations::ViaBtcInscriptionRequestType,
+        l1::{L1Tx, OpProcessingType, PriorityQueueType},
+        via_wallet::{SystemWallets, SystemWalletsDetails},
+        Address as L2Address, Execute, L1BatchNumber, L1BlockNumber, L1TxCommonData, L2BlockNumber,
+        PriorityOpId, ProtocolVersion, ProtocolVersionId, H256, U256,
+    };
+    use zksync_vm_interface::{TransactionExecutionResult, TxExecutionStatus, VmExecutionMetrics};
+
+    use crate::{
+        message_processors::{MessageProcessorError, SystemWalletProcessor},
+        BtcWatch, BtcWatchState,
+    };
+
+    /// A Bitcoin chain held in memory, the block at height `i` being `blocks[i]`.
+    #[derive(Debug, Clone, Default)]
+    struct TestChain {
+        blocks: Vec<Block>,
+    }
+
+    impl TestChain {
+        fn new(len: usize) -> Self {
+            let mut chain = Self::default();
+            chain.extend(len, 0);
+            chain
+        }
+
+        /// Mines `count` blocks, `salt` tells them apart from the blocks of another branch.
+        fn extend(&mut self, count: usize, salt: u32) {
+            for _ in 0..count {
+                let prev_blockhash = self
+                    .blocks
+                    .last()
+                    .map_or(BlockHash::all_zeros(), Block::block_hash);
+                self.blocks.push(Block {
+                    header: Header {
+                        version: Version::TWO,
+                        prev_blockhash,
+                        merkle_root: TxMerkleNode::all_zeros(),
+                        time: self.blocks.len() as u32,
+                        bits: CompactTarget::from_consensus(0),
+                        nonce: salt,
+                    },
+                    txdata: vec![],
+                });
+            }
+        }
+
+        /// Returns the chain where the blocks above `fork_block` are replaced by `count` blocks.
+        fn reorg(&self, fork_block: u32, count: usize) -> Self {
+            let mut chain = Self {
+                blocks: self.blocks[..=fork_block as usize].to_vec(),
+            };
+            chain.extend(count, 1);
+            chain
+        }
+
+        fn hash(&self, block_number: u32) -> BlockHash {
+            self.blocks[block_number as usize].block_hash()
+        }
+    }
+
+    #[async_trait]
+    impl BitcoinOps for TestChain {
+        async fn get_balance(&self, _address: &Address) -> BitcoinClientResult<u128> {
+            unimplemented!()
+        }
+
+        async fn broadcast_signed_transaction(
+            &self,
+            _signed_transaction: &str,
+        ) -> BitcoinClientResult<Txid> {
+            unimplemented!()
+        }
+
+        async fn fetch_utxos(
+            &self,
+            _address: &Address,
+        ) -> BitcoinClientResult<Vec<(OutPoint, TxOut)>> {
+            unimplemented!()
+        }
+
+        async fn check_tx_confirmation(
+            &self,
+            _txid: &Txid,
+            _conf_num: u32,
+        ) -> BitcoinClientResult<bool> {
+            unimplemented!()
+        }
+
+        async fn fetch_block_height(&self) -> BitcoinClientResult<u64> {
+            Ok(self.blocks.len() as u64 - 1)
+        }
+
+        async fn get_fee_rate(&self, _conf_target: u16) -> BitcoinClientResult<u64> {
+            unimplemented!()
+        }
+
+        fn get_network(&self) -> Network {
+            Network::Regtest
+        }
+
+        async fn fetch_block(&self, block_height: u128) -> BitcoinClientResult<Block> {
+            self.blocks
+                .get(block_height as usize)
+                .cloned()
+                .ok_or_else(|| BitcoinError::Other(format!("No block {block_height}")))
+        }
+
+        async fn get_transaction(&self, _txid: &Txid) -> BitcoinClientResult<Transaction> {
+            unimplemented!()
+        }
+
+        async fn fetch_block_by_hash(&self, block_hash: &BlockHash) -> BitcoinClientResult<Block> {
+            self.blocks
+                .iter()
+                .find(|block| block.block_hash() == *block_hash)
+                .cloned()
+                .ok_or_else(|| BitcoinError::Other(format!("No block {block_hash}")))
+        }
+
+        async fn get_block_stats(&self, _height: u64) -> BitcoinClientResult<GetBlockStatsResult> {
+            unimplemented!()
+        }
+
+        async fn get_fee_history(
+            &self,
+            _from_block_height: usize,
+            _to_block_height: usize,
+        ) -> BitcoinClientResult<Vec<u64>> {
+            unimplemented!()
+        }
+    }
+
+    fn create_btc_watch(pool: &ConnectionPool<Core>, chain: TestChain) -> BtcWatch {
+        BtcWatch {
+            btc_watch_config: ViaBtcWatchConfig::for_tests(),
+            indexer: BitcoinInscriptionIndexer::with_client(
+                Arc::new(chain),
+                Arc::new(test_wallets()),
+            ),
+            pool: pool.clone(),
+            state: BtcWatchState {
+                last_processed_bitcoin_block: 0,
+            },
+            system_wallet_processor: Box::new(SystemWalletProcessor::new(Arc::new(
+                test_bitcoin_client(),
+            ))),
+            message_processors: vec![],
+        }
+    }
+
+    /// Indexes the blocks `1..=last_block` of `chain`.
+    async fn index_chain(
+        storage: &mut Connection<'_, Core>,
+        btc_watch: &mut BtcWatch,
+        chain: &TestChain,
+        last_block: u32,
+    ) {
+        let block_hashes: Vec<_> = (1..=last_block)
+            .map(|number| (number, chain.hash(number).to_byte_array().to_vec()))
+            .collect();
+        let mut indexer_dal = storage.via_indexer_dal();
+        indexer_dal
+            .init_indexer_metadata(BtcWatch::module_name(), 1)
+            .await
+            .unwrap();
+        indexer_dal
+            .insert_l1_block_hashes(BtcWatch::module_name(), &block_hashes)
+            .await
+            .unwrap();
+        indexer_dal
+            .update_last_processed_l1_block(BtcWatch::module_name(), last_block)
+            .await
+            .unwrap();
+        btc_watch.state.last_processed_bitcoin_block = last_block;
+    }
+
+    fn priority_tx(serial_id: u64) -> L1Tx {
+        L1Tx {
+            common_data: L1TxCommonData {
+                sender: L2Address::random(),
+                serial_id: PriorityOpId(serial_id),
+                layer_2_tip_fee: U256::zero(),
+                full_fee: U256::zero(),
+                max_fee_per_gas: U256::one(),
+                gas_limit: U256::from(100_000),
+                gas_per_pubdata_limit: U256::from(800),
+                op_processing_type: OpProcessingType::Common,
+                priority_queue_type: PriorityQueueType::Deque,
+                canonical_tx_hash: H256::from_low_u64_be(serial_id),
+                to_mint: U256::zero(),
+                refund_recipient: L2Address::random(),
+                eth_block: 0,
+            },
+            execute: Execute {
+                contract_address: L2Address::random(),
+                calldata: vec![],
+                value: U256::zero(),
+                factory_deps: vec![],
+            },
+            received_timestamp_ms: 0,
+        }
+    }
+
+    /// Inserts a deposit indexed from `l1_block_number`, returns its Bitcoin txid.
+    async fn insert_deposit(
+        storage: &mut Connection<'_, Core>,
+        tx: &L1Tx,
+        l1_block_number: u32,
+    ) -> H256 {
+        let txid = H256::random();
+        storage
+            .via_transactions_dal()
+            .insert_transaction_l1(tx, L1BlockNumber(l1_block_number), txid)
+            .await
+            .unwrap();
+        txid
+    }
+
+    async fn execute_deposit(storage: &mut Connection<'_, Core>, tx: L1Tx) {
+        let l2_block_header = L2BlockHeader {
+            number: L2BlockNumber(1),
+            timestamp: 1,
+            hash: H256::from_low_u64_be(1),
+            l1_tx_count: 1,
+            l2_tx_count: 0,
+            fee_account_address: L2Address::default(),
+            base_fee_per_gas: 0,
+            batch_fee_input: Default::default(),
+            gas_per_pubdata_limit: 0,
+            base_system_contracts_hashes: Default::default(),
+            protocol_version: Some(ProtocolVersionId::latest()),
+            virtual_blocks: 1,
+            gas_limit: 0,
+            logs_bloom: Default::default(),
+        };
+        storage
+            .blocks_dal()
+            .insert_l2_block(&l2_block_header)
+            .await
+            .unwrap();
+
+        let execution_result = TransactionExecutionResult {
+            hash: tx.hash(),
+            transaction: tx.into(),
+            execution_info: VmExecutionMetrics::default(),
+            execution_status: TxExecutionStatus::Success,
+            refunded_gas: 0,
+            operator_suggested_refund: 0,
+            compressed_bytecodes: vec![],
+            call_traces: vec![],
+            revert_reason: None,
+        };
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_l2_block(
+                L2BlockNumber(1),
+                &[execution_result],
+                U256::one(),
+                ProtocolVersionId::latest(),
+                false,
+            )
+            .await
+            .unwrap();
+    }
+
+    /// Inserts the L1 batch `number`, finalized by a vote indexed from `l1_block_number`.
+    async fn insert_finalized_l1_batch(
+        storage: &mut Connection<'_, Core>,
+        number: u32,
+        l1_block_number: u32,
+    ) {
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(number),
+            number.into(),
+            Default::default(),
+            ProtocolVersionId::latest(),
+        );
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&l1_batch_header)
+            .await
+            .unwrap();
+
+        let inscription_request_id = storage
+            .btc_sender_dal()
+            .via_save_btc_inscriptions_request(
+                L1BatchNumber(number),
+                ViaBtcInscriptionRequestType::CommitL1BatchOnchain
+                    .as_str()
+                    .to_string(),
+                vec![],
+                0,
+            )
+            .await
+            .unwrap();
+        storage
+            .via_blocks_dal()
+            .insert_l1_batch_inscription_request_id(
+                L1BatchNumber(number),
+                inscription_request_id,
+                ViaBtcInscriptionRequestType::CommitL1BatchOnchain,
+            )
+            .await
+            .unwrap();
+
+        let mut votes_dal = storage.via_votes_dal();
+        votes_dal
+            .insert_vote(number, &[], "verifier_address", true, l1_block_number)
+            .await
+            .unwrap();
+        assert!(votes_dal
+            .finalize_transaction_if_needed(number, 0.5, 1)
+            .await
+            .unwrap());
+    }
+
+    async fn system_wallets(storage: &mut Connection<'_, Core>) -> SystemWallets {
+        let system_wallets_map = storage
+            .via_wallet_dal()
+            .get_system_wallets_raw()
+            .await
+            .unwrap()
+            .unwrap();
+        SystemWallets::try_from(system_wallets_map).unwrap()
+    }
+
+    async fn setup_storage(storage: &mut Connection<'_, Core>) {
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        storage
+            .via_wallet_dal()
+            .insert_wallets(
+                &SystemWalletsDetails::try_from(test_wallets()).unwrap(),
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_find_fork_block() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        let chain = TestChain::new(11);
+
+        let mut next_chain = chain.clone();
+        next_chain.extend(1, 0);
+        let mut btc_watch = create_btc_watch(&pool, next_chain.clone());
+        index_chain(&mut storage, &mut btc_watch, &chain, 10).await;
+
+        // The next block extends the processed ones.
+        assert_eq!(
+            btc_watch
+                .find_fork_block(&mut storage, &next_chain.hash(11))
+                .await
+                .unwrap(),
+            None
+        );
+
+        // The last 3 processed blocks were reorganized out.
+        let reorged_chain = chain.reorg(7, 5);
+        btc_watch.indexer = BitcoinInscriptionIndexer::with_client(
+            Arc::new(reorged_chain.clone()),
+            Arc::new(test_wallets()),
+        );
+        assert_eq!(
+            btc_watch
+                .find_fork_block(&mut storage, &reorged_chain.hash(11))
+                .await
+                .unwrap(),
+            Some(7)
+        );
+
+        // The blocks processed before their hashes were stored are assumed to be final.
+        storage
+            .via_indexer_dal()
+            .delete_l1_block_hashes_after(BtcWatch::module_name(), 0)
+            .await
+            .unwrap();
+        storage
+            .via_indexer_dal()
+            .insert_l1_block_hashes(
+                BtcWatch::module_name(),
+                &[(10, chain.hash(10).to_byte_array().to_vec())],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            btc_watch
+                .find_fork_block(&mut storage, &reorged_chain.hash(11))
+                .await
+                .unwrap(),
+            Some(9)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rewind_orphaned_blocks() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        setup_storage(&mut storage).await;
+
+        let chain = TestChain::new(11);
+        let reorged_chain = chain.reorg(7, 5);
+        let mut btc_watch = create_btc_watch(&pool, reorged_chain.clone());
+        index_chain(&mut storage, &mut btc_watch, &chain, 10).await;
+
+        // The deposits, votes and wallet updates indexed from the blocks 5 and 9.
+        let kept_deposit = insert_deposit(&mut storage, &priority_tx(0), 5).await;
+        let orphaned_deposit = insert_deposit(&mut storage, &priority_tx(1), 9).await;
+        insert_finalized_l1_batch(&mut storage, 1, 5).await;
+        insert_finalized_l1_batch(&mut storage, 2, 9).await;
+
+        let mut updated_wallets = test_wallets();
+        updated_wallets.sequencer = random_bitcoin_wallet().1;
+        storage
+            .via_wallet_dal()
+            .insert_wallets(
+                &SystemWalletsDetails::try_from(updated_wallets.clone()).unwrap(),
+                Some(9),
+            )
+            .await
+            .unwrap();
+        assert_eq!(system_wallets(&mut storage).await, updated_wallets);
+        assert_eq!(
+            storage
+                .via_blocks_dal()
+                .get_last_finalized_l1_batch()
+                .await
+                .unwrap(),
+            2
+        );
+
+        // The first new block doesn't extend the processed chain, the watch rewinds to the fork.
+        btc_watch.loop_iteration(&mut storage).await.unwrap();
+        assert_eq!(btc_watch.state.last_processed_bitcoin_block, 7);
+        assert_eq!(
+            storage
+                .via_indexer_dal()
+                .get_last_processed_l1_block(BtcWatch::module_name())
+                .await
+                .unwrap(),
+            7
+        );
+
+        let mut transactions_dal = storage.via_transactions_dal();
+        assert!(transactions_dal
+            .transaction_exists_with_txid(&kept_deposit)
+            .await
+            .unwrap());
+        assert!(!transactions_dal
+            .transaction_exists_with_txid(&orphaned_deposit)
+            .await
+            .unwrap());
+
+        // The L1 batch finalized by an orphaned vote waits for the votes of the new chain.
+        assert_eq!(
+            storage
+                .via_blocks_dal()
+                .get_last_finalized_l1_batch()
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            storage.via_votes_dal().get_vote_count(2).await.unwrap(),
+            (0, 0, 0)
+        );
+
+        assert_eq!(system_wallets(&mut storage).await, test_wallets());
+        assert_eq!(*btc_watch.indexer.get_state(), test_wallets());
+
+        let mut indexer_dal = storage.via_indexer_dal();
+        assert!(indexer_dal
+            .get_l1_block_hash(BtcWatch::module_name(), 7)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(indexer_dal
+            .get_l1_block_hash(BtcWatch::module_name(), 8)
+            .await
+            .unwrap()
+            .is_none());
+
+        // The blocks of the new chain are indexed from the fork.
+        btc_watch.loop_iteration(&mut storage).await.unwrap();
+        assert_eq!(btc_watch.state.last_processed_bitcoin_block, 12);
+        assert_eq!(
+            storage
+                .via_indexer_dal()
+                .get_l1_block_hash(BtcWatch::module_name(), 8)
+                .await
+                .unwrap(),
+            Some(reorged_chain.hash(8).to_byte_array().to_vec())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rewind_refused_when_orphaned_deposits_were_executed() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        setup_storage(&mut storage).await;
+
+        let chain = TestChain::new(11);
+        let mut btc_watch = create_btc_watch(&pool, chain.reorg(7, 5));
+        index_chain(&mut storage, &mut btc_watch, &chain, 10).await;
+
+        let executed_deposit = priority_tx(0);
+        let txid = insert_deposit(&mut storage, &executed_deposit, 9).await;
+        execute_deposit(&mut storage, executed_deposit).await;
+
+        let err = btc_watch.loop_iteration(&mut storage).await.unwrap_err();
+        assert!(matches!(err, MessageProcessorError::Internal(_)));
+
+        // Nothing was rewound.
+        assert_eq!(btc_watch.state.last_processed_bitcoin_block, 10);
+        assert_eq!(
+            storage
+                .via_indexer_dal()
+                .get_last_processed_l1_block(BtcWatch::module_name())
+                .await
+                .unwrap(),
+            10
+        );
+        assert!(storage
+            .via_indexer_dal()
+            .get_l1_block_hash(BtcWatch::module_name(), 10)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(storage
+            .via_transactions_dal()
+            .transaction_exists_with_txid(&txid)
+            .await
+            .unwrap());
+    }
+}
@@ -216,7 +216,8 @@ impl ConsistencyChecker {
         let mut parser = MessageParser::new(self.btc_client.config.network());
         let inscriptions = parser.parse_system_transaction(&tx, 0, None);
 
-        for inscription in inscriptions {
+        // The commit transaction can pack other messages along the L1 batch reference.
+        for inscription in &inscriptions {
             if let FullInscriptionMessage::L1BatchDAReference(msg) = inscription {
                 if msg.common.p2wpkh_address != Some(self.sequencer_address.clone()) {
                     let err = anyhow::anyhow!(
@@ -241,7 +242,9 @@ impl ConsistencyChecker {
 
                 return Ok(());
             }
+        }
 
+        if !inscriptions.is_empty() {
             let err = anyhow::anyhow!(
                 "Commit transaction {:?} does is not valid, data not found",
                 commit_tx_hash
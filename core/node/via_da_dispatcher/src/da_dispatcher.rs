@@ -4,6 +4,10 @@ use anyhow::Context;
 use chrono::Utc;
 use rand::Rng;
 use tokio::sync::watch::Receiver;
+use via_da_client::{
+    envelope::{encode_blob, BlobCodec},
+    pubdata::PubdataBlob,
+};
 use zksync_config::DADispatcherConfig;
 use zksync_da_client::{
     types::{DAError, InclusionData},
@@ -13,7 +17,11 @@ use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_l1_contract_interface::i_executor::methods::ProveBatches;
 use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_prover_interface::outputs::L1BatchProofForL1;
-use zksync_types::{protocol_version::ProtocolSemanticVersion, L1BatchNumber};
+use zksync_types::{
+    commitment::L1BatchWithMetadata, protocol_version::ProtocolSemanticVersion,
+    writes::StateDiffRecord, L1BatchNumber, StorageKey, U256,
+};
+use zksync_utils::h256_to_u256;
 
 use crate::metrics::METRICS;
 
@@ -24,6 +32,8 @@ pub struct ViaDataAvailabilityDispatcher {
     config: DADispatcherConfig,
     blob_store: Arc<dyn ObjectStore>,
     dispatch_real_proof: bool,
+    /// Maximum number of consecutive L1 batches proven by a single proof blob.
+    max_batches_per_proof: usize,
 }
 
 impl ViaDataAvailabilityDispatcher {
@@ -33,6 +43,7 @@ impl ViaDataAvailabilityDispatcher {
         client: Box<dyn DataAvailabilityClient>,
         blob_store: Arc<dyn ObjectStore>,
         dispatch_real_proof: bool,
+        max_batches_per_proof: usize,
     ) -> Self {
         Self {
             pool,
@@ -40,6 +51,17 @@ impl ViaDataAvailabilityDispatcher {
             client,
             blob_store,
             dispatch_real_proof,
+            max_batches_per_proof: max_batches_per_proof.max(1),
+        }
+    }
+
+    /// Compresses `data` into the blob envelope decoded by the verifiers. Without compression, the
+    /// blob is dispatched as is, so the verifiers unaware of the envelope keep decoding it.
+    fn encode_blob(&self, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+        if self.config.compress_blobs() {
+            encode_blob(data, BlobCodec::Zstd)
+        } else {
+            Ok(data.to_vec())
         }
     }
 
@@ -103,9 +125,14 @@ impl ViaDataAvailabilityDispatcher {
         for batch in batches {
             let dispatch_latency = METRICS.blob_dispatch_latency.start();
 
+            let mut conn = self.pool.connection_tagged("da_dispatcher").await?;
+            let state_diffs = load_state_diffs(&mut conn, batch.l1_batch_number).await?;
+            drop(conn);
+
+            let blob = self.encode_blob(&PubdataBlob::encode(&batch.pubdata, &state_diffs))?;
             let dispatch_response = retry(self.config.max_retries(), batch.l1_batch_number, || {
                 self.client
-                    .dispatch_blob(batch.l1_batch_number.0, batch.pubdata.clone())
+                    .dispatch_blob(batch.l1_batch_number.0, blob.clone())
             })
             .await
             .with_context(|| {
@@ -132,11 +159,12 @@ impl ViaDataAvailabilityDispatcher {
             METRICS
                 .last_dispatched_l1_batch
                 .set(batch.l1_batch_number.0 as usize);
-            METRICS.blob_size.observe(batch.pubdata.len());
+            METRICS.blob_size.observe(blob.len());
             tracing::info!(
-                "Dispatched a DA for batch_number: {}, pubdata_size: {}, dispatch_latency: {dispatch_latency_duration:?}",
+                "Dispatched a DA for batch_number: {}, pubdata_size: {}, blob_size: {}, dispatch_latency: {dispatch_latency_duration:?}",
                 batch.l1_batch_number,
                 batch.pubdata.len(),
+                blob.len(),
             );
         }
 
@@ -166,38 +194,37 @@ impl ViaDataAvailabilityDispatcher {
 
         drop(conn);
 
-        for batch in batches {
+        for range in split_into_ranges(&batches, self.max_batches_per_proof) {
+            let (first, last) = (range[0], range[range.len() - 1]);
             let dispatch_latency = METRICS.blob_dispatch_latency.start();
 
             let dummy_proof = self
-                .prepare_dummy_proof_operation(batch)
+                .prepare_dummy_proof_operation(range)
                 .await
                 .with_context(|| {
                     format!(
-                        "failed to prepare a dummy proof for batch_number: {}",
-                        batch
+                        "failed to prepare a dummy proof for batches: {}-{}",
+                        first, last
                     )
                 })?;
 
-            let dispatch_response = retry(self.config.max_retries(), batch, || {
-                self.client.dispatch_blob(batch.0, dummy_proof.clone())
+            let dummy_proof = self.encode_blob(&dummy_proof)?;
+            let dispatch_response = retry(self.config.max_retries(), first, || {
+                self.client.dispatch_blob(first.0, dummy_proof.clone())
             })
             .await?;
 
             let dispatch_latency_duration = dispatch_latency.observe();
 
-            let sent_at = Utc::now().naive_utc();
-
-            let mut conn = self.pool.connection_tagged("da_dispatcher").await?;
-            conn.via_data_availability_dal()
-                .insert_proof_da(batch, dispatch_response.blob_id.as_str(), sent_at)
+            self.insert_proof_da(range, dispatch_response.blob_id.as_str())
                 .await?;
 
-            METRICS.last_dispatched_proof_batch.set(batch.0 as usize);
+            METRICS.last_dispatched_proof_batch.set(last.0 as usize);
             METRICS.blob_size.observe(dummy_proof.len());
             tracing::info!(
-                "Dispatched a dummy proof for batch_number: {}, proof_size: {}, dispatch_latency: {dispatch_latency_duration:?}",
-                batch,
+                "Dispatched a dummy proof for batches: {}-{}, proof_size: {}, dispatch_latency: {dispatch_latency_duration:?}",
+                first,
+                last,
                 dummy_proof.len(),
             );
         }
@@ -219,63 +246,97 @@ impl ViaDataAvailabilityDispatcher {
 
         drop(conn);
 
-        for proof in proofs {
-            // fetch the proof from object store
-            let final_proof = match self.load_real_proof_operation(proof.l1_batch_number).await {
+        let batches: Vec<_> = proofs.iter().map(|proof| proof.l1_batch_number).collect();
+        for range in split_into_ranges(&batches, self.max_batches_per_proof) {
+            let (first, last) = (range[0], range[range.len() - 1]);
+
+            // fetch the proofs from object store
+            let final_proof = match self.load_real_proof_operation(range).await {
                 Some(proof) => proof,
                 None => {
-                    tracing::error!("Failed to load proof for batch {}", proof.l1_batch_number.0);
+                    tracing::error!("Failed to load proof for batches {}-{}", first.0, last.0);
                     continue;
                 }
             };
 
             let dispatch_latency = METRICS.proof_dispatch_latency.start();
 
-            let dispatch_response = retry(self.config.max_retries(), proof.l1_batch_number, || {
-                self.client
-                    .dispatch_blob(proof.l1_batch_number.0, final_proof.clone())
+            let final_proof = self.encode_blob(&final_proof)?;
+            let dispatch_response = retry(self.config.max_retries(), first, || {
+                self.client.dispatch_blob(first.0, final_proof.clone())
             })
             .await
             .with_context(|| {
                 format!(
-                    "failed to dispatch a proof with batch_number: {}, proof_len: {}",
-                    proof.l1_batch_number,
+                    "failed to dispatch a proof with batches: {}-{}, proof_len: {}",
+                    first,
+                    last,
                     final_proof.len()
                 )
             })?;
 
             let dispatch_latency_duration = dispatch_latency.observe();
 
-            let sent_at = Utc::now().naive_utc();
-
-            let mut conn = self.pool.connection_tagged("da_dispatcher").await?;
-            conn.via_data_availability_dal()
-                .insert_proof_da(
-                    proof.l1_batch_number,
-                    dispatch_response.blob_id.as_str(),
-                    sent_at,
-                )
+            self.insert_proof_da(range, dispatch_response.blob_id.as_str())
                 .await?;
-            drop(conn);
 
-            METRICS
-                .last_dispatched_proof_batch
-                .set(proof.l1_batch_number.0 as usize);
+            METRICS.last_dispatched_proof_batch.set(last.0 as usize);
             METRICS.blob_size.observe(final_proof.len());
             tracing::info!(
-                "Dispatched a proof for batch_number: {}, proof_size: {}, dispatch_latency: {dispatch_latency_duration:?}",
-                proof.l1_batch_number,
+                "Dispatched a proof for batches: {}-{}, proof_size: {}, dispatch_latency: {dispatch_latency_duration:?}",
+                first,
+                last,
                 final_proof.len(),
             );
         }
         Ok(())
     }
 
-    /// Loads a real proof operation for a given L1 batch number.
-    async fn load_real_proof_operation(&self, batch_to_prove: L1BatchNumber) -> Option<Vec<u8>> {
+    /// Stores the proof blob of every L1 batch of the range at once, so the BTC sender never
+    /// sees a partial range.
+    async fn insert_proof_da(&self, range: &[L1BatchNumber], blob_id: &str) -> anyhow::Result<()> {
+        let sent_at = Utc::now().naive_utc();
+
+        let mut conn = self.pool.connection_tagged("da_dispatcher").await?;
+        let mut transaction = conn.start_transaction().await?;
+        for batch in range {
+            transaction
+                .via_data_availability_dal()
+                .insert_proof_da(*batch, blob_id, sent_at)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Loads a real proof operation for the given range of consecutive L1 batches.
+    async fn load_real_proof_operation(&self, batches: &[L1BatchNumber]) -> Option<Vec<u8>> {
+        let mut proofs = Vec::with_capacity(batches.len());
+        for batch_to_prove in batches {
+            proofs.push(self.load_real_proof(*batch_to_prove).await?);
+        }
+
         let mut storage = self.pool.connection_tagged("da_dispatcher").await.ok()?;
+        let previous_batch_number = batches[0] - 1;
+        let prev_l1_batch = load_l1_batch_metadata(&mut storage, previous_batch_number).await?;
+        let mut l1_batches = Vec::with_capacity(batches.len());
+        for batch_to_prove in batches {
+            l1_batches.push(load_l1_batch_metadata(&mut storage, *batch_to_prove).await?);
+        }
+
+        let res = ProveBatches {
+            prev_l1_batch,
+            l1_batches,
+            proofs,
+            should_verify: true,
+        };
+
+        serialize_prove_batches(&res)
+    }
 
-        let previous_batch_number = batch_to_prove - 1;
+    /// Loads the real proof of a given L1 batch number.
+    async fn load_real_proof(&self, batch_to_prove: L1BatchNumber) -> Option<L1BatchProofForL1> {
+        let mut storage = self.pool.connection_tagged("da_dispatcher").await.ok()?;
 
         let minor_version = match storage
             .blocks_dal()
@@ -335,77 +396,16 @@ impl ViaDataAvailabilityDispatcher {
             })
             .collect();
 
-        let proof = match self
+        let proof = self
             .load_wrapped_fri_proofs_for_range(batch_to_prove, &allowed_versions)
-            .await
-        {
-            Some(proof) => proof,
-            None => {
-                tracing::error!("Failed to load proof for batch {}", batch_to_prove);
-                return None;
-            }
-        };
-
-        let previous_proven_batch_metadata = match storage
-            .blocks_dal()
-            .get_l1_batch_metadata(previous_batch_number)
-            .await
-        {
-            Ok(Some(metadata)) => metadata,
-            Ok(None) => {
-                tracing::error!(
-                    "L1 batch #{} with submitted proof is not complete in the DB",
-                    previous_batch_number
-                );
-                return None;
-            }
-            Err(e) => {
-                tracing::error!(
-                    "Failed to retrieve L1 batch #{} metadata: {}",
-                    previous_batch_number,
-                    e
-                );
-                return None;
-            }
-        };
-
-        let metadata_for_batch_being_proved = match storage
-            .blocks_dal()
-            .get_l1_batch_metadata(batch_to_prove)
-            .await
-        {
-            Ok(Some(metadata)) => metadata,
-            Ok(None) => {
-                tracing::error!(
-                    "L1 batch #{} with generated proof is not complete in the DB",
-                    batch_to_prove
-                );
-                return None;
-            }
-            Err(e) => {
-                tracing::error!(
-                    "Failed to retrieve L1 batch #{} metadata: {}",
-                    batch_to_prove,
-                    e
-                );
-                return None;
-            }
-        };
-
-        let res = ProveBatches {
-            prev_l1_batch: previous_proven_batch_metadata,
-            l1_batches: vec![metadata_for_batch_being_proved],
-            proofs: vec![proof],
-            should_verify: true,
-        };
-
-        serialize_prove_batches(&res)
+            .await;
+        if proof.is_none() {
+            tracing::error!("Failed to load proof for batch {}", batch_to_prove);
+        }
+        proof
     }
 
-    async fn prepare_dummy_proof_operation(
-        &self,
-        batch_to_prove: L1BatchNumber,
-    ) -> Option<Vec<u8>> {
+    async fn prepare_dummy_proof_operation(&self, batches: &[L1BatchNumber]) -> Option<Vec<u8>> {
         let mut storage = self.pool.connection_tagged("da_dispatcher").await.ok()?;
 
         let previous_proven_batch_number =
@@ -417,55 +417,16 @@ impl ViaDataAvailabilityDispatcher {
                 }
             };
 
-        let previous_proven_batch_metadata = match storage
-            .blocks_dal()
-            .get_l1_batch_metadata(previous_proven_batch_number)
-            .await
-        {
-            Ok(Some(metadata)) => metadata,
-            Ok(None) => {
-                tracing::error!(
-                    "L1 batch #{} with submitted proof is not complete in the DB",
-                    previous_proven_batch_number
-                );
-                return None;
-            }
-            Err(e) => {
-                tracing::error!(
-                    "Failed to retrieve L1 batch #{} metadata: {}",
-                    previous_proven_batch_number,
-                    e
-                );
-                return None;
-            }
-        };
-
-        let metadata_for_batch_being_proved = match storage
-            .blocks_dal()
-            .get_l1_batch_metadata(batch_to_prove)
-            .await
-        {
-            Ok(Some(metadata)) => metadata,
-            Ok(None) => {
-                tracing::error!(
-                    "L1 batch #{} with generated proof is not complete in the DB",
-                    batch_to_prove
-                );
-                return None;
-            }
-            Err(e) => {
-                tracing::error!(
-                    "Failed to retrieve L1 batch #{} metadata: {}",
-                    batch_to_prove,
-                    e
-                );
-                return None;
-            }
-        };
+        let prev_l1_batch =
+            load_l1_batch_metadata(&mut storage, previous_proven_batch_number).await?;
+        let mut l1_batches = Vec::with_capacity(batches.len());
+        for batch_to_prove in batches {
+            l1_batches.push(load_l1_batch_metadata(&mut storage, *batch_to_prove).await?);
+        }
 
         let res = ProveBatches {
-            prev_l1_batch: previous_proven_batch_metadata,
-            l1_batches: vec![metadata_for_batch_being_proved],
+            prev_l1_batch,
+            l1_batches,
             proofs: vec![],
             should_verify: false,
         };
@@ -669,6 +630,85 @@ impl ViaDataAvailabilityDispatcher {
     }
 }
 
+/// Loads the state diffs of the L1 batch the same way the commitment generator does, so the
+/// verifiers can recompute the state diff hash of the batch from its pubdata blob.
+async fn load_state_diffs(
+    conn: &mut Connection<'_, Core>,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<Vec<StateDiffRecord>> {
+    let touched_slots = conn
+        .storage_logs_dal()
+        .get_touched_slots_for_executed_l1_batch(l1_batch_number)
+        .await?;
+    let touched_hashed_keys: Vec<_> = touched_slots.keys().map(|key| key.hashed_key()).collect();
+    let previous_values = conn
+        .storage_logs_dal()
+        .get_previous_storage_values(&touched_hashed_keys, l1_batch_number)
+        .await?;
+    let l1_batches_for_initial_writes = conn
+        .storage_logs_dal()
+        .get_l1_batches_and_indices_for_initial_writes(&touched_hashed_keys)
+        .await?;
+
+    let mut state_diffs = Vec::new();
+    for (key, value) in touched_slots {
+        let hashed_key = key.hashed_key();
+        let prev_value = previous_values[&hashed_key].unwrap_or_default();
+        if prev_value == value {
+            continue;
+        }
+        let (initial_write_l1_batch_number, index) = *l1_batches_for_initial_writes
+            .get(&hashed_key)
+            .with_context(|| format!("Initial write of slot {hashed_key:?} is missing"))?;
+        let (enumeration_index, initial_value) = if initial_write_l1_batch_number == l1_batch_number
+        {
+            (0, U256::zero())
+        } else {
+            (index, h256_to_u256(prev_value))
+        };
+        state_diffs.push(StateDiffRecord {
+            address: *key.address(),
+            key: h256_to_u256(*key.key()),
+            derived_key: StorageKey::raw_hashed_key(key.address(), key.key()),
+            enumeration_index,
+            initial_value,
+            final_value: h256_to_u256(value),
+        });
+    }
+    state_diffs.sort_unstable_by_key(|rec| (rec.address, rec.key));
+    Ok(state_diffs)
+}
+
+/// Splits the ordered L1 batches into ranges of at most `max_len` consecutive batches.
+fn split_into_ranges(batches: &[L1BatchNumber], max_len: usize) -> Vec<&[L1BatchNumber]> {
+    let mut ranges = vec![];
+    let mut start = 0;
+    for end in 1..=batches.len() {
+        if end == batches.len() || end - start == max_len || batches[end - 1] + 1 != batches[end] {
+            ranges.push(&batches[start..end]);
+            start = end;
+        }
+    }
+    ranges
+}
+
+async fn load_l1_batch_metadata(
+    storage: &mut Connection<'_, Core>,
+    number: L1BatchNumber,
+) -> Option<L1BatchWithMetadata> {
+    match storage.blocks_dal().get_l1_batch_metadata(number).await {
+        Ok(Some(metadata)) => Some(metadata),
+        Ok(None) => {
+            tracing::error!("L1 batch #{} is not complete in the DB", number);
+            None
+        }
+        Err(e) => {
+            tracing::error!("Failed to retrieve L1 batch #{} metadata: {}", number, e);
+            None
+        }
+    }
+}
+
 fn serialize_prove_batches(prove_batches: &ProveBatches) -> Option<Vec<u8>> {
     let prev_l1_batch_bytes = bincode::serialize(&prove_batches.prev_l1_batch)
         .map_err(|e| {
@@ -38,7 +38,7 @@ impl ViaMainNodeStorageInitializer {
             .connection()
             .await?
             .via_wallet_dal()
-            .insert_wallets(&indexer_wallets_details)
+            .insert_wallets(&indexer_wallets_details, None)
             .await?;
 
         let wallets = state
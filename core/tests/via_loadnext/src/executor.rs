@@ -94,6 +94,10 @@ impl Executor {
             external_apis: vec![],
             fee_strategies: vec![],
             use_rpc_for_fee_rate: None,
+            esplora_url: None,
+            fee_rate_providers: None,
+            fee_rate_aggregation: None,
+            fee_rate_quorum: None,
         };
 
         let btc_client = BitcoinClient::new(
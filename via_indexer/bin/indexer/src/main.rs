@@ -4,7 +4,7 @@ use zksync_config::{
         via_bridge::ViaBridgeConfig,
         via_btc_client::ViaBtcClientConfig,
         via_consensus::ViaGenesisConfig,
-        via_l1_indexer::ViaIndexerConfig,
+        via_l1_indexer::{ViaIndexerApiConfig, ViaIndexerConfig},
         via_secrets::{ViaL1Secrets, ViaSecrets},
         DatabaseSecrets, ObservabilityConfig, PrometheusConfig, Secrets,
     },
@@ -35,8 +35,10 @@ fn main() -> anyhow::Result<()> {
             },
             via_l1: ViaL1Secrets::from_env().ok(),
             via_da: None,
+            via_keystore: None,
         },
         via_bridge_config: ViaBridgeConfig::from_env()?,
+        api_config: ViaIndexerApiConfig::from_env()?,
     };
 
     let node_builder = node_builder::ViaNodeBuilder::new(via_indexer_config.clone())?;
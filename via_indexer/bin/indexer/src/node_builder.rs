@@ -4,7 +4,8 @@ use zksync_node_framework::{
     implementations::layers::{
         healtcheck_server::HealthCheckLayer, pools_layer::PoolsLayerBuilder,
         prometheus_exporter::PrometheusExporterLayer, sigint::SigintHandlerLayer,
-        via_btc_client::BtcClientLayer, via_l1_indexer::L1IndexerLayer,
+        via_btc_client::BtcClientLayer, via_indexer_api::ViaIndexerApiLayer,
+        via_l1_indexer::L1IndexerLayer,
     },
     service::{ZkStackService, ZkStackServiceBuilder},
 };
@@ -82,6 +83,22 @@ impl ViaNodeBuilder {
         self.node.add_layer(indexer_layer);
         Ok(self)
     }
+
+    fn add_indexer_api_layer(mut self) -> anyhow::Result<Self> {
+        let api_config = self.via_indexer_config.api_config.clone();
+        let block_confirmations = self
+            .via_indexer_config
+            .via_btc_watch_config
+            .block_confirmations;
+        let network = self.via_indexer_config.via_btc_client_config.network();
+
+        self.node.add_layer(ViaIndexerApiLayer::new(
+            api_config,
+            block_confirmations,
+            network,
+        ));
+        Ok(self)
+    }
     pub fn build(mut self) -> anyhow::Result<ZkStackService> {
         self = self
             .add_sigint_handler_layer()?
@@ -89,7 +106,8 @@ impl ViaNodeBuilder {
             .add_prometheus_exporter_layer()?
             .add_pools_layer()?
             .add_btc_client_layer()?
-            .add_l1_indexer_layer()?;
+            .add_l1_indexer_layer()?
+            .add_indexer_api_layer()?;
 
         Ok(self.node.build())
     }
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_withdrawals_bridge_withdrawal_id;
+DROP INDEX IF EXISTS idx_withdrawals_receiver;
+DROP INDEX IF EXISTS idx_deposits_sender;
+DROP INDEX IF EXISTS idx_deposits_receiver;
@@ -0,0 +1,5 @@
+-- Indexes of the lookups served by the indexer API.
+CREATE INDEX IF NOT EXISTS idx_deposits_receiver ON deposits (receiver);
+CREATE INDEX IF NOT EXISTS idx_deposits_sender ON deposits (sender);
+CREATE INDEX IF NOT EXISTS idx_withdrawals_receiver ON withdrawals (receiver);
+CREATE INDEX IF NOT EXISTS idx_withdrawals_bridge_withdrawal_id ON withdrawals (bridge_withdrawal_id);
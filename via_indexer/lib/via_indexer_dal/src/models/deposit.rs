@@ -10,3 +10,32 @@ pub struct Deposit {
     pub canonical_tx_hash: Vec<u8>,
     pub block_timestamp: u64,
 }
+
+#[derive(Debug, Clone)]
+pub struct StorageDeposit {
+    pub priority_id: i64,
+    pub tx_id: Vec<u8>,
+    pub block_number: i64,
+    pub sender: String,
+    pub receiver: String,
+    pub value: i64,
+    pub calldata: Option<Vec<u8>>,
+    pub canonical_tx_hash: Vec<u8>,
+    pub created_at: i64,
+}
+
+impl From<StorageDeposit> for Deposit {
+    fn from(deposit: StorageDeposit) -> Self {
+        Self {
+            priority_id: deposit.priority_id,
+            tx_id: deposit.tx_id,
+            block_number: deposit.block_number as u32,
+            sender: deposit.sender,
+            receiver: deposit.receiver,
+            value: deposit.value,
+            calldata: deposit.calldata.unwrap_or_default(),
+            canonical_tx_hash: deposit.canonical_tx_hash,
+            block_timestamp: deposit.created_at as u64,
+        }
+    }
+}
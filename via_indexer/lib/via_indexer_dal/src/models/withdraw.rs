@@ -15,3 +15,14 @@ pub struct WithdrawalParam {
     pub receiver: String,
     pub value: i64,
 }
+
+/// A withdrawal paid by an indexed bridge transaction.
+#[derive(Debug, Clone)]
+pub struct Withdrawal {
+    pub bridge_tx_id: Vec<u8>,
+    pub l1_batch_reveal_tx_id: Vec<u8>,
+    pub block_number: i64,
+    pub tx_index: i64,
+    pub receiver: String,
+    pub value: i64,
+}
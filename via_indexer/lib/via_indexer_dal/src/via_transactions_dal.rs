@@ -2,8 +2,8 @@ use zksync_db_connection::{connection::Connection, error::DalResult, instrument:
 
 use crate::{
     models::{
-        deposit::Deposit,
-        withdraw::{BridgeWithdrawalParam, WithdrawalParam},
+        deposit::{Deposit, StorageDeposit},
+        withdraw::{BridgeWithdrawalParam, Withdrawal, WithdrawalParam},
     },
     Indexer,
 };
@@ -131,9 +131,9 @@ impl ViaTransactionsDal<'_, '_> {
             bridget_withdrawal_param.tx_id,
             bridget_withdrawal_param.l1_batch_reveal_tx_id,
             bridget_withdrawal_param.block_number,
+            bridget_withdrawal_param.fee,
             bridget_withdrawal_param.vsize,
             bridget_withdrawal_param.total_size,
-            bridget_withdrawal_param.fee,
             withdrawals.len() as i64
         )
         .instrument("insert_bridge_withdrawal")
@@ -183,4 +183,254 @@ impl ViaTransactionsDal<'_, '_> {
         self.delete_all_withdrawals(block_number).await?;
         Ok(())
     }
+
+    pub async fn get_deposit(&mut self, tx_id: &[u8]) -> DalResult<Option<Deposit>> {
+        let deposit = sqlx::query_as!(
+            StorageDeposit,
+            r#"
+            SELECT
+                *
+            FROM
+                deposits
+            WHERE
+                tx_id = $1
+            "#,
+            tx_id
+        )
+        .instrument("get_deposit")
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(deposit.map(Deposit::from))
+    }
+
+    pub async fn get_deposit_by_canonical_tx_hash(
+        &mut self,
+        canonical_tx_hash: &[u8],
+    ) -> DalResult<Option<Deposit>> {
+        let deposit = sqlx::query_as!(
+            StorageDeposit,
+            r#"
+            SELECT
+                *
+            FROM
+                deposits
+            WHERE
+                canonical_tx_hash = $1
+            "#,
+            canonical_tx_hash
+        )
+        .instrument("get_deposit_by_canonical_tx_hash")
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(deposit.map(Deposit::from))
+    }
+
+    /// Returns the deposits to an L2 address, most recent first.
+    pub async fn list_deposits_by_receiver(
+        &mut self,
+        receiver: &str,
+        limit: i64,
+        offset: i64,
+    ) -> DalResult<Vec<Deposit>> {
+        let deposits = sqlx::query_as!(
+            StorageDeposit,
+            r#"
+            SELECT
+                *
+            FROM
+                deposits
+            WHERE
+                receiver = $1
+            ORDER BY
+                priority_id DESC
+            LIMIT
+                $2
+            OFFSET
+                $3
+            "#,
+            receiver,
+            limit,
+            offset
+        )
+        .instrument("list_deposits_by_receiver")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(deposits.into_iter().map(Deposit::from).collect())
+    }
+
+    /// Returns the deposits from a Bitcoin address, most recent first.
+    pub async fn list_deposits_by_sender(
+        &mut self,
+        sender: &str,
+        limit: i64,
+        offset: i64,
+    ) -> DalResult<Vec<Deposit>> {
+        let deposits = sqlx::query_as!(
+            StorageDeposit,
+            r#"
+            SELECT
+                *
+            FROM
+                deposits
+            WHERE
+                sender = $1
+            ORDER BY
+                priority_id DESC
+            LIMIT
+                $2
+            OFFSET
+                $3
+            "#,
+            sender,
+            limit,
+            offset
+        )
+        .instrument("list_deposits_by_sender")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(deposits.into_iter().map(Deposit::from).collect())
+    }
+
+    pub async fn get_bridge_withdrawal(
+        &mut self,
+        tx_id: &[u8],
+    ) -> DalResult<Option<BridgeWithdrawalParam>> {
+        let bridge_withdrawal = sqlx::query_as!(
+            BridgeWithdrawalParam,
+            r#"
+            SELECT
+                tx_id,
+                l1_batch_reveal_tx_id,
+                block_number,
+                fee,
+                vsize,
+                total_size,
+                withdrawals_count
+            FROM
+                bridge_withdrawals
+            WHERE
+                tx_id = $1
+            "#,
+            tx_id
+        )
+        .instrument("get_bridge_withdrawal")
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(bridge_withdrawal)
+    }
+
+    /// Returns the withdrawals paid by the bridge transaction `tx_id`.
+    pub async fn list_withdrawals_by_bridge_tx_id(
+        &mut self,
+        tx_id: &[u8],
+    ) -> DalResult<Vec<Withdrawal>> {
+        let withdrawals = sqlx::query_as!(
+            Withdrawal,
+            r#"
+            SELECT
+                bridge_withdrawals.tx_id AS bridge_tx_id,
+                bridge_withdrawals.l1_batch_reveal_tx_id,
+                bridge_withdrawals.block_number,
+                withdrawals.tx_index,
+                withdrawals.receiver,
+                withdrawals.value
+            FROM
+                withdrawals
+                JOIN bridge_withdrawals ON bridge_withdrawals.id = withdrawals.bridge_withdrawal_id
+            WHERE
+                bridge_withdrawals.tx_id = $1
+            ORDER BY
+                withdrawals.tx_index ASC
+            "#,
+            tx_id
+        )
+        .instrument("list_withdrawals_by_bridge_tx_id")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(withdrawals)
+    }
+
+    /// Returns the withdrawals to `receivers` paid by the bridge transactions of the L1 batch proof
+    /// revealed in `l1_batch_reveal_tx_id`.
+    pub async fn list_withdrawals_by_l1_batch_reveal_tx_id(
+        &mut self,
+        l1_batch_reveal_tx_id: &[u8],
+        receivers: &[String],
+    ) -> DalResult<Vec<Withdrawal>> {
+        let withdrawals = sqlx::query_as!(
+            Withdrawal,
+            r#"
+            SELECT
+                bridge_withdrawals.tx_id AS bridge_tx_id,
+                bridge_withdrawals.l1_batch_reveal_tx_id,
+                bridge_withdrawals.block_number,
+                withdrawals.tx_index,
+                withdrawals.receiver,
+                withdrawals.value
+            FROM
+                withdrawals
+                JOIN bridge_withdrawals ON bridge_withdrawals.id = withdrawals.bridge_withdrawal_id
+            WHERE
+                bridge_withdrawals.l1_batch_reveal_tx_id = $1
+                AND withdrawals.receiver = ANY ($2)
+            ORDER BY
+                bridge_withdrawals.block_number ASC,
+                withdrawals.tx_index ASC
+            "#,
+            l1_batch_reveal_tx_id,
+            receivers
+        )
+        .instrument("list_withdrawals_by_l1_batch_reveal_tx_id")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(withdrawals)
+    }
+
+    /// Returns the withdrawals to a Bitcoin address, most recent first.
+    pub async fn list_withdrawals_by_receiver(
+        &mut self,
+        receiver: &str,
+        limit: i64,
+        offset: i64,
+    ) -> DalResult<Vec<Withdrawal>> {
+        let withdrawals = sqlx::query_as!(
+            Withdrawal,
+            r#"
+            SELECT
+                bridge_withdrawals.tx_id AS bridge_tx_id,
+                bridge_withdrawals.l1_batch_reveal_tx_id,
+                bridge_withdrawals.block_number,
+                withdrawals.tx_index,
+                withdrawals.receiver,
+                withdrawals.value
+            FROM
+                withdrawals
+                JOIN bridge_withdrawals ON bridge_withdrawals.id = withdrawals.bridge_withdrawal_id
+            WHERE
+                withdrawals.receiver = $1
+            ORDER BY
+                bridge_withdrawals.block_number DESC,
+                withdrawals.id DESC
+            LIMIT
+                $2
+            OFFSET
+                $3
+            "#,
+            receiver,
+            limit,
+            offset
+        )
+        .instrument("list_withdrawals_by_receiver")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(withdrawals)
+    }
 }
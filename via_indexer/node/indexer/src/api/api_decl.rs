@@ -0,0 +1,63 @@
+use std::{sync::Arc, time::Duration};
+
+use tower::ServiceBuilder;
+use tower_http::{cors::CorsLayer, timeout::TimeoutLayer};
+use via_indexer_dal::{ConnectionPool, Indexer};
+use zksync_config::configs::via_l1_indexer::ViaIndexerApiConfig;
+
+use crate::api::l2_client::L2WithdrawalsClient;
+
+pub struct RestApi {
+    pub config: ViaIndexerApiConfig,
+    pub pool: ConnectionPool<Indexer>,
+    /// Number of blocks on top of a transaction for it to be considered final.
+    pub block_confirmations: u64,
+    /// Client of the L2 node, `None` when the lookup by L2 tx hash is disabled.
+    pub l2_client: Option<Box<dyn L2WithdrawalsClient>>,
+}
+
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl RestApi {
+    pub fn new(
+        config: ViaIndexerApiConfig,
+        pool: ConnectionPool<Indexer>,
+        block_confirmations: u64,
+        l2_client: Option<Box<dyn L2WithdrawalsClient>>,
+    ) -> Self {
+        Self {
+            config,
+            pool,
+            block_confirmations,
+            l2_client,
+        }
+    }
+
+    pub fn into_router(self) -> axum::Router<()> {
+        let shared_state = Arc::new(self);
+
+        axum::Router::new()
+            .route("/deposits", axum::routing::get(Self::list_deposits))
+            .route("/deposits/:tx_id", axum::routing::get(Self::get_deposit))
+            .route(
+                "/deposits/l2/:canonical_tx_hash",
+                axum::routing::get(Self::get_deposit_by_canonical_tx_hash),
+            )
+            .route("/withdrawals", axum::routing::get(Self::list_withdrawals))
+            .route(
+                "/withdrawals/:tx_id",
+                axum::routing::get(Self::get_bridge_withdrawal),
+            )
+            .route(
+                "/withdrawals/l2/:l2_tx_hash",
+                axum::routing::get(Self::get_withdrawal_by_l2_tx_hash),
+            )
+            .with_state(shared_state)
+            .layer(
+                ServiceBuilder::new()
+                    .layer(TimeoutLayer::new(API_TIMEOUT))
+                    .layer(CorsLayer::permissive())
+                    .into_inner(),
+            )
+    }
+}
@@ -0,0 +1,259 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::Response,
+};
+use serde::Serialize;
+use tracing::instrument;
+use via_btc_client::types::{BitcoinSecp256k1::hashes::Hash, BitcoinTxid};
+use via_indexer_dal::{Connection, Indexer, IndexerDal};
+use zksync_types::{Address, H256};
+
+use crate::{
+    api::{
+        api_decl::RestApi,
+        error::ApiError,
+        types::{
+            BridgeWithdrawalResponse, DepositResponse, DepositsQuery, L2WithdrawalResponse, Page,
+            WithdrawalResponse, WithdrawalsQuery,
+        },
+    },
+    L1Indexer,
+};
+
+fn ok_json<T: Serialize>(data: T) -> Response<String> {
+    Response::builder()
+        .status(axum::http::StatusCode::OK)
+        .header(axum::http::header::CONTENT_TYPE, "application/json")
+        .body(serde_json::to_string(&data).expect("Failed to serialize"))
+        .unwrap()
+}
+
+/// Parses a Bitcoin tx id into the display-order bytes it is stored with.
+fn parse_tx_id(tx_id: &str) -> Result<Vec<u8>, ApiError> {
+    let txid = BitcoinTxid::from_str(tx_id)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid tx id: {tx_id}")))?;
+    let mut tx_id_bytes = txid.to_byte_array();
+    tx_id_bytes.reverse();
+    Ok(tx_id_bytes.to_vec())
+}
+
+/// L2 receivers are stored as lowercase `0x`-prefixed hex strings.
+fn normalize_l2_address(address: &str) -> Result<String, ApiError> {
+    let address = Address::from_str(address)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid L2 address: {address}")))?;
+    Ok(format!("{address:#x}"))
+}
+
+impl RestApi {
+    async fn connection(&self) -> Result<Connection<'_, Indexer>, ApiError> {
+        Ok(self.pool.connection_tagged("indexer api").await?)
+    }
+
+    async fn last_processed_block(storage: &mut Connection<'_, Indexer>) -> Result<u64, ApiError> {
+        Ok(storage
+            .via_indexer_dal()
+            .get_last_processed_l1_block(L1Indexer::module_name())
+            .await?)
+    }
+
+    /// Returns the deposit made by the Bitcoin transaction `tx_id`.
+    #[instrument(skip(self_))]
+    pub async fn get_deposit(
+        State(self_): State<Arc<Self>>,
+        Path(tx_id): Path<String>,
+    ) -> anyhow::Result<Response<String>, ApiError> {
+        let tx_id_bytes = parse_tx_id(&tx_id)?;
+
+        let mut storage = self_.connection().await?;
+        let deposit = storage
+            .via_transactions_dal()
+            .get_deposit(&tx_id_bytes)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Deposit {tx_id}")))?;
+        let last_processed_block = Self::last_processed_block(&mut storage).await?;
+
+        Ok(ok_json(DepositResponse::new(
+            deposit,
+            last_processed_block,
+            self_.block_confirmations,
+        )))
+    }
+
+    /// Returns the deposit which was executed on L2 as the priority transaction `canonical_tx_hash`.
+    #[instrument(skip(self_))]
+    pub async fn get_deposit_by_canonical_tx_hash(
+        State(self_): State<Arc<Self>>,
+        Path(canonical_tx_hash): Path<String>,
+    ) -> anyhow::Result<Response<String>, ApiError> {
+        let hash = H256::from_str(&canonical_tx_hash).map_err(|_| {
+            ApiError::BadRequest(format!("Invalid L2 tx hash: {canonical_tx_hash}"))
+        })?;
+
+        let mut storage = self_.connection().await?;
+        let deposit = storage
+            .via_transactions_dal()
+            .get_deposit_by_canonical_tx_hash(hash.as_bytes())
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Deposit with L2 tx {canonical_tx_hash}")))?;
+        let last_processed_block = Self::last_processed_block(&mut storage).await?;
+
+        Ok(ok_json(DepositResponse::new(
+            deposit,
+            last_processed_block,
+            self_.block_confirmations,
+        )))
+    }
+
+    /// Lists the deposits of an L2 receiver or of a Bitcoin sender, newest first.
+    #[instrument(skip(self_))]
+    pub async fn list_deposits(
+        State(self_): State<Arc<Self>>,
+        Query(query): Query<DepositsQuery>,
+    ) -> anyhow::Result<Response<String>, ApiError> {
+        let (page, limit) = query.pagination().resolve(self_.config.max_page_size());
+        let offset = i64::from(page - 1) * i64::from(limit);
+
+        let mut storage = self_.connection().await?;
+        let deposits = match (&query.receiver, &query.sender) {
+            (Some(receiver), None) => {
+                let receiver = normalize_l2_address(receiver)?;
+                storage
+                    .via_transactions_dal()
+                    .list_deposits_by_receiver(&receiver, limit.into(), offset)
+                    .await?
+            }
+            (None, Some(sender)) => {
+                storage
+                    .via_transactions_dal()
+                    .list_deposits_by_sender(sender, limit.into(), offset)
+                    .await?
+            }
+            _ => {
+                return Err(ApiError::BadRequest(
+                    "Exactly one of `receiver` or `sender` must be provided".to_string(),
+                ))
+            }
+        };
+        let last_processed_block = Self::last_processed_block(&mut storage).await?;
+
+        let items = deposits
+            .into_iter()
+            .map(|deposit| {
+                DepositResponse::new(deposit, last_processed_block, self_.block_confirmations)
+            })
+            .collect();
+        Ok(ok_json(Page { page, limit, items }))
+    }
+
+    /// Returns the bridge transaction `tx_id` together with the withdrawals it paid.
+    #[instrument(skip(self_))]
+    pub async fn get_bridge_withdrawal(
+        State(self_): State<Arc<Self>>,
+        Path(tx_id): Path<String>,
+    ) -> anyhow::Result<Response<String>, ApiError> {
+        let tx_id_bytes = parse_tx_id(&tx_id)?;
+
+        let mut storage = self_.connection().await?;
+        let bridge_withdrawal = storage
+            .via_transactions_dal()
+            .get_bridge_withdrawal(&tx_id_bytes)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Bridge withdrawal {tx_id}")))?;
+        let withdrawals = storage
+            .via_transactions_dal()
+            .list_withdrawals_by_bridge_tx_id(&tx_id_bytes)
+            .await?;
+        let last_processed_block = Self::last_processed_block(&mut storage).await?;
+
+        let withdrawals = withdrawals
+            .into_iter()
+            .map(|withdrawal| {
+                WithdrawalResponse::new(withdrawal, last_processed_block, self_.block_confirmations)
+            })
+            .collect();
+        Ok(ok_json(BridgeWithdrawalResponse::new(
+            bridge_withdrawal,
+            withdrawals,
+            last_processed_block,
+            self_.block_confirmations,
+        )))
+    }
+
+    /// Returns the withdrawals requested by the L2 transaction `l2_tx_hash`, with the bridge
+    /// outputs that paid them once its L1 batch is proven.
+    #[instrument(skip(self_))]
+    pub async fn get_withdrawal_by_l2_tx_hash(
+        State(self_): State<Arc<Self>>,
+        Path(l2_tx_hash): Path<String>,
+    ) -> anyhow::Result<Response<String>, ApiError> {
+        let hash = H256::from_str(&l2_tx_hash)
+            .map_err(|_| ApiError::BadRequest(format!("Invalid L2 tx hash: {l2_tx_hash}")))?;
+        let l2_client = self_.l2_client.as_ref().ok_or_else(|| {
+            ApiError::BadRequest("The lookup by L2 tx hash is not enabled".to_string())
+        })?;
+
+        let l2_withdrawals = l2_client
+            .get_withdrawals(hash)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("L2 tx {l2_tx_hash}")))?;
+        if l2_withdrawals.receivers.is_empty() {
+            return Err(ApiError::NotFound(format!(
+                "Withdrawal in L2 tx {l2_tx_hash}"
+            )));
+        }
+
+        let mut storage = self_.connection().await?;
+        let withdrawals = match &l2_withdrawals.l1_batch_reveal_tx_id {
+            Some(l1_batch_reveal_tx_id) => {
+                storage
+                    .via_transactions_dal()
+                    .list_withdrawals_by_l1_batch_reveal_tx_id(
+                        l1_batch_reveal_tx_id.as_bytes(),
+                        &l2_withdrawals.receivers,
+                    )
+                    .await?
+            }
+            None => vec![],
+        };
+        let last_processed_block = Self::last_processed_block(&mut storage).await?;
+
+        let withdrawals = withdrawals
+            .into_iter()
+            .map(|withdrawal| {
+                WithdrawalResponse::new(withdrawal, last_processed_block, self_.block_confirmations)
+            })
+            .collect();
+        Ok(ok_json(L2WithdrawalResponse::new(
+            hash,
+            l2_withdrawals,
+            withdrawals,
+        )))
+    }
+
+    /// Lists the withdrawals paid to a Bitcoin receiver, newest first.
+    #[instrument(skip(self_))]
+    pub async fn list_withdrawals(
+        State(self_): State<Arc<Self>>,
+        Query(query): Query<WithdrawalsQuery>,
+    ) -> anyhow::Result<Response<String>, ApiError> {
+        let (page, limit) = query.pagination().resolve(self_.config.max_page_size());
+        let offset = i64::from(page - 1) * i64::from(limit);
+
+        let mut storage = self_.connection().await?;
+        let withdrawals = storage
+            .via_transactions_dal()
+            .list_withdrawals_by_receiver(&query.receiver, limit.into(), offset)
+            .await?;
+        let last_processed_block = Self::last_processed_block(&mut storage).await?;
+
+        let items = withdrawals
+            .into_iter()
+            .map(|withdrawal| {
+                WithdrawalResponse::new(withdrawal, last_processed_block, self_.block_confirmations)
+            })
+            .collect();
+        Ok(ok_json(Page { page, limit, items }))
+    }
+}
@@ -0,0 +1,50 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use thiserror::Error;
+use via_indexer_dal::DalError;
+
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("Invalid input: {0}")]
+    BadRequest(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Unexpected error: {0}")]
+    InternalServerError(String),
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        ApiError::InternalServerError(error.to_string())
+    }
+}
+
+impl From<DalError> for ApiError {
+    fn from(error: DalError) -> Self {
+        ApiError::InternalServerError(error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match &self {
+            ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            ApiError::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            ApiError::InternalServerError(_) => {
+                tracing::error!("Indexer API error: {self}");
+                (StatusCode::INTERNAL_SERVER_ERROR, self.to_string())
+            }
+        };
+
+        (status, Json(ErrorResponse { message })).into_response()
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    message: String,
+}
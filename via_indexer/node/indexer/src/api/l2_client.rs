@@ -0,0 +1,118 @@
+//! Access to the L2 node, used to find the withdrawals requested by an L2 transaction.
+
+use std::fmt;
+
+use anyhow::Context as _;
+use via_btc_client::types::BitcoinNetwork;
+use via_withdrawal_client::withdraw::parse_l2_withdrawal_message;
+use zksync_types::{
+    api::TransactionReceipt, ethabi, web3::keccak256, H256, L1_MESSENGER_ADDRESS,
+    L2_BASE_TOKEN_ADDRESS,
+};
+use zksync_web3_decl::{
+    client::{DynClient, L2},
+    error::ClientRpcContext,
+    namespaces::{EthNamespaceClient, ZksNamespaceClient},
+};
+
+/// The withdrawals requested by an L2 transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct L2Withdrawals {
+    /// L1 batch of the transaction, once sealed.
+    pub l1_batch_number: Option<u32>,
+    /// Reveal tx id of the proof of the L1 batch, in display order, once proven.
+    pub l1_batch_reveal_tx_id: Option<H256>,
+    /// Bitcoin receivers of the withdrawals.
+    pub receivers: Vec<String>,
+}
+
+#[async_trait::async_trait]
+pub trait L2WithdrawalsClient: 'static + fmt::Debug + Send + Sync {
+    /// Returns the withdrawals requested by the L2 transaction `l2_tx_hash`, `None` if the
+    /// transaction is unknown.
+    async fn get_withdrawals(&self, l2_tx_hash: H256) -> anyhow::Result<Option<L2Withdrawals>>;
+}
+
+/// Reads the withdrawals from the JSON-RPC API of an L2 node.
+#[derive(Debug)]
+pub struct L2RpcWithdrawalsClient {
+    client: Box<DynClient<L2>>,
+    network: BitcoinNetwork,
+}
+
+impl L2RpcWithdrawalsClient {
+    pub fn new(client: Box<DynClient<L2>>, network: BitcoinNetwork) -> Self {
+        Self {
+            client: client.for_component("indexer_api"),
+            network,
+        }
+    }
+
+    /// Parses the receivers of the withdrawal messages sent to L1 by the base token contract.
+    fn withdrawal_receivers(
+        receipt: &TransactionReceipt,
+        network: BitcoinNetwork,
+    ) -> anyhow::Result<Vec<String>> {
+        let l1_message_signature = ethabi::long_signature(
+            "L1MessageSent",
+            &[
+                ethabi::ParamType::Address,
+                ethabi::ParamType::FixedBytes(32),
+                ethabi::ParamType::Bytes,
+            ],
+        );
+        let base_token_topic = H256::from(L2_BASE_TOKEN_ADDRESS);
+
+        let mut receivers = Vec::new();
+        for log in &receipt.logs {
+            if log.address != L1_MESSENGER_ADDRESS
+                || log.topics.len() != 3
+                || log.topics[0] != l1_message_signature
+                || log.topics[1] != base_token_topic
+            {
+                continue;
+            }
+
+            let message = ethabi::decode(&[ethabi::ParamType::Bytes], &log.data.0)
+                .context("Invalid L1MessageSent event")?
+                .pop()
+                .and_then(ethabi::Token::into_bytes)
+                .context("Invalid L1MessageSent event")?;
+            if H256(keccak256(&message)) != log.topics[2] {
+                anyhow::bail!("L1MessageSent event doesn't match its message hash");
+            }
+            let withdrawal = parse_l2_withdrawal_message(message, network)?;
+            receivers.push(withdrawal.address.to_string());
+        }
+        Ok(receivers)
+    }
+}
+
+#[async_trait::async_trait]
+impl L2WithdrawalsClient for L2RpcWithdrawalsClient {
+    async fn get_withdrawals(&self, l2_tx_hash: H256) -> anyhow::Result<Option<L2Withdrawals>> {
+        let Some(receipt) = self
+            .client
+            .get_transaction_receipt(l2_tx_hash)
+            .rpc_context("get_transaction_receipt")
+            .with_arg("hash", &l2_tx_hash)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let receivers = Self::withdrawal_receivers(&receipt, self.network)?;
+
+        let details = self
+            .client
+            .get_transaction_details(l2_tx_hash)
+            .rpc_context("get_transaction_details")
+            .with_arg("hash", &l2_tx_hash)
+            .await?;
+
+        Ok(Some(L2Withdrawals {
+            l1_batch_number: receipt.l1_batch_number.map(|number| number.as_u32()),
+            l1_batch_reveal_tx_id: details.and_then(|details| details.prove_tx_hash),
+            receivers,
+        }))
+    }
+}
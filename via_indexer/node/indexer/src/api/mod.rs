@@ -0,0 +1,45 @@
+//! HTTP API serving the indexed deposits and withdrawals, so the users can track their bridge
+//! transactions.
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use via_indexer_dal::{ConnectionPool, Indexer};
+use zksync_config::configs::via_l1_indexer::ViaIndexerApiConfig;
+
+use crate::api::{api_decl::RestApi, l2_client::L2WithdrawalsClient};
+
+mod api_decl;
+mod api_impl;
+mod error;
+pub mod l2_client;
+#[cfg(test)]
+mod tests;
+pub mod types;
+
+pub async fn start_api_server(
+    config: ViaIndexerApiConfig,
+    pool: ConnectionPool<Indexer>,
+    block_confirmations: u64,
+    l2_client: Option<Box<dyn L2WithdrawalsClient>>,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let bind_address = config.bind_addr();
+    let api = RestApi::new(config, pool, block_confirmations, l2_client).into_router();
+
+    let listener = tokio::net::TcpListener::bind(bind_address)
+        .await
+        .context("Cannot bind to the specified address")?;
+    axum::serve(listener, api)
+        .with_graceful_shutdown(async move {
+            if stop_receiver.changed().await.is_err() {
+                tracing::warn!(
+                    "Stop signal sender for indexer API server was dropped without sending a signal"
+                );
+            }
+            tracing::info!("Stop signal received, indexer API server is shutting down");
+        })
+        .await
+        .with_context(|| "indexer API server failed")?;
+    tracing::info!("indexer API server shut down");
+    Ok(())
+}
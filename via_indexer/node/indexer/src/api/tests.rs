@@ -0,0 +1,482 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{Request, StatusCode},
+    response::Response,
+};
+use serde_json::Value;
+use tower::ServiceExt;
+use via_btc_client::types::BitcoinSecp256k1::hashes::hex::{Case, DisplayHex};
+use via_indexer_dal::{
+    models::{
+        deposit::Deposit,
+        withdraw::{BridgeWithdrawalParam, WithdrawalParam},
+    },
+    Connection, ConnectionPool, Indexer, IndexerDal,
+};
+use zksync_config::configs::via_l1_indexer::ViaIndexerApiConfig;
+use zksync_types::{Address, H256};
+
+use crate::{
+    api::{
+        api_decl::RestApi,
+        error::ApiError,
+        l2_client::{L2Withdrawals, L2WithdrawalsClient},
+        types::{DepositsQuery, Pagination, WithdrawalsQuery},
+    },
+    L1Indexer,
+};
+
+const MAX_PAGE_SIZE: u32 = 2;
+const BLOCK_CONFIRMATIONS: u64 = 3;
+const LAST_PROCESSED_BLOCK: u32 = 10;
+
+#[derive(Debug, Default)]
+struct MockL2Client {
+    withdrawals: HashMap<H256, L2Withdrawals>,
+}
+
+#[async_trait::async_trait]
+impl L2WithdrawalsClient for MockL2Client {
+    async fn get_withdrawals(&self, l2_tx_hash: H256) -> anyhow::Result<Option<L2Withdrawals>> {
+        Ok(self.withdrawals.get(&l2_tx_hash).cloned())
+    }
+}
+
+fn rest_api(
+    pool: &ConnectionPool<Indexer>,
+    l2_client: Option<Box<dyn L2WithdrawalsClient>>,
+) -> RestApi {
+    let config = ViaIndexerApiConfig {
+        port: 0,
+        max_page_size: Some(MAX_PAGE_SIZE),
+        l2_rpc_url: None,
+    };
+    RestApi::new(config, pool.clone(), BLOCK_CONFIRMATIONS, l2_client)
+}
+
+fn create_api(
+    pool: &ConnectionPool<Indexer>,
+    l2_client: Option<Box<dyn L2WithdrawalsClient>>,
+) -> Arc<RestApi> {
+    Arc::new(rest_api(pool, l2_client))
+}
+
+fn json(response: Response<String>) -> Value {
+    assert_eq!(response.status(), StatusCode::OK);
+    serde_json::from_str(response.body()).unwrap()
+}
+
+/// Tx ids are stored in display order, so their string is the hex of the stored bytes.
+fn tx_id_string(tx_id: &[u8]) -> String {
+    tx_id.to_hex_string(Case::Lower)
+}
+
+fn deposit(priority_id: i64, receiver: Address, block_number: u32) -> Deposit {
+    Deposit {
+        priority_id,
+        tx_id: H256::from_low_u64_be(priority_id as u64)
+            .as_bytes()
+            .to_vec(),
+        block_number,
+        sender: "bc1qsender".to_string(),
+        receiver: format!("{receiver:#x}"),
+        value: 1_000,
+        calldata: vec![],
+        canonical_tx_hash: H256::from_low_u64_be(1_000 + priority_id as u64)
+            .as_bytes()
+            .to_vec(),
+        block_timestamp: 0,
+    }
+}
+
+fn deposits_query(receiver: Option<String>, sender: Option<String>) -> DepositsQuery {
+    DepositsQuery {
+        receiver,
+        sender,
+        page: None,
+        limit: None,
+    }
+}
+
+async fn setup_storage(storage: &mut Connection<'_, Indexer>) {
+    storage
+        .via_indexer_dal()
+        .init_indexer_metadata(L1Indexer::module_name(), LAST_PROCESSED_BLOCK)
+        .await
+        .unwrap();
+}
+
+async fn insert_bridge_withdrawal(
+    storage: &mut Connection<'_, Indexer>,
+    tx_id: H256,
+    l1_batch_reveal_tx_id: H256,
+    receivers: &[&str],
+) {
+    let bridge_withdrawal = BridgeWithdrawalParam {
+        tx_id: tx_id.as_bytes().to_vec(),
+        l1_batch_reveal_tx_id: l1_batch_reveal_tx_id.as_bytes().to_vec(),
+        block_number: i64::from(LAST_PROCESSED_BLOCK),
+        fee: 500,
+        vsize: 200,
+        total_size: 300,
+        withdrawals_count: receivers.len() as i64,
+    };
+    let withdrawals = receivers
+        .iter()
+        .enumerate()
+        .map(|(index, receiver)| WithdrawalParam {
+            tx_index: index as i64,
+            receiver: receiver.to_string(),
+            value: 1_000,
+        })
+        .collect();
+    storage
+        .via_transactions_dal()
+        .insert_withdraw(bridge_withdrawal, withdrawals)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn test_pagination_bounds() {
+    let resolve = |page, limit| Pagination { page, limit }.resolve(MAX_PAGE_SIZE);
+
+    assert_eq!(resolve(None, None), (1, MAX_PAGE_SIZE));
+    assert_eq!(resolve(Some(0), Some(0)), (1, 1));
+    assert_eq!(resolve(Some(3), Some(1)), (3, 1));
+    assert_eq!(resolve(Some(1), Some(u32::MAX)), (1, MAX_PAGE_SIZE));
+}
+
+#[tokio::test]
+async fn test_list_deposits_pagination() {
+    let pool = ConnectionPool::<Indexer>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    setup_storage(&mut storage).await;
+
+    let receiver = Address::repeat_byte(0xab);
+    let deposits = (1..=3).map(|id| deposit(id, receiver, 5)).collect();
+    storage
+        .via_transactions_dal()
+        .insert_deposit_many(deposits)
+        .await
+        .unwrap();
+    let api = create_api(&pool, None);
+
+    let page_ids = |page: Value| -> Vec<i64> {
+        page["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["priorityId"].as_i64().unwrap())
+            .collect()
+    };
+
+    // The limit is capped to the max page size and the deposits are listed newest first. The
+    // receiver is matched whatever the case of its hex encoding.
+    let upper_case_receiver = format!("0x{}", receiver.as_bytes().to_hex_string(Case::Upper));
+    let mut query = deposits_query(Some(upper_case_receiver), None);
+    query.limit = Some(100);
+    let page = json(
+        RestApi::list_deposits(State(api.clone()), Query(query))
+            .await
+            .unwrap(),
+    );
+    assert_eq!(page["limit"], MAX_PAGE_SIZE);
+    assert_eq!(page_ids(page), [3, 2]);
+
+    for (page_number, expected_ids) in [(2, vec![1]), (3, vec![])] {
+        let mut query = deposits_query(Some(format!("{receiver:#x}")), None);
+        query.page = Some(page_number);
+        let page = json(
+            RestApi::list_deposits(State(api.clone()), Query(query))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(page["page"], page_number);
+        assert_eq!(page_ids(page), expected_ids);
+    }
+
+    let page = json(
+        RestApi::list_deposits(
+            State(api.clone()),
+            Query(deposits_query(None, Some("bc1qsender".to_string()))),
+        )
+        .await
+        .unwrap(),
+    );
+    assert_eq!(page_ids(page), [3, 2]);
+
+    for query in [
+        deposits_query(None, None),
+        deposits_query(
+            Some(format!("{receiver:#x}")),
+            Some("bc1qsender".to_string()),
+        ),
+        deposits_query(Some("not an address".to_string()), None),
+    ] {
+        let err = RestApi::list_deposits(State(api.clone()), Query(query))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ApiError::BadRequest(_)), "{err}");
+    }
+}
+
+#[tokio::test]
+async fn test_list_through_router() {
+    let pool = ConnectionPool::<Indexer>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    setup_storage(&mut storage).await;
+
+    let receiver = Address::repeat_byte(0xab);
+    let deposits = (1..=3).map(|id| deposit(id, receiver, 5)).collect();
+    storage
+        .via_transactions_dal()
+        .insert_deposit_many(deposits)
+        .await
+        .unwrap();
+    insert_bridge_withdrawal(
+        &mut storage,
+        H256::repeat_byte(0x11),
+        H256::repeat_byte(0xaa),
+        &["bc1qalice", "bc1qbob"],
+    )
+    .await;
+    let router = rest_api(&pool, None).into_router();
+
+    let get = |uri: String| {
+        let router = router.clone();
+        async move {
+            let request = Request::get(uri).body(Body::empty()).unwrap();
+            let response = router.oneshot(request).await.unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            (status, serde_json::from_slice::<Value>(&body).ok())
+        }
+    };
+
+    // The numeric pagination fields are parsed from the query string.
+    let (status, page) = get(format!("/deposits?receiver={receiver:#x}&page=2&limit=1")).await;
+    assert_eq!(status, StatusCode::OK);
+    let page = page.unwrap();
+    assert_eq!(page["page"], 2);
+    assert_eq!(page["limit"], 1);
+    assert_eq!(page["items"][0]["priorityId"], 2);
+
+    let (status, page) = get("/withdrawals?receiver=bc1qbob&page=1&limit=5".to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    let page = page.unwrap();
+    assert_eq!(page["limit"], MAX_PAGE_SIZE);
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+
+    let (status, _) = get("/deposits?sender=bc1qsender&page=first".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_deposit_lookups() {
+    let pool = ConnectionPool::<Indexer>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    setup_storage(&mut storage).await;
+
+    let confirmed = deposit(1, Address::repeat_byte(1), 5);
+    let pending = deposit(2, Address::repeat_byte(2), LAST_PROCESSED_BLOCK);
+    storage
+        .via_transactions_dal()
+        .insert_deposit_many(vec![confirmed.clone(), pending.clone()])
+        .await
+        .unwrap();
+    let api = create_api(&pool, None);
+
+    let response = json(
+        RestApi::get_deposit(State(api.clone()), Path(tx_id_string(&confirmed.tx_id)))
+            .await
+            .unwrap(),
+    );
+    assert_eq!(response["priorityId"], 1);
+    assert_eq!(response["confirmations"], 6);
+    assert_eq!(response["status"], "Confirmed");
+
+    let canonical_tx_hash = format!("0x{}", tx_id_string(&pending.canonical_tx_hash));
+    let response = json(
+        RestApi::get_deposit_by_canonical_tx_hash(State(api.clone()), Path(canonical_tx_hash))
+            .await
+            .unwrap(),
+    );
+    assert_eq!(response["priorityId"], 2);
+    assert_eq!(response["confirmations"], 1);
+    assert_eq!(response["status"], "Pending");
+
+    let err = RestApi::get_deposit(State(api.clone()), Path(tx_id_string(&[7; 32])))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ApiError::NotFound(_)), "{err}");
+    let err = RestApi::get_deposit(State(api.clone()), Path("0x1234".to_string()))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ApiError::BadRequest(_)), "{err}");
+    let err =
+        RestApi::get_deposit_by_canonical_tx_hash(State(api.clone()), Path("0x12".to_string()))
+            .await
+            .unwrap_err();
+    assert!(matches!(err, ApiError::BadRequest(_)), "{err}");
+}
+
+#[tokio::test]
+async fn test_withdrawal_lookups() {
+    let pool = ConnectionPool::<Indexer>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    setup_storage(&mut storage).await;
+
+    let bridge_tx_id = H256::repeat_byte(0x11);
+    insert_bridge_withdrawal(
+        &mut storage,
+        bridge_tx_id,
+        H256::repeat_byte(0xaa),
+        &["bc1qalice", "bc1qbob"],
+    )
+    .await;
+    let api = create_api(&pool, None);
+
+    let response = json(
+        RestApi::get_bridge_withdrawal(
+            State(api.clone()),
+            Path(tx_id_string(bridge_tx_id.as_bytes())),
+        )
+        .await
+        .unwrap(),
+    );
+    assert_eq!(response["fee"], 500);
+    assert_eq!(response["withdrawalsCount"], 2);
+    assert_eq!(response["status"], "Pending");
+    let receivers: Vec<_> = response["withdrawals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|withdrawal| withdrawal["receiver"].as_str().unwrap())
+        .collect();
+    assert_eq!(receivers, ["bc1qalice", "bc1qbob"]);
+
+    let err = RestApi::get_bridge_withdrawal(State(api.clone()), Path(tx_id_string(&[7; 32])))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ApiError::NotFound(_)), "{err}");
+
+    let query = WithdrawalsQuery {
+        receiver: "bc1qbob".to_string(),
+        page: None,
+        limit: None,
+    };
+    let page = json(
+        RestApi::list_withdrawals(State(api.clone()), Query(query))
+            .await
+            .unwrap(),
+    );
+    let items = page["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(
+        items[0]["bridgeTxId"],
+        tx_id_string(bridge_tx_id.as_bytes())
+    );
+    assert_eq!(items[0]["txIndex"], 1);
+}
+
+#[tokio::test]
+async fn test_withdrawal_by_l2_tx_hash() {
+    let pool = ConnectionPool::<Indexer>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    setup_storage(&mut storage).await;
+
+    let l1_batch_reveal_tx_id = H256::repeat_byte(0xaa);
+    let bridge_tx_id = H256::repeat_byte(0x11);
+    insert_bridge_withdrawal(
+        &mut storage,
+        bridge_tx_id,
+        l1_batch_reveal_tx_id,
+        &["bc1qalice", "bc1qbob"],
+    )
+    .await;
+
+    let proven_tx = H256::repeat_byte(1);
+    let unproven_tx = H256::repeat_byte(2);
+    let transfer_tx = H256::repeat_byte(3);
+    let mut l2_client = MockL2Client::default();
+    l2_client.withdrawals.insert(
+        proven_tx,
+        L2Withdrawals {
+            l1_batch_number: Some(1),
+            l1_batch_reveal_tx_id: Some(l1_batch_reveal_tx_id),
+            receivers: vec!["bc1qbob".to_string()],
+        },
+    );
+    l2_client.withdrawals.insert(
+        unproven_tx,
+        L2Withdrawals {
+            l1_batch_number: None,
+            l1_batch_reveal_tx_id: None,
+            receivers: vec!["bc1qalice".to_string()],
+        },
+    );
+    l2_client.withdrawals.insert(
+        transfer_tx,
+        L2Withdrawals {
+            l1_batch_number: Some(1),
+            l1_batch_reveal_tx_id: Some(l1_batch_reveal_tx_id),
+            receivers: vec![],
+        },
+    );
+    let api = create_api(&pool, Some(Box::new(l2_client)));
+
+    let response = json(
+        RestApi::get_withdrawal_by_l2_tx_hash(State(api.clone()), Path(format!("{proven_tx:?}")))
+            .await
+            .unwrap(),
+    );
+    assert_eq!(response["l1BatchNumber"], 1);
+    assert_eq!(
+        response["l1BatchRevealTxId"],
+        tx_id_string(l1_batch_reveal_tx_id.as_bytes())
+    );
+    let withdrawals = response["withdrawals"].as_array().unwrap();
+    assert_eq!(withdrawals.len(), 1);
+    assert_eq!(withdrawals[0]["receiver"], "bc1qbob");
+    assert_eq!(
+        withdrawals[0]["bridgeTxId"],
+        tx_id_string(bridge_tx_id.as_bytes())
+    );
+
+    // The withdrawal is known on L2 but not paid yet.
+    let response = json(
+        RestApi::get_withdrawal_by_l2_tx_hash(State(api.clone()), Path(format!("{unproven_tx:?}")))
+            .await
+            .unwrap(),
+    );
+    assert_eq!(response["l1BatchRevealTxId"], Value::Null);
+    assert_eq!(response["receivers"][0], "bc1qalice");
+    assert!(response["withdrawals"].as_array().unwrap().is_empty());
+
+    for l2_tx_hash in [transfer_tx, H256::repeat_byte(4)] {
+        let err = RestApi::get_withdrawal_by_l2_tx_hash(
+            State(api.clone()),
+            Path(format!("{l2_tx_hash:?}")),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ApiError::NotFound(_)), "{err}");
+    }
+    let err = RestApi::get_withdrawal_by_l2_tx_hash(State(api.clone()), Path("0x12".to_string()))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ApiError::BadRequest(_)), "{err}");
+
+    // The lookup is disabled without an L2 node.
+    let api = create_api(&pool, None);
+    let err = RestApi::get_withdrawal_by_l2_tx_hash(State(api), Path(format!("{proven_tx:?}")))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ApiError::BadRequest(_)), "{err}");
+}
@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use via_btc_client::types::BitcoinSecp256k1::hashes::hex::{Case, DisplayHex};
+use via_indexer_dal::models::{
+    deposit::Deposit,
+    withdraw::{BridgeWithdrawalParam, Withdrawal},
+};
+use zksync_types::H256;
+
+use crate::api::l2_client::L2Withdrawals;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TransactionStatus {
+    /// The transaction is indexed but did not reach the required number of confirmations yet.
+    Pending,
+    /// The transaction has at least the required number of confirmations.
+    Confirmed,
+}
+
+impl TransactionStatus {
+    pub fn new(confirmations: u64, block_confirmations: u64) -> Self {
+        if confirmations >= block_confirmations {
+            Self::Confirmed
+        } else {
+            Self::Pending
+        }
+    }
+}
+
+/// Number of blocks on top of `block_number`, the block itself included.
+pub fn confirmations(last_processed_block: u64, block_number: i64) -> u64 {
+    let block_number = u64::try_from(block_number).unwrap_or_default();
+    if block_number > last_processed_block {
+        return 0;
+    }
+    last_processed_block - block_number + 1
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", bytes.to_hex_string(Case::Lower))
+}
+
+/// Bitcoin tx ids are stored in display order, so they are encoded without the `0x` prefix.
+fn txid_to_string(bytes: &[u8]) -> String {
+    bytes.to_hex_string(Case::Lower)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Pagination {
+    /// 1-based page number.
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+impl Pagination {
+    /// Returns the requested `(page, limit)` pair, with the limit capped to `max_page_size`.
+    pub fn resolve(&self, max_page_size: u32) -> (u32, u32) {
+        let page = self.page.unwrap_or(1).max(1);
+        let limit = self.limit.unwrap_or(max_page_size).clamp(1, max_page_size);
+        (page, limit)
+    }
+}
+
+// The pagination fields are not flattened: `serde_urlencoded` only yields strings to the
+// flattened fields, which fail to deserialize as numbers.
+#[derive(Debug, Deserialize)]
+pub struct DepositsQuery {
+    /// L2 receiver address.
+    pub receiver: Option<String>,
+    /// Bitcoin sender address.
+    pub sender: Option<String>,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+impl DepositsQuery {
+    pub fn pagination(&self) -> Pagination {
+        Pagination {
+            page: self.page,
+            limit: self.limit,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WithdrawalsQuery {
+    /// Bitcoin receiver address.
+    pub receiver: String,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+impl WithdrawalsQuery {
+    pub fn pagination(&self) -> Pagination {
+        Pagination {
+            page: self.page,
+            limit: self.limit,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositResponse {
+    pub priority_id: i64,
+    pub tx_id: String,
+    pub block_number: u32,
+    pub block_timestamp: u64,
+    pub sender: String,
+    pub receiver: String,
+    pub value: i64,
+    pub calldata: String,
+    pub l2_canonical_tx_hash: String,
+    pub confirmations: u64,
+    pub status: TransactionStatus,
+}
+
+impl DepositResponse {
+    pub fn new(deposit: Deposit, last_processed_block: u64, block_confirmations: u64) -> Self {
+        let confirmations = confirmations(last_processed_block, deposit.block_number.into());
+        Self {
+            priority_id: deposit.priority_id,
+            tx_id: txid_to_string(&deposit.tx_id),
+            block_number: deposit.block_number,
+            block_timestamp: deposit.block_timestamp,
+            sender: deposit.sender,
+            receiver: deposit.receiver,
+            value: deposit.value,
+            calldata: to_hex(&deposit.calldata),
+            l2_canonical_tx_hash: to_hex(&deposit.canonical_tx_hash),
+            confirmations,
+            status: TransactionStatus::new(confirmations, block_confirmations),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalResponse {
+    pub bridge_tx_id: String,
+    pub l1_batch_reveal_tx_id: String,
+    pub block_number: i64,
+    pub tx_index: i64,
+    pub receiver: String,
+    pub value: i64,
+    pub confirmations: u64,
+    pub status: TransactionStatus,
+}
+
+impl WithdrawalResponse {
+    pub fn new(
+        withdrawal: Withdrawal,
+        last_processed_block: u64,
+        block_confirmations: u64,
+    ) -> Self {
+        let confirmations = confirmations(last_processed_block, withdrawal.block_number);
+        Self {
+            bridge_tx_id: txid_to_string(&withdrawal.bridge_tx_id),
+            l1_batch_reveal_tx_id: txid_to_string(&withdrawal.l1_batch_reveal_tx_id),
+            block_number: withdrawal.block_number,
+            tx_index: withdrawal.tx_index,
+            receiver: withdrawal.receiver,
+            value: withdrawal.value,
+            confirmations,
+            status: TransactionStatus::new(confirmations, block_confirmations),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeWithdrawalResponse {
+    pub tx_id: String,
+    pub l1_batch_reveal_tx_id: String,
+    pub block_number: i64,
+    pub fee: i64,
+    pub vsize: i64,
+    pub total_size: i64,
+    pub withdrawals_count: i64,
+    pub confirmations: u64,
+    pub status: TransactionStatus,
+    pub withdrawals: Vec<WithdrawalResponse>,
+}
+
+impl BridgeWithdrawalResponse {
+    pub fn new(
+        bridge_withdrawal: BridgeWithdrawalParam,
+        withdrawals: Vec<WithdrawalResponse>,
+        last_processed_block: u64,
+        block_confirmations: u64,
+    ) -> Self {
+        let confirmations = confirmations(last_processed_block, bridge_withdrawal.block_number);
+        Self {
+            tx_id: txid_to_string(&bridge_withdrawal.tx_id),
+            l1_batch_reveal_tx_id: txid_to_string(&bridge_withdrawal.l1_batch_reveal_tx_id),
+            block_number: bridge_withdrawal.block_number,
+            fee: bridge_withdrawal.fee,
+            vsize: bridge_withdrawal.vsize,
+            total_size: bridge_withdrawal.total_size,
+            withdrawals_count: bridge_withdrawal.withdrawals_count,
+            confirmations,
+            status: TransactionStatus::new(confirmations, block_confirmations),
+            withdrawals,
+        }
+    }
+}
+
+/// The withdrawals requested by an L2 transaction, and the bridge outputs that paid them once the
+/// L1 batch is proven.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L2WithdrawalResponse {
+    pub l2_tx_hash: String,
+    pub l1_batch_number: Option<u32>,
+    pub l1_batch_reveal_tx_id: Option<String>,
+    pub receivers: Vec<String>,
+    pub withdrawals: Vec<WithdrawalResponse>,
+}
+
+impl L2WithdrawalResponse {
+    pub fn new(
+        l2_tx_hash: H256,
+        l2_withdrawals: L2Withdrawals,
+        withdrawals: Vec<WithdrawalResponse>,
+    ) -> Self {
+        Self {
+            l2_tx_hash: to_hex(l2_tx_hash.as_bytes()),
+            l1_batch_number: l2_withdrawals.l1_batch_number,
+            l1_batch_reveal_tx_id: l2_withdrawals
+                .l1_batch_reveal_tx_id
+                .map(|tx_id| txid_to_string(tx_id.as_bytes())),
+            receivers: l2_withdrawals.receivers,
+            withdrawals,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub page: u32,
+    pub limit: u32,
+    pub items: Vec<T>,
+}
@@ -1,3 +1,4 @@
+pub mod api;
 mod message_processors;
 mod metrics;
 
@@ -161,7 +162,7 @@ impl L1Indexer {
         Ok(())
     }
 
-    fn module_name() -> &'static str {
+    pub(crate) fn module_name() -> &'static str {
         "l1_indexer"
     }
 }
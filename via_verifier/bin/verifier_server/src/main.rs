@@ -2,7 +2,7 @@ use anyhow::Context as _;
 use clap::Parser;
 use zksync_config::{
     configs::{
-        via_secrets::{ViaDASecrets, ViaL1Secrets, ViaSecrets},
+        via_secrets::{ViaDASecrets, ViaKeystoreSecrets, ViaL1Secrets, ViaSecrets},
         via_wallets::ViaWallets,
         DatabaseSecrets, L1Secrets, Secrets,
     },
@@ -70,6 +70,7 @@ fn main() -> anyhow::Result<()> {
             },
             via_l1: ViaL1Secrets::from_env().ok(),
             via_da: ViaDASecrets::from_env().ok(),
+            via_keystore: ViaKeystoreSecrets::from_env().ok(),
         },
     };
 
@@ -1,7 +1,17 @@
 use anyhow::Context;
-use via_da_clients::celestia::wiring_layer::ViaCelestiaClientWiringLayer;
+use via_da_clients::{
+    bitcoin::{self as bitcoin_da, wiring_layer::ViaBitcoinDAClientWiringLayer},
+    celestia::{self, wiring_layer::ViaCelestiaClientWiringLayer},
+    multi::{
+        self,
+        wiring_layer::{ViaDABackend, ViaMultiDAClientWiringLayer},
+    },
+};
 use zksync_config::{
-    configs::{via_secrets::ViaSecrets, via_wallets::ViaWallets},
+    configs::{
+        via_secrets::{ViaKeystoreSecrets, ViaSecrets},
+        via_wallets::ViaWallets,
+    },
     GenesisConfig, ViaGeneralConfig,
 };
 use zksync_node_framework::{
@@ -16,7 +26,8 @@ use zksync_node_framework::{
             vote::ViaBtcVoteInscriptionLayer, vote_manager::ViaInscriptionManagerLayer,
         },
         via_verifier::{
-            coordinator_api::ViaCoordinatorApiLayer, verifier::ViaWithdrawalVerifierLayer,
+            coordinator_api::ViaCoordinatorApiLayer, status_api::ViaVerifierStatusApiLayer,
+            verifier::ViaWithdrawalVerifierLayer,
         },
         via_verifier_btc_watch::VerifierBtcWatchLayer,
         via_verifier_storage_init::ViaVerifierInitLayer,
@@ -52,7 +63,9 @@ impl ViaNodeBuilder {
         wallets: ViaWallets,
     ) -> anyhow::Result<Self> {
         let via_verifier_config = try_load_config!(via_general_config.via_verifier_config);
-        let is_coordinator = via_verifier_config.role == ViaNodeRole::Coordinator;
+        // With leader rotation, every verifier can be elected and serves the coordinator API.
+        let is_coordinator = via_verifier_config.role == ViaNodeRole::Coordinator
+            || via_verifier_config.leader_rotation_enabled();
         Ok(Self {
             is_coordinator,
             node: ZkStackServiceBuilder::new().context("Cannot create ZkStackServiceBuilder")?,
@@ -72,6 +85,10 @@ impl ViaNodeBuilder {
         Ok(self)
     }
 
+    fn keystore_secrets(&self) -> ViaKeystoreSecrets {
+        self.secrets.via_keystore.clone().unwrap_or_default()
+    }
+
     fn add_btc_client_layer(mut self) -> anyhow::Result<Self> {
         let via_btc_client_config = try_load_config!(self.configs.via_btc_client_config);
         let secrets = self.secrets.via_l1.clone().unwrap();
@@ -86,11 +103,73 @@ impl ViaNodeBuilder {
         Ok(self)
     }
 
-    fn add_via_celestia_da_client_layer(mut self) -> anyhow::Result<Self> {
+    fn via_celestia_da_client_layer(&self) -> anyhow::Result<ViaCelestiaClientWiringLayer> {
         let secrets = self.secrets.via_da.clone().unwrap();
         let celestia_config = try_load_config!(self.configs.via_celestia_config);
-        self.node
-            .add_layer(ViaCelestiaClientWiringLayer::new(celestia_config, secrets));
+        // The verifier doesn't trust the Celestia node and checks the blobs against their
+        // commitment.
+        Ok(ViaCelestiaClientWiringLayer::new(celestia_config, secrets)
+            .with_inclusion_proof_verification())
+    }
+
+    fn via_bitcoin_da_client_layer(&self) -> anyhow::Result<ViaBitcoinDAClientWiringLayer> {
+        let via_btc_client_config = try_load_config!(self.configs.via_btc_client_config);
+        let secrets = self.secrets.via_l1.clone().unwrap();
+        // The verifier only reads the blobs inscribed by the sequencer.
+        Ok(ViaBitcoinDAClientWiringLayer::new(
+            via_btc_client_config,
+            secrets,
+            None,
+        ))
+    }
+
+    fn via_da_backend(&self, da_identifier: &str) -> anyhow::Result<ViaDABackend> {
+        match da_identifier {
+            celestia::DA_IDENTIFIER => {
+                Ok(ViaDABackend::Celestia(self.via_celestia_da_client_layer()?))
+            }
+            bitcoin_da::DA_IDENTIFIER => {
+                Ok(ViaDABackend::Bitcoin(self.via_bitcoin_da_client_layer()?))
+            }
+            other => anyhow::bail!("Unsupported DA layer `{other}`"),
+        }
+    }
+
+    /// Returns the other known DA layers with a configuration, to fetch the blobs inscribed with
+    /// them before the sequencer switched its DA layers.
+    fn via_read_only_da_backends(
+        &self,
+        da_identifiers: &[&str],
+    ) -> anyhow::Result<Vec<ViaDABackend>> {
+        let mut backends = Vec::new();
+        if !da_identifiers.contains(&celestia::DA_IDENTIFIER)
+            && self.configs.via_celestia_config.is_some()
+            && self.secrets.via_da.is_some()
+        {
+            backends.push(ViaDABackend::Celestia(self.via_celestia_da_client_layer()?));
+        }
+        if !da_identifiers.contains(&bitcoin_da::DA_IDENTIFIER) {
+            backends.push(ViaDABackend::Bitcoin(self.via_bitcoin_da_client_layer()?));
+        }
+        Ok(backends)
+    }
+
+    /// Adds the client of the DA layers the sequencer stores the blobs in. Several DA layers
+    /// joined by `+` are all dispatched to, and tried in order when fetching the blobs. The blobs
+    /// are fetched from the DA layers inscribed with them, which can differ after a switch.
+    fn add_via_da_client_layer(mut self) -> anyhow::Result<Self> {
+        let btc_sender_config = try_load_config!(self.configs.via_btc_sender_config);
+        let da_identifier = btc_sender_config.da_identifier();
+        let da_identifiers = multi::split_da_identifier(&da_identifier);
+        let backends = da_identifiers
+            .iter()
+            .map(|da_identifier| self.via_da_backend(da_identifier))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let read_only_backends = self.via_read_only_da_backends(&da_identifiers)?;
+
+        self.node.add_layer(
+            ViaMultiDAClientWiringLayer::new(backends).with_read_only_backends(read_only_backends),
+        );
         Ok(self)
     }
 
@@ -124,8 +203,10 @@ impl ViaNodeBuilder {
         let btc_sender_config = try_load_config!(self.configs.via_btc_sender_config);
         self.node
             .add_layer(ViaBtcVoteInscriptionLayer::new(btc_sender_config.clone()));
-        self.node
-            .add_layer(ViaInscriptionManagerLayer::new(btc_sender_config, wallet));
+        self.node.add_layer(
+            ViaInscriptionManagerLayer::new(btc_sender_config, wallet)
+                .with_keystore_password(self.keystore_secrets().btc_sender_password),
+        );
         Ok(self)
     }
 
@@ -176,11 +257,24 @@ impl ViaNodeBuilder {
             .clone()
             .expect("Empty verifier wallet");
 
-        self.node.add_layer(ViaWithdrawalVerifierLayer::new(
-            via_bridge_config,
-            via_btc_client_config,
+        self.node.add_layer(
+            ViaWithdrawalVerifierLayer::new(
+                via_bridge_config,
+                via_btc_client_config,
+                via_verifier_config,
+                wallet,
+            )
+            .with_keystore_password(self.keystore_secrets().verifier_password),
+        );
+        Ok(self)
+    }
+
+    fn add_verifier_status_api_layer(mut self) -> anyhow::Result<Self> {
+        let via_verifier_config = try_load_config!(self.configs.via_verifier_config);
+
+        self.node.add_layer(ViaVerifierStatusApiLayer::new(
             via_verifier_config,
-            wallet,
+            self.wallets.clone(),
         ));
         Ok(self)
     }
@@ -191,6 +285,7 @@ impl ViaNodeBuilder {
         self.node.add_layer(ViaBtcProofVerificationLayer::new(
             via_verifier_config,
             via_bridge_config,
+            self.genesis_config.clone(),
         ));
         Ok(self)
     }
@@ -217,14 +312,16 @@ impl ViaNodeBuilder {
             .add_storage_initialization_layer()?
             .add_btc_sender_layer()?
             .add_verifier_btc_watcher_layer()?
-            .add_via_celestia_da_client_layer()?
+            .add_via_da_client_layer()?
             .add_zkp_verification_layer()?;
 
         if self.is_coordinator {
             self = self.add_verifier_coordinator_api_layer()?
         }
 
-        self = self.add_withdrawal_verifier_task_layer()?;
+        self = self
+            .add_withdrawal_verifier_task_layer()?
+            .add_verifier_status_api_layer()?;
 
         Ok(self.node.build())
     }
@@ -0,0 +1,6 @@
+ALTER TABLE via_wallets DROP COLUMN IF EXISTS l1_block_number;
+ALTER TABLE via_votes DROP COLUMN IF EXISTS l1_block_number;
+ALTER TABLE via_votable_transactions DROP COLUMN IF EXISTS l1_block_number;
+ALTER TABLE via_transactions DROP COLUMN IF EXISTS l1_block_number;
+
+DROP TABLE IF EXISTS via_indexer_blocks;
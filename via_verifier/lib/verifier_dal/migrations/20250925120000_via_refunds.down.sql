@@ -0,0 +1 @@
+DROP TABLE IF EXISTS via_refunds;
@@ -0,0 +1,18 @@
+-- Deposits that can't be credited on L2, the BTC is returned to the sender by the verifier network.
+CREATE TABLE IF NOT EXISTS via_refunds (
+    "id" BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    "deposit_tx_id" BYTEA NOT NULL,
+    "deposit_vout" BIGINT NOT NULL,
+    "l1_block_number" BIGINT NOT NULL,
+    "sender" VARCHAR,
+    "amount" BIGINT NOT NULL,
+    "reason" VARCHAR NOT NULL,
+    "status" VARCHAR NOT NULL DEFAULT 'Pending',
+    "data" BYTEA,
+    "refund_tx_id" BYTEA,
+    "created_at" TIMESTAMP NOT NULL DEFAULT NOW(),
+    "updated_at" TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_refund_deposit UNIQUE ("deposit_tx_id", "deposit_vout")
+);
+
+CREATE INDEX IF NOT EXISTS idx_via_refunds_status ON via_refunds (status);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS via_signing_session_partial_sigs;
+DROP TABLE IF EXISTS via_signing_session_nonces;
+DROP TABLE IF EXISTS via_signing_sessions;
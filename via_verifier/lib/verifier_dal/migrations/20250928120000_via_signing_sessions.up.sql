@@ -0,0 +1,38 @@
+-- MuSig2 signing sessions of the coordinator, persisted so a restart doesn't lose the collected nonces and signatures.
+CREATE TABLE IF NOT EXISTS via_signing_sessions (
+    "id" BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    "session_type" VARCHAR NOT NULL,
+    "session_op" BYTEA NOT NULL,
+    "messages_hash" BYTEA NOT NULL,
+    "is_active" BOOLEAN NOT NULL DEFAULT TRUE,
+    "started_at" BIGINT NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT NOW(),
+    "updated_at" TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- The coordinator runs a single session at a time.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_via_signing_sessions_active ON via_signing_sessions (is_active)
+WHERE
+    is_active;
+
+CREATE TABLE IF NOT EXISTS via_signing_session_nonces (
+    "session_id" BIGINT NOT NULL REFERENCES via_signing_sessions (id) ON DELETE CASCADE,
+    "input_index" BIGINT NOT NULL,
+    "signer_index" BIGINT NOT NULL,
+    "pub_nonce" BYTEA NOT NULL,
+    "messages_hash" BYTEA NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY ("session_id", "input_index", "signer_index")
+);
+
+-- A public nonce is bound to the messages it was submitted for.
+CREATE INDEX IF NOT EXISTS idx_via_signing_session_nonces_pub_nonce ON via_signing_session_nonces (pub_nonce);
+
+CREATE TABLE IF NOT EXISTS via_signing_session_partial_sigs (
+    "session_id" BIGINT NOT NULL REFERENCES via_signing_sessions (id) ON DELETE CASCADE,
+    "input_index" BIGINT NOT NULL,
+    "signer_index" BIGINT NOT NULL,
+    "partial_sig" BYTEA NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY ("session_id", "input_index", "signer_index")
+);
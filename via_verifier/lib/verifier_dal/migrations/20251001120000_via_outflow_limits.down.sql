@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS via_withdrawal_deferrals;
+DROP TABLE IF EXISTS via_bridge_outflows;
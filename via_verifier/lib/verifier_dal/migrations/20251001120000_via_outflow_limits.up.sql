@@ -0,0 +1,28 @@
+-- Bridge outflows approved by the verifier network, summed over the rolling window of the outflow limits.
+CREATE TABLE IF NOT EXISTS via_bridge_outflows (
+    "id" BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    "l1_batch_number" BIGINT NOT NULL,
+    "bridge_tx_index" BIGINT NOT NULL,
+    "script_pubkey" BYTEA NOT NULL,
+    "amount" BIGINT NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_bridge_outflow UNIQUE ("l1_batch_number", "bridge_tx_index", "script_pubkey")
+);
+
+CREATE INDEX IF NOT EXISTS idx_via_bridge_outflows_created_at ON via_bridge_outflows (created_at);
+CREATE INDEX IF NOT EXISTS idx_via_bridge_outflows_script_pubkey ON via_bridge_outflows (script_pubkey, created_at);
+
+-- Withdrawal sessions held back because they exceed the outflow limits.
+CREATE TABLE IF NOT EXISTS via_withdrawal_deferrals (
+    "l1_batch_number" BIGINT NOT NULL,
+    "bridge_tx_index" BIGINT NOT NULL,
+    "amount" BIGINT NOT NULL DEFAULT 0,
+    "reason" VARCHAR,
+    "status" VARCHAR NOT NULL DEFAULT 'Deferred',
+    "override_signature" VARCHAR,
+    "created_at" TIMESTAMP NOT NULL DEFAULT NOW(),
+    "updated_at" TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY ("l1_batch_number", "bridge_tx_index")
+);
+
+CREATE INDEX IF NOT EXISTS idx_via_withdrawal_deferrals_status ON via_withdrawal_deferrals (status);
@@ -0,0 +1 @@
+ALTER TABLE via_votable_transactions DROP COLUMN IF EXISTS l1_batch_commitment;
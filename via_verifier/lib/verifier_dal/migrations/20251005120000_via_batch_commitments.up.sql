@@ -0,0 +1,2 @@
+-- Commitment of the L1 batch recomputed by the verifier, used as the previous commitment of the next batch.
+ALTER TABLE via_votable_transactions ADD COLUMN IF NOT EXISTS l1_batch_commitment BYTEA;
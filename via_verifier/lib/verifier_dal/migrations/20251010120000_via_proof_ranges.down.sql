@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_via_votable_transactions_proof_reveal_tx_id;
+ALTER TABLE via_votable_transactions ADD CONSTRAINT via_votable_transactions_proof_blob_id_key UNIQUE (proof_blob_id);
+ALTER TABLE via_votable_transactions ADD CONSTRAINT via_votable_transactions_proof_reveal_tx_id_key UNIQUE (proof_reveal_tx_id);
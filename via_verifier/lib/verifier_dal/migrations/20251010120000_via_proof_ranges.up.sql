@@ -0,0 +1,4 @@
+-- A proof can cover a range of L1 batches, each of them referencing the same proof inscription and blob.
+ALTER TABLE via_votable_transactions DROP CONSTRAINT IF EXISTS via_votable_transactions_proof_blob_id_key;
+ALTER TABLE via_votable_transactions DROP CONSTRAINT IF EXISTS via_votable_transactions_proof_reveal_tx_id_key;
+CREATE INDEX IF NOT EXISTS idx_via_votable_transactions_proof_reveal_tx_id ON via_votable_transactions (proof_reveal_tx_id);
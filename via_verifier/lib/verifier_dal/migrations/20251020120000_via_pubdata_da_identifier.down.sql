@@ -0,0 +1 @@
+ALTER TABLE via_votable_transactions DROP COLUMN IF EXISTS pubdata_da_identifier;
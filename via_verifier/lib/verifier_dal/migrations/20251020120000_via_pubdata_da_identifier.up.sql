@@ -0,0 +1,5 @@
+-- The pubdata blob is fetched from the DA layer of the L1 batch inscription, which can differ from
+-- the one of the proof inscription when the DA layers are switched between them.
+ALTER TABLE via_votable_transactions ADD COLUMN IF NOT EXISTS pubdata_da_identifier VARCHAR;
+UPDATE via_votable_transactions SET pubdata_da_identifier = da_identifier;
+ALTER TABLE via_votable_transactions ALTER COLUMN pubdata_da_identifier SET NOT NULL;
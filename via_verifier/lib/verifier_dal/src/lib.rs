@@ -16,7 +16,9 @@ pub use zksync_db_connection::{
 
 use crate::{
     via_blocks_dal::ViaBlocksDal, via_btc_sender_dal::ViaBtcSenderDal,
-    via_indexer_dal::ViaIndexerDal, via_votes_dal::ViaVotesDal, via_wallet_dal::ViaWalletDal,
+    via_indexer_dal::ViaIndexerDal, via_outflows_dal::ViaOutflowsDal,
+    via_refunds_dal::ViaRefundsDal, via_signing_sessions_dal::ViaSigningSessionsDal,
+    via_votes_dal::ViaVotesDal, via_wallet_dal::ViaWalletDal,
 };
 
 pub mod models;
@@ -24,7 +26,10 @@ pub mod via_blocks_dal;
 pub mod via_bridge;
 pub mod via_btc_sender_dal;
 pub mod via_indexer_dal;
+pub mod via_outflows_dal;
 pub mod via_protocol_versions_dal;
+pub mod via_refunds_dal;
+pub mod via_signing_sessions_dal;
 pub mod via_transactions_dal;
 pub mod via_votes_dal;
 pub mod via_wallet_dal;
@@ -51,6 +56,9 @@ where
     fn via_indexer_dal(&mut self) -> ViaIndexerDal<'_, 'a>;
     fn via_bridge_dal(&mut self) -> ViaBridgeDal<'_, 'a>;
     fn via_wallet_dal(&mut self) -> ViaWalletDal<'_, 'a>;
+    fn via_refunds_dal(&mut self) -> ViaRefundsDal<'_, 'a>;
+    fn via_signing_sessions_dal(&mut self) -> ViaSigningSessionsDal<'_, 'a>;
+    fn via_outflows_dal(&mut self) -> ViaOutflowsDal<'_, 'a>;
 }
 
 #[derive(Clone, Debug)]
@@ -93,4 +101,16 @@ impl<'a> VerifierDal<'a> for Connection<'a, Verifier> {
     fn via_wallet_dal(&mut self) -> ViaWalletDal<'_, 'a> {
         ViaWalletDal { storage: self }
     }
+
+    fn via_refunds_dal(&mut self) -> ViaRefundsDal<'_, 'a> {
+        ViaRefundsDal { storage: self }
+    }
+
+    fn via_signing_sessions_dal(&mut self) -> ViaSigningSessionsDal<'_, 'a> {
+        ViaSigningSessionsDal { storage: self }
+    }
+
+    fn via_outflows_dal(&mut self) -> ViaOutflowsDal<'_, 'a> {
+        ViaOutflowsDal { storage: self }
+    }
 }
@@ -1,2 +1,5 @@
 pub mod storage_btc_inscription_request;
+pub mod storage_refund;
+pub mod storage_signing_session;
 pub mod storage_vote;
+pub mod storage_withdrawal_deferral;
@@ -0,0 +1,43 @@
+use sqlx::types::chrono::NaiveDateTime;
+use strum::{Display, EnumString};
+use zksync_types::H256;
+
+/// Lifecycle of the refund of a deposit that can't be credited on L2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+pub enum RefundStatus {
+    /// Waiting for the verifier network to sign the refund transaction.
+    Pending,
+    /// The refund transaction was broadcast, waiting for it to be indexed.
+    Broadcast,
+    /// The refund transaction was indexed, the BTC is back to the sender.
+    Refunded,
+    /// The deposit can't be refunded, either the sender is unknown or the amount doesn't cover
+    /// the refund fee.
+    Unrefundable,
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageRefund {
+    pub id: i64,
+    pub deposit_tx_id: Vec<u8>,
+    pub deposit_vout: i64,
+    pub l1_block_number: i64,
+    pub sender: Option<String>,
+    pub amount: i64,
+    pub reason: String,
+    pub status: String,
+    pub data: Option<Vec<u8>>,
+    pub refund_tx_id: Option<Vec<u8>>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl StorageRefund {
+    pub fn deposit_tx_id(&self) -> H256 {
+        H256::from_slice(&self.deposit_tx_id)
+    }
+
+    pub fn status(&self) -> RefundStatus {
+        self.status.parse().unwrap_or(RefundStatus::Pending)
+    }
+}
@@ -0,0 +1,28 @@
+use sqlx::types::chrono::NaiveDateTime;
+
+#[derive(Debug, Clone)]
+pub struct StorageSigningSession {
+    pub id: i64,
+    pub session_type: String,
+    pub session_op: Vec<u8>,
+    pub messages_hash: Vec<u8>,
+    pub is_active: bool,
+    /// Unix timestamp the session was started at, used for the session timeout.
+    pub started_at: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageSessionNonce {
+    pub input_index: i64,
+    pub signer_index: i64,
+    pub pub_nonce: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageSessionPartialSignature {
+    pub input_index: i64,
+    pub signer_index: i64,
+    pub partial_sig: Vec<u8>,
+}
@@ -9,3 +9,15 @@ pub struct CanonicalChainStatus {
     pub total_transactions_in_db: i64,
     pub has_genesis: bool,
 }
+
+/// A votable transaction along with the verification result of the verifier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VotableTransactionStatus {
+    pub id: i64,
+    pub l1_batch_number: i64,
+    pub l1_batch_hash: Vec<u8>,
+    pub proof_reveal_tx_id: Vec<u8>,
+    /// Whether the verifier accepted the batch proof, `None` while not verified.
+    pub l1_batch_status: Option<bool>,
+    pub is_finalized: Option<bool>,
+}
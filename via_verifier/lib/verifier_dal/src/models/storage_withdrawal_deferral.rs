@@ -0,0 +1,31 @@
+use sqlx::types::chrono::NaiveDateTime;
+use strum::{Display, EnumString};
+
+/// Lifecycle of a withdrawal session held back by the bridge outflow limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+pub enum DeferralStatus {
+    /// The session exceeds the outflow limits, it is retried in a later session.
+    Deferred,
+    /// The session fits in the outflow limits again.
+    Released,
+    /// The governance approved the session above the outflow limits.
+    Overridden,
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageWithdrawalDeferral {
+    pub l1_batch_number: i64,
+    pub bridge_tx_index: i64,
+    pub amount: i64,
+    pub reason: Option<String>,
+    pub status: String,
+    pub override_signature: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl StorageWithdrawalDeferral {
+    pub fn status(&self) -> DeferralStatus {
+        self.status.parse().unwrap_or(DeferralStatus::Deferred)
+    }
+}
@@ -1,8 +1,13 @@
 use rand::random;
 use zksync_db_connection::{connection::Connection, connection_pool::ConnectionPool};
-use zksync_types::H256;
+use zksync_types::{
+    via_verifier_btc_inscription_operations::ViaVerifierBtcInscriptionRequestType, H256,
+};
 
-use crate::{Verifier, VerifierDal};
+use crate::{
+    models::{storage_refund::RefundStatus, storage_withdrawal_deferral::DeferralStatus},
+    Verifier, VerifierDal,
+};
 
 // Helper functions for testing
 async fn create_test_connection() -> Connection<'static, Verifier> {
@@ -37,7 +42,9 @@ async fn test_via_vote_workflow() {
             proof_reveal_tx_id,
             "test_blob_id".to_string(),
             "test_pubdata_tx_id".to_string(),
+            "test_da_id".to_string(),
             "test_pubdata_blob_id".to_string(),
+            0,
         )
         .await
         .unwrap();
@@ -46,7 +53,7 @@ async fn test_via_vote_workflow() {
     // Test inserting a vote
     storage
         .via_votes_dal()
-        .insert_vote(votable_transaction_id, &verifier_address, vote)
+        .insert_vote(votable_transaction_id, &verifier_address, vote, 0)
         .await
         .unwrap();
     storage
@@ -99,7 +106,9 @@ async fn test_get_first_not_verified_l1_batch_in_canonical_inscription_chain() {
                 proof_reveal_tx_id,
                 format!("test_blob_id_{i}").to_string(),
                 format!("test_pubdata_tx_id_{i}").to_string(),
+                "test_da_id".to_string(),
                 format!("test_pubdata_blob_id_{i}").to_string(),
+                0,
             )
             .await
             .unwrap();
@@ -111,7 +120,7 @@ async fn test_get_first_not_verified_l1_batch_in_canonical_inscription_chain() {
 
         storage
             .via_votes_dal()
-            .insert_vote(votable_transaction_id, &verifier_address, true)
+            .insert_vote(votable_transaction_id, &verifier_address, true, 0)
             .await
             .unwrap();
         storage
@@ -165,7 +174,9 @@ async fn test_get_first_not_verified_l1_batch_in_canonical_inscription_chain_whe
                 proof_reveal_tx_id,
                 format!("test_blob_id_{i}").to_string(),
                 format!("test_pubdata_tx_id_{i}").to_string(),
+                "test_da_id".to_string(),
                 format!("test_pubdata_blob_id_{i}").to_string(),
+                0,
             )
             .await
             .unwrap();
@@ -191,7 +202,7 @@ async fn test_get_first_not_verified_l1_batch_in_canonical_inscription_chain_whe
 
         storage
             .via_votes_dal()
-            .insert_vote(votable_transaction_id, &verifier_address, vote)
+            .insert_vote(votable_transaction_id, &verifier_address, vote, 0)
             .await
             .unwrap();
         storage
@@ -248,7 +259,9 @@ async fn test_get_first_not_verified_l1_batch_in_canonical_inscription_chain_whe
                 proof_reveal_tx_id,
                 format!("test_blob_id_{i}_fix").to_string(),
                 format!("test_pubdata_tx_id_{i}_fix").to_string(),
+                "test_da_id".to_string(),
                 format!("test_pubdata_blob_id_{i}_fix").to_string(),
+                0,
             )
             .await
             .unwrap();
@@ -261,7 +274,7 @@ async fn test_get_first_not_verified_l1_batch_in_canonical_inscription_chain_whe
 
         storage
             .via_votes_dal()
-            .insert_vote(votable_transaction_id, &verifier_address, vote)
+            .insert_vote(votable_transaction_id, &verifier_address, vote, 0)
             .await
             .unwrap();
 
@@ -310,3 +323,767 @@ async fn test_get_first_not_verified_l1_batch_in_canonical_inscription_chain_whe
         .unwrap();
     assert!(rejected_l1_batch.is_none());
 }
+
+#[tokio::test]
+async fn test_delete_indexed_data_after_l1_block() {
+    let mut storage = create_test_connection().await;
+    let verifier_address = "0x1234567890123456789012345678901234567890".to_string();
+
+    // Batch 1 is indexed from L1 block 10 and batch 2 from the L1 block 20, which gets orphaned.
+    let mut proof_reveal_tx_ids = vec![];
+    for (l1_batch_number, l1_block_number) in [(1, 10), (2, 20)] {
+        let proof_reveal_tx_id = H256::random();
+        storage
+            .via_votes_dal()
+            .insert_votable_transaction(
+                l1_batch_number,
+                H256::random(),
+                H256::random(),
+                "test_da_id".to_string(),
+                proof_reveal_tx_id,
+                format!("test_blob_id_{l1_batch_number}"),
+                format!("test_pubdata_tx_id_{l1_batch_number}"),
+                "test_da_id".to_string(),
+                format!("test_pubdata_blob_id_{l1_batch_number}"),
+                l1_block_number,
+            )
+            .await
+            .unwrap();
+        proof_reveal_tx_ids.push(proof_reveal_tx_id);
+    }
+    let votable_transaction_id = storage
+        .via_votes_dal()
+        .get_votable_transaction_id(proof_reveal_tx_ids[0].as_bytes(), 1)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The vote for batch 1 is indexed from the orphaned block as well.
+    storage
+        .via_votes_dal()
+        .insert_vote(votable_transaction_id, &verifier_address, true, 20)
+        .await
+        .unwrap();
+    storage
+        .via_votes_dal()
+        .verify_votable_transaction(1, proof_reveal_tx_ids[0], true)
+        .await
+        .unwrap();
+    assert!(storage
+        .via_votes_dal()
+        .finalize_transaction_if_needed(votable_transaction_id, 1.0, 1)
+        .await
+        .unwrap());
+
+    storage
+        .via_indexer_dal()
+        .insert_l1_block_hashes("test", &[(10, vec![1; 32]), (20, vec![2; 32])])
+        .await
+        .unwrap();
+
+    assert!(!storage
+        .via_votes_dal()
+        .has_withdrawals_after_l1_block(15)
+        .await
+        .unwrap());
+
+    storage
+        .via_votes_dal()
+        .delete_votes_after_l1_block(15)
+        .await
+        .unwrap();
+    storage
+        .via_votes_dal()
+        .delete_votable_transactions_after_l1_block(15)
+        .await
+        .unwrap();
+    storage
+        .via_indexer_dal()
+        .delete_l1_block_hashes_after("test", 15)
+        .await
+        .unwrap();
+
+    let (_, _, total_votes) = storage
+        .via_votes_dal()
+        .get_vote_count(votable_transaction_id)
+        .await
+        .unwrap();
+    assert_eq!(total_votes, 0);
+    assert_eq!(
+        storage
+            .via_votes_dal()
+            .get_last_finalized_l1_batch()
+            .await
+            .unwrap(),
+        None
+    );
+    assert!(storage
+        .via_votes_dal()
+        .get_votable_transaction_id(proof_reveal_tx_ids[1].as_bytes(), 2)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        storage
+            .via_indexer_dal()
+            .get_l1_block_hash("test", 10)
+            .await
+            .unwrap(),
+        Some(vec![1; 32])
+    );
+    assert!(storage
+        .via_indexer_dal()
+        .get_l1_block_hash("test", 20)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_refund_workflow() {
+    let mut storage = create_test_connection().await;
+    let deposit_tx_id = H256::random();
+    let unknown_sender_tx_id = H256::random();
+
+    storage
+        .via_refunds_dal()
+        .insert_refund(
+            deposit_tx_id,
+            0,
+            10,
+            Some("bcrt1qw2mvkvm6alfhe86yf328kgvr7mupdx4vln7kpv".to_string()),
+            10_000,
+            "invalid L2 receiver address",
+        )
+        .await
+        .unwrap();
+    // Re-indexing the same deposit doesn't record a second refund.
+    storage
+        .via_refunds_dal()
+        .insert_refund(deposit_tx_id, 0, 10, None, 10_000, "duplicate")
+        .await
+        .unwrap();
+    storage
+        .via_refunds_dal()
+        .insert_refund(unknown_sender_tx_id, 1, 20, None, 5_000, "missing sender")
+        .await
+        .unwrap();
+
+    let refund = storage
+        .via_refunds_dal()
+        .get_next_pending_refund()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(refund.deposit_tx_id(), deposit_tx_id);
+    assert_eq!(refund.status(), RefundStatus::Pending);
+    assert_eq!(refund.reason, "invalid L2 receiver address");
+
+    let unknown_sender = storage
+        .via_refunds_dal()
+        .get_refunds_by_deposit(unknown_sender_tx_id)
+        .await
+        .unwrap();
+    assert_eq!(unknown_sender.len(), 1);
+    assert_eq!(unknown_sender[0].status(), RefundStatus::Unrefundable);
+
+    storage
+        .via_refunds_dal()
+        .set_refund_unsigned_tx(refund.id, &[1, 2, 3])
+        .await
+        .unwrap();
+    storage
+        .via_refunds_dal()
+        .update_refund_status(refund.id, RefundStatus::Broadcast, Some(&[4; 32]))
+        .await
+        .unwrap();
+    let broadcast = storage
+        .via_refunds_dal()
+        .list_broadcast_refunds()
+        .await
+        .unwrap();
+    assert_eq!(broadcast.len(), 1);
+    assert_eq!(broadcast[0].id, refund.id);
+    assert!(storage
+        .via_refunds_dal()
+        .get_next_pending_refund()
+        .await
+        .unwrap()
+        .is_none());
+    assert!(storage
+        .via_refunds_dal()
+        .has_processed_refunds_after_l1_block(5)
+        .await
+        .unwrap());
+
+    let updated = storage
+        .via_refunds_dal()
+        .mark_refunded(deposit_tx_id, &[4; 32])
+        .await
+        .unwrap();
+    assert_eq!(updated, 1);
+
+    let refund = storage
+        .via_refunds_dal()
+        .get_refund(refund.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(refund.status(), RefundStatus::Refunded);
+    assert_eq!(refund.data, Some(vec![1, 2, 3]));
+    assert_eq!(refund.refund_tx_id, Some(vec![4; 32]));
+
+    assert_eq!(
+        storage
+            .via_refunds_dal()
+            .delete_refunds_after_l1_block(15)
+            .await
+            .unwrap(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_signing_session_workflow() {
+    let mut storage = create_test_connection().await;
+    let messages_hash = [1u8; 32];
+    let other_messages_hash = [2u8; 32];
+
+    assert!(storage
+        .via_signing_sessions_dal()
+        .get_active_session()
+        .await
+        .unwrap()
+        .is_none());
+
+    let session_id = storage
+        .via_signing_sessions_dal()
+        .start_session("Withdrawal", &[1, 2, 3], &messages_hash, 100)
+        .await
+        .unwrap();
+
+    storage
+        .via_signing_sessions_dal()
+        .insert_nonce(session_id, 0, 0, &[10; 66], &messages_hash)
+        .await
+        .unwrap();
+    // The first nonce submitted for an input is kept.
+    storage
+        .via_signing_sessions_dal()
+        .insert_nonce(session_id, 0, 0, &[11; 66], &messages_hash)
+        .await
+        .unwrap();
+    storage
+        .via_signing_sessions_dal()
+        .insert_partial_signature(session_id, 0, 0, &[20; 32])
+        .await
+        .unwrap();
+
+    let session = storage
+        .via_signing_sessions_dal()
+        .get_active_session()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(session.id, session_id);
+    assert_eq!(session.session_op, vec![1, 2, 3]);
+    assert_eq!(session.started_at, 100);
+
+    let nonces = storage
+        .via_signing_sessions_dal()
+        .get_session_nonces(session_id)
+        .await
+        .unwrap();
+    assert_eq!(nonces.len(), 1);
+    assert_eq!(nonces[0].pub_nonce, vec![10; 66]);
+
+    let signatures = storage
+        .via_signing_sessions_dal()
+        .get_session_partial_signatures(session_id)
+        .await
+        .unwrap();
+    assert_eq!(signatures.len(), 1);
+    assert_eq!(signatures[0].partial_sig, vec![20; 32]);
+
+    assert!(!storage
+        .via_signing_sessions_dal()
+        .is_nonce_used_in_other_session(&[10; 66], session_id)
+        .await
+        .unwrap());
+
+    // Starting a new session closes the previous one.
+    let next_session_id = storage
+        .via_signing_sessions_dal()
+        .start_session("Refund", &[4, 5, 6], &other_messages_hash, 200)
+        .await
+        .unwrap();
+    assert!(storage
+        .via_signing_sessions_dal()
+        .is_nonce_used_in_other_session(&[10; 66], next_session_id)
+        .await
+        .unwrap());
+    let session = storage
+        .via_signing_sessions_dal()
+        .get_active_session()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(session.id, next_session_id);
+    assert!(storage
+        .via_signing_sessions_dal()
+        .get_session_nonces(next_session_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    storage
+        .via_signing_sessions_dal()
+        .close_active_session()
+        .await
+        .unwrap();
+    assert!(storage
+        .via_signing_sessions_dal()
+        .get_active_session()
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_outflows_workflow() {
+    let mut storage = create_test_connection().await;
+    let alice = vec![0x51, 0x20, 1];
+    let bob = vec![0x51, 0x20, 2];
+
+    // The window is anchored to the L1 blocks the finalized L1 batches were inscribed in.
+    for (l1_batch_number, l1_block_number) in [(1, 100), (2, 150), (3, 300)] {
+        let proof_reveal_tx_id = H256::random();
+        storage
+            .via_votes_dal()
+            .insert_votable_transaction(
+                l1_batch_number,
+                H256::random(),
+                H256::random(),
+                "test_da_id".to_string(),
+                proof_reveal_tx_id,
+                format!("test_blob_id_{l1_batch_number}"),
+                format!("test_pubdata_tx_id_{l1_batch_number}"),
+                "test_da_id".to_string(),
+                format!("test_pubdata_blob_id_{l1_batch_number}"),
+                l1_block_number,
+            )
+            .await
+            .unwrap();
+        let votable_transaction_id = storage
+            .via_votes_dal()
+            .verify_votable_transaction(i64::from(l1_batch_number), proof_reveal_tx_id, true)
+            .await
+            .unwrap();
+        storage
+            .via_votes_dal()
+            .insert_vote(votable_transaction_id, "verifier", true, l1_block_number)
+            .await
+            .unwrap();
+        storage
+            .via_votes_dal()
+            .finalize_transaction_if_needed(votable_transaction_id, 1.0, 1)
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        storage
+            .via_votes_dal()
+            .get_finalized_l1_block_number(3)
+            .await
+            .unwrap(),
+        Some(300)
+    );
+    assert_eq!(
+        storage
+            .via_votes_dal()
+            .get_finalized_l1_block_number(4)
+            .await
+            .unwrap(),
+        None
+    );
+
+    storage
+        .via_outflows_dal()
+        .insert_outflows(1, 0, &[(alice.clone(), 1000), (bob.clone(), 500)])
+        .await
+        .unwrap();
+    // Recording the same bridge transaction again doesn't count it twice.
+    storage
+        .via_outflows_dal()
+        .insert_outflows(1, 0, &[(alice.clone(), 1000)])
+        .await
+        .unwrap();
+    storage
+        .via_outflows_dal()
+        .insert_outflows(2, 0, &[(alice.clone(), 200)])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        storage
+            .via_outflows_dal()
+            .get_window_outflow(0..=300, 3, 0)
+            .await
+            .unwrap(),
+        1700
+    );
+    // The bridge transaction being checked is left out.
+    assert_eq!(
+        storage
+            .via_outflows_dal()
+            .get_window_outflow(0..=150, 2, 0)
+            .await
+            .unwrap(),
+        1500
+    );
+    // The L1 batches inscribed before the window are left out.
+    assert_eq!(
+        storage
+            .via_outflows_dal()
+            .get_window_outflow(101..=300, 3, 0)
+            .await
+            .unwrap(),
+        200
+    );
+
+    let mut by_address = storage
+        .via_outflows_dal()
+        .get_window_outflows_by_address(0..=300, 3, 0, &[alice.clone()])
+        .await
+        .unwrap();
+    by_address.sort();
+    assert_eq!(by_address, vec![(alice.clone(), 1200)]);
+    let by_address = storage
+        .via_outflows_dal()
+        .get_window_outflows_by_address(101..=300, 3, 0, &[alice.clone(), bob])
+        .await
+        .unwrap();
+    assert_eq!(by_address, vec![(alice, 200)]);
+
+    assert!(storage
+        .via_outflows_dal()
+        .get_deferral(3, 0)
+        .await
+        .unwrap()
+        .is_none());
+    storage
+        .via_outflows_dal()
+        .defer_withdrawal(3, 0, 5000, "window limit exceeded")
+        .await
+        .unwrap();
+    let deferral = storage
+        .via_outflows_dal()
+        .get_deferral(3, 0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(deferral.status(), DeferralStatus::Deferred);
+    assert_eq!(deferral.amount, 5000);
+
+    storage
+        .via_outflows_dal()
+        .release_withdrawal(3, 0)
+        .await
+        .unwrap();
+    let deferral = storage
+        .via_outflows_dal()
+        .get_deferral(3, 0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(deferral.status(), DeferralStatus::Released);
+
+    // A governance override is recorded even when the verifier didn't defer the session itself.
+    storage
+        .via_outflows_dal()
+        .set_override_signature(4, 1, "signature")
+        .await
+        .unwrap();
+    let deferral = storage
+        .via_outflows_dal()
+        .get_deferral(4, 1)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(deferral.status(), DeferralStatus::Overridden);
+    assert_eq!(deferral.override_signature.as_deref(), Some("signature"));
+}
+
+#[tokio::test]
+async fn test_verifier_status_workflow() {
+    let mut storage = create_test_connection().await;
+
+    let mut prev_l1_batch_hash = H256::random();
+    for l1_batch_number in 1..=3 {
+        let l1_batch_hash = H256::random();
+        storage
+            .via_votes_dal()
+            .insert_votable_transaction(
+                l1_batch_number,
+                l1_batch_hash,
+                prev_l1_batch_hash,
+                "test_da_id".to_string(),
+                H256::from_low_u64_be(l1_batch_number.into()),
+                format!("test_blob_id_{l1_batch_number}"),
+                format!("test_pubdata_tx_id_{l1_batch_number}"),
+                "test_da_id".to_string(),
+                format!("test_pubdata_blob_id_{l1_batch_number}"),
+                0,
+            )
+            .await
+            .unwrap();
+        prev_l1_batch_hash = l1_batch_hash;
+    }
+    storage
+        .via_votes_dal()
+        .verify_votable_transaction(1, H256::from_low_u64_be(1), true)
+        .await
+        .unwrap();
+
+    let transactions = storage
+        .via_votes_dal()
+        .list_votable_transactions(2, 10)
+        .await
+        .unwrap();
+    assert_eq!(
+        transactions
+            .iter()
+            .map(|tx| (tx.l1_batch_number, tx.l1_batch_status))
+            .collect::<Vec<_>>(),
+        vec![(2, None), (1, Some(true))]
+    );
+
+    let inscription_request = storage
+        .via_btc_sender_dal()
+        .via_save_btc_inscriptions_request("VoteOnchain".to_string(), vec![1], 100)
+        .await
+        .unwrap();
+    storage
+        .via_block_dal()
+        .insert_vote_l1_batch_inscription_request_id(
+            transactions[1].id,
+            inscription_request.id,
+            ViaVerifierBtcInscriptionRequestType::VoteOnchain,
+        )
+        .await
+        .unwrap();
+    storage
+        .via_btc_sender_dal()
+        .insert_inscription_request_history(
+            "commit_tx_id".to_string(),
+            "reveal_tx_id".to_string(),
+            inscription_request.id,
+            vec![],
+            vec![],
+            100,
+            10,
+        )
+        .await
+        .unwrap();
+
+    let vote_inscriptions = storage
+        .via_btc_sender_dal()
+        .get_vote_inscriptions(&[transactions[0].id, transactions[1].id])
+        .await
+        .unwrap();
+    assert_eq!(
+        vote_inscriptions,
+        vec![(transactions[1].id, "reveal_tx_id".to_string(), false)]
+    );
+}
+
+#[tokio::test]
+async fn test_l1_batch_commitment() {
+    let mut storage = create_test_connection().await;
+
+    let l1_batch_hash = H256::random();
+    let proof_reveal_tx_id = H256::random();
+    storage
+        .via_votes_dal()
+        .insert_votable_transaction(
+            1,
+            l1_batch_hash,
+            H256::random(),
+            "test_da_id".to_string(),
+            proof_reveal_tx_id,
+            "test_blob_id".to_string(),
+            "test_pubdata_tx_id".to_string(),
+            "test_da_id".to_string(),
+            "test_pubdata_blob_id".to_string(),
+            0,
+        )
+        .await
+        .unwrap();
+
+    // Not verified yet.
+    let commitment = storage
+        .via_votes_dal()
+        .get_verified_l1_batch_commitment(l1_batch_hash)
+        .await
+        .unwrap();
+    assert_eq!(commitment, None);
+
+    storage
+        .via_votes_dal()
+        .verify_votable_transaction(1, proof_reveal_tx_id, true)
+        .await
+        .unwrap();
+    let commitment = storage
+        .via_votes_dal()
+        .get_verified_l1_batch_commitment(l1_batch_hash)
+        .await
+        .unwrap();
+    assert_eq!(commitment, Some(None));
+
+    let l1_batch_commitment = H256::random();
+    storage
+        .via_votes_dal()
+        .set_l1_batch_commitment(1, proof_reveal_tx_id, l1_batch_commitment)
+        .await
+        .unwrap();
+    let commitment = storage
+        .via_votes_dal()
+        .get_verified_l1_batch_commitment(l1_batch_hash)
+        .await
+        .unwrap();
+    assert_eq!(commitment, Some(Some(l1_batch_commitment)));
+}
+
+#[tokio::test]
+async fn test_proof_of_l1_batch_range() {
+    let mut storage = create_test_connection().await;
+
+    // Batches 1 and 2 are proven by the same proof inscription and blob.
+    let proof_reveal_tx_id = H256::random();
+    for l1_batch_number in [1, 2] {
+        storage
+            .via_votes_dal()
+            .insert_votable_transaction(
+                l1_batch_number,
+                H256::random(),
+                H256::random(),
+                "test_da_id".to_string(),
+                proof_reveal_tx_id,
+                "test_blob_id".to_string(),
+                format!("test_pubdata_tx_id_{l1_batch_number}"),
+                "test_da_id".to_string(),
+                format!("test_pubdata_blob_id_{l1_batch_number}"),
+                0,
+            )
+            .await
+            .unwrap();
+    }
+
+    let votable_transaction_ids = storage
+        .via_votes_dal()
+        .get_votable_transaction_ids(proof_reveal_tx_id.as_bytes())
+        .await
+        .unwrap();
+    assert_eq!(votable_transaction_ids.len(), 2);
+    assert_eq!(votable_transaction_ids[0].1, 1);
+    assert_eq!(votable_transaction_ids[1].1, 2);
+
+    for (votable_transaction_id, l1_batch_number) in votable_transaction_ids {
+        assert_eq!(
+            storage
+                .via_votes_dal()
+                .get_votable_transaction_id(
+                    proof_reveal_tx_id.as_bytes(),
+                    i64::from(l1_batch_number)
+                )
+                .await
+                .unwrap(),
+            Some(votable_transaction_id)
+        );
+
+        let (id, number, _) = storage
+            .via_votes_dal()
+            .get_vote_transaction_info(proof_reveal_tx_id, Some(l1_batch_number), 0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((id, number), (votable_transaction_id, l1_batch_number));
+    }
+
+    // A withdrawal without L1 batch number resolves to the first batch of the range.
+    let (_, number, _) = storage
+        .via_votes_dal()
+        .get_vote_transaction_info(proof_reveal_tx_id, None, 0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(number, 1);
+}
+
+#[tokio::test]
+async fn test_first_finalized_l1_block_since() {
+    let mut storage = create_test_connection().await;
+    let verifier_address = "0x1234567890123456789012345678901234567890".to_string();
+
+    // Batches 1 and 2 are finalized, batch 3 is not.
+    for (l1_batch_number, l1_block_number) in [(1, 10), (2, 20), (3, 30)] {
+        let proof_reveal_tx_id = H256::random();
+        storage
+            .via_votes_dal()
+            .insert_votable_transaction(
+                l1_batch_number,
+                H256::random(),
+                H256::random(),
+                "test_da_id".to_string(),
+                proof_reveal_tx_id,
+                format!("test_blob_id_{l1_batch_number}"),
+                format!("test_pubdata_tx_id_{l1_batch_number}"),
+                "test_da_id".to_string(),
+                format!("test_pubdata_blob_id_{l1_batch_number}"),
+                l1_block_number,
+            )
+            .await
+            .unwrap();
+        if l1_batch_number == 3 {
+            continue;
+        }
+
+        let votable_transaction_id = storage
+            .via_votes_dal()
+            .get_votable_transaction_id(proof_reveal_tx_id.as_bytes(), i64::from(l1_batch_number))
+            .await
+            .unwrap()
+            .unwrap();
+        storage
+            .via_votes_dal()
+            .insert_vote(
+                votable_transaction_id,
+                &verifier_address,
+                true,
+                l1_block_number,
+            )
+            .await
+            .unwrap();
+        storage
+            .via_votes_dal()
+            .verify_votable_transaction(i64::from(l1_batch_number), proof_reveal_tx_id, true)
+            .await
+            .unwrap();
+        assert!(storage
+            .via_votes_dal()
+            .finalize_transaction_if_needed(votable_transaction_id, 1.0, 1)
+            .await
+            .unwrap());
+    }
+
+    for (l1_batch_number, expected) in [(0, Some(10)), (2, Some(20)), (3, None)] {
+        assert_eq!(
+            storage
+                .via_votes_dal()
+                .get_first_finalized_l1_block_since(l1_batch_number)
+                .await
+                .unwrap(),
+            expected
+        );
+    }
+}
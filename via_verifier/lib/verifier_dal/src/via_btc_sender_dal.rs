@@ -170,6 +170,52 @@ impl ViaBtcSenderDal<'_, '_> {
         Ok(inscription_request_history.map(ViaBtcInscriptionRequestHistory::from))
     }
 
+    /// Returns the last vote inscription of each of `votable_transaction_ids`, as the reveal tx id
+    /// and whether it's confirmed.
+    pub async fn get_vote_inscriptions(
+        &mut self,
+        votable_transaction_ids: &[i64],
+    ) -> sqlx::Result<Vec<(i64, String, bool)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                via_l1_batch_vote_inscription_request.votable_transaction_id,
+                via_btc_inscriptions_request_history.reveal_tx_id,
+                via_btc_inscriptions_request_history.confirmed_at IS NOT NULL AS "is_confirmed!"
+            FROM
+                via_l1_batch_vote_inscription_request
+                JOIN via_btc_inscriptions_request_history ON via_btc_inscriptions_request_history.id = (
+                    SELECT
+                        id
+                    FROM
+                        via_btc_inscriptions_request_history
+                    WHERE
+                        inscription_request_id = via_l1_batch_vote_inscription_request.vote_l1_batch_inscription_id
+                    ORDER BY
+                        id DESC
+                    LIMIT
+                        1
+                )
+            WHERE
+                via_l1_batch_vote_inscription_request.votable_transaction_id = ANY ($1)
+            "#,
+            votable_transaction_ids
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.votable_transaction_id,
+                    row.reveal_tx_id,
+                    row.is_confirmed,
+                )
+            })
+            .collect())
+    }
+
     pub async fn confirm_inscription(
         &mut self,
         inscriptions_request_id: i64,
@@ -72,4 +72,98 @@ impl ViaIndexerDal<'_, '_> {
 
         Ok(record.map(|r| r.last_indexer_l1_block as u64).unwrap_or(0))
     }
+
+    /// Stores the hashes of the processed L1 blocks, overwriting the hashes previously stored at
+    /// the same heights.
+    pub async fn insert_l1_block_hashes(
+        &mut self,
+        module: &str,
+        blocks: &[(u32, Vec<u8>)],
+    ) -> DalResult<()> {
+        let (numbers, hashes): (Vec<i64>, Vec<Vec<u8>>) = blocks
+            .iter()
+            .map(|(number, hash)| (i64::from(*number), hash.clone()))
+            .unzip();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                via_indexer_blocks (module, number, hash)
+            SELECT
+                $1,
+                u.number,
+                u.hash
+            FROM
+                UNNEST($2::BIGINT[], $3::BYTEA[]) AS u (number, hash)
+            ON CONFLICT (module, number) DO
+            UPDATE
+            SET
+                hash = excluded.hash,
+                created_at = NOW()
+            "#,
+            module,
+            &numbers,
+            &hashes,
+        )
+        .instrument("insert_l1_block_hashes")
+        .with_arg("module", &module)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the stored hash of the processed L1 block at `l1_block`, if any.
+    pub async fn get_l1_block_hash(
+        &mut self,
+        module: &str,
+        l1_block: u32,
+    ) -> DalResult<Option<Vec<u8>>> {
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                hash
+            FROM
+                via_indexer_blocks
+            WHERE
+                module = $1
+                AND number = $2
+            "#,
+            module,
+            i64::from(l1_block),
+        )
+        .instrument("get_l1_block_hash")
+        .with_arg("l1_block", &l1_block)
+        .report_latency()
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(record.map(|r| r.hash))
+    }
+
+    /// Removes the stored hashes of the L1 blocks above `l1_block`.
+    pub async fn delete_l1_block_hashes_after(
+        &mut self,
+        module: &str,
+        l1_block: u32,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM via_indexer_blocks
+            WHERE
+                module = $1
+                AND number > $2
+            "#,
+            module,
+            i64::from(l1_block),
+        )
+        .instrument("delete_l1_block_hashes_after")
+        .with_arg("l1_block", &l1_block)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
 }
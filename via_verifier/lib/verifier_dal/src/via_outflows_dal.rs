@@ -0,0 +1,272 @@
+use std::ops::RangeInclusive;
+
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+
+use crate::{
+    models::storage_withdrawal_deferral::{DeferralStatus, StorageWithdrawalDeferral},
+    Verifier,
+};
+
+#[derive(Debug)]
+pub struct ViaOutflowsDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Verifier>,
+}
+
+impl ViaOutflowsDal<'_, '_> {
+    /// Records the outputs of a bridge transaction approved by the verifier. Recording the same
+    /// bridge transaction again is a no-op.
+    pub async fn insert_outflows(
+        &mut self,
+        l1_batch_number: i64,
+        bridge_tx_index: i64,
+        outflows: &[(Vec<u8>, i64)],
+    ) -> DalResult<()> {
+        let mut db_transaction = self.storage.start_transaction().await?;
+
+        for (script_pubkey, amount) in outflows {
+            sqlx::query!(
+                r#"
+                INSERT INTO
+                    via_bridge_outflows (l1_batch_number, bridge_tx_index, script_pubkey, amount)
+                VALUES
+                    ($1, $2, $3, $4)
+                ON CONFLICT (l1_batch_number, bridge_tx_index, script_pubkey) DO NOTHING
+                "#,
+                l1_batch_number,
+                bridge_tx_index,
+                script_pubkey,
+                amount
+            )
+            .instrument("insert_outflows")
+            .with_arg("l1_batch_number", &l1_batch_number)
+            .execute(&mut db_transaction)
+            .await?;
+        }
+
+        db_transaction.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns the total outflow recorded for the finalized L1 batches inscribed in the L1 blocks of
+    /// `window`, the bridge transaction `(l1_batch_number, bridge_tx_index)` left out.
+    pub async fn get_window_outflow(
+        &mut self,
+        window: RangeInclusive<u32>,
+        l1_batch_number: i64,
+        bridge_tx_index: i64,
+    ) -> DalResult<i64> {
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(SUM(amount), 0)::BIGINT AS "total!"
+            FROM
+                via_bridge_outflows
+            WHERE
+                l1_batch_number IN (
+                    SELECT
+                        l1_batch_number
+                    FROM
+                        via_votable_transactions
+                    WHERE
+                        is_finalized = TRUE
+                        AND l1_block_number BETWEEN $1 AND $2
+                )
+                AND NOT (
+                    l1_batch_number = $3
+                    AND bridge_tx_index = $4
+                )
+            "#,
+            i64::from(*window.start()),
+            i64::from(*window.end()),
+            l1_batch_number,
+            bridge_tx_index
+        )
+        .instrument("get_window_outflow")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(record.total)
+    }
+
+    /// Returns the outflow to each of `script_pubkeys` recorded for the finalized L1 batches
+    /// inscribed in the L1 blocks of `window`, the bridge transaction
+    /// `(l1_batch_number, bridge_tx_index)` left out.
+    pub async fn get_window_outflows_by_address(
+        &mut self,
+        window: RangeInclusive<u32>,
+        l1_batch_number: i64,
+        bridge_tx_index: i64,
+        script_pubkeys: &[Vec<u8>],
+    ) -> DalResult<Vec<(Vec<u8>, i64)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                script_pubkey,
+                COALESCE(SUM(amount), 0)::BIGINT AS "total!"
+            FROM
+                via_bridge_outflows
+            WHERE
+                l1_batch_number IN (
+                    SELECT
+                        l1_batch_number
+                    FROM
+                        via_votable_transactions
+                    WHERE
+                        is_finalized = TRUE
+                        AND l1_block_number BETWEEN $1 AND $2
+                )
+                AND NOT (
+                    l1_batch_number = $3
+                    AND bridge_tx_index = $4
+                )
+                AND script_pubkey = ANY ($5)
+            GROUP BY
+                script_pubkey
+            "#,
+            i64::from(*window.start()),
+            i64::from(*window.end()),
+            l1_batch_number,
+            bridge_tx_index,
+            script_pubkeys
+        )
+        .instrument("get_window_outflows_by_address")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.script_pubkey, row.total))
+            .collect())
+    }
+
+    /// Records a withdrawal session held back by the outflow limits.
+    pub async fn defer_withdrawal(
+        &mut self,
+        l1_batch_number: i64,
+        bridge_tx_index: i64,
+        amount: i64,
+        reason: &str,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                via_withdrawal_deferrals (l1_batch_number, bridge_tx_index, amount, reason, status)
+            VALUES
+                ($1, $2, $3, $4, 'Deferred')
+            ON CONFLICT (l1_batch_number, bridge_tx_index) DO
+            UPDATE
+            SET
+                amount = $3,
+                reason = $4,
+                status = 'Deferred',
+                updated_at = NOW()
+            "#,
+            l1_batch_number,
+            bridge_tx_index,
+            amount,
+            reason
+        )
+        .instrument("defer_withdrawal")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks a deferred withdrawal session as released once it fits in the outflow limits.
+    pub async fn release_withdrawal(
+        &mut self,
+        l1_batch_number: i64,
+        bridge_tx_index: i64,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE via_withdrawal_deferrals
+            SET
+                status = 'Released',
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+                AND bridge_tx_index = $2
+                AND status = 'Deferred'
+            "#,
+            l1_batch_number,
+            bridge_tx_index
+        )
+        .instrument("release_withdrawal")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stores the governance signature approving a withdrawal session above the outflow limits.
+    pub async fn set_override_signature(
+        &mut self,
+        l1_batch_number: i64,
+        bridge_tx_index: i64,
+        signature: &str,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                via_withdrawal_deferrals (
+                    l1_batch_number,
+                    bridge_tx_index,
+                    status,
+                    override_signature
+                )
+            VALUES
+                ($1, $2, $3, $4)
+            ON CONFLICT (l1_batch_number, bridge_tx_index) DO
+            UPDATE
+            SET
+                status = $3,
+                override_signature = $4,
+                updated_at = NOW()
+            "#,
+            l1_batch_number,
+            bridge_tx_index,
+            DeferralStatus::Overridden.to_string(),
+            signature
+        )
+        .instrument("set_override_signature")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_deferral(
+        &mut self,
+        l1_batch_number: i64,
+        bridge_tx_index: i64,
+    ) -> DalResult<Option<StorageWithdrawalDeferral>> {
+        let deferral = sqlx::query_as!(
+            StorageWithdrawalDeferral,
+            r#"
+            SELECT
+                *
+            FROM
+                via_withdrawal_deferrals
+            WHERE
+                l1_batch_number = $1
+                AND bridge_tx_index = $2
+            "#,
+            l1_batch_number,
+            bridge_tx_index
+        )
+        .instrument("get_deferral")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(deferral)
+    }
+}
@@ -0,0 +1,309 @@
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::H256;
+
+use crate::{
+    models::storage_refund::{RefundStatus, StorageRefund},
+    Verifier,
+};
+
+#[derive(Debug)]
+pub struct ViaRefundsDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Verifier>,
+}
+
+impl ViaRefundsDal<'_, '_> {
+    /// Records a deposit that can't be credited on L2. A deposit without a known sender is
+    /// recorded as `Unrefundable`.
+    pub async fn insert_refund(
+        &mut self,
+        deposit_tx_id: H256,
+        deposit_vout: i64,
+        l1_block_number: u32,
+        sender: Option<String>,
+        amount: i64,
+        reason: &str,
+    ) -> DalResult<()> {
+        let status = if sender.is_some() {
+            RefundStatus::Pending
+        } else {
+            RefundStatus::Unrefundable
+        };
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                via_refunds (
+                    deposit_tx_id,
+                    deposit_vout,
+                    l1_block_number,
+                    sender,
+                    amount,
+                    reason,
+                    status
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (deposit_tx_id, deposit_vout) DO NOTHING
+            "#,
+            deposit_tx_id.as_bytes(),
+            deposit_vout,
+            i64::from(l1_block_number),
+            sender,
+            amount,
+            reason,
+            status.to_string(),
+        )
+        .instrument("insert_refund")
+        .with_arg("deposit_tx_id", &deposit_tx_id)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the oldest refund waiting to be signed by the verifier network.
+    pub async fn get_next_pending_refund(&mut self) -> DalResult<Option<StorageRefund>> {
+        let refund = sqlx::query_as!(
+            StorageRefund,
+            r#"
+            SELECT
+                *
+            FROM
+                via_refunds
+            WHERE
+                status = 'Pending'
+            ORDER BY
+                id ASC
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_next_pending_refund")
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(refund)
+    }
+
+    /// Returns the refunds whose transaction was broadcast but not indexed yet.
+    pub async fn list_broadcast_refunds(&mut self) -> DalResult<Vec<StorageRefund>> {
+        let refunds = sqlx::query_as!(
+            StorageRefund,
+            r#"
+            SELECT
+                *
+            FROM
+                via_refunds
+            WHERE
+                status = 'Broadcast'
+            ORDER BY
+                id ASC
+            "#
+        )
+        .instrument("list_broadcast_refunds")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(refunds)
+    }
+
+    pub async fn get_refund(&mut self, id: i64) -> DalResult<Option<StorageRefund>> {
+        let refund = sqlx::query_as!(
+            StorageRefund,
+            r#"
+            SELECT
+                *
+            FROM
+                via_refunds
+            WHERE
+                id = $1
+            "#,
+            id
+        )
+        .instrument("get_refund")
+        .with_arg("id", &id)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(refund)
+    }
+
+    /// Returns the refunds of the invalid deposits made by the transaction `deposit_tx_id`.
+    pub async fn get_refunds_by_deposit(
+        &mut self,
+        deposit_tx_id: H256,
+    ) -> DalResult<Vec<StorageRefund>> {
+        let refunds = sqlx::query_as!(
+            StorageRefund,
+            r#"
+            SELECT
+                *
+            FROM
+                via_refunds
+            WHERE
+                deposit_tx_id = $1
+            ORDER BY
+                deposit_vout ASC
+            "#,
+            deposit_tx_id.as_bytes()
+        )
+        .instrument("get_refunds_by_deposit")
+        .with_arg("deposit_tx_id", &deposit_tx_id)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(refunds)
+    }
+
+    /// Stores the unsigned refund transaction built for the signing session.
+    pub async fn set_refund_unsigned_tx(&mut self, id: i64, data: &[u8]) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE via_refunds
+            SET
+                data = $2,
+                updated_at = NOW()
+            WHERE
+                id = $1
+            "#,
+            id,
+            data
+        )
+        .instrument("set_refund_unsigned_tx")
+        .with_arg("id", &id)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_refund_status(
+        &mut self,
+        id: i64,
+        status: RefundStatus,
+        refund_tx_id: Option<&[u8]>,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE via_refunds
+            SET
+                status = $2,
+                refund_tx_id = COALESCE($3, refund_tx_id),
+                updated_at = NOW()
+            WHERE
+                id = $1
+            "#,
+            id,
+            status.to_string(),
+            refund_tx_id
+        )
+        .instrument("update_refund_status")
+        .with_arg("id", &id)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Moves a broadcast refund whose transaction was dropped back to `Pending`, with the unsigned
+    /// transaction replacing it.
+    pub async fn reset_broadcast_refund(&mut self, id: i64, data: &[u8]) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE via_refunds
+            SET
+                status = 'Pending',
+                data = $2,
+                refund_tx_id = NULL,
+                updated_at = NOW()
+            WHERE
+                id = $1
+                AND status = 'Broadcast'
+            "#,
+            id,
+            data
+        )
+        .instrument("reset_broadcast_refund")
+        .with_arg("id", &id)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks the refunds of `deposit_tx_id` as done once the refund transaction is indexed.
+    /// Returns the number of refunds updated.
+    pub async fn mark_refunded(
+        &mut self,
+        deposit_tx_id: H256,
+        refund_tx_id: &[u8],
+    ) -> DalResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE via_refunds
+            SET
+                status = 'Refunded',
+                refund_tx_id = $2,
+                updated_at = NOW()
+            WHERE
+                deposit_tx_id = $1
+                AND status IN ('Pending', 'Broadcast')
+            "#,
+            deposit_tx_id.as_bytes(),
+            refund_tx_id
+        )
+        .instrument("mark_refunded")
+        .with_arg("deposit_tx_id", &deposit_tx_id)
+        .execute(self.storage)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Returns whether a refund of a deposit from an L1 block above `l1_block_number` was already
+    /// signed or sent.
+    pub async fn has_processed_refunds_after_l1_block(
+        &mut self,
+        l1_block_number: u32,
+    ) -> DalResult<bool> {
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        via_refunds
+                    WHERE
+                        l1_block_number > $1
+                        AND status IN ('Broadcast', 'Refunded')
+                ) AS "exists!"
+            "#,
+            i64::from(l1_block_number),
+        )
+        .instrument("has_processed_refunds_after_l1_block")
+        .with_arg("l1_block_number", &l1_block_number)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(record.exists)
+    }
+
+    /// Removes the refunds of the deposits indexed from the L1 blocks above `l1_block_number`.
+    pub async fn delete_refunds_after_l1_block(&mut self, l1_block_number: u32) -> DalResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM via_refunds
+            WHERE
+                l1_block_number > $1
+            "#,
+            i64::from(l1_block_number),
+        )
+        .instrument("delete_refunds_after_l1_block")
+        .with_arg("l1_block_number", &l1_block_number)
+        .execute(self.storage)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
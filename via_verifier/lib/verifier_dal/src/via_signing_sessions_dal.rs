@@ -0,0 +1,266 @@
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+
+use crate::{
+    models::storage_signing_session::{
+        StorageSessionNonce, StorageSessionPartialSignature, StorageSigningSession,
+    },
+    Verifier,
+};
+
+#[derive(Debug)]
+pub struct ViaSigningSessionsDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Verifier>,
+}
+
+impl ViaSigningSessionsDal<'_, '_> {
+    /// Starts a new signing session, the previously active session is closed.
+    pub async fn start_session(
+        &mut self,
+        session_type: &str,
+        session_op: &[u8],
+        messages_hash: &[u8],
+        started_at: i64,
+    ) -> DalResult<i64> {
+        let mut db_transaction = self.storage.start_transaction().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE via_signing_sessions
+            SET
+                is_active = FALSE,
+                updated_at = NOW()
+            WHERE
+                is_active = TRUE
+            "#
+        )
+        .instrument("start_session#deactivate")
+        .execute(&mut db_transaction)
+        .await?;
+
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO
+                via_signing_sessions (session_type, session_op, messages_hash, started_at)
+            VALUES
+                ($1, $2, $3, $4)
+            RETURNING
+                id
+            "#,
+            session_type,
+            session_op,
+            messages_hash,
+            started_at
+        )
+        .instrument("start_session")
+        .with_arg("session_type", &session_type)
+        .fetch_one(&mut db_transaction)
+        .await?;
+
+        db_transaction.commit().await?;
+
+        Ok(id)
+    }
+
+    /// Closes the active signing session, if any.
+    pub async fn close_active_session(&mut self) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE via_signing_sessions
+            SET
+                is_active = FALSE,
+                updated_at = NOW()
+            WHERE
+                is_active = TRUE
+            "#
+        )
+        .instrument("close_active_session")
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_active_session(&mut self) -> DalResult<Option<StorageSigningSession>> {
+        let session = sqlx::query_as!(
+            StorageSigningSession,
+            r#"
+            SELECT
+                *
+            FROM
+                via_signing_sessions
+            WHERE
+                is_active = TRUE
+            "#
+        )
+        .instrument("get_active_session")
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(session)
+    }
+
+    /// Records the public nonce of a signer. The first nonce submitted for an input is kept.
+    pub async fn insert_nonce(
+        &mut self,
+        session_id: i64,
+        input_index: i64,
+        signer_index: i64,
+        pub_nonce: &[u8],
+        messages_hash: &[u8],
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                via_signing_session_nonces (
+                    session_id,
+                    input_index,
+                    signer_index,
+                    pub_nonce,
+                    messages_hash
+                )
+            VALUES
+                ($1, $2, $3, $4, $5)
+            ON CONFLICT (session_id, input_index, signer_index) DO NOTHING
+            "#,
+            session_id,
+            input_index,
+            signer_index,
+            pub_nonce,
+            messages_hash
+        )
+        .instrument("insert_nonce")
+        .with_arg("session_id", &session_id)
+        .with_arg("input_index", &input_index)
+        .with_arg("signer_index", &signer_index)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Whether the public nonce was already submitted in another session. MuSig2 nonces must be
+    /// used only once.
+    pub async fn is_nonce_used_in_other_session(
+        &mut self,
+        pub_nonce: &[u8],
+        session_id: i64,
+    ) -> DalResult<bool> {
+        let used = sqlx::query_scalar!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        via_signing_session_nonces
+                    WHERE
+                        pub_nonce = $1
+                        AND session_id != $2
+                )
+            "#,
+            pub_nonce,
+            session_id
+        )
+        .instrument("is_nonce_used_in_other_session")
+        .with_arg("session_id", &session_id)
+        .fetch_one(self.storage)
+        .await?
+        .unwrap_or(false);
+
+        Ok(used)
+    }
+
+    /// Returns the nonces of the session which were submitted for the session messages.
+    pub async fn get_session_nonces(
+        &mut self,
+        session_id: i64,
+    ) -> DalResult<Vec<StorageSessionNonce>> {
+        let nonces = sqlx::query_as!(
+            StorageSessionNonce,
+            r#"
+            SELECT
+                n.input_index,
+                n.signer_index,
+                n.pub_nonce
+            FROM
+                via_signing_session_nonces n
+                JOIN via_signing_sessions s ON s.id = n.session_id
+            WHERE
+                n.session_id = $1
+                AND n.messages_hash = s.messages_hash
+            ORDER BY
+                n.input_index,
+                n.signer_index
+            "#,
+            session_id
+        )
+        .instrument("get_session_nonces")
+        .with_arg("session_id", &session_id)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(nonces)
+    }
+
+    pub async fn insert_partial_signature(
+        &mut self,
+        session_id: i64,
+        input_index: i64,
+        signer_index: i64,
+        partial_sig: &[u8],
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                via_signing_session_partial_sigs (session_id, input_index, signer_index, partial_sig)
+            VALUES
+                ($1, $2, $3, $4)
+            ON CONFLICT (session_id, input_index, signer_index) DO
+            UPDATE
+            SET
+                partial_sig = EXCLUDED.partial_sig
+            "#,
+            session_id,
+            input_index,
+            signer_index,
+            partial_sig
+        )
+        .instrument("insert_partial_signature")
+        .with_arg("session_id", &session_id)
+        .with_arg("input_index", &input_index)
+        .with_arg("signer_index", &signer_index)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_session_partial_signatures(
+        &mut self,
+        session_id: i64,
+    ) -> DalResult<Vec<StorageSessionPartialSignature>> {
+        let signatures = sqlx::query_as!(
+            StorageSessionPartialSignature,
+            r#"
+            SELECT
+                input_index,
+                signer_index,
+                partial_sig
+            FROM
+                via_signing_session_partial_sigs
+            WHERE
+                session_id = $1
+            ORDER BY
+                input_index,
+                signer_index
+            "#,
+            session_id
+        )
+        .instrument("get_session_partial_signatures")
+        .with_arg("session_id", &session_id)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(signatures)
+    }
+}
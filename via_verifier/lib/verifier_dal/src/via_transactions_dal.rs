@@ -9,6 +9,7 @@ pub struct ViaTransactionsDal<'a, 'c> {
 }
 
 impl ViaTransactionsDal<'_, '_> {
+    #[allow(clippy::too_many_arguments)]
     pub async fn insert_transaction(
         &mut self,
         priority_id: i64,
@@ -17,13 +18,22 @@ impl ViaTransactionsDal<'_, '_> {
         value: i64,
         calldata: Vec<u8>,
         canonical_tx_hash: H256,
+        l1_block_number: u32,
     ) -> DalResult<()> {
         sqlx::query!(
             r#"
             INSERT INTO
-                via_transactions (priority_id, tx_id, receiver, value, calldata, canonical_tx_hash)
+                via_transactions (
+                    priority_id,
+                    tx_id,
+                    receiver,
+                    value,
+                    calldata,
+                    canonical_tx_hash,
+                    l1_block_number
+                )
             VALUES
-                ($1, $2, $3, $4, $5, $6)
+                ($1, $2, $3, $4, $5, $6, $7)
             ON CONFLICT (tx_id) DO NOTHING
             "#,
             priority_id,
@@ -32,6 +42,7 @@ impl ViaTransactionsDal<'_, '_> {
             value,
             calldata,
             canonical_tx_hash.as_bytes(),
+            i64::from(l1_block_number),
         )
         .instrument("insert_transaction")
         .fetch_optional(self.storage)
@@ -121,4 +132,54 @@ impl ViaTransactionsDal<'_, '_> {
 
         Ok(exists.is_some())
     }
+
+    /// Returns whether a deposit from an L1 block above `l1_block_number` was already processed
+    /// as part of a verified L1 batch.
+    pub async fn has_processed_transactions_after_l1_block(
+        &mut self,
+        l1_block_number: u32,
+    ) -> DalResult<bool> {
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        via_transactions
+                    WHERE
+                        l1_block_number > $1
+                        AND status IS NOT NULL
+                ) AS "exists!"
+            "#,
+            i64::from(l1_block_number),
+        )
+        .instrument("has_processed_transactions_after_l1_block")
+        .with_arg("l1_block_number", &l1_block_number)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(record.exists)
+    }
+
+    /// Removes the deposits indexed from the L1 blocks above `l1_block_number`.
+    pub async fn delete_transactions_after_l1_block(
+        &mut self,
+        l1_block_number: u32,
+    ) -> DalResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM via_transactions
+            WHERE
+                l1_block_number > $1
+            "#,
+            i64::from(l1_block_number),
+        )
+        .instrument("delete_transactions_after_l1_block")
+        .with_arg("l1_block_number", &l1_block_number)
+        .execute(self.storage)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
 }
@@ -1,7 +1,10 @@
 use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
 use zksync_types::H256;
 
-use crate::{models::storage_vote::CanonicalChainStatus, Verifier};
+use crate::{
+    models::storage_vote::{CanonicalChainStatus, VotableTransactionStatus},
+    Verifier,
+};
 
 pub struct ViaVotesDal<'c, 'a> {
     pub(crate) storage: &'c mut Connection<'a, Verifier>,
@@ -19,7 +22,9 @@ impl ViaVotesDal<'_, '_> {
         proof_reveal_tx_id: H256,
         proof_blob_id: String,
         pubdata_reveal_tx_id: String,
+        pubdata_da_identifier: String,
         pubdata_blob_id: String,
+        l1_block_number: u32,
     ) -> DalResult<()> {
         sqlx::query!(
             r#"
@@ -32,10 +37,12 @@ impl ViaVotesDal<'_, '_> {
                     da_identifier,
                     proof_blob_id,
                     pubdata_reveal_tx_id,
-                    pubdata_blob_id
+                    pubdata_da_identifier,
+                    pubdata_blob_id,
+                    l1_block_number
                 )
             VALUES
-                ($1, $2, $3, $4, $5, $6, $7, $8)
+                ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             ON CONFLICT (l1_batch_hash) DO NOTHING
             "#,
             i64::from(l1_batch_number),
@@ -45,7 +52,9 @@ impl ViaVotesDal<'_, '_> {
             da_identifier,
             proof_blob_id,
             pubdata_reveal_tx_id,
-            pubdata_blob_id
+            pubdata_da_identifier,
+            pubdata_blob_id,
+            i64::from(l1_block_number)
         )
         .instrument("insert_votable_transaction")
         .fetch_optional(self.storage)
@@ -54,9 +63,12 @@ impl ViaVotesDal<'_, '_> {
         Ok(())
     }
 
+    /// Returns the votable transaction of the L1 batch proven by the proof inscription. A proof
+    /// can cover several L1 batches, so the batch number is required to tell them apart.
     pub async fn get_votable_transaction_id(
         &mut self,
         proof_reveal_tx_id: &[u8],
+        l1_batch_number: i64,
     ) -> DalResult<Option<i64>> {
         let row = sqlx::query!(
             r#"
@@ -66,33 +78,67 @@ impl ViaVotesDal<'_, '_> {
                 via_votable_transactions
             WHERE
                 proof_reveal_tx_id = $1
+                AND l1_batch_number = $2
             "#,
             proof_reveal_tx_id,
+            l1_batch_number,
         )
         .instrument("get_votable_transaction_id")
+        .with_arg("l1_batch_number", &l1_batch_number)
         .fetch_optional(self.storage)
         .await?;
         Ok(row.map(|r| r.id))
     }
 
+    /// Returns the votable transactions of all the L1 batches proven by the proof inscription,
+    /// ordered by L1 batch number.
+    pub async fn get_votable_transaction_ids(
+        &mut self,
+        proof_reveal_tx_id: &[u8],
+    ) -> DalResult<Vec<(i64, i64)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                l1_batch_number
+            FROM
+                via_votable_transactions
+            WHERE
+                proof_reveal_tx_id = $1
+            ORDER BY
+                l1_batch_number
+            "#,
+            proof_reveal_tx_id,
+        )
+        .instrument("get_votable_transaction_ids")
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.id, r.l1_batch_number))
+            .collect())
+    }
+
     /// Inserts a new vote row in `via_votes`.
     pub async fn insert_vote(
         &mut self,
         votable_transaction_id: i64,
         verifier_address: &str,
         vote: bool,
+        l1_block_number: u32,
     ) -> DalResult<()> {
         sqlx::query!(
             r#"
             INSERT INTO
-                via_votes (votable_transaction_id, verifier_address, vote)
+                via_votes (votable_transaction_id, verifier_address, vote, l1_block_number)
             VALUES
-                ($1, $2, $3)
+                ($1, $2, $3, $4)
             ON CONFLICT (votable_transaction_id, verifier_address) DO NOTHING
             "#,
             votable_transaction_id,
             verifier_address,
-            vote
+            vote,
+            i64::from(l1_block_number)
         )
         .instrument("insert_vote")
         .with_arg("votable_transaction_id", &votable_transaction_id)
@@ -217,6 +263,57 @@ impl ViaVotesDal<'_, '_> {
         Ok(row.max_batch_number.map(|n| n as u32))
     }
 
+    /// Returns the first L1 block a finalized L1 batch numbered `l1_batch_number` or above was
+    /// inscribed in.
+    pub async fn get_first_finalized_l1_block_since(
+        &mut self,
+        l1_batch_number: u32,
+    ) -> DalResult<Option<u32>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MIN(l1_block_number) AS l1_block_number
+            FROM
+                via_votable_transactions
+            WHERE
+                is_finalized = TRUE
+                AND l1_batch_number >= $1
+            "#,
+            i64::from(l1_batch_number)
+        )
+        .instrument("get_first_finalized_l1_block_since")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.l1_block_number.map(|n| n as u32))
+    }
+
+    /// Returns the L1 block the finalized L1 batch `l1_batch_number` was inscribed in.
+    pub async fn get_finalized_l1_block_number(
+        &mut self,
+        l1_batch_number: i64,
+    ) -> DalResult<Option<u32>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_block_number) AS l1_block_number
+            FROM
+                via_votable_transactions
+            WHERE
+                is_finalized = TRUE
+                AND l1_batch_number = $1
+            "#,
+            l1_batch_number
+        )
+        .instrument("get_finalized_l1_block_number")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.l1_block_number.map(|n| n as u32))
+    }
+
     pub async fn get_last_voted_l1_batch(&mut self) -> DalResult<u32> {
         let row = sqlx::query!(
             r#"
@@ -234,6 +331,43 @@ impl ViaVotesDal<'_, '_> {
         Ok(row.max_batch_number.unwrap_or(0) as u32)
     }
 
+    /// Lists the votable transactions up to `to_l1_batch_number`, the latest batches first.
+    pub async fn list_votable_transactions(
+        &mut self,
+        to_l1_batch_number: i64,
+        limit: i64,
+    ) -> DalResult<Vec<VotableTransactionStatus>> {
+        let transactions = sqlx::query_as!(
+            VotableTransactionStatus,
+            r#"
+            SELECT
+                id,
+                l1_batch_number,
+                l1_batch_hash,
+                proof_reveal_tx_id,
+                l1_batch_status,
+                is_finalized
+            FROM
+                via_votable_transactions
+            WHERE
+                l1_batch_number <= $1
+            ORDER BY
+                l1_batch_number DESC,
+                id DESC
+            LIMIT
+                $2
+            "#,
+            to_l1_batch_number,
+            limit
+        )
+        .instrument("list_votable_transactions")
+        .with_arg("to_l1_batch_number", &to_l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(transactions)
+    }
+
     pub async fn get_last_votable_l1_batch(&mut self) -> DalResult<u32> {
         let row = sqlx::query!(
             r#"
@@ -297,6 +431,66 @@ impl ViaVotesDal<'_, '_> {
         Ok(record.id)
     }
 
+    /// Stores the commitment the verifier recomputed for a verified L1 batch.
+    pub async fn set_l1_batch_commitment(
+        &mut self,
+        l1_batch_number: i64,
+        proof_reveal_tx_id: H256,
+        l1_batch_commitment: H256,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE via_votable_transactions
+            SET
+                l1_batch_commitment = $3,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+                AND proof_reveal_tx_id = $2
+            "#,
+            l1_batch_number,
+            proof_reveal_tx_id.as_bytes(),
+            l1_batch_commitment.as_bytes()
+        )
+        .instrument("set_l1_batch_commitment")
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the commitment recomputed for the valid L1 batch with the given hash. The outer
+    /// `None` means the batch was not verified as valid, the inner one that it was verified before
+    /// the commitments were stored.
+    pub async fn get_verified_l1_batch_commitment(
+        &mut self,
+        l1_batch_hash: H256,
+    ) -> DalResult<Option<Option<H256>>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_commitment
+            FROM
+                via_votable_transactions
+            WHERE
+                l1_batch_hash = $1
+                AND l1_batch_status = TRUE
+            LIMIT
+                1
+            "#,
+            l1_batch_hash.as_bytes()
+        )
+        .instrument("get_verified_l1_batch_commitment")
+        .with_arg("l1_batch_hash", &l1_batch_hash)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|r| {
+            r.l1_batch_commitment
+                .map(|commitment| H256::from_slice(&commitment))
+        }))
+    }
+
     pub async fn get_first_non_finalized_l1_batch_in_canonical_inscription_chain(
         &mut self,
     ) -> DalResult<Option<i64>> {
@@ -419,10 +613,11 @@ impl ViaVotesDal<'_, '_> {
     pub async fn get_finalized_block_and_non_processed_withdrawal(
         &mut self,
         l1_batch_number: i64,
-    ) -> DalResult<Option<(String, Vec<u8>)>> {
+    ) -> DalResult<Option<(String, String, Vec<u8>)>> {
         let result = sqlx::query!(
             r#"
             SELECT
+                v.pubdata_da_identifier,
                 v.pubdata_blob_id,
                 v.proof_reveal_tx_id
             FROM
@@ -447,17 +642,24 @@ impl ViaVotesDal<'_, '_> {
         .fetch_optional(self.storage)
         .await?;
 
-        let mapped_result = result.map(|row| (row.pubdata_blob_id, row.proof_reveal_tx_id));
+        let mapped_result = result.map(|row| {
+            (
+                row.pubdata_da_identifier,
+                row.pubdata_blob_id,
+                row.proof_reveal_tx_id,
+            )
+        });
         Ok(mapped_result)
     }
 
     pub async fn list_finalized_blocks_and_non_processed_withdrawals(
         &mut self,
-    ) -> DalResult<Vec<(i64, String, Vec<u8>)>> {
+    ) -> DalResult<Vec<(i64, String, String, Vec<u8>)>> {
         let rows = sqlx::query!(
             r#"
             SELECT
                 v.l1_batch_number,
+                v.pubdata_da_identifier,
                 v.pubdata_blob_id,
                 v.proof_reveal_tx_id
             FROM
@@ -478,9 +680,16 @@ impl ViaVotesDal<'_, '_> {
         .fetch_all(self.storage)
         .await?;
 
-        let result: Vec<(i64, String, Vec<u8>)> = rows
+        let result: Vec<(i64, String, String, Vec<u8>)> = rows
             .into_iter()
-            .map(|r| (r.l1_batch_number, r.pubdata_blob_id, r.proof_reveal_tx_id))
+            .map(|r| {
+                (
+                    r.l1_batch_number,
+                    r.pubdata_da_identifier,
+                    r.pubdata_blob_id,
+                    r.proof_reveal_tx_id,
+                )
+            })
             .collect();
 
         Ok(result)
@@ -584,9 +793,12 @@ impl ViaVotesDal<'_, '_> {
         Ok(result)
     }
 
+    /// Returns the votable transaction of a withdrawal, its L1 batch number and the bridge tx at
+    /// `index`. Withdrawals made before the proofs of L1 batch ranges carry no L1 batch number.
     pub async fn get_vote_transaction_info(
         &mut self,
         proof_reveal_tx_id: H256,
+        l1_batch_number: Option<i64>,
         index: i64,
     ) -> DalResult<Option<(i64, i64, Option<Vec<u8>>)>> {
         let res = sqlx::query!(
@@ -601,9 +813,18 @@ impl ViaVotesDal<'_, '_> {
                 AND b.index = $2
             WHERE
                 v.proof_reveal_tx_id = $1
+                AND (
+                    $3::BIGINT IS NULL
+                    OR v.l1_batch_number = $3
+                )
+            ORDER BY
+                v.l1_batch_number
+            LIMIT
+                1
             "#,
             proof_reveal_tx_id.as_bytes(),
-            index
+            index,
+            l1_batch_number
         )
         .instrument("get_vote_transaction_info")
         .fetch_optional(self.storage)
@@ -846,4 +1067,127 @@ impl ViaVotesDal<'_, '_> {
 
         Ok(exists.unwrap_or(false))
     }
+
+    /// Returns whether a withdrawal was already sent for an l1 batch whose votable transaction or
+    /// votes were indexed from the L1 blocks above `l1_block_number`.
+    pub async fn has_withdrawals_after_l1_block(
+        &mut self,
+        l1_block_number: u32,
+    ) -> DalResult<bool> {
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        via_bridge_tx bt
+                        JOIN via_votable_transactions vt ON vt.id = bt.votable_tx_id
+                    WHERE
+                        bt.hash IS NOT NULL
+                        AND bt.hash != $2
+                        AND (
+                            vt.l1_block_number > $1
+                            OR EXISTS (
+                                SELECT
+                                    1
+                                FROM
+                                    via_votes v
+                                WHERE
+                                    v.votable_transaction_id = vt.id
+                                    AND v.l1_block_number > $1
+                            )
+                        )
+                ) AS "exists!"
+            "#,
+            i64::from(l1_block_number),
+            H256::zero().as_bytes(),
+        )
+        .instrument("has_withdrawals_after_l1_block")
+        .with_arg("l1_block_number", &l1_block_number)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(record.exists)
+    }
+
+    /// Removes the votes indexed from the L1 blocks above `l1_block_number` and resets the
+    /// finalization of the votable transactions they were cast for.
+    pub async fn delete_votes_after_l1_block(&mut self, l1_block_number: u32) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            WITH
+                deleted_votes AS (
+                    DELETE FROM via_votes
+                    WHERE
+                        l1_block_number > $1
+                    RETURNING
+                        votable_transaction_id
+                )
+            UPDATE via_votable_transactions
+            SET
+                is_finalized = NULL,
+                updated_at = NOW()
+            WHERE
+                id IN (
+                    SELECT
+                        votable_transaction_id
+                    FROM
+                        deleted_votes
+                )
+            "#,
+            i64::from(l1_block_number),
+        )
+        .instrument("delete_votes_after_l1_block")
+        .with_arg("l1_block_number", &l1_block_number)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes the votable transactions indexed from the L1 blocks above `l1_block_number`,
+    /// together with their votes and unsent bridge transactions.
+    pub async fn delete_votable_transactions_after_l1_block(
+        &mut self,
+        l1_block_number: u32,
+    ) -> DalResult<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM via_bridge_tx
+            WHERE
+                votable_tx_id IN (
+                    SELECT
+                        id
+                    FROM
+                        via_votable_transactions
+                    WHERE
+                        l1_block_number > $1
+                )
+            "#,
+            i64::from(l1_block_number),
+        )
+        .instrument("delete_votable_transactions_after_l1_block#bridge_txs")
+        .with_arg("l1_block_number", &l1_block_number)
+        .execute(&mut transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM via_votable_transactions
+            WHERE
+                l1_block_number > $1
+            "#,
+            i64::from(l1_block_number),
+        )
+        .instrument("delete_votable_transactions_after_l1_block")
+        .with_arg("l1_block_number", &l1_block_number)
+        .execute(&mut transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
 }
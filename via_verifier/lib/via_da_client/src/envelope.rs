@@ -0,0 +1,115 @@
+use anyhow::{bail, ensure, Context};
+
+/// Prefix of the blobs wrapped in an envelope. The blobs without it are dispatched uncompressed, the
+/// pubdata (starting with the number of user logs) and the bincode-serialized proofs (starting with
+/// the L1 batch number) can't realistically start with it.
+pub const BLOB_ENVELOPE_MAGIC: &[u8; 4] = b"VIAB";
+const BLOB_ENVELOPE_VERSION: u8 = 1;
+const HEADER_SIZE: usize = BLOB_ENVELOPE_MAGIC.len() + 2;
+
+/// Max size of a decompressed blob, so a malicious blob can't exhaust the memory.
+const MAX_DECODED_BLOB_SIZE: usize = 128 * 1024 * 1024;
+
+/// Level used to compress the blobs with zstd.
+const ZSTD_COMPRESSION_LEVEL: i32 = 9;
+
+/// The compression applied to the payload of the envelope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum BlobCodec {
+    None = 0,
+    Zstd = 1,
+}
+
+impl TryFrom<u8> for BlobCodec {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u8) -> anyhow::Result<Self> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Zstd),
+            _ => bail!("Unknown blob codec {value}"),
+        }
+    }
+}
+
+/// Wraps `data` in an envelope, compressed with `codec`.
+/// Format: `[magic || version as u8 || codec as u8 || payload]`
+pub fn encode_blob(data: &[u8], codec: BlobCodec) -> anyhow::Result<Vec<u8>> {
+    let payload = match codec {
+        BlobCodec::None => data.to_vec(),
+        BlobCodec::Zstd => zstd::bulk::compress(data, ZSTD_COMPRESSION_LEVEL)
+            .with_context(|| "Failed to compress the blob")?,
+    };
+
+    let mut blob = Vec::with_capacity(HEADER_SIZE + payload.len());
+    blob.extend_from_slice(BLOB_ENVELOPE_MAGIC);
+    blob.push(BLOB_ENVELOPE_VERSION);
+    blob.push(codec as u8);
+    blob.extend(payload);
+    Ok(blob)
+}
+
+/// Returns the data of a blob fetched from the DA layer, decompressing the blobs wrapped in an
+/// envelope and returning the uncompressed ones as is.
+pub fn decode_blob(blob: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+    if !blob.starts_with(BLOB_ENVELOPE_MAGIC) {
+        return Ok(blob);
+    }
+    ensure!(blob.len() >= HEADER_SIZE, "Truncated blob envelope");
+
+    let version = blob[BLOB_ENVELOPE_MAGIC.len()];
+    ensure!(
+        version == BLOB_ENVELOPE_VERSION,
+        "Unsupported blob envelope version {version}"
+    );
+
+    let payload = &blob[HEADER_SIZE..];
+    match BlobCodec::try_from(blob[BLOB_ENVELOPE_MAGIC.len() + 1])? {
+        BlobCodec::None => Ok(payload.to_vec()),
+        BlobCodec::Zstd => zstd::bulk::decompress(payload, MAX_DECODED_BLOB_SIZE)
+            .with_context(|| "Failed to decompress the blob"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_envelope_roundtrip() {
+        let data = [1u8, 2, 3, 4].repeat(1000);
+
+        for codec in [BlobCodec::None, BlobCodec::Zstd] {
+            let blob = encode_blob(&data, codec).unwrap();
+            assert_eq!(decode_blob(blob).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_zstd_blob_is_compressed() {
+        let data = [1u8, 2, 3, 4].repeat(1000);
+
+        let blob = encode_blob(&data, BlobCodec::Zstd).unwrap();
+        assert!(blob.len() < data.len());
+    }
+
+    #[test]
+    fn test_decode_blob_without_envelope() {
+        let data = vec![0, 0, 0, 1, 2, 3];
+        assert_eq!(decode_blob(data.clone()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_invalid_blob_envelope() {
+        let mut blob = encode_blob(&[1, 2, 3], BlobCodec::None).unwrap();
+        blob[BLOB_ENVELOPE_MAGIC.len() + 1] = 7;
+        assert!(decode_blob(blob).is_err());
+
+        let mut blob = encode_blob(&[1, 2, 3], BlobCodec::None).unwrap();
+        blob[BLOB_ENVELOPE_MAGIC.len()] = 2;
+        assert!(decode_blob(blob).is_err());
+
+        assert!(decode_blob(BLOB_ENVELOPE_MAGIC.to_vec()).is_err());
+    }
+}
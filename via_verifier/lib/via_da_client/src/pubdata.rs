@@ -2,6 +2,11 @@ use std::io::{Cursor, Read};
 
 use anyhow::Context;
 use byteorder::{BigEndian, ReadBytesExt};
+use zksync_types::{
+    commitment::{serialize_commitments, SerializeCommitment},
+    l2_to_l1_log::UserL2ToL1Log,
+    writes::{StateDiffRecord, PADDED_ENCODED_STORAGE_DIFF_LEN_BYTES},
+};
 
 use crate::types::L1MessengerL2ToL1Log;
 
@@ -66,6 +71,136 @@ impl Pubdata {
     }
 }
 
+/// The size of a state diff record once its padding is stripped.
+const STATE_DIFF_RECORD_SIZE: usize = 156;
+
+/// The blob dispatched to the DA layer for an L1 batch: the pubdata of the batch followed by its
+/// uncompressed state diffs, which are required to recompute the state diff hash of the batch.
+/// Format: `[pubdata || (numberOfStateDiffs as u32) || stateDiffs[1] || ... || stateDiffs[n]]`,
+/// each state diff being padded to `PADDED_ENCODED_STORAGE_DIFF_LEN_BYTES`.
+#[derive(Debug, Clone, Default)]
+pub struct PubdataBlob {
+    /// The pubdata of the L1 batch, as committed by the bootloader.
+    pub pubdata: Vec<u8>,
+    /// The compressed state diffs of the pubdata, including their header.
+    pub compressed_state_diffs: Vec<u8>,
+    /// The uncompressed state diffs of the L1 batch, sorted by address and key.
+    pub state_diffs: Vec<StateDiffRecord>,
+}
+
+impl PubdataBlob {
+    pub fn encode(pubdata: &[u8], state_diffs: &[StateDiffRecord]) -> Vec<u8> {
+        let mut blob = pubdata.to_vec();
+        blob.extend((state_diffs.len() as u32).to_be_bytes());
+        blob.extend(serialize_commitments(state_diffs));
+        blob
+    }
+
+    pub fn decode(blob: &[u8]) -> anyhow::Result<PubdataBlob> {
+        let (state_diffs_start, pubdata_len) = pubdata_sections(blob)?;
+        let (pubdata, encoded_state_diffs) = blob.split_at(pubdata_len);
+
+        let num_state_diffs = encoded_state_diffs
+            .get(..4)
+            .context("Failed to decode num state diffs")?;
+        let num_state_diffs = u32::from_be_bytes(num_state_diffs.try_into().unwrap()) as usize;
+        let encoded_state_diffs = &encoded_state_diffs[4..];
+        anyhow::ensure!(
+            encoded_state_diffs.len() == num_state_diffs * PADDED_ENCODED_STORAGE_DIFF_LEN_BYTES,
+            "Invalid size of the state diffs, expected {num_state_diffs} state diffs got {} bytes",
+            encoded_state_diffs.len()
+        );
+
+        let state_diffs = encoded_state_diffs
+            .chunks(PADDED_ENCODED_STORAGE_DIFF_LEN_BYTES)
+            .map(|encoded| {
+                let state_diff =
+                    StateDiffRecord::try_from_slice(&encoded[..STATE_DIFF_RECORD_SIZE]).unwrap();
+                anyhow::ensure!(
+                    state_diff.encode_padded() == encoded,
+                    "Invalid padding of the state diff"
+                );
+                Ok(state_diff)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(PubdataBlob {
+            pubdata: pubdata.to_vec(),
+            compressed_state_diffs: pubdata[state_diffs_start..].to_vec(),
+            state_diffs,
+        })
+    }
+    /// Decodes a legacy blob, dispatched before the state diffs were appended to the pubdata. The
+    /// blob is the pubdata alone, so the state diffs of the decoded blob are empty.
+    pub fn decode_legacy(blob: &[u8]) -> anyhow::Result<PubdataBlob> {
+        let (state_diffs_start, pubdata_len) = pubdata_sections(blob)?;
+        anyhow::ensure!(
+            pubdata_len == blob.len(),
+            "Unexpected {} bytes after the pubdata of the legacy blob",
+            blob.len() - pubdata_len
+        );
+
+        Ok(PubdataBlob {
+            pubdata: blob.to_vec(),
+            compressed_state_diffs: blob[state_diffs_start..].to_vec(),
+            state_diffs: vec![],
+        })
+    }
+}
+
+/// Returns the offset of the compressed state diffs in the pubdata at the start of `blob` and the
+/// length of that pubdata.
+fn pubdata_sections(blob: &[u8]) -> anyhow::Result<(usize, usize)> {
+    let mut cursor = Cursor::new(blob);
+
+    // Skip the user L2->L1 logs.
+    let num_user_logs = cursor
+        .read_u32::<BigEndian>()
+        .with_context(|| "Failed to decode num user logs")?;
+    skip_bytes(
+        &mut cursor,
+        u64::from(num_user_logs) * UserL2ToL1Log::SERIALIZED_SIZE as u64,
+    )?;
+
+    // Skip the L2->L1 messages and the published bytecodes, encoded the same way.
+    for section in ["l2 to l1 messages", "bytecodes"] {
+        let count = cursor
+            .read_u32::<BigEndian>()
+            .with_context(|| format!("Failed to decode num {section}"))?;
+        for _ in 0..count {
+            let len = cursor
+                .read_u32::<BigEndian>()
+                .with_context(|| format!("Failed to decode the length of the {section}"))?;
+            skip_bytes(&mut cursor, len.into())?;
+        }
+    }
+
+    // Compressed state diffs: `[version (u8) || length (u24) || enumeration index size (u8) || data]`.
+    let state_diffs_start = cursor.position() as usize;
+    cursor
+        .read_u8()
+        .with_context(|| "Failed to decode the state diffs compression version")?;
+    let compressed_len = cursor
+        .read_u24::<BigEndian>()
+        .with_context(|| "Failed to decode the compressed state diffs length")?;
+    cursor
+        .read_u8()
+        .with_context(|| "Failed to decode the enumeration index size")?;
+    skip_bytes(&mut cursor, compressed_len.into())?;
+
+    Ok((state_diffs_start, cursor.position() as usize))
+}
+
+fn skip_bytes(cursor: &mut Cursor<&[u8]>, num_bytes: u64) -> anyhow::Result<()> {
+    let position = cursor.position() + num_bytes;
+    anyhow::ensure!(
+        position <= cursor.get_ref().len() as u64,
+        "Unexpected end of the pubdata"
+    );
+    cursor.set_position(position);
+    Ok(())
+}
+
 /// Helper function to read a specific number of bytes
 #[allow(unused)]
 fn read_bytes<R: Read>(reader: &mut R, num_bytes: usize) -> anyhow::Result<Vec<u8>> {
@@ -79,11 +214,49 @@ mod tests {
     use std::str::FromStr;
 
     use hex::encode;
-    use zksync_types::{web3::keccak256, Address, H256};
+    use zksync_types::{web3::keccak256, writes::compress_state_diffs, Address, H256, U256};
 
     use super::*;
     use crate::types::L2_BASE_TOKEN_SYSTEM_CONTRACT_ADDR;
 
+    const REAL_PUBDATA: &str = "00000001000100000000000000000000000000000000000000008008000000000000000000000000000000000000000000000000000000000000800aa1fd131a17718668a78581197d19972abd907b7b343b9694e02246d18c3801c500000001000000506c0960f962637274317178326c6b30756e756b6d3830716d65706a703439687766397a36786e7a307337336b396a35360000000000000000000000000000000000000000000000000000000005f5e10000000000010001280400032c1818e4770f08c05b28829d7d5f9d401d492c7432c166dfecf4af04238ea323009d7042e8fb0f249338d18505e5ba1d4a546e9d21f47c847ca725ff53ac29f740ca1bbc31cc849a8092a36f9a321e17412dee200b956038af1c2dc83430a0e8b000d3e2c6760d91078e517a2cb882cd3c9551de3ab5f30d554d51b17e3744cf92b0cf368ce957aed709b985423cd3ba11615de01ecafa15eb9a11bc6cdef4f6327900436ef22b96a07224eb06f0eecfecc184033da7db2a5fb58f867f17298b896b55000000420901000000362205f5e1000000003721032b8b14000000382209216c140000003a8901000000000000000000000000000000170000003b8902000000000000000000000000000000170000003e890200000000000000000000000000000017";
+
+    fn state_diffs() -> Vec<StateDiffRecord> {
+        vec![
+            StateDiffRecord {
+                address: Address::repeat_byte(1),
+                key: U256::from(1),
+                derived_key: [2; 32],
+                enumeration_index: 0,
+                initial_value: U256::zero(),
+                final_value: U256::from(100),
+            },
+            StateDiffRecord {
+                address: Address::repeat_byte(1),
+                key: U256::from(2),
+                derived_key: [3; 32],
+                enumeration_index: 7,
+                initial_value: U256::from(100),
+                final_value: U256::from(50),
+            },
+        ]
+    }
+
+    /// Builds the pubdata of a batch with a single message and the given state diffs.
+    fn pubdata_with_state_diffs(state_diffs: &[StateDiffRecord]) -> Vec<u8> {
+        let mut pubdata = Pubdata {
+            user_logs: vec![],
+            l2_to_l1_messages: vec![hex::decode("deadbeef").unwrap()],
+        }
+        .encode_pubdata();
+        // A single published bytecode.
+        pubdata.extend(1_u32.to_be_bytes());
+        pubdata.extend(32_u32.to_be_bytes());
+        pubdata.extend([5; 32]);
+        pubdata.extend(compress_state_diffs(state_diffs.to_vec()));
+        pubdata
+    }
+
     fn generate_random_hex(len: usize) -> String {
         // Generate random bytes
         let random_bytes: Vec<u8> = (0..len).map(|_| rand::random::<u8>()).collect();
@@ -198,11 +371,75 @@ mod tests {
 
     #[test]
     fn test_decode_pubdata_with_single_real_l1_messager_l2_to_l1_log() {
-        let input = "00000001000100000000000000000000000000000000000000008008000000000000000000000000000000000000000000000000000000000000800aa1fd131a17718668a78581197d19972abd907b7b343b9694e02246d18c3801c500000001000000506c0960f962637274317178326c6b30756e756b6d3830716d65706a703439687766397a36786e7a307337336b396a35360000000000000000000000000000000000000000000000000000000005f5e10000000000010001280400032c1818e4770f08c05b28829d7d5f9d401d492c7432c166dfecf4af04238ea323009d7042e8fb0f249338d18505e5ba1d4a546e9d21f47c847ca725ff53ac29f740ca1bbc31cc849a8092a36f9a321e17412dee200b956038af1c2dc83430a0e8b000d3e2c6760d91078e517a2cb882cd3c9551de3ab5f30d554d51b17e3744cf92b0cf368ce957aed709b985423cd3ba11615de01ecafa15eb9a11bc6cdef4f6327900436ef22b96a07224eb06f0eecfecc184033da7db2a5fb58f867f17298b896b55000000420901000000362205f5e1000000003721032b8b14000000382209216c140000003a8901000000000000000000000000000000170000003b8902000000000000000000000000000000170000003e890200000000000000000000000000000017";
-        let encoded_pubdata = hex::decode(input).unwrap();
+        let encoded_pubdata = hex::decode(REAL_PUBDATA).unwrap();
         let pubdata_input = Pubdata::decode_pubdata(encoded_pubdata).unwrap();
 
         let hash = keccak256(&pubdata_input.l2_to_l1_messages[0].clone());
         assert_eq!(H256::from(hash), pubdata_input.user_logs[0].value);
     }
+
+    #[test]
+    fn test_pubdata_blob_roundtrip() {
+        let state_diffs = state_diffs();
+        let pubdata = pubdata_with_state_diffs(&state_diffs);
+
+        let blob = PubdataBlob::decode(&PubdataBlob::encode(&pubdata, &state_diffs)).unwrap();
+        assert_eq!(blob.pubdata, pubdata);
+        assert_eq!(
+            blob.compressed_state_diffs,
+            compress_state_diffs(state_diffs.clone())
+        );
+        assert_eq!(blob.state_diffs, state_diffs);
+    }
+
+    #[test]
+    fn test_pubdata_blob_with_real_pubdata() {
+        let pubdata = hex::decode(REAL_PUBDATA).unwrap();
+
+        let blob = PubdataBlob::decode(&PubdataBlob::encode(&pubdata, &[])).unwrap();
+        assert_eq!(blob.pubdata, pubdata);
+        assert_eq!(blob.compressed_state_diffs, pubdata[184..]);
+        assert!(blob.state_diffs.is_empty());
+    }
+
+    #[test]
+    fn test_pubdata_blob_without_state_diffs() {
+        let state_diffs = state_diffs();
+        let pubdata = pubdata_with_state_diffs(&state_diffs);
+
+        let err = PubdataBlob::decode(&pubdata).unwrap_err();
+        assert!(err.to_string().contains("num state diffs"), "{err}");
+
+        let blob = PubdataBlob::decode_legacy(&pubdata).unwrap();
+        assert_eq!(blob.pubdata, pubdata);
+        assert_eq!(
+            blob.compressed_state_diffs,
+            compress_state_diffs(state_diffs.clone())
+        );
+        assert!(blob.state_diffs.is_empty());
+
+        let err =
+            PubdataBlob::decode_legacy(&PubdataBlob::encode(&pubdata, &state_diffs)).unwrap_err();
+        assert!(err.to_string().contains("after the pubdata"), "{err}");
+    }
+
+    #[test]
+    fn test_pubdata_blob_with_invalid_state_diffs() {
+        let state_diffs = state_diffs();
+        let pubdata = pubdata_with_state_diffs(&state_diffs);
+        let blob = PubdataBlob::encode(&pubdata, &state_diffs);
+
+        // A truncated state diff.
+        assert!(PubdataBlob::decode(&blob[..blob.len() - 1]).is_err());
+
+        // A state diff with a non zero padding.
+        let mut invalid_blob = blob.clone();
+        *invalid_blob.last_mut().unwrap() = 1;
+        let err = PubdataBlob::decode(&invalid_blob).unwrap_err();
+        assert!(err.to_string().contains("padding"), "{err}");
+
+        // A truncated pubdata.
+        let err = PubdataBlob::decode(&pubdata[..pubdata.len() - 1]).unwrap_err();
+        assert!(err.to_string().contains("end of the pubdata"), "{err}");
+    }
 }
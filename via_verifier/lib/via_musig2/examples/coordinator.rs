@@ -520,6 +520,10 @@ async fn create_test_withdrawal_builder(
         external_apis: vec![],
         fee_strategies: vec![],
         use_rpc_for_fee_rate: None,
+        esplora_url: None,
+        fee_rate_providers: None,
+        fee_rate_aggregation: None,
+        fee_rate_quorum: None,
     };
 
     let btc_client = Arc::new(BitcoinClient::new(rpc_url, auth, config).unwrap());
@@ -10,6 +10,7 @@ use rand::{rngs::OsRng, Rng};
 use secp256k1_musig2::{PublicKey, Secp256k1, SecretKey};
 pub mod constants;
 pub mod fee;
+pub mod threshold;
 pub mod transaction_builder;
 pub mod types;
 pub mod utils;
@@ -222,6 +223,11 @@ impl Signer {
         self.signer_index
     }
 
+    /// Message of the signing session the signer started.
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
     pub fn has_not_started(&self) -> bool {
         self.first_round.is_none()
     }
@@ -291,9 +297,17 @@ pub fn get_signer_with_merkle_root(
     merkle_root: Option<TapNodeHash>,
 ) -> anyhow::Result<Signer> {
     let private_key = PrivateKey::from_wif(private_key_wif)?;
-    let secret_key =
-        secp256k1_musig2::SecretKey::from_byte_array(&private_key.inner.secret_bytes())
-            .with_context(|| "Error to compute the coordinator sk")?;
+    get_signer_from_secret_key(&private_key.inner, verifiers_pub_keys_str, merkle_root)
+}
+
+/// Same as [`get_signer_with_merkle_root`], from the secret key held by a signer instead of a WIF.
+pub fn get_signer_from_secret_key(
+    secret_key: &bitcoin::secp256k1::SecretKey,
+    verifiers_pub_keys_str: Vec<String>,
+    merkle_root: Option<TapNodeHash>,
+) -> anyhow::Result<Signer> {
+    let secret_key = secp256k1_musig2::SecretKey::from_byte_array(&secret_key.secret_bytes())
+        .with_context(|| "Error to compute the coordinator sk")?;
     let secp = secp256k1_musig2::Secp256k1::new();
     let public_key = PublicKey::from_secret_key(&secp, &secret_key);
 
@@ -5,15 +5,27 @@ mod tests {
     use anyhow::Result;
     use async_trait::async_trait;
     use bitcoin::{
-        hashes::Hash, policy::MAX_STANDARD_TX_WEIGHT, Address, Amount, Network, OutPoint,
-        ScriptBuf, Transaction, TxOut, Txid,
+        hashes::Hash, policy::MAX_STANDARD_TX_WEIGHT, secp256k1::Secp256k1, Address, Amount,
+        Network, OutPoint, PrivateKey, ScriptBuf, Transaction, TxOut, Txid,
     };
     use bitcoincore_rpc::json::GetBlockStatsResult;
     use mockall::{mock, predicate::*};
     use rand::RngCore;
     use via_btc_client::{traits::BitcoinOps, types::BitcoinError};
+    use via_verifier_types::transaction::UnsignedBridgeTx;
 
-    use crate::{fee::WithdrawalFeeStrategy, transaction_builder::TransactionBuilder};
+    use crate::{
+        fee::{FeeStrategy, WithdrawalFeeStrategy},
+        threshold::{sign_script_path, BridgeTaproot},
+        transaction_builder::TransactionBuilder,
+        types::TransactionMetadata,
+    };
+
+    const PRIVATE_KEYS: [&str; 3] = [
+        "cVZduZu265sWeAqFYygoDEE1FZ7wV9rpW5qdqjRkUehjaUMWLT1R",
+        "cUWA5dZXc6NwLovW3Kr9DykfY5ysFigKZM5Annzty7J8a43Fe2YF",
+        "cRaUbRSn8P8cXUcg6cMZ7oTZ1wbDjktYTsbdGw62tuqqD9ttQWMm",
+    ];
 
     mock! {
         BitcoinOpsService {}
@@ -308,6 +320,46 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_transaction_metadata_split_by_max_tx_outflow() -> Result<()> {
+        let tx_builder =
+            create_tx_builder_mock(None)?.with_max_tx_outflow(Some(Amount::from_sat(5000000)));
+        let available_utxos = generate_dummy_utxos(4, 10000000);
+        let fee_rate = 1;
+
+        let outputs = generate_dummy_outputs(4, 2000000);
+        let txs_metadata = tx_builder
+            .get_transaction_metadata(
+                &available_utxos,
+                &outputs,
+                fee_rate,
+                Arc::new(WithdrawalFeeStrategy::new()),
+                MAX_STANDARD_TX_WEIGHT as u64,
+            )
+            .await?;
+
+        assert_eq!(txs_metadata.len(), 2);
+        for tx_metadata in &txs_metadata {
+            assert_eq!(tx_metadata.outputs.len(), 2);
+            assert!(tx_metadata.total_amount <= Amount::from_sat(5000000));
+        }
+
+        // A single output above the limit can't be split.
+        let outputs = generate_dummy_outputs(1, 6000000);
+        let txs_metadata = tx_builder
+            .get_transaction_metadata(
+                &available_utxos,
+                &outputs,
+                fee_rate,
+                Arc::new(WithdrawalFeeStrategy::new()),
+                MAX_STANDARD_TX_WEIGHT as u64,
+            )
+            .await?;
+        assert_eq!(txs_metadata.len(), 1);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_build_bridge_txs() -> Result<()> {
         let tx_builder = create_tx_builder_mock(None)?;
@@ -362,4 +414,171 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_build_bridge_txs_without_dust_change() -> Result<()> {
+        let tx_builder = create_tx_builder_mock(None)?;
+        let dust_limit = get_bridge_address_mock()
+            .script_pubkey()
+            .minimal_non_dust()
+            .to_sat();
+        let outputs = generate_dummy_outputs(2, 4000);
+        let op_return_data = Txid::all_zeros().to_byte_array();
+
+        for (change, expected_change) in [(dust_limit - 1, 0), (dust_limit, dust_limit)] {
+            let txs_metadata = vec![TransactionMetadata {
+                outputs: outputs.clone(),
+                inputs: generate_dummy_utxos(1, 9000 + change),
+                total_amount: Amount::from_sat(8000),
+                fee: Amount::from_sat(1000),
+            }];
+
+            let bridge_txs = tx_builder.build_bridge_txs(
+                &txs_metadata,
+                1,
+                b"VIA_PROTOCOL:WITHDRAWAL:",
+                vec![&op_return_data[..]],
+            )?;
+
+            let bridge_tx = &bridge_txs[0];
+            assert_eq!(bridge_tx.change_amount, Amount::from_sat(expected_change));
+            assert_eq!(
+                bridge_tx.tx.output.len(),
+                outputs.len() + 1 + usize::from(expected_change > 0)
+            );
+            // The dust change is paid to the miners, not charged to the users.
+            assert_eq!(bridge_tx.fee, Amount::from_sat(1000));
+            assert_eq!(bridge_tx.get_fee_per_user(), Amount::from_sat(500));
+            assert!(!bridge_tx.is_empty());
+        }
+
+        Ok(())
+    }
+
+    fn get_bridge_taproot() -> (Vec<PrivateKey>, BridgeTaproot) {
+        let secp = Secp256k1::new();
+        let private_keys: Vec<_> = PRIVATE_KEYS
+            .iter()
+            .map(|wif| PrivateKey::from_wif(wif).unwrap())
+            .collect();
+        let pubkeys: Vec<_> = private_keys
+            .iter()
+            .map(|key| key.public_key(&secp).to_string())
+            .collect();
+        (private_keys, BridgeTaproot::new(&pubkeys, 2, None).unwrap())
+    }
+
+    fn build_bridge_tx(
+        tx_builder: &TransactionBuilder,
+        inputs: Vec<(OutPoint, TxOut)>,
+        fee_rate: u64,
+    ) -> Result<UnsignedBridgeTx> {
+        // The users withdraw to taproot addresses, like the change.
+        let outputs = (0..2)
+            .map(|_| TxOut {
+                value: Amount::from_sat(100_000),
+                script_pubkey: get_bridge_address_mock().script_pubkey(),
+            })
+            .collect();
+        let (outputs, fee, total_amount) = WithdrawalFeeStrategy::new().apply_fee_to_outputs(
+            outputs,
+            inputs.len() as u32,
+            fee_rate,
+        )?;
+        let txs_metadata = vec![TransactionMetadata {
+            outputs,
+            inputs,
+            total_amount,
+            fee,
+        }];
+        let op_return_data = Txid::all_zeros().to_byte_array();
+
+        Ok(tx_builder
+            .build_bridge_txs(
+                &txs_metadata,
+                fee_rate,
+                b"VIA_PROTOCOL:WITHDRAWAL:",
+                vec![&op_return_data[..]],
+            )?
+            .remove(0))
+    }
+
+    #[tokio::test]
+    async fn test_build_script_path_tx() -> Result<()> {
+        let (private_keys, bridge_taproot) = get_bridge_taproot();
+        let bridge_address =
+            Address::from_script(&bridge_taproot.script_pubkey(), get_network()).unwrap();
+        let tx_builder = TransactionBuilder::new(create_btc_client_mock(vec![]), bridge_address)?;
+        let fee_rate = 10;
+
+        let inputs: Vec<_> = generate_dummy_utxos(3, 100_000)
+            .into_iter()
+            .map(|(outpoint, txout)| {
+                let script_pubkey = bridge_taproot.script_pubkey();
+                (
+                    outpoint,
+                    TxOut {
+                        script_pubkey,
+                        ..txout
+                    },
+                )
+            })
+            .collect();
+        let unsigned_tx = build_bridge_tx(&tx_builder, inputs.clone(), fee_rate)?;
+        let script_path_tx = tx_builder.build_script_path_tx(&unsigned_tx, &bridge_taproot)?;
+
+        // Only the change pays the extra fee.
+        let change_index = unsigned_tx.tx.output.len() - 1;
+        assert_eq!(
+            unsigned_tx.tx.output[..change_index],
+            script_path_tx.tx.output[..change_index]
+        );
+        assert!(script_path_tx.change_amount < unsigned_tx.change_amount);
+        assert_eq!(
+            script_path_tx.tx.output[change_index].value,
+            script_path_tx.change_amount
+        );
+        assert_eq!(script_path_tx.txid, script_path_tx.tx.compute_txid());
+        assert_ne!(script_path_tx.txid, unsigned_tx.txid);
+
+        // Spend the inputs through the 2-of-3 script path.
+        let sighashes = tx_builder
+            .get_tr_script_sighashes(&script_path_tx, bridge_taproot.threshold_leaf_hash())?;
+        let mut signed_tx = script_path_tx.tx.clone();
+        for (input_index, sighash) in sighashes.iter().enumerate() {
+            let signatures = private_keys
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(signer_index, key)| {
+                    (signer_index, sign_script_path(&key.inner, sighash).unwrap())
+                })
+                .collect();
+            signed_tx.input[input_index].witness = bridge_taproot.witness(&signatures)?;
+        }
+
+        let total_input_amount = inputs.iter().map(|(_, txout)| txout.value).sum::<Amount>();
+        let total_output_amount = signed_tx
+            .output
+            .iter()
+            .map(|txout| txout.value)
+            .sum::<Amount>();
+        assert_eq!(total_input_amount - total_output_amount, script_path_tx.fee);
+
+        // The key path fee doesn't cover the script path witness, the adjusted one does.
+        let required_fee = Amount::from_sat(fee_rate * signed_tx.vsize() as u64);
+        assert!(unsigned_tx.fee < required_fee);
+        assert!(script_path_tx.fee >= required_fee);
+
+        // Without change, nothing pays for the larger witness.
+        let mut unsigned_tx = unsigned_tx;
+        unsigned_tx.tx.output.pop();
+        unsigned_tx.utxos[0].1.value -= unsigned_tx.change_amount;
+        unsigned_tx.change_amount = Amount::ZERO;
+        assert!(tx_builder
+            .build_script_path_tx(&unsigned_tx, &bridge_taproot)
+            .is_err());
+
+        Ok(())
+    }
 }
@@ -338,8 +338,12 @@ mod tests {
             .sum::<Amount>()
             + bridge_tx.fee.clone();
 
-        // The total outputs with fee should be equal to the total bridge address before transaction.
-        assert_eq!(total_output_value_include_fee, total_bridge_value_before);
+        // The amount of the 3rd user is below the dust limit, it's left to the miners instead of
+        // sent back to the bridge address.
+        assert_eq!(
+            total_output_value_include_fee + users_request_small_value,
+            total_bridge_value_before
+        );
 
         // Divide the fee per 2 because the 3rd user was ignored due to low value.
         let fee_per_user = Amount::from_sat(bridge_tx.fee.to_sat() / 2);
@@ -354,22 +358,13 @@ mod tests {
             assert_eq!(user1_output.value + fee_per_user, *value);
         }
 
-        // The user 3 request amount should sent back to the bridge address
-        let bridge_address_change_output = bridge_tx.tx.output.last().unwrap();
-        assert_eq!(
-            bridge_address_change_output.value,
-            users_request_small_value
-        );
+        assert_eq!(bridge_tx.change_amount, Amount::ZERO);
 
-        // Expected outputs [user1, user2 and the OP_RETURN, change], there is no "user3" output.
-        assert_eq!(bridge_tx.tx.output.len(), 4);
+        // Expected outputs [user1, user2 and the OP_RETURN], there is no "user3" nor change output.
+        assert_eq!(bridge_tx.tx.output.len(), 3);
 
-        // The len-1 output should be the OP_RETURN
-        let op_return_output = bridge_tx
-            .tx
-            .output
-            .get(bridge_tx.tx.output.len() - 2)
-            .unwrap();
+        // The last output should be the OP_RETURN
+        let op_return_output = bridge_tx.tx.output.last().unwrap();
 
         // Check if the prefix is included
         assert!(op_return_output
@@ -421,8 +416,12 @@ mod tests {
             .sum::<Amount>()
             + bridge_tx.fee.clone();
 
-        // The total outputs with fee should be equal to the total bridge address before transaction.
-        assert_eq!(total_output_value_include_fee, total_bridge_value_before);
+        // The amount of the 3rd user is below the dust limit, it's left to the miners instead of
+        // sent back to the bridge address.
+        assert_eq!(
+            total_output_value_include_fee + users_request_small_value,
+            total_bridge_value_before
+        );
 
         // Divide the fee per 2 because the 3rd user was ignored due to low value.
         let fee_per_user = Amount::from_sat(bridge_tx.fee.to_sat() / 2);
@@ -439,22 +438,13 @@ mod tests {
             i += 1;
         }
 
-        // The user 3 request amount should sent back to the bridge address
-        let bridge_address_change_output = bridge_tx.tx.output.last().unwrap();
-        assert_eq!(
-            bridge_address_change_output.value,
-            users_request_small_value
-        );
+        assert_eq!(bridge_tx.change_amount, Amount::ZERO);
 
-        // Expected outputs [user1, user2 and the OP_RETURN, change], there is no "user3" output.
-        assert_eq!(bridge_tx.tx.output.len(), 4);
+        // Expected outputs [user1, user2 and the OP_RETURN], there is no "user3" nor change output.
+        assert_eq!(bridge_tx.tx.output.len(), 3);
 
-        // The len-1 output should be the OP_RETURN
-        let op_return_output = bridge_tx
-            .tx
-            .output
-            .get(bridge_tx.tx.output.len() - 2)
-            .unwrap();
+        // The last output should be the OP_RETURN
+        let op_return_output = bridge_tx.tx.output.last().unwrap();
 
         // Check if the prefix is included
         assert!(op_return_output
@@ -572,6 +562,7 @@ mod tests {
                     &available_utxos,
                     fee_rate,
                     fee_strategy,
+                    MAX_STANDARD_TX_WEIGHT as u64,
                 )
                 .await?;
         assert_eq!(adjusted_total_value_needed + actual_fee, total_requested);
@@ -639,6 +630,7 @@ mod tests {
                     &available_utxos,
                     fee_rate,
                     fee_strategy,
+                    MAX_STANDARD_TX_WEIGHT as u64,
                 )
                 .await?;
         assert_eq!(adjusted_total_value_needed + actual_fee, total_requested);
@@ -704,7 +696,13 @@ mod tests {
         let fee_strategy = Arc::new(WithdrawalFeeStrategy::new());
 
         let (adjusted_outputs, adjusted_total_value_needed, actual_fee, _) = builder
-            .prepare_build_transaction(outputs.clone(), &available_utxos, fee_rate, fee_strategy)
+            .prepare_build_transaction(
+                outputs.clone(),
+                &available_utxos,
+                fee_rate,
+                fee_strategy,
+                MAX_STANDARD_TX_WEIGHT as u64,
+            )
             .await?;
         // The user 2 is not included because his withdrawal value can not cover the fee
         assert_eq!(adjusted_outputs.len(), 1);
@@ -758,7 +756,13 @@ mod tests {
         let fee_strategy = Arc::new(WithdrawalFeeStrategy::new());
 
         let (adjusted_outputs, adjusted_total_value_needed, actual_fee, selected_utxos) = builder
-            .prepare_build_transaction(outputs.clone(), &available_utxos, fee_rate, fee_strategy)
+            .prepare_build_transaction(
+                outputs.clone(),
+                &available_utxos,
+                fee_rate,
+                fee_strategy,
+                MAX_STANDARD_TX_WEIGHT as u64,
+            )
             .await?;
 
         assert_eq!(adjusted_outputs.len(), 0);
@@ -0,0 +1,292 @@
+//! k-of-n script path of the bridge address.
+//!
+//! The bridge output key commits to the MuSig2 aggregated key of the verifiers (key path, n-of-n)
+//! and to a taproot tree holding a `<pk_0> CHECKSIG <pk_1> CHECKSIGADD ... <k> NUMEQUAL` leaf, so
+//! any k verifiers can spend the bridge UTXOs when some verifiers are offline.
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use anyhow::Context;
+use bitcoin::{
+    blockdata::{opcodes::all::*, script::Builder},
+    key::Keypair,
+    secp256k1::{schnorr, Message, Secp256k1, SecretKey},
+    taproot::{self, LeafVersion, TaprootBuilder, TaprootSpendInfo},
+    PublicKey, ScriptBuf, TapLeafHash, TapNodeHash, TapSighashType, Witness, XOnlyPublicKey,
+};
+use musig2::KeyAggContext;
+
+/// Builds the `threshold`-of-n Schnorr multisig leaf script of the verifiers.
+pub fn threshold_script(pubkeys: &[XOnlyPublicKey], threshold: usize) -> anyhow::Result<ScriptBuf> {
+    if threshold == 0 || threshold > pubkeys.len() {
+        anyhow::bail!(
+            "Invalid threshold {}, must be between 1 and {}",
+            threshold,
+            pubkeys.len()
+        );
+    }
+
+    let mut builder = Builder::new();
+    for (i, pubkey) in pubkeys.iter().enumerate() {
+        let opcode = if i == 0 { OP_CHECKSIG } else { OP_CHECKSIGADD };
+        builder = builder.push_x_only_key(pubkey).push_opcode(opcode);
+    }
+
+    Ok(builder
+        .push_int(threshold as i64)
+        .push_opcode(OP_NUMEQUAL)
+        .into_script())
+}
+
+/// Taproot spending data of the bridge address.
+#[derive(Debug, Clone)]
+pub struct BridgeTaproot {
+    spend_info: TaprootSpendInfo,
+    threshold_script: ScriptBuf,
+    pubkeys: Vec<XOnlyPublicKey>,
+    threshold: usize,
+}
+
+impl BridgeTaproot {
+    /// Computes the bridge taproot from the verifiers public keys (compressed hex). The
+    /// governance script, when the bridge has one, is a sibling leaf of the threshold leaf.
+    pub fn new(
+        verifiers_pub_keys: &[String],
+        threshold: usize,
+        governance_script: Option<ScriptBuf>,
+    ) -> anyhow::Result<Self> {
+        let secp = Secp256k1::verification_only();
+
+        let musig_pubkeys = verifiers_pub_keys
+            .iter()
+            .map(|key| secp256k1_musig2::PublicKey::from_str(key))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| "Invalid verifier public key")?;
+        let agg_pubkey =
+            KeyAggContext::new(musig_pubkeys)?.aggregated_pubkey::<secp256k1_musig2::PublicKey>();
+        let internal_key =
+            XOnlyPublicKey::from_slice(&agg_pubkey.x_only_public_key().0.serialize())?;
+
+        let pubkeys = verifiers_pub_keys
+            .iter()
+            .map(|key| Ok(PublicKey::from_str(key)?.inner.x_only_public_key().0))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let threshold_script = threshold_script(&pubkeys, threshold)?;
+
+        let builder = match governance_script {
+            Some(governance_script) => TaprootBuilder::new()
+                .add_leaf(1, governance_script)?
+                .add_leaf(1, threshold_script.clone())?,
+            None => TaprootBuilder::new().add_leaf(0, threshold_script.clone())?,
+        };
+        let spend_info = builder
+            .finalize(&secp, internal_key)
+            .map_err(|_| anyhow::anyhow!("Failed to finalize the bridge taproot tree"))?;
+
+        Ok(Self {
+            spend_info,
+            threshold_script,
+            pubkeys,
+            threshold,
+        })
+    }
+
+    pub fn merkle_root(&self) -> Option<TapNodeHash> {
+        self.spend_info.merkle_root()
+    }
+
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        ScriptBuf::new_p2tr_tweaked(self.spend_info.output_key())
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn threshold_script(&self) -> &ScriptBuf {
+        &self.threshold_script
+    }
+
+    pub fn threshold_leaf_hash(&self) -> TapLeafHash {
+        TapLeafHash::from_script(&self.threshold_script, LeafVersion::TapScript)
+    }
+
+    /// Verifies the script path signature of the verifier `signer_index` over `sighash`.
+    pub fn verify_signature(
+        &self,
+        signer_index: usize,
+        sighash: &[u8],
+        signature: &schnorr::Signature,
+    ) -> anyhow::Result<()> {
+        let pubkey = self
+            .pubkeys
+            .get(signer_index)
+            .with_context(|| format!("Unknown verifier index {signer_index}"))?;
+        Secp256k1::verification_only()
+            .verify_schnorr(signature, &Message::from_digest_slice(sighash)?, pubkey)
+            .with_context(|| format!("Invalid signature of verifier index {signer_index}"))
+    }
+
+    /// Builds the script path witness of an input from the verifiers signatures. The leaf
+    /// requires exactly `threshold` valid signatures, the extra ones are left out.
+    pub fn witness(
+        &self,
+        signatures: &BTreeMap<usize, schnorr::Signature>,
+    ) -> anyhow::Result<Witness> {
+        if signatures.len() < self.threshold {
+            anyhow::bail!(
+                "Not enough signatures, expected {}, got {}",
+                self.threshold,
+                signatures.len()
+            );
+        }
+
+        let control_block = self
+            .spend_info
+            .control_block(&(self.threshold_script.clone(), LeafVersion::TapScript))
+            .with_context(|| "Threshold leaf missing from the bridge taproot tree")?;
+
+        let signers: Vec<usize> = signatures.keys().take(self.threshold).copied().collect();
+
+        // The first key of the script consumes the top of the stack, push the signatures in
+        // reverse order.
+        let mut witness = Witness::new();
+        for signer_index in (0..self.pubkeys.len()).rev() {
+            match signatures.get(&signer_index) {
+                Some(signature) if signers.contains(&signer_index) => {
+                    let signature = taproot::Signature {
+                        signature: *signature,
+                        sighash_type: TapSighashType::All,
+                    };
+                    witness.push(signature.to_vec());
+                }
+                _ => witness.push(&[]),
+            }
+        }
+        witness.push(self.threshold_script.as_bytes());
+        witness.push(control_block.serialize());
+
+        Ok(witness)
+    }
+
+    /// Builds a script path witness of the size of a signed one, used to estimate the fee of the
+    /// transaction before the verifiers sign it.
+    pub fn placeholder_witness(&self) -> anyhow::Result<Witness> {
+        let signature = schnorr::Signature::from_slice(&[1u8; 64])?;
+        let signatures = (0..self.threshold)
+            .map(|signer_index| (signer_index, signature))
+            .collect();
+        self.witness(&signatures)
+    }
+}
+
+/// Signs a script path sighash of the bridge with the verifier key.
+pub fn sign_script_path(
+    secret_key: &SecretKey,
+    sighash: &[u8],
+) -> anyhow::Result<schnorr::Signature> {
+    let secp = Secp256k1::new();
+    let keypair = Keypair::from_secret_key(&secp, secret_key);
+    Ok(secp.sign_schnorr(&Message::from_digest_slice(sighash)?, &keypair))
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, PrivateKey};
+
+    use super::*;
+
+    const PRIVATE_KEYS: [&str; 3] = [
+        "cVZduZu265sWeAqFYygoDEE1FZ7wV9rpW5qdqjRkUehjaUMWLT1R",
+        "cUWA5dZXc6NwLovW3Kr9DykfY5ysFigKZM5Annzty7J8a43Fe2YF",
+        "cRaUbRSn8P8cXUcg6cMZ7oTZ1wbDjktYTsbdGw62tuqqD9ttQWMm",
+    ];
+
+    fn verifiers() -> (Vec<SecretKey>, Vec<String>) {
+        let secp = Secp256k1::new();
+        PRIVATE_KEYS
+            .iter()
+            .map(|wif| {
+                let private_key = PrivateKey::from_wif(wif).unwrap();
+                let pubkey = private_key.public_key(&secp).to_string();
+                (private_key.inner, pubkey)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn test_threshold_script() {
+        let (_, pubkeys) = verifiers();
+        let xonly: Vec<_> = pubkeys
+            .iter()
+            .map(|key| {
+                PublicKey::from_str(key)
+                    .unwrap()
+                    .inner
+                    .x_only_public_key()
+                    .0
+            })
+            .collect();
+
+        let script = threshold_script(&xonly, 2).unwrap();
+        let instructions: Vec<_> = script.instructions().collect::<Result<_, _>>().unwrap();
+        // 3 keys with their CHECKSIG(ADD), the threshold and NUMEQUAL.
+        assert_eq!(instructions.len(), 8);
+
+        assert!(threshold_script(&xonly, 0).is_err());
+        assert!(threshold_script(&xonly, 4).is_err());
+    }
+
+    #[test]
+    fn test_threshold_witness() {
+        let (secret_keys, pubkeys) = verifiers();
+        let taproot = BridgeTaproot::new(&pubkeys, 2, None).unwrap();
+        assert!(taproot.merkle_root().is_some());
+
+        let sighash = [7u8; 32];
+        let mut signatures = BTreeMap::new();
+        for (index, secret_key) in secret_keys.iter().enumerate().skip(1) {
+            let signature = sign_script_path(secret_key, &sighash).unwrap();
+            taproot
+                .verify_signature(index, &sighash, &signature)
+                .unwrap();
+            signatures.insert(index, signature);
+        }
+        assert!(taproot
+            .verify_signature(0, &sighash, &signatures[&1])
+            .is_err());
+
+        let witness = taproot.witness(&signatures).unwrap();
+        // A slot per verifier, the script and the control block.
+        assert_eq!(witness.len(), 5);
+        // Verifier 2 is on the top of the signatures, verifier 0 didn't sign.
+        assert_eq!(witness.nth(0).unwrap().len(), 65);
+        assert_eq!(witness.nth(1).unwrap().len(), 65);
+        assert!(witness.nth(2).unwrap().is_empty());
+        assert_eq!(
+            witness.nth(3).unwrap(),
+            taproot.threshold_script().as_bytes()
+        );
+
+        signatures.remove(&1);
+        assert!(taproot.witness(&signatures).is_err());
+    }
+
+    #[test]
+    fn test_governance_leaf_changes_the_address() {
+        let (_, pubkeys) = verifiers();
+        let governance_script = Builder::new().push_opcode(OP_PUSHNUM_1).into_script();
+
+        let taproot = BridgeTaproot::new(&pubkeys, 2, None).unwrap();
+        let with_governance = BridgeTaproot::new(&pubkeys, 2, Some(governance_script)).unwrap();
+        assert_ne!(taproot.script_pubkey(), with_governance.script_pubkey());
+        assert_eq!(
+            taproot.threshold_leaf_hash(),
+            with_governance.threshold_leaf_hash()
+        );
+        assert_ne!(
+            taproot.merkle_root().unwrap().to_byte_array(),
+            with_governance.merkle_root().unwrap().to_byte_array()
+        );
+    }
+}
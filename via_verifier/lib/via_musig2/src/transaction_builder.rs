@@ -6,8 +6,8 @@ use bitcoin::{
     hashes::Hash,
     script::PushBytesBuf,
     sighash::{Prevouts, SighashCache},
-    transaction, Address, Amount, OutPoint, ScriptBuf, Sequence, TapSighashType, Transaction, TxIn,
-    TxOut, Witness,
+    transaction, Address, Amount, OutPoint, ScriptBuf, Sequence, TapLeafHash, TapSighashType,
+    Transaction, TxIn, TxOut, Witness,
 };
 use tracing::instrument;
 use via_btc_client::traits::BitcoinOps;
@@ -19,14 +19,17 @@ use crate::{
         WITNESS_OVERHEAD,
     },
     fee::FeeStrategy,
+    threshold::BridgeTaproot,
     types::TransactionMetadata,
-    utxo_manager::UtxoManager,
+    utxo_manager::{ConsolidationPolicy, UtxoManager},
 };
 
 #[derive(Debug, Clone)]
 pub struct TransactionBuilder {
     pub utxo_manager: UtxoManager,
     pub bridge_address: Address,
+    /// Max amount sent by a transaction, the outputs above it are split into several transactions.
+    max_tx_outflow: Option<Amount>,
 }
 
 impl TransactionBuilder {
@@ -42,9 +45,32 @@ impl TransactionBuilder {
         Ok(Self {
             utxo_manager,
             bridge_address,
+            max_tx_outflow: None,
         })
     }
 
+    pub fn with_consolidation_policy(mut self, consolidation_policy: ConsolidationPolicy) -> Self {
+        self.utxo_manager = self
+            .utxo_manager
+            .with_consolidation_policy(consolidation_policy);
+        self
+    }
+
+    pub fn with_max_tx_outflow(mut self, max_tx_outflow: Option<Amount>) -> Self {
+        self.max_tx_outflow = max_tx_outflow;
+        self
+    }
+
+    /// Whether the outputs exceed the max amount sent by a transaction. A single output can't be
+    /// split further, so it always fits.
+    fn exceeds_max_tx_outflow(&self, outputs: &[TxOut]) -> bool {
+        let Some(max_tx_outflow) = self.max_tx_outflow else {
+            return false;
+        };
+        outputs.len() > 1
+            && outputs.iter().map(|output| output.value).sum::<Amount>() > max_tx_outflow
+    }
+
     #[instrument(
         skip(self, outputs, op_return_data, fee_strategy),
         target = "bitcoin_transaction_builder"
@@ -113,6 +139,13 @@ impl TransactionBuilder {
         base_size * 4 + witness_size
     }
 
+    /// Max number of inputs of a transaction with `outputs` outputs within `max_tx_weight`.
+    pub(crate) fn max_input_count(&self, outputs: u64, max_tx_weight: u64) -> usize {
+        let fixed_weight = self.estimate_transaction_weight(0, outputs) + WITNESS_OVERHEAD;
+        let input_weight = INPUT_BASE_SIZE * 4 + INPUT_WITNESS_SIZE;
+        (max_tx_weight.saturating_sub(fixed_weight) / input_weight) as usize
+    }
+
     pub(crate) fn chunk_outputs(outputs: &[TxOut], chunks: usize) -> Vec<Vec<TxOut>> {
         let mut result = Vec::new();
         let mut start = 0;
@@ -141,6 +174,13 @@ impl TransactionBuilder {
             let mut result = Vec::new();
             let mut all_chunks_fit = true;
 
+            if output_chunks
+                .iter()
+                .any(|output_chunk| self.exceeds_max_tx_outflow(output_chunk))
+            {
+                continue;
+            }
+
             for output_chunk in output_chunks {
                 let (adjusted_outputs, adjusted_total_value_needed, actual_fee, selected_utxos) =
                     self.prepare_build_transaction(
@@ -148,6 +188,7 @@ impl TransactionBuilder {
                         &utxos_pool,
                         fee_rate,
                         fee_strategy.clone(),
+                        max_tx_weight,
                     )
                     .await?;
 
@@ -249,12 +290,17 @@ impl TransactionBuilder {
                     anyhow::anyhow!("Change amount calculation overflow in tx index {}", i)
                 })?;
 
-            if change_amount.to_sat() > 0 {
+            // A change below the dust limit isn't relayed, it's left to the miners instead.
+            let change_script = self.bridge_address.script_pubkey();
+            let change_amount = if change_amount >= change_script.minimal_non_dust() {
                 outputs.push(TxOut {
                     value: change_amount,
-                    script_pubkey: self.bridge_address.script_pubkey(),
+                    script_pubkey: change_script,
                 });
-            }
+                change_amount
+            } else {
+                Amount::ZERO
+            };
 
             // Build unsigned transaction
             let unsigned_tx = Transaction {
@@ -287,6 +333,7 @@ impl TransactionBuilder {
         available_utxos: &[(OutPoint, TxOut)],
         fee_rate: u64,
         fee_strategy: Arc<dyn FeeStrategy>,
+        max_tx_weight: u64,
     ) -> anyhow::Result<(Vec<TxOut>, Amount, Amount, Vec<(OutPoint, TxOut)>)> {
         let adjusted_outputs = outputs;
 
@@ -296,9 +343,10 @@ impl TransactionBuilder {
             .sum::<Amount>();
 
         // Select UTXOs for the total amount including fee
+        let max_inputs = self.max_input_count(adjusted_outputs.len() as u64, max_tx_weight);
         let selected_utxos = self
             .utxo_manager
-            .select_utxos_by_target_value(&available_utxos, total_needed)
+            .select_utxos_by_target_value(&available_utxos, total_needed, fee_rate, max_inputs)
             .await?;
 
         tracing::debug!("Selected UTXOs {:?}", &selected_utxos);
@@ -345,6 +393,110 @@ impl TransactionBuilder {
         Ok(sighashes)
     }
 
+    /// Sighashes of the inputs spent through the `leaf_hash` script path of the bridge.
+    #[instrument(skip(self, unsigned_tx), target = "bitcoin_transaction_builder")]
+    pub fn get_tr_script_sighashes(
+        &self,
+        unsigned_tx: &UnsignedBridgeTx,
+        leaf_hash: TapLeafHash,
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
+        let mut sighash_cache = SighashCache::new(&unsigned_tx.tx);
+
+        let txout_list: Vec<TxOut> = unsigned_tx
+            .utxos
+            .iter()
+            .map(|(_, txout)| txout.clone())
+            .collect();
+
+        let mut sighashes = vec![];
+        for (i, _) in txout_list.iter().enumerate() {
+            let sighash = sighash_cache
+                .taproot_script_spend_signature_hash(
+                    i,
+                    &Prevouts::All(&txout_list),
+                    leaf_hash,
+                    TapSighashType::All,
+                )
+                .with_context(|| "Error taproot_script_spend_signature_hash")?;
+            sighashes.push(sighash.to_raw_hash().to_byte_array().to_vec());
+        }
+
+        Ok(sighashes)
+    }
+
+    /// Builds the transaction spending the inputs of `unsigned_tx` through the k-of-n script path
+    /// of the bridge. Its witness is larger than the key path signature the fee was estimated
+    /// for, so the missing fee is taken from the change, dropped when it falls below the dust
+    /// limit. It's derived from the session transaction only, the verifiers sign the same one.
+    pub fn build_script_path_tx(
+        &self,
+        unsigned_tx: &UnsignedBridgeTx,
+        bridge_taproot: &BridgeTaproot,
+    ) -> anyhow::Result<UnsignedBridgeTx> {
+        let mut tx = unsigned_tx.tx.clone();
+        let witness = bridge_taproot.placeholder_witness()?;
+        for input in &mut tx.input {
+            input.witness = witness.clone();
+        }
+        let required_fee = Amount::from_sat(unsigned_tx.fee_rate * tx.vsize() as u64);
+
+        let total_input_amount = unsigned_tx
+            .utxos
+            .iter()
+            .map(|(_, txout)| txout.value)
+            .sum::<Amount>();
+        let total_output_amount = tx.output.iter().map(|txout| txout.value).sum::<Amount>();
+        let fee = total_input_amount
+            .checked_sub(total_output_amount)
+            .with_context(|| "Outputs of the bridge transaction exceed its inputs")?;
+
+        for input in &mut tx.input {
+            input.witness = Witness::default();
+        }
+
+        let mut script_path_tx = unsigned_tx.clone();
+        if fee < required_fee {
+            let missing_fee = required_fee - fee;
+            let change_script = self.bridge_address.script_pubkey();
+            let change_index = tx
+                .output
+                .iter()
+                .rposition(|txout| txout.script_pubkey == change_script)
+                .filter(|_| unsigned_tx.change_amount > Amount::ZERO)
+                .with_context(|| {
+                    format!(
+                        "Bridge transaction {} has no change to pay the script path fee {}",
+                        unsigned_tx.txid, missing_fee
+                    )
+                })?;
+
+            let change_amount = tx.output[change_index]
+                .value
+                .checked_sub(missing_fee)
+                .with_context(|| {
+                    format!(
+                        "Change of the bridge transaction {} can't pay the script path fee {}",
+                        unsigned_tx.txid, missing_fee
+                    )
+                })?;
+
+            // A change below the dust limit isn't relayed, it's left to the miners instead.
+            if change_amount >= change_script.minimal_non_dust() {
+                tx.output[change_index].value = change_amount;
+                script_path_tx.change_amount = change_amount;
+            } else {
+                tx.output.remove(change_index);
+                script_path_tx.change_amount = Amount::ZERO;
+            }
+            script_path_tx.fee =
+                total_input_amount - tx.output.iter().map(|txout| txout.value).sum::<Amount>();
+        }
+
+        script_path_tx.txid = tx.compute_txid();
+        script_path_tx.tx = tx;
+        Ok(script_path_tx)
+    }
+
     // Helper function to create OP_RETURN script
     pub fn create_op_return_script(prefix: &[u8], inputs: Vec<&[u8]>) -> Result<ScriptBuf> {
         let total_input_size: usize = inputs.iter().map(|input| input.len()).sum();
@@ -4,8 +4,30 @@ use bitcoin::{Address, Amount, OutPoint, Transaction, TxOut};
 use tokio::sync::RwLock;
 use via_btc_client::traits::BitcoinOps;
 
+use crate::constants::{INPUT_WEIGHT, OUTPUT_SIZE};
+
 const CTX_REQUIRED_CONFIRMATIONS: u32 = 1;
 const DEFAULT_CAPACITY: usize = 100;
+/// Max number of branches explored by the branch and bound selection.
+const BNB_MAX_TRIES: usize = 100_000;
+
+/// When the bridge UTXOs are worth consolidating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsolidationPolicy {
+    /// Max fee rate (sat/vB) at which the UTXOs are consolidated.
+    pub max_fee_rate: u64,
+    /// Only the UTXOs below this amount are consolidated.
+    pub max_utxo_amount: Amount,
+}
+
+impl Default for ConsolidationPolicy {
+    fn default() -> Self {
+        Self {
+            max_fee_rate: 2,
+            max_utxo_amount: Amount::from_sat(100_000),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct UtxoManager {
@@ -19,6 +41,8 @@ pub struct UtxoManager {
     minimum_amount: Amount,
     /// The maximum number of utxos to merge in a single tx
     merge_limit: usize,
+    /// When the utxos are merged
+    consolidation_policy: ConsolidationPolicy,
 }
 
 impl UtxoManager {
@@ -34,9 +58,15 @@ impl UtxoManager {
             context: Arc::new(RwLock::new(VecDeque::with_capacity(DEFAULT_CAPACITY))),
             minimum_amount,
             merge_limit,
+            consolidation_policy: ConsolidationPolicy::default(),
         }
     }
 
+    pub fn with_consolidation_policy(mut self, consolidation_policy: ConsolidationPolicy) -> Self {
+        self.consolidation_policy = consolidation_policy;
+        self
+    }
+
     pub async fn get_available_utxos(&self) -> anyhow::Result<Vec<(OutPoint, TxOut)>> {
         // fetch utxos from client
         let mut utxos = self.btc_client.fetch_utxos(&self.address).await?;
@@ -79,27 +109,31 @@ impl UtxoManager {
         Ok(utxos)
     }
 
+    /// Selects the UTXOs funding `target_amount` with at most `max_inputs` inputs. The selection
+    /// only depends on the given UTXOs, so every verifier selects the same ones, and selecting
+    /// again from the selected UTXOs gives the same result.
     pub async fn select_utxos_by_target_value(
         &self,
         utxos: &[(OutPoint, TxOut)],
         target_amount: Amount,
+        fee_rate: u64,
+        max_inputs: usize,
     ) -> anyhow::Result<Vec<(OutPoint, TxOut)>> {
-        // Simple implementation - could be improved with better UTXO selection algorithm
-        let mut selected = Vec::new();
-        let mut total = Amount::ZERO;
-
-        for utxo in utxos {
-            selected.push(utxo.clone());
-            total = total
-                .checked_add(utxo.1.value)
-                .ok_or_else(|| anyhow::anyhow!("Amount overflow during UTXO selection"))?;
-
-            if total >= target_amount {
-                break;
-            }
-        }
-
-        if total < target_amount {
+        // A zero-value UTXO only adds to the fee, and would stall the search below.
+        let mut candidates: Vec<_> = utxos
+            .iter()
+            .filter(|(_, txout)| txout.value > Amount::ZERO)
+            .cloned()
+            .collect();
+        // Largest first, the ties are broken by outpoint.
+        candidates.sort_by(|a, b| b.1.value.cmp(&a.1.value).then_with(|| a.0.cmp(&b.0)));
+
+        let total = candidates
+            .iter()
+            .try_fold(Amount::ZERO, |acc, (_, txout)| acc.checked_add(txout.value))
+            .ok_or_else(|| anyhow::anyhow!("Amount overflow during UTXO selection"))?;
+
+        if total < target_amount || candidates.is_empty() {
             return Err(anyhow::anyhow!(
                 "Insufficient funds: have {}, need {}",
                 total,
@@ -107,22 +141,57 @@ impl UtxoManager {
             ));
         }
 
-        Ok(selected)
-    }
+        let values: Vec<u64> = candidates
+            .iter()
+            .map(|(_, txout)| txout.value.to_sat())
+            .collect();
+        let input_cost = input_cost(fee_rate);
+        let cost_of_change = fee_rate * OUTPUT_SIZE + input_cost;
+
+        // When no selection leaves a change below its cost, or none fits in `max_inputs`, fall
+        // back to the fewest inputs. The caller rejects the transaction if it's still too heavy.
+        // A change below the dust limit is added to the fee by the transaction builder.
+        let selected = branch_and_bound(
+            &values,
+            target_amount.to_sat(),
+            input_cost,
+            cost_of_change,
+            max_inputs,
+        )
+        .unwrap_or_else(|| largest_first(&values, target_amount.to_sat()));
 
-    pub async fn get_utxos_to_merge(&self) -> anyhow::Result<Vec<(OutPoint, TxOut)>> {
-        let mut utxos_to_merge = Vec::new();
-        let available_utxos = self.get_available_utxos().await?;
+        Ok(selected
+            .into_iter()
+            .map(|index| candidates[index].clone())
+            .collect())
+    }
 
-        // If the amount is greater than the minimum amount to merge
-        for (outpoint, txout) in available_utxos.iter() {
-            if txout.value >= self.minimum_amount {
-                utxos_to_merge.push((*outpoint, txout.clone()));
-            }
-            if utxos_to_merge.len() == self.merge_limit {
-                break;
-            }
+    /// Returns the small UTXOs worth merging at `fee_rate`, none when the fee rate is above the
+    /// consolidation policy. The smallest UTXOs are merged first.
+    pub async fn get_utxos_to_merge(
+        &self,
+        fee_rate: u64,
+    ) -> anyhow::Result<Vec<(OutPoint, TxOut)>> {
+        if fee_rate > self.consolidation_policy.max_fee_rate {
+            return Ok(vec![]);
         }
+
+        // A UTXO which doesn't pay for its own input only loses value when merged.
+        let input_cost = Amount::from_sat(input_cost(fee_rate));
+
+        let mut utxos_to_merge: Vec<(OutPoint, TxOut)> = self
+            .get_available_utxos()
+            .await?
+            .into_iter()
+            .filter(|(_, txout)| {
+                txout.value >= self.minimum_amount
+                    && txout.value > input_cost
+                    && txout.value < self.consolidation_policy.max_utxo_amount
+            })
+            .collect();
+        utxos_to_merge.sort_by(|a, b| a.1.value.cmp(&b.1.value).then_with(|| a.0.cmp(&b.0)));
+        utxos_to_merge.truncate(self.merge_limit);
+
         if utxos_to_merge.len() > 1 {
             return Ok(utxos_to_merge);
         }
@@ -173,6 +242,100 @@ impl UtxoManager {
     }
 }
 
+/// Fee of spending a bridge UTXO at `fee_rate`, the witness only counts for a quarter of its size.
+fn input_cost(fee_rate: u64) -> u64 {
+    fee_rate * INPUT_WEIGHT.div_ceil(4)
+}
+
+/// Depth-first search of the selection with the lowest waste (input fees and change), among
+/// the ones whose change is below `cost_of_change`. `values` are sorted from big to small, the
+/// returned indexes are sorted.
+fn branch_and_bound(
+    values: &[u64],
+    target: u64,
+    input_cost: u64,
+    cost_of_change: u64,
+    max_inputs: usize,
+) -> Option<Vec<usize>> {
+    // remaining[i] is the total value of the UTXOs from index i.
+    let mut remaining = vec![0u64; values.len() + 1];
+    for i in (0..values.len()).rev() {
+        remaining[i] = remaining[i + 1].saturating_add(values[i]);
+    }
+    let upper_bound = target.saturating_add(cost_of_change);
+
+    let mut best: Option<(u64, Vec<usize>)> = None;
+    let mut selected: Vec<usize> = Vec::new();
+    let mut value = 0u64;
+    let mut index = 0;
+
+    for _ in 0..BNB_MAX_TRIES {
+        let backtrack = if value > upper_bound || value.saturating_add(remaining[index]) < target {
+            true
+        } else if value >= target && !selected.is_empty() {
+            let waste = selected.len() as u64 * input_cost + (value - target);
+            if best
+                .as_ref()
+                .map_or(true, |(best_waste, _)| waste < *best_waste)
+            {
+                best = Some((waste, selected.clone()));
+            }
+            true
+        } else if index == values.len() {
+            true
+        } else {
+            // The next UTXO is the largest left, it bounds the number of inputs still needed.
+            let needed = (target.saturating_sub(value))
+                .div_ceil(values[index])
+                .max(1) as usize;
+            let inputs = selected.len() + needed;
+            inputs > max_inputs
+                || best.as_ref().map_or(false, |(best_waste, _)| {
+                    inputs as u64 * input_cost >= *best_waste
+                })
+        };
+
+        if backtrack {
+            // Leave out the last selected UTXO and try the next ones.
+            let Some(last) = selected.pop() else {
+                break;
+            };
+            value -= values[last];
+            index = last + 1;
+        } else {
+            selected.push(index);
+            value += values[index];
+            index += 1;
+        }
+    }
+
+    best.map(|(_, selected)| selected)
+}
+
+/// Selects the largest UTXOs until the target is reached, the last one is replaced by the
+/// smallest UTXO which still reaches it. `values` are sorted from big to small.
+fn largest_first(values: &[u64], target: u64) -> Vec<usize> {
+    let mut selected = Vec::new();
+    let mut value = 0u64;
+    for (index, utxo_value) in values.iter().enumerate() {
+        if value >= target && !selected.is_empty() {
+            break;
+        }
+        selected.push(index);
+        value = value.saturating_add(*utxo_value);
+    }
+
+    if let Some(last) = selected.pop() {
+        let missing = target.saturating_sub(value - values[last]);
+        let smallest = (last..values.len())
+            .rev()
+            .find(|&index| values[index] >= missing)
+            .unwrap_or(last);
+        selected.push(smallest);
+    }
+    selected
+}
+
 #[cfg(test)]
 mod tests {
     use std::{str::FromStr, sync::Arc};
@@ -254,9 +417,10 @@ mod tests {
 
         let client = Arc::new(MockBitcoinOps::new(config));
         let manager = UtxoManager::new(client, bridge_address, Amount::from_sat(500), 100);
-        let utxos_out = manager.get_utxos_to_merge().await.unwrap();
+        let utxos_out = manager.get_utxos_to_merge(1).await.unwrap();
 
-        assert_eq!(utxos, utxos_out);
+        // The smallest UTXOs are merged first.
+        assert_eq!(vec![utxos[1].clone(), utxos[0].clone()], utxos_out);
     }
 
     #[tokio::test]
@@ -290,7 +454,7 @@ mod tests {
         let client = Arc::new(MockBitcoinOps::new(config));
         let merge_limit = 2;
         let manager = UtxoManager::new(client, bridge_address, Amount::from_sat(0), merge_limit);
-        let utxos_out = manager.get_utxos_to_merge().await.unwrap();
+        let utxos_out = manager.get_utxos_to_merge(1).await.unwrap();
 
         assert_eq!(utxos_out.len(), merge_limit);
     }
@@ -335,7 +499,7 @@ mod tests {
 
         let client = Arc::new(MockBitcoinOps::new(config));
         let manager = UtxoManager::new(client, bridge_address, Amount::from_sat(500), 100);
-        let utxos_out = manager.get_utxos_to_merge().await.unwrap();
+        let utxos_out = manager.get_utxos_to_merge(1).await.unwrap();
         let expected_utxos = vec![utxos[1].clone(), utxos[2].clone()];
         assert_eq!(expected_utxos, utxos_out);
     }
@@ -358,7 +522,7 @@ mod tests {
 
         let client = Arc::new(MockBitcoinOps::new(config));
         let manager = UtxoManager::new(client, bridge_address, Amount::from_sat(500), 100);
-        let utxos_out = manager.get_utxos_to_merge().await.unwrap();
+        let utxos_out = manager.get_utxos_to_merge(1).await.unwrap();
         let expected_utxos: Vec<(OutPoint, TxOut)> = vec![];
         assert_eq!(expected_utxos, utxos_out);
     }
@@ -532,4 +696,151 @@ mod tests {
             assert!(sorted_utxos[i - 1].1.value >= sorted_utxos[i].1.value);
         }
     }
+
+    fn utxos_with_values(values: &[u64]) -> Vec<(OutPoint, TxOut)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                (
+                    OutPoint {
+                        txid: Txid::all_zeros(),
+                        vout: i as u32,
+                    },
+                    TxOut {
+                        value: Amount::from_sat(*value),
+                        script_pubkey: bridge_address().script_pubkey(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn manager_with_utxos(utxos: Vec<(OutPoint, TxOut)>) -> UtxoManager {
+        let mut config = MockBitcoinOpsConfig::default();
+        config.set_utxos(utxos);
+        UtxoManager::new(
+            Arc::new(MockBitcoinOps::new(config)),
+            bridge_address(),
+            Amount::ZERO,
+            100,
+        )
+    }
+
+    fn values(utxos: &[(OutPoint, TxOut)]) -> Vec<u64> {
+        utxos
+            .iter()
+            .map(|(_, txout)| txout.value.to_sat())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_get_utxos_to_merge_when_fee_rate_above_policy() {
+        let utxos = utxos_with_values(&[600, 500]);
+        let manager = manager_with_utxos(utxos);
+
+        assert_eq!(manager.get_utxos_to_merge(2).await.unwrap().len(), 2);
+        assert!(manager.get_utxos_to_merge(3).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_utxos_to_merge_only_small_utxos() {
+        let utxos = utxos_with_values(&[600, 500, 400, 50]);
+        let manager =
+            manager_with_utxos(utxos.clone()).with_consolidation_policy(ConsolidationPolicy {
+                max_fee_rate: 2,
+                max_utxo_amount: Amount::from_sat(550),
+            });
+
+        // 600 is above the policy amount and 50 doesn't pay for its input.
+        let utxos_out = manager.get_utxos_to_merge(1).await.unwrap();
+        assert_eq!(values(&utxos_out), vec![400, 500]);
+    }
+
+    #[tokio::test]
+    async fn test_get_utxos_to_merge_paying_for_input_vsize() {
+        // An input weighs 229 WU, 58 vB.
+        assert_eq!(input_cost(1), 58);
+
+        let utxos = utxos_with_values(&[600, 100, 58]);
+        let manager = manager_with_utxos(utxos);
+
+        let utxos_out = manager.get_utxos_to_merge(1).await.unwrap();
+        assert_eq!(values(&utxos_out), vec![100, 600]);
+    }
+
+    #[tokio::test]
+    async fn test_select_utxos_minimises_change() {
+        let utxos = utxos_with_values(&[10000, 6000, 4000, 3000]);
+        let manager = manager_with_utxos(vec![]);
+
+        let selected = manager
+            .select_utxos_by_target_value(&utxos, Amount::from_sat(7000), 1, 100)
+            .await
+            .unwrap();
+        assert_eq!(values(&selected), vec![4000, 3000]);
+
+        // A single input can't match the target, the smallest UTXO covering it is used.
+        let selected = manager
+            .select_utxos_by_target_value(&utxos, Amount::from_sat(5000), 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(values(&selected), vec![6000]);
+
+        assert!(manager
+            .select_utxos_by_target_value(&utxos, Amount::from_sat(23001), 1, 100)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_select_utxos_skips_zero_value_utxos() {
+        let utxos = utxos_with_values(&[0, 5000, 0, 3000]);
+        let manager = manager_with_utxos(vec![]);
+
+        let selected = manager
+            .select_utxos_by_target_value(&utxos, Amount::from_sat(7000), 1, 100)
+            .await
+            .unwrap();
+        assert_eq!(values(&selected), vec![5000, 3000]);
+
+        assert!(manager
+            .select_utxos_by_target_value(&utxos_with_values(&[0, 0]), Amount::ZERO, 1, 100)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_select_utxos_is_reproducible() {
+        let utxos = utxos_with_values(&[
+            91_000, 52_000, 48_500, 33_000, 21_000, 20_000, 12_345, 9_000, 5_500, 1_200,
+        ]);
+        let manager = manager_with_utxos(vec![]);
+
+        for target in [1_000, 25_000, 60_000, 100_000, 150_000] {
+            let selected = manager
+                .select_utxos_by_target_value(&utxos, Amount::from_sat(target), 2, 100)
+                .await
+                .unwrap();
+
+            let mut shuffled = utxos.clone();
+            shuffled.reverse();
+            assert_eq!(
+                manager
+                    .select_utxos_by_target_value(&shuffled, Amount::from_sat(target), 2, 100)
+                    .await
+                    .unwrap(),
+                selected
+            );
+
+            // The verifiers select again from the UTXOs of the session.
+            assert_eq!(
+                manager
+                    .select_utxos_by_target_value(&selected, Amount::from_sat(target), 2, 100)
+                    .await
+                    .unwrap(),
+                selected
+            );
+        }
+    }
 }
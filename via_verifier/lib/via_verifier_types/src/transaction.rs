@@ -1,7 +1,9 @@
-use bincode::{deserialize, serialize};
 use bitcoin::{Amount, OutPoint, Transaction, TxOut, Txid};
 use serde::{Deserialize, Serialize};
-use via_btc_client::traits::Serializable;
+use via_btc_client::{
+    traits::Serializable,
+    wire::{self, WireFormatResult},
+};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UnsignedBridgeTx {
@@ -14,8 +16,14 @@ pub struct UnsignedBridgeTx {
 }
 
 impl UnsignedBridgeTx {
+    /// Number of withdrawal outputs, without the OP_RETURN output and the change output if any.
+    fn withdrawals_count(&self) -> u64 {
+        let change_outputs = u64::from(self.change_amount > Amount::ZERO);
+        (self.tx.output.len() as u64).saturating_sub(1 + change_outputs)
+    }
+
     pub fn get_fee_per_user(&self) -> Amount {
-        let withdrawals_count = self.tx.output.len() as u64 - 2;
+        let withdrawals_count = self.withdrawals_count();
         if withdrawals_count == 0 {
             return self.fee;
         }
@@ -23,27 +31,28 @@ impl UnsignedBridgeTx {
     }
 
     pub fn is_empty(&self) -> bool {
-        self.tx.output.len() as u64 - 2 == 0
+        self.withdrawals_count() == 0
     }
 
-    pub fn to_vec(unsigned_bridge_txs_bytes: Vec<Vec<u8>>) -> Vec<UnsignedBridgeTx> {
-        let mut unsigned_bridge_txs = vec![];
-        for unsigned_bridge_tx in unsigned_bridge_txs_bytes {
-            unsigned_bridge_txs.push(UnsignedBridgeTx::from_bytes(&unsigned_bridge_tx))
-        }
-        unsigned_bridge_txs
+    pub fn to_vec(
+        unsigned_bridge_txs_bytes: Vec<Vec<u8>>,
+    ) -> WireFormatResult<Vec<UnsignedBridgeTx>> {
+        unsigned_bridge_txs_bytes
+            .iter()
+            .map(|bytes| UnsignedBridgeTx::from_bytes(bytes))
+            .collect()
     }
 }
 
 impl Serializable for UnsignedBridgeTx {
-    fn to_bytes(&self) -> Vec<u8> {
-        serialize(self).expect("error serialize the UnsignedBridgeTx")
+    fn to_bytes(&self) -> WireFormatResult<Vec<u8>> {
+        wire::encode(self)
     }
 
-    fn from_bytes(bytes: &[u8]) -> Self
+    fn from_bytes(bytes: &[u8]) -> WireFormatResult<Self>
     where
         Self: Sized,
     {
-        deserialize(bytes).expect("error deserialize the UnsignedBridgeTx")
+        wire::decode(bytes)
     }
 }
@@ -2,7 +2,7 @@ use std::{env, str::FromStr};
 
 use anyhow::{Context, Result};
 use tracing::info;
-use via_da_clients::celestia::client::CelestiaClient;
+use via_da_clients::celestia::{self, client::CelestiaClient};
 use via_withdrawal_client::client::WithdrawalClient;
 use zksync_config::{
     configs::{via_celestia::ProofSendingMode, via_secrets::ViaDASecrets},
@@ -67,7 +67,7 @@ async fn main() -> Result<()> {
     let withdrawal_client = WithdrawalClient::new(da_client, bitcoin::Network::Regtest);
 
     let withdrawals = withdrawal_client
-        .get_withdrawals(header.blob_id.as_str())
+        .get_withdrawals(celestia::DA_IDENTIFIER, header.blob_id.as_str())
         .await?;
 
     info!("--------------------------------------------------------");
@@ -3,6 +3,7 @@ use std::{collections::HashMap, str::FromStr};
 use anyhow::Context;
 use bitcoin::Network;
 use via_da_client::{
+    envelope::decode_blob,
     pubdata::Pubdata,
     types::{L2BridgeLogMetadata, L2_BASE_TOKEN_SYSTEM_CONTRACT_ADDR},
 };
@@ -23,9 +24,15 @@ impl WithdrawalClient {
         Self { client, network }
     }
 
-    pub async fn get_withdrawals(&self, blob_id: &str) -> anyhow::Result<Vec<WithdrawalRequest>> {
+    /// Returns the withdrawals of the pubdata blob `blob_id`, dispatched to the DA layer
+    /// `da_identifier` of its L1 batch inscription.
+    pub async fn get_withdrawals(
+        &self,
+        da_identifier: &str,
+        blob_id: &str,
+    ) -> anyhow::Result<Vec<WithdrawalRequest>> {
         let pubdata_bytes = self
-            .fetch_pubdata(blob_id)
+            .fetch_pubdata(da_identifier, blob_id)
             .await
             .with_context(|| "Failed to fetch pubdata from DA")?;
         let pubdata = Pubdata::decode_pubdata(pubdata_bytes)?;
@@ -34,10 +41,13 @@ impl WithdrawalClient {
         Ok(withdrawals)
     }
 
-    async fn fetch_pubdata(&self, blob_id: &str) -> anyhow::Result<Vec<u8>> {
-        let response = self.client.get_inclusion_data(blob_id).await?;
+    async fn fetch_pubdata(&self, da_identifier: &str, blob_id: &str) -> anyhow::Result<Vec<u8>> {
+        let response = self
+            .client
+            .get_inclusion_data_from(da_identifier, blob_id)
+            .await?;
         if let Some(inclusion_data) = response {
-            return Ok(inclusion_data.data);
+            return decode_blob(inclusion_data.data);
         };
         Ok(Vec::new())
     }
@@ -1,2 +1,2 @@
 pub mod client;
-mod withdraw;
+pub mod withdraw;
@@ -201,7 +201,8 @@ impl ViaBtcInscriptionManager {
             .await? as i64;
 
         let input =
-            InscriptionMessage::from_bytes(&tx.inscription_message.clone().unwrap_or_default());
+            InscriptionMessage::from_bytes(&tx.inscription_message.clone().unwrap_or_default())
+                .with_context(|| format!("Malformed inscription request {}", tx.id))?;
 
         let latency = METRICS.broadcast_time.start();
         let inscribe_info = match self.inscriber.inscribe(input).await {
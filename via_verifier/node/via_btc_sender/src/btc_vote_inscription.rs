@@ -64,7 +64,7 @@ impl ViaVoteInscription {
                 .via_btc_sender_dal()
                 .via_save_btc_inscriptions_request(
                     ViaVerifierBtcInscriptionRequestType::VoteOnchain.to_string(),
-                    InscriptionMessage::to_bytes(&inscription_message),
+                    InscriptionMessage::to_bytes(&inscription_message)?,
                     0,
                 )
                 .await?;
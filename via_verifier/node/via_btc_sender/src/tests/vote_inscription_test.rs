@@ -60,6 +60,8 @@ mod tests {
                 "".to_string(),
                 "".to_string(),
                 "".to_string(),
+                "".to_string(),
+                0,
             )
             .await;
 
@@ -114,7 +116,8 @@ mod tests {
                     .inscription_message
                     .as_ref()
                     .unwrap()
-            ),
+            )
+            .unwrap(),
             inscription
         );
     }
@@ -142,6 +145,8 @@ mod tests {
                 "".to_string(),
                 "".to_string(),
                 "".to_string(),
+                "".to_string(),
+                0,
             )
             .await;
 
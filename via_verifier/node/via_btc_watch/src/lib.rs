@@ -3,18 +3,24 @@ mod metrics;
 
 use std::sync::Arc;
 
-use message_processors::{GovernanceUpgradesEventProcessor, WithdrawalProcessor};
+use message_processors::{GovernanceUpgradesEventProcessor, RefundProcessor, WithdrawalProcessor};
 use tokio::sync::watch;
 // re-export via_btc_client types
 pub use via_btc_client::types::BitcoinNetwork;
-use via_btc_client::{client::BitcoinClient, indexer::BitcoinInscriptionIndexer};
+use via_btc_client::{
+    client::BitcoinClient,
+    indexer::BitcoinInscriptionIndexer,
+    types::{BitcoinBlockHash, BitcoinSecp256k1::hashes::Hash},
+};
 use via_verifier_dal::{Connection, ConnectionPool, Verifier, VerifierDal};
 use via_verifier_types::protocol_version::check_if_supported_sequencer_version;
 use zksync_config::{configs::via_btc_watch::L1_BLOCKS_CHUNK, ViaBtcWatchConfig};
+use zksync_types::via_wallet::SystemWallets;
 
 use self::message_processors::{MessageProcessor, MessageProcessorError};
-use crate::message_processors::{
-    L1ToL2MessageProcessor, SystemWalletProcessor, VerifierMessageProcessor,
+use crate::{
+    message_processors::{L1ToL2MessageProcessor, SystemWalletProcessor, VerifierMessageProcessor},
+    metrics::METRICS,
 };
 
 #[cfg(test)]
@@ -65,6 +71,7 @@ impl VerifierBtcWatch {
             )),
             Box::new(VerifierMessageProcessor::new(zk_agreement_threshold)),
             Box::new(WithdrawalProcessor::new()),
+            Box::new(RefundProcessor::new()),
         ];
 
         Ok(Self {
@@ -165,12 +172,20 @@ impl VerifierBtcWatch {
             to_block = current_l1_block_number;
         }
 
-        let mut messages = self
+        let (mut messages, block_hashes) = self
             .indexer
-            .process_blocks(self.last_processed_bitcoin_block + 1, to_block)
+            .process_blocks_with_hashes(self.last_processed_bitcoin_block + 1, to_block)
             .await
             .map_err(|e| MessageProcessorError::Internal(e.into()))?;
 
+        // The first new block must extend the processed chain, otherwise the processed blocks were
+        // reorganized out and everything indexed from them has to be rewound.
+        if let Some((_, first_block_hash)) = block_hashes.first() {
+            if let Some(fork_block) = self.find_fork_block(storage, first_block_hash).await? {
+                return self.rewind(storage, fork_block).await;
+            }
+        }
+
         // Re-process blocks if system wallets were updated, since the new wallet state
         // may change how subsequent messages are interpreted.
         if self
@@ -193,6 +208,15 @@ impl VerifierBtcWatch {
                 .map_err(|e| MessageProcessorError::Internal(e.into()))?;
         }
 
+        let block_hashes: Vec<_> = block_hashes
+            .into_iter()
+            .map(|(number, hash)| (number, hash.to_byte_array().to_vec()))
+            .collect();
+        storage
+            .via_indexer_dal()
+            .insert_l1_block_hashes(VerifierBtcWatch::module_name(), &block_hashes)
+            .await?;
+
         storage
             .via_indexer_dal()
             .update_last_processed_l1_block(VerifierBtcWatch::module_name(), to_block)
@@ -203,6 +227,158 @@ impl VerifierBtcWatch {
         Ok(())
     }
 
+    /// Checks that `next_block_hash` extends the last processed block. If it doesn't, returns the
+    /// highest processed block that is still part of the best chain.
+    async fn find_fork_block(
+        &self,
+        storage: &mut Connection<'_, Verifier>,
+        next_block_hash: &BitcoinBlockHash,
+    ) -> Result<Option<u32>, MessageProcessorError> {
+        let last_processed_block = self.last_processed_bitcoin_block;
+        let Some(last_processed_hash) = self
+            .stored_block_hash(storage, last_processed_block)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        if self
+            .indexer
+            .are_blocks_connected(&last_processed_hash, next_block_hash)
+            .await?
+        {
+            return Ok(None);
+        }
+
+        // Walk back until a processed block is found in the best chain. Blocks processed before
+        // their hashes were stored can't be checked and are assumed to be final.
+        let mut fork_block = last_processed_block;
+        while fork_block > 0 {
+            fork_block -= 1;
+            let Some(stored_hash) = self.stored_block_hash(storage, fork_block).await? else {
+                break;
+            };
+            if stored_hash == self.indexer.fetch_block_hash(fork_block).await? {
+                break;
+            }
+        }
+
+        tracing::warn!(
+            "Bitcoin reorg detected, processed blocks {}..={} are no longer in the best chain",
+            fork_block + 1,
+            last_processed_block
+        );
+        Ok(Some(fork_block))
+    }
+
+    async fn stored_block_hash(
+        &self,
+        storage: &mut Connection<'_, Verifier>,
+        block_number: u32,
+    ) -> Result<Option<BitcoinBlockHash>, MessageProcessorError> {
+        let Some(hash) = storage
+            .via_indexer_dal()
+            .get_l1_block_hash(VerifierBtcWatch::module_name(), block_number)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let hash = BitcoinBlockHash::from_slice(&hash)
+            .map_err(|e| MessageProcessorError::Internal(e.into()))?;
+        Ok(Some(hash))
+    }
+
+    /// Removes the deposits, refunds, votable transactions, votes and system wallet updates indexed
+    /// from the blocks above `fork_block`, so the blocks of the new best chain are indexed from
+    /// there.
+    async fn rewind(
+        &mut self,
+        storage: &mut Connection<'_, Verifier>,
+        fork_block: u32,
+    ) -> Result<(), MessageProcessorError> {
+        let mut transaction = storage.start_transaction().await?;
+
+        if transaction
+            .via_votes_dal()
+            .has_withdrawals_after_l1_block(fork_block)
+            .await?
+        {
+            return Err(MessageProcessorError::Internal(anyhow::anyhow!(
+                "Bitcoin reorg orphaned l1 batches above block {fork_block} that were already withdrawn"
+            )));
+        }
+        if transaction
+            .via_transactions_dal()
+            .has_processed_transactions_after_l1_block(fork_block)
+            .await?
+        {
+            return Err(MessageProcessorError::Internal(anyhow::anyhow!(
+                "Bitcoin reorg orphaned deposits above block {fork_block} that were already processed"
+            )));
+        }
+
+        if transaction
+            .via_refunds_dal()
+            .has_processed_refunds_after_l1_block(fork_block)
+            .await?
+        {
+            return Err(MessageProcessorError::Internal(anyhow::anyhow!(
+                "Bitcoin reorg orphaned deposits above block {fork_block} that were already refunded"
+            )));
+        }
+
+        let removed_deposits = transaction
+            .via_transactions_dal()
+            .delete_transactions_after_l1_block(fork_block)
+            .await?;
+        transaction
+            .via_refunds_dal()
+            .delete_refunds_after_l1_block(fork_block)
+            .await?;
+        transaction
+            .via_votes_dal()
+            .delete_votes_after_l1_block(fork_block)
+            .await?;
+        transaction
+            .via_votes_dal()
+            .delete_votable_transactions_after_l1_block(fork_block)
+            .await?;
+        transaction
+            .via_wallet_dal()
+            .delete_wallets_after_l1_block(fork_block)
+            .await?;
+        transaction
+            .via_indexer_dal()
+            .delete_l1_block_hashes_after(VerifierBtcWatch::module_name(), fork_block)
+            .await?;
+        transaction
+            .via_indexer_dal()
+            .update_last_processed_l1_block(VerifierBtcWatch::module_name(), fork_block)
+            .await?;
+
+        // The orphaned blocks could have updated the system wallets.
+        let system_wallets_map = transaction
+            .via_wallet_dal()
+            .get_system_wallets_raw()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("System wallets are missing"))?;
+        let system_wallets = SystemWallets::try_from(system_wallets_map)?;
+
+        transaction.commit().await?;
+
+        self.indexer.update_system_wallets(
+            Some(system_wallets.sequencer),
+            Some(system_wallets.bridge),
+            Some(system_wallets.verifiers),
+            Some(system_wallets.governance),
+        );
+        self.last_processed_bitcoin_block = fork_block;
+        METRICS.reorgs.inc();
+
+        tracing::info!("Rewound to block {fork_block}, removed {removed_deposits} deposits");
+        Ok(())
+    }
+
     fn module_name() -> &'static str {
         "via_btc_watch"
     }
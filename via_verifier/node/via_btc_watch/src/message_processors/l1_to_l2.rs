@@ -18,6 +18,24 @@ pub struct L1ToL2Transaction {
     value: i64,
     calldata: Vec<u8>,
     canonical_tx_hash: H256,
+    l1_block_number: u32,
+}
+
+/// A deposit that can't be credited on L2, the BTC is refunded to the sender.
+#[derive(Debug)]
+pub struct InvalidDeposit {
+    tx_id: H256,
+    vout: i64,
+    sender: Option<String>,
+    value: i64,
+    reason: &'static str,
+    l1_block_number: u32,
+}
+
+#[derive(Debug)]
+enum Deposit {
+    Valid(L1ToL2Transaction),
+    Invalid(InvalidDeposit),
 }
 
 #[derive(Debug)]
@@ -40,6 +58,7 @@ impl MessageProcessor for L1ToL2MessageProcessor {
         _: &mut BitcoinInscriptionIndexer,
     ) -> Result<bool, MessageProcessorError> {
         let mut priority_ops = Vec::new();
+        let mut invalid_deposits = Vec::new();
 
         for msg in msgs {
             if let FullInscriptionMessage::L1ToL2Message(l1_to_l2_msg) = msg {
@@ -64,16 +83,36 @@ impl MessageProcessor for L1ToL2MessageProcessor {
                         );
                         continue;
                     }
-                    let Some(l1_tx) = self.create_l1_tx_from_message(tx_id, &l1_to_l2_msg)? else {
-                        tracing::warn!("Invalid deposit, l1 tx_id {}", &l1_to_l2_msg.common.tx_id);
-                        continue;
-                    };
-
-                    priority_ops.push(l1_tx);
+                    match self.create_l1_tx_from_message(tx_id, &l1_to_l2_msg)? {
+                        Deposit::Valid(l1_tx) => priority_ops.push(l1_tx),
+                        Deposit::Invalid(deposit) => {
+                            tracing::warn!(
+                                "Invalid deposit, l1 tx_id {}: {}, recording it for refund",
+                                &l1_to_l2_msg.common.tx_id,
+                                deposit.reason
+                            );
+                            invalid_deposits.push(deposit);
+                        }
+                    }
                 }
             }
         }
 
+        for deposit in &invalid_deposits {
+            storage
+                .via_refunds_dal()
+                .insert_refund(
+                    deposit.tx_id,
+                    deposit.vout,
+                    deposit.l1_block_number,
+                    deposit.sender.clone(),
+                    deposit.value,
+                    deposit.reason,
+                )
+                .await
+                .map_err(|e| MessageProcessorError::DatabaseError(e.to_string()))?;
+        }
+
         if priority_ops.is_empty() {
             return Ok(false);
         }
@@ -88,6 +127,7 @@ impl MessageProcessor for L1ToL2MessageProcessor {
                     new_op.value,
                     new_op.calldata,
                     new_op.canonical_tx_hash,
+                    new_op.l1_block_number,
                 )
                 .await
                 .map_err(|e| MessageProcessorError::DatabaseError(e.to_string()))?;
@@ -102,7 +142,7 @@ impl L1ToL2MessageProcessor {
         &self,
         tx_id: H256,
         msg: &L1ToL2Message,
-    ) -> Result<Option<L1ToL2Transaction>, MessageProcessorError> {
+    ) -> Result<Deposit, MessageProcessorError> {
         let deposit = ViaL1Deposit {
             l2_receiver_address: msg.input.receiver_l2_address,
             amount: msg.amount.to_sat(),
@@ -116,35 +156,44 @@ impl L1ToL2MessageProcessor {
             })?,
         };
 
-        if let Some(l1_tx) = deposit.l1_tx() {
-            tracing::info!(
-                "Created L1 transaction with serial id {:?} (block {}) with deposit amount {} and tx hash {}",
-                l1_tx.common_data.serial_id,
-                l1_tx.common_data.eth_block,
-                deposit.amount,
-                l1_tx.common_data.canonical_tx_hash,
-            );
-
-            METRICS.inscriptions_processed[&InscriptionStage::Deposit]
-                .set(deposit.priority_id().0 as usize);
-
-            tracing::info!(
-                "Created L1 transaction with serial id {:?} (block {}) with deposit amount {} and tx hash {}",
-                l1_tx.common_data.serial_id,
-                l1_tx.common_data.eth_block,
-                deposit.amount,
-                l1_tx.common_data.canonical_tx_hash,
-            );
-
-            return Ok(Some(L1ToL2Transaction {
-                priority_id: deposit.priority_id().0 as i64,
+        let Some(l1_tx) = deposit.l1_tx() else {
+            return Ok(Deposit::Invalid(InvalidDeposit {
                 tx_id,
-                receiver: deposit.l2_receiver_address,
+                vout: deposit.output_vout as i64,
+                sender: msg.common.p2wpkh_address.as_ref().map(|a| a.to_string()),
                 value: deposit.amount as i64,
-                calldata: deposit.calldata,
-                canonical_tx_hash: l1_tx.common_data.canonical_tx_hash,
+                reason: deposit.invalid_reason().unwrap_or("invalid deposit"),
+                l1_block_number: msg.common.block_height,
             }));
-        }
-        Ok(None)
+        };
+
+        tracing::info!(
+            "Created L1 transaction with serial id {:?} (block {}) with deposit amount {} and tx hash {}",
+            l1_tx.common_data.serial_id,
+            l1_tx.common_data.eth_block,
+            deposit.amount,
+            l1_tx.common_data.canonical_tx_hash,
+        );
+
+        METRICS.inscriptions_processed[&InscriptionStage::Deposit]
+            .set(deposit.priority_id().0 as usize);
+
+        tracing::info!(
+            "Created L1 transaction with serial id {:?} (block {}) with deposit amount {} and tx hash {}",
+            l1_tx.common_data.serial_id,
+            l1_tx.common_data.eth_block,
+            deposit.amount,
+            l1_tx.common_data.canonical_tx_hash,
+        );
+
+        Ok(Deposit::Valid(L1ToL2Transaction {
+            priority_id: deposit.priority_id().0 as i64,
+            tx_id,
+            receiver: deposit.l2_receiver_address,
+            value: deposit.amount as i64,
+            calldata: deposit.calldata,
+            canonical_tx_hash: l1_tx.common_data.canonical_tx_hash,
+            l1_block_number: msg.common.block_height,
+        }))
     }
 }
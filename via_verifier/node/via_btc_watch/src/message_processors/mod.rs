@@ -1,5 +1,6 @@
 pub(crate) use governance_upgrade::GovernanceUpgradesEventProcessor;
 pub(crate) use l1_to_l2::L1ToL2MessageProcessor;
+pub(crate) use refund::RefundProcessor;
 pub(crate) use system_wallet::SystemWalletProcessor;
 pub(crate) use verifier::VerifierMessageProcessor;
 use via_btc_client::{
@@ -12,6 +13,7 @@ use zksync_types::H256;
 
 mod governance_upgrade;
 mod l1_to_l2;
+mod refund;
 mod system_wallet;
 mod verifier;
 mod withdrawal;
@@ -0,0 +1,60 @@
+use via_btc_client::{
+    indexer::BitcoinInscriptionIndexer,
+    types::{BitcoinSecp256k1::hashes::Hash, FullInscriptionMessage},
+};
+use via_verifier_dal::{Connection, Verifier, VerifierDal};
+
+use super::{convert_txid_to_h256, MessageProcessor, MessageProcessorError};
+use crate::metrics::{InscriptionStage, METRICS};
+
+#[derive(Debug)]
+pub struct RefundProcessor;
+
+impl RefundProcessor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl MessageProcessor for RefundProcessor {
+    async fn process_messages(
+        &mut self,
+        storage: &mut Connection<'_, Verifier>,
+        msgs: Vec<FullInscriptionMessage>,
+        _: &mut BitcoinInscriptionIndexer,
+    ) -> Result<bool, MessageProcessorError> {
+        for msg in msgs {
+            if let FullInscriptionMessage::BridgeRefund(refund_msg) = msg {
+                tracing::info!("Processing refund bridge transaction...");
+
+                let deposit_tx_id = convert_txid_to_h256(refund_msg.input.deposit_tx_id);
+                let refund_tx_id = refund_msg.common.tx_id.as_raw_hash().to_byte_array();
+
+                let updated = storage
+                    .via_refunds_dal()
+                    .mark_refunded(deposit_tx_id, &refund_tx_id)
+                    .await
+                    .map_err(|e| MessageProcessorError::DatabaseError(e.to_string()))?;
+
+                if updated == 0 {
+                    tracing::warn!(
+                        "No pending refund found for deposit {}, refund tx {}",
+                        deposit_tx_id,
+                        refund_msg.common.tx_id
+                    );
+                    continue;
+                }
+
+                tracing::info!(
+                    "Marked the refund of deposit {} as processed",
+                    deposit_tx_id
+                );
+                METRICS.inscriptions_processed[&InscriptionStage::Refund]
+                    .set(refund_msg.common.block_height as usize);
+            }
+        }
+
+        Ok(true)
+    }
+}
@@ -1,4 +1,7 @@
-use via_btc_client::{indexer::BitcoinInscriptionIndexer, types::FullInscriptionMessage};
+use via_btc_client::{
+    indexer::BitcoinInscriptionIndexer,
+    types::{BitcoinTxid, FullInscriptionMessage, L1BatchDAReference},
+};
 use via_verifier_dal::{Connection, Verifier, VerifierDal};
 
 use super::{convert_txid_to_h256, MessageProcessor, MessageProcessorError};
@@ -43,33 +46,23 @@ impl MessageProcessor for VerifierMessageProcessor {
                         continue;
                     }
 
-                    let pubdata_msgs = indexer
-                        .parse_transaction(&proof_msg.input.l1_batch_reveal_txid)
-                        .await?;
-
-                    if pubdata_msgs.len() != 1 {
-                        return Err(MessageProcessorError::Internal(anyhow::Error::msg(
-                            "Invalid pubdata msg lenght",
-                        )));
+                    // A proof may cover a range of consecutive L1 batches, each one referenced by its
+                    // own pubdata inscription.
+                    let mut l1_batch_da_refs = Vec::new();
+                    for l1_batch_reveal_txid in proof_msg.input.l1_batch_reveal_txids() {
+                        let l1_batch_da_ref =
+                            parse_l1_batch_da_reference(indexer, l1_batch_reveal_txid).await?;
+                        l1_batch_da_refs.push((l1_batch_reveal_txid, l1_batch_da_ref));
                     }
 
-                    let inscription = pubdata_msgs[0].clone();
-
-                    let l1_batch_da_ref_inscription = match inscription {
-                        FullInscriptionMessage::L1BatchDAReference(da_msg) => da_msg,
-                        _ => {
-                            return Err(MessageProcessorError::Internal(anyhow::Error::msg(
-                                "Invalid inscription type",
-                            )))
-                        }
-                    };
-
-                    let new_l1_batch_number = l1_batch_da_ref_inscription.input.l1_batch_index.0;
+                    let first_l1_batch_da_ref = &l1_batch_da_refs[0].1;
+                    let new_l1_batch_number = first_l1_batch_da_ref.input.l1_batch_index.0;
 
                     tracing::info!(
-                        "Processing ProofDAReference for batch {} with hash {:?}",
+                        "Processing ProofDAReference for batch {} with hash {:?} covering {} batch(es)",
                         new_l1_batch_number,
-                        l1_batch_da_ref_inscription.input.l1_batch_hash
+                        first_l1_batch_da_ref.input.l1_batch_hash,
+                        l1_batch_da_refs.len()
                     );
 
                     if new_l1_batch_number == 0 {
@@ -98,106 +91,136 @@ impl MessageProcessor for VerifierMessageProcessor {
                         if last_batch_in_canonical_chain.0 + 1 != new_l1_batch_number {
                             tracing::info!(
                                 "Skipping ProofDAReference message with l1_batch_number: {:?}. Last batch in canonical chain: {:?}",
-                                l1_batch_da_ref_inscription.input.l1_batch_index,
+                                first_l1_batch_da_ref.input.l1_batch_index,
                                 last_batch_in_canonical_chain
                             );
                             continue;
                         }
 
                         if last_batch_in_canonical_chain.1
-                            != l1_batch_da_ref_inscription.input.prev_l1_batch_hash.0
+                            != first_l1_batch_da_ref.input.prev_l1_batch_hash.0
                         {
                             tracing::info!(
                             "Skipping ProofDAReference message with l1_batch_number: {:?}. Last batch in canonical chain: {:?}",
-                            l1_batch_da_ref_inscription.input.l1_batch_index,
+                            first_l1_batch_da_ref.input.l1_batch_index,
                             last_batch_in_canonical_chain
                         );
                             continue;
                         }
                     }
 
-                    METRICS.inscriptions_processed[&InscriptionStage::IndexedL1Batch]
-                        .set(new_l1_batch_number as usize);
-
-                    votes_dal
-                        .insert_votable_transaction(
-                            new_l1_batch_number,
-                            l1_batch_da_ref_inscription.input.l1_batch_hash,
-                            l1_batch_da_ref_inscription.input.prev_l1_batch_hash,
-                            proof_msg.input.da_identifier.clone(),
-                            proof_reveal_tx_id,
-                            proof_msg.input.blob_id.clone(),
-                            proof_msg.input.l1_batch_reveal_txid.to_string(),
-                            l1_batch_da_ref_inscription.input.blob_id,
-                        )
-                        .await?;
+                    // The remaining batches of the range must extend the first one.
+                    if let Some(pair) = l1_batch_da_refs.windows(2).find(|pair| {
+                        let (prev, next) = (&pair[0].1.input, &pair[1].1.input);
+                        prev.l1_batch_index.0 + 1 != next.l1_batch_index.0
+                            || prev.l1_batch_hash != next.prev_l1_batch_hash
+                    }) {
+                        tracing::info!(
+                            "Skipping ProofDAReference message, l1_batch_number {:?} does not extend {:?}",
+                            pair[1].1.input.l1_batch_index,
+                            pair[0].1.input.l1_batch_index
+                        );
+                        continue;
+                    }
 
-                    tracing::info!(
-                        "New votable transaction for L1 batch {:?}",
-                        new_l1_batch_number
-                    );
-                }
-                ref f @ FullInscriptionMessage::ValidatorAttestation(ref attestation_msg) => {
-                    if let Some(l1_batch_number) = indexer.get_l1_batch_number(f).await {
-                        let reveal_proof_txid =
-                            convert_txid_to_h256(attestation_msg.input.reference_txid);
-                        let tx_id = convert_txid_to_h256(attestation_msg.common.tx_id);
-
-                        // Vote = true if attestation_msg.input.attestation == Vote::Ok
-                        let is_ok = matches!(
-                            attestation_msg.input.attestation,
-                            via_btc_client::types::Vote::Ok
+                    let mut transaction = storage.start_transaction().await?;
+
+                    for (l1_batch_reveal_txid, l1_batch_da_ref) in &l1_batch_da_refs {
+                        transaction
+                            .via_votes_dal()
+                            .insert_votable_transaction(
+                                l1_batch_da_ref.input.l1_batch_index.0,
+                                l1_batch_da_ref.input.l1_batch_hash,
+                                l1_batch_da_ref.input.prev_l1_batch_hash,
+                                proof_msg.input.da_identifier.clone(),
+                                proof_reveal_tx_id,
+                                proof_msg.input.blob_id.clone(),
+                                l1_batch_reveal_txid.to_string(),
+                                l1_batch_da_ref.input.da_identifier.clone(),
+                                l1_batch_da_ref.input.blob_id.clone(),
+                                proof_msg.common.block_height,
+                            )
+                            .await?;
+
+                        tracing::info!(
+                            "New votable transaction for L1 batch {:?}",
+                            l1_batch_da_ref.input.l1_batch_index
                         );
+                    }
+
+                    transaction.commit().await?;
+
+                    if let Some((_, last_l1_batch_da_ref)) = l1_batch_da_refs.last() {
+                        METRICS.inscriptions_processed[&InscriptionStage::IndexedL1Batch]
+                            .set(last_l1_batch_da_ref.input.l1_batch_index.0 as usize);
+                    }
+                }
+                FullInscriptionMessage::ValidatorAttestation(ref attestation_msg) => {
+                    let reveal_proof_txid =
+                        convert_txid_to_h256(attestation_msg.input.reference_txid);
+                    let tx_id = convert_txid_to_h256(attestation_msg.common.tx_id);
+
+                    // Vote = true if attestation_msg.input.attestation == Vote::Ok
+                    let is_ok = matches!(
+                        attestation_msg.input.attestation,
+                        via_btc_client::types::Vote::Ok
+                    );
+
+                    // A single attestation votes for every L1 batch covered by the proof.
+                    let votable_transactions = storage
+                        .via_votes_dal()
+                        .get_votable_transaction_ids(reveal_proof_txid.as_bytes())
+                        .await?;
+                    if votable_transactions.is_empty() {
+                        continue;
+                    }
 
-                        if let Some(votable_transaction_id) = storage
+                    let p2wpkh_address = attestation_msg
+                        .common
+                        .p2wpkh_address
+                        .as_ref()
+                        .expect("ValidatorAttestation message must have a p2wpkh address");
+
+                    let mut transaction = storage.start_transaction().await?;
+
+                    for (votable_transaction_id, l1_batch_number) in votable_transactions {
+                        transaction
                             .via_votes_dal()
-                            .get_votable_transaction_id(&reveal_proof_txid.as_bytes())
+                            .insert_vote(
+                                votable_transaction_id,
+                                &p2wpkh_address.to_string(),
+                                is_ok,
+                                attestation_msg.common.block_height,
+                            )
+                            .await?;
+
+                        tracing::info!("New vote found for L1 batch {:?}", l1_batch_number);
+
+                        METRICS.inscriptions_processed[&InscriptionStage::Vote]
+                            .set(l1_batch_number as usize);
+
+                        // Check finalization
+                        if transaction
+                            .via_votes_dal()
+                            .finalize_transaction_if_needed(
+                                votable_transaction_id,
+                                self.zk_agreement_threshold,
+                                indexer.get_number_of_verifiers(),
+                            )
                             .await?
                         {
-                            let p2wpkh_address =
-                                attestation_msg.common.p2wpkh_address.as_ref().expect(
-                                    "ValidatorAttestation message must have a p2wpkh address",
-                                );
-
-                            let mut transaction = storage.start_transaction().await?;
-
-                            transaction
-                                .via_votes_dal()
-                                .insert_vote(
-                                    votable_transaction_id,
-                                    &p2wpkh_address.to_string(),
-                                    is_ok,
-                                )
-                                .await?;
-
-                            tracing::info!("New vote found for L1 batch {:?}", l1_batch_number);
-
-                            METRICS.inscriptions_processed[&InscriptionStage::Vote]
-                                .set(l1_batch_number.0 as usize);
-
-                            // Check finalization
-                            if transaction
-                                .via_votes_dal()
-                                .finalize_transaction_if_needed(
-                                    votable_transaction_id,
-                                    self.zk_agreement_threshold,
-                                    indexer.get_number_of_verifiers(),
-                                )
-                                .await?
-                            {
-                                METRICS
-                                    .last_finalized_l1_batch
-                                    .set(l1_batch_number.0 as usize);
-                                tracing::info!(
-                                        "Finalizing transaction with tx_id: {:?} and block number: {:?}",
-                                        tx_id,
-                                        l1_batch_number
-                                );
-                            }
-
-                            transaction.commit().await?
+                            METRICS
+                                .last_finalized_l1_batch
+                                .set(l1_batch_number as usize);
+                            tracing::info!(
+                                "Finalizing transaction with tx_id: {:?} and block number: {:?}",
+                                tx_id,
+                                l1_batch_number
+                            );
                         }
                     }
+
+                    transaction.commit().await?
                 }
                 _ => (),
             }
@@ -205,3 +228,27 @@ impl MessageProcessor for VerifierMessageProcessor {
         Ok(false)
     }
 }
+
+async fn parse_l1_batch_da_reference(
+    indexer: &mut BitcoinInscriptionIndexer,
+    l1_batch_reveal_txid: &BitcoinTxid,
+) -> Result<L1BatchDAReference, MessageProcessorError> {
+    let pubdata_msgs = indexer.parse_transaction(l1_batch_reveal_txid).await?;
+
+    // The batch inscription can pack other messages, but a single L1 batch reference.
+    let mut l1_batch_da_ref_inscriptions = pubdata_msgs
+        .into_iter()
+        .filter_map(|inscription| match inscription {
+            FullInscriptionMessage::L1BatchDAReference(da_msg) => Some(da_msg),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if l1_batch_da_ref_inscriptions.len() != 1 {
+        return Err(MessageProcessorError::Internal(anyhow::Error::msg(
+            "Invalid pubdata msg lenght",
+        )));
+    }
+
+    Ok(l1_batch_da_ref_inscriptions.remove(0))
+}
@@ -1,6 +1,8 @@
+use std::str::FromStr;
+
 use via_btc_client::{
     indexer::BitcoinInscriptionIndexer,
-    types::{BitcoinSecp256k1::hashes::Hash, FullInscriptionMessage},
+    types::{BitcoinAddress, BitcoinSecp256k1::hashes::Hash, FullInscriptionMessage},
 };
 use via_verifier_dal::{Connection, Verifier, VerifierDal};
 use zksync_types::H256;
@@ -44,6 +46,7 @@ impl MessageProcessor for WithdrawalProcessor {
                     .via_votes_dal()
                     .get_vote_transaction_info(
                         proof_reveal_tx_id.clone(),
+                        withdrawal_msg.input.l1_batch_number.map(i64::from),
                         withdrawal_msg.input.index_withdrawal,
                     )
                     .await
@@ -81,6 +84,29 @@ impl MessageProcessor for WithdrawalProcessor {
                     .await
                     .map_err(|e| MessageProcessorError::DatabaseError(e.to_string()))?;
 
+                // The outflows of the bridge transaction count toward the outflow limits of the
+                // next withdrawal sessions.
+                let outflows = withdrawal_msg
+                    .input
+                    .withdrawals
+                    .iter()
+                    .map(|(address, amount)| {
+                        let address = BitcoinAddress::from_str(address)
+                            .map_err(|e| MessageProcessorError::Internal(e.into()))?
+                            .assume_checked();
+                        Ok((address.script_pubkey().to_bytes(), *amount))
+                    })
+                    .collect::<Result<Vec<_>, MessageProcessorError>>()?;
+                storage
+                    .via_outflows_dal()
+                    .insert_outflows(
+                        l1_batch_number,
+                        withdrawal_msg.input.index_withdrawal,
+                        &outflows,
+                    )
+                    .await
+                    .map_err(|e| MessageProcessorError::DatabaseError(e.to_string()))?;
+
                 tracing::info!(
                     "Marked withdrawal for L1 batch {} as processed",
                     l1_batch_number
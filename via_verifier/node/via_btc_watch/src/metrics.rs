@@ -1,4 +1,4 @@
-use vise::{EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
@@ -8,6 +8,7 @@ pub enum InscriptionStage {
     Upgrade,
     Vote,
     Withdrawal,
+    Refund,
 }
 
 #[derive(Debug, Metrics)]
@@ -18,6 +19,9 @@ pub struct ViaVerifierBtcWatcherMetrics {
 
     /// Last indexed l1 batch number.
     pub last_finalized_l1_batch: Gauge<usize>,
+
+    /// Number of Bitcoin reorgs that rewound the processed blocks.
+    pub reorgs: Counter,
 }
 
 #[vise::register]
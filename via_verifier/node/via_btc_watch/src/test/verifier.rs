@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
     use via_test_utils::utils::{
-        create_chained_inscriptions, test_create_indexer, test_verifier_add_1, test_verifier_add_2,
+        create_chained_inscriptions, create_range_proof_inscription, test_create_indexer,
+        test_verifier_add_1, test_verifier_add_2,
     };
     use via_verifier_dal::{ConnectionPool, Verifier, VerifierDal};
     use zksync_types::H256;
@@ -221,6 +222,7 @@ mod tests {
                         votable_transaction_id,
                         &test_verifier_add_1().to_string(),
                         true,
+                        0,
                     )
                     .await?;
 
@@ -231,6 +233,7 @@ mod tests {
                         votable_transaction_id,
                         &test_verifier_add_2().to_string(),
                         true,
+                        0,
                     )
                     .await?;
 
@@ -325,6 +328,45 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_insert_range_of_batches_with_single_proof() -> anyhow::Result<()> {
+        let pool = ConnectionPool::<Verifier>::test_pool().await;
+        let mut indexer = test_create_indexer();
+        let mut processor = VerifierMessageProcessor::new(1.0);
+        let (msgs, _) = create_range_proof_inscription(1, 3, None).await?;
+
+        processor
+            .process_messages(&mut pool.connection().await?, msgs, &mut indexer)
+            .await?;
+
+        verify_canonical_chain(pool.clone(), 3).await?;
+
+        // Every batch of the range shares the same proof reveal tx.
+        let proof_tx_id = pool
+            .connection()
+            .await?
+            .via_votes_dal()
+            .get_first_not_verified_l1_batch_in_canonical_inscription_chain()
+            .await?
+            .map(|(_, proof_tx_id)| proof_tx_id)
+            .expect("Range proof not indexed");
+        let votable_transactions = pool
+            .connection()
+            .await?
+            .via_votes_dal()
+            .get_votable_transaction_ids(&proof_tx_id)
+            .await?;
+        assert_eq!(
+            votable_transactions
+                .iter()
+                .map(|(_, l1_batch_number)| *l1_batch_number)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        Ok(())
+    }
+
     // Scenario: the batch 3 was rejected by the verifier network, then the sequencer created a new valid batch 3.
     #[tokio::test]
     async fn test_should_insert_new_valid_batch_with_same_block_number_after_it_was_rejected(
@@ -377,6 +419,7 @@ mod tests {
                         votable_transaction_id,
                         &test_verifier_add_1().to_string(),
                         vote,
+                        0,
                     )
                     .await?;
 
@@ -387,6 +430,7 @@ mod tests {
                         votable_transaction_id,
                         &test_verifier_add_2().to_string(),
                         vote,
+                        0,
                     )
                     .await?;
 
@@ -1,9 +1,56 @@
+use std::{collections::HashMap, sync::Mutex};
+
 use anyhow::Context;
 use base64::Engine;
 use bitcoin::secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 
+/// Builds the payload signed by a verifier for a coordinator request. It commits to the request
+/// method, path and body, and to a nonce used once.
+pub fn request_payload(
+    timestamp: &str,
+    verifier_index: &str,
+    sequencer_version: &str,
+    method: &str,
+    path: &str,
+    body: &[u8],
+    nonce: &str,
+) -> serde_json::Value {
+    serde_json::json!({
+        "timestamp": timestamp,
+        "verifier_index": verifier_index,
+        "sequencer_version": sequencer_version,
+        "method": method,
+        "path": path,
+        "body_hash": hex::encode(Sha256::digest(body)),
+        "nonce": nonce,
+    })
+}
+
+/// Nonces of the authenticated requests, kept while their timestamp is valid to reject replays.
+#[derive(Debug, Default)]
+pub struct RequestNonces {
+    seen: Mutex<HashMap<(usize, String), i64>>,
+}
+
+impl RequestNonces {
+    /// Records the nonce of a request signed at `timestamp`, returns false when it was already used.
+    pub fn insert(
+        &self,
+        verifier_index: usize,
+        nonce: &str,
+        timestamp: i64,
+        now: i64,
+        validity: i64,
+    ) -> bool {
+        let mut seen = self.seen.lock().expect("request nonces lock poisoned");
+        seen.retain(|_, seen_timestamp| now - *seen_timestamp <= validity);
+        seen.insert((verifier_index, nonce.to_string()), timestamp)
+            .is_none()
+    }
+}
+
 /// Signs a request payload using the verifier's private key.
 pub fn sign_request<T: Serialize>(payload: &T, secret_key: &SecretKey) -> anyhow::Result<String> {
     let secp = Secp256k1::new();
@@ -77,4 +124,42 @@ mod tests {
         assert!(!verify_signature(&payload, &signature, &wrong_public_key)
             .expect("Verification with wrong key unexpectedly succeeded"));
     }
+
+    #[test]
+    fn test_request_payload_commits_to_the_body() {
+        let secp = Secp256k1::new();
+        let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+
+        let payload = request_payload("1", "0", "0.1.0", "POST", "/session/nonce", b"{}", "n1");
+        let signature = sign_request(&payload, &secret_key).unwrap();
+
+        let tampered_body = request_payload(
+            "1",
+            "0",
+            "0.1.0",
+            "POST",
+            "/session/nonce",
+            b"{\"0\":1}",
+            "n1",
+        );
+        assert!(!verify_signature(&tampered_body, &signature, &public_key).unwrap());
+
+        let other_path =
+            request_payload("1", "0", "0.1.0", "POST", "/session/signature", b"{}", "n1");
+        assert!(!verify_signature(&other_path, &signature, &public_key).unwrap());
+    }
+
+    #[test]
+    fn test_request_nonces_reject_replays() {
+        let nonces = RequestNonces::default();
+
+        assert!(nonces.insert(0, "n1", 100, 100, 10));
+        assert!(!nonces.insert(0, "n1", 100, 105, 10));
+        // Nonces are scoped by verifier.
+        assert!(nonces.insert(1, "n1", 100, 105, 10));
+
+        // Expired nonces are pruned, the timestamp check rejects their requests.
+        assert!(nonces.insert(0, "n2", 120, 120, 10));
+        assert!(nonces.insert(0, "n1", 100, 120, 10));
+    }
 }
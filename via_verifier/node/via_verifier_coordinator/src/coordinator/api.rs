@@ -4,6 +4,7 @@ use anyhow::Context as _;
 use bitcoin::Address;
 use tokio::sync::watch;
 use via_btc_client::traits::BitcoinOps;
+use via_musig2::threshold::BridgeTaproot;
 use via_verifier_dal::{ConnectionPool, Verifier};
 use via_withdrawal_client::client::WithdrawalClient;
 use zksync_config::configs::via_verifier::ViaVerifierConfig;
@@ -19,9 +20,15 @@ pub async fn start_coordinator_server(
     bridge_address: Address,
     verifiers_pub_keys: Vec<String>,
     required_signers: usize,
+    bridge_taproot: Option<BridgeTaproot>,
     mut stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let bind_address = config.bind_addr();
+    let tls_acceptor = config
+        .mtls_enabled()
+        .then(|| crate::tls::acceptor(&config))
+        .transpose()
+        .context("Failed to load the coordinator TLS configuration")?;
     let api = RestApi::new(
         config,
         master_connection_pool,
@@ -30,12 +37,26 @@ pub async fn start_coordinator_server(
         bridge_address,
         verifiers_pub_keys,
         required_signers,
-    )?
-    .into_router();
+        bridge_taproot,
+    )?;
+    api.restore_session()
+        .await
+        .context("Failed to restore the signing session")?;
+    let api = api.into_router();
 
     let listener = tokio::net::TcpListener::bind(bind_address)
         .await
         .context("Cannot bind to the specified address")?;
+
+    if let Some(tls_acceptor) = tls_acceptor {
+        tracing::info!("Coordinator server requires mutual TLS");
+        crate::tls::serve(listener, api, tls_acceptor, stop_receiver)
+            .await
+            .with_context(|| "coordinator handler server failed")?;
+        tracing::info!("coordinator handler server shut down");
+        return Ok(());
+    }
+
     axum::serve(listener, api)
         .with_graceful_shutdown(async move {
             if stop_receiver.changed().await.is_err() {
@@ -2,21 +2,25 @@ use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
 
 use axum::middleware;
 use bitcoin::Address;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tower::ServiceBuilder;
 use tower_http::{cors::CorsLayer, timeout::TimeoutLayer};
 use via_btc_client::traits::BitcoinOps;
-use via_musig2::transaction_builder::TransactionBuilder;
+use via_musig2::{threshold::BridgeTaproot, transaction_builder::TransactionBuilder};
 use via_verifier_dal::{ConnectionPool, Verifier};
 use via_withdrawal_client::client::WithdrawalClient;
 use zksync_config::configs::via_verifier::ViaVerifierConfig;
 use zksync_utils::time::seconds_since_epoch;
 
 use crate::{
+    auth::RequestNonces,
     coordinator::auth_middleware,
-    sessions::{session_manager::SessionManager, withdrawal::WithdrawalSession},
+    sessions::{
+        refund::RefundSession, session_manager::SessionManager, withdrawal::WithdrawalSession,
+    },
     traits::ISession,
     types::{SessionType, SigningSession, ViaWithdrawalState},
+    utils::{consolidation_policy, max_tx_outflow},
 };
 
 pub struct RestApi {
@@ -24,11 +28,17 @@ pub struct RestApi {
     pub state: ViaWithdrawalState,
     pub session_manager: SessionManager,
     pub master_connection_pool: ConnectionPool<Verifier>,
+    pub transaction_builder: Arc<TransactionBuilder>,
+    pub request_nonces: RequestNonces,
 }
 
 const API_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Number of session events buffered for a slow subscriber before it misses some.
+const SESSION_EVENTS_CAPACITY: usize = 16;
+
 impl RestApi {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: ViaVerifierConfig,
         master_connection_pool: ConnectionPool<Verifier>,
@@ -37,6 +47,7 @@ impl RestApi {
         bridge_address: Address,
         verifiers_pub_keys: Vec<String>,
         required_signers: usize,
+        bridge_taproot: Option<BridgeTaproot>,
     ) -> anyhow::Result<Self> {
         let state = ViaWithdrawalState {
             signing_session: Arc::new(RwLock::new(SigningSession::default())),
@@ -47,10 +58,15 @@ impl RestApi {
                 .collect(),
             verifier_request_timeout: config.verifier_request_timeout,
             session_timeout: config.session_timeout,
+            bridge_taproot,
+            session_events: broadcast::channel(SESSION_EVENTS_CAPACITY).0,
         };
 
-        let transaction_builder =
-            Arc::new(TransactionBuilder::new(btc_client.clone(), bridge_address)?);
+        let transaction_builder = Arc::new(
+            TransactionBuilder::new(btc_client.clone(), bridge_address)?
+                .with_consolidation_policy(consolidation_policy(&config))
+                .with_max_tx_outflow(max_tx_outflow(&config)),
+        );
 
         let withdrawal_session = WithdrawalSession::new(
             config.clone(),
@@ -59,11 +75,23 @@ impl RestApi {
             withdrawal_client.clone(),
         );
 
+        let refund_session = RefundSession::new(
+            config.clone(),
+            master_connection_pool.clone(),
+            transaction_builder.clone(),
+        );
+
         // Add sessions type the verifier network can process
-        let sessions: HashMap<SessionType, Arc<dyn ISession>> = [(
-            SessionType::Withdrawal,
-            Arc::new(withdrawal_session) as Arc<dyn ISession>,
-        )]
+        let sessions: HashMap<SessionType, Arc<dyn ISession>> = [
+            (
+                SessionType::Withdrawal,
+                Arc::new(withdrawal_session) as Arc<dyn ISession>,
+            ),
+            (
+                SessionType::Refund,
+                Arc::new(refund_session) as Arc<dyn ISession>,
+            ),
+        ]
         .into_iter()
         .collect();
 
@@ -72,6 +100,8 @@ impl RestApi {
             session_manager: SessionManager::new(sessions),
             state,
             master_connection_pool,
+            transaction_builder,
+            request_nonces: RequestNonces::default(),
         })
     }
 
@@ -98,8 +128,32 @@ impl RestApi {
             )
             .route("/nonce", axum::routing::post(Self::submit_nonce))
             .route("/nonce", axum::routing::get(Self::get_nonces))
-            .route_layer(body_mw)
+            .route("/events", axum::routing::get(Self::session_events))
+            .route(
+                "/threshold-signature",
+                axum::routing::post(Self::submit_threshold_signature),
+            )
+            .route(
+                "/threshold-signature",
+                axum::routing::get(Self::get_threshold_signatures),
+            )
+            // The body is extracted before the authentication, the request signature covers it.
             .route_layer(auth_mw)
+            .route_layer(body_mw)
+            .with_state(shared_state.clone())
+            .layer(
+                ServiceBuilder::new()
+                    .layer(TimeoutLayer::new(API_TIMEOUT))
+                    .layer(CorsLayer::permissive())
+                    .into_inner(),
+            );
+
+        // Refund status is public, so the users can track the refund of their invalid deposits.
+        let refunds_router = axum::Router::new()
+            .route(
+                "/:deposit_tx_id",
+                axum::routing::get(Self::get_refund_status),
+            )
             .with_state(shared_state.clone())
             .layer(
                 ServiceBuilder::new()
@@ -108,7 +162,28 @@ impl RestApi {
                     .into_inner(),
             );
 
-        axum::Router::new().nest("/session", router)
+        // The outflow overrides are authenticated by the governance signature.
+        let outflow_router = axum::Router::new()
+            .route(
+                "/overrides",
+                axum::routing::post(Self::submit_outflow_override),
+            )
+            .route(
+                "/deferrals/:l1_batch_number/:bridge_tx_index",
+                axum::routing::get(Self::get_withdrawal_deferral),
+            )
+            .with_state(shared_state)
+            .layer(
+                ServiceBuilder::new()
+                    .layer(TimeoutLayer::new(API_TIMEOUT))
+                    .layer(CorsLayer::permissive())
+                    .into_inner(),
+            );
+
+        axum::Router::new()
+            .nest("/session", router)
+            .nest("/refunds", refunds_router)
+            .nest("/outflow", outflow_router)
     }
 
     pub async fn is_session_timeout(&self) -> bool {
@@ -1,19 +1,39 @@
-use std::{collections::BTreeMap, sync::Arc};
-
-use axum::{extract::State, response::Response, Json};
+use std::{collections::BTreeMap, convert::Infallible, str::FromStr, sync::Arc};
+
+use axum::{
+    extract::{Path, State},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
+    Json,
+};
 use base64::Engine;
-use musig2::{BinaryEncoding, PubNonce};
+use bitcoin::{hashes::Hash, Txid};
+use futures::Stream;
+use musig2::{BinaryEncoding, PartialSignature, PubNonce};
 use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
 use tracing::instrument;
 use via_btc_client::traits::Serializable;
 use via_musig2::utils::verify_partial_signature;
+use via_verifier_dal::VerifierDal;
+use zksync_types::H256;
 use zksync_utils::time::seconds_since_epoch;
 
 use super::{api_decl::RestApi, error::ApiError};
 use crate::{
     metrics::{MetricSessionType, VerifierErrorLabel, METRICS},
-    types::{NoncePair, PartialSignaturePair, SigningSession, SigningSessionResponse},
-    utils::{decode_signature, encode_signature},
+    outflow::verify_override,
+    types::{
+        NoncePair, OutflowOverrideRequest, PartialSignaturePair, RefundStatusResponse,
+        SessionEvent, SessionOperation, SigningSession, SigningSessionResponse,
+        ThresholdSignaturePair, WithdrawalDeferralResponse,
+    },
+    utils::{
+        decode_signature, decode_threshold_signature, encode_signature, encode_threshold_signature,
+        messages_hash,
+    },
 };
 
 fn ok_json<T: Serialize>(data: T) -> Response<String> {
@@ -61,29 +81,52 @@ impl RestApi {
 
             METRICS.session_new[&MetricSessionType::from(session_op.get_session_type())].inc();
 
-            let new_session = SigningSession {
+            let session_op_bytes = session_op
+                .to_bytes()
+                .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+            let created_at = seconds_since_epoch();
+
+            let mut signing_session = self_.state.signing_session.write().await;
+            let id = self_
+                .master_connection_pool
+                .connection_tagged("coordinator api")
+                .await?
+                .via_signing_sessions_dal()
+                .start_session(
+                    &session_op.get_session_type().to_string(),
+                    &session_op_bytes,
+                    &messages_hash(&session_op.get_message_to_sign()),
+                    created_at as i64,
+                )
+                .await?;
+
+            *signing_session = SigningSession {
+                id,
                 session_op: Some(session_op),
                 received_nonces: BTreeMap::new(),
                 received_sigs: BTreeMap::new(),
-                created_at: seconds_since_epoch(),
+                received_threshold_sigs: BTreeMap::new(),
+                created_at,
             };
-
-            let mut signing_session = self_.state.signing_session.write().await;
-            *signing_session = new_session;
+            self_.notify(SessionEvent::NewSession { session_id: id });
         } else {
-            self_.reset_session().await;
+            self_.reset_session().await?;
         }
 
         return Ok(ok_json(""));
     }
 
     #[instrument(skip(self_))]
-    pub async fn get_session(State(self_): State<Arc<Self>>) -> Response<String> {
+    pub async fn get_session(
+        State(self_): State<Arc<Self>>,
+    ) -> anyhow::Result<Response<String>, ApiError> {
         let session = self_.state.signing_session.read().await;
 
         let mut session_op_bytes = Vec::new();
         if let Some(session_op) = self_.state.signing_session.read().await.session_op.clone() {
-            session_op_bytes = session_op.to_bytes();
+            session_op_bytes = session_op
+                .to_bytes()
+                .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
         };
 
         let received_nonces = session
@@ -98,13 +141,36 @@ impl RestApi {
             .map(|(k, inner_map)| (*k, inner_map.len()))
             .collect();
 
-        return ok_json(SigningSessionResponse {
+        let received_threshold_signatures = session
+            .received_threshold_sigs
+            .iter()
+            .map(|(k, inner_map)| (*k, inner_map.len()))
+            .collect();
+
+        // The verifiers check the governance override themselves before signing above the limits.
+        let mut outflow_override = None;
+        if let Some(SessionOperation::Withdrawal(l1_batch_number, _, _, _, index)) =
+            &session.session_op
+        {
+            outflow_override = self_
+                .master_connection_pool
+                .connection_tagged("coordinator api")
+                .await?
+                .via_outflows_dal()
+                .get_deferral(*l1_batch_number, *index as i64)
+                .await?
+                .and_then(|deferral| deferral.override_signature);
+        }
+
+        Ok(ok_json(SigningSessionResponse {
             session_op: session_op_bytes,
             required_signers: self_.state.required_signers,
             received_nonces,
             received_partial_signatures,
+            received_threshold_signatures,
             created_at: session.created_at,
-        });
+            outflow_override,
+        }))
     }
 
     #[instrument(skip(self_))]
@@ -114,6 +180,18 @@ impl RestApi {
     ) -> anyhow::Result<Response<String>, ApiError> {
         let mut session = self_.state.signing_session.write().await;
 
+        let session_messages_hash = match session.session_op.as_ref() {
+            Some(session_op) => messages_hash(&session_op.get_message_to_sign()),
+            None => return Ok(ok_json("no session")),
+        };
+        let session_id = session.id;
+        let nonces_completed = session.nonces_completed(self_.state.required_signers);
+
+        let mut storage = self_
+            .master_connection_pool
+            .connection_tagged("coordinator api")
+            .await?;
+
         for (input_index, nonce_pair) in nonce_pair_per_input {
             let decoded = base64::engine::general_purpose::STANDARD
                 .decode(&nonce_pair.nonce)
@@ -122,12 +200,52 @@ impl RestApi {
             let pub_nonce = PubNonce::from_bytes(&decoded)
                 .map_err(|_| ApiError::BadRequest("Invalid public nonce format".to_string()))?;
 
-            let inner_map = session
+            if let Some(submitted) = session
+                .received_nonces
+                .get(&input_index)
+                .and_then(|nonces| nonces.get(&nonce_pair.signer_index))
+            {
+                if *submitted == pub_nonce {
+                    continue;
+                }
+                return Err(ApiError::BadRequest(format!(
+                    "Nonce already submitted for input {} by verifier index {}",
+                    input_index, nonce_pair.signer_index
+                )));
+            }
+
+            // Reusing a nonce in another session would leak the verifier private key.
+            if storage
+                .via_signing_sessions_dal()
+                .is_nonce_used_in_other_session(&decoded, session_id)
+                .await?
+            {
+                return Err(ApiError::BadRequest(format!(
+                    "Nonce reused by verifier index {}",
+                    nonce_pair.signer_index
+                )));
+            }
+
+            storage
+                .via_signing_sessions_dal()
+                .insert_nonce(
+                    session_id,
+                    input_index as i64,
+                    nonce_pair.signer_index as i64,
+                    &decoded,
+                    &session_messages_hash,
+                )
+                .await?;
+
+            session
                 .received_nonces
                 .entry(input_index)
-                .or_insert_with(BTreeMap::new);
+                .or_insert_with(BTreeMap::new)
+                .insert(nonce_pair.signer_index, pub_nonce);
+        }
 
-            inner_map.insert(nonce_pair.signer_index, pub_nonce);
+        if !nonces_completed && session.nonces_completed(self_.state.required_signers) {
+            self_.notify(SessionEvent::NoncesCompleted { session_id });
         }
 
         Ok(ok_json("Success"))
@@ -154,6 +272,7 @@ impl RestApi {
             .collect();
 
         let messages = session_op.get_message_to_sign();
+        let signatures_completed = session.signatures_completed(self_.state.required_signers);
 
         for (input_index, sig_pair) in sig_pair_per_input {
             let partial_sig = decode_signature(sig_pair.signature.clone()).map_err(|_| {
@@ -198,7 +317,7 @@ impl RestApi {
             ) {
                 drop(session);
 
-                self_.reset_session().await;
+                self_.reset_session().await?;
 
                 METRICS.verifier_errors[&VerifierErrorLabel {
                     pubkey: individual_pubkey_str.clone(),
@@ -214,6 +333,19 @@ impl RestApi {
                 )));
             }
 
+            self_
+                .master_connection_pool
+                .connection_tagged("coordinator api")
+                .await?
+                .via_signing_sessions_dal()
+                .insert_partial_signature(
+                    session.id,
+                    input_index as i64,
+                    sig_pair.signer_index as i64,
+                    &partial_sig.serialize(),
+                )
+                .await?;
+
             session
                 .received_sigs
                 .entry(input_index)
@@ -225,10 +357,135 @@ impl RestApi {
                 individual_pubkey_str
             );
         }
+
+        if !signatures_completed && session.signatures_completed(self_.state.required_signers) {
+            self_.notify(SessionEvent::SignaturesCompleted {
+                session_id: session.id,
+            });
+        }
+        drop(session);
+        Ok(ok_json("Success"))
+    }
+
+    /// Collects the script path signatures of the k-of-n leaf, used to spend the bridge when some
+    /// verifiers don't complete the MuSig2 session.
+    #[instrument(skip(self_))]
+    pub async fn submit_threshold_signature(
+        State(self_): State<Arc<Self>>,
+        Json(sig_pair_per_input): Json<BTreeMap<usize, ThresholdSignaturePair>>,
+    ) -> anyhow::Result<Response<String>, ApiError> {
+        let Some(bridge_taproot) = self_.state.bridge_taproot.as_ref() else {
+            return Err(ApiError::BadRequest(
+                "Threshold signing is not enabled".to_string(),
+            ));
+        };
+
+        let mut session = self_.state.signing_session.write().await;
+
+        let unsigned_tx = match session.session_op.as_ref() {
+            Some(session_op) => session_op.get_unsigned_bridge_tx(),
+            None => return Ok(ok_json("no session")),
+        };
+
+        let sighashes = self_
+            .transaction_builder
+            .build_script_path_tx(&unsigned_tx, bridge_taproot)
+            .and_then(|script_path_tx| {
+                self_
+                    .transaction_builder
+                    .get_tr_script_sighashes(&script_path_tx, bridge_taproot.threshold_leaf_hash())
+            })
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+
+        for (input_index, sig_pair) in sig_pair_per_input {
+            let signature = decode_threshold_signature(&sig_pair.signature).map_err(|_| {
+                ApiError::BadRequest("Error decoding the threshold signature".to_string())
+            })?;
+
+            let sighash = sighashes.get(input_index).ok_or_else(|| {
+                ApiError::BadRequest("Invalid input index in sighash list".to_string())
+            })?;
+
+            if let Err(e) =
+                bridge_taproot.verify_signature(sig_pair.signer_index, sighash, &signature)
+            {
+                let pubkey = self_
+                    .state
+                    .verifiers_pub_keys
+                    .get(sig_pair.signer_index)
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                METRICS.verifier_errors[&VerifierErrorLabel {
+                    pubkey,
+                    kind: crate::metrics::ErrorKind::ThresholdSignature,
+                }]
+                    .inc();
+
+                tracing::info!("Reject threshold signature: {}", e);
+
+                return Err(ApiError::BadRequest(format!(
+                    "Invalid threshold signature for verifier index: {}",
+                    sig_pair.signer_index
+                )));
+            }
+
+            session
+                .received_threshold_sigs
+                .entry(input_index)
+                .or_insert_with(BTreeMap::new)
+                .insert(sig_pair.signer_index, signature);
+        }
         drop(session);
         Ok(ok_json("Success"))
     }
 
+    #[instrument(skip(self_))]
+    pub async fn get_threshold_signatures(State(self_): State<Arc<Self>>) -> Response<String> {
+        let session = self_.state.signing_session.read().await;
+
+        let signatures: BTreeMap<usize, Vec<ThresholdSignaturePair>> = session
+            .received_threshold_sigs
+            .iter()
+            .map(|(&input_index, sigs_per_signer)| {
+                let encoded_sigs = sigs_per_signer
+                    .iter()
+                    .map(|(&signer_index, signature)| {
+                        encode_threshold_signature(signer_index, signature)
+                    })
+                    .collect();
+                (input_index, encoded_sigs)
+            })
+            .collect();
+        drop(session);
+        ok_json(signatures)
+    }
+
+    /// Streams the session events to a verifier as server-sent events.
+    #[instrument(skip(self_))]
+    pub async fn session_events(
+        State(self_): State<Arc<Self>>,
+    ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+        let receiver = self_.state.session_events.subscribe();
+
+        let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => match Event::default().json_data(&event) {
+                        Ok(sse_event) => return Some((Ok(sse_event), receiver)),
+                        Err(err) => tracing::warn!("Failed to encode session event: {err}"),
+                    },
+                    // A lagging verifier catches up with the next event or by polling.
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Session events subscriber skipped {skipped} events");
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+
+        Sse::new(stream).keep_alive(KeepAlive::default())
+    }
+
     #[instrument(skip(self_))]
     pub async fn get_nonces(State(self_): State<Arc<Self>>) -> Response<String> {
         let session = self_.state.signing_session.read().await;
@@ -271,8 +528,207 @@ impl RestApi {
         ok_json(signatures)
     }
 
-    pub async fn reset_session(&self) {
+    /// Returns the refunds of the invalid deposits made by a transaction.
+    #[instrument(skip(self_))]
+    pub async fn get_refund_status(
+        State(self_): State<Arc<Self>>,
+        Path(deposit_tx_id): Path<String>,
+    ) -> anyhow::Result<Response<String>, ApiError> {
+        let deposit_txid = Txid::from_str(&deposit_tx_id)
+            .map_err(|_| ApiError::BadRequest("Invalid deposit tx id".to_string()))?;
+        // Deposit tx ids are stored in display order.
+        let mut deposit_tx_id_bytes = deposit_txid.to_byte_array();
+        deposit_tx_id_bytes.reverse();
+
+        let refunds = self_
+            .master_connection_pool
+            .connection_tagged("coordinator api")
+            .await?
+            .via_refunds_dal()
+            .get_refunds_by_deposit(H256::from(deposit_tx_id_bytes))
+            .await?;
+
+        let refunds = refunds
+            .into_iter()
+            .map(|refund| RefundStatusResponse {
+                deposit_tx_id: deposit_txid.to_string(),
+                deposit_vout: refund.deposit_vout,
+                status: refund.status().to_string(),
+                refund_tx_id: refund
+                    .refund_tx_id
+                    .as_deref()
+                    .and_then(|bytes| Txid::from_slice(bytes).ok())
+                    .map(|txid| txid.to_string()),
+                sender: refund.sender,
+                amount: refund.amount,
+                reason: refund.reason,
+            })
+            .collect::<Vec<_>>();
+
+        Ok(ok_json(refunds))
+    }
+
+    /// Stores the governance approval of a withdrawal session above the outflow limits.
+    #[instrument(skip(self_))]
+    pub async fn submit_outflow_override(
+        State(self_): State<Arc<Self>>,
+        Json(request): Json<OutflowOverrideRequest>,
+    ) -> anyhow::Result<Response<String>, ApiError> {
+        let valid = verify_override(
+            &self_.config,
+            request.l1_batch_number,
+            request.bridge_tx_index,
+            &request.signature,
+        )
+        .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+        if !valid {
+            return Err(ApiError::Unauthorized("Invalid outflow override".into()));
+        }
+
+        self_
+            .master_connection_pool
+            .connection_tagged("coordinator api")
+            .await?
+            .via_outflows_dal()
+            .set_override_signature(
+                request.l1_batch_number,
+                request.bridge_tx_index,
+                &request.signature,
+            )
+            .await?;
+
+        tracing::info!(
+            "Outflow override approved for l1 batch {}, index {}",
+            request.l1_batch_number,
+            request.bridge_tx_index
+        );
+
+        Ok(ok_json("Outflow override submitted"))
+    }
+
+    /// Returns the status of a withdrawal session held back by the outflow limits.
+    #[instrument(skip(self_))]
+    pub async fn get_withdrawal_deferral(
+        State(self_): State<Arc<Self>>,
+        Path((l1_batch_number, bridge_tx_index)): Path<(i64, i64)>,
+    ) -> anyhow::Result<Response<String>, ApiError> {
+        let deferral = self_
+            .master_connection_pool
+            .connection_tagged("coordinator api")
+            .await?
+            .via_outflows_dal()
+            .get_deferral(l1_batch_number, bridge_tx_index)
+            .await?
+            .ok_or_else(|| ApiError::BadRequest("Withdrawal session not deferred".into()))?;
+
+        Ok(ok_json(WithdrawalDeferralResponse {
+            l1_batch_number: deferral.l1_batch_number,
+            bridge_tx_index: deferral.bridge_tx_index,
+            amount: deferral.amount,
+            status: deferral.status().to_string(),
+            reason: deferral.reason,
+        }))
+    }
+
+    fn notify(&self, event: SessionEvent) {
+        // No subscriber is not an error, the verifiers still poll the session.
+        let _ = self.state.session_events.send(event);
+    }
+
+    pub async fn reset_session(&self) -> anyhow::Result<(), ApiError> {
         let mut session = self.state.signing_session.write().await;
+        self.master_connection_pool
+            .connection_tagged("coordinator api")
+            .await?
+            .via_signing_sessions_dal()
+            .close_active_session()
+            .await?;
         *session = SigningSession::default();
+        Ok(())
+    }
+
+    /// Resumes the signing session which was active when the coordinator stopped, with the nonces
+    /// and partial signatures collected so far.
+    pub async fn restore_session(&self) -> anyhow::Result<()> {
+        let mut storage = self
+            .master_connection_pool
+            .connection_tagged("coordinator api")
+            .await?;
+
+        let Some(stored_session) = storage
+            .via_signing_sessions_dal()
+            .get_active_session()
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let session_op = match SessionOperation::from_bytes(&stored_session.session_op) {
+            Ok(session_op)
+                if messages_hash(&session_op.get_message_to_sign())
+                    == stored_session.messages_hash =>
+            {
+                session_op
+            }
+            _ => {
+                tracing::warn!(
+                    "Discard malformed signing session {} from storage",
+                    stored_session.id
+                );
+                storage
+                    .via_signing_sessions_dal()
+                    .close_active_session()
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        let mut received_nonces: BTreeMap<usize, BTreeMap<usize, PubNonce>> = BTreeMap::new();
+        for nonce in storage
+            .via_signing_sessions_dal()
+            .get_session_nonces(stored_session.id)
+            .await?
+        {
+            received_nonces
+                .entry(nonce.input_index as usize)
+                .or_default()
+                .insert(
+                    nonce.signer_index as usize,
+                    PubNonce::from_bytes(&nonce.pub_nonce)?,
+                );
+        }
+
+        let mut received_sigs: BTreeMap<usize, BTreeMap<usize, PartialSignature>> = BTreeMap::new();
+        for signature in storage
+            .via_signing_sessions_dal()
+            .get_session_partial_signatures(stored_session.id)
+            .await?
+        {
+            received_sigs
+                .entry(signature.input_index as usize)
+                .or_default()
+                .insert(
+                    signature.signer_index as usize,
+                    PartialSignature::from_slice(&signature.partial_sig)?,
+                );
+        }
+
+        tracing::info!(
+            "Restored {} signing session {} with {} nonces and {} partial signatures",
+            session_op.get_session_type(),
+            stored_session.id,
+            received_nonces.values().map(BTreeMap::len).sum::<usize>(),
+            received_sigs.values().map(BTreeMap::len).sum::<usize>(),
+        );
+
+        *self.state.signing_session.write().await = SigningSession {
+            id: stored_session.id,
+            session_op: Some(session_op),
+            received_nonces,
+            received_sigs,
+            received_threshold_sigs: BTreeMap::new(),
+            created_at: stored_session.started_at as u64,
+        };
+        Ok(())
     }
 }
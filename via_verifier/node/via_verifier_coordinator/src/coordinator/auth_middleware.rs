@@ -1,8 +1,8 @@
 use std::{str::FromStr, sync::Arc};
 
 use axum::{
-    body::{self, Body},
-    extract::{Request, State},
+    body::{self, Body, Bytes},
+    extract::{OriginalUri, Request, State},
     middleware::Next,
     response::Response,
 };
@@ -40,27 +40,54 @@ pub async fn auth_middleware(
         .and_then(|h| h.to_str().ok())
         .ok_or_else(|| ApiError::Unauthorized("Missing sequencer version header".into()))?;
 
+    let nonce = headers
+        .get("X-Nonce")
+        .and_then(|h| h.to_str().ok())
+        .filter(|nonce| !nonce.is_empty())
+        .ok_or_else(|| ApiError::Unauthorized("Missing nonce header".into()))?;
+
     // Validate the verifier index
     if verifier_index >= state.state.verifiers_pub_keys.len() {
         return Err(ApiError::Unauthorized("Invalid verifier index".into()));
     }
 
+    let request_timestamp = timestamp
+        .parse::<i64>()
+        .map_err(|_| ApiError::Unauthorized("Invalid timestamp header".into()))?;
     let timestamp_now = chrono::Utc::now().timestamp();
-    let timestamp_diff = timestamp_now - timestamp.parse::<i64>().unwrap();
+    let request_timeout = i64::from(state.state.verifier_request_timeout);
 
-    if timestamp_diff > state.state.verifier_request_timeout.into() {
+    if timestamp_now - request_timestamp > request_timeout {
         return Err(ApiError::Unauthorized("Timestamp is too old".into()));
     }
+    if request_timestamp - timestamp_now > request_timeout {
+        return Err(ApiError::Unauthorized("Timestamp is in the future".into()));
+    }
 
     // Get the public key for this verifier
     let public_key = &state.state.verifiers_pub_keys[verifier_index];
 
-    //  verify timestamp + verifier_index
-    let payload = serde_json::json!({
-        "timestamp": timestamp,
-        "verifier_index": verifier_index.to_string(),
-        "sequencer_version": sequencer_version
-    });
+    // The body is captured by `extract_body`, which runs first.
+    let body = request
+        .extensions()
+        .get::<Bytes>()
+        .cloned()
+        .unwrap_or_default();
+    // Nested routers see the path stripped from their prefix, the verifier signs the full path.
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map_or_else(|| request.uri().path(), |uri| uri.0.path());
+
+    let payload = crate::auth::request_payload(
+        timestamp,
+        &verifier_index.to_string(),
+        sequencer_version,
+        request.method().as_str(),
+        path,
+        &body,
+        nonce,
+    );
 
     // Verify the signature
     if !crate::auth::verify_signature(&payload, signature, public_key)
@@ -71,6 +98,17 @@ pub async fn auth_middleware(
         ));
     }
 
+    // Checked after the signature, so a caller can't fill the cache with forged nonces.
+    if !state.request_nonces.insert(
+        verifier_index,
+        nonce,
+        request_timestamp,
+        timestamp_now,
+        request_timeout,
+    ) {
+        return Err(ApiError::Unauthorized("Request replayed".into()));
+    }
+
     // Check the protocol version after the signature validation to make sure the caller is legit and avoid access db
     let mut storage = state.master_connection_pool.connection().await?;
 
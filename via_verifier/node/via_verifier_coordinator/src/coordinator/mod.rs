@@ -2,4 +2,4 @@ pub mod api;
 mod api_decl;
 mod api_impl;
 mod auth_middleware;
-mod error;
+pub(crate) mod error;
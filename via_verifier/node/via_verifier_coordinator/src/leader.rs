@@ -0,0 +1,328 @@
+//! Deterministic election of the coordinator among the verifiers.
+//!
+//! The leader is derived from the chain state indexed by every verifier, so they agree on it
+//! without any communication. A term spans `rotation_interval` finalized L1 batches, and its
+//! leader is the verifier at index `term % n`. The role then moves to the next verifier every
+//! `liveness_l1_blocks` Bitcoin blocks since the term started, so an unresponsive leader is
+//! replaced by the same verifier on every node.
+
+/// The chain state the leader is derived from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LeaderState {
+    /// The last finalized L1 batch.
+    pub l1_batch_number: u32,
+    /// The L1 block the first finalized L1 batch of the term was inscribed in.
+    pub term_l1_block_number: u32,
+    /// The last L1 block indexed by the verifier.
+    pub l1_block_number: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct LeaderElection {
+    coordinator_urls: Vec<String>,
+    own_index: usize,
+    rotation_interval: u64,
+    liveness_l1_blocks: u64,
+    leader_index: usize,
+}
+
+impl LeaderElection {
+    pub fn new(
+        coordinator_urls: Vec<String>,
+        own_index: usize,
+        rotation_interval: u64,
+        liveness_l1_blocks: u64,
+    ) -> anyhow::Result<Self> {
+        if own_index >= coordinator_urls.len() {
+            anyhow::bail!(
+                "Verifier index {} out of {} coordinator urls",
+                own_index,
+                coordinator_urls.len()
+            );
+        }
+        if rotation_interval == 0 {
+            anyhow::bail!("Leader rotation interval must be positive");
+        }
+        if liveness_l1_blocks == 0 {
+            anyhow::bail!("Leader liveness must be positive");
+        }
+
+        Ok(Self {
+            coordinator_urls,
+            own_index,
+            rotation_interval,
+            liveness_l1_blocks,
+            leader_index: 0,
+        })
+    }
+
+    /// Returns the first L1 batch of the term of `l1_batch_number`.
+    pub fn term_start(&self, l1_batch_number: u32) -> u32 {
+        let term_start =
+            u64::from(l1_batch_number) / self.rotation_interval * self.rotation_interval;
+        term_start as u32
+    }
+
+    /// Moves to the leader of `state`, returns whether the leader changed.
+    pub fn update(&mut self, state: LeaderState) -> bool {
+        let term = u64::from(state.l1_batch_number) / self.rotation_interval;
+        let failovers = u64::from(
+            state
+                .l1_block_number
+                .saturating_sub(state.term_l1_block_number),
+        ) / self.liveness_l1_blocks;
+        let leader_index = ((term + failovers) % self.coordinator_urls.len() as u64) as usize;
+
+        let previous_leader = std::mem::replace(&mut self.leader_index, leader_index);
+        previous_leader != leader_index
+    }
+
+    pub fn leader_index(&self) -> usize {
+        self.leader_index
+    }
+
+    pub fn leader_url(&self) -> &str {
+        &self.coordinator_urls[self.leader_index]
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.leader_index == self.own_index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use musig2::{CompactSignature, PartialSignature, PubNonce};
+    use secp256k1_musig2::{PublicKey, Secp256k1, SecretKey};
+    use via_musig2::{verify_signature, Signer};
+
+    use super::*;
+
+    fn election(own_index: usize) -> LeaderElection {
+        let urls = (0..3)
+            .map(|i| format!("http://verifier-{i}:6060"))
+            .collect();
+        LeaderElection::new(urls, own_index, 10, 6).unwrap()
+    }
+
+    fn state(l1_batch_number: u32, l1_block_number: u32) -> LeaderState {
+        LeaderState {
+            l1_batch_number,
+            term_l1_block_number: 100,
+            l1_block_number,
+        }
+    }
+
+    /// The state of the signing session held by the leader.
+    #[derive(Default)]
+    struct LeaderSession {
+        message: Vec<u8>,
+        nonces: BTreeMap<usize, PubNonce>,
+        partial_sigs: BTreeMap<usize, PartialSignature>,
+    }
+
+    /// A verifier following the elected leader, see `ViaWithdrawalVerifier`.
+    struct TestVerifier {
+        election: LeaderElection,
+        secret_key: SecretKey,
+        all_pubkeys: Vec<PublicKey>,
+        signer: Option<Signer>,
+    }
+
+    impl TestVerifier {
+        fn new(secret_keys: &[SecretKey], index: usize) -> Self {
+            let secp = Secp256k1::new();
+            Self {
+                election: election(index),
+                secret_key: secret_keys[index],
+                all_pubkeys: secret_keys
+                    .iter()
+                    .map(|key| PublicKey::from_secret_key(&secp, key))
+                    .collect(),
+                signer: None,
+            }
+        }
+
+        fn update(&mut self, state: LeaderState) {
+            if self.election.update(state) {
+                // The session of the previous leader is lost, start over with the new one.
+                self.signer = None;
+            }
+        }
+
+        fn submit_nonce(&mut self, session: &mut LeaderSession) {
+            let mut signer = Signer::new(
+                self.secret_key,
+                self.election.own_index,
+                self.all_pubkeys.clone(),
+                None,
+            )
+            .unwrap();
+            let nonce = signer
+                .start_signing_session(session.message.clone())
+                .unwrap();
+            session.nonces.insert(self.election.own_index, nonce);
+            self.signer = Some(signer);
+        }
+
+        fn submit_partial_sig(&mut self, session: &mut LeaderSession) {
+            let signer = self.signer.as_mut().unwrap();
+            for (index, nonce) in &session.nonces {
+                if *index != self.election.own_index {
+                    signer.receive_nonce(*index, nonce.clone()).unwrap();
+                }
+            }
+            let partial_sig = signer.create_partial_signature().unwrap();
+            session
+                .partial_sigs
+                .insert(self.election.own_index, partial_sig);
+        }
+
+        fn create_final_signature(&mut self, session: &LeaderSession) -> CompactSignature {
+            let signer = self.signer.as_mut().unwrap();
+            for (index, partial_sig) in &session.partial_sigs {
+                if *index != self.election.own_index {
+                    signer
+                        .receive_partial_signature(*index, *partial_sig)
+                        .unwrap();
+                }
+            }
+            signer.create_final_signature().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_leader_rotates_with_l1_batches() {
+        let mut election = election(1);
+        assert_eq!(election.leader_index(), 0);
+        assert!(!election.is_leader());
+
+        assert!(!election.update(state(9, 100)));
+        assert_eq!(election.leader_index(), 0);
+
+        assert!(election.update(state(10, 100)));
+        assert_eq!(election.leader_index(), 1);
+        assert_eq!(election.leader_url(), "http://verifier-1:6060");
+        assert!(election.is_leader());
+
+        assert!(election.update(state(25, 100)));
+        assert_eq!(election.leader_index(), 2);
+
+        assert!(election.update(state(30, 100)));
+        assert_eq!(election.leader_index(), 0);
+
+        assert_eq!(election.term_start(0), 0);
+        assert_eq!(election.term_start(9), 0);
+        assert_eq!(election.term_start(25), 20);
+    }
+
+    #[test]
+    fn test_leader_moves_with_l1_blocks() {
+        let mut election = election(0);
+        assert!(!election.update(state(0, 105)));
+        assert!(election.is_leader());
+
+        // The term lasted `liveness_l1_blocks`, the next verifier takes over.
+        assert!(election.update(state(0, 106)));
+        assert_eq!(election.leader_index(), 1);
+        assert!(election.update(state(0, 118)));
+        assert_eq!(election.leader_index(), 0);
+
+        // The next term starts from its own leader again.
+        let mut next_term = state(10, 118);
+        next_term.term_l1_block_number = 118;
+        assert!(election.update(next_term));
+        assert_eq!(election.leader_index(), 1);
+    }
+
+    #[test]
+    fn test_all_verifiers_agree_on_the_leader() {
+        let mut elections: Vec<_> = (0..3).map(election).collect();
+        for (l1_batch_number, l1_block_number) in
+            [(0, 100), (7, 103), (13, 110), (42, 99), (1_000, 200)]
+        {
+            for election in &mut elections {
+                election.update(state(l1_batch_number, l1_block_number));
+            }
+            let leaders: Vec<_> = elections.iter().filter(|e| e.is_leader()).collect();
+            assert_eq!(leaders.len(), 1);
+            assert!(elections
+                .iter()
+                .all(|e| e.leader_index() == elections[0].leader_index()));
+        }
+    }
+
+    #[test]
+    fn test_leader_killed_mid_session() {
+        let secret_keys: Vec<_> = (1..=3)
+            .map(|i| SecretKey::from_byte_array(&[i; 32]).unwrap())
+            .collect();
+        let mut verifiers: Vec<_> = (0..3).map(|i| TestVerifier::new(&secret_keys, i)).collect();
+        let message = vec![7; 32];
+
+        for verifier in &mut verifiers {
+            verifier.update(state(0, 100));
+        }
+        assert!(verifiers[0].election.is_leader());
+
+        // The leader collects the nonces and the partial signature of verifier 1, then crashes and
+        // loses its session.
+        let mut session = LeaderSession {
+            message: message.clone(),
+            ..LeaderSession::default()
+        };
+        for verifier in &mut verifiers {
+            verifier.submit_nonce(&mut session);
+        }
+        verifiers[1].submit_partial_sig(&mut session);
+        let stale_nonce = session.nonces[&1].clone();
+        drop(session);
+        verifiers.remove(0);
+
+        // Until the liveness elapses, the verifiers keep their session with the dead leader.
+        for verifier in &mut verifiers {
+            verifier.update(state(0, 105));
+            assert_eq!(verifier.election.leader_index(), 0);
+            assert!(verifier.signer.is_some());
+        }
+
+        // Then every verifier moves to the same leader and drops the session of the dead one.
+        for verifier in &mut verifiers {
+            verifier.update(state(0, 106));
+            assert_eq!(verifier.election.leader_index(), 1);
+            assert!(verifier.signer.is_none());
+        }
+        assert!(verifiers[0].election.is_leader());
+
+        // The new leader signs the message from scratch with all the verifiers, including the
+        // crashed one once restarted, as its key is part of the aggregated key.
+        let mut session = LeaderSession {
+            message: message.clone(),
+            ..LeaderSession::default()
+        };
+        let mut restarted = TestVerifier::new(&secret_keys, 0);
+        restarted.update(state(0, 106));
+        verifiers.insert(0, restarted);
+        for verifier in &mut verifiers {
+            verifier.submit_nonce(&mut session);
+        }
+        // The nonces of the lost session are never reused.
+        assert_ne!(session.nonces[&1], stale_nonce);
+        for verifier in &mut verifiers {
+            verifier.submit_partial_sig(&mut session);
+        }
+        let signature = verifiers[1].create_final_signature(&session);
+
+        let aggregated_pubkey = verifiers[1].signer.as_ref().unwrap().aggregated_pubkey();
+        verify_signature(aggregated_pubkey, signature, &message).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_election() {
+        assert!(LeaderElection::new(vec![], 0, 10, 6).is_err());
+        assert!(LeaderElection::new(vec!["http://verifier-0:6060".into()], 0, 0, 6).is_err());
+        assert!(LeaderElection::new(vec!["http://verifier-0:6060".into()], 0, 10, 0).is_err());
+    }
+}
@@ -1,10 +1,14 @@
 pub mod coordinator;
+pub mod leader;
 pub mod sessions;
+pub mod status;
+pub mod utils;
 pub mod verifier;
 
 mod auth;
 mod metrics;
+mod outflow;
+mod tls;
 mod types;
-mod utils;
 
 mod traits;
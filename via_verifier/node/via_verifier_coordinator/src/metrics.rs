@@ -8,6 +8,7 @@ use crate::types::SessionType;
 #[metrics(rename_all = "snake_case")]
 pub enum ErrorKind {
     PartialSignature,
+    ThresholdSignature,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
@@ -20,12 +21,14 @@ pub struct VerifierErrorLabel {
 #[metrics(label = "error_type", rename_all = "snake_case")]
 pub enum MetricSessionType {
     Withdrawal,
+    Refund,
 }
 
 impl From<SessionType> for MetricSessionType {
     fn from(value: SessionType) -> Self {
         match value {
             SessionType::Withdrawal => MetricSessionType::Withdrawal,
+            SessionType::Refund => MetricSessionType::Refund,
         }
     }
 }
@@ -51,6 +54,9 @@ pub struct ViaVerifierCoordinatorMetrics {
 
     /// Errors
     pub verifier_errors: Family<VerifierErrorLabel, Counter>,
+
+    /// Index of the verifier currently acting as coordinator.
+    pub leader_index: Gauge<usize>,
 }
 
 #[vise::register]
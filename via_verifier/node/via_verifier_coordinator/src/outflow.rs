@@ -0,0 +1,256 @@
+//! Outflow limits of the bridge.
+//!
+//! The withdrawals of an L1 batch are split into bridge transactions sending at most
+//! `max_session_outflow` each, every bridge transaction being signed in a session of its own. A
+//! session is held back when it still withdraws more than `max_session_outflow` (a single
+//! withdrawal above it), when it brings the total withdrawn within the outflow window above
+//! `max_window_outflow`, or the total withdrawn to one address above `max_address_outflow`. The
+//! governance can approve a session above the limits by signing an override.
+//!
+//! The outflows count toward the window once the bridge transaction is broadcast or indexed.
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use bitcoin::{secp256k1::PublicKey, Script, Transaction};
+use serde::Serialize;
+use zksync_config::configs::via_verifier::ViaVerifierConfig;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct OutflowLimits {
+    pub max_session_outflow: Option<u64>,
+    pub max_window_outflow: Option<u64>,
+    pub max_address_outflow: Option<u64>,
+}
+
+impl OutflowLimits {
+    pub fn from_config(config: &ViaVerifierConfig) -> Self {
+        Self {
+            max_session_outflow: config.max_session_outflow,
+            max_window_outflow: config.max_window_outflow,
+            max_address_outflow: config.max_address_outflow,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_session_outflow.is_some()
+            || self.max_window_outflow.is_some()
+            || self.max_address_outflow.is_some()
+    }
+
+    /// Checks the outflows of a session against the limits, given the totals already withdrawn
+    /// within the outflow window. Returns the reason the session exceeds the limits.
+    pub fn check(
+        &self,
+        outflows: &BTreeMap<Vec<u8>, u64>,
+        window_outflow: u64,
+        address_outflows: &BTreeMap<Vec<u8>, u64>,
+    ) -> Option<String> {
+        let session_outflow = outflows.values().sum::<u64>();
+
+        if let Some(max) = self.max_session_outflow {
+            if session_outflow > max {
+                return Some(format!(
+                    "Session outflow {session_outflow} exceeds the limit {max}"
+                ));
+            }
+        }
+
+        if let Some(max) = self.max_window_outflow {
+            let total = window_outflow.saturating_add(session_outflow);
+            if total > max {
+                return Some(format!("Window outflow {total} exceeds the limit {max}"));
+            }
+        }
+
+        if let Some(max) = self.max_address_outflow {
+            for (script_pubkey, amount) in outflows {
+                let total = address_outflows
+                    .get(script_pubkey)
+                    .copied()
+                    .unwrap_or_default()
+                    .saturating_add(*amount);
+                if total > max {
+                    return Some(format!(
+                        "Outflow {total} to {} exceeds the limit {max}",
+                        hex::encode(script_pubkey)
+                    ));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns the amount withdrawn to each address by a bridge transaction. The OP_RETURN and the
+/// change sent back to the bridge, which the transaction only has when there is some change left,
+/// are not outflows.
+pub(crate) fn session_outflows(
+    tx: &Transaction,
+    bridge_script_pubkey: &Script,
+) -> BTreeMap<Vec<u8>, u64> {
+    let mut outflows = BTreeMap::new();
+    for txout in &tx.output {
+        if txout.script_pubkey.is_op_return()
+            || txout.script_pubkey.as_script() == bridge_script_pubkey
+        {
+            continue;
+        }
+        *outflows.entry(txout.script_pubkey.to_bytes()).or_default() += txout.value.to_sat();
+    }
+    outflows
+}
+
+/// Payload signed by the governance to approve a withdrawal session above the outflow limits.
+#[derive(Debug, Serialize)]
+struct OutflowOverride {
+    action: &'static str,
+    l1_batch_number: i64,
+    bridge_tx_index: i64,
+}
+
+impl OutflowOverride {
+    fn new(l1_batch_number: i64, bridge_tx_index: i64) -> Self {
+        Self {
+            action: "outflow_override",
+            l1_batch_number,
+            bridge_tx_index,
+        }
+    }
+}
+
+/// Verifies the governance override of the withdrawal session `(l1_batch_number, bridge_tx_index)`.
+/// An override is never valid when no governance key is configured.
+pub(crate) fn verify_override(
+    config: &ViaVerifierConfig,
+    l1_batch_number: i64,
+    bridge_tx_index: i64,
+    signature: &str,
+) -> anyhow::Result<bool> {
+    let Some(governance_pub_key) = config
+        .outflow_governance_pub_key
+        .as_deref()
+        .filter(|key| !key.is_empty())
+    else {
+        return Ok(false);
+    };
+    let public_key = PublicKey::from_str(governance_pub_key)?;
+
+    crate::auth::verify_signature(
+        &OutflowOverride::new(l1_batch_number, bridge_tx_index),
+        signature,
+        &public_key,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, secp256k1::Secp256k1, transaction::Version, Amount, ScriptBuf, TxOut,
+    };
+
+    use super::*;
+
+    fn limits() -> OutflowLimits {
+        OutflowLimits {
+            max_session_outflow: Some(1000),
+            max_window_outflow: Some(2000),
+            max_address_outflow: Some(600),
+        }
+    }
+
+    #[test]
+    fn test_check_outflow_limits() {
+        let alice = vec![1];
+        let bob = vec![2];
+        let outflows = BTreeMap::from([(alice.clone(), 500), (bob.clone(), 400)]);
+
+        assert_eq!(limits().check(&outflows, 0, &BTreeMap::new()), None);
+        assert!(OutflowLimits::default()
+            .check(&outflows, u64::MAX, &BTreeMap::new())
+            .is_none());
+
+        let too_large = BTreeMap::from([(alice.clone(), 500), (bob.clone(), 501)]);
+        assert!(limits()
+            .check(&too_large, 0, &BTreeMap::new())
+            .unwrap()
+            .starts_with("Session outflow"));
+
+        assert!(limits()
+            .check(&outflows, 1101, &BTreeMap::new())
+            .unwrap()
+            .starts_with("Window outflow"));
+
+        let address_outflows = BTreeMap::from([(bob, 201)]);
+        assert!(limits()
+            .check(&outflows, 0, &address_outflows)
+            .unwrap()
+            .contains("to 02"));
+    }
+
+    fn output(script: &[u8], value: u64) -> TxOut {
+        TxOut {
+            value: Amount::from_sat(value),
+            script_pubkey: ScriptBuf::from_bytes(script.to_vec()),
+        }
+    }
+
+    fn bridge_tx(output: Vec<TxOut>) -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output,
+        }
+    }
+
+    #[test]
+    fn test_session_outflows() {
+        let bridge = ScriptBuf::from_bytes(vec![3]);
+        let tx = bridge_tx(vec![
+            output(&[1], 100),
+            output(&[2], 200),
+            output(&[1], 50),
+            // OP_RETURN and change.
+            output(&[0x6a], 0),
+            output(&[3], 10000),
+        ]);
+
+        assert_eq!(
+            session_outflows(&tx, &bridge),
+            BTreeMap::from([(vec![1], 150), (vec![2], 200)])
+        );
+    }
+
+    #[test]
+    fn test_session_outflows_without_change() {
+        let bridge = ScriptBuf::from_bytes(vec![3]);
+        let tx = bridge_tx(vec![
+            output(&[1], 100),
+            output(&[2], 200),
+            output(&[0x6a], 0),
+        ]);
+
+        assert_eq!(
+            session_outflows(&tx, &bridge),
+            BTreeMap::from([(vec![1], 100), (vec![2], 200)])
+        );
+    }
+
+    #[test]
+    fn test_verify_override() {
+        let secp = Secp256k1::new();
+        let (secret_key, public_key) =
+            secp.generate_keypair(&mut bitcoin::secp256k1::rand::rngs::OsRng);
+        let signature =
+            crate::auth::sign_request(&OutflowOverride::new(10, 0), &secret_key).unwrap();
+
+        let mut config = ViaVerifierConfig::for_tests();
+        assert!(!verify_override(&config, 10, 0, &signature).unwrap());
+
+        config.outflow_governance_pub_key = Some(public_key.to_string());
+        assert!(verify_override(&config, 10, 0, &signature).unwrap());
+        assert!(!verify_override(&config, 10, 1, &signature).unwrap());
+        assert!(!verify_override(&config, 11, 0, &signature).unwrap());
+    }
+}
@@ -1,2 +1,3 @@
+pub(crate) mod refund;
 pub mod session_manager;
 pub(crate) mod withdrawal;
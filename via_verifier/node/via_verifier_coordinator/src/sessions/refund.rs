@@ -0,0 +1,570 @@
+use std::{any::Any, str::FromStr, sync::Arc};
+
+use anyhow::Context;
+use axum::async_trait;
+use bitcoin::{hashes::Hash, Address, Amount, OutPoint, TxOut, Txid};
+use via_btc_client::{
+    traits::{BitcoinOps, Serializable},
+    types::BitcoinError,
+};
+use via_musig2::{fee::WithdrawalFeeStrategy, transaction_builder::TransactionBuilder};
+use via_verifier_dal::{
+    models::storage_refund::{RefundStatus, StorageRefund},
+    ConnectionPool, Verifier, VerifierDal,
+};
+use via_verifier_types::transaction::UnsignedBridgeTx;
+use zksync_config::ViaVerifierConfig;
+
+use crate::{traits::ISession, types::SessionOperation, utils::h256_to_txid};
+
+const OP_RETURN_REFUND_PREFIX: &[u8] = b"VIA_PROTOCOL:REFUND:";
+
+/// Returns the BTC of the deposits that can't be credited on L2 to their sender, minus the
+/// transaction fee.
+#[derive(Debug, Clone)]
+pub struct RefundSession {
+    verifier_config: ViaVerifierConfig,
+    master_connection_pool: ConnectionPool<Verifier>,
+    transaction_builder: Arc<TransactionBuilder>,
+}
+
+impl RefundSession {
+    pub fn new(
+        verifier_config: ViaVerifierConfig,
+        master_connection_pool: ConnectionPool<Verifier>,
+        transaction_builder: Arc<TransactionBuilder>,
+    ) -> Self {
+        Self {
+            verifier_config,
+            master_connection_pool,
+            transaction_builder,
+        }
+    }
+}
+
+#[async_trait]
+impl ISession for RefundSession {
+    async fn session(&self) -> anyhow::Result<Option<SessionOperation>> {
+        self.retry_dropped_refunds().await?;
+
+        let Some(refund) = self
+            .master_connection_pool
+            .connection_tagged("refund session")
+            .await?
+            .via_refunds_dal()
+            .get_next_pending_refund()
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let unsigned_tx = match &refund.data {
+            Some(data) => UnsignedBridgeTx::from_bytes(data)?,
+            None => {
+                let unsigned_tx = self.create_unsigned_tx(&refund, None, None).await?;
+
+                if unsigned_tx.is_empty() {
+                    tracing::warn!(
+                        "The deposit {} of {} sats doesn't cover the refund fee",
+                        refund.deposit_tx_id(),
+                        refund.amount
+                    );
+                    self.update_refund_status(refund.id, RefundStatus::Unrefundable, None)
+                        .await?;
+                    return Ok(None);
+                }
+
+                self.master_connection_pool
+                    .connection_tagged("refund session")
+                    .await?
+                    .via_refunds_dal()
+                    .set_refund_unsigned_tx(refund.id, &unsigned_tx.to_bytes()?)
+                    .await?;
+                unsigned_tx
+            }
+        };
+
+        let sighashes = self.transaction_builder.get_tr_sighashes(&unsigned_tx)?;
+
+        tracing::info!(
+            "New refund session found for deposit {}",
+            refund.deposit_tx_id()
+        );
+
+        Ok(Some(SessionOperation::Refund(
+            refund.deposit_tx_id(),
+            refund.deposit_vout,
+            vec![unsigned_tx],
+            sighashes,
+            0,
+        )))
+    }
+
+    async fn is_session_in_progress(&self, session_op: &SessionOperation) -> anyhow::Result<bool> {
+        match self.get_refund(session_op).await? {
+            Some(refund) => self.is_refund_pending(&refund).await,
+            None => Ok(false),
+        }
+    }
+
+    async fn verify_message(&self, session_op: &SessionOperation) -> anyhow::Result<bool> {
+        let Some((unsigned_tx, messages)) = session_op.session() else {
+            return Ok(false);
+        };
+
+        let Some(refund) = self.get_refund(session_op).await? else {
+            tracing::error!("Refund session for an unknown deposit");
+            return Ok(false);
+        };
+
+        if !self.is_refund_pending(&refund).await? {
+            tracing::error!(
+                "Refund of deposit {} is not pending",
+                refund.deposit_tx_id()
+            );
+            return Ok(false);
+        }
+
+        // Verify the fee used to build the refund transaction.
+        let fee_rate = self
+            .transaction_builder
+            .utxo_manager
+            .get_btc_client()
+            .get_fee_rate(1)
+            .await?;
+
+        // Acceptable if difference is within ±1 sat/vbyte
+        if (unsigned_tx.fee_rate as i64 - fee_rate as i64).abs() > 1 {
+            tracing::error!(
+                "Fee mismatch: used={}, network={}",
+                unsigned_tx.fee_rate,
+                fee_rate
+            );
+            return Ok(false);
+        }
+
+        let recovered_unsigned_tx = self
+            .create_unsigned_tx(
+                &refund,
+                Some(unsigned_tx.fee_rate),
+                Some(unsigned_tx.utxos.clone()),
+            )
+            .await?;
+
+        if recovered_unsigned_tx != unsigned_tx || recovered_unsigned_tx.is_empty() {
+            tracing::error!(
+                "Mismatch in unsigned refund transaction for deposit {}",
+                refund.deposit_tx_id()
+            );
+            return Ok(false);
+        }
+
+        let sighashes = self.transaction_builder.get_tr_sighashes(&unsigned_tx)?;
+        if messages != &sighashes {
+            tracing::error!(
+                "Invalid transaction sighashes for the refund of deposit {}",
+                refund.deposit_tx_id()
+            );
+            return Ok(false);
+        }
+
+        tracing::info!("Refund of deposit {} verified", refund.deposit_tx_id());
+        Ok(true)
+    }
+
+    async fn before_process_session(&self, _: &SessionOperation) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+
+    async fn before_broadcast_final_transaction(
+        &self,
+        session_op: &SessionOperation,
+    ) -> anyhow::Result<bool> {
+        self.is_session_in_progress(session_op).await
+    }
+
+    async fn after_broadcast_final_transaction(
+        &self,
+        txid: Txid,
+        session_op: &SessionOperation,
+    ) -> anyhow::Result<bool> {
+        let refund = self
+            .get_refund(session_op)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Refund does not exist"))?;
+
+        self.update_refund_status(
+            refund.id,
+            RefundStatus::Broadcast,
+            Some(&txid.to_byte_array()),
+        )
+        .await?;
+
+        self.transaction_builder
+            .utxo_manager_insert_transaction(session_op.get_unsigned_bridge_tx().tx.clone())
+            .await;
+
+        tracing::info!(
+            "Final refund transaction broadcasted: deposit {}, txid {}",
+            refund.deposit_tx_id(),
+            txid
+        );
+
+        Ok(true)
+    }
+
+    async fn is_bridge_session_already_processed(
+        &self,
+        session_op: &SessionOperation,
+    ) -> anyhow::Result<bool> {
+        match self.get_refund(session_op).await? {
+            Some(refund) => Ok(!self.is_refund_pending(&refund).await?),
+            None => Ok(false),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl RefundSession {
+    fn btc_client(&self) -> Arc<dyn BitcoinOps> {
+        self.transaction_builder.utxo_manager.get_btc_client()
+    }
+
+    /// Whether the refund still has to be signed: it's pending, or its broadcast transaction was
+    /// dropped. A verifier other than the one which broadcast it only sees it pending.
+    async fn is_refund_pending(&self, refund: &StorageRefund) -> anyhow::Result<bool> {
+        match refund.status() {
+            RefundStatus::Pending => Ok(true),
+            RefundStatus::Broadcast => self.is_refund_tx_dropped(refund).await,
+            RefundStatus::Refunded | RefundStatus::Unrefundable => Ok(false),
+        }
+    }
+
+    /// Returns whether the broadcast refund transaction is unknown to the node, i.e. it was dropped
+    /// from the mempool without being mined.
+    async fn is_refund_tx_dropped(&self, refund: &StorageRefund) -> anyhow::Result<bool> {
+        let Some(refund_tx_id) = &refund.refund_tx_id else {
+            return Ok(false);
+        };
+        let txid = Txid::from_slice(refund_tx_id).with_context(|| "Invalid refund tx id")?;
+
+        match self.btc_client().get_transaction(&txid).await {
+            Ok(_) => Ok(false),
+            Err(BitcoinError::NotFound(_)) => Ok(true),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Moves the broadcast refunds whose transaction was dropped back to `Pending`, so they're
+    /// signed and broadcast again. The new transaction spends the UTXOs of the dropped one at the
+    /// current fee rate, so at most one of them is mined.
+    async fn retry_dropped_refunds(&self) -> anyhow::Result<()> {
+        let refunds = self
+            .master_connection_pool
+            .connection_tagged("refund session")
+            .await?
+            .via_refunds_dal()
+            .list_broadcast_refunds()
+            .await?;
+
+        for refund in refunds {
+            if !self.is_refund_tx_dropped(&refund).await? {
+                continue;
+            }
+
+            let utxos = match &refund.data {
+                Some(data) => Some(UnsignedBridgeTx::from_bytes(data)?.utxos),
+                None => None,
+            };
+            let unsigned_tx = self.create_unsigned_tx(&refund, None, utxos).await?;
+            if unsigned_tx.is_empty() {
+                tracing::warn!(
+                    "The refund of deposit {} can't be retried at the current fee rate",
+                    refund.deposit_tx_id()
+                );
+                continue;
+            }
+
+            tracing::warn!(
+                "The refund transaction of deposit {} was dropped, retrying it",
+                refund.deposit_tx_id()
+            );
+            self.master_connection_pool
+                .connection_tagged("refund session")
+                .await?
+                .via_refunds_dal()
+                .reset_broadcast_refund(refund.id, &unsigned_tx.to_bytes()?)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn get_refund(
+        &self,
+        session_op: &SessionOperation,
+    ) -> anyhow::Result<Option<StorageRefund>> {
+        let Some((deposit_tx_id, deposit_vout)) = session_op.get_refund_deposit() else {
+            return Ok(None);
+        };
+
+        let refunds = self
+            .master_connection_pool
+            .connection_tagged("refund session")
+            .await?
+            .via_refunds_dal()
+            .get_refunds_by_deposit(deposit_tx_id)
+            .await?;
+
+        Ok(refunds
+            .into_iter()
+            .find(|refund| refund.deposit_vout == deposit_vout))
+    }
+
+    async fn update_refund_status(
+        &self,
+        id: i64,
+        status: RefundStatus,
+        refund_tx_id: Option<&[u8]>,
+    ) -> anyhow::Result<()> {
+        self.master_connection_pool
+            .connection_tagged("refund session")
+            .await?
+            .via_refunds_dal()
+            .update_refund_status(id, status, refund_tx_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn create_unsigned_tx(
+        &self,
+        refund: &StorageRefund,
+        default_fee_rate: Option<u64>,
+        default_available_utxos: Option<Vec<(OutPoint, TxOut)>>,
+    ) -> anyhow::Result<UnsignedBridgeTx> {
+        let sender = refund
+            .sender
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Refund without a sender"))?;
+        let sender = Address::from_str(sender)
+            .with_context(|| "Invalid refund sender")?
+            .assume_checked();
+        let deposit_txid = h256_to_txid(refund.deposit_tx_id.as_slice())
+            .with_context(|| "Invalid deposit tx id")?;
+
+        let outputs = vec![TxOut {
+            value: Amount::from_sat(refund.amount as u64),
+            script_pubkey: sender.script_pubkey(),
+        }];
+
+        let mut unsigned_txs = self
+            .transaction_builder
+            .build_transaction_with_op_return(
+                outputs,
+                OP_RETURN_REFUND_PREFIX,
+                vec![deposit_txid.as_byte_array().as_slice()],
+                Arc::new(WithdrawalFeeStrategy::new()),
+                default_fee_rate,
+                default_available_utxos,
+                self.verifier_config.max_tx_weight(),
+            )
+            .await?;
+
+        // A single output always fits in one transaction.
+        if unsigned_txs.len() != 1 {
+            anyhow::bail!(
+                "Expected a single refund transaction, got {}",
+                unsigned_txs.len()
+            );
+        }
+        Ok(unsigned_txs.remove(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, secp256k1::Secp256k1, transaction::Version, CompressedPublicKey,
+        Network, PrivateKey, Transaction,
+    };
+    use via_btc_client::inscriber::test_utils::{MockBitcoinOps, MockBitcoinOpsConfig};
+    use zksync_types::H256;
+
+    use super::*;
+
+    const SENDER: &str = "bcrt1qw2mvkvm6alfhe86yf328kgvr7mupdx4vln7kpv";
+
+    fn bridge_address() -> Address {
+        let secp = Secp256k1::new();
+        let key = CompressedPublicKey::from_private_key(
+            &secp,
+            &PrivateKey::from_slice(&[1; 32], Network::Regtest).unwrap(),
+        )
+        .unwrap();
+        Address::p2wpkh(&key, Network::Regtest)
+    }
+
+    /// Creates a refund session whose node knows the transaction `known_tx`, any other transaction
+    /// being unknown.
+    fn create_session(
+        pool: &ConnectionPool<Verifier>,
+        known_tx: Option<Transaction>,
+    ) -> RefundSession {
+        let utxos = (0..2)
+            .map(|vout| {
+                (
+                    OutPoint {
+                        txid: Txid::from_byte_array([7; 32]),
+                        vout,
+                    },
+                    TxOut {
+                        value: Amount::from_sat(100_000),
+                        script_pubkey: bridge_address().script_pubkey(),
+                    },
+                )
+            })
+            .collect();
+        let mut config = MockBitcoinOpsConfig::default();
+        config.set_utxos(utxos);
+        config.fee_rate = 2;
+        config.transaction = known_tx;
+
+        let transaction_builder =
+            TransactionBuilder::new(Arc::new(MockBitcoinOps::new(config)), bridge_address())
+                .unwrap();
+        RefundSession::new(
+            ViaVerifierConfig::for_tests(),
+            pool.clone(),
+            Arc::new(transaction_builder),
+        )
+    }
+
+    async fn insert_refund(pool: &ConnectionPool<Verifier>, deposit_tx_id: H256, amount: i64) {
+        pool.connection()
+            .await
+            .unwrap()
+            .via_refunds_dal()
+            .insert_refund(
+                deposit_tx_id,
+                0,
+                10,
+                Some(SENDER.to_string()),
+                amount,
+                "invalid L2 receiver address",
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn refund(pool: &ConnectionPool<Verifier>, deposit_tx_id: H256) -> StorageRefund {
+        pool.connection()
+            .await
+            .unwrap()
+            .via_refunds_dal()
+            .get_refunds_by_deposit(deposit_tx_id)
+            .await
+            .unwrap()
+            .remove(0)
+    }
+
+    fn empty_tx() -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refund_session() {
+        let pool = ConnectionPool::<Verifier>::test_pool().await;
+        let deposit_tx_id = H256::repeat_byte(1);
+        insert_refund(&pool, deposit_tx_id, 10_000).await;
+        let session = create_session(&pool, Some(empty_tx()));
+
+        let session_op = session.session().await.unwrap().unwrap();
+        assert_eq!(session_op.get_refund_deposit(), Some((deposit_tx_id, 0)));
+        let unsigned_tx = session_op.get_unsigned_bridge_tx();
+        let sender = Address::from_str(SENDER).unwrap().assume_checked();
+        assert!(unsigned_tx.tx.output.iter().any(|txout| {
+            txout.script_pubkey == sender.script_pubkey() && txout.value < Amount::from_sat(10_000)
+        }));
+
+        // The unsigned transaction is stored, the verifiers rebuild the same one.
+        assert!(refund(&pool, deposit_tx_id).await.data.is_some());
+        assert!(session.is_session_in_progress(&session_op).await.unwrap());
+        assert!(session.verify_message(&session_op).await.unwrap());
+        assert!(!session
+            .is_bridge_session_already_processed(&session_op)
+            .await
+            .unwrap());
+
+        let txid = unsigned_tx.txid;
+        assert!(session
+            .after_broadcast_final_transaction(txid, &session_op)
+            .await
+            .unwrap());
+        let broadcast = refund(&pool, deposit_tx_id).await;
+        assert_eq!(broadcast.status(), RefundStatus::Broadcast);
+        assert_eq!(broadcast.refund_tx_id, Some(txid.to_byte_array().to_vec()));
+
+        // The refund transaction is known to the node, the refund isn't signed again.
+        assert!(session.session().await.unwrap().is_none());
+        assert!(!session.verify_message(&session_op).await.unwrap());
+        assert!(session
+            .is_bridge_session_already_processed(&session_op)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_refund_session_without_enough_value_for_the_fee() {
+        let pool = ConnectionPool::<Verifier>::test_pool().await;
+        let deposit_tx_id = H256::repeat_byte(2);
+        insert_refund(&pool, deposit_tx_id, 100).await;
+        let session = create_session(&pool, None);
+
+        assert!(session.session().await.unwrap().is_none());
+        assert_eq!(
+            refund(&pool, deposit_tx_id).await.status(),
+            RefundStatus::Unrefundable
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refund_session_retries_dropped_refund() {
+        let pool = ConnectionPool::<Verifier>::test_pool().await;
+        let deposit_tx_id = H256::repeat_byte(3);
+        insert_refund(&pool, deposit_tx_id, 10_000).await;
+        let session = create_session(&pool, Some(empty_tx()));
+
+        let session_op = session.session().await.unwrap().unwrap();
+        let dropped_tx = session_op.get_unsigned_bridge_tx();
+        session
+            .after_broadcast_final_transaction(dropped_tx.txid, &session_op)
+            .await
+            .unwrap();
+
+        // The node doesn't know the refund transaction anymore, a verifier which broadcast it
+        // signs the refund again.
+        let session = create_session(&pool, None);
+        assert!(session.verify_message(&session_op).await.unwrap());
+
+        let session_op = session.session().await.unwrap().unwrap();
+        let retried = refund(&pool, deposit_tx_id).await;
+        assert_eq!(retried.status(), RefundStatus::Pending);
+        assert_eq!(retried.refund_tx_id, None);
+
+        // The new transaction spends the UTXOs of the dropped one.
+        let unsigned_tx = session_op.get_unsigned_bridge_tx();
+        assert_eq!(unsigned_tx.utxos, dropped_tx.utxos);
+        assert_eq!(
+            UnsignedBridgeTx::from_bytes(&retried.data.unwrap()).unwrap(),
+            unsigned_tx
+        );
+        assert!(session.verify_message(&session_op).await.unwrap());
+    }
+}
@@ -1,4 +1,4 @@
-use std::{any::Any, sync::Arc};
+use std::{any::Any, collections::BTreeMap, sync::Arc};
 
 use anyhow::{Context, Ok};
 use axum::async_trait;
@@ -16,7 +16,12 @@ use via_withdrawal_client::client::WithdrawalClient;
 use zksync_config::ViaVerifierConfig;
 use zksync_types::H256;
 
-use crate::{traits::ISession, types::SessionOperation, utils::h256_to_txid};
+use crate::{
+    outflow::{session_outflows, verify_override, OutflowLimits},
+    traits::ISession,
+    types::SessionOperation,
+    utils::h256_to_txid,
+};
 
 const OP_RETURN_WITHDRAW_PREFIX: &[u8] = b"VIA_PROTOCOL:WITHDRAWAL:";
 
@@ -62,7 +67,9 @@ impl ISession for WithdrawalSession {
 
         let proof_txid = h256_to_txid(&raw_proof_tx_id).with_context(|| "Invalid proof tx id")?;
 
-        let votable_tx_id_opt = self.get_votable_tx_id(&raw_proof_tx_id).await?;
+        let votable_tx_id_opt = self
+            .get_votable_tx_id(&raw_proof_tx_id, l1_batch_number)
+            .await?;
         let Some(votable_tx_id) = votable_tx_id_opt else {
             return Ok(None);
         };
@@ -72,7 +79,13 @@ impl ISession for WithdrawalSession {
                 (index, unsigned_txs)
             } else {
                 let (index, unsigned_txs) = self
-                    .create_unsigned_txs(withdrawals_to_process, proof_txid, None, None)
+                    .create_unsigned_txs(
+                        withdrawals_to_process,
+                        proof_txid,
+                        l1_batch_number,
+                        None,
+                        None,
+                    )
                     .await
                     .map_err(|e| {
                         anyhow::format_err!("Invalid unsigned tx for batch {l1_batch_number}: {e}")
@@ -83,6 +96,29 @@ impl ISession for WithdrawalSession {
                 (index, unsigned_txs)
             };
 
+        if let Some(reason) = self
+            .check_outflow_limits(l1_batch_number, index, &unsigned_txs[index])
+            .await?
+        {
+            tracing::warn!("Withdrawal session for l1 batch {l1_batch_number} deferred: {reason}");
+
+            let amount = self.outflows(&unsigned_txs[index]).values().sum::<u64>();
+            self.master_connection_pool
+                .connection_tagged("withdrawal session")
+                .await?
+                .via_outflows_dal()
+                .defer_withdrawal(l1_batch_number, index as i64, amount as i64, &reason)
+                .await?;
+            return Ok(None);
+        }
+
+        self.master_connection_pool
+            .connection_tagged("withdrawal session")
+            .await?
+            .via_outflows_dal()
+            .release_withdrawal(l1_batch_number, index as i64)
+            .await?;
+
         let sighashes = self
             .transaction_builder
             .get_tr_sighashes(&unsigned_txs[index])?;
@@ -119,7 +155,7 @@ impl ISession for WithdrawalSession {
     async fn verify_message(&self, session_op: &SessionOperation) -> anyhow::Result<bool> {
         if let Some((unsigned_tx, messages)) = session_op.session() {
             // Get the l1 batches finalized but withdrawals not yet processed
-            if let Some((blob_id, proof_tx_id)) = self
+            if let Some((da_identifier, blob_id, proof_tx_id)) = self
                 .master_connection_pool
                 .connection_tagged("verifier withdrawal session verify message")
                 .await?
@@ -128,16 +164,37 @@ impl ISession for WithdrawalSession {
                 .await?
             {
                 if !self
-                    ._verify_withdrawals(&session_op, &blob_id, proof_tx_id)
+                    ._verify_withdrawals(&session_op, &da_identifier, &blob_id, proof_tx_id)
                     .await?
                 {
                     tracing::error!("Failed to verify withdrawals");
                     return Ok(false);
                 }
 
-                return self
+                if !self
                     ._verify_sighashes(session_op.get_l1_batch_number(), &unsigned_tx, messages)
-                    .await;
+                    .await?
+                {
+                    return Ok(false);
+                }
+
+                if let Some(reason) = self
+                    .check_outflow_limits(
+                        session_op.get_l1_batch_number(),
+                        session_op.index(),
+                        &unsigned_tx,
+                    )
+                    .await?
+                {
+                    tracing::error!(
+                        "Withdrawal session for l1 batch {} exceeds the outflow limits: {}",
+                        session_op.get_l1_batch_number(),
+                        reason
+                    );
+                    return Ok(false);
+                }
+
+                return Ok(true);
             }
         }
         Ok(false)
@@ -168,7 +225,10 @@ impl ISession for WithdrawalSession {
         session_op: &SessionOperation,
     ) -> anyhow::Result<bool> {
         let votable_tx_id = self
-            .get_votable_tx_id(&session_op.get_proof_tx_id())
+            .get_votable_tx_id(
+                &session_op.get_proof_tx_id(),
+                session_op.get_l1_batch_number(),
+            )
             .await?
             .ok_or_else(|| anyhow::anyhow!("Votable transaction does not exist"))?;
 
@@ -181,6 +241,13 @@ impl ISession for WithdrawalSession {
             .update_bridge_tx(votable_tx_id, session_op.index() as i64, &hash_bytes)
             .await?;
 
+        self.insert_outflows(
+            session_op.get_l1_batch_number(),
+            session_op.index(),
+            &session_op.get_unsigned_bridge_tx(),
+        )
+        .await?;
+
         self.transaction_builder
             .utxo_manager_insert_transaction(session_op.get_unsigned_bridge_tx().tx.clone())
             .await;
@@ -240,10 +307,10 @@ impl WithdrawalSession {
         );
 
         let mut l1_batch_number: i64 = 0;
-        for (batch_number, blob_id, proof_tx_id) in l1_batches.iter() {
+        for (batch_number, da_identifier, blob_id, proof_tx_id) in l1_batches.iter() {
             let withdrawals: Vec<WithdrawalRequest> = self
                 .withdrawal_client
-                .get_withdrawals(blob_id)
+                .get_withdrawals(da_identifier, blob_id)
                 .await
                 .with_context(|| "Error to get withdrawals from DA")?;
             raw_proof_tx_id = proof_tx_id.clone();
@@ -260,7 +327,7 @@ impl WithdrawalSession {
             } else {
                 // If there is no withdrawals to process in a batch, update the status and mark it as processed
                 let votable_tx_id = self
-                    .get_votable_tx_id(&raw_proof_tx_id)
+                    .get_votable_tx_id(&raw_proof_tx_id, *batch_number)
                     .await?
                     .ok_or_else(|| anyhow::anyhow!("Votable transaction does not exist"))?;
 
@@ -305,7 +372,7 @@ impl WithdrawalSession {
 
             return Ok(Some((
                 index,
-                UnsignedBridgeTx::to_vec(unsigned_bridge_txs_bytes),
+                UnsignedBridgeTx::to_vec(unsigned_bridge_txs_bytes)?,
             )));
         }
 
@@ -316,6 +383,7 @@ impl WithdrawalSession {
         &self,
         withdrawals: Vec<WithdrawalRequest>,
         proof_txid: Txid,
+        l1_batch_number: i64,
         default_fee_rate: Option<u64>,
         default_available_utxos: Option<Vec<(OutPoint, TxOut)>>,
     ) -> anyhow::Result<(usize, Vec<UnsignedBridgeTx>)> {
@@ -331,12 +399,16 @@ impl WithdrawalSession {
             })
             .collect();
 
+        // The L1 batch number tells apart the L1 batches proven by the same proof.
+        let proof_txid_bytes = proof_txid.as_raw_hash().to_byte_array();
+        let l1_batch_number_bytes = (l1_batch_number as u32).to_le_bytes();
+
         let unsigned_bridge_txs = self
             .transaction_builder
             .build_transaction_with_op_return(
                 outputs,
                 OP_RETURN_WITHDRAW_PREFIX,
-                vec![&proof_txid.as_raw_hash().to_byte_array().to_vec()],
+                vec![&proof_txid_bytes[..], &l1_batch_number_bytes[..]],
                 Arc::new(WithdrawalFeeStrategy::new()),
                 default_fee_rate,
                 default_available_utxos,
@@ -350,10 +422,14 @@ impl WithdrawalSession {
     async fn _verify_withdrawals(
         &self,
         session_operation: &SessionOperation,
+        da_identifier: &str,
         blob_id: &str,
         raw_proof_tx_id: Vec<u8>,
     ) -> anyhow::Result<bool> {
-        let withdrawals = self.withdrawal_client.get_withdrawals(blob_id).await?;
+        let withdrawals = self
+            .withdrawal_client
+            .get_withdrawals(da_identifier, blob_id)
+            .await?;
 
         // Verify the fee used to build the withdrawal transaction.
         let fee_rate = self
@@ -383,6 +459,7 @@ impl WithdrawalSession {
             .create_unsigned_txs(
                 withdrawals.clone(),
                 proof_txid,
+                session_operation.get_l1_batch_number(),
                 Some(used_fee_rate),
                 Some(selected_utxos),
             )
@@ -393,7 +470,10 @@ impl WithdrawalSession {
             return Ok(false);
         }
 
-        let Some(votable_tx_id) = self.get_votable_tx_id(&raw_proof_tx_id).await? else {
+        let Some(votable_tx_id) = self
+            .get_votable_tx_id(&raw_proof_tx_id, session_operation.get_l1_batch_number())
+            .await?
+        else {
             tracing::error!(
                 "Theres is no votable transaction with proof_tx_id {}",
                 raw_proof_tx_id.to_hex_string(Case::Lower)
@@ -459,6 +539,135 @@ impl WithdrawalSession {
         Ok(true)
     }
 
+    /// Checks a bridge transaction of the session against the outflow limits, returns the reason
+    /// it exceeds them. A session approved by the governance is never held back.
+    pub async fn check_outflow_limits(
+        &self,
+        l1_batch_number: i64,
+        index: usize,
+        unsigned_tx: &UnsignedBridgeTx,
+    ) -> anyhow::Result<Option<String>> {
+        let limits = OutflowLimits::from_config(&self.verifier_config);
+        if !limits.is_enabled() {
+            return Ok(None);
+        }
+
+        let bridge_tx_index = index as i64;
+        let mut storage = self
+            .master_connection_pool
+            .connection_tagged("verifier outflow limits")
+            .await?;
+
+        let override_signature = storage
+            .via_outflows_dal()
+            .get_deferral(l1_batch_number, bridge_tx_index)
+            .await?
+            .and_then(|deferral| deferral.override_signature);
+        if let Some(signature) = override_signature {
+            if verify_override(
+                &self.verifier_config,
+                l1_batch_number,
+                bridge_tx_index,
+                &signature,
+            )? {
+                return Ok(None);
+            }
+        }
+
+        let outflows = self.outflows(unsigned_tx);
+        // The window ends at the L1 block the L1 batch was inscribed in, the same for every
+        // verifier.
+        let window_end = storage
+            .via_votes_dal()
+            .get_finalized_l1_block_number(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch {l1_batch_number} is not finalized"))?;
+        let window_start =
+            (window_end + 1).saturating_sub(self.verifier_config.outflow_window_l1_blocks());
+        let window = window_start..=window_end;
+
+        let window_outflow = storage
+            .via_outflows_dal()
+            .get_window_outflow(window.clone(), l1_batch_number, bridge_tx_index)
+            .await?;
+        let script_pubkeys: Vec<Vec<u8>> = outflows.keys().cloned().collect();
+        let address_outflows: BTreeMap<Vec<u8>, u64> = storage
+            .via_outflows_dal()
+            .get_window_outflows_by_address(
+                window,
+                l1_batch_number,
+                bridge_tx_index,
+                &script_pubkeys,
+            )
+            .await?
+            .into_iter()
+            .map(|(script_pubkey, amount)| (script_pubkey, amount as u64))
+            .collect();
+
+        Ok(limits.check(&outflows, window_outflow as u64, &address_outflows))
+    }
+
+    /// Stores the governance override of a withdrawal session shared by the coordinator, once
+    /// verified with the governance key of this verifier.
+    pub async fn store_outflow_override(
+        &self,
+        l1_batch_number: i64,
+        index: usize,
+        signature: &str,
+    ) -> anyhow::Result<()> {
+        if !verify_override(
+            &self.verifier_config,
+            l1_batch_number,
+            index as i64,
+            signature,
+        )? {
+            tracing::warn!(
+                "Invalid outflow override for l1 batch {l1_batch_number}, index {index}"
+            );
+            return Ok(());
+        }
+
+        self.master_connection_pool
+            .connection_tagged("verifier outflow limits")
+            .await?
+            .via_outflows_dal()
+            .set_override_signature(l1_batch_number, index as i64, signature)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the amount withdrawn to each address by a bridge transaction of the session.
+    fn outflows(&self, unsigned_tx: &UnsignedBridgeTx) -> BTreeMap<Vec<u8>, u64> {
+        session_outflows(
+            &unsigned_tx.tx,
+            &self.transaction_builder.bridge_address.script_pubkey(),
+        )
+    }
+
+    /// Records the outflows of a bridge transaction broadcast by this verifier, they count toward
+    /// the limits of the next sessions. The other verifiers record them once the bridge
+    /// transaction is indexed.
+    async fn insert_outflows(
+        &self,
+        l1_batch_number: i64,
+        index: usize,
+        unsigned_tx: &UnsignedBridgeTx,
+    ) -> anyhow::Result<()> {
+        let outflows: Vec<(Vec<u8>, i64)> = self
+            .outflows(unsigned_tx)
+            .into_iter()
+            .map(|(script_pubkey, amount)| (script_pubkey, amount as i64))
+            .collect();
+
+        self.master_connection_pool
+            .connection_tagged("verifier outflow limits")
+            .await?
+            .via_outflows_dal()
+            .insert_outflows(l1_batch_number, index as i64, &outflows)
+            .await?;
+        Ok(())
+    }
+
     async fn _verify_sighashes(
         &self,
         l1_batch_number: i64,
@@ -484,7 +693,7 @@ impl WithdrawalSession {
     ) -> anyhow::Result<()> {
         let mut data = vec![];
         for unsigned_bridge_tx in unsigned_bridge_txs.iter() {
-            data.push(unsigned_bridge_tx.to_bytes());
+            data.push(unsigned_bridge_tx.to_bytes()?);
         }
 
         self.master_connection_pool
@@ -497,13 +706,17 @@ impl WithdrawalSession {
         Ok(())
     }
 
-    async fn get_votable_tx_id(&self, proof_txid: &[u8]) -> anyhow::Result<Option<i64>> {
+    async fn get_votable_tx_id(
+        &self,
+        proof_txid: &[u8],
+        l1_batch_number: i64,
+    ) -> anyhow::Result<Option<i64>> {
         let votable_tx_id = self
             .master_connection_pool
             .connection_tagged("verifier")
             .await?
             .via_votes_dal()
-            .get_votable_transaction_id(proof_txid)
+            .get_votable_transaction_id(proof_txid, l1_batch_number)
             .await?;
         Ok(votable_tx_id)
     }
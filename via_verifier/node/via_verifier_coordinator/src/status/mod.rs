@@ -0,0 +1,380 @@
+//! Read-only status API of a verifier node, so the operators can monitor and debug the node
+//! without access to its database. The same status is reported in the health check details.
+
+use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use axum::{
+    extract::{Query, Request, State},
+    http::header,
+    middleware::{self, Next},
+    response::Response,
+    Json,
+};
+use bitcoin::Address;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::sync::watch;
+use tower::ServiceBuilder;
+use tower_http::timeout::TimeoutLayer;
+use via_btc_client::traits::BitcoinOps;
+use via_verifier_dal::{ConnectionPool, Verifier, VerifierDal};
+use zksync_config::configs::via_verifier::ViaVerifierConfig;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater};
+
+use self::types::{
+    BatchStatusResponse, BatchesQuery, CanonicalChainResponse, PendingBridgeTx,
+    VerifierStatusResponse, VoteInscriptionResponse, WalletBalance,
+};
+use crate::{coordinator::error::ApiError, utils::h256_to_txid};
+
+pub mod types;
+
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+/// Interval between the updates of the health check details.
+const HEALTH_UPDATE_INTERVAL: Duration = Duration::from_secs(30);
+const DEFAULT_BATCHES_LIMIT: i64 = 50;
+const MAX_BATCHES_LIMIT: i64 = 500;
+
+pub struct StatusApi {
+    master_connection_pool: ConnectionPool<Verifier>,
+    btc_client: Arc<dyn BitcoinOps>,
+    /// The wallets of the node, by role.
+    wallets: Vec<(String, Address)>,
+    /// SHA-256 of the bearer token.
+    token_hash: Vec<u8>,
+}
+
+/// Health check details of the verifier node.
+#[derive(Debug, Serialize)]
+struct VerifierStatusDetails<'a> {
+    last_verified_l1_batch: u32,
+    last_finalized_l1_batch: Option<u32>,
+    canonical_chain: &'a CanonicalChainResponse,
+    pending_bridge_txs: usize,
+    inflight_inscriptions: usize,
+    wallets: &'a [WalletBalance],
+}
+
+impl VerifierStatusResponse {
+    fn health(&self) -> Health {
+        // A node which didn't index any batch yet has no canonical chain.
+        let status =
+            if self.canonical_chain.is_valid || self.canonical_chain.total_canonical_batches == 0 {
+                HealthStatus::Ready
+            } else {
+                HealthStatus::Affected
+            };
+        Health::from(status).with_details(VerifierStatusDetails {
+            last_verified_l1_batch: self.last_verified_l1_batch,
+            last_finalized_l1_batch: self.last_finalized_l1_batch,
+            canonical_chain: &self.canonical_chain,
+            pending_bridge_txs: self.pending_bridge_txs.len(),
+            inflight_inscriptions: self.inflight_inscriptions,
+            wallets: &self.wallets,
+        })
+    }
+}
+
+impl StatusApi {
+    pub fn new(
+        master_connection_pool: ConnectionPool<Verifier>,
+        btc_client: Arc<dyn BitcoinOps>,
+        wallets: Vec<(String, Address)>,
+        token: &str,
+    ) -> Self {
+        Self {
+            master_connection_pool,
+            btc_client,
+            wallets,
+            token_hash: Sha256::digest(token.as_bytes()).to_vec(),
+        }
+    }
+
+    pub fn into_router(self: Arc<Self>) -> axum::Router<()> {
+        let auth_mw = middleware::from_fn_with_state(self.clone(), Self::auth_middleware);
+
+        let router = axum::Router::new()
+            .route("/", axum::routing::get(Self::get_status))
+            .route("/batches", axum::routing::get(Self::get_batches))
+            .route_layer(auth_mw)
+            .with_state(self)
+            .layer(
+                ServiceBuilder::new()
+                    .layer(TimeoutLayer::new(API_TIMEOUT))
+                    .into_inner(),
+            );
+
+        axum::Router::new().nest("/status", router)
+    }
+
+    async fn auth_middleware(
+        State(self_): State<Arc<Self>>,
+        request: Request,
+        next: Next,
+    ) -> Result<Response, ApiError> {
+        let token = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .ok_or_else(|| ApiError::Unauthorized("Missing bearer token".into()))?;
+
+        // The digests are compared, so the comparison time doesn't depend on the token.
+        if Sha256::digest(token.as_bytes()).as_slice() != self_.token_hash.as_slice() {
+            return Err(ApiError::Unauthorized("Invalid bearer token".into()));
+        }
+
+        Ok(next.run(request).await)
+    }
+
+    async fn get_status(
+        State(self_): State<Arc<Self>>,
+    ) -> anyhow::Result<Json<VerifierStatusResponse>, ApiError> {
+        Ok(Json(self_.status().await?))
+    }
+
+    async fn get_batches(
+        State(self_): State<Arc<Self>>,
+        Query(query): Query<BatchesQuery>,
+    ) -> anyhow::Result<Json<Vec<BatchStatusResponse>>, ApiError> {
+        let limit = query.limit.unwrap_or(DEFAULT_BATCHES_LIMIT);
+        if !(1..=MAX_BATCHES_LIMIT).contains(&limit) {
+            return Err(ApiError::BadRequest(format!(
+                "The limit must be between 1 and {MAX_BATCHES_LIMIT}"
+            )));
+        }
+
+        let mut storage = self_
+            .master_connection_pool
+            .connection_tagged("verifier status api")
+            .await?;
+        let transactions = storage
+            .via_votes_dal()
+            .list_votable_transactions(query.to.unwrap_or(i64::MAX), limit)
+            .await?;
+        let ids: Vec<i64> = transactions.iter().map(|tx| tx.id).collect();
+        let vote_inscriptions: HashMap<i64, VoteInscriptionResponse> = storage
+            .via_btc_sender_dal()
+            .get_vote_inscriptions(&ids)
+            .await
+            .context("Failed to load the vote inscriptions")?
+            .into_iter()
+            .map(|(id, reveal_tx_id, is_confirmed)| {
+                (
+                    id,
+                    VoteInscriptionResponse {
+                        reveal_tx_id,
+                        is_confirmed,
+                    },
+                )
+            })
+            .collect();
+        drop(storage);
+
+        let mut batches = Vec::with_capacity(transactions.len());
+        for tx in transactions {
+            let vote = match tx.l1_batch_status {
+                Some(true) => "Accepted",
+                Some(false) => "Rejected",
+                None => "Pending",
+            };
+            batches.push(BatchStatusResponse {
+                l1_batch_number: tx.l1_batch_number,
+                l1_batch_hash: hex::encode(&tx.l1_batch_hash),
+                proof_reveal_tx_id: h256_to_txid(&tx.proof_reveal_tx_id)?.to_string(),
+                vote: vote.to_string(),
+                is_finalized: tx.is_finalized,
+                vote_inscription: vote_inscriptions.get(&tx.id).cloned(),
+            });
+        }
+
+        Ok(Json(batches))
+    }
+
+    pub async fn status(&self) -> anyhow::Result<VerifierStatusResponse> {
+        let mut storage = self
+            .master_connection_pool
+            .connection_tagged("verifier status api")
+            .await?;
+
+        let last_verified_l1_batch = storage.via_votes_dal().get_last_voted_l1_batch().await?;
+        let last_finalized_l1_batch = storage
+            .via_votes_dal()
+            .get_last_finalized_l1_batch()
+            .await?;
+        let canonical_chain = storage.via_votes_dal().verify_canonical_chain().await?;
+        let pending_bridge_txs = storage
+            .via_bridge_dal()
+            .list_bridge_txs_not_yet_processed()
+            .await?
+            .into_iter()
+            .map(|(votable_tx_id, bridge_tx_id)| PendingBridgeTx {
+                votable_tx_id,
+                bridge_tx_id,
+            })
+            .collect();
+        let inflight_inscriptions = storage
+            .via_btc_sender_dal()
+            .get_inflight_inscriptions()
+            .await
+            .context("Failed to load the inflight inscriptions")?
+            .len();
+        drop(storage);
+
+        let mut wallets = Vec::with_capacity(self.wallets.len());
+        for (role, address) in &self.wallets {
+            let balance = match self.btc_client.get_balance(address).await {
+                Ok(balance) => Some(balance),
+                Err(err) => {
+                    tracing::warn!("Failed to fetch the balance of the {role} wallet: {err}");
+                    None
+                }
+            };
+            wallets.push(WalletBalance {
+                role: role.clone(),
+                address: address.to_string(),
+                balance,
+            });
+        }
+
+        Ok(VerifierStatusResponse {
+            last_verified_l1_batch,
+            last_finalized_l1_batch,
+            canonical_chain: canonical_chain.into(),
+            pending_bridge_txs,
+            inflight_inscriptions,
+            wallets,
+        })
+    }
+
+    /// Reports the status of the node in the health check until the stop signal.
+    async fn update_health(
+        self: Arc<Self>,
+        health_updater: HealthUpdater,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        loop {
+            match self.status().await {
+                Ok(status) => {
+                    health_updater.update(status.health());
+                }
+                Err(err) => {
+                    tracing::warn!("Failed to collect the verifier status: {err:#}");
+                    health_updater.update(HealthStatus::Affected.into());
+                }
+            }
+
+            if tokio::time::timeout(HEALTH_UPDATE_INTERVAL, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reports the status of the node in the health check, and serves the status API when it's
+/// configured.
+pub async fn run_status_api(
+    config: ViaVerifierConfig,
+    master_connection_pool: ConnectionPool<Verifier>,
+    btc_client: Arc<dyn BitcoinOps>,
+    wallets: Vec<(String, String)>,
+    health_updater: HealthUpdater,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let wallets = wallets
+        .into_iter()
+        .map(|(role, address)| {
+            let address = Address::from_str(&address)
+                .with_context(|| format!("Invalid {role} wallet address"))?
+                .assume_checked();
+            Ok((role, address))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let api = Arc::new(StatusApi::new(
+        master_connection_pool,
+        btc_client,
+        wallets,
+        config.status_api_token.as_deref().unwrap_or_default(),
+    ));
+    let health_task = api
+        .clone()
+        .update_health(health_updater, stop_receiver.clone());
+
+    let Some(bind_address) = config.status_api_addr() else {
+        return health_task.await;
+    };
+
+    let listener = tokio::net::TcpListener::bind(bind_address)
+        .await
+        .context("Cannot bind to the status api address")?;
+    let mut server_stop_receiver = stop_receiver;
+    let server = async move {
+        axum::serve(listener, api.into_router())
+            .with_graceful_shutdown(async move {
+                if server_stop_receiver.changed().await.is_err() {
+                    tracing::warn!("Stop signal sender for status api server was dropped without sending a signal");
+                }
+                tracing::info!("Stop signal received, status api server is shutting down");
+            })
+            .await
+            .context("status api server failed")
+    };
+
+    tokio::try_join!(health_task, server)?;
+    tracing::info!("status api server shut down");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(canonical_chain: CanonicalChainResponse) -> VerifierStatusResponse {
+        VerifierStatusResponse {
+            last_verified_l1_batch: 2,
+            last_finalized_l1_batch: Some(1),
+            canonical_chain,
+            pending_bridge_txs: vec![PendingBridgeTx {
+                votable_tx_id: 1,
+                bridge_tx_id: 1,
+            }],
+            inflight_inscriptions: 0,
+            wallets: vec![],
+        }
+    }
+
+    fn canonical_chain(is_valid: bool, total_canonical_batches: i64) -> CanonicalChainResponse {
+        CanonicalChainResponse {
+            is_valid,
+            has_genesis: total_canonical_batches > 0,
+            total_canonical_batches,
+            min_batch_number: None,
+            max_batch_number: None,
+            missing_batches: vec![],
+        }
+    }
+
+    #[test]
+    fn test_status_health() {
+        let health = status(canonical_chain(true, 2)).health();
+        assert_eq!(health.status(), HealthStatus::Ready);
+        let details = health.details().unwrap();
+        assert_eq!(details["last_verified_l1_batch"], 2);
+        assert_eq!(details["pending_bridge_txs"], 1);
+
+        assert_eq!(
+            status(canonical_chain(false, 0)).health().status(),
+            HealthStatus::Ready
+        );
+        assert_eq!(
+            status(canonical_chain(false, 2)).health().status(),
+            HealthStatus::Affected
+        );
+    }
+}
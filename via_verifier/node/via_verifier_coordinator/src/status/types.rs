@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use via_verifier_dal::models::storage_vote::CanonicalChainStatus;
+
+/// The view of the verifier node on the L1 batches, the bridge and its wallets.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VerifierStatusResponse {
+    /// Last L1 batch verified by the node.
+    pub last_verified_l1_batch: u32,
+    pub last_finalized_l1_batch: Option<u32>,
+    pub canonical_chain: CanonicalChainResponse,
+    /// Bridge transactions of the finalized batches not processed yet.
+    pub pending_bridge_txs: Vec<PendingBridgeTx>,
+    /// Inscriptions sent by the node and not confirmed yet.
+    pub inflight_inscriptions: usize,
+    pub wallets: Vec<WalletBalance>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CanonicalChainResponse {
+    pub is_valid: bool,
+    pub has_genesis: bool,
+    pub total_canonical_batches: i64,
+    pub min_batch_number: Option<u32>,
+    pub max_batch_number: Option<u32>,
+    pub missing_batches: Vec<u32>,
+}
+
+impl From<CanonicalChainStatus> for CanonicalChainResponse {
+    fn from(status: CanonicalChainStatus) -> Self {
+        Self {
+            is_valid: status.is_valid,
+            has_genesis: status.has_genesis,
+            total_canonical_batches: status.total_canonical_batches,
+            min_batch_number: status.min_batch_number,
+            max_batch_number: status.max_batch_number,
+            missing_batches: status.missing_batches,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PendingBridgeTx {
+    pub votable_tx_id: i64,
+    pub bridge_tx_id: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WalletBalance {
+    pub role: String,
+    pub address: String,
+    /// Balance in sats, `None` when the bitcoin node can't be reached.
+    pub balance: Option<u128>,
+}
+
+/// An L1 batch along with the vote of the verifier node.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BatchStatusResponse {
+    pub l1_batch_number: i64,
+    pub l1_batch_hash: String,
+    pub proof_reveal_tx_id: String,
+    /// `Accepted` or `Rejected` once the node verified the batch proof, `Pending` before.
+    pub vote: String,
+    pub is_finalized: Option<bool>,
+    pub vote_inscription: Option<VoteInscriptionResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VoteInscriptionResponse {
+    pub reveal_tx_id: String,
+    pub is_confirmed: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct BatchesQuery {
+    /// Last L1 batch to list, the latest when unset.
+    pub to: Option<i64>,
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,108 @@
+//! Mutual TLS between the verifiers and the coordinator.
+
+use std::{fs, io::BufReader, sync::Arc};
+
+use anyhow::Context;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto,
+    service::TowerToHyperService,
+};
+use rustls::{server::WebPkiClientVerifier, RootCertStore, ServerConfig};
+use rustls_pemfile::{certs, private_key};
+use tokio::{net::TcpListener, sync::watch};
+use tokio_rustls::TlsAcceptor;
+use zksync_config::configs::via_verifier::ViaVerifierConfig;
+
+fn read_file(path: &Option<String>) -> anyhow::Result<Vec<u8>> {
+    let path = path.as_deref().context("TLS file path not set")?;
+    fs::read(path).with_context(|| format!("Failed to read {path}"))
+}
+
+/// Builds the acceptor of the coordinator, which only accepts the clients presenting a
+/// certificate issued by the verifier network CA.
+pub(crate) fn acceptor(config: &ViaVerifierConfig) -> anyhow::Result<TlsAcceptor> {
+    // Multiple crypto backends are enabled in the workspace, `rustls` can't choose one.
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    let cert_pem = read_file(&config.tls_cert_path)?;
+    let key_pem = read_file(&config.tls_key_path)?;
+    let ca_cert_pem = read_file(&config.tls_ca_cert_path)?;
+
+    let cert_chain = certs(&mut BufReader::new(cert_pem.as_slice()))
+        .collect::<Result<Vec<_>, _>>()
+        .context("Invalid TLS certificate")?;
+    let key = private_key(&mut BufReader::new(key_pem.as_slice()))
+        .context("Invalid TLS private key")?
+        .context("No TLS private key found")?;
+
+    let mut roots = RootCertStore::empty();
+    for ca_cert in certs(&mut BufReader::new(ca_cert_pem.as_slice())) {
+        roots.add(ca_cert.context("Invalid TLS CA certificate")?)?;
+    }
+    let client_verifier = WebPkiClientVerifier::builder(Arc::new(roots)).build()?;
+
+    let server_config = ServerConfig::builder()
+        .with_client_cert_verifier(client_verifier)
+        .with_single_cert(cert_chain, key)?;
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+/// Builds the verifier http client, which presents the node certificate and only trusts the
+/// verifier network CA. Uses `rustls` like the coordinator, not the default `native-tls` backend.
+pub(crate) fn client(config: &ViaVerifierConfig) -> anyhow::Result<reqwest::Client> {
+    // The `rustls` identity is read from a single PEM holding the private key and the certificates.
+    let mut identity_pem = read_file(&config.tls_key_path)?;
+    identity_pem.push(b'\n');
+    identity_pem.extend(read_file(&config.tls_cert_path)?);
+    let identity = reqwest::Identity::from_pem(&identity_pem)?;
+    let ca_cert = reqwest::Certificate::from_pem(&read_file(&config.tls_ca_cert_path)?)?;
+
+    Ok(reqwest::Client::builder()
+        .use_rustls_tls()
+        .identity(identity)
+        .add_root_certificate(ca_cert)
+        .tls_built_in_root_certs(false)
+        .build()?)
+}
+
+/// Serves the router over TLS until the stop signal. Failed handshakes only drop their connection.
+pub(crate) async fn serve(
+    listener: TcpListener,
+    router: axum::Router,
+    acceptor: TlsAcceptor,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    loop {
+        let (stream, remote_addr) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    tracing::warn!("Failed to accept a coordinator connection: {err}");
+                    continue;
+                }
+            },
+            _ = stop_receiver.changed() => break,
+        };
+
+        let acceptor = acceptor.clone();
+        let service = TowerToHyperService::new(router.clone());
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    tracing::debug!("TLS handshake with {remote_addr} failed: {err}");
+                    return;
+                }
+            };
+
+            if let Err(err) = auto::Builder::new(TokioExecutor::new())
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("Coordinator connection with {remote_addr} failed: {err}");
+            }
+        });
+    }
+    Ok(())
+}
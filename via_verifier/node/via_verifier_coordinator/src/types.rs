@@ -1,15 +1,21 @@
 use std::{clone::Clone, collections::BTreeMap, fmt, sync::Arc};
 
-use bincode::{deserialize, serialize};
+use bitcoin::secp256k1::schnorr;
 use musig2::{PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
-use via_btc_client::traits::Serializable;
+use tokio::sync::{broadcast, RwLock};
+use via_btc_client::{
+    traits::Serializable,
+    wire::{self, WireFormatError, WireFormatResult},
+};
+use via_musig2::threshold::BridgeTaproot;
 use via_verifier_types::transaction::UnsignedBridgeTx;
+use zksync_types::H256;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SessionType {
     Withdrawal,
+    Refund,
 }
 
 impl fmt::Display for SessionType {
@@ -19,6 +25,7 @@ impl fmt::Display for SessionType {
             "{}",
             match self {
                 SessionType::Withdrawal => "Withdrawal",
+                SessionType::Refund => "Refund",
             }
         )
     }
@@ -28,24 +35,29 @@ impl fmt::Display for SessionType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SessionOperation {
     Withdrawal(i64, Vec<UnsignedBridgeTx>, Vec<Vec<u8>>, Vec<u8>, usize),
+    /// Refund of the deposit output `(deposit_tx_id, deposit_vout)`.
+    Refund(H256, i64, Vec<UnsignedBridgeTx>, Vec<Vec<u8>>, usize),
 }
 
 impl SessionOperation {
     pub fn get_l1_batch_number(&self) -> i64 {
         match self {
             Self::Withdrawal(l1_batch_number, _, _, _, _) => *l1_batch_number,
+            Self::Refund(..) => 0,
         }
     }
 
     pub fn get_session_type(&self) -> SessionType {
         match self {
             Self::Withdrawal(_, _, _, _, _) => SessionType::Withdrawal,
+            Self::Refund(..) => SessionType::Refund,
         }
     }
 
     pub fn get_message_to_sign(&self) -> Vec<Vec<u8>> {
         match self {
             Self::Withdrawal(_, _, message, _, _) => message.clone(),
+            Self::Refund(_, _, _, message, _) => message.clone(),
         }
     }
 
@@ -54,12 +66,32 @@ impl SessionOperation {
             Self::Withdrawal(_, unsigned_txs, _, _, index) => {
                 return unsigned_txs[index.clone()].clone();
             }
+            Self::Refund(_, _, unsigned_txs, _, index) => unsigned_txs[*index].clone(),
         }
     }
 
+    /// Replaces the session transaction, e.g. by its script path variant.
+    pub fn with_unsigned_bridge_tx(mut self, unsigned_tx: UnsignedBridgeTx) -> Self {
+        match &mut self {
+            Self::Withdrawal(_, unsigned_txs, _, _, index) => unsigned_txs[*index] = unsigned_tx,
+            Self::Refund(_, _, unsigned_txs, _, index) => unsigned_txs[*index] = unsigned_tx,
+        }
+        self
+    }
+
     pub fn get_proof_tx_id(&self) -> Vec<u8> {
         match self {
             Self::Withdrawal(_, _, _, proof_tx_id, _) => proof_tx_id.clone(),
+            Self::Refund(..) => vec![],
+        }
+    }
+
+    pub fn get_refund_deposit(&self) -> Option<(H256, i64)> {
+        match self {
+            Self::Withdrawal(..) => None,
+            Self::Refund(deposit_tx_id, deposit_vout, _, _, _) => {
+                Some((*deposit_tx_id, *deposit_vout))
+            }
         }
     }
 
@@ -68,32 +100,46 @@ impl SessionOperation {
             Self::Withdrawal(_, unsigned_txs, message, _, index) => {
                 Some((unsigned_txs[index.clone()].clone(), message))
             }
+            Self::Refund(_, _, unsigned_txs, message, index) => {
+                Some((unsigned_txs[*index].clone(), message))
+            }
         }
     }
 
     pub fn unsigned_txs(&self) -> &Vec<UnsignedBridgeTx> {
         match self {
             Self::Withdrawal(_, unsigned_txs, _, _, _) => unsigned_txs,
+            Self::Refund(_, _, unsigned_txs, _, _) => unsigned_txs,
         }
     }
 
     pub fn index(&self) -> usize {
         match self {
             Self::Withdrawal(_, _, _, _, index) => *index,
+            Self::Refund(_, _, _, _, index) => *index,
         }
     }
 }
 
 impl Serializable for SessionOperation {
-    fn to_bytes(&self) -> Vec<u8> {
-        serialize(self).expect("error serialize the SessionOperation")
+    fn to_bytes(&self) -> WireFormatResult<Vec<u8>> {
+        wire::encode(self)
     }
 
-    fn from_bytes(bytes: &[u8]) -> Self
+    fn from_bytes(bytes: &[u8]) -> WireFormatResult<Self>
     where
         Self: Sized,
     {
-        deserialize(bytes).expect("error deserialize the SessionOperation")
+        let session_op: Self = wire::decode(bytes)?;
+        // The accessors index the unsigned transactions, reject sessions pointing outside of them.
+        if session_op.index() >= session_op.unsigned_txs().len() {
+            return Err(WireFormatError::InvalidMessage(format!(
+                "session index {} out of {} unsigned transactions",
+                session_op.index(),
+                session_op.unsigned_txs().len()
+            )));
+        }
+        Ok(session_op)
     }
 }
 
@@ -104,16 +150,68 @@ pub struct ViaWithdrawalState {
     pub verifiers_pub_keys: Vec<bitcoin::secp256k1::PublicKey>,
     pub verifier_request_timeout: u8,
     pub session_timeout: u64,
+    /// Set when the bridge can be spent through the k-of-n script path.
+    pub bridge_taproot: Option<BridgeTaproot>,
+    /// Progress of the signing session, streamed to the verifiers subscribed to `/session/events`.
+    pub session_events: broadcast::Sender<SessionEvent>,
 }
 
 #[derive(Default, Debug, Clone)]
 pub struct SigningSession {
+    /// Id of the session in `via_signing_sessions`.
+    pub id: i64,
     pub session_op: Option<SessionOperation>,
     pub received_nonces: BTreeMap<usize, BTreeMap<usize, PubNonce>>,
     pub received_sigs: BTreeMap<usize, BTreeMap<usize, PartialSignature>>,
+    /// Script path signatures of the k-of-n leaf, per input and verifier index.
+    pub received_threshold_sigs: BTreeMap<usize, BTreeMap<usize, schnorr::Signature>>,
     pub created_at: u64,
 }
 
+impl SigningSession {
+    /// Whether every input of the session has a nonce from `required_signers` verifiers.
+    pub fn nonces_completed(&self, required_signers: usize) -> bool {
+        self.inputs_completed(&self.received_nonces, required_signers)
+    }
+
+    /// Whether every input of the session has a partial signature from `required_signers` verifiers.
+    pub fn signatures_completed(&self, required_signers: usize) -> bool {
+        self.inputs_completed(&self.received_sigs, required_signers)
+    }
+
+    fn inputs_completed<T>(
+        &self,
+        received: &BTreeMap<usize, BTreeMap<usize, T>>,
+        required_signers: usize,
+    ) -> bool {
+        let input_count = self
+            .session_op
+            .as_ref()
+            .map_or(0, |session_op| session_op.get_message_to_sign().len());
+
+        input_count > 0
+            && (0..input_count).all(|input_index| {
+                received
+                    .get(&input_index)
+                    .map_or(0, |per_signer| per_signer.len())
+                    >= required_signers
+            })
+    }
+}
+
+/// Signing session progress pushed by the coordinator, the verifiers fetch the session data from
+/// the REST endpoints when notified.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// A new session is open for nonces.
+    NewSession { session_id: i64 },
+    /// The nonces of the required signers were received for every input.
+    NoncesCompleted { session_id: i64 },
+    /// The partial signatures of the required signers were received for every input.
+    SignaturesCompleted { session_id: i64 },
+}
+
 /// Data posted by other signers to submit their nonce
 #[derive(Serialize, Deserialize, Debug)]
 pub struct NoncePair {
@@ -130,11 +228,55 @@ pub struct PartialSignaturePair {
     pub signature: String,
 }
 
+/// Data posted by other signers to submit their k-of-n script path signature
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ThresholdSignaturePair {
+    pub signer_index: usize,
+    /// Base64 encoded schnorr signature
+    pub signature: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SigningSessionResponse {
     pub session_op: Vec<u8>,
     pub required_signers: usize,
     pub received_nonces: BTreeMap<usize, usize>,
     pub received_partial_signatures: BTreeMap<usize, usize>,
+    #[serde(default)]
+    pub received_threshold_signatures: BTreeMap<usize, usize>,
     pub created_at: u64,
+    /// Governance signature approving the withdrawal session above the outflow limits.
+    #[serde(default)]
+    pub outflow_override: Option<String>,
+}
+
+/// Governance approval of a withdrawal session above the outflow limits.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutflowOverrideRequest {
+    pub l1_batch_number: i64,
+    pub bridge_tx_index: i64,
+    /// Base64 encoded ECDSA signature of the governance key.
+    pub signature: String,
+}
+
+/// Status of a withdrawal session held back by the outflow limits.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WithdrawalDeferralResponse {
+    pub l1_batch_number: i64,
+    pub bridge_tx_index: i64,
+    pub amount: i64,
+    pub reason: Option<String>,
+    pub status: String,
+}
+
+/// Status of the refund of a deposit that can't be credited on L2.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RefundStatusResponse {
+    pub deposit_tx_id: String,
+    pub deposit_vout: i64,
+    pub sender: Option<String>,
+    pub amount: i64,
+    pub reason: String,
+    pub status: String,
+    pub refund_tx_id: Option<String>,
 }
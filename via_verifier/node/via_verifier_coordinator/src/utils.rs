@@ -1,9 +1,15 @@
 use anyhow::Context;
 use base64::Engine;
-use bitcoin::{hashes::Hash, Txid};
+use bitcoin::{
+    hashes::{sha256, Hash, HashEngine},
+    secp256k1::schnorr,
+    Amount, ScriptBuf, TapNodeHash, Txid,
+};
 use musig2::{BinaryEncoding, PartialSignature, PubNonce};
+use via_musig2::{threshold::BridgeTaproot, utxo_manager::ConsolidationPolicy};
+use zksync_config::configs::{via_bridge::ViaBridgeConfig, via_verifier::ViaVerifierConfig};
 
-use crate::types::{NoncePair, PartialSignaturePair};
+use crate::types::{NoncePair, PartialSignaturePair, ThresholdSignaturePair};
 
 pub fn decode_signature(signature: String) -> anyhow::Result<PartialSignature> {
     let decoded_sig = base64::engine::general_purpose::STANDARD
@@ -24,6 +30,23 @@ pub fn encode_signature(
     Ok(sig_pair)
 }
 
+pub fn encode_threshold_signature(
+    signer_index: usize,
+    signature: &schnorr::Signature,
+) -> ThresholdSignaturePair {
+    ThresholdSignaturePair {
+        signer_index,
+        signature: base64::engine::general_purpose::STANDARD.encode(signature.serialize()),
+    }
+}
+
+pub fn decode_threshold_signature(signature: &str) -> anyhow::Result<schnorr::Signature> {
+    let decoded_sig = base64::engine::general_purpose::STANDARD
+        .decode(signature)
+        .with_context(|| "Error to decode threshold signature")?;
+    Ok(schnorr::Signature::from_slice(&decoded_sig)?)
+}
+
 pub fn encode_nonce(signer_index: usize, nonce: PubNonce) -> anyhow::Result<NoncePair> {
     let nonce = base64::engine::general_purpose::STANDARD.encode(nonce.to_bytes());
     Ok(NoncePair {
@@ -49,3 +72,63 @@ pub(crate) fn h256_to_txid(h256_bytes: &[u8]) -> anyhow::Result<Txid> {
     reversed_bytes.reverse();
     Txid::from_slice(&reversed_bytes).with_context(|| "Failed to convert H256 to Txid")
 }
+
+/// Commits to the messages of a signing session, the nonces submitted for a session are bound to it.
+pub fn messages_hash(messages: &[Vec<u8>]) -> Vec<u8> {
+    let mut engine = sha256::Hash::engine();
+    for message in messages {
+        engine.input(&(message.len() as u64).to_le_bytes());
+        engine.input(message);
+    }
+    sha256::Hash::from_engine(engine).to_byte_array().to_vec()
+}
+
+pub fn consolidation_policy(config: &ViaVerifierConfig) -> ConsolidationPolicy {
+    ConsolidationPolicy {
+        max_fee_rate: config.consolidation_max_fee_rate(),
+        max_utxo_amount: Amount::from_sat(config.consolidation_max_utxo_amount()),
+    }
+}
+
+/// Max amount sent by a bridge transaction. The withdrawals above the session outflow limit are
+/// split into several bridge transactions, each one processed as a session of its own.
+pub fn max_tx_outflow(config: &ViaVerifierConfig) -> Option<Amount> {
+    config.max_session_outflow.map(Amount::from_sat)
+}
+
+/// Returns the bridge taproot when the k-of-n script path is enabled, after checking the configured
+/// bridge address and merkle root commit to it.
+pub fn bridge_taproot(
+    via_bridge_config: &ViaBridgeConfig,
+    bridge_address_merkle_root: Option<TapNodeHash>,
+) -> anyhow::Result<Option<BridgeTaproot>> {
+    let Some(threshold) = via_bridge_config.threshold else {
+        return Ok(None);
+    };
+
+    let governance_script = via_bridge_config
+        .governance_script
+        .as_deref()
+        .filter(|script| !script.is_empty())
+        .map(|script| hex::decode(script).map(ScriptBuf::from_bytes))
+        .transpose()
+        .with_context(|| "Invalid governance script")?;
+
+    let taproot = BridgeTaproot::new(
+        &via_bridge_config.verifiers_pub_keys,
+        threshold,
+        governance_script,
+    )?;
+
+    if taproot.merkle_root() != bridge_address_merkle_root {
+        anyhow::bail!(
+            "The bridge address merkle root does not commit to the {}-of-{} script path",
+            threshold,
+            via_bridge_config.verifiers_pub_keys.len()
+        );
+    }
+    if taproot.script_pubkey() != via_bridge_config.bridge_address()?.script_pubkey() {
+        anyhow::bail!("The bridge address does not match the verifiers taproot");
+    }
+    Ok(Some(taproot))
+}
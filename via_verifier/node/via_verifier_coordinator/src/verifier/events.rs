@@ -0,0 +1,109 @@
+//! Subscription to the server-sent events of the coordinator signing session.
+
+use reqwest::{
+    header::{self, HeaderMap},
+    Client, Response, StatusCode,
+};
+
+use crate::types::SessionEvent;
+
+pub(crate) struct SessionEventStream {
+    url: String,
+    response: Response,
+    buffer: Vec<u8>,
+}
+
+impl SessionEventStream {
+    pub async fn connect(client: &Client, url: String, headers: HeaderMap) -> anyhow::Result<Self> {
+        let response = client
+            .get(&url)
+            .headers(headers)
+            .header(header::ACCEPT, "text/event-stream")
+            .send()
+            .await?;
+
+        if response.status() != StatusCode::OK {
+            anyhow::bail!(
+                "Error to subscribe to the session events, status: {}, url: {}",
+                response.status(),
+                url
+            );
+        }
+
+        Ok(Self {
+            url,
+            response,
+            buffer: Vec::new(),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Waits for the next session event, returns `None` when the coordinator closed the stream.
+    pub async fn next_event(&mut self) -> anyhow::Result<Option<SessionEvent>> {
+        loop {
+            if let Some(event) = take_event(&mut self.buffer) {
+                return Ok(Some(event));
+            }
+
+            match self.response.chunk().await? {
+                Some(chunk) => self.buffer.extend_from_slice(&chunk),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Pops the first complete event from the buffer, skipping the keep-alive comments and the events
+/// this verifier doesn't know.
+fn take_event(buffer: &mut Vec<u8>) -> Option<SessionEvent> {
+    while let Some(end) = buffer.windows(2).position(|window| window == b"\n\n") {
+        let block: Vec<u8> = buffer.drain(..end + 2).collect();
+
+        let data = String::from_utf8_lossy(&block)
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(str::trim_start)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if data.is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str(&data) {
+            Ok(event) => return Some(event),
+            Err(err) => tracing::debug!("Skip unknown session event {data}: {err}"),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_event() {
+        let mut buffer = b":\n\ndata: {\"event\":\"new_session\",\"session_id\":1}\n\ndata: {\"event\":\"nonces_"
+            .to_vec();
+
+        assert_eq!(
+            take_event(&mut buffer),
+            Some(SessionEvent::NewSession { session_id: 1 })
+        );
+        // The second event is not complete yet.
+        assert_eq!(take_event(&mut buffer), None);
+
+        buffer.extend_from_slice(
+            b"completed\",\"session_id\":1}\n\ndata: {\"event\":\"unknown\"}\n\n",
+        );
+        assert_eq!(
+            take_event(&mut buffer),
+            Some(SessionEvent::NoncesCompleted { session_id: 1 })
+        );
+        assert_eq!(take_event(&mut buffer), None);
+        assert!(buffer.is_empty());
+    }
+}
@@ -1,3 +1,7 @@
+mod events;
+#[cfg(test)]
+mod tests;
+
 use std::{
     collections::{BTreeMap, HashMap},
     sync::Arc,
@@ -5,36 +9,50 @@ use std::{
 };
 
 use anyhow::{anyhow, Context, Result};
-use bitcoin::{TapSighashType, Witness};
+use bitcoin::{secp256k1::schnorr, TapSighashType, Witness};
 use musig2::{CompactSignature, PartialSignature};
-use reqwest::{header, Client, StatusCode};
+use reqwest::{header, Client, Method, StatusCode};
 use tokio::sync::watch;
-use via_btc_client::traits::{BitcoinOps, Serializable};
+use via_btc_client::{
+    signer::KeyManager,
+    traits::{BitcoinOps, Serializable},
+};
 use via_musig2::{
-    get_signer_with_merkle_root, transaction_builder::TransactionBuilder, verify_signature, Signer,
+    get_signer_from_secret_key,
+    threshold::{sign_script_path, BridgeTaproot},
+    transaction_builder::TransactionBuilder,
+    verify_signature, Signer,
 };
 use via_verifier_dal::{ConnectionPool, Verifier, VerifierDal};
 use via_verifier_types::{protocol_version::get_sequencer_version, transaction::UnsignedBridgeTx};
 use via_withdrawal_client::client::WithdrawalClient;
-use zksync_config::configs::{
-    via_bridge::ViaBridgeConfig, via_verifier::ViaVerifierConfig, via_wallets::ViaWallet,
-};
+use zksync_config::configs::{via_bridge::ViaBridgeConfig, via_verifier::ViaVerifierConfig};
 use zksync_types::{via_roles::ViaNodeRole, via_wallet::SystemWallets, H256};
 use zksync_utils::time::seconds_since_epoch;
 
+use self::events::SessionEventStream;
 use crate::{
+    leader::{LeaderElection, LeaderState},
     metrics::METRICS,
-    sessions::{session_manager::SessionManager, withdrawal::WithdrawalSession},
+    sessions::{
+        refund::RefundSession, session_manager::SessionManager, withdrawal::WithdrawalSession,
+    },
     traits::ISession,
     types::{
-        NoncePair, PartialSignaturePair, SessionOperation, SessionType, SigningSessionResponse,
+        NoncePair, PartialSignaturePair, SessionEvent, SessionOperation, SessionType,
+        SigningSessionResponse, ThresholdSignaturePair,
+    },
+    utils::{
+        bridge_taproot, consolidation_policy, decode_nonce, decode_signature,
+        decode_threshold_signature, encode_nonce, encode_signature, encode_threshold_signature,
+        max_tx_outflow,
     },
-    utils::{decode_nonce, decode_signature, encode_nonce, encode_signature},
 };
 
 pub struct ViaWithdrawalVerifier {
     verifier_config: ViaVerifierConfig,
-    wallet: ViaWallet,
+    /// Holds the verifier key, used for the MuSig2 sessions and to authenticate the requests.
+    key_manager: KeyManager,
     session_manager: SessionManager,
     btc_client: Arc<dyn BitcoinOps>,
     master_connection_pool: ConnectionPool<Verifier>,
@@ -42,21 +60,27 @@ pub struct ViaWithdrawalVerifier {
     signer_per_utxo_input: BTreeMap<usize, Signer>,
     final_sig_per_utxo_input: BTreeMap<usize, CompactSignature>,
     via_bridge_config: ViaBridgeConfig,
+    /// Set when the coordinator role rotates among the verifiers.
+    leader_election: Option<LeaderElection>,
+    transaction_builder: Arc<TransactionBuilder>,
+    /// Set when the bridge can be spent through the k-of-n script path.
+    bridge_taproot: Option<BridgeTaproot>,
 }
 
 impl ViaWithdrawalVerifier {
     pub fn new(
         verifier_config: ViaVerifierConfig,
-        wallet: ViaWallet,
+        key_manager: KeyManager,
         master_connection_pool: ConnectionPool<Verifier>,
         btc_client: Arc<dyn BitcoinOps>,
         withdrawal_client: WithdrawalClient,
         via_bridge_config: ViaBridgeConfig,
     ) -> anyhow::Result<Self> {
-        let transaction_builder = Arc::new(TransactionBuilder::new(
-            btc_client.clone(),
-            via_bridge_config.bridge_address()?,
-        )?);
+        let transaction_builder = Arc::new(
+            TransactionBuilder::new(btc_client.clone(), via_bridge_config.bridge_address()?)?
+                .with_consolidation_policy(consolidation_policy(&verifier_config))
+                .with_max_tx_outflow(max_tx_outflow(&verifier_config)),
+        );
 
         let withdrawal_session = WithdrawalSession::new(
             verifier_config.clone(),
@@ -65,33 +89,90 @@ impl ViaWithdrawalVerifier {
             withdrawal_client,
         );
 
+        let refund_session = RefundSession::new(
+            verifier_config.clone(),
+            master_connection_pool.clone(),
+            transaction_builder.clone(),
+        );
+
         // Add sessions type the verifier network can process
-        let sessions: HashMap<SessionType, Arc<dyn ISession>> = [(
-            SessionType::Withdrawal,
-            Arc::new(withdrawal_session) as Arc<dyn ISession>,
-        )]
+        let sessions: HashMap<SessionType, Arc<dyn ISession>> = [
+            (
+                SessionType::Withdrawal,
+                Arc::new(withdrawal_session) as Arc<dyn ISession>,
+            ),
+            (
+                SessionType::Refund,
+                Arc::new(refund_session) as Arc<dyn ISession>,
+            ),
+        ]
         .into_iter()
         .collect();
 
+        let leader_election = match verifier_config.coordinator_urls.clone() {
+            Some(coordinator_urls) if verifier_config.leader_rotation_enabled() => {
+                let own_index = get_signer_from_secret_key(
+                    key_manager.secret_key(),
+                    via_bridge_config.verifiers_pub_keys.clone(),
+                    verifier_config.bridge_address_merkle_root(),
+                )?
+                .signer_index();
+                if coordinator_urls.len() != via_bridge_config.verifiers_pub_keys.len() {
+                    anyhow::bail!(
+                        "Expected a coordinator url per verifier, got {} urls for {} verifiers",
+                        coordinator_urls.len(),
+                        via_bridge_config.verifiers_pub_keys.len()
+                    );
+                }
+                Some(LeaderElection::new(
+                    coordinator_urls,
+                    own_index,
+                    verifier_config.leader_rotation_interval(),
+                    verifier_config.leader_liveness_l1_blocks(),
+                )?)
+            }
+            _ => None,
+        };
+
+        let bridge_taproot = bridge_taproot(
+            &via_bridge_config,
+            verifier_config.bridge_address_merkle_root(),
+        )?;
+
+        let client = if verifier_config.mtls_enabled() {
+            crate::tls::client(&verifier_config)?
+        } else {
+            Client::new()
+        };
+
         Ok(Self {
             verifier_config,
-            wallet,
+            key_manager,
             session_manager: SessionManager::new(sessions),
             btc_client,
             master_connection_pool,
-            client: Client::new(),
+            client,
             signer_per_utxo_input: BTreeMap::new(),
             final_sig_per_utxo_input: BTreeMap::new(),
             via_bridge_config,
+            leader_election,
+            transaction_builder,
+            bridge_taproot,
         })
     }
 
     pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let mut timer = tokio::time::interval(self.verifier_config.polling_interval());
+        let mut session_events = None;
 
         while !*stop_receiver.borrow_and_update() {
+            self.subscribe_session_events(&mut session_events).await;
+
             tokio::select! {
                 _ = timer.tick() => { /* continue iterations */ }
+                Some(event) = next_session_event(&mut session_events) => {
+                    tracing::debug!("Received session event {:?}", event);
+                }
                 _ = stop_receiver.changed() => break,
             }
 
@@ -113,6 +194,8 @@ impl ViaWithdrawalVerifier {
             return Ok(());
         }
 
+        self.update_leader().await?;
+
         let mut session_info = self.get_session().await?;
 
         if self.is_coordinator() {
@@ -124,7 +207,8 @@ impl ViaWithdrawalVerifier {
                 return Ok(());
             }
 
-            let session_op = SessionOperation::from_bytes(&session_info.session_op);
+            let session_op = SessionOperation::from_bytes(&session_info.session_op)
+                .context("Malformed session operation from the coordinator")?;
 
             if !self
                 .session_manager
@@ -140,7 +224,8 @@ impl ViaWithdrawalVerifier {
             tracing::debug!("Empty session, nothing to process");
             return Ok(());
         }
-        let session_op = SessionOperation::from_bytes(&session_info.session_op);
+        let session_op = SessionOperation::from_bytes(&session_info.session_op)
+            .context("Malformed session operation from the coordinator")?;
 
         if self
             .build_and_broadcast_final_transaction(&session_info, &session_op)
@@ -166,6 +251,16 @@ impl ViaWithdrawalVerifier {
                 .downcast_ref::<WithdrawalSession>()
                 .ok_or_else(|| anyhow!("Failed to cast to WithdrawalSession"))?;
 
+            if let Some(signature) = &session_info.outflow_override {
+                withdrawal_session
+                    .store_outflow_override(
+                        session_op.get_l1_batch_number(),
+                        session_op.index(),
+                        signature,
+                    )
+                    .await?;
+            }
+
             let (expected_l1_batch_number, _, _) =
                 withdrawal_session.prepare_withdrawal_session().await?;
 
@@ -186,7 +281,8 @@ impl ViaWithdrawalVerifier {
             .await?
         {
             tracing::info!(
-                "Session already processed l1_batch_number {}",
+                "{} session already processed l1_batch_number {}",
+                session_op.get_session_type(),
                 session_op.get_l1_batch_number()
             );
             return Ok(());
@@ -217,6 +313,8 @@ impl ViaWithdrawalVerifier {
             .get(&input_index)
             .map_or(false, |map| map.contains_key(&signer.signer_index()));
 
+        let signer_index = signer.signer_index();
+
         if already_signed && already_sent_nonce {
             return Ok(());
         }
@@ -239,13 +337,18 @@ impl ViaWithdrawalVerifier {
             }
 
             // If the session is valid but there is no withdrawal to process, insert and empty hash.
-            if session_op.get_unsigned_bridge_tx().is_empty() {
+            if session_op.get_session_type() == SessionType::Withdrawal
+                && session_op.get_unsigned_bridge_tx().is_empty()
+            {
                 let votable_tx_id = self
                     .master_connection_pool
                     .connection_tagged("verifier task")
                     .await?
                     .via_votes_dal()
-                    .get_votable_transaction_id(&session_op.get_proof_tx_id())
+                    .get_votable_transaction_id(
+                        &session_op.get_proof_tx_id(),
+                        session_op.get_l1_batch_number(),
+                    )
                     .await?
                     .ok_or_else(|| anyhow::anyhow!("Votable transaction does not exist"))?;
 
@@ -262,6 +365,17 @@ impl ViaWithdrawalVerifier {
             if !already_sent_nonce {
                 self.submit_nonce(messages).await?;
             }
+
+            if self.bridge_taproot.is_some()
+                && !self
+                    .get_threshold_signatures()
+                    .await?
+                    .get(&input_index)
+                    .map_or(false, |map| map.contains_key(&signer_index))
+            {
+                self.submit_threshold_signature(&session_op, signer_index)
+                    .await?;
+            }
         } else if received_nonces >= session_info.required_signers {
             if signer.has_created_partial_sig() {
                 return Ok(());
@@ -277,27 +391,37 @@ impl ViaWithdrawalVerifier {
         Ok(())
     }
 
-    fn create_request_headers(&self) -> anyhow::Result<header::HeaderMap> {
+    /// Signs a request to the coordinator, the signature commits to the method, path and body of
+    /// the request and to a fresh nonce.
+    fn create_request_headers(
+        &self,
+        method: Method,
+        path: &str,
+        body: &[u8],
+    ) -> anyhow::Result<header::HeaderMap> {
         let mut headers = header::HeaderMap::new();
         let timestamp = chrono::Utc::now().timestamp().to_string();
-        let signer = get_signer_with_merkle_root(
-            &self.wallet.private_key,
+        let signer = get_signer_from_secret_key(
+            self.key_manager.secret_key(),
             self.via_bridge_config.verifiers_pub_keys.clone(),
             self.verifier_config.bridge_address_merkle_root(),
         )?;
         let verifier_index = signer.signer_index().to_string();
         let sequencer_version = get_sequencer_version().to_string();
+        let nonce = uuid::Uuid::new_v4().to_string();
 
-        let private_key = bitcoin::PrivateKey::from_wif(&self.wallet.private_key)?;
-        let secret_key = private_key.inner;
+        let secret_key = self.key_manager.secret_key();
 
-        // Sign timestamp + verifier_index + sequencer_version as a JSON object
-        let payload = serde_json::json!({
-            "timestamp": timestamp,
-            "verifier_index": verifier_index,
-            "sequencer_version": sequencer_version
-        });
-        let signature = crate::auth::sign_request(&payload, &secret_key)?;
+        let payload = crate::auth::request_payload(
+            &timestamp,
+            &verifier_index,
+            &sequencer_version,
+            method.as_str(),
+            path,
+            body,
+            &nonce,
+        );
+        let signature = crate::auth::sign_request(&payload, secret_key)?;
 
         headers.insert("X-Timestamp", header::HeaderValue::from_str(&timestamp)?);
         headers.insert(
@@ -309,13 +433,14 @@ impl ViaWithdrawalVerifier {
             "X-Sequencer-Version",
             header::HeaderValue::from_str(&sequencer_version)?,
         );
+        headers.insert("X-Nonce", header::HeaderValue::from_str(&nonce)?);
 
         Ok(headers)
     }
 
     async fn get_session(&self) -> anyhow::Result<SigningSessionResponse> {
-        let url = format!("{}/session", self.verifier_config.coordinator_http_url);
-        let headers = self.create_request_headers()?;
+        let url = format!("{}/session", self.coordinator_url());
+        let headers = self.create_request_headers(Method::GET, "/session", &[])?;
         let resp = self
             .client
             .get(&url)
@@ -336,11 +461,8 @@ impl ViaWithdrawalVerifier {
     }
 
     async fn get_session_nonces(&self) -> anyhow::Result<BTreeMap<usize, BTreeMap<usize, String>>> {
-        let nonces_url = format!(
-            "{}/session/nonce",
-            self.verifier_config.coordinator_http_url
-        );
-        let headers = self.create_request_headers()?;
+        let nonces_url = format!("{}/session/nonce", self.coordinator_url());
+        let headers = self.create_request_headers(Method::GET, "/session/nonce", &[])?;
         let resp = self
             .client
             .get(&nonces_url)
@@ -362,11 +484,30 @@ impl ViaWithdrawalVerifier {
     }
 
     pub async fn submit_nonce(&mut self, messages: Vec<Vec<u8>>) -> anyhow::Result<()> {
+        // A signer started for other messages holds a nonce which must never sign these ones,
+        // e.g. when the coordinator restarted with another session.
+        let has_stale_signer = self
+            .signer_per_utxo_input
+            .iter()
+            .any(|(input_index, signer)| {
+                !signer.has_not_started()
+                    && messages
+                        .get(*input_index)
+                        .map_or(true, |message| signer.message() != message.as_slice())
+            });
+        if has_stale_signer {
+            tracing::warn!("Session messages changed, discard the started signers");
+            self.init_signers(messages.len())?;
+        }
+
         let mut nonce_map: BTreeMap<usize, NoncePair> = BTreeMap::new();
 
         for (input_index, signer) in self.signer_per_utxo_input.iter_mut() {
             if signer.has_not_started() {
-                signer.start_signing_session(messages[*input_index].clone())?;
+                let message = messages
+                    .get(*input_index)
+                    .ok_or_else(|| anyhow::anyhow!("No message for input {}", input_index))?;
+                signer.start_signing_session(message.clone())?;
             }
 
             let nonce = signer
@@ -379,17 +520,16 @@ impl ViaWithdrawalVerifier {
             nonce_map.insert(*input_index, nonce_pair);
         }
 
-        let url = format!(
-            "{}/session/nonce",
-            self.verifier_config.coordinator_http_url
-        );
-        let headers = self.create_request_headers()?;
+        let url = format!("{}/session/nonce", self.coordinator_url());
+        let body = serde_json::to_vec(&nonce_map)?;
+        let headers = self.create_request_headers(Method::POST, "/session/nonce", &body)?;
 
         let res = self
             .client
             .post(&url)
             .headers(headers.clone())
-            .json(&nonce_map)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body)
             .send()
             .await?;
 
@@ -414,11 +554,8 @@ impl ViaWithdrawalVerifier {
     pub async fn get_session_signatures(
         &self,
     ) -> anyhow::Result<BTreeMap<usize, BTreeMap<usize, PartialSignature>>> {
-        let url = format!(
-            "{}/session/signature",
-            self.verifier_config.coordinator_http_url
-        );
-        let headers = self.create_request_headers()?;
+        let url = format!("{}/session/signature", self.coordinator_url());
+        let headers = self.create_request_headers(Method::GET, "/session/signature", &[])?;
         let resp = self
             .client
             .get(&url)
@@ -516,11 +653,9 @@ impl ViaWithdrawalVerifier {
             sig_pair_per_input.insert(input_index, encoded);
         }
 
-        let url = format!(
-            "{}/session/signature",
-            self.verifier_config.coordinator_http_url
-        );
-        let headers = self.create_request_headers()?;
+        let url = format!("{}/session/signature", self.coordinator_url());
+        let body = serde_json::to_vec(&sig_pair_per_input)?;
+        let headers = self.create_request_headers(Method::POST, "/session/signature", &body)?;
 
         tracing::debug!("Submitting all partial signatures to {}", url);
 
@@ -528,7 +663,8 @@ impl ViaWithdrawalVerifier {
             .client
             .post(&url)
             .headers(headers.clone())
-            .json(&sig_pair_per_input)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body)
             .send()
             .await?;
 
@@ -556,14 +692,123 @@ impl ViaWithdrawalVerifier {
         }
     }
 
+    pub async fn get_threshold_signatures(
+        &self,
+    ) -> anyhow::Result<BTreeMap<usize, BTreeMap<usize, schnorr::Signature>>> {
+        let url = format!("{}/session/threshold-signature", self.coordinator_url());
+        let headers =
+            self.create_request_headers(Method::GET, "/session/threshold-signature", &[])?;
+        let resp = self
+            .client
+            .get(&url)
+            .headers(headers.clone())
+            .send()
+            .await?;
+
+        if resp.status() != StatusCode::OK {
+            anyhow::bail!(
+                "Error fetching threshold signatures. Status: {}, URL: {}, Headers: {:?}, Body: {}",
+                resp.status(),
+                url,
+                headers,
+                resp.text().await?
+            );
+        }
+
+        let raw_sigs: BTreeMap<usize, Vec<ThresholdSignaturePair>> = resp.json().await?;
+        let mut decoded_sigs = BTreeMap::new();
+
+        for (input_index, sigs_per_signer) in raw_sigs {
+            let mut inner_map = BTreeMap::new();
+
+            for encoded_sig in sigs_per_signer {
+                let sig =
+                    decode_threshold_signature(&encoded_sig.signature).with_context(|| {
+                        format!(
+                            "Failed to decode threshold signature for input {} signer {}",
+                            input_index, encoded_sig.signer_index
+                        )
+                    })?;
+                inner_map.insert(encoded_sig.signer_index, sig);
+            }
+
+            decoded_sigs.insert(input_index, inner_map);
+        }
+
+        Ok(decoded_sigs)
+    }
+
+    /// Signs the inputs of the session transaction for the k-of-n script path of the bridge.
+    pub async fn submit_threshold_signature(
+        &self,
+        session_op: &SessionOperation,
+        signer_index: usize,
+    ) -> anyhow::Result<()> {
+        let Some(bridge_taproot) = self.bridge_taproot.as_ref() else {
+            return Ok(());
+        };
+
+        // The MuSig2 session goes on without the fallback when the change can't pay its fee.
+        let script_path_tx = match self
+            .transaction_builder
+            .build_script_path_tx(&session_op.get_unsigned_bridge_tx(), bridge_taproot)
+        {
+            Ok(script_path_tx) => script_path_tx,
+            Err(err) => {
+                tracing::warn!("Skip the threshold signatures: {err:?}");
+                return Ok(());
+            }
+        };
+        let sighashes = self
+            .transaction_builder
+            .get_tr_script_sighashes(&script_path_tx, bridge_taproot.threshold_leaf_hash())?;
+        let secret_key = self.key_manager.secret_key();
+
+        let mut sig_pair_per_input = BTreeMap::new();
+        for (input_index, sighash) in sighashes.iter().enumerate() {
+            let signature = sign_script_path(secret_key, sighash)?;
+            sig_pair_per_input.insert(
+                input_index,
+                encode_threshold_signature(signer_index, &signature),
+            );
+        }
+
+        let url = format!("{}/session/threshold-signature", self.coordinator_url());
+        let body = serde_json::to_vec(&sig_pair_per_input)?;
+        let headers =
+            self.create_request_headers(Method::POST, "/session/threshold-signature", &body)?;
+
+        let response = self
+            .client
+            .post(&url)
+            .headers(headers.clone())
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to submit threshold signatures. Status: {}, URL: {}, Headers: {:?}, Body: {}",
+                response.status(),
+                url,
+                headers,
+                response.text().await.unwrap_or_default()
+            );
+        }
+
+        tracing::debug!("Threshold signatures submitted successfully");
+        Ok(())
+    }
+
     fn init_signers(&mut self, count: usize) -> anyhow::Result<()> {
         self.clear_signers();
 
         for i in 0..count {
             self.signer_per_utxo_input.insert(
                 i,
-                get_signer_with_merkle_root(
-                    &self.wallet.private_key,
+                get_signer_from_secret_key(
+                    self.key_manager.secret_key(),
                     self.via_bridge_config.verifiers_pub_keys.clone(),
                     self.verifier_config.bridge_address_merkle_root(),
                 )?,
@@ -578,8 +823,8 @@ impl ViaWithdrawalVerifier {
     }
 
     async fn create_new_session(&mut self) -> anyhow::Result<()> {
-        let url = format!("{}/session/new", self.verifier_config.coordinator_http_url);
-        let headers = self.create_request_headers()?;
+        let url = format!("{}/session/new", self.coordinator_url());
+        let headers = self.create_request_headers(Method::POST, "/session/new", &[])?;
         let resp = self
             .client
             .post(&url)
@@ -705,7 +950,9 @@ impl ViaWithdrawalVerifier {
             .map_or(0, |len| *len);
 
         if received_partial_signatures < session_info.required_signers {
-            return Ok(false);
+            return self
+                .build_and_broadcast_threshold_transaction(session_info, session_op)
+                .await;
         }
 
         if let Some((unsigned_tx, messages)) = session_op.session() {
@@ -714,51 +961,216 @@ impl ViaWithdrawalVerifier {
                 .map_err(|e| anyhow::format_err!("Error create final signature: {e}"))?;
 
             if !self.final_sig_per_utxo_input.is_empty() {
-                if !self
-                    .session_manager
-                    .before_broadcast_final_transaction(session_op)
-                    .await?
-                {
-                    return Ok(false);
-                }
-
                 let signed_tx = self.sign_transaction(unsigned_tx.clone());
+                return self
+                    .broadcast_final_transaction(session_info, session_op, signed_tx)
+                    .await;
+            }
+        }
+        Ok(false)
+    }
 
-                tracing::debug!("Signed transaction {:?}", &signed_tx);
+    /// Spends the bridge through the k-of-n script path when the MuSig2 session is not complete
+    /// after the fallback timeout. The verifiers signed the script path transaction, its change
+    /// pays for the witness being larger than the key path signature.
+    async fn build_and_broadcast_threshold_transaction(
+        &mut self,
+        session_info: &SigningSessionResponse,
+        session_op: &SessionOperation,
+    ) -> anyhow::Result<bool> {
+        let Some(bridge_taproot) = self.bridge_taproot.clone() else {
+            return Ok(false);
+        };
 
-                let txid = self
-                    .btc_client
-                    .broadcast_signed_transaction(&signed_tx)
-                    .await?;
+        if seconds_since_epoch()
+            < session_info.created_at + self.verifier_config.threshold_fallback_timeout()
+        {
+            return Ok(false);
+        }
 
-                tracing::info!(
-                    "Broadcast {} signed transaction with txid {}",
-                    &session_op.get_session_type(),
-                    &txid.to_string()
-                );
+        let mut unsigned_tx = self
+            .transaction_builder
+            .build_script_path_tx(&session_op.get_unsigned_bridge_tx(), &bridge_taproot)?;
+        let input_count = unsigned_tx.tx.input.len();
+        let has_threshold_signatures = (0..input_count).all(|input_index| {
+            session_info
+                .received_threshold_signatures
+                .get(&input_index)
+                .map_or(0, |len| *len)
+                >= bridge_taproot.threshold()
+        });
+        if input_count == 0 || !has_threshold_signatures {
+            return Ok(false);
+        }
 
-                if !self
-                    .session_manager
-                    .after_broadcast_final_transaction(txid, session_op)
-                    .await?
-                {
-                    return Ok(false);
-                }
+        let sighashes = self
+            .transaction_builder
+            .get_tr_script_sighashes(&unsigned_tx, bridge_taproot.threshold_leaf_hash())?;
+        let signatures = self.get_threshold_signatures().await?;
+
+        for (input_index, sighash) in sighashes.iter().enumerate() {
+            // Only the signatures checked against the verifiers keys make it into the witness.
+            let valid_signatures: BTreeMap<usize, schnorr::Signature> = signatures
+                .get(&input_index)
+                .into_iter()
+                .flatten()
+                .filter(|(signer_index, signature)| {
+                    bridge_taproot
+                        .verify_signature(**signer_index, sighash, signature)
+                        .is_ok()
+                })
+                .map(|(signer_index, signature)| (*signer_index, *signature))
+                .collect();
 
-                METRICS.session_time.observe(Duration::from_secs(
-                    seconds_since_epoch() - session_info.created_at,
-                ));
+            unsigned_tx.tx.input[input_index].witness =
+                bridge_taproot.witness(&valid_signatures).with_context(|| {
+                    format!("Error build threshold witness (input {input_index})")
+                })?;
+        }
 
-                self.clear_signers();
+        tracing::info!(
+            "Spend the {} session transaction through the {}-of-{} script path",
+            session_op.get_session_type(),
+            bridge_taproot.threshold(),
+            self.via_bridge_config.verifiers_pub_keys.len()
+        );
 
-                return Ok(true);
-            }
+        let signed_tx = bitcoin::consensus::encode::serialize_hex(&unsigned_tx.tx);
+        // The broadcasted transaction is the one the bridge UTXOs are tracked with.
+        let session_op = session_op.clone().with_unsigned_bridge_tx(unsigned_tx);
+        self.broadcast_final_transaction(session_info, &session_op, signed_tx)
+            .await
+    }
+
+    async fn broadcast_final_transaction(
+        &mut self,
+        session_info: &SigningSessionResponse,
+        session_op: &SessionOperation,
+        signed_tx: String,
+    ) -> anyhow::Result<bool> {
+        if !self
+            .session_manager
+            .before_broadcast_final_transaction(session_op)
+            .await?
+        {
+            return Ok(false);
         }
-        Ok(false)
+
+        tracing::debug!("Signed transaction {:?}", &signed_tx);
+
+        let txid = self
+            .btc_client
+            .broadcast_signed_transaction(&signed_tx)
+            .await?;
+
+        tracing::info!(
+            "Broadcast {} signed transaction with txid {}",
+            &session_op.get_session_type(),
+            &txid.to_string()
+        );
+
+        if !self
+            .session_manager
+            .after_broadcast_final_transaction(txid, session_op)
+            .await?
+        {
+            return Ok(false);
+        }
+
+        METRICS.session_time.observe(Duration::from_secs(
+            seconds_since_epoch() - session_info.created_at,
+        ));
+
+        self.clear_signers();
+
+        Ok(true)
     }
 
     fn is_coordinator(&self) -> bool {
-        self.verifier_config.role == ViaNodeRole::Coordinator
+        match &self.leader_election {
+            Some(leader_election) => leader_election.is_leader(),
+            None => self.verifier_config.role == ViaNodeRole::Coordinator,
+        }
+    }
+
+    fn coordinator_url(&self) -> &str {
+        match &self.leader_election {
+            Some(leader_election) => leader_election.leader_url(),
+            None => &self.verifier_config.coordinator_http_url,
+        }
+    }
+
+    /// Follows the leader derived from the chain state indexed by the verifier.
+    async fn update_leader(&mut self) -> anyhow::Result<()> {
+        let Some(leader_election) = self.leader_election.as_mut() else {
+            return Ok(());
+        };
+
+        let mut storage = self
+            .master_connection_pool
+            .connection_tagged("verifier task")
+            .await?;
+        let l1_batch_number = storage
+            .via_votes_dal()
+            .get_last_finalized_l1_batch()
+            .await?
+            .unwrap_or_default();
+        let term_l1_block_number = storage
+            .via_votes_dal()
+            .get_first_finalized_l1_block_since(leader_election.term_start(l1_batch_number))
+            .await?
+            .unwrap_or_default();
+        let l1_block_number = storage
+            .via_indexer_dal()
+            .get_last_processed_l1_block("via_btc_watch")
+            .await? as u32;
+        drop(storage);
+
+        let state = LeaderState {
+            l1_batch_number,
+            term_l1_block_number,
+            l1_block_number,
+        };
+        if leader_election.update(state) {
+            tracing::info!(
+                "Coordinator moved to verifier {} at {} ({:?})",
+                leader_election.leader_index(),
+                leader_election.leader_url(),
+                state
+            );
+            METRICS.leader_index.set(leader_election.leader_index());
+            // The session of the previous leader is lost, start over with the new one.
+            self.clear_signers();
+        }
+        Ok(())
+    }
+
+    /// Subscribes to the session events of the current coordinator, the verifier falls back to
+    /// polling while it can't subscribe.
+    async fn subscribe_session_events(&self, session_events: &mut Option<SessionEventStream>) {
+        if !self.verifier_config.session_events_enabled() {
+            return;
+        }
+
+        let url = format!("{}/session/events", self.coordinator_url());
+        if session_events
+            .as_ref()
+            .map_or(false, |stream| stream.url() == url)
+        {
+            return;
+        }
+
+        let subscription = match self.create_request_headers(Method::GET, "/session/events", &[]) {
+            Ok(headers) => SessionEventStream::connect(&self.client, url, headers).await,
+            Err(err) => Err(err),
+        };
+        *session_events = match subscription {
+            Ok(stream) => Some(stream),
+            Err(err) => {
+                tracing::debug!("Failed to subscribe to the session events: {err}");
+                None
+            }
+        };
     }
 
     async fn sync_in_progress(&self) -> anyhow::Result<bool> {
@@ -801,3 +1213,26 @@ impl ViaWithdrawalVerifier {
         wallets.is_valid_bridge_address(self.via_bridge_config.bridge_address()?)
     }
 }
+
+/// Waits for the next session event, never resolves without a subscription.
+async fn next_session_event(
+    session_events: &mut Option<SessionEventStream>,
+) -> Option<SessionEvent> {
+    let Some(stream) = session_events.as_mut() else {
+        return std::future::pending().await;
+    };
+
+    match stream.next_event().await {
+        Ok(Some(event)) => Some(event),
+        Ok(None) => {
+            tracing::debug!("Session events stream closed by the coordinator");
+            *session_events = None;
+            None
+        }
+        Err(err) => {
+            tracing::debug!("Session events stream failed: {err}");
+            *session_events = None;
+            None
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use bitcoin::{
+    secp256k1::{Secp256k1, SecretKey},
+    Address, CompressedPublicKey, Network, PrivateKey,
+};
+use via_btc_client::inscriber::test_utils::{MockBitcoinOps, MockBitcoinOpsConfig};
+use zksync_default_da_clients::no_da::client::NoDAClient;
+
+use super::*;
+
+const VERIFIERS: usize = 3;
+
+fn secret_keys() -> Vec<SecretKey> {
+    (1..=VERIFIERS as u8)
+        .map(|i| SecretKey::from_slice(&[i; 32]).unwrap())
+        .collect()
+}
+
+fn coordinator_url(index: usize) -> String {
+    format!("http://verifier-{index}:6060")
+}
+
+/// Creates the verifier `own_index` of a network of `VERIFIERS` verifiers rotating the
+/// coordinator role.
+async fn create_verifier(
+    pool: ConnectionPool<Verifier>,
+    own_index: usize,
+) -> ViaWithdrawalVerifier {
+    let secp = Secp256k1::new();
+    let secret_keys = secret_keys();
+    let verifiers_pub_keys = secret_keys
+        .iter()
+        .map(|key| key.public_key(&secp).to_string())
+        .collect();
+    let bridge_key = CompressedPublicKey::from_private_key(
+        &secp,
+        &PrivateKey::new(secret_keys[0], Network::Regtest),
+    )
+    .unwrap();
+
+    let verifier_config = ViaVerifierConfig {
+        coordinator_urls: Some((0..VERIFIERS).map(coordinator_url).collect()),
+        leader_rotation_interval: Some(10),
+        leader_liveness_l1_blocks: Some(6),
+        session_events: Some(false),
+        ..ViaVerifierConfig::for_tests()
+    };
+    let via_bridge_config = ViaBridgeConfig {
+        verifiers_pub_keys,
+        bridge_address: Address::p2wpkh(&bridge_key, Network::Regtest).to_string(),
+        required_signers: VERIFIERS,
+        ..Default::default()
+    };
+
+    ViaWithdrawalVerifier::new(
+        verifier_config,
+        KeyManager::from_secret_key(secret_keys[own_index], Network::Regtest).unwrap(),
+        pool,
+        Arc::new(MockBitcoinOps::new(MockBitcoinOpsConfig::default())),
+        WithdrawalClient::new(Box::new(NoDAClient), Network::Regtest),
+        via_bridge_config,
+    )
+    .unwrap()
+}
+
+/// Finalizes the L1 batch `l1_batch_number`, inscribed in the L1 block `l1_block_number`.
+async fn finalize_l1_batch(
+    pool: &ConnectionPool<Verifier>,
+    l1_batch_number: u32,
+    l1_block_number: u32,
+) {
+    let mut storage = pool.connection().await.unwrap();
+    let proof_reveal_tx_id = H256::random();
+    storage
+        .via_votes_dal()
+        .insert_votable_transaction(
+            l1_batch_number,
+            H256::random(),
+            H256::random(),
+            "test_da_id".to_string(),
+            proof_reveal_tx_id,
+            format!("test_blob_id_{l1_batch_number}"),
+            format!("test_pubdata_tx_id_{l1_batch_number}"),
+            "test_da_id".to_string(),
+            format!("test_pubdata_blob_id_{l1_batch_number}"),
+            l1_block_number,
+        )
+        .await
+        .unwrap();
+    let votable_transaction_id = storage
+        .via_votes_dal()
+        .get_votable_transaction_id(proof_reveal_tx_id.as_bytes(), i64::from(l1_batch_number))
+        .await
+        .unwrap()
+        .unwrap();
+    storage
+        .via_votes_dal()
+        .insert_vote(
+            votable_transaction_id,
+            "0x1234567890123456789012345678901234567890",
+            true,
+            l1_block_number,
+        )
+        .await
+        .unwrap();
+    storage
+        .via_votes_dal()
+        .verify_votable_transaction(i64::from(l1_batch_number), proof_reveal_tx_id, true)
+        .await
+        .unwrap();
+    assert!(storage
+        .via_votes_dal()
+        .finalize_transaction_if_needed(votable_transaction_id, 1.0, 1)
+        .await
+        .unwrap());
+}
+
+async fn set_l1_block_number(pool: &ConnectionPool<Verifier>, l1_block_number: u32) {
+    let mut storage = pool.connection().await.unwrap();
+    storage
+        .via_indexer_dal()
+        .init_indexer_metadata("via_btc_watch", l1_block_number)
+        .await
+        .unwrap();
+    storage
+        .via_indexer_dal()
+        .update_last_processed_l1_block("via_btc_watch", l1_block_number)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_verifier_follows_leader_change() {
+    let pool = ConnectionPool::<Verifier>::test_pool().await;
+    finalize_l1_batch(&pool, 1, 100).await;
+    set_l1_block_number(&pool, 100).await;
+
+    let mut verifier = create_verifier(pool.clone(), 1);
+    verifier.update_leader().await.unwrap();
+    assert_eq!(verifier.coordinator_url(), coordinator_url(0));
+    assert!(!verifier.is_coordinator());
+
+    // A session with the first leader is in progress when it stops responding.
+    let signer = get_signer_from_secret_key(
+        verifier.key_manager.secret_key(),
+        verifier.via_bridge_config.verifiers_pub_keys.clone(),
+        None,
+    )
+    .unwrap();
+    verifier.signer_per_utxo_input.insert(0, signer);
+
+    // The leader didn't finalize an L1 batch within the liveness window, the role moves to the
+    // next verifier.
+    set_l1_block_number(&pool, 105).await;
+    verifier.update_leader().await.unwrap();
+    assert_eq!(verifier.coordinator_url(), coordinator_url(0));
+    assert_eq!(verifier.signer_per_utxo_input.len(), 1);
+
+    set_l1_block_number(&pool, 106).await;
+    verifier.update_leader().await.unwrap();
+    assert_eq!(verifier.coordinator_url(), coordinator_url(1));
+    assert!(verifier.is_coordinator());
+    // The session of the previous leader is dropped.
+    assert!(verifier.signer_per_utxo_input.is_empty());
+
+    // The term of the new leader starts once an L1 batch of the next term is finalized.
+    finalize_l1_batch(&pool, 10, 110).await;
+    set_l1_block_number(&pool, 111).await;
+    verifier.update_leader().await.unwrap();
+    assert_eq!(verifier.coordinator_url(), coordinator_url(1));
+    assert!(verifier.is_coordinator());
+
+    set_l1_block_number(&pool, 116).await;
+    verifier.update_leader().await.unwrap();
+    assert_eq!(verifier.coordinator_url(), coordinator_url(2));
+    assert!(!verifier.is_coordinator());
+}
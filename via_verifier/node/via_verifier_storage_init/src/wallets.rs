@@ -31,7 +31,7 @@ impl ViaWalletsInitializer {
             .connection()
             .await?
             .via_wallet_dal()
-            .insert_wallets(&indexer_wallets_details)
+            .insert_wallets(&indexer_wallets_details, None)
             .await?;
 
         let wallets = state
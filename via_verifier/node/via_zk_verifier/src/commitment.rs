@@ -0,0 +1,526 @@
+//! Recomputation of the L1 batch commitment by the verifier, so the public inputs of the proof don't
+//! rely on the commitments supplied by the sequencer.
+
+use anyhow::Context;
+use via_da_client::pubdata::{Pubdata, PubdataBlob};
+use zksync_l1_contract_interface::i_executor::commit::kzg::{
+    pubdata_to_blob_commitments, ZK_SYNC_BYTES_PER_BLOB,
+};
+use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_system_constants::{
+    L2_TO_L1_LOGS_TREE_ROOT_KEY, STATE_DIFF_HASH_KEY, ZKPORTER_IS_AVAILABLE,
+};
+use zksync_types::{
+    blob::{num_blobs_created, num_blobs_required},
+    commitment::{
+        serialize_commitments, L1BatchCommitmentMode, L1BatchMetaParameters, L1BatchWithMetadata,
+        SerializeCommitment,
+    },
+    l2_to_l1_log::{
+        l2_to_l1_logs_tree_size, parse_system_logs_for_blob_hashes, SystemL2ToL1Log, UserL2ToL1Log,
+    },
+    web3::keccak256,
+    writes::compress_state_diffs,
+    H256,
+};
+
+/// Recomputes the commitment of the L1 batch from its header, the pubdata blob published to the DA
+/// layer and the tree data of the batch. Returns an error if the batch is not consistent with the
+/// pubdata blob.
+///
+/// A `legacy_pubdata` blob holds only the pubdata: its state diffs can't be checked against the
+/// state diff hash of the batch, which is then taken from the system logs as is.
+pub(crate) fn recompute_l1_batch_commitment(
+    l1_batch: &L1BatchWithMetadata,
+    pubdata_blob: &[u8],
+    legacy_pubdata: bool,
+    commitment_mode: L1BatchCommitmentMode,
+) -> anyhow::Result<H256> {
+    let header = &l1_batch.header;
+    let metadata = &l1_batch.metadata;
+    let protocol_version = header
+        .protocol_version
+        .context("Protocol version is missing")?;
+    anyhow::ensure!(
+        !protocol_version.is_pre_boojum(),
+        "Pre-boojum L1 batches are not supported"
+    );
+
+    let PubdataBlob {
+        pubdata,
+        compressed_state_diffs,
+        state_diffs,
+    } = if legacy_pubdata {
+        PubdataBlob::decode_legacy(pubdata_blob)?
+    } else {
+        PubdataBlob::decode(pubdata_blob)?
+    };
+    if let Some(pubdata_input) = &header.pubdata_input {
+        anyhow::ensure!(
+            *pubdata_input == pubdata,
+            "The pubdata input of the L1 batch doesn't match the DA blob"
+        );
+    }
+
+    // The user logs published to the DA layer must be the ones committed in the system logs.
+    let user_logs = Pubdata::decode_pubdata(pubdata.clone())?.user_logs;
+    let merkle_tree_leaves = user_logs
+        .iter()
+        .map(|log| <[u8; UserL2ToL1Log::SERIALIZED_SIZE]>::try_from(log.encode_packed()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| anyhow::anyhow!("Invalid size of the L2 to L1 log"))?;
+    let l2_to_l1_logs_root = MiniMerkleTree::new(
+        merkle_tree_leaves.into_iter(),
+        Some(l2_to_l1_logs_tree_size(protocol_version)),
+    )
+    .merkle_root();
+    anyhow::ensure!(
+        l2_to_l1_logs_root == system_log_value(&header.system_logs, L2_TO_L1_LOGS_TREE_ROOT_KEY)?,
+        "L2 to L1 logs tree root mismatch"
+    );
+
+    // The state diffs published to the DA layer must be the ones hashed in the system logs and
+    // compressed in the pubdata.
+    let state_diff_hash = system_log_value(&header.system_logs, STATE_DIFF_HASH_KEY)?;
+    if !legacy_pubdata {
+        anyhow::ensure!(
+            H256(keccak256(&serialize_commitments(&state_diffs))) == state_diff_hash,
+            "State diff hash mismatch"
+        );
+        anyhow::ensure!(
+            compress_state_diffs(state_diffs) == compressed_state_diffs,
+            "The state diffs don't match the compressed state diffs of the pubdata"
+        );
+    }
+
+    let (blob_linear_hashes, blob_commitments) = match commitment_mode {
+        L1BatchCommitmentMode::Rollup => {
+            let num_blobs = num_blobs_required(&protocol_version);
+            let blob_linear_hashes =
+                parse_system_logs_for_blob_hashes(&protocol_version, &header.system_logs);
+            let blob_commitments = if protocol_version.is_post_1_4_2() {
+                // The blobs published by the batch must be the chunks of the pubdata.
+                let num_blobs_created = num_blobs_created(&protocol_version);
+                anyhow::ensure!(
+                    pubdata.len() <= num_blobs_created * ZK_SYNC_BYTES_PER_BLOB,
+                    "The pubdata doesn't fit in {num_blobs_created} blobs"
+                );
+                anyhow::ensure!(
+                    pubdata_to_blob_linear_hashes(num_blobs, &pubdata) == blob_linear_hashes,
+                    "Blob linear hashes mismatch"
+                );
+                pubdata_to_blob_commitments(num_blobs, &pubdata)
+            } else {
+                vec![H256::zero(); num_blobs]
+            };
+            (blob_linear_hashes, blob_commitments)
+        }
+        L1BatchCommitmentMode::Validium => {
+            let num_blobs = num_blobs_required(&protocol_version);
+            (vec![H256::zero(); num_blobs], vec![H256::zero(); num_blobs])
+        }
+    };
+
+    let mut aux_output = Vec::new();
+    aux_output.extend(keccak256(&serialize_commitments(&header.system_logs)));
+    aux_output.extend(state_diff_hash.as_bytes());
+    aux_output.extend(
+        metadata
+            .bootloader_initial_content_commitment
+            .context("Bootloader initial content commitment is missing")?
+            .as_bytes(),
+    );
+    aux_output.extend(
+        metadata
+            .events_queue_commitment
+            .context("Events queue commitment is missing")?
+            .as_bytes(),
+    );
+    for (linear_hash, commitment) in blob_linear_hashes.iter().zip(&blob_commitments) {
+        aux_output.extend(linear_hash.as_bytes());
+        aux_output.extend(commitment.as_bytes());
+    }
+
+    // The rollup tree state followed by the empty zk porter one.
+    let mut pass_through_data = Vec::new();
+    pass_through_data.extend(metadata.rollup_last_leaf_index.to_be_bytes());
+    pass_through_data.extend(metadata.root_hash.as_bytes());
+    pass_through_data.extend(0_u64.to_be_bytes());
+    pass_through_data.extend(H256::zero().as_bytes());
+
+    let meta_parameters = L1BatchMetaParameters {
+        zkporter_is_available: ZKPORTER_IS_AVAILABLE,
+        bootloader_code_hash: header.base_system_contracts_hashes.bootloader,
+        default_aa_code_hash: header.base_system_contracts_hashes.default_aa,
+        protocol_version: Some(protocol_version),
+    };
+
+    let mut commitment = Vec::new();
+    commitment.extend(keccak256(&pass_through_data));
+    commitment.extend(meta_parameters.hash().as_bytes());
+    commitment.extend(keccak256(&aux_output));
+    Ok(H256(keccak256(&commitment)))
+}
+
+/// Hashes each blob of the pubdata padded with zeroes, as the pubdata chunk publisher does.
+fn pubdata_to_blob_linear_hashes(num_blobs: usize, pubdata: &[u8]) -> Vec<H256> {
+    let mut blob_linear_hashes: Vec<_> = pubdata
+        .chunks(ZK_SYNC_BYTES_PER_BLOB)
+        .map(|chunk| {
+            let mut blob = chunk.to_vec();
+            blob.resize(ZK_SYNC_BYTES_PER_BLOB, 0);
+            H256(keccak256(&blob))
+        })
+        .collect();
+    blob_linear_hashes.resize(num_blobs, H256::zero());
+    blob_linear_hashes
+}
+
+fn system_log_value(system_logs: &[SystemL2ToL1Log], key: u32) -> anyhow::Result<H256> {
+    system_logs
+        .iter()
+        .find_map(|log| (log.0.key == H256::from_low_u64_be(key.into())).then_some(log.0.value))
+        .with_context(|| format!("System log with key {key} is missing"))
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_system_constants::{
+        BLOB1_LINEAR_HASH_KEY, L1_MESSENGER_ADDRESS, PUBDATA_CHUNK_PUBLISHER_ADDRESS,
+    };
+    use zksync_types::{
+        block::L1BatchHeader,
+        commitment::{
+            AuxCommitments, CommitmentCommonInput, CommitmentInput, L1BatchAuxiliaryOutput,
+            L1BatchCommitment, L1BatchMetadata,
+        },
+        l2_to_l1_log::L2ToL1Log,
+        writes::StateDiffRecord,
+        Address, L1BatchNumber, ProtocolVersionId, U256,
+    };
+
+    use super::*;
+
+    struct TestBatch {
+        l1_batch: L1BatchWithMetadata,
+        pubdata: Vec<u8>,
+        state_diffs: Vec<StateDiffRecord>,
+    }
+
+    impl TestBatch {
+        fn blob(&self) -> Vec<u8> {
+            PubdataBlob::encode(&self.pubdata, &self.state_diffs)
+        }
+
+        fn set_system_log(&mut self, key: u32, value: H256) {
+            let key = H256::from_low_u64_be(key.into());
+            for log in &mut self.l1_batch.header.system_logs {
+                if log.0.key == key {
+                    log.0.value = value;
+                }
+            }
+        }
+    }
+
+    fn system_log(sender: Address, key: u32, value: H256) -> SystemL2ToL1Log {
+        SystemL2ToL1Log(L2ToL1Log {
+            shard_id: 0,
+            is_service: true,
+            tx_number_in_block: 0,
+            sender,
+            key: H256::from_low_u64_be(key.into()),
+            value,
+        })
+    }
+
+    fn state_diffs() -> Vec<StateDiffRecord> {
+        vec![
+            StateDiffRecord {
+                address: Address::repeat_byte(1),
+                key: U256::from(1),
+                derived_key: [2; 32],
+                enumeration_index: 0,
+                initial_value: U256::zero(),
+                final_value: U256::from(100),
+            },
+            StateDiffRecord {
+                address: Address::repeat_byte(1),
+                key: U256::from(2),
+                derived_key: [3; 32],
+                enumeration_index: 7,
+                initial_value: U256::from(100),
+                final_value: U256::from(50),
+            },
+        ]
+    }
+
+    /// Builds an L1 batch consistent with its pubdata, along with the commitment computed by the
+    /// commitment generator of the sequencer.
+    fn test_batch(commitment_mode: L1BatchCommitmentMode) -> (TestBatch, H256) {
+        let protocol_version = ProtocolVersionId::latest();
+        let state_diffs = state_diffs();
+        let user_log = UserL2ToL1Log(L2ToL1Log {
+            shard_id: 0,
+            is_service: true,
+            tx_number_in_block: 1,
+            sender: L1_MESSENGER_ADDRESS,
+            key: H256::repeat_byte(1),
+            value: H256::repeat_byte(2),
+        });
+
+        // One user log, no messages and no published bytecodes.
+        let mut pubdata = Vec::new();
+        pubdata.extend(1_u32.to_be_bytes());
+        pubdata.extend(user_log.0.to_bytes());
+        pubdata.extend(0_u32.to_be_bytes());
+        pubdata.extend(0_u32.to_be_bytes());
+        pubdata.extend(compress_state_diffs(state_diffs.clone()));
+
+        let l2_to_l1_logs_root = MiniMerkleTree::new(
+            [user_log.0.to_bytes()].into_iter(),
+            Some(l2_to_l1_logs_tree_size(protocol_version)),
+        )
+        .merkle_root();
+        let mut padded_pubdata = pubdata.clone();
+        padded_pubdata.resize(ZK_SYNC_BYTES_PER_BLOB, 0);
+        let mut system_logs = vec![
+            system_log(
+                L1_MESSENGER_ADDRESS,
+                L2_TO_L1_LOGS_TREE_ROOT_KEY,
+                l2_to_l1_logs_root,
+            ),
+            system_log(
+                L1_MESSENGER_ADDRESS,
+                STATE_DIFF_HASH_KEY,
+                H256(keccak256(&serialize_commitments(&state_diffs))),
+            ),
+            system_log(
+                PUBDATA_CHUNK_PUBLISHER_ADDRESS,
+                BLOB1_LINEAR_HASH_KEY,
+                H256(keccak256(&padded_pubdata)),
+            ),
+        ];
+        for i in 1..num_blobs_created(&protocol_version) as u32 {
+            system_logs.push(system_log(
+                PUBDATA_CHUNK_PUBLISHER_ADDRESS,
+                BLOB1_LINEAR_HASH_KEY + i,
+                H256::zero(),
+            ));
+        }
+
+        let mut header =
+            L1BatchHeader::new(L1BatchNumber(1), 1, Default::default(), protocol_version);
+        header.base_system_contracts_hashes.bootloader = H256::repeat_byte(6);
+        header.base_system_contracts_hashes.default_aa = H256::repeat_byte(7);
+        header.l2_to_l1_logs = vec![user_log];
+        header.system_logs = system_logs;
+        header.pubdata_input = Some(pubdata.clone());
+
+        let aux_commitments = AuxCommitments {
+            events_queue_commitment: H256::repeat_byte(3),
+            bootloader_initial_content_commitment: H256::repeat_byte(4),
+        };
+        let num_blobs = num_blobs_required(&protocol_version);
+        let blob_commitments = match commitment_mode {
+            L1BatchCommitmentMode::Rollup => pubdata_to_blob_commitments(num_blobs, &pubdata),
+            L1BatchCommitmentMode::Validium => vec![H256::zero(); num_blobs],
+        };
+        let mut commitment = L1BatchCommitment::new(CommitmentInput::PostBoojum {
+            common: CommitmentCommonInput {
+                l2_to_l1_logs: header.l2_to_l1_logs.clone(),
+                rollup_last_leaf_index: 10,
+                rollup_root_hash: H256::repeat_byte(5),
+                bootloader_code_hash: header.base_system_contracts_hashes.bootloader,
+                default_aa_code_hash: header.base_system_contracts_hashes.default_aa,
+                protocol_version,
+            },
+            system_logs: header.system_logs.clone(),
+            state_diffs: state_diffs.clone(),
+            aux_commitments,
+            blob_commitments,
+        });
+        if let (
+            L1BatchCommitmentMode::Validium,
+            L1BatchAuxiliaryOutput::PostBoojum {
+                blob_linear_hashes,
+                blob_commitments,
+                ..
+            },
+        ) = (commitment_mode, &mut commitment.auxiliary_output)
+        {
+            blob_linear_hashes.fill(H256::zero());
+            blob_commitments.fill(H256::zero());
+        }
+        let commitment = commitment.hash().commitment;
+
+        let metadata = L1BatchMetadata {
+            root_hash: H256::repeat_byte(5),
+            rollup_last_leaf_index: 10,
+            initial_writes_compressed: None,
+            repeated_writes_compressed: None,
+            commitment,
+            l2_l1_merkle_root: l2_to_l1_logs_root,
+            block_meta_params: L1BatchMetaParameters {
+                zkporter_is_available: ZKPORTER_IS_AVAILABLE,
+                bootloader_code_hash: header.base_system_contracts_hashes.bootloader,
+                default_aa_code_hash: header.base_system_contracts_hashes.default_aa,
+                protocol_version: Some(protocol_version),
+            },
+            aux_data_hash: H256::zero(),
+            meta_parameters_hash: H256::zero(),
+            pass_through_data_hash: H256::zero(),
+            events_queue_commitment: Some(aux_commitments.events_queue_commitment),
+            bootloader_initial_content_commitment: Some(
+                aux_commitments.bootloader_initial_content_commitment,
+            ),
+            state_diffs_compressed: compress_state_diffs(state_diffs.clone()),
+        };
+
+        let batch = TestBatch {
+            l1_batch: L1BatchWithMetadata {
+                header,
+                metadata,
+                raw_published_factory_deps: vec![],
+            },
+            pubdata,
+            state_diffs,
+        };
+        (batch, commitment)
+    }
+
+    #[test]
+    fn test_recompute_l1_batch_commitment() {
+        for commitment_mode in [
+            L1BatchCommitmentMode::Rollup,
+            L1BatchCommitmentMode::Validium,
+        ] {
+            let (batch, commitment) = test_batch(commitment_mode);
+
+            let recomputed = recompute_l1_batch_commitment(
+                &batch.l1_batch,
+                &batch.blob(),
+                false,
+                commitment_mode,
+            )
+            .unwrap();
+            assert_eq!(recomputed, commitment, "{commitment_mode:?}");
+        }
+    }
+
+    #[test]
+    fn test_recompute_l1_batch_commitment_with_invalid_state_diffs() {
+        let (mut batch, _) = test_batch(L1BatchCommitmentMode::Rollup);
+        batch.state_diffs[1].final_value = U256::from(51);
+
+        let err = recompute_l1_batch_commitment(
+            &batch.l1_batch,
+            &batch.blob(),
+            false,
+            L1BatchCommitmentMode::Rollup,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("State diff hash mismatch"),
+            "{err}"
+        );
+
+        // The state diffs hashed by the batch must also be the ones compressed in the pubdata.
+        let state_diff_hash = H256(keccak256(&serialize_commitments(&batch.state_diffs)));
+        batch.set_system_log(STATE_DIFF_HASH_KEY, state_diff_hash);
+        let err = recompute_l1_batch_commitment(
+            &batch.l1_batch,
+            &batch.blob(),
+            false,
+            L1BatchCommitmentMode::Rollup,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("compressed state diffs"), "{err}");
+    }
+
+    #[test]
+    fn test_recompute_l1_batch_commitment_with_legacy_blob() {
+        let (batch, commitment) = test_batch(L1BatchCommitmentMode::Rollup);
+
+        // The legacy blob is the pubdata alone, it's only accepted for the pre-upgrade batches.
+        assert!(recompute_l1_batch_commitment(
+            &batch.l1_batch,
+            &batch.pubdata,
+            false,
+            L1BatchCommitmentMode::Rollup,
+        )
+        .is_err());
+        let recomputed = recompute_l1_batch_commitment(
+            &batch.l1_batch,
+            &batch.pubdata,
+            true,
+            L1BatchCommitmentMode::Rollup,
+        )
+        .unwrap();
+        assert_eq!(recomputed, commitment);
+
+        // The rest of the pubdata is still checked.
+        let err = recompute_l1_batch_commitment(
+            &batch.l1_batch,
+            &batch.blob(),
+            true,
+            L1BatchCommitmentMode::Rollup,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("after the pubdata"), "{err}");
+        let (mut batch, _) = test_batch(L1BatchCommitmentMode::Rollup);
+        batch.set_system_log(BLOB1_LINEAR_HASH_KEY, H256::repeat_byte(8));
+        let err = recompute_l1_batch_commitment(
+            &batch.l1_batch,
+            &batch.pubdata,
+            true,
+            L1BatchCommitmentMode::Rollup,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("Blob linear hashes mismatch"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_recompute_l1_batch_commitment_with_invalid_blob_linear_hash() {
+        let (mut batch, _) = test_batch(L1BatchCommitmentMode::Rollup);
+        batch.set_system_log(BLOB1_LINEAR_HASH_KEY, H256::repeat_byte(8));
+
+        let err = recompute_l1_batch_commitment(
+            &batch.l1_batch,
+            &batch.blob(),
+            false,
+            L1BatchCommitmentMode::Rollup,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("Blob linear hashes mismatch"),
+            "{err}"
+        );
+
+        // The blobs are not published in validium mode.
+        recompute_l1_batch_commitment(
+            &batch.l1_batch,
+            &batch.blob(),
+            false,
+            L1BatchCommitmentMode::Validium,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_recompute_l1_batch_commitment_with_invalid_pubdata() {
+        let (mut batch, _) = test_batch(L1BatchCommitmentMode::Rollup);
+        batch.l1_batch.header.pubdata_input = Some(vec![0; 4]);
+
+        let err = recompute_l1_batch_commitment(
+            &batch.l1_batch,
+            &batch.blob(),
+            false,
+            L1BatchCommitmentMode::Rollup,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("pubdata input"), "{err}");
+    }
+}
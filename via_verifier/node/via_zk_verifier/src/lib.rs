@@ -6,22 +6,30 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::{watch, RwLock};
 use via_btc_client::{
     indexer::BitcoinInscriptionIndexer,
-    types::{BitcoinTxid, FullInscriptionMessage, L1BatchDAReference, ProofDAReference},
+    types::{FullInscriptionMessage, L1BatchDAReference, ProofDAReference},
     utils::bytes_to_txid,
 };
-use via_da_client::{pubdata::Pubdata, types::L2_BOOTLOADER_CONTRACT_ADDR};
+use via_da_client::{envelope::decode_blob, pubdata::Pubdata, types::L2_BOOTLOADER_CONTRACT_ADDR};
 use via_verification::proof::{
     Bn256, ProofTrait, ViaZKProof, ZkSyncProof, ZkSyncSnarkWrapperCircuit,
 };
 use via_verifier_dal::{Connection, ConnectionPool, Verifier, VerifierDal};
 use via_verifier_types::protocol_version::check_if_supported_sequencer_version;
 use zksync_config::ViaVerifierConfig;
-use zksync_da_client::{types::InclusionData, DataAvailabilityClient};
+use zksync_da_client::{
+    types::{DAError, InclusionData, InvalidBlobError},
+    DataAvailabilityClient,
+};
 use zksync_types::{
-    commitment::L1BatchWithMetadata, protocol_version::ProtocolSemanticVersion,
-    via_wallet::SystemWallets, ProtocolVersionId, H160, H256,
+    commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
+    protocol_version::ProtocolSemanticVersion,
+    via_wallet::SystemWallets,
+    ProtocolVersionId, H160, H256,
 };
 
+use crate::commitment::recompute_l1_batch_commitment;
+
+mod commitment;
 mod metrics;
 
 /// Copy of `zksync_l1_contract_interface::i_executor::methods::ProveBatches`
@@ -40,6 +48,17 @@ pub struct L1BatchProofForL1 {
     pub protocol_version: ProtocolSemanticVersion,
 }
 
+/// An inscribed L1 batch of the range covered by a proof.
+#[derive(Debug)]
+struct ProvenL1Batch {
+    number: i64,
+    hash: H256,
+    prev_hash: H256,
+    /// The pubdata blob of the batch, i.e. its pubdata followed by its state diffs, or only its
+    /// pubdata for the legacy blobs.
+    pubdata: Vec<u8>,
+}
+
 #[derive(Debug)]
 pub struct ViaVerifier {
     config: ViaVerifierConfig,
@@ -48,6 +67,8 @@ pub struct ViaVerifier {
     indexer: BitcoinInscriptionIndexer,
     test_zk_proof_invalid_l1_batch_numbers: Arc<RwLock<Vec<i64>>>,
     zk_agreement_threshold: f64,
+    genesis_commitment: Option<H256>,
+    commitment_mode: L1BatchCommitmentMode,
 }
 
 impl ViaVerifier {
@@ -58,6 +79,8 @@ impl ViaVerifier {
         pool: ConnectionPool<Verifier>,
         da_client: Box<dyn DataAvailabilityClient>,
         zk_agreement_threshold: f64,
+        genesis_commitment: Option<H256>,
+        commitment_mode: L1BatchCommitmentMode,
     ) -> anyhow::Result<Self> {
         Ok(Self {
             config: config.clone(),
@@ -68,6 +91,8 @@ impl ViaVerifier {
                 config.test_zk_proof_invalid_l1_batch_numbers,
             )),
             zk_agreement_threshold,
+            genesis_commitment,
+            commitment_mode,
         })
     }
 
@@ -111,7 +136,9 @@ impl ViaVerifier {
             let proof_txid = bytes_to_txid(&raw_tx_id).with_context(|| "Failed to parse tx_id")?;
             tracing::info!("trying to get proof_txid: {}", proof_txid);
             let proof_msgs = self.indexer.parse_transaction(&proof_txid).await?;
-            let proof_msg = self.expect_single_msg(&proof_msgs, "ProofDAReference")?;
+            let proof_msg = self.expect_single_msg(&proof_msgs, "ProofDAReference", |msg| {
+                matches!(msg, FullInscriptionMessage::ProofDAReference(_))
+            })?;
 
             let proof_da = match proof_msg {
                 FullInscriptionMessage::ProofDAReference(ref a) => a,
@@ -121,44 +148,106 @@ impl ViaVerifier {
                 }
             };
 
-            let (proof_blob, batch_tx_id) = self.process_proof_da_reference(proof_da).await?;
-
-            let batch_msgs = self.indexer.parse_transaction(&batch_tx_id).await?;
-            let batch_msg = self.expect_single_msg(&batch_msgs, "L1BatchDAReference")?;
-
-            let batch_da = match batch_msg {
-                FullInscriptionMessage::L1BatchDAReference(ref a) => a,
-                _ => {
-                    tracing::error!("Expected L1BatchDAReference, got something else");
-                    return Ok(());
+            let l1_batches_count = proof_da.input.l1_batch_reveal_txids().count();
+            let proof_blob = match self.process_proof_da_reference(proof_da).await {
+                Ok(proof_blob) => proof_blob,
+                Err(error) if is_invalid_blob(&error) => {
+                    tracing::error!("Invalid proof blob {}: {error:#}", proof_da.input.blob_id);
+                    return self
+                        .reject_l1_batches(storage, l1_batch_number, l1_batches_count, db_raw_tx_id)
+                        .await;
                 }
+                Err(error) => return Err(error),
             };
 
-            tracing::info!(
-                "Fetch l1 batch pubdata for blob id  {}",
-                batch_da.input.blob_id
-            );
-
-            let (batch_blob, batch_hash) = self.process_batch_da_reference(batch_da).await?;
-            let mut pubdata = Pubdata::decode_pubdata(batch_blob.data.clone().to_vec())?;
+            // The proof covers a range of consecutive L1 batches starting at the first one not
+            // verified yet.
+            let mut l1_batches = Vec::new();
+            let mut user_logs = Vec::new();
+            for (l1_batch_number, batch_tx_id) in
+                (l1_batch_number..).zip(proof_da.input.l1_batch_reveal_txids())
+            {
+                let batch_msgs = self.indexer.parse_transaction(batch_tx_id).await?;
+                let batch_msg =
+                    self.expect_single_msg(&batch_msgs, "L1BatchDAReference", |msg| {
+                        matches!(msg, FullInscriptionMessage::L1BatchDAReference(_))
+                    })?;
+
+                let batch_da = match batch_msg {
+                    FullInscriptionMessage::L1BatchDAReference(ref a) => a,
+                    _ => {
+                        tracing::error!("Expected L1BatchDAReference, got something else");
+                        return Ok(());
+                    }
+                };
+
+                if i64::from(batch_da.input.l1_batch_index.0) != l1_batch_number {
+                    anyhow::bail!(
+                        "Expected l1 batch {} in the proof range, got {}",
+                        l1_batch_number,
+                        batch_da.input.l1_batch_index
+                    );
+                }
 
-            let upgrade_tx_hash_opt = self.verify_upgrade_tx_hash(storage, &pubdata).await?;
+                tracing::info!(
+                    "Fetch l1 batch pubdata for blob id  {}",
+                    batch_da.input.blob_id
+                );
 
-            if upgrade_tx_hash_opt.is_some() {
-                // Discard the first log since it related to protocol upgrade.
-                pubdata.user_logs.remove(0);
+                let (batch_blob, batch_hash) = match self.process_batch_da_reference(batch_da).await
+                {
+                    Ok(batch) => batch,
+                    Err(error) if is_invalid_blob(&error) => {
+                        tracing::error!(
+                            "Invalid pubdata blob {}: {error:#}",
+                            batch_da.input.blob_id
+                        );
+                        return self
+                            .reject_l1_batches(
+                                storage,
+                                l1_batch_number - l1_batches.len() as i64,
+                                l1_batches_count,
+                                db_raw_tx_id,
+                            )
+                            .await;
+                    }
+                    Err(error) => return Err(error),
+                };
+                let mut pubdata = Pubdata::decode_pubdata(batch_blob.data.clone().to_vec())?;
+
+                let upgrade_tx_hash_opt = self.verify_upgrade_tx_hash(storage, &pubdata).await?;
+
+                if upgrade_tx_hash_opt.is_some() {
+                    // Discard the first log since it related to protocol upgrade.
+                    pubdata.user_logs.remove(0);
+
+                    // Check if the new protocol version is supported by the verifier node.
+                    let last_protocol_version = storage
+                        .via_protocol_versions_dal()
+                        .latest_protocol_semantic_version()
+                        .await
+                        .expect("Failed to load the latest protocol semantic version")
+                        .ok_or_else(|| anyhow::anyhow!("Protocol version is missing"))?;
 
-                // Check if the new protocol version is supported by the verifier node.
-                let last_protocol_version = storage
-                    .via_protocol_versions_dal()
-                    .latest_protocol_semantic_version()
-                    .await
-                    .expect("Failed to load the latest protocol semantic version")
-                    .ok_or_else(|| anyhow::anyhow!("Protocol version is missing"))?;
+                    check_if_supported_sequencer_version(last_protocol_version)?;
+                }
 
-                check_if_supported_sequencer_version(last_protocol_version)?;
+                user_logs.extend(pubdata.user_logs);
+                l1_batches.push(ProvenL1Batch {
+                    number: l1_batch_number,
+                    hash: batch_hash,
+                    prev_hash: batch_da.input.prev_l1_batch_hash,
+                    pubdata: batch_blob.data,
+                });
             }
 
+            // The deposits of the whole range are processed in order.
+            let pubdata = Pubdata {
+                user_logs,
+                ..Default::default()
+            };
+
+            let mut l1_batch_commitments = None;
             let (mut is_verified, deposits) = self
                 .verify_op_priority_id(storage, l1_batch_number, &pubdata)
                 .await?;
@@ -168,9 +257,10 @@ impl ViaVerifier {
 
                 let proof_data: ProveBatches = bincode::deserialize(&proof_blob.data)?;
 
-                let protocol_version_id = proof_data.l1_batches[0]
-                    .header
-                    .protocol_version
+                let protocol_version_id = proof_data
+                    .l1_batches
+                    .first()
+                    .and_then(|l1_batch| l1_batch.header.protocol_version)
                     .ok_or_else(|| anyhow::anyhow!("Protocol version is missing"))?;
 
                 let recursion_scheduler_level_vk_hash = storage
@@ -178,32 +268,56 @@ impl ViaVerifier {
                     .get_recursion_scheduler_level_vk_hash(protocol_version_id)
                     .await?;
 
-                is_verified = self
-                    .verify_proof(
-                        l1_batch_number,
-                        batch_hash,
-                        proof_data,
-                        recursion_scheduler_level_vk_hash,
-                        protocol_version_id,
-                    )
+                let commitments = self
+                    .recompute_commitments(storage, &l1_batches, &proof_data)
                     .await?;
+
+                if let Some(commitments) = commitments {
+                    is_verified = self
+                        .verify_proof(
+                            &l1_batches,
+                            proof_data,
+                            &commitments,
+                            recursion_scheduler_level_vk_hash,
+                            protocol_version_id,
+                        )
+                        .await?;
+                    l1_batch_commitments = Some(commitments);
+                } else {
+                    is_verified = false;
+                }
             }
             let mut transaction = storage.start_transaction().await?;
 
-            let votable_transaction_id = transaction
-                .via_votes_dal()
-                .verify_votable_transaction(l1_batch_number, db_raw_tx_id, is_verified)
-                .await?;
+            for (index, l1_batch) in l1_batches.iter().enumerate() {
+                let votable_transaction_id = transaction
+                    .via_votes_dal()
+                    .verify_votable_transaction(l1_batch.number, db_raw_tx_id, is_verified)
+                    .await?;
 
-            transaction
-                .via_votes_dal()
-                .finalize_transaction_if_needed(
-                    votable_transaction_id,
-                    self.zk_agreement_threshold,
-                    self.indexer.get_number_of_verifiers(),
-                )
-                .await?;
+                transaction
+                    .via_votes_dal()
+                    .finalize_transaction_if_needed(
+                        votable_transaction_id,
+                        self.zk_agreement_threshold,
+                        self.indexer.get_number_of_verifiers(),
+                    )
+                    .await?;
+
+                if let (true, Some(commitments)) = (is_verified, &l1_batch_commitments) {
+                    // The first commitment is the one of the parent of the range.
+                    transaction
+                        .via_votes_dal()
+                        .set_l1_batch_commitment(
+                            l1_batch.number,
+                            db_raw_tx_id,
+                            commitments[index + 1],
+                        )
+                        .await?;
+                }
+            }
 
+            let last_l1_batch_number = l1_batch_number + l1_batches.len() as i64 - 1;
             if is_verified {
                 // Update the transaction status only if the l1 batch is valid.
                 for (hash, status) in deposits {
@@ -218,9 +332,13 @@ impl ViaVerifier {
                     .delete_invalid_votable_transactions_if_exists()
                     .await?;
 
-                METRICS.last_valid_l1_batch.set(l1_batch_number as usize);
+                METRICS
+                    .last_valid_l1_batch
+                    .set(last_l1_batch_number as usize);
             } else {
-                METRICS.last_invalid_l1_batch.set(l1_batch_number as usize);
+                METRICS
+                    .last_invalid_l1_batch
+                    .set(last_l1_batch_number as usize);
             }
 
             transaction.commit().await?;
@@ -231,6 +349,44 @@ impl ViaVerifier {
         Ok(())
     }
 
+    /// Votes against the `count` L1 batches starting at `first_l1_batch_number` covered by the proof
+    /// `db_raw_tx_id`, when a blob of the proof fails its verification by the DA client.
+    async fn reject_l1_batches(
+        &self,
+        storage: &mut Connection<'_, Verifier>,
+        first_l1_batch_number: i64,
+        count: usize,
+        db_raw_tx_id: H256,
+    ) -> anyhow::Result<()> {
+        let mut transaction = storage.start_transaction().await?;
+
+        let l1_batch_numbers = first_l1_batch_number..first_l1_batch_number + count as i64;
+        for l1_batch_number in l1_batch_numbers.clone() {
+            let votable_transaction_id = transaction
+                .via_votes_dal()
+                .verify_votable_transaction(l1_batch_number, db_raw_tx_id, false)
+                .await?;
+
+            transaction
+                .via_votes_dal()
+                .finalize_transaction_if_needed(
+                    votable_transaction_id,
+                    self.zk_agreement_threshold,
+                    self.indexer.get_number_of_verifiers(),
+                )
+                .await?;
+        }
+
+        transaction.commit().await?;
+
+        if let Some(last_l1_batch_number) = l1_batch_numbers.last() {
+            METRICS
+                .last_invalid_l1_batch
+                .set(last_l1_batch_number as usize);
+        }
+        Ok(())
+    }
+
     /// Check whether the first user_log corresponds to an upgrade transaction.
     pub async fn verify_upgrade_tx_hash(
         &mut self,
@@ -317,14 +473,17 @@ impl ViaVerifier {
         Ok((true, deposits))
     }
 
-    /// Helper to ensure there's exactly one message in the array, or log an error.
+    /// Helper to ensure there's exactly one message of the expected type in the array, or log an
+    /// error. Other messages packed in the same inscription are ignored.
     fn expect_single_msg<'a>(
         &self,
         msgs: &'a [FullInscriptionMessage],
         expected_type: &str,
+        is_expected_type: impl Fn(&FullInscriptionMessage) -> bool,
     ) -> anyhow::Result<&'a FullInscriptionMessage> {
-        match msgs.len() {
-            1 => Ok(&msgs[0]),
+        let expected_msgs: Vec<_> = msgs.iter().filter(|msg| is_expected_type(msg)).collect();
+        match expected_msgs.len() {
+            1 => Ok(expected_msgs[0]),
             n => {
                 tracing::error!("Expected 1 {expected_type} message, got {n}");
                 Err(anyhow::anyhow!("Expected exactly 1 message, got {n}"))
@@ -336,16 +495,15 @@ impl ViaVerifier {
     async fn process_proof_da_reference(
         &mut self,
         proof_msg: &ProofDAReference,
-    ) -> anyhow::Result<(InclusionData, BitcoinTxid)> {
+    ) -> anyhow::Result<InclusionData> {
         let blob = self
             .da_client
-            .get_inclusion_data(&proof_msg.input.blob_id)
+            .get_inclusion_data_from(&proof_msg.input.da_identifier, &proof_msg.input.blob_id)
             .await
             .with_context(|| "Failed to fetch the blob")?
             .ok_or_else(|| anyhow::anyhow!("Blob not found"))?;
-        let batch_tx_id = proof_msg.input.l1_batch_reveal_txid;
 
-        Ok((blob, batch_tx_id))
+        decode_inclusion_data(blob)
     }
 
     /// Processes an `L1BatchDAReference` message by retrieving the DA blob
@@ -355,35 +513,160 @@ impl ViaVerifier {
     ) -> anyhow::Result<(InclusionData, H256)> {
         let blob = self
             .da_client
-            .get_inclusion_data(&batch_msg.input.blob_id)
+            .get_inclusion_data_from(&batch_msg.input.da_identifier, &batch_msg.input.blob_id)
             .await
             .with_context(|| "Failed to fetch the blob")?
             .ok_or_else(|| anyhow::anyhow!("Blob not found"))?;
         let hash = batch_msg.input.l1_batch_hash;
 
-        Ok((blob, hash))
+        Ok((decode_inclusion_data(blob)?, hash))
     }
 
+    /// Recomputes the commitments of the parent and of every L1 batch of the range instead of
+    /// trusting the ones of the proof blob. Returns `None` if the proof blob doesn't match them.
+    async fn recompute_commitments(
+        &self,
+        storage: &mut Connection<'_, Verifier>,
+        l1_batches: &[ProvenL1Batch],
+        proof_data: &ProveBatches,
+    ) -> anyhow::Result<Option<Vec<H256>>> {
+        let Some(first_l1_batch) = l1_batches.first() else {
+            return Ok(None);
+        };
+        if proof_data.l1_batches.len() != l1_batches.len() {
+            tracing::error!(
+                "Expected {} L1Batches, got {}",
+                l1_batches.len(),
+                proof_data.l1_batches.len()
+            );
+            return Ok(None);
+        }
+        let prev_l1_batch = &proof_data.prev_l1_batch;
+
+        if i64::from(prev_l1_batch.header.number.0) != first_l1_batch.number - 1
+            || prev_l1_batch.metadata.root_hash != first_l1_batch.prev_hash
+        {
+            tracing::error!(
+                "The proof of l1 batch {} doesn't match the parent {}",
+                first_l1_batch.number,
+                first_l1_batch.prev_hash
+            );
+            return Ok(None);
+        }
+
+        let prev_commitment = if first_l1_batch.number == 1 {
+            self.genesis_commitment
+        } else {
+            match storage
+                .via_votes_dal()
+                .get_verified_l1_batch_commitment(first_l1_batch.prev_hash)
+                .await?
+            {
+                Some(prev_commitment) => prev_commitment,
+                None => {
+                    tracing::error!(
+                        "The parent of l1 batch {} was not verified as valid",
+                        first_l1_batch.number
+                    );
+                    return Ok(None);
+                }
+            }
+        };
+        let prev_commitment = prev_commitment.unwrap_or_else(|| {
+            // The parent was verified before the commitments were stored.
+            tracing::warn!(
+                "No commitment stored for the parent of l1 batch {}, using the one of the proof",
+                first_l1_batch.number
+            );
+            prev_l1_batch.metadata.commitment
+        });
+
+        if prev_commitment != prev_l1_batch.metadata.commitment {
+            tracing::error!(
+                "Commitment mismatch for the parent of l1 batch {}, expected {} got {}",
+                first_l1_batch.number,
+                prev_commitment,
+                prev_l1_batch.metadata.commitment
+            );
+            return Ok(None);
+        }
+
+        // A single verification key is loaded for the whole range.
+        let protocol_version = proof_data.l1_batches[0].header.protocol_version;
+        let mut commitments = vec![prev_commitment];
+        for (l1_batch, proven) in proof_data.l1_batches.iter().zip(l1_batches) {
+            if i64::from(l1_batch.header.number.0) != proven.number
+                || l1_batch.metadata.root_hash != proven.hash
+                || l1_batch.header.protocol_version != protocol_version
+            {
+                tracing::error!(
+                    "The proof of l1 batch {} doesn't match the inscribed l1 batch {}",
+                    proven.number,
+                    proven.hash
+                );
+                return Ok(None);
+            }
+
+            let legacy_pubdata = self
+                .config
+                .legacy_pubdata_last_l1_batch
+                .map_or(false, |last| proven.number <= i64::from(last));
+            let commitment = match recompute_l1_batch_commitment(
+                l1_batch,
+                &proven.pubdata,
+                legacy_pubdata,
+                self.commitment_mode,
+            ) {
+                Ok(commitment) => commitment,
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to recompute the commitment of l1 batch {}: {err}",
+                        proven.number
+                    );
+                    return Ok(None);
+                }
+            };
+
+            if commitment != l1_batch.metadata.commitment {
+                tracing::error!(
+                    "Commitment mismatch for l1 batch {}, expected {} got {}",
+                    proven.number,
+                    commitment,
+                    l1_batch.metadata.commitment
+                );
+                return Ok(None);
+            }
+            commitments.push(commitment);
+        }
+
+        Ok(Some(commitments))
+    }
+
+    /// Verifies the proofs of the range, each one chained to the commitment of its parent.
     async fn verify_proof(
         &self,
-        l1_batch_number: i64,
-        batch_hash: H256,
+        l1_batches: &[ProvenL1Batch],
         proof_data: ProveBatches,
+        commitments: &[H256],
         recursion_scheduler_level_vk_hash: H256,
         protocol_version_id: ProtocolVersionId,
     ) -> anyhow::Result<bool> {
-        tracing::info!(
-            "Batch_hash {}, recursion_scheduler_level_vk_hash {}, protocol_version_id {}",
-            batch_hash,
-            recursion_scheduler_level_vk_hash,
-            protocol_version_id
-        );
+        for l1_batch in l1_batches {
+            tracing::info!(
+                "Batch_hash {}, recursion_scheduler_level_vk_hash {}, protocol_version_id {}",
+                l1_batch.hash,
+                recursion_scheduler_level_vk_hash,
+                protocol_version_id
+            );
+        }
 
-        if proof_data.l1_batches.len() != 1 {
+        if proof_data.l1_batches.len() != l1_batches.len()
+            || commitments.len() != l1_batches.len() + 1
+        {
             tracing::error!(
-                "Expected exactly one L1Batch and one proof, got {} and {}",
-                proof_data.l1_batches.len(),
-                proof_data.proofs.len()
+                "Expected {} L1Batches, got {}",
+                l1_batches.len(),
+                proof_data.l1_batches.len()
             );
             return Ok(false);
         }
@@ -406,37 +689,44 @@ impl ViaVerifier {
             );
 
             tracing::info!("Skipping verification");
-            self.verification_invalid_l1_batch_numbers(l1_batch_number)
-                .await
+            let mut is_valid = true;
+            for l1_batch in l1_batches {
+                is_valid &= self
+                    .verification_invalid_l1_batch_numbers(l1_batch.number)
+                    .await?;
+            }
+            Ok(is_valid)
         } else {
-            if proof_data.proofs.len() != 1 {
+            if proof_data.proofs.len() != l1_batches.len() {
                 tracing::error!(
-                    "Expected exactly one proof, got {}",
+                    "Expected {} proofs, got {}",
+                    l1_batches.len(),
                     proof_data.proofs.len()
                 );
                 return Ok(false);
             }
 
-            let (prev_commitment, curr_commitment) = (
-                proof_data.prev_l1_batch.metadata.commitment,
-                proof_data.l1_batches[0].metadata.commitment,
-            );
-            let mut proof = proof_data.proofs[0].scheduler_proof.clone();
+            for (proof, commitments) in proof_data.proofs.iter().zip(commitments.windows(2)) {
+                let mut proof = proof.scheduler_proof.clone();
 
-            // Put correct inputs
-            proof.inputs = via_verification::public_inputs::generate_inputs(
-                &prev_commitment,
-                &curr_commitment,
-            );
+                // Put correct inputs
+                proof.inputs = via_verification::public_inputs::generate_inputs(
+                    &commitments[0],
+                    &commitments[1],
+                );
 
-            // Verify the proof
-            let via_proof = ViaZKProof { proof };
+                // Verify the proof
+                let via_proof = ViaZKProof { proof };
 
-            let is_valid = via_proof.verify(vk_inner)?;
+                if !via_proof.verify(vk_inner.clone())? {
+                    tracing::info!("Proof verification result: false");
+                    return Ok(false);
+                }
+            }
 
-            tracing::info!("Proof verification result: {}", is_valid);
+            tracing::info!("Proof verification result: true");
 
-            Ok(is_valid)
+            Ok(true)
         }
     }
 
@@ -470,3 +760,22 @@ impl ViaVerifier {
         wallets.is_valid_verifier_address(self.config.wallet_address()?)
     }
 }
+
+/// Decodes the envelope of a blob, a blob the sequencer published with an invalid envelope makes the
+/// proof invalid.
+fn decode_inclusion_data(blob: InclusionData) -> anyhow::Result<InclusionData> {
+    let data = decode_blob(blob.data).map_err(|error| DAError {
+        error: InvalidBlobError(format!("{error:#}")).into(),
+        is_retriable: false,
+    })?;
+
+    Ok(InclusionData { data })
+}
+
+/// Whether `error` comes from a blob failing its verification by the DA client, which makes the
+/// proof invalid instead of being retried.
+fn is_invalid_blob(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<DAError>()
+        .is_some_and(DAError::is_invalid_blob)
+}